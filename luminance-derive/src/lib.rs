@@ -21,9 +21,18 @@
 //!
 //! [See the full documentation here](https://docs.rs/luminance-derive/latest/luminance_derive/derive.UniformInterface.html)
 //!
+//! # `Std140` and `Std430`
+//!
+//! Those macros allow to derive the [`Std140`] and [`Std430`] traits for a custom `struct` type,
+//! generating its encoded representation with the right padding.
+//!
+//! [See the full documentation here](https://docs.rs/luminance-derive/latest/luminance_derive/derive.Std140.html)
+//!
 //! [luminance]: https://docs.rs/luminance
 //! [`Vertex`]: https://docs.rs/luminance/latest/luminance/vertex/trait.Vertex.html
 //! [`Semantics`]: https://docs.rs/luminance/latest/luminance/vertex/trait.Semantics.html
+//! [`Std140`]: https://docs.rs/luminance/latest/luminance/layout/trait.Std140.html
+//! [`Std430`]: https://docs.rs/luminance/latest/luminance/layout/trait.Std430.html
 
 #![deny(missing_docs)]
 
@@ -31,10 +40,12 @@ extern crate proc_macro;
 
 mod attrib;
 mod semantics;
+mod std_layout;
 mod uniform_interface;
 mod vertex;
 
 use crate::semantics::generate_enum_semantics_impl;
+use crate::std_layout::{generate_std_layout_impl, StdLayout};
 use crate::uniform_interface::generate_uniform_interface_impl;
 use crate::vertex::generate_vertex_impl;
 use proc_macro::TokenStream;
@@ -232,3 +243,99 @@ pub fn derive_uniform_interface(input: TokenStream) -> TokenStream {
     _ => panic!("only structs are currently supported for deriving UniformInterface"),
   }
 }
+
+/// The [`Std140`] derive proc-macro.
+///
+/// Shader blocks declared with the `std140` layout impose alignment rules on their members that
+/// Rust types don’t follow. This proc-macro generates, for a `struct` type, a companion type with
+/// the same fields encoded with [`Std140`], along with the padding required before each of them
+/// and at the end of the structure. That companion type is named after the `struct` type with the
+/// `Std140` suffix and is the [`Std140::Encoded`] type of the `struct`:
+///
+/// ```
+/// # use luminance::layout::Std140 as _;
+/// # use luminance_derive::Std140;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Std140)]
+/// struct Light {
+///   position: [f32; 3], // vec3
+///   intensity: f32,     // float, packed right after the vec3
+///   color: [f32; 3],    // vec3, aligned on 16 bytes
+///   coefficients: [[f32; 2]; 3], // vec2[3], each element aligned on 16 bytes
+/// }
+///
+/// let light = Light {
+///   position: [1., 2., 3.],
+///   intensity: 0.5,
+///   color: [1., 1., 1.],
+///   coefficients: [[0., 1.]; 3],
+/// };
+/// let encoded: LightStd140 = light.std140_encode();
+///
+/// assert_eq!(std::mem::size_of_val(&encoded), 80);
+/// assert_eq!(Light::std140_decode(encoded), light);
+/// ```
+///
+/// Every field must have a type implementing [`Std140`]: scalars, vectors, arrays — matrices
+/// being arrays of column vectors — and other `struct` types deriving [`Std140`], that can be
+/// nested and stored in arrays as well. A field of an unsupported type doesn’t compile. The
+/// offset of every field and the size of the companion type are checked at compile time.
+///
+/// Fields of type `[T; N]`, where `T` is a scalar and `N` a literal other than `2`, `3` or `4`,
+/// are arrays of scalars — `[f32; 8]` is a `float[8]` — and are encoded as [`Scalars`]. Arrays of
+/// two, three or four scalars are vectors; wrap them in [`Scalars`] to get arrays of scalars.
+///
+/// Only non-generic `struct` types with named fields are supported.
+///
+/// [`Std140`]: https://docs.rs/luminance/latest/luminance/layout/trait.Std140.html
+/// [`Std140::Encoded`]: https://docs.rs/luminance/latest/luminance/layout/trait.Std140.html#associatedtype.Encoded
+/// [`Scalars`]: https://docs.rs/luminance/latest/luminance/layout/struct.Scalars.html
+#[proc_macro_derive(Std140)]
+pub fn derive_std140(input: TokenStream) -> TokenStream {
+  derive_std_layout(StdLayout::Std140, input)
+}
+
+/// The [`Std430`] derive proc-macro.
+///
+/// This proc-macro works exactly like the [`Std140`](derive.Std140.html) proc-macro, but with the
+/// more compact `std430` layout, usable with storage blocks only. The companion type is named
+/// after the `struct` type with the `Std430` suffix.
+///
+/// ```
+/// # use luminance::layout::Std430 as _;
+/// # use luminance_derive::Std430;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Std430)]
+/// struct Particle {
+///   position: [f32; 3],
+///   mass: f32,
+///   velocity: [f32; 2],
+/// }
+///
+/// assert_eq!(std::mem::size_of::<ParticleStd430>(), 32);
+/// ```
+///
+/// [`Std430`]: https://docs.rs/luminance/latest/luminance/layout/trait.Std430.html
+#[proc_macro_derive(Std430)]
+pub fn derive_std430(input: TokenStream) -> TokenStream {
+  derive_std_layout(StdLayout::Std430, input)
+}
+
+fn derive_std_layout(layout: StdLayout, input: TokenStream) -> TokenStream {
+  let di: DeriveInput = parse_macro_input!(input);
+
+  match di.data {
+    // for now, we only handle structs
+    Data::Struct(struct_) => {
+      match generate_std_layout_impl(layout, di.vis, di.ident, di.generics, struct_) {
+        Ok(impl_) => impl_,
+        Err(e) => panic!("{}", e),
+      }
+    }
+
+    _ => panic!(
+      "only structs are currently supported for deriving {}",
+      layout.name()
+    ),
+  }
+}
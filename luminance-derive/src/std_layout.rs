@@ -0,0 +1,249 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use std::error;
+use std::fmt;
+use syn::{DataStruct, Expr, ExprLit, Fields, Generics, Ident, Lit, Type, TypeArray, Visibility};
+
+/// Scalar types, which arrays are wrapped in `Scalars` unless they are vectors.
+const SCALARS: [&str; 5] = ["f32", "f64", "i32", "u32", "bool"];
+
+/// Memory layout to derive.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum StdLayout {
+  Std140,
+  Std430,
+}
+
+impl StdLayout {
+  pub(crate) fn name(self) -> &'static str {
+    match self {
+      StdLayout::Std140 => "Std140",
+      StdLayout::Std430 => "Std430",
+    }
+  }
+
+  // minimum alignment of structures; std140 rounds it up to the alignment of a vec4
+  fn min_struct_alignment(self) -> usize {
+    match self {
+      StdLayout::Std140 => 16,
+      StdLayout::Std430 => 1,
+    }
+  }
+
+  // name of a method of the layout traits
+  fn method(self, suffix: &str) -> Ident {
+    let name = format!("{}{}", self.name().to_lowercase(), suffix);
+    Ident::new(&name, Span::call_site())
+  }
+}
+
+#[non_exhaustive]
+#[derive(Debug)]
+pub(crate) enum DeriveStdLayoutError {
+  UnnamedFields,
+  UnitStruct,
+  GenericStruct,
+}
+
+impl DeriveStdLayoutError {
+  pub(crate) fn unnamed_fields() -> Self {
+    DeriveStdLayoutError::UnnamedFields
+  }
+
+  pub(crate) fn unit_struct() -> Self {
+    DeriveStdLayoutError::UnitStruct
+  }
+
+  pub(crate) fn generic_struct() -> Self {
+    DeriveStdLayoutError::GenericStruct
+  }
+}
+
+impl fmt::Display for DeriveStdLayoutError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      DeriveStdLayoutError::UnnamedFields => f.write_str("unsupported unnamed fields"),
+      DeriveStdLayoutError::UnitStruct => f.write_str("unsupported unit struct"),
+      DeriveStdLayoutError::GenericStruct => f.write_str("unsupported generic struct"),
+    }
+  }
+}
+
+impl error::Error for DeriveStdLayoutError {}
+
+pub(crate) fn generate_std_layout_impl(
+  layout: StdLayout,
+  vis: Visibility,
+  ident: Ident,
+  generics: Generics,
+  struct_: DataStruct,
+) -> Result<TokenStream, DeriveStdLayoutError> {
+  if !generics.params.is_empty() {
+    return Err(DeriveStdLayoutError::generic_struct());
+  }
+
+  let named_fields = match struct_.fields {
+    Fields::Named(named_fields) => named_fields,
+    Fields::Unnamed(_) => return Err(DeriveStdLayoutError::unnamed_fields()),
+    Fields::Unit => return Err(DeriveStdLayoutError::unit_struct()),
+  };
+
+  let layout_trait = Ident::new(layout.name(), Span::call_site());
+  let element_trait = Ident::new(&format!("{}ArrayElement", layout.name()), Span::call_site());
+  let encode = layout.method("_encode");
+  let decode = layout.method("_decode");
+  let encode_element = layout.method("_encode_element");
+  let decode_element = layout.method("_decode_element");
+  let encoded_ident = Ident::new(&format!("{}{}", ident, layout.name()), ident.span());
+  let min_struct_alignment = layout.min_struct_alignment();
+
+  // fields of the encoded struct, along with the padding fields that precede them
+  let mut encoded_fields = Vec::new();
+  // initializers of the encoded struct fields
+  let mut encoded_inits = Vec::new();
+  // initializers of the decoded struct fields
+  let mut decoded_inits = Vec::new();
+  // compile-time offset checks of the encoded fields
+  let mut offset_checks = Vec::new();
+  let mut field_alignments = Vec::new();
+  // end of the previous field, in bytes
+  let mut end = quote! { 0 };
+
+  for (i, field) in named_fields.named.into_iter().enumerate() {
+    let field_ident = field.ident.unwrap();
+    let field_vis = field.vis;
+    let pad_ident = Ident::new(&format!("_pad{}", i), Span::call_site());
+
+    // arrays of scalars are encoded as Scalars, and unwrapped when decoded
+    let (field_ty, value, unwrap) = match scalar_array(&field.ty) {
+      Some((scalar, len)) => (
+        quote! { luminance::layout::Scalars<#scalar, #len> },
+        quote! { luminance::layout::Scalars(self.#field_ident) },
+        quote! { .0 },
+      ),
+      None => {
+        let field_ty = field.ty;
+        (
+          quote! { #field_ty },
+          quote! { self.#field_ident },
+          quote! {},
+        )
+      }
+    };
+
+    let alignment = quote! { <#field_ty as luminance::layout::#layout_trait>::ALIGNMENT };
+    let encoded_ty = quote! { <#field_ty as luminance::layout::#layout_trait>::Encoded };
+    let offset = quote! { luminance::layout::align_up(#end, #alignment) };
+
+    encoded_fields.push(quote! {
+      #pad_ident: [u8; #offset - (#end)],
+      #field_vis #field_ident: #encoded_ty
+    });
+    encoded_inits.push(quote! {
+      #pad_ident: [0; #offset - (#end)],
+      #field_ident: <#field_ty as luminance::layout::#layout_trait>::#encode(#value)
+    });
+    decoded_inits.push(quote! {
+      #field_ident: <#field_ty as luminance::layout::#layout_trait>::#decode(
+        encoded.#field_ident
+      )#unwrap
+    });
+    offset_checks.push(quote! {
+      assert!(::std::mem::offset_of!(#encoded_ident, #field_ident) == #offset);
+    });
+    field_alignments.push(alignment);
+
+    end = quote! { #offset + ::std::mem::size_of::<#encoded_ty>() };
+  }
+
+  let alignment = quote! {
+    luminance::layout::max_alignment(#min_struct_alignment, &[#(#field_alignments),*])
+  };
+  let size = quote! { luminance::layout::align_up(#end, #alignment) };
+  let doc = format!(
+    "[`{}`] encoded with the `{}` layout.",
+    ident,
+    layout.name().to_lowercase()
+  );
+
+  let output = quote! {
+    #[doc = #doc]
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    #vis struct #encoded_ident {
+      #(#encoded_fields,)*
+      _pad: [u8; #size - (#end)],
+    }
+
+    unsafe impl luminance::layout::#layout_trait for #ident {
+      type Encoded = #encoded_ident;
+
+      const ALIGNMENT: usize = #alignment;
+
+      fn #encode(self) -> Self::Encoded {
+        #encoded_ident {
+          #(#encoded_inits,)*
+          _pad: [0; #size - (#end)],
+        }
+      }
+
+      fn #decode(encoded: Self::Encoded) -> Self {
+        #ident {
+          #(#decoded_inits,)*
+        }
+      }
+    }
+
+    // the encoded structure already ends with its trailing padding
+    unsafe impl luminance::layout::#element_trait for #ident {
+      type Element = #encoded_ident;
+
+      fn #encode_element(self) -> Self::Element {
+        luminance::layout::#layout_trait::#encode(self)
+      }
+
+      fn #decode_element(element: Self::Element) -> Self {
+        luminance::layout::#layout_trait::#decode(element)
+      }
+    }
+
+    const _: () = {
+      #(#offset_checks)*
+      assert!(::std::mem::size_of::<#encoded_ident>() == #size);
+    };
+  };
+
+  Ok(output.into())
+}
+
+/// Scalar type and length of arrays of scalars which are not vectors — i.e. `[T; N]` where `T` is a
+/// scalar and `N` a literal other than `2`, `3` or `4`.
+fn scalar_array(ty: &Type) -> Option<(&Type, &Expr)> {
+  let (elem, len) = match ty {
+    Type::Array(TypeArray { elem, len, .. }) => (&**elem, len),
+    _ => return None,
+  };
+
+  let is_scalar = match elem {
+    Type::Path(path) if path.qself.is_none() => path
+      .path
+      .get_ident()
+      .is_some_and(|ident| SCALARS.iter().any(|scalar| ident == scalar)),
+    _ => false,
+  };
+
+  let is_vector = match len {
+    Expr::Lit(ExprLit {
+      lit: Lit::Int(n), ..
+    }) => matches!(n.base10_parse::<usize>(), Ok(2..=4)),
+    // lengths which are not literals cannot be told apart from vectors
+    _ => true,
+  };
+
+  if is_scalar && !is_vector {
+    Some((elem, len))
+  } else {
+    None
+  }
+}
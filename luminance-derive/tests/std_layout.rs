@@ -0,0 +1,160 @@
+use luminance::layout::{Scalars, Std140, Std430};
+use luminance::{Std140, Std430};
+use std::mem::{offset_of, size_of};
+
+#[derive(Clone, Copy, Debug, PartialEq, Std140, Std430)]
+struct Material {
+  albedo: [f32; 3],
+  roughness: f32,
+  emissive: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Std140, Std430)]
+struct Object {
+  transform: [[f32; 4]; 4],
+  normal: [[f32; 3]; 3],
+  material: Material,
+  weights: [[f32; 2]; 3],
+  depth: f64,
+  flags: [bool; 2],
+  materials: [Material; 2],
+  id: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Std140, Std430)]
+struct Kernel {
+  radius: f32,
+  weights: [f32; 5],
+  offsets: [i32; 8],
+  samples: Scalars<u32, 3>,
+  scale: f64,
+  single: [f64; 1],
+}
+
+fn kernel() -> Kernel {
+  Kernel {
+    radius: 2.,
+    weights: [0.1, 0.2, 0.4, 0.2, 0.1],
+    offsets: [-4, -3, -2, -1, 1, 2, 3, 4],
+    samples: Scalars([1, 2, 3]),
+    scale: 0.5,
+    single: [1.5],
+  }
+}
+
+fn object() -> Object {
+  let material = Material {
+    albedo: [0.25, 0.5, 0.75],
+    roughness: 1.,
+    emissive: true,
+  };
+
+  Object {
+    transform: [
+      [1., 0., 0., 0.],
+      [0., 1., 0., 0.],
+      [0., 0., 1., 0.],
+      [0., 0., 0., 1.],
+    ],
+    normal: [[1., 2., 3.], [4., 5., 6.], [7., 8., 9.]],
+    material,
+    weights: [[1., 2.], [3., 4.], [5., 6.]],
+    depth: 0.5,
+    flags: [false, true],
+    materials: [material; 2],
+    id: 42,
+  }
+}
+
+#[test]
+fn derive_std140() {
+  assert_eq!(<Material as Std140>::ALIGNMENT, 16);
+  assert_eq!(offset_of!(MaterialStd140, albedo), 0);
+  assert_eq!(offset_of!(MaterialStd140, roughness), 12);
+  assert_eq!(offset_of!(MaterialStd140, emissive), 16);
+  assert_eq!(size_of::<MaterialStd140>(), 32);
+
+  assert_eq!(<Object as Std140>::ALIGNMENT, 16);
+  assert_eq!(offset_of!(ObjectStd140, transform), 0);
+  assert_eq!(offset_of!(ObjectStd140, normal), 64);
+  assert_eq!(offset_of!(ObjectStd140, material), 112);
+  assert_eq!(offset_of!(ObjectStd140, weights), 144);
+  assert_eq!(offset_of!(ObjectStd140, depth), 192);
+  assert_eq!(offset_of!(ObjectStd140, flags), 200);
+  assert_eq!(offset_of!(ObjectStd140, materials), 208);
+  assert_eq!(offset_of!(ObjectStd140, id), 272);
+  assert_eq!(size_of::<ObjectStd140>(), 288);
+}
+
+#[test]
+fn derive_std430() {
+  assert_eq!(<Material as Std430>::ALIGNMENT, 16);
+  assert_eq!(size_of::<MaterialStd430>(), 32);
+
+  assert_eq!(<Object as Std430>::ALIGNMENT, 16);
+  assert_eq!(offset_of!(ObjectStd430, transform), 0);
+  assert_eq!(offset_of!(ObjectStd430, normal), 64);
+  assert_eq!(offset_of!(ObjectStd430, material), 112);
+  assert_eq!(offset_of!(ObjectStd430, weights), 144);
+  assert_eq!(offset_of!(ObjectStd430, depth), 168);
+  assert_eq!(offset_of!(ObjectStd430, flags), 176);
+  assert_eq!(offset_of!(ObjectStd430, materials), 192);
+  assert_eq!(offset_of!(ObjectStd430, id), 256);
+  assert_eq!(size_of::<ObjectStd430>(), 272);
+}
+
+#[test]
+fn derive_std140_scalar_arrays() {
+  // the stride of arrays of scalars is rounded up to 16 bytes
+  assert_eq!(<Kernel as Std140>::ALIGNMENT, 16);
+  assert_eq!(offset_of!(KernelStd140, radius), 0);
+  assert_eq!(offset_of!(KernelStd140, weights), 16);
+  assert_eq!(offset_of!(KernelStd140, offsets), 96);
+  assert_eq!(offset_of!(KernelStd140, samples), 224);
+  assert_eq!(offset_of!(KernelStd140, scale), 272);
+  assert_eq!(offset_of!(KernelStd140, single), 288);
+  assert_eq!(size_of::<KernelStd140>(), 304);
+}
+
+#[test]
+fn derive_std430_scalar_arrays() {
+  // arrays of scalars are tightly packed
+  assert_eq!(<Kernel as Std430>::ALIGNMENT, 8);
+  assert_eq!(offset_of!(KernelStd430, radius), 0);
+  assert_eq!(offset_of!(KernelStd430, weights), 4);
+  assert_eq!(offset_of!(KernelStd430, offsets), 24);
+  assert_eq!(offset_of!(KernelStd430, samples), 56);
+  assert_eq!(offset_of!(KernelStd430, scale), 72);
+  assert_eq!(offset_of!(KernelStd430, single), 80);
+  assert_eq!(size_of::<KernelStd430>(), 88);
+}
+
+#[test]
+fn std_layout_round_trip() {
+  let object = object();
+
+  let encoded = object.std140_encode();
+  assert_eq!(encoded.material.emissive, 1);
+  assert_eq!(encoded.weights[1], [3., 4., 0., 0.]);
+  assert_eq!(Object::std140_decode(encoded), object);
+
+  let encoded = object.std430_encode();
+  assert_eq!(encoded.weights[1], [3., 4.]);
+  assert_eq!(encoded.normal[2], [7., 8., 9., 0.]);
+  assert_eq!(Object::std430_decode(encoded), object);
+}
+
+#[test]
+fn scalar_arrays_round_trip() {
+  let kernel = kernel();
+
+  let encoded = kernel.std140_encode();
+  assert_eq!(encoded.weights[2], [0.4, 0., 0., 0.]);
+  assert_eq!(encoded.single, [[1.5, 0.]]);
+  assert_eq!(Kernel::std140_decode(encoded), kernel);
+
+  let encoded = kernel.std430_encode();
+  assert_eq!(encoded.offsets, [-4, -3, -2, -1, 1, 2, 3, 4]);
+  assert_eq!(encoded.samples, [1, 2, 3]);
+  assert_eq!(Kernel::std430_decode(encoded), kernel);
+}
@@ -16,6 +16,7 @@
 //! https://docs.rs/luminance
 
 use glfw::{Action, Context as _, Key, WindowEvent};
+use image::GenericImageView as _;
 use luminance::blending::{Blending, Equation, Factor};
 use luminance::context::GraphicsContext;
 use luminance::pipeline::{PipelineState, TextureBinding};
@@ -23,7 +24,7 @@ use luminance::pixel::{NormRGB8UI, NormUnsigned};
 use luminance::render_state::RenderState;
 use luminance::shader::Uniform;
 use luminance::tess::Mode;
-use luminance::texture::{Dim2, GenMipmaps, Sampler};
+use luminance::texture::{Dim2, Sampler, Texture};
 use luminance::UniformInterface;
 use luminance_glfw::GlfwSurface;
 use luminance_windowing::{WindowDim, WindowOpt};
@@ -48,25 +49,24 @@ fn main() {
     .skip(1)
     .next()
     .expect("Please provide an image path for the core texture");
-  let texture_image = image::open(&texture_path)
-    .expect("Could not load image from path")
-    .flipv()
-    .to_rgb();
+  let texture_image = image::open(&texture_path).expect("Could not load image from path");
   let (width, height) = texture_image.dimensions();
 
+  // displacement maps are uploaded with their first row at the bottom of the texture; flip them
+  // beforehand, as from_image flips rows
   let displacement_map_1 = image::load_from_memory_with_format(
     include_bytes!("./displacement-map-resources/displacement_1.png"),
     image::ImageFormat::Png,
   )
   .expect("Could not load displacement map")
-  .to_rgb();
+  .flipv();
 
   let displacement_map_2 = image::load_from_memory_with_format(
     include_bytes!("./displacement-map-resources/displacement_2.png"),
     image::ImageFormat::Png,
   )
   .expect("Could not load displacement map")
-  .to_rgb();
+  .flipv();
 
   let dim = WindowDim::Windowed { width, height };
   let surface = GlfwSurface::new_gl33("Displacement Map", WindowOpt::default().set_dim(dim))
@@ -74,27 +74,25 @@ fn main() {
   let mut context = surface.context;
   let events = surface.events_rx;
 
-  let texels = texture_image.into_raw();
-  let mut tex = context
-    .new_texture::<Dim2, NormRGB8UI>([width, height], 0, Sampler::default())
-    .expect("Could not create luminance texture");
-  tex.upload_raw(GenMipmaps::No, &texels).unwrap();
-
-  let texels = displacement_map_1.into_raw();
-  let mut displacement_tex_1 = context
-    .new_texture::<Dim2, NormRGB8UI>([128, 128], 0, Sampler::default())
-    .expect("Could not create luminance texture");
-  displacement_tex_1
-    .upload_raw(GenMipmaps::No, &texels)
-    .unwrap();
+  let mut tex =
+    Texture::<_, Dim2, NormRGB8UI>::from_image(&mut context, &texture_image, 0, Sampler::default())
+      .expect("Could not create luminance texture");
 
-  let texels = displacement_map_2.into_raw();
-  let mut displacement_tex_2 = context
-    .new_texture::<Dim2, NormRGB8UI>([101, 101], 0, Sampler::default())
-    .expect("Could not create luminance texture");
-  displacement_tex_2
-    .upload_raw(GenMipmaps::No, &texels)
-    .unwrap();
+  let mut displacement_tex_1 = Texture::<_, Dim2, NormRGB8UI>::from_image(
+    &mut context,
+    &displacement_map_1,
+    0,
+    Sampler::default(),
+  )
+  .expect("Could not create luminance texture");
+
+  let mut displacement_tex_2 = Texture::<_, Dim2, NormRGB8UI>::from_image(
+    &mut context,
+    &displacement_map_2,
+    0,
+    Sampler::default(),
+  )
+  .expect("Could not create luminance texture");
 
   let mut program = context
     .new_shader_program::<(), (), ShaderInterface>()
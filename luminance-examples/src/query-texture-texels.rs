@@ -9,7 +9,6 @@ mod common;
 
 use crate::common::{Semantics, Vertex, VertexColor, VertexPosition};
 use glfw::{Action, Context as _, Key, WindowEvent};
-use image::{save_buffer, ColorType};
 use luminance::context::GraphicsContext as _;
 use luminance::pipeline::PipelineState;
 use luminance::pixel::NormRGBA8UI;
@@ -135,10 +134,10 @@ fn main() {
       .assume();
 
     if !generated {
-      // the backbuffer contains our texels
-      let texels = fb.color_slot().get_raw_texels().unwrap();
+      // the backbuffer contains our texels; read them as an image, the right way up
+      let image = fb.color_slot().to_image().unwrap();
       // create a .png file and output it
-      save_buffer("./rendered.png", &texels, 960, 540, ColorType::Rgba8).unwrap();
+      image.save("./rendered.png").unwrap();
 
       generated = true;
     }
@@ -12,7 +12,7 @@
 //! https://docs.rs/luminance
 
 use glfw::{Action, Context as _, Key, WindowEvent};
-use luminance::backend::texture::Texture as TextureBackend;
+use image::GenericImageView as _;
 use luminance::blending::{Blending, Equation, Factor};
 use luminance::context::GraphicsContext;
 use luminance::pipeline::{PipelineState, TextureBinding};
@@ -20,7 +20,7 @@ use luminance::pixel::{NormRGB8UI, NormUnsigned};
 use luminance::render_state::RenderState;
 use luminance::shader::Uniform;
 use luminance::tess::Mode;
-use luminance::texture::{Dim2, GenMipmaps, Sampler, Texture};
+use luminance::texture::{Dim2, Sampler, Texture};
 use luminance::UniformInterface;
 use luminance_glfw::GlfwSurface;
 use luminance_windowing::{WindowDim, WindowOpt};
@@ -45,7 +45,8 @@ struct ShaderInterface {
 }
 
 fn run(texture_path: &Path) {
-  let img = read_image(texture_path).expect("error while reading image on disk");
+  // read the texture into memory as a whole bloc (i.e. no streaming)
+  let img = image::open(texture_path).expect("error while reading image on disk");
   let (width, height) = img.dimensions();
 
   let dim = WindowDim::Windowed { width, height };
@@ -54,7 +55,13 @@ fn run(texture_path: &Path) {
   let mut context = surface.context;
   let events = surface.events_rx;
 
-  let mut tex = load_from_disk(&mut context, img);
+  // create the luminance texture out of the image, converting its pixels to NormRGB8UI and
+  // flipping it so that its first row is at the bottom of the texture; the third argument is the
+  // number of mipmaps we want (leave it to 0 for now) and the latest is the sampler to use when
+  // sampling the texels in the shader (we’ll just use the default one)
+  let mut tex =
+    Texture::<_, Dim2, NormRGB8UI>::from_image(&mut context, &img, 0, Sampler::default())
+      .expect("luminance texture creation");
 
   // set the uniform interface to our type so that we can read textures from the shader
   let mut program = context
@@ -129,28 +136,3 @@ fn run(texture_path: &Path) {
     }
   }
 }
-
-// read the texture into memory as a whole bloc (i.e. no streaming)
-fn read_image(path: &Path) -> Option<image::RgbImage> {
-  image::open(path).map(|img| img.flipv().to_rgb()).ok()
-}
-
-fn load_from_disk<B>(context: &mut B, img: image::RgbImage) -> Texture<B::Backend, Dim2, NormRGB8UI>
-where
-  B: GraphicsContext,
-  B::Backend: TextureBackend<Dim2, NormRGB8UI>,
-{
-  let (width, height) = img.dimensions();
-  let texels = img.into_raw();
-
-  // create the luminance texture; the third argument is the number of mipmaps we want (leave it
-  // to 0 for now) and the latest is the sampler to use when sampling the texels in the
-  // shader (we’ll just use the default one)
-  let mut tex = Texture::new(context, [width, height], 0, Sampler::default())
-    .expect("luminance texture creation");
-
-  // the first argument disables mipmap generation (we don’t care so far)
-  tex.upload_raw(GenMipmaps::No, &texels).unwrap();
-
-  tex
-}
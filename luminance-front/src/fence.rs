@@ -0,0 +1,5 @@
+use crate::Backend;
+
+pub use luminance::fence::FenceError;
+
+pub type Fence = luminance::fence::Fence<Backend>;
@@ -1,4 +1,6 @@
 use crate::Backend;
 
 pub type Framebuffer<D, CS, DS> = luminance::framebuffer::Framebuffer<Backend, D, CS, DS>;
-pub use luminance::framebuffer::{FramebufferError, IncompleteReason};
+pub use luminance::framebuffer::{
+  BlitFilter, BlitMask, BlitRect, FramebufferError, IncompleteReason,
+};
@@ -61,11 +61,16 @@
 
 pub mod buffer;
 pub mod context;
+pub mod fence;
 pub mod framebuffer;
 pub mod pipeline;
+pub mod query;
+pub mod readback;
 pub mod render_gate;
+pub mod renderbuffer;
 pub mod shader;
 pub mod shading_gate;
+pub mod stream;
 pub mod tess;
 pub mod tess_gate;
 pub mod texture;
@@ -74,8 +79,13 @@ pub mod texture;
 pub use luminance::blending;
 pub use luminance::depth_test;
 pub use luminance::face_culling;
+#[cfg(feature = "image")]
+pub use luminance::image;
+#[cfg(feature = "loader")]
+pub use luminance::loader;
 pub use luminance::pixel;
 pub use luminance::render_state;
+pub use luminance::stencil;
 pub use luminance::vertex;
 
 // select the backend type
@@ -0,0 +1,5 @@
+use crate::Backend;
+
+pub use luminance::query::{QueryError, QueryType};
+
+pub type Query = luminance::query::Query<Backend>;
@@ -0,0 +1,5 @@
+use crate::Backend;
+
+pub use luminance::readback::ReadbackError;
+
+pub type Readback<T> = luminance::readback::Readback<Backend, T>;
@@ -0,0 +1,3 @@
+use crate::Backend;
+
+pub type Renderbuffer<P> = luminance::renderbuffer::Renderbuffer<Backend, P>;
@@ -0,0 +1,5 @@
+use crate::Backend;
+
+pub use luminance::stream::StreamError;
+
+pub type StreamBuffer<T> = luminance::stream::StreamBuffer<Backend, T>;
@@ -1,16 +1,22 @@
 //! OpenGL 3.3 backend.
 
-mod buffer;
+pub(crate) mod buffer;
 mod depth_test;
-mod framebuffer;
-mod pipeline;
-mod pixel;
-mod shader;
-mod state;
-mod tess;
-mod texture;
+pub(crate) mod fence;
+pub(crate) mod framebuffer;
+pub(crate) mod pipeline;
+pub(crate) mod pixel;
+pub(crate) mod query;
+pub(crate) mod readback;
+pub(crate) mod renderbuffer;
+pub(crate) mod shader;
+pub(crate) mod state;
+mod stencil;
+pub(crate) mod tess;
+pub(crate) mod texture;
 mod vertex_restart;
 
+use self::state::GLApi;
 pub use self::state::GLState;
 pub use self::state::StateQueryError;
 use std::cell::RefCell;
@@ -24,7 +30,7 @@ pub struct GL33 {
 
 impl GL33 {
   pub fn new() -> Result<Self, StateQueryError> {
-    GLState::new().map(|state| GL33 {
+    GLState::new(GLApi::GL).map(|state| GL33 {
       state: Rc::new(RefCell::new(state)),
     })
   }
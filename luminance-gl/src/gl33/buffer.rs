@@ -10,10 +10,14 @@ use std::ptr;
 use std::rc::Rc;
 use std::slice;
 
+use crate::gl33::readback::Readback;
 use crate::gl33::state::{Bind, GLState};
 use crate::gl33::GL33;
-use luminance::backend::buffer::{Buffer as BufferBackend, BufferSlice as BufferSliceBackend};
-use luminance::buffer::BufferError;
+use luminance::backend::buffer::{
+  Buffer as BufferBackend, BufferReadback as BufferReadbackBackend,
+  BufferSlice as BufferSliceBackend,
+};
+use luminance::buffer::{BufferError, BufferUsage, PreserveContent};
 
 /// Wrapped OpenGL buffer.
 ///
@@ -39,46 +43,90 @@ pub struct Buffer<T> {
   /// A cached version of the GPU buffer; emulate persistent mapping.
   pub(crate) buf: Vec<T>,
   gl_buf: BufferWrapper,
+  /// Pointer to the GPU memory if the buffer is persistently mapped.
+  pub(crate) persistent: Option<ptr::NonNull<T>>,
+  /// Usage the buffer was created with; required to re-allocate it.
+  pub(crate) usage: BufferUsage,
 }
 
 impl<T> Buffer<T> {
   /// Build a buffer with a number of elements for a given type.
   ///
   /// That function is required to implement repeat without Default.
-  fn new(gl33: &mut GL33, len: usize, clear_value: T) -> Result<Self, BufferError>
+  fn new(
+    gl33: &mut GL33,
+    len: usize,
+    clear_value: T,
+    usage: BufferUsage,
+  ) -> Result<Self, BufferError>
   where
     T: Copy,
   {
     let mut buf = Vec::new();
     buf.resize_with(len, || clear_value);
 
+    Ok(Self::from_vec(&gl33.state, buf, usage))
+  }
+
+  /// Build a buffer out of a [`Vec`].
+  ///
+  /// Only the state is required, so that buffers can be created by objects owning them, such as
+  /// tessellations.
+  pub(crate) fn from_vec(state: &Rc<RefCell<GLState>>, vec: Vec<T>, usage: BufferUsage) -> Self {
     // generate a buffer and force binding the handle; this prevent side-effects from previous bound
     // resources to prevent binding the buffer
     let mut handle: GLuint = 0;
     unsafe {
       gl::GenBuffers(1, &mut handle);
-      gl33
-        .state
-        .borrow_mut()
-        .bind_array_buffer(handle, Bind::Forced);
+      state.borrow_mut().bind_array_buffer(handle, Bind::Forced);
 
-      let bytes = mem::size_of::<T>() * len;
+      let bytes = mem::size_of::<T>() * vec.len();
       gl::BufferData(
         gl::ARRAY_BUFFER,
         bytes as isize,
-        buf.as_ptr() as _,
-        gl::STREAM_DRAW,
+        vec.as_ptr() as _,
+        opengl_usage(usage),
       );
     }
-    let state = gl33.state.clone();
+    let state = state.clone();
     let gl_buf = BufferWrapper { handle, state };
 
-    Ok(Buffer { gl_buf, buf })
+    Buffer {
+      gl_buf,
+      buf: vec,
+      persistent: None,
+      usage,
+    }
+  }
+
+  /// Wrap an already allocated OpenGL buffer, whose content is `buf`.
+  ///
+  /// `persistent` is the pointer to the GPU memory if the buffer is persistently mapped.
+  #[cfg(feature = "gl45")]
+  pub(crate) fn from_handle(
+    handle: GLuint,
+    state: Rc<RefCell<GLState>>,
+    buf: Vec<T>,
+    persistent: Option<ptr::NonNull<T>>,
+    usage: BufferUsage,
+  ) -> Self {
+    let gl_buf = BufferWrapper { handle, state };
+    Buffer {
+      gl_buf,
+      buf,
+      persistent,
+      usage,
+    }
   }
 
   pub(crate) fn handle(&self) -> GLuint {
     self.gl_buf.handle
   }
+
+  #[cfg(feature = "gl45")]
+  pub(crate) fn state(&self) -> &Rc<RefCell<GLState>> {
+    &self.gl_buf.state
+  }
 }
 
 unsafe impl<T> BufferBackend<T> for GL33
@@ -87,42 +135,36 @@ where
 {
   type BufferRepr = Buffer<T>;
 
-  unsafe fn new_buffer(&mut self, len: usize) -> Result<Self::BufferRepr, BufferError>
+  unsafe fn new_buffer(
+    &mut self,
+    len: usize,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>
   where
     T: Default,
   {
-    Buffer::new(self, len, T::default())
+    Buffer::new(self, len, T::default(), usage)
   }
 
   unsafe fn len(buffer: &Self::BufferRepr) -> usize {
     buffer.buf.len()
   }
 
-  unsafe fn from_vec(&mut self, vec: Vec<T>) -> Result<Self::BufferRepr, BufferError> {
-    let mut handle: GLuint = 0;
-
-    gl::GenBuffers(1, &mut handle);
-    self
-      .state
-      .borrow_mut()
-      .bind_array_buffer(handle, Bind::Forced);
-
-    let len = vec.len();
-    let bytes = mem::size_of::<T>() * len;
-    gl::BufferData(
-      gl::ARRAY_BUFFER,
-      bytes as isize,
-      vec.as_ptr() as _,
-      gl::STREAM_DRAW,
-    );
-    let state = self.state.clone();
-    let gl_buf = BufferWrapper { handle, state };
-
-    Ok(Buffer { gl_buf, buf: vec })
+  unsafe fn from_vec(
+    &mut self,
+    vec: Vec<T>,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Ok(Buffer::from_vec(&self.state, vec, usage))
   }
 
-  unsafe fn repeat(&mut self, len: usize, value: T) -> Result<Self::BufferRepr, BufferError> {
-    Buffer::new(self, len, value)
+  unsafe fn repeat(
+    &mut self,
+    len: usize,
+    value: T,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Buffer::new(self, len, value, usage)
   }
 
   unsafe fn at(buffer: &Self::BufferRepr, i: usize) -> Option<T> {
@@ -146,8 +188,8 @@ where
         .state
         .borrow_mut()
         .bind_array_buffer(buffer.handle(), Bind::Cached);
-      let ptr = gl::MapBuffer(gl::ARRAY_BUFFER, gl::WRITE_ONLY) as *mut T;
-      *ptr.add(i) = x;
+      let ptr = map_array_buffer::<T>(i, 1, gl::MAP_WRITE_BIT);
+      *ptr = x;
       let _ = gl::UnmapBuffer(gl::ARRAY_BUFFER);
 
       Ok(())
@@ -167,6 +209,11 @@ where
       _ => (),
     }
 
+    // mapping an empty range is an error
+    if buffer_len == 0 {
+      return Ok(());
+    }
+
     // first update the OpenGL buffer; if it’s okay, then we can update the cache buffer
     buffer
       .gl_buf
@@ -174,8 +221,13 @@ where
       .borrow_mut()
       .bind_array_buffer(buffer.handle(), Bind::Cached);
 
-    let ptr = gl::MapBuffer(gl::ARRAY_BUFFER, gl::WRITE_ONLY);
-    ptr::copy_nonoverlapping(values.as_ptr(), ptr as *mut T, buffer_len);
+    let ptr = map_array_buffer::<T>(0, buffer_len, gl::MAP_WRITE_BIT);
+
+    if ptr.is_null() {
+      return Err(BufferError::map_failed());
+    }
+
+    ptr::copy_nonoverlapping(values.as_ptr(), ptr, buffer_len);
     let _ = gl::UnmapBuffer(gl::ARRAY_BUFFER);
 
     buffer.buf.copy_from_slice(values);
@@ -183,33 +235,213 @@ where
     Ok(())
   }
 
+  unsafe fn write_range(
+    buffer: &mut Self::BufferRepr,
+    offset: usize,
+    values: &[T],
+  ) -> Result<(), BufferError> {
+    buffer
+      .gl_buf
+      .state
+      .borrow_mut()
+      .bind_array_buffer(buffer.handle(), Bind::Cached);
+
+    gl::BufferSubData(
+      gl::ARRAY_BUFFER,
+      (offset * mem::size_of::<T>()) as GLintptr,
+      mem::size_of_val(values) as GLsizeiptr,
+      values.as_ptr() as _,
+    );
+
+    buffer.buf[offset..offset + values.len()].copy_from_slice(values);
+
+    Ok(())
+  }
+
+  unsafe fn read_range(
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Vec<T>, BufferError> {
+    // mapping an empty range is an error
+    if len == 0 {
+      return Ok(Vec::new());
+    }
+
+    buffer
+      .gl_buf
+      .state
+      .borrow_mut()
+      .bind_array_buffer(buffer.handle(), Bind::Cached);
+
+    let ptr = map_array_buffer::<T>(start, len, gl::MAP_READ_BIT) as *const T;
+
+    if ptr.is_null() {
+      return Err(BufferError::map_failed());
+    }
+
+    let values = slice::from_raw_parts(ptr, len).to_vec();
+    let _ = gl::UnmapBuffer(gl::ARRAY_BUFFER);
+
+    Ok(values)
+  }
+
+  unsafe fn copy_from(
+    buffer: &mut Self::BufferRepr,
+    src: &Self::BufferRepr,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  ) -> Result<(), BufferError> {
+    let item_bytes = mem::size_of::<T>();
+
+    gl::BindBuffer(gl::COPY_READ_BUFFER, src.handle());
+    gl::BindBuffer(gl::COPY_WRITE_BUFFER, buffer.handle());
+    gl::CopyBufferSubData(
+      gl::COPY_READ_BUFFER,
+      gl::COPY_WRITE_BUFFER,
+      (src_start * item_bytes) as GLintptr,
+      (dst_offset * item_bytes) as GLintptr,
+      (len * item_bytes) as GLsizeiptr,
+    );
+
+    buffer.buf[dst_offset..dst_offset + len].copy_from_slice(&src.buf[src_start..src_start + len]);
+
+    Ok(())
+  }
+
   unsafe fn clear(buffer: &mut Self::BufferRepr, x: T) -> Result<(), BufferError> {
+    // mapping an empty range is an error
+    if buffer.buf.is_empty() {
+      return Ok(());
+    }
+
+    buffer
+      .gl_buf
+      .state
+      .borrow_mut()
+      .bind_array_buffer(buffer.handle(), Bind::Cached);
+
+    let ptr = map_array_buffer::<T>(0, buffer.buf.len(), gl::MAP_WRITE_BIT);
+
+    if ptr.is_null() {
+      return Err(BufferError::map_failed());
+    }
+
     for item in &mut buffer.buf {
       *item = x;
     }
 
+    ptr::copy_nonoverlapping(buffer.buf.as_ptr(), ptr, buffer.buf.len());
+    let _ = gl::UnmapBuffer(gl::ARRAY_BUFFER);
+
+    Ok(())
+  }
+
+  unsafe fn resize(
+    buffer: &mut Self::BufferRepr,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError> {
+    let mut buf = match preserve {
+      PreserveContent::Yes => GL33::read_range(buffer, 0, buffer.buf.len().min(len))?,
+      PreserveContent::No => Vec::with_capacity(len),
+    };
+    buf.resize(len, value);
+
+    // re-allocate the storage of the same buffer, so that vertex arrays using it remain valid
     buffer
       .gl_buf
       .state
       .borrow_mut()
       .bind_array_buffer(buffer.handle(), Bind::Cached);
 
-    let ptr = gl::MapBuffer(gl::ARRAY_BUFFER, gl::WRITE_ONLY);
-    ptr::copy_nonoverlapping(buffer.buf.as_ptr(), ptr as *mut T, buffer.buf.len());
-    let _ = gl::UnmapBuffer(gl::ARRAY_BUFFER);
+    gl::BufferData(
+      gl::ARRAY_BUFFER,
+      mem::size_of_val(buf.as_slice()) as GLsizeiptr,
+      buf.as_ptr() as _,
+      opengl_usage(buffer.usage),
+    );
+
+    buffer.buf = buf;
 
     Ok(())
   }
 }
 
+unsafe impl<T> BufferReadbackBackend<T> for GL33
+where
+  T: Copy,
+{
+  unsafe fn whole_async(buffer: &Self::BufferRepr) -> Result<Self::ReadbackRepr, BufferError> {
+    let len = buffer.buf.len();
+    let staging = Readback::<T>::new_staging_buffer(gl::COPY_WRITE_BUFFER, len);
+
+    gl::BindBuffer(gl::COPY_READ_BUFFER, buffer.handle());
+    gl::CopyBufferSubData(
+      gl::COPY_READ_BUFFER,
+      gl::COPY_WRITE_BUFFER,
+      0,
+      0,
+      (len * mem::size_of::<T>()) as GLsizeiptr,
+    );
+
+    Readback::from_handle(staging, len).map_err(|_| BufferError::cannot_create())
+  }
+}
+
+/// Get the OpenGL usage hint of a [`BufferUsage`].
+///
+/// Persistent mapping isn’t supported, so persistent buffers are hinted as dynamic ones.
+pub(crate) fn opengl_usage(usage: BufferUsage) -> GLenum {
+  match usage {
+    BufferUsage::Static => gl::STATIC_DRAW,
+    BufferUsage::Dynamic | BufferUsage::Persistent => gl::DYNAMIC_DRAW,
+    BufferUsage::Stream => gl::STREAM_DRAW,
+    BufferUsage::ReadBack => gl::DYNAMIC_READ,
+  }
+}
+
+/// Map `len` items of the currently bound array buffer, starting at item `offset`.
+///
+/// `glMapBufferRange` is used instead of `glMapBuffer`, which doesn’t exist on OpenGL ES.
+unsafe fn map_array_buffer<T>(offset: usize, len: usize, access: GLbitfield) -> *mut T {
+  let item_bytes = mem::size_of::<T>();
+
+  gl::MapBufferRange(
+    gl::ARRAY_BUFFER,
+    (offset * item_bytes) as GLintptr,
+    (len * item_bytes) as GLsizeiptr,
+    access,
+  ) as *mut T
+}
+
 /// Wrapper to drop buffer slices.
+///
+/// Slices of persistently mapped buffers don’t unmap anything when dropped.
 struct BufferSliceWrapper {
   handle: GLuint,
   state: Rc<RefCell<GLState>>,
+  persistent: bool,
+}
+
+impl BufferSliceWrapper {
+  fn new<T>(buffer: &Buffer<T>) -> Self {
+    BufferSliceWrapper {
+      handle: buffer.handle(),
+      state: buffer.gl_buf.state.clone(),
+      persistent: buffer.persistent.is_some(),
+    }
+  }
 }
 
 impl Drop for BufferSliceWrapper {
   fn drop(&mut self) {
+    if self.persistent {
+      return;
+    }
+
     unsafe {
       self
         .state
@@ -298,20 +530,26 @@ where
   type SliceMutRepr = BufferSliceMut<T>;
 
   unsafe fn slice_buffer(buffer: &Self::BufferRepr) -> Result<Self::SliceRepr, BufferError> {
+    if let Some(ptr) = buffer.persistent {
+      let raw = BufferSliceWrapper::new(buffer);
+      let len = buffer.buf.len();
+      let ptr = ptr.as_ptr() as *const T;
+
+      return Ok(BufferSlice { raw, len, ptr });
+    }
+
     buffer
       .gl_buf
       .state
       .borrow_mut()
       .bind_array_buffer(buffer.handle(), Bind::Cached);
 
-    let ptr = gl::MapBuffer(gl::ARRAY_BUFFER, gl::READ_ONLY) as *const T;
+    let ptr = map_array_buffer::<T>(0, buffer.buf.len(), gl::MAP_READ_BIT) as *const T;
 
     if ptr.is_null() {
       Err(BufferError::map_failed())
     } else {
-      let handle = buffer.handle();
-      let state = buffer.gl_buf.state.clone();
-      let raw = BufferSliceWrapper { handle, state };
+      let raw = BufferSliceWrapper::new(buffer);
       let len = buffer.buf.len();
 
       Ok(BufferSlice { raw, len, ptr })
@@ -321,20 +559,26 @@ where
   unsafe fn slice_buffer_mut(
     buffer: &mut Self::BufferRepr,
   ) -> Result<Self::SliceMutRepr, BufferError> {
+    if let Some(ptr) = buffer.persistent {
+      let raw = BufferSliceWrapper::new(buffer);
+      let len = buffer.buf.len();
+      let ptr = ptr.as_ptr();
+
+      return Ok(BufferSliceMut { raw, len, ptr });
+    }
+
     buffer
       .gl_buf
       .state
       .borrow_mut()
       .bind_array_buffer(buffer.handle(), Bind::Cached);
 
-    let ptr = gl::MapBuffer(gl::ARRAY_BUFFER, gl::READ_WRITE) as *mut T;
+    let ptr = map_array_buffer::<T>(0, buffer.buf.len(), gl::MAP_READ_BIT | gl::MAP_WRITE_BIT);
 
     if ptr.is_null() {
       Err(BufferError::map_failed())
     } else {
-      let handle = buffer.handle();
-      let state = buffer.gl_buf.state.clone();
-      let raw = BufferSliceWrapper { handle, state };
+      let raw = BufferSliceWrapper::new(buffer);
       let len = buffer.buf.len();
 
       Ok(BufferSliceMut { raw, len, ptr })
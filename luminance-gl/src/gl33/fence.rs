@@ -0,0 +1,59 @@
+//! OpenGL fence implementation.
+
+use gl;
+use gl::types::*;
+
+use crate::gl33::GL33;
+use luminance::backend::fence::Fence as FenceBackend;
+use luminance::fence::FenceError;
+
+pub struct Fence {
+  sync: GLsync,
+  // once signaled, a sync object stays signaled
+  signaled: bool,
+}
+
+impl Drop for Fence {
+  fn drop(&mut self) {
+    unsafe { gl::DeleteSync(self.sync) };
+  }
+}
+
+impl Fence {
+  /// Insert a fence after all the commands issued so far.
+  pub(crate) unsafe fn new() -> Result<Self, FenceError> {
+    let sync = gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+
+    if sync.is_null() {
+      Err(FenceError::cannot_create())
+    } else {
+      Ok(Fence {
+        sync,
+        signaled: false,
+      })
+    }
+  }
+
+  /// Whether the GPU has executed all the commands issued before the fence, without blocking.
+  pub(crate) unsafe fn is_signaled(&mut self) -> bool {
+    if !self.signaled {
+      // flush the commands, so that the fence eventually gets signaled even if nothing else does
+      let status = gl::ClientWaitSync(self.sync, gl::SYNC_FLUSH_COMMANDS_BIT, 0);
+      self.signaled = status == gl::ALREADY_SIGNALED || status == gl::CONDITION_SATISFIED;
+    }
+
+    self.signaled
+  }
+}
+
+unsafe impl FenceBackend for GL33 {
+  type FenceRepr = Fence;
+
+  unsafe fn new_fence(&mut self) -> Result<Self::FenceRepr, FenceError> {
+    Fence::new()
+  }
+
+  unsafe fn fence_signaled(fence: &mut Self::FenceRepr) -> bool {
+    fence.is_signaled()
+  }
+}
@@ -3,12 +3,16 @@ use gl::types::*;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::gl33::state::{Bind, GLState};
+use crate::gl33::pixel::opengl_pixel_format;
+use crate::gl33::state::{Bind, GLState, ScissorState};
 use crate::gl33::GL33;
 use luminance::backend::color_slot::ColorSlot;
 use luminance::backend::depth_slot::DepthSlot;
-use luminance::backend::framebuffer::{Framebuffer as FramebufferBackend, FramebufferBackBuffer};
-use luminance::framebuffer::{FramebufferError, IncompleteReason};
+use luminance::backend::framebuffer::{
+  Framebuffer as FramebufferBackend, FramebufferBackBuffer, FramebufferBlit, FramebufferMultisample,
+};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError, IncompleteReason};
+use luminance::pixel::PixelFormat;
 use luminance::texture::{Dim2, Dimensionable, Sampler};
 
 pub struct Framebuffer<D>
@@ -16,9 +20,12 @@ where
   D: Dimensionable,
 {
   pub(crate) handle: GLuint,
-  renderbuffer: Option<GLuint>,
+  pub(crate) renderbuffer: Option<GLuint>,
+  pub(crate) multisample: Option<Multisample>,
+  // attachment point of the depth slot, which depends on whether it has a stencil channel
+  pub(crate) depth_attachment: GLenum,
   pub(crate) size: D::Size,
-  state: Rc<RefCell<GLState>>,
+  pub(crate) state: Rc<RefCell<GLState>>,
 }
 
 impl<D> Drop for Framebuffer<D>
@@ -40,31 +47,26 @@ where
   }
 }
 
-unsafe impl<D> FramebufferBackend<D> for GL33
+impl<D> Framebuffer<D>
 where
   D: Dimensionable,
 {
-  type FramebufferRepr = Framebuffer<D>;
-
-  unsafe fn new_framebuffer<CS, DS>(
-    &mut self,
+  /// Create a framebuffer drawing to as many color attachments as `color_formats`.
+  ///
+  /// If `depth_renderbuffer` is `true`, a depth renderbuffer is attached.
+  pub(crate) unsafe fn new(
+    state: &Rc<RefCell<GLState>>,
     size: D::Size,
-    _: usize,
-    _: &Sampler,
-  ) -> Result<Self::FramebufferRepr, FramebufferError>
-  where
-    CS: ColorSlot<Self, D>,
-    DS: DepthSlot<Self, D>,
-  {
+    color_formats: &[PixelFormat],
+    depth_format: Option<PixelFormat>,
+    depth_renderbuffer: bool,
+  ) -> Self {
     let mut handle: GLuint = 0;
-    let color_formats = CS::color_formats();
-    let depth_format = DS::depth_format();
-    let mut depth_renderbuffer: Option<GLuint> = None;
 
     gl::GenFramebuffers(1, &mut handle);
 
     {
-      let mut state = self.state.borrow_mut();
+      let mut state = state.borrow_mut();
 
       state.bind_draw_framebuffer(handle);
 
@@ -75,7 +77,13 @@ where
 
     // color textures
     if color_formats.is_empty() {
-      gl::DrawBuffer(gl::NONE);
+      gl::DrawBuffers(1, &gl::NONE);
+
+      // there is no color to read either, which would make the framebuffer incomplete when read
+      // from, while blitting its depth for instance
+      gl::BindFramebuffer(gl::READ_FRAMEBUFFER, handle);
+      gl::ReadBuffer(gl::NONE);
+      gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
     } else {
       // specify the list of color buffers to draw to
       let color_buf_nb = color_formats.len() as GLsizei;
@@ -85,8 +93,8 @@ where
       gl::DrawBuffers(color_buf_nb, color_buffers.as_ptr());
     }
 
-    // depth texture
-    if depth_format.is_none() {
+    // depth renderbuffer, if no depth texture is used
+    let renderbuffer = if depth_renderbuffer {
       let mut renderbuffer: GLuint = 0;
 
       gl::GenRenderbuffers(1, &mut renderbuffer);
@@ -106,16 +114,232 @@ where
         renderbuffer,
       );
 
-      depth_renderbuffer = Some(renderbuffer);
-    }
+      Some(renderbuffer)
+    } else {
+      None
+    };
 
-    let framebuffer = Framebuffer {
+    Framebuffer {
       handle,
-      renderbuffer: depth_renderbuffer,
+      renderbuffer,
+      multisample: None,
+      depth_attachment: depth_attachment(depth_format),
       size,
-      state: self.state.clone(),
+      state: state.clone(),
+    }
+  }
+
+  /// Handle of the framebuffer pipelines render to.
+  pub(crate) fn draw_handle(&self) -> GLuint {
+    self
+      .multisample
+      .as_ref()
+      .map_or(self.handle, |multisample| multisample.handle)
+  }
+
+  /// Resolve the multisampled storage, if any, into the attached textures.
+  pub(crate) unsafe fn resolve(&self) {
+    let multisample = match self.multisample {
+      Some(ref multisample) => multisample,
+      None => return,
+    };
+
+    let mut state = self.state.borrow_mut();
+    let bounds = [
+      0,
+      0,
+      D::width(self.size) as GLint,
+      D::height(self.size) as GLint,
+    ];
+
+    state.bind_draw_framebuffer(self.handle);
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, multisample.handle);
+
+    // blits are clipped by the scissor test
+    state.set_scissor_state(ScissorState::Off);
+
+    // only the read buffer is blitted, to all the draw buffers; resolve the color attachments one
+    // by one
+    let color_buffers: Vec<_> =
+      (gl::COLOR_ATTACHMENT0..gl::COLOR_ATTACHMENT0 + multisample.color_nb as GLenum).collect();
+
+    for &color_buffer in &color_buffers {
+      let draw_buffers: Vec<_> = color_buffers
+        .iter()
+        .map(|&b| if b == color_buffer { b } else { gl::NONE })
+        .collect();
+
+      gl::ReadBuffer(color_buffer);
+      gl::DrawBuffers(draw_buffers.len() as GLsizei, draw_buffers.as_ptr());
+      resolve_blit(bounds, gl::COLOR_BUFFER_BIT);
+    }
+
+    if !color_buffers.is_empty() {
+      gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+      gl::DrawBuffers(color_buffers.len() as GLsizei, color_buffers.as_ptr());
+    }
+
+    if multisample.resolve_depth {
+      resolve_blit(bounds, gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+    }
+
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+  }
+}
+
+/// Attachment point of a depth slot of the given pixel format.
+pub(crate) fn depth_attachment(depth_format: Option<PixelFormat>) -> GLenum {
+  match depth_format {
+    Some(pf) if pf.is_depth_stencil_pixel() => gl::DEPTH_STENCIL_ATTACHMENT,
+    _ => gl::DEPTH_ATTACHMENT,
+  }
+}
+
+unsafe fn resolve_blit([x0, y0, x1, y1]: [GLint; 4], mask: GLbitfield) {
+  gl::BlitFramebuffer(x0, y0, x1, y1, x0, y0, x1, y1, mask, gl::NEAREST);
+}
+
+/// Multisampled storage of a framebuffer.
+///
+/// Pipelines render to it instead of the attached textures, which receive the resolved samples
+/// once the pipelines are done.
+pub(crate) struct Multisample {
+  handle: GLuint,
+  renderbuffers: Vec<GLuint>,
+  color_nb: usize,
+  resolve_depth: bool,
+  state: Rc<RefCell<GLState>>,
+}
+
+impl Drop for Multisample {
+  fn drop(&mut self) {
+    unsafe {
+      gl::DeleteRenderbuffers(
+        self.renderbuffers.len() as GLsizei,
+        self.renderbuffers.as_ptr(),
+      );
+
+      // deleting the bound framebuffer binds the default one
+      let mut state = self.state.borrow_mut();
+      state.bind_draw_framebuffer(0);
+      gl::DeleteFramebuffers(1, &self.handle);
+    }
+  }
+}
+
+impl Multisample {
+  /// Create a multisampled framebuffer with a renderbuffer per color format, and a depth
+  /// renderbuffer.
+  ///
+  /// The depth is resolved only if `depth_format` is known.
+  pub(crate) unsafe fn new(
+    state: &Rc<RefCell<GLState>>,
+    size: [u32; 2],
+    samples: usize,
+    color_formats: &[PixelFormat],
+    depth_format: Option<PixelFormat>,
+  ) -> Result<Self, FramebufferError> {
+    let mut max_samples = 0;
+    gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_samples);
+
+    if samples > max_samples as usize {
+      return Err(FramebufferError::unsupported_samples(samples));
+    }
+
+    let mut handle: GLuint = 0;
+    gl::GenFramebuffers(1, &mut handle);
+    state.borrow_mut().bind_draw_framebuffer(handle);
+
+    let mut multisample = Multisample {
+      handle,
+      renderbuffers: Vec::with_capacity(color_formats.len() + 1),
+      color_nb: color_formats.len(),
+      resolve_depth: depth_format.is_some(),
+      state: state.clone(),
+    };
+
+    for (i, &pf) in color_formats.iter().enumerate() {
+      let iformat = opengl_pixel_format(pf)
+        .map(|(_, iformat, _)| iformat)
+        .ok_or(FramebufferError::unsupported_attachment())?;
+
+      multisample.attach_renderbuffer(gl::COLOR_ATTACHMENT0 + i as GLenum, iformat, size, samples);
+    }
+
+    if color_formats.is_empty() {
+      gl::DrawBuffers(1, &gl::NONE);
+      gl::ReadBuffer(gl::NONE);
+    } else {
+      let color_buffers: Vec<_> =
+        (gl::COLOR_ATTACHMENT0..gl::COLOR_ATTACHMENT0 + color_formats.len() as GLenum).collect();
+
+      gl::DrawBuffers(color_buffers.len() as GLsizei, color_buffers.as_ptr());
+    }
+
+    let depth_iformat = match depth_format {
+      Some(pf) => opengl_pixel_format(pf)
+        .map(|(_, iformat, _)| iformat)
+        .ok_or(FramebufferError::unsupported_attachment())?,
+      None => gl::DEPTH_COMPONENT32F,
     };
 
+    multisample.attach_renderbuffer(depth_attachment(depth_format), depth_iformat, size, samples);
+
+    get_framebuffer_status()?;
+
+    Ok(multisample)
+  }
+
+  unsafe fn attach_renderbuffer(
+    &mut self,
+    attachment: GLenum,
+    iformat: GLenum,
+    [width, height]: [u32; 2],
+    samples: usize,
+  ) {
+    let mut renderbuffer: GLuint = 0;
+
+    gl::GenRenderbuffers(1, &mut renderbuffer);
+    gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+    gl::RenderbufferStorageMultisample(
+      gl::RENDERBUFFER,
+      samples as GLsizei,
+      iformat,
+      width as GLsizei,
+      height as GLsizei,
+    );
+    gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+    gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, renderbuffer);
+
+    self.renderbuffers.push(renderbuffer);
+  }
+}
+
+unsafe impl<D> FramebufferBackend<D> for GL33
+where
+  D: Dimensionable,
+{
+  type FramebufferRepr = Framebuffer<D>;
+
+  unsafe fn new_framebuffer<CS, DS>(
+    &mut self,
+    size: D::Size,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, D>,
+    DS: DepthSlot<Self, D>,
+  {
+    let depth_format = DS::depth_format();
+    let framebuffer = Framebuffer::new(
+      &self.state,
+      size,
+      &CS::color_formats(),
+      depth_format,
+      depth_format.is_none(),
+    );
+
     Ok(framebuffer)
   }
 
@@ -135,10 +359,15 @@ where
   }
 
   unsafe fn attach_depth_texture(
-    _: &mut Self::FramebufferRepr,
+    framebuffer: &mut Self::FramebufferRepr,
     texture: &Self::TextureRepr,
   ) -> Result<(), FramebufferError> {
-    gl::FramebufferTexture(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, texture.handle, 0);
+    gl::FramebufferTexture(
+      gl::FRAMEBUFFER,
+      framebuffer.depth_attachment,
+      texture.handle,
+      0,
+    );
 
     Ok(())
   }
@@ -156,9 +385,13 @@ where
   }
 }
 
-fn get_framebuffer_status() -> Result<(), IncompleteReason> {
+pub(crate) fn get_framebuffer_status() -> Result<(), IncompleteReason> {
   let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+  framebuffer_status(status)
+}
 
+/// Turn a framebuffer completeness status into an [`IncompleteReason`], if any.
+pub(crate) fn framebuffer_status(status: GLenum) -> Result<(), IncompleteReason> {
   match status {
     gl::FRAMEBUFFER_COMPLETE => Ok(()),
     gl::FRAMEBUFFER_UNDEFINED => Err(IncompleteReason::Undefined),
@@ -184,8 +417,130 @@ unsafe impl FramebufferBackBuffer for GL33 {
     Ok(Framebuffer {
       handle: 0,
       renderbuffer: None,
+      multisample: None,
+      depth_attachment: gl::DEPTH_ATTACHMENT,
       size,
       state: self.state.clone(),
     })
   }
 }
+
+unsafe impl FramebufferBlit for GL33 {
+  unsafe fn blit_framebuffer(
+    src: &Self::FramebufferRepr,
+    dst: &mut Self::FramebufferRepr,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError> {
+    let mut state = dst.state.borrow_mut();
+
+    state.bind_draw_framebuffer(dst.handle);
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, src.handle);
+    blit(&mut state, src_rect, dst_rect, mask, filter);
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+
+    Ok(())
+  }
+}
+
+/// Blit the read framebuffer to the draw framebuffer.
+pub(crate) unsafe fn blit(
+  state: &mut GLState,
+  src_rect: BlitRect,
+  dst_rect: BlitRect,
+  mask: BlitMask,
+  filter: BlitFilter,
+) {
+  // blits are clipped by the scissor test
+  state.set_scissor_state(ScissorState::Off);
+
+  let [src_x0, src_y0, src_x1, src_y1] = blit_bounds(src_rect);
+  let [dst_x0, dst_y0, dst_x1, dst_y1] = blit_bounds(dst_rect);
+
+  gl::BlitFramebuffer(
+    src_x0,
+    src_y0,
+    src_x1,
+    src_y1,
+    dst_x0,
+    dst_y0,
+    dst_x1,
+    dst_y1,
+    opengl_blit_mask(mask),
+    opengl_blit_filter(filter),
+  );
+}
+
+/// Bounds of a [`BlitRect`], as `[x0, y0, x1, y1]`.
+pub(crate) fn blit_bounds(rect: BlitRect) -> [GLint; 4] {
+  [
+    rect.x as GLint,
+    rect.y as GLint,
+    (rect.x + rect.width) as GLint,
+    (rect.y + rect.height) as GLint,
+  ]
+}
+
+pub(crate) fn opengl_blit_mask(mask: BlitMask) -> GLbitfield {
+  let mut bits = 0;
+
+  if mask.color {
+    bits |= gl::COLOR_BUFFER_BIT;
+  }
+
+  // the stencil is blitted along with the depth; it’s ignored by framebuffers without stencil
+  if mask.depth {
+    bits |= gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT;
+  }
+
+  bits
+}
+
+pub(crate) fn opengl_blit_filter(filter: BlitFilter) -> GLenum {
+  match filter {
+    BlitFilter::Nearest => gl::NEAREST,
+    BlitFilter::Linear => gl::LINEAR,
+  }
+}
+
+unsafe impl FramebufferMultisample for GL33 {
+  unsafe fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, Dim2>,
+    DS: DepthSlot<Self, Dim2>,
+  {
+    new_multisample_framebuffer(
+      &self.state,
+      size,
+      samples,
+      &CS::color_formats(),
+      DS::depth_format(),
+    )
+  }
+}
+
+/// Create a multisampled framebuffer, and leave its resolve framebuffer bound so that the slots are
+/// attached to it.
+pub(crate) unsafe fn new_multisample_framebuffer(
+  state: &Rc<RefCell<GLState>>,
+  size: [u32; 2],
+  samples: usize,
+  color_formats: &[PixelFormat],
+  depth_format: Option<PixelFormat>,
+) -> Result<Framebuffer<Dim2>, FramebufferError> {
+  let multisample = Multisample::new(state, size, samples, color_formats, depth_format)?;
+
+  // the depth is rendered to the multisampled storage, so there’s no need for a depth renderbuffer
+  let mut framebuffer = Framebuffer::new(state, size, color_formats, depth_format, false);
+  framebuffer.multisample = Some(multisample);
+
+  Ok(framebuffer)
+}
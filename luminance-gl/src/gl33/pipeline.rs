@@ -15,13 +15,16 @@ use luminance::tess::{Deinterleaved, DeinterleavedData, Interleaved, TessIndex,
 use luminance::texture::Dimensionable;
 use std::cell::RefCell;
 use std::marker::PhantomData;
+use std::mem;
 use std::rc::Rc;
 
-use crate::gl33::state::{BlendingState, DepthTest, FaceCullingState, GLState, ScissorState};
+use crate::gl33::state::{
+  BlendingState, DepthTest, FaceCullingState, GLState, ScissorState, StencilTestState,
+};
 use crate::gl33::GL33;
 
 pub struct Pipeline {
-  state: Rc<RefCell<GLState>>,
+  pub(crate) state: Rc<RefCell<GLState>>,
 }
 
 pub struct BoundBuffer {
@@ -85,7 +88,7 @@ where
   ) {
     let mut state = self.state.borrow_mut();
 
-    state.bind_draw_framebuffer(framebuffer.handle);
+    state.bind_draw_framebuffer(framebuffer.draw_handle());
 
     let clear_color = pipeline_state.clear_color;
     let size = framebuffer.size;
@@ -112,7 +115,12 @@ where
       clear_color[3] as _,
     ]);
 
-    if pipeline_state.clear_color_enabled || pipeline_state.clear_depth_enabled {
+    state.set_clear_stencil(pipeline_state.clear_stencil);
+
+    if pipeline_state.clear_color_enabled
+      || pipeline_state.clear_depth_enabled
+      || pipeline_state.clear_stencil_enabled
+    {
       let color_bit = if pipeline_state.clear_color_enabled {
         gl::COLOR_BUFFER_BIT
       } else {
@@ -125,6 +133,15 @@ where
         0
       };
 
+      let stencil_bit = if pipeline_state.clear_stencil_enabled {
+        // clears are masked by the stencil write mask, which might have been changed by a render
+        // state
+        state.set_stencil_write_mask(0xFF);
+        gl::STENCIL_BUFFER_BIT
+      } else {
+        0
+      };
+
       match pipeline_state.scissor().as_ref() {
         Some(region) => {
           state.set_scissor_state(ScissorState::On);
@@ -134,11 +151,15 @@ where
         None => state.set_scissor_state(ScissorState::Off),
       }
 
-      gl::Clear(color_bit | depth_bit);
+      gl::Clear(color_bit | depth_bit | stencil_bit);
     }
 
     state.enable_srgb_framebuffer(pipeline_state.srgb_enabled);
   }
+
+  unsafe fn end_pipeline(&mut self, framebuffer: &Self::FramebufferRepr) {
+    framebuffer.resolve();
+  }
 }
 
 unsafe impl<T> PipelineBuffer<T> for GL33
@@ -169,9 +190,44 @@ where
     })
   }
 
+  unsafe fn bind_buffer_range(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    let mut state = pipeline.state.borrow_mut();
+    let alignment = state.uniform_buffer_offset_alignment();
+    let offset = start * mem::size_of::<T>();
+
+    if offset / alignment * alignment != offset {
+      return Err(PipelineError::unaligned_buffer_range(offset, alignment));
+    }
+
+    let bstack = state.binding_stack_mut();
+
+    let binding = bstack.free_buffer_bindings.pop().unwrap_or_else(|| {
+      // no more free bindings; reserve one
+      let binding = bstack.next_buffer_binding;
+      bstack.next_buffer_binding += 1;
+      binding
+    });
+
+    state.bind_buffer_range(buffer.handle(), binding, offset, len * mem::size_of::<T>());
+
+    Ok(BoundBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
   unsafe fn buffer_binding(bound: &Self::BoundBufferRepr) -> u32 {
     bound.binding
   }
+
+  unsafe fn buffer_offset_alignment(&mut self) -> usize {
+    self.state.borrow().uniform_buffer_offset_alignment()
+  }
 }
 
 unsafe impl<D, P> PipelineTexture<D, P> for GL33
@@ -281,6 +337,20 @@ unsafe impl RenderGate for GL33 {
 
     gfx_state.set_depth_write(rdr_st.depth_write());
 
+    // stencil-related state
+    if let Some(stencil_test) = rdr_st.stencil_test() {
+      gfx_state.set_stencil_test_state(StencilTestState::On);
+      gfx_state.set_stencil_test(stencil_test);
+      gfx_state.set_stencil_operations(
+        rdr_st.front_stencil_operations(),
+        rdr_st.back_stencil_operations(),
+      );
+    } else {
+      gfx_state.set_stencil_test_state(StencilTestState::Off);
+    }
+
+    gfx_state.set_stencil_write_mask(rdr_st.stencil_write_mask());
+
     // face-culling state
     match rdr_st.face_culling() {
       Some(face_culling) => {
@@ -1,6 +1,6 @@
 use gl::types::*;
 
-use luminance::pixel::{Format, PixelFormat, Size, Type};
+use luminance::pixel::{Compression, Format, PixelFormat, Size, Type};
 
 // OpenGL format, internal sized-format and type.
 pub(crate) fn opengl_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GLenum)> {
@@ -23,6 +23,7 @@ pub(crate) fn opengl_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GL
     (Format::R(Size::Sixteen), Type::Unsigned) => {
       Some((gl::RED_INTEGER, gl::R16UI, gl::UNSIGNED_SHORT))
     }
+    (Format::R(Size::Sixteen), Type::Floating) => Some((gl::RED, gl::R16F, gl::HALF_FLOAT)),
 
     (Format::R(Size::ThirtyTwo), Type::NormUnsigned) => {
       Some((gl::RED_INTEGER, gl::RED, gl::UNSIGNED_INT))
@@ -60,6 +61,9 @@ pub(crate) fn opengl_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GL
     (Format::RG(Size::Sixteen, Size::Sixteen), Type::Unsigned) => {
       Some((gl::RG_INTEGER, gl::RG16UI, gl::UNSIGNED_SHORT))
     }
+    (Format::RG(Size::Sixteen, Size::Sixteen), Type::Floating) => {
+      Some((gl::RG, gl::RG16F, gl::HALF_FLOAT))
+    }
 
     (Format::RG(Size::ThirtyTwo, Size::ThirtyTwo), Type::NormUnsigned) => {
       Some((gl::RG, gl::RG, gl::UNSIGNED_INT))
@@ -108,6 +112,14 @@ pub(crate) fn opengl_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GL
       Some((gl::RGB, gl::R11F_G11F_B10F, gl::FLOAT))
     }
 
+    (Format::RGB(Size::Five, Size::Six, Size::Five), Type::NormUnsigned) => {
+      Some((gl::RGB, gl::RGB565, gl::UNSIGNED_SHORT_5_6_5))
+    }
+
+    (Format::RGBE(Size::Nine, Size::Nine, Size::Nine, Size::Five), Type::Floating) => {
+      Some((gl::RGB, gl::RGB9_E5, gl::UNSIGNED_INT_5_9_9_9_REV))
+    }
+
     (Format::RGB(Size::ThirtyTwo, Size::ThirtyTwo, Size::ThirtyTwo), Type::NormUnsigned) => {
       Some((gl::RGB, gl::RGB, gl::UNSIGNED_INT))
     }
@@ -152,6 +164,13 @@ pub(crate) fn opengl_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GL
     (Format::RGBA(Size::Sixteen, Size::Sixteen, Size::Sixteen, Size::Sixteen), Type::Unsigned) => {
       Some((gl::RGBA_INTEGER, gl::RGBA16UI, gl::UNSIGNED_SHORT))
     }
+    (Format::RGBA(Size::Sixteen, Size::Sixteen, Size::Sixteen, Size::Sixteen), Type::Floating) => {
+      Some((gl::RGBA, gl::RGBA16F, gl::HALF_FLOAT))
+    }
+
+    (Format::RGBA(Size::Ten, Size::Ten, Size::Ten, Size::Two), Type::NormUnsigned) => {
+      Some((gl::RGBA, gl::RGB10_A2, gl::UNSIGNED_INT_2_10_10_10_REV))
+    }
 
     (
       Format::RGBA(Size::ThirtyTwo, Size::ThirtyTwo, Size::ThirtyTwo, Size::ThirtyTwo),
@@ -192,6 +211,111 @@ pub(crate) fn opengl_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GL
       Some((gl::DEPTH_COMPONENT, gl::DEPTH_COMPONENT32F, gl::FLOAT))
     }
 
+    // depth and stencil
+    (Format::DepthStencil(Size::TwentyFour, Size::Eight), Type::NormUnsigned) => Some((
+      gl::DEPTH_STENCIL,
+      gl::DEPTH24_STENCIL8,
+      gl::UNSIGNED_INT_24_8,
+    )),
+    (Format::DepthStencil(Size::ThirtyTwo, Size::Eight), Type::Floating) => Some((
+      gl::DEPTH_STENCIL,
+      gl::DEPTH32F_STENCIL8,
+      gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+    )),
+
+    _ => None,
+  }
+}
+
+// S3TC formats, from EXT_texture_compression_s3tc and EXT_texture_sRGB.
+const COMPRESSED_RGB_S3TC_DXT1: GLenum = 0x83F0;
+const COMPRESSED_RGBA_S3TC_DXT1: GLenum = 0x83F1;
+const COMPRESSED_RGBA_S3TC_DXT3: GLenum = 0x83F2;
+const COMPRESSED_RGBA_S3TC_DXT5: GLenum = 0x83F3;
+const COMPRESSED_SRGB_S3TC_DXT1: GLenum = 0x8C4C;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT1: GLenum = 0x8C4D;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT3: GLenum = 0x8C4E;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT5: GLenum = 0x8C4F;
+
+// ASTC formats, from KHR_texture_compression_astc_ldr; block sizes are consecutive from these.
+const COMPRESSED_RGBA_ASTC_4X4: GLenum = 0x93B0;
+const COMPRESSED_SRGB8_ALPHA8_ASTC_4X4: GLenum = 0x93D0;
+
+// OpenGL compressed internal format.
+pub(crate) fn opengl_compressed_format(pf: PixelFormat) -> Option<GLenum> {
+  match (pf.format, pf.encoding) {
+    (Format::Compressed(Compression::BC1), _) => Some(COMPRESSED_RGB_S3TC_DXT1),
+    (Format::Compressed(Compression::BC1A), _) => Some(COMPRESSED_RGBA_S3TC_DXT1),
+    (Format::Compressed(Compression::BC2), _) => Some(COMPRESSED_RGBA_S3TC_DXT3),
+    (Format::Compressed(Compression::BC3), _) => Some(COMPRESSED_RGBA_S3TC_DXT5),
+    (Format::SRGBCompressed(Compression::BC1), _) => Some(COMPRESSED_SRGB_S3TC_DXT1),
+    (Format::SRGBCompressed(Compression::BC1A), _) => Some(COMPRESSED_SRGB_ALPHA_S3TC_DXT1),
+    (Format::SRGBCompressed(Compression::BC2), _) => Some(COMPRESSED_SRGB_ALPHA_S3TC_DXT3),
+    (Format::SRGBCompressed(Compression::BC3), _) => Some(COMPRESSED_SRGB_ALPHA_S3TC_DXT5),
+
+    (Format::Compressed(Compression::BC4), Type::NormUnsigned) => Some(gl::COMPRESSED_RED_RGTC1),
+    (Format::Compressed(Compression::BC4), Type::NormIntegral) => {
+      Some(gl::COMPRESSED_SIGNED_RED_RGTC1)
+    }
+    (Format::Compressed(Compression::BC5), Type::NormUnsigned) => Some(gl::COMPRESSED_RG_RGTC2),
+    (Format::Compressed(Compression::BC5), Type::NormIntegral) => {
+      Some(gl::COMPRESSED_SIGNED_RG_RGTC2)
+    }
+
+    (Format::Compressed(Compression::BC6H), _) => Some(gl::COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT),
+    (Format::Compressed(Compression::BC6HSigned), _) => Some(gl::COMPRESSED_RGB_BPTC_SIGNED_FLOAT),
+    (Format::Compressed(Compression::BC7), _) => Some(gl::COMPRESSED_RGBA_BPTC_UNORM),
+    (Format::SRGBCompressed(Compression::BC7), _) => Some(gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM),
+
+    (Format::Compressed(Compression::ETC2), _) => Some(gl::COMPRESSED_RGB8_ETC2),
+    (Format::Compressed(Compression::ETC2A1), _) => {
+      Some(gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2)
+    }
+    (Format::Compressed(Compression::ETC2EAC), _) => Some(gl::COMPRESSED_RGBA8_ETC2_EAC),
+    (Format::SRGBCompressed(Compression::ETC2), _) => Some(gl::COMPRESSED_SRGB8_ETC2),
+    (Format::SRGBCompressed(Compression::ETC2A1), _) => {
+      Some(gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2)
+    }
+    (Format::SRGBCompressed(Compression::ETC2EAC), _) => Some(gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC),
+    (Format::Compressed(Compression::EACR11), Type::NormUnsigned) => Some(gl::COMPRESSED_R11_EAC),
+    (Format::Compressed(Compression::EACR11), Type::NormIntegral) => {
+      Some(gl::COMPRESSED_SIGNED_R11_EAC)
+    }
+    (Format::Compressed(Compression::EACRG11), Type::NormUnsigned) => Some(gl::COMPRESSED_RG11_EAC),
+    (Format::Compressed(Compression::EACRG11), Type::NormIntegral) => {
+      Some(gl::COMPRESSED_SIGNED_RG11_EAC)
+    }
+
+    (Format::Compressed(c @ Compression::ASTC { .. }), _) => {
+      astc_block_index(c).map(|i| COMPRESSED_RGBA_ASTC_4X4 + i)
+    }
+    (Format::SRGBCompressed(c @ Compression::ASTC { .. }), _) => {
+      astc_block_index(c).map(|i| COMPRESSED_SRGB8_ALPHA8_ASTC_4X4 + i)
+    }
+
     _ => None,
   }
 }
+
+// Index of the block size of an ASTC compression, in the order of the ASTC formats.
+fn astc_block_index(compression: Compression) -> Option<GLenum> {
+  let index = match compression.block_size() {
+    [4, 4] => 0,
+    [5, 4] => 1,
+    [5, 5] => 2,
+    [6, 5] => 3,
+    [6, 6] => 4,
+    [8, 5] => 5,
+    [8, 6] => 6,
+    [8, 8] => 7,
+    [10, 5] => 8,
+    [10, 6] => 9,
+    [10, 8] => 10,
+    [10, 10] => 11,
+    [12, 10] => 12,
+    [12, 12] => 13,
+    _ => return None,
+  };
+
+  Some(index)
+}
@@ -0,0 +1,108 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::GL33;
+use luminance::backend::query::Query as QueryBackend;
+use luminance::query::{QueryError, QueryType};
+
+pub struct Query {
+  pub(crate) handle: GLuint,
+  ty: QueryType,
+  // whether the query has been ended at least once; querying the result of a query that never
+  // ran is an error
+  issued: bool,
+}
+
+impl Drop for Query {
+  fn drop(&mut self) {
+    unsafe { gl::DeleteQueries(1, &self.handle) };
+  }
+}
+
+impl Query {
+  pub(crate) unsafe fn new(ty: QueryType) -> Self {
+    let mut handle: GLuint = 0;
+    gl::GenQueries(1, &mut handle);
+
+    Self::from_handle(handle, ty)
+  }
+
+  pub(crate) fn from_handle(handle: GLuint, ty: QueryType) -> Self {
+    Query {
+      handle,
+      ty,
+      issued: false,
+    }
+  }
+
+  /// Whether the result of the query is available.
+  pub(crate) unsafe fn is_available(&self) -> bool {
+    if !self.issued {
+      return false;
+    }
+
+    let mut available: GLuint = gl::FALSE as _;
+    gl::GetQueryObjectuiv(self.handle, gl::QUERY_RESULT_AVAILABLE, &mut available);
+
+    available == gl::TRUE as _
+  }
+}
+
+unsafe impl QueryBackend for GL33 {
+  type QueryRepr = Query;
+
+  unsafe fn new_query(&mut self, ty: QueryType) -> Result<Self::QueryRepr, QueryError> {
+    Ok(Query::new(ty))
+  }
+
+  unsafe fn begin_query(&mut self, query: &mut Self::QueryRepr) -> Result<(), QueryError> {
+    // timestamps are recorded when the query ends
+    if query.ty == QueryType::Timestamp {
+      return Ok(());
+    }
+
+    if self
+      .state
+      .borrow_mut()
+      .begin_query(opengl_query_target(query.ty), query.handle)
+    {
+      Ok(())
+    } else {
+      Err(QueryError::already_active(query.ty))
+    }
+  }
+
+  unsafe fn end_query(&mut self, query: &mut Self::QueryRepr) {
+    if query.ty == QueryType::Timestamp {
+      gl::QueryCounter(query.handle, gl::TIMESTAMP);
+    } else {
+      self
+        .state
+        .borrow_mut()
+        .end_query(opengl_query_target(query.ty));
+    }
+
+    query.issued = true;
+  }
+
+  unsafe fn query_result(query: &mut Self::QueryRepr) -> Result<Option<u64>, QueryError> {
+    if !query.is_available() {
+      return Ok(None);
+    }
+
+    let mut result: GLuint64 = 0;
+    gl::GetQueryObjectui64v(query.handle, gl::QUERY_RESULT, &mut result);
+
+    Ok(Some(result))
+  }
+}
+
+pub(crate) fn opengl_query_target(ty: QueryType) -> GLenum {
+  match ty {
+    QueryType::TimeElapsed => gl::TIME_ELAPSED,
+    QueryType::Timestamp => gl::TIMESTAMP,
+    QueryType::SamplesPassed => gl::SAMPLES_PASSED,
+    QueryType::AnySamplesPassed => gl::ANY_SAMPLES_PASSED,
+    QueryType::PrimitivesGenerated => gl::PRIMITIVES_GENERATED,
+  }
+}
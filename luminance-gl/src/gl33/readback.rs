@@ -0,0 +1,110 @@
+//! OpenGL readback implementation.
+//!
+//! Readbacks copy the read data to a staging buffer — with a buffer copy or a pixel pack — and
+//! insert a fence right after. The staging buffer is mapped once the fence is signaled. Staging
+//! buffers are bound to the copy and pixel pack targets only, which are not tracked by the graphics
+//! state.
+
+use gl;
+use gl::types::*;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+use crate::gl33::fence::Fence;
+use crate::gl33::GL33;
+use luminance::backend::readback::Readback as ReadbackBackend;
+use luminance::fence::FenceError;
+use luminance::readback::ReadbackError;
+
+pub struct Readback<T> {
+  handle: GLuint, // staging buffer
+  len: usize,
+  fence: Fence,
+  _t: PhantomData<T>,
+}
+
+impl<T> Drop for Readback<T> {
+  fn drop(&mut self) {
+    unsafe { gl::DeleteBuffers(1, &self.handle) };
+  }
+}
+
+impl<T> Readback<T> {
+  /// Create a staging buffer able to hold `len` items, and bind it to `target`.
+  pub(crate) unsafe fn new_staging_buffer(target: GLenum, len: usize) -> GLuint {
+    let mut handle: GLuint = 0;
+
+    gl::GenBuffers(1, &mut handle);
+    gl::BindBuffer(target, handle);
+    gl::BufferData(
+      target,
+      (len * mem::size_of::<T>()) as GLsizeiptr,
+      ptr::null(),
+      gl::STREAM_READ,
+    );
+
+    handle
+  }
+
+  /// Wrap the staging buffer `handle` holding `len` items, once the commands writing to it are
+  /// issued.
+  ///
+  /// The staging buffer is deleted if the fence cannot be inserted.
+  pub(crate) unsafe fn from_handle(handle: GLuint, len: usize) -> Result<Self, FenceError> {
+    match Fence::new() {
+      Ok(fence) => Ok(Readback {
+        handle,
+        len,
+        fence,
+        _t: PhantomData,
+      }),
+
+      Err(e) => {
+        gl::DeleteBuffers(1, &handle);
+        Err(e)
+      }
+    }
+  }
+}
+
+unsafe impl<T> ReadbackBackend<T> for GL33
+where
+  T: Copy,
+{
+  type ReadbackRepr = Readback<T>;
+
+  unsafe fn readback_result(
+    readback: &mut Self::ReadbackRepr,
+  ) -> Result<Option<Vec<T>>, ReadbackError> {
+    if !readback.fence.is_signaled() {
+      return Ok(None);
+    }
+
+    // empty ranges cannot be mapped
+    if readback.len == 0 {
+      return Ok(Some(Vec::new()));
+    }
+
+    gl::BindBuffer(gl::COPY_READ_BUFFER, readback.handle);
+
+    let ptr = gl::MapBufferRange(
+      gl::COPY_READ_BUFFER,
+      0,
+      (readback.len * mem::size_of::<T>()) as GLsizeiptr,
+      gl::MAP_READ_BIT,
+    ) as *const T;
+
+    if ptr.is_null() {
+      return Err(ReadbackError::map_failed());
+    }
+
+    let mut data = Vec::with_capacity(readback.len);
+    ptr::copy_nonoverlapping(ptr, data.as_mut_ptr(), readback.len);
+    data.set_len(readback.len);
+
+    gl::UnmapBuffer(gl::COPY_READ_BUFFER);
+
+    Ok(Some(data))
+  }
+}
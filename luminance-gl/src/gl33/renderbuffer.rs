@@ -0,0 +1,101 @@
+//! OpenGL renderbuffer implementation.
+
+use gl;
+use gl::types::*;
+
+use crate::gl33::pixel::opengl_pixel_format;
+use crate::gl33::GL33;
+use luminance::backend::renderbuffer::{Renderbuffer as RenderbufferBackend, RenderbufferBase};
+use luminance::framebuffer::FramebufferError;
+use luminance::pixel::Pixel;
+
+pub struct Renderbuffer {
+  pub(crate) handle: GLuint,
+}
+
+impl Drop for Renderbuffer {
+  fn drop(&mut self) {
+    unsafe { gl::DeleteRenderbuffers(1, &self.handle) };
+  }
+}
+
+impl Renderbuffer {
+  /// Create a renderbuffer with storage for `size` pixels of internal format `iformat`.
+  pub(crate) unsafe fn new(iformat: GLenum, [width, height]: [u32; 2]) -> Self {
+    let mut handle: GLuint = 0;
+
+    gl::GenRenderbuffers(1, &mut handle);
+    gl::BindRenderbuffer(gl::RENDERBUFFER, handle);
+    gl::RenderbufferStorage(
+      gl::RENDERBUFFER,
+      iformat,
+      width as GLsizei,
+      height as GLsizei,
+    );
+    gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+    Renderbuffer { handle }
+  }
+}
+
+/// Internal format of renderbuffers, given the OpenGL format, internal format and type of their
+/// pixel format.
+///
+/// Renderbuffer storage requires sized internal formats, which rules out the 32-bit normalized
+/// formats stored with unsized internal formats.
+pub(crate) fn renderbuffer_format(
+  format: Option<(GLenum, GLenum, GLenum)>,
+) -> Result<GLenum, FramebufferError> {
+  match format {
+    Some((_, iformat, _)) if !matches!(iformat, gl::RED | gl::RG | gl::RGB | gl::RGBA) => {
+      Ok(iformat)
+    }
+    _ => Err(FramebufferError::unsupported_attachment()),
+  }
+}
+
+unsafe impl RenderbufferBase for GL33 {
+  type RenderbufferRepr = Renderbuffer;
+}
+
+unsafe impl<P> RenderbufferBackend<P> for GL33
+where
+  P: Pixel,
+{
+  unsafe fn new_renderbuffer(
+    &mut self,
+    size: [u32; 2],
+  ) -> Result<Self::RenderbufferRepr, FramebufferError> {
+    let iformat = renderbuffer_format(opengl_pixel_format(P::pixel_format()))?;
+    Ok(Renderbuffer::new(iformat, size))
+  }
+
+  unsafe fn attach_color_renderbuffer(
+    _: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    gl::FramebufferRenderbuffer(
+      gl::FRAMEBUFFER,
+      gl::COLOR_ATTACHMENT0 + attachment_index as GLenum,
+      gl::RENDERBUFFER,
+      renderbuffer.handle,
+    );
+
+    Ok(())
+  }
+
+  unsafe fn attach_depth_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+  ) -> Result<(), FramebufferError> {
+    gl::FramebufferRenderbuffer(
+      gl::FRAMEBUFFER,
+      framebuffer.depth_attachment,
+      gl::RENDERBUFFER,
+      renderbuffer.handle,
+    );
+
+    Ok(())
+  }
+}
@@ -20,6 +20,46 @@ pub struct Stage {
   ty: StageType,
 }
 
+impl Stage {
+  /// Compile a stage from its full source, including the `#version` directive.
+  pub(crate) unsafe fn new(ty: StageType, src: &str) -> Result<Self, StageError> {
+    let handle = gl::CreateShader(opengl_shader_type(ty));
+
+    if handle == 0 {
+      return Err(StageError::compilation_failed(
+        ty,
+        "unable to create shader stage",
+      ));
+    }
+
+    let c_src = CString::new(src.as_bytes()).unwrap();
+    gl::ShaderSource(handle, 1, [c_src.as_ptr()].as_ptr(), null());
+    gl::CompileShader(handle);
+
+    let mut compiled: GLint = gl::FALSE.into();
+    gl::GetShaderiv(handle, gl::COMPILE_STATUS, &mut compiled);
+
+    if compiled == gl::TRUE.into() {
+      Ok(Stage { handle, ty })
+    } else {
+      let mut log_len: GLint = 0;
+      gl::GetShaderiv(handle, gl::INFO_LOG_LENGTH, &mut log_len);
+
+      let mut log: Vec<u8> = Vec::with_capacity(log_len as usize);
+      gl::GetShaderInfoLog(handle, log_len, null_mut(), log.as_mut_ptr() as *mut GLchar);
+
+      gl::DeleteShader(handle);
+
+      log.set_len(log_len as usize);
+
+      Err(StageError::compilation_failed(
+        ty,
+        String::from_utf8(log).unwrap(),
+      ))
+    }
+  }
+}
+
 impl Drop for Stage {
   fn drop(&mut self) {
     unsafe {
@@ -42,6 +82,23 @@ impl Drop for Program {
 }
 
 impl Program {
+  /// Link a compute program out of a single compute stage.
+  #[cfg(feature = "gl45")]
+  pub(crate) unsafe fn from_compute_stage(stage: &Stage) -> Result<Self, ProgramError> {
+    if stage.ty != StageType::ComputeShader {
+      return Err(ProgramError::link_failed(format!(
+        "cannot link a compute program with a {}",
+        stage.ty
+      )));
+    }
+
+    let handle = gl::CreateProgram();
+    gl::AttachShader(handle, stage.handle);
+
+    let program = Program { handle };
+    program.link().map(move |_| program)
+  }
+
   fn link(&self) -> Result<(), ProgramError> {
     let handle = self.handle;
 
@@ -79,10 +136,23 @@ impl UniformBuilder {
     }
   }
 
-  fn ask_uniform<T>(&self, name: &str) -> Result<Uniform<T>, UniformWarning>
-  where
-    T: Uniformable<GL33>,
-  {
+  /// Ask for a uniform of type `ty`, checking the type against the one used in the program.
+  pub(crate) fn ask<T>(&self, name: &str, ty: UniformType) -> Result<Uniform<T>, UniformWarning> {
+    let uniform = match ty {
+      UniformType::BufferBinding => self.ask_uniform_block(name)?,
+
+      // storage blocks are not uniforms, so there’s no type to check them against
+      UniformType::StorageBufferBinding => return self.ask_storage_block(name),
+
+      _ => self.ask_uniform(name)?,
+    };
+
+    uniform_type_match(self.handle, name, ty)?;
+
+    Ok(uniform)
+  }
+
+  fn ask_uniform<T>(&self, name: &str) -> Result<Uniform<T>, UniformWarning> {
     let location = {
       let c_name = CString::new(name.as_bytes()).unwrap();
       unsafe { gl::GetUniformLocation(self.handle, c_name.as_ptr() as *const GLchar) }
@@ -95,10 +165,7 @@ impl UniformBuilder {
     }
   }
 
-  fn ask_uniform_block<T>(&self, name: &str) -> Result<Uniform<T>, UniformWarning>
-  where
-    T: Uniformable<GL33>,
-  {
+  fn ask_uniform_block<T>(&self, name: &str) -> Result<Uniform<T>, UniformWarning> {
     let location = {
       let c_name = CString::new(name.as_bytes()).unwrap();
       unsafe { gl::GetUniformBlockIndex(self.handle, c_name.as_ptr() as *const GLchar) }
@@ -110,6 +177,25 @@ impl UniformBuilder {
       Ok(unsafe { Uniform::new(location as _) })
     }
   }
+
+  fn ask_storage_block<T>(&self, name: &str) -> Result<Uniform<T>, UniformWarning> {
+    let location = {
+      let c_name = CString::new(name.as_bytes()).unwrap();
+      unsafe {
+        gl::GetProgramResourceIndex(
+          self.handle,
+          gl::SHADER_STORAGE_BLOCK,
+          c_name.as_ptr() as *const GLchar,
+        )
+      }
+    };
+
+    if location == gl::INVALID_INDEX {
+      Err(UniformWarning::inactive(name))
+    } else {
+      Ok(unsafe { Uniform::new(location as _) })
+    }
+  }
 }
 
 unsafe impl Shader for GL33 {
@@ -120,39 +206,10 @@ unsafe impl Shader for GL33 {
   type UniformBuilderRepr = UniformBuilder;
 
   unsafe fn new_stage(&mut self, ty: StageType, src: &str) -> Result<Self::StageRepr, StageError> {
-    let handle = gl::CreateShader(opengl_shader_type(ty));
-
-    if handle == 0 {
-      return Err(StageError::compilation_failed(
-        ty,
-        "unable to create shader stage",
-      ));
-    }
-
-    let c_src = CString::new(glsl_pragma_src(src).as_bytes()).unwrap();
-    gl::ShaderSource(handle, 1, [c_src.as_ptr()].as_ptr(), null());
-    gl::CompileShader(handle);
-
-    let mut compiled: GLint = gl::FALSE.into();
-    gl::GetShaderiv(handle, gl::COMPILE_STATUS, &mut compiled);
-
-    if compiled == gl::TRUE.into() {
-      Ok(Stage { handle, ty })
-    } else {
-      let mut log_len: GLint = 0;
-      gl::GetShaderiv(handle, gl::INFO_LOG_LENGTH, &mut log_len);
-
-      let mut log: Vec<u8> = Vec::with_capacity(log_len as usize);
-      gl::GetShaderInfoLog(handle, log_len, null_mut(), log.as_mut_ptr() as *mut GLchar);
-
-      gl::DeleteShader(handle);
-
-      log.set_len(log_len as usize);
-
-      Err(StageError::compilation_failed(
-        ty,
-        String::from_utf8(log).unwrap(),
-      ))
+    match ty {
+      // compute shaders are core since OpenGL 4.3
+      StageType::ComputeShader => Err(StageError::unsupported_type(ty)),
+      _ => Stage::new(ty, &glsl_pragma_src(src)),
     }
   }
 
@@ -212,14 +269,7 @@ unsafe impl Shader for GL33 {
   where
     T: Uniformable<Self>,
   {
-    let uniform = match T::ty() {
-      UniformType::BufferBinding => uniform_builder.ask_uniform_block(name)?,
-      _ => uniform_builder.ask_uniform(name)?,
-    };
-
-    uniform_type_match(uniform_builder.handle, name, T::ty())?;
-
-    Ok(uniform)
+    uniform_builder.ask(name, T::ty())
   }
 
   unsafe fn unbound<T>(_: &mut Self::UniformBuilderRepr) -> Uniform<T>
@@ -237,6 +287,7 @@ fn opengl_shader_type(t: StageType) -> GLenum {
     StageType::VertexShader => gl::VERTEX_SHADER,
     StageType::GeometryShader => gl::GEOMETRY_SHADER,
     StageType::FragmentShader => gl::FRAGMENT_SHADER,
+    StageType::ComputeShader => gl::COMPUTE_SHADER,
   }
 }
 
@@ -403,8 +454,8 @@ fn get_vertex_attrib_location(
 }
 
 macro_rules! impl_Uniformable {
-  (&[[$t:ty; $dim:expr]], $uty:tt, $f:tt) => {
-    unsafe impl<'a> Uniformable<GL33> for &'a [[$t; $dim]] {
+  ($backend:ty, &[[$t:ty; $dim:expr]], $uty:tt, $f:tt) => {
+    unsafe impl<'a> Uniformable<$backend> for &'a [[$t; $dim]] {
       unsafe fn ty() -> UniformType {
         UniformType::$uty
       }
@@ -415,8 +466,8 @@ macro_rules! impl_Uniformable {
     }
   };
 
-  (&[$t:ty], $uty:tt, $f:tt) => {
-    unsafe impl<'a> Uniformable<GL33> for &'a [$t] {
+  ($backend:ty, &[$t:ty], $uty:tt, $f:tt) => {
+    unsafe impl<'a> Uniformable<$backend> for &'a [$t] {
       unsafe fn ty() -> UniformType {
         UniformType::$uty
       }
@@ -427,8 +478,8 @@ macro_rules! impl_Uniformable {
     }
   };
 
-  ([$t:ty; $dim:expr], $uty:tt, $f:tt) => {
-    unsafe impl Uniformable<GL33> for [$t; $dim] {
+  ($backend:ty, [$t:ty; $dim:expr], $uty:tt, $f:tt) => {
+    unsafe impl Uniformable<$backend> for [$t; $dim] {
       unsafe fn ty() -> UniformType {
         UniformType::$uty
       }
@@ -439,8 +490,8 @@ macro_rules! impl_Uniformable {
     }
   };
 
-  ($t:ty, $uty:tt, $f:tt) => {
-    unsafe impl Uniformable<GL33> for $t {
+  ($backend:ty, $t:ty, $uty:tt, $f:tt) => {
+    unsafe impl Uniformable<$backend> for $t {
       unsafe fn ty() -> UniformType {
         UniformType::$uty
       }
@@ -452,8 +503,8 @@ macro_rules! impl_Uniformable {
   };
 
   // matrix notation
-  (mat &[$t:ty], $uty:tt, $f:tt) => {
-    unsafe impl<'a> Uniformable<GL33> for &'a [$t] {
+  ($backend:ty, mat &[$t:ty], $uty:tt, $f:tt) => {
+    unsafe impl<'a> Uniformable<$backend> for &'a [$t] {
       unsafe fn ty() -> UniformType {
         UniformType::$uty
       }
@@ -469,8 +520,8 @@ macro_rules! impl_Uniformable {
     }
   };
 
-  (mat $t:ty, $uty:tt, $f:tt) => {
-    unsafe impl Uniformable<GL33> for $t {
+  ($backend:ty, mat $t:ty, $uty:tt, $f:tt) => {
+    unsafe impl Uniformable<$backend> for $t {
       unsafe fn ty() -> UniformType {
         UniformType::$uty
       }
@@ -482,236 +533,249 @@ macro_rules! impl_Uniformable {
   };
 }
 
-impl_Uniformable!(i32, Int, Uniform1i);
-impl_Uniformable!([i32; 2], IVec2, Uniform2iv);
-impl_Uniformable!([i32; 3], IVec3, Uniform3iv);
-impl_Uniformable!([i32; 4], IVec4, Uniform4iv);
-impl_Uniformable!(&[i32], Int, Uniform1iv);
-impl_Uniformable!(&[[i32; 2]], IVec2, Uniform2iv);
-impl_Uniformable!(&[[i32; 3]], IVec3, Uniform3iv);
-impl_Uniformable!(&[[i32; 4]], IVec4, Uniform4iv);
-
-impl_Uniformable!(u32, UInt, Uniform1ui);
-impl_Uniformable!([u32; 2], UIVec2, Uniform2uiv);
-impl_Uniformable!([u32; 3], UIVec3, Uniform3uiv);
-impl_Uniformable!([u32; 4], UIVec4, Uniform4uiv);
-impl_Uniformable!(&[u32], UInt, Uniform1uiv);
-impl_Uniformable!(&[[u32; 2]], UIVec2, Uniform2uiv);
-impl_Uniformable!(&[[u32; 3]], UIVec3, Uniform3uiv);
-impl_Uniformable!(&[[u32; 4]], UIVec4, Uniform4uiv);
-
-impl_Uniformable!(f32, Float, Uniform1f);
-impl_Uniformable!([f32; 2], Vec2, Uniform2fv);
-impl_Uniformable!([f32; 3], Vec3, Uniform3fv);
-impl_Uniformable!([f32; 4], Vec4, Uniform4fv);
-impl_Uniformable!(&[f32], Float, Uniform1fv);
-impl_Uniformable!(&[[f32; 2]], Vec2, Uniform2fv);
-impl_Uniformable!(&[[f32; 3]], Vec3, Uniform3fv);
-impl_Uniformable!(&[[f32; 4]], Vec4, Uniform4fv);
-
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(f64, Double, Uniform1d);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!([f64; 2], DVec2, Uniform2dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!([f64; 3], DVec3, Uniform3dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!([f64; 4], DVec4, Uniform4dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(&[f64], Double, Uniform1dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(&[[f64; 2]], DVec2, Uniform2dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(&[[f64; 3]], DVec3, Uniform3dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(&[[f64; 4]], DVec4, Uniform4dv);
+#[cfg(any(feature = "gles3", feature = "gl45"))]
+pub(crate) use impl_Uniformable;
+
+// Implement Uniformable for all the types supported by every OpenGL backend.
+macro_rules! impl_Uniformables {
+  ($backend:ty) => {
+    impl_Uniformable!($backend, i32, Int, Uniform1i);
+    impl_Uniformable!($backend, [i32; 2], IVec2, Uniform2iv);
+    impl_Uniformable!($backend, [i32; 3], IVec3, Uniform3iv);
+    impl_Uniformable!($backend, [i32; 4], IVec4, Uniform4iv);
+    impl_Uniformable!($backend, &[i32], Int, Uniform1iv);
+    impl_Uniformable!($backend, &[[i32; 2]], IVec2, Uniform2iv);
+    impl_Uniformable!($backend, &[[i32; 3]], IVec3, Uniform3iv);
+    impl_Uniformable!($backend, &[[i32; 4]], IVec4, Uniform4iv);
+
+    impl_Uniformable!($backend, u32, UInt, Uniform1ui);
+    impl_Uniformable!($backend, [u32; 2], UIVec2, Uniform2uiv);
+    impl_Uniformable!($backend, [u32; 3], UIVec3, Uniform3uiv);
+    impl_Uniformable!($backend, [u32; 4], UIVec4, Uniform4uiv);
+    impl_Uniformable!($backend, &[u32], UInt, Uniform1uiv);
+    impl_Uniformable!($backend, &[[u32; 2]], UIVec2, Uniform2uiv);
+    impl_Uniformable!($backend, &[[u32; 3]], UIVec3, Uniform3uiv);
+    impl_Uniformable!($backend, &[[u32; 4]], UIVec4, Uniform4uiv);
+
+    impl_Uniformable!($backend, f32, Float, Uniform1f);
+    impl_Uniformable!($backend, [f32; 2], Vec2, Uniform2fv);
+    impl_Uniformable!($backend, [f32; 3], Vec3, Uniform3fv);
+    impl_Uniformable!($backend, [f32; 4], Vec4, Uniform4fv);
+    impl_Uniformable!($backend, &[f32], Float, Uniform1fv);
+    impl_Uniformable!($backend, &[[f32; 2]], Vec2, Uniform2fv);
+    impl_Uniformable!($backend, &[[f32; 3]], Vec3, Uniform3fv);
+    impl_Uniformable!($backend, &[[f32; 4]], Vec4, Uniform4fv);
+
+    impl_Uniformable!($backend, mat [[f32; 2]; 2], M22, UniformMatrix2fv);
+    impl_Uniformable!($backend, mat & [[[f32; 2]; 2]], M22, UniformMatrix2fv);
+
+    impl_Uniformable!($backend, mat [[f32; 3]; 3], M33, UniformMatrix3fv);
+    impl_Uniformable!($backend, mat & [[[f32; 3]; 3]], M33, UniformMatrix3fv);
+
+    impl_Uniformable!($backend, mat [[f32; 4]; 4], M44, UniformMatrix4fv);
+    impl_Uniformable!($backend, mat & [[[f32; 4]; 4]], M44, UniformMatrix4fv);
+
+    unsafe impl Uniformable<$backend> for bool {
+      unsafe fn ty() -> UniformType {
+        UniformType::Bool
+      }
 
-impl_Uniformable!(mat [[f32; 2]; 2], M22, UniformMatrix2fv);
-impl_Uniformable!(mat & [[[f32; 2]; 2]], M22, UniformMatrix2fv);
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        gl::Uniform1ui(uniform.index(), self as u32);
+      }
+    }
 
-impl_Uniformable!(mat [[f32; 3]; 3], M33, UniformMatrix3fv);
-impl_Uniformable!(mat & [[[f32; 3]; 3]], M33, UniformMatrix3fv);
+    unsafe impl Uniformable<$backend> for [bool; 2] {
+      unsafe fn ty() -> UniformType {
+        UniformType::BVec2
+      }
 
-impl_Uniformable!(mat [[f32; 4]; 4], M44, UniformMatrix4fv);
-impl_Uniformable!(mat & [[[f32; 4]; 4]], M44, UniformMatrix4fv);
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        let v = [self[0] as u32, self[1] as u32];
+        gl::Uniform2uiv(uniform.index(), 1, v.as_ptr() as _);
+      }
+    }
 
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(mat [[f64; 2]; 2], DM22, UniformMatrix2dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(mat & [[[f64; 2]; 2]], DM22, UniformMatrix2dv);
+    unsafe impl Uniformable<$backend> for [bool; 3] {
+      unsafe fn ty() -> UniformType {
+        UniformType::BVec3
+      }
 
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(mat [[f64; 3]; 3], DM33, UniformMatrix3dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(mat & [[[f64; 3]; 3]], DM33, UniformMatrix3dv);
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        let v = [self[0] as u32, self[1] as u32, self[2] as u32];
+        gl::Uniform3uiv(uniform.index(), 1, v.as_ptr() as _);
+      }
+    }
 
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(mat [[f64; 4]; 4], DM44, UniformMatrix4dv);
-#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
-impl_Uniformable!(mat & [[[f64; 4]; 4]], DM44, UniformMatrix4dv);
+    unsafe impl Uniformable<$backend> for [bool; 4] {
+      unsafe fn ty() -> UniformType {
+        UniformType::BVec4
+      }
 
-unsafe impl Uniformable<GL33> for bool {
-  unsafe fn ty() -> UniformType {
-    UniformType::Bool
-  }
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        let v = [
+          self[0] as u32,
+          self[1] as u32,
+          self[2] as u32,
+          self[3] as u32,
+        ];
+        gl::Uniform4uiv(uniform.index(), 1, v.as_ptr() as _);
+      }
+    }
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    gl::Uniform1ui(uniform.index(), self as u32);
-  }
-}
+    unsafe impl<'a> Uniformable<$backend> for &'a [bool] {
+      unsafe fn ty() -> UniformType {
+        UniformType::Bool
+      }
 
-unsafe impl Uniformable<GL33> for [bool; 2] {
-  unsafe fn ty() -> UniformType {
-    UniformType::BVec2
-  }
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        let v: Vec<_> = self.iter().map(|x| *x as u32).collect();
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    let v = [self[0] as u32, self[1] as u32];
-    gl::Uniform2uiv(uniform.index(), 1, v.as_ptr() as _);
-  }
-}
+        gl::Uniform1uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
+      }
+    }
 
-unsafe impl Uniformable<GL33> for [bool; 3] {
-  unsafe fn ty() -> UniformType {
-    UniformType::BVec3
-  }
+    unsafe impl<'a> Uniformable<$backend> for &'a [[bool; 2]] {
+      unsafe fn ty() -> UniformType {
+        UniformType::BVec2
+      }
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    let v = [self[0] as u32, self[1] as u32, self[2] as u32];
-    gl::Uniform3uiv(uniform.index(), 1, v.as_ptr() as _);
-  }
-}
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        let v: Vec<_> = self.iter().map(|x| [x[0] as u32, x[1] as u32]).collect();
 
-unsafe impl Uniformable<GL33> for [bool; 4] {
-  unsafe fn ty() -> UniformType {
-    UniformType::BVec4
-  }
+        gl::Uniform2uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
+      }
+    }
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    let v = [
-      self[0] as u32,
-      self[1] as u32,
-      self[2] as u32,
-      self[3] as u32,
-    ];
-    gl::Uniform4uiv(uniform.index(), 1, v.as_ptr() as _);
-  }
-}
+    unsafe impl<'a> Uniformable<$backend> for &'a [[bool; 3]] {
+      unsafe fn ty() -> UniformType {
+        UniformType::BVec3
+      }
 
-unsafe impl<'a> Uniformable<GL33> for &'a [bool] {
-  unsafe fn ty() -> UniformType {
-    UniformType::Bool
-  }
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        let v: Vec<_> = self
+          .iter()
+          .map(|x| [x[0] as u32, x[1] as u32, x[2] as u32])
+          .collect();
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    let v: Vec<_> = self.iter().map(|x| *x as u32).collect();
+        gl::Uniform3uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
+      }
+    }
 
-    gl::Uniform1uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
-  }
-}
+    unsafe impl<'a> Uniformable<$backend> for &'a [[bool; 4]] {
+      unsafe fn ty() -> UniformType {
+        UniformType::BVec4
+      }
 
-unsafe impl<'a> Uniformable<GL33> for &'a [[bool; 2]] {
-  unsafe fn ty() -> UniformType {
-    UniformType::BVec2
-  }
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        let v: Vec<_> = self
+          .iter()
+          .map(|x| [x[0] as u32, x[1] as u32, x[2] as u32, x[3] as u32])
+          .collect();
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    let v: Vec<_> = self.iter().map(|x| [x[0] as u32, x[1] as u32]).collect();
+        gl::Uniform4uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
+      }
+    }
 
-    gl::Uniform2uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
-  }
-}
+    unsafe impl<T> Uniformable<$backend> for BufferBinding<T> {
+      unsafe fn ty() -> UniformType {
+        UniformType::BufferBinding
+      }
 
-unsafe impl<'a> Uniformable<GL33> for &'a [[bool; 3]] {
-  unsafe fn ty() -> UniformType {
-    UniformType::BVec3
-  }
+      unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+        gl::UniformBlockBinding(
+          program.handle,
+          uniform.index() as GLuint,
+          self.binding() as GLuint,
+        )
+      }
+    }
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    let v: Vec<_> = self
-      .iter()
-      .map(|x| [x[0] as u32, x[1] as u32, x[2] as u32])
-      .collect();
+    unsafe impl<D, S> Uniformable<$backend> for TextureBinding<D, S>
+    where
+      D: Dimensionable,
+      S: SamplerType,
+    {
+      unsafe fn ty() -> UniformType {
+        match (S::sample_type(), D::dim()) {
+          (PixelType::NormIntegral, Dim::Dim1) => UniformType::Sampler1D,
+          (PixelType::NormUnsigned, Dim::Dim1) => UniformType::Sampler1D,
+          (PixelType::Integral, Dim::Dim1) => UniformType::ISampler1D,
+          (PixelType::Unsigned, Dim::Dim1) => UniformType::UISampler1D,
+          (PixelType::Floating, Dim::Dim1) => UniformType::Sampler1D,
+
+          (PixelType::NormIntegral, Dim::Dim2) => UniformType::Sampler2D,
+          (PixelType::NormUnsigned, Dim::Dim2) => UniformType::Sampler2D,
+          (PixelType::Integral, Dim::Dim2) => UniformType::ISampler2D,
+          (PixelType::Unsigned, Dim::Dim2) => UniformType::UISampler2D,
+          (PixelType::Floating, Dim::Dim2) => UniformType::Sampler2D,
+
+          (PixelType::NormIntegral, Dim::Dim3) => UniformType::Sampler3D,
+          (PixelType::NormUnsigned, Dim::Dim3) => UniformType::Sampler3D,
+          (PixelType::Integral, Dim::Dim3) => UniformType::ISampler3D,
+          (PixelType::Unsigned, Dim::Dim3) => UniformType::UISampler3D,
+          (PixelType::Floating, Dim::Dim3) => UniformType::Sampler3D,
+
+          (PixelType::NormIntegral, Dim::Cubemap) => UniformType::Cubemap,
+          (PixelType::NormUnsigned, Dim::Cubemap) => UniformType::Cubemap,
+          (PixelType::Integral, Dim::Cubemap) => UniformType::ICubemap,
+          (PixelType::Unsigned, Dim::Cubemap) => UniformType::UICubemap,
+          (PixelType::Floating, Dim::Cubemap) => UniformType::Cubemap,
+
+          (PixelType::NormIntegral, Dim::Dim1Array) => UniformType::Sampler1DArray,
+          (PixelType::NormUnsigned, Dim::Dim1Array) => UniformType::Sampler1DArray,
+          (PixelType::Integral, Dim::Dim1Array) => UniformType::ISampler1DArray,
+          (PixelType::Unsigned, Dim::Dim1Array) => UniformType::UISampler1DArray,
+          (PixelType::Floating, Dim::Dim1Array) => UniformType::Sampler1DArray,
+
+          (PixelType::NormIntegral, Dim::Dim2Array) => UniformType::Sampler2DArray,
+          (PixelType::NormUnsigned, Dim::Dim2Array) => UniformType::Sampler2DArray,
+          (PixelType::Integral, Dim::Dim2Array) => UniformType::ISampler2DArray,
+          (PixelType::Unsigned, Dim::Dim2Array) => UniformType::UISampler2DArray,
+          (PixelType::Floating, Dim::Dim2Array) => UniformType::Sampler2DArray,
+        }
+      }
 
-    gl::Uniform3uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
-  }
+      unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
+        gl::Uniform1i(uniform.index(), self.binding() as GLint)
+      }
+    }
+  };
 }
 
-unsafe impl<'a> Uniformable<GL33> for &'a [[bool; 4]] {
-  unsafe fn ty() -> UniformType {
-    UniformType::BVec4
-  }
-
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    let v: Vec<_> = self
-      .iter()
-      .map(|x| [x[0] as u32, x[1] as u32, x[2] as u32, x[3] as u32])
-      .collect();
+#[cfg(any(feature = "gles3", feature = "gl45"))]
+pub(crate) use impl_Uniformables;
 
-    gl::Uniform4uiv(uniform.index(), v.len() as GLsizei, v.as_ptr() as _);
-  }
-}
+impl_Uniformables!(GL33);
 
-unsafe impl<T> Uniformable<GL33> for BufferBinding<T> {
-  unsafe fn ty() -> UniformType {
-    UniformType::BufferBinding
-  }
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, f64, Double, Uniform1d);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, [f64; 2], DVec2, Uniform2dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, [f64; 3], DVec3, Uniform3dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, [f64; 4], DVec4, Uniform4dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, &[f64], Double, Uniform1dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, &[[f64; 2]], DVec2, Uniform2dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, &[[f64; 3]], DVec3, Uniform3dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, &[[f64; 4]], DVec4, Uniform4dv);
 
-  unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
-    gl::UniformBlockBinding(
-      program.handle,
-      uniform.index() as GLuint,
-      self.binding() as GLuint,
-    )
-  }
-}
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, mat [[f64; 2]; 2], DM22, UniformMatrix2dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, mat & [[[f64; 2]; 2]], DM22, UniformMatrix2dv);
 
-unsafe impl<D, S> Uniformable<GL33> for TextureBinding<D, S>
-where
-  D: Dimensionable,
-  S: SamplerType,
-{
-  unsafe fn ty() -> UniformType {
-    match (S::sample_type(), D::dim()) {
-      (PixelType::NormIntegral, Dim::Dim1) => UniformType::Sampler1D,
-      (PixelType::NormUnsigned, Dim::Dim1) => UniformType::Sampler1D,
-      (PixelType::Integral, Dim::Dim1) => UniformType::ISampler1D,
-      (PixelType::Unsigned, Dim::Dim1) => UniformType::UISampler1D,
-      (PixelType::Floating, Dim::Dim1) => UniformType::Sampler1D,
-
-      (PixelType::NormIntegral, Dim::Dim2) => UniformType::Sampler2D,
-      (PixelType::NormUnsigned, Dim::Dim2) => UniformType::Sampler2D,
-      (PixelType::Integral, Dim::Dim2) => UniformType::ISampler2D,
-      (PixelType::Unsigned, Dim::Dim2) => UniformType::UISampler2D,
-      (PixelType::Floating, Dim::Dim2) => UniformType::Sampler2D,
-
-      (PixelType::NormIntegral, Dim::Dim3) => UniformType::Sampler3D,
-      (PixelType::NormUnsigned, Dim::Dim3) => UniformType::Sampler3D,
-      (PixelType::Integral, Dim::Dim3) => UniformType::ISampler3D,
-      (PixelType::Unsigned, Dim::Dim3) => UniformType::UISampler3D,
-      (PixelType::Floating, Dim::Dim3) => UniformType::Sampler3D,
-
-      (PixelType::NormIntegral, Dim::Cubemap) => UniformType::Cubemap,
-      (PixelType::NormUnsigned, Dim::Cubemap) => UniformType::Cubemap,
-      (PixelType::Integral, Dim::Cubemap) => UniformType::ICubemap,
-      (PixelType::Unsigned, Dim::Cubemap) => UniformType::UICubemap,
-      (PixelType::Floating, Dim::Cubemap) => UniformType::Cubemap,
-
-      (PixelType::NormIntegral, Dim::Dim1Array) => UniformType::Sampler1DArray,
-      (PixelType::NormUnsigned, Dim::Dim1Array) => UniformType::Sampler1DArray,
-      (PixelType::Integral, Dim::Dim1Array) => UniformType::ISampler1DArray,
-      (PixelType::Unsigned, Dim::Dim1Array) => UniformType::UISampler1DArray,
-      (PixelType::Floating, Dim::Dim1Array) => UniformType::Sampler1DArray,
-
-      (PixelType::NormIntegral, Dim::Dim2Array) => UniformType::Sampler2DArray,
-      (PixelType::NormUnsigned, Dim::Dim2Array) => UniformType::Sampler2DArray,
-      (PixelType::Integral, Dim::Dim2Array) => UniformType::ISampler2DArray,
-      (PixelType::Unsigned, Dim::Dim2Array) => UniformType::UISampler2DArray,
-      (PixelType::Floating, Dim::Dim2Array) => UniformType::Sampler2DArray,
-    }
-  }
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, mat [[f64; 3]; 3], DM33, UniformMatrix3dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, mat & [[[f64; 3]; 3]], DM33, UniformMatrix3dv);
 
-  unsafe fn update(self, _: &mut Program, uniform: &Uniform<Self>) {
-    gl::Uniform1i(uniform.index(), self.binding() as GLint)
-  }
-}
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, mat [[f64; 4]; 4], DM44, UniformMatrix4dv);
+#[cfg(feature = "GL_ARB_gpu_shader_fp64")]
+impl_Uniformable!(GL33, mat & [[[f64; 4]; 4]], DM44, UniformMatrix4dv);
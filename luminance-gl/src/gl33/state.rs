@@ -3,15 +3,20 @@
 use gl::types::*;
 use std::cell::RefCell;
 use std::error;
+use std::ffi::CStr;
 use std::fmt;
 use std::marker::PhantomData;
 
 use crate::gl33::depth_test::depth_comparison_to_glenum;
+use crate::gl33::stencil::{stencil_comparison_to_glenum, stencil_op_to_glenum};
+use crate::gl33::texture::MAX_TEXTURE_MAX_ANISOTROPY;
 use crate::gl33::vertex_restart::VertexRestart;
 use luminance::blending::{Equation, Factor};
 use luminance::depth_test::{DepthComparison, DepthWrite};
 use luminance::face_culling::{FaceCullingMode, FaceCullingOrder};
+use luminance::pixel::CompressionFamily;
 use luminance::scissor::ScissorRegion;
+use luminance::stencil::{StencilComparison, StencilOperations, StencilTest};
 
 // TLS synchronization barrier for `GLState`.
 //
@@ -24,6 +29,10 @@ pub(crate) struct BindingStack {
   pub(crate) free_texture_units: Vec<u32>,
   pub(crate) next_buffer_binding: u32,
   pub(crate) free_buffer_bindings: Vec<u32>,
+  #[cfg(feature = "gl45")]
+  pub(crate) next_storage_buffer_binding: u32,
+  #[cfg(feature = "gl45")]
+  pub(crate) free_storage_buffer_bindings: Vec<u32>,
 }
 
 impl BindingStack {
@@ -34,6 +43,10 @@ impl BindingStack {
       free_texture_units: Vec::new(),
       next_buffer_binding: 0,
       free_buffer_bindings: Vec::new(),
+      #[cfg(feature = "gl45")]
+      next_storage_buffer_binding: 0,
+      #[cfg(feature = "gl45")]
+      free_storage_buffer_bindings: Vec::new(),
     }
   }
 }
@@ -89,6 +102,15 @@ where
   }
 }
 
+/// OpenGL API driven by a [`GLState`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum GLApi {
+  /// Desktop OpenGL.
+  GL,
+  /// OpenGL ES.
+  ES,
+}
+
 /// The graphics state.
 ///
 /// This type represents the current state of a given graphics context. It acts
@@ -99,6 +121,9 @@ where
 pub struct GLState {
   _a: PhantomData<*const ()>, // !Send and !Sync
 
+  // API the state drives
+  api: GLApi,
+
   // binding stack
   binding_stack: BindingStack,
 
@@ -107,6 +132,7 @@ pub struct GLState {
 
   // clear buffers
   clear_color: Cached<[GLfloat; 4]>,
+  clear_stencil: Cached<u8>,
 
   // blending
   blending_state: Cached<BlendingState>,
@@ -120,6 +146,13 @@ pub struct GLState {
   // depth write
   depth_write: Cached<DepthWrite>,
 
+  // stencil test
+  stencil_test_state: Cached<StencilTestState>,
+  stencil_test: Cached<StencilTest>,
+  front_stencil_operations: Cached<StencilOperations>,
+  back_stencil_operations: Cached<StencilOperations>,
+  stencil_write_mask: Cached<u8>,
+
   // face culling
   face_culling_state: Cached<FaceCullingState>,
   face_culling_order: Cached<FaceCullingOrder>,
@@ -149,6 +182,13 @@ pub struct GLState {
 
   // uniform buffer
   bound_uniform_buffers: Vec<GLuint>,
+  uniform_buffer_offset_alignment: usize,
+
+  // shader storage buffer
+  bound_storage_buffers: Vec<GLuint>,
+
+  // slots of the active queries
+  active_queries: Vec<GLenum>,
 
   // array buffer
   bound_array_buffer: GLuint,
@@ -167,6 +207,16 @@ pub struct GLState {
 
   // framebuffer sRGB
   srgb_framebuffer_enabled: Cached<bool>,
+
+  // supported compressed pixel format families
+  compression_families: Vec<CompressionFamily>,
+
+  // maximum degree of anisotropy, if anisotropic filtering is supported
+  max_anisotropy: Option<f32>,
+
+  // seamless cubemaps, either per texture or for all of them
+  seamless_cubemap_per_texture: bool,
+  seamless_cubemaps_enabled: Cached<bool>,
 }
 
 impl GLState {
@@ -175,14 +225,14 @@ impl GLState {
   /// > Note: keep in mind you can create only one per thread. However, if you’re building without
   /// > standard library, this function will always return successfully. You have to take extra care
   /// > in this case.
-  pub(crate) fn new() -> Result<Self, StateQueryError> {
+  pub(crate) fn new(api: GLApi) -> Result<Self, StateQueryError> {
     TLS_ACQUIRE_GFX_STATE.with(|rc| {
       let mut inner = rc.borrow_mut();
 
       match *inner {
         Some(_) => {
           inner.take();
-          Self::get_from_context()
+          Self::get_from_context(api)
         }
 
         None => Err(StateQueryError::UnavailableGLState),
@@ -191,46 +241,69 @@ impl GLState {
   }
 
   /// Get a `GraphicsContext` from the current OpenGL context.
-  fn get_from_context() -> Result<Self, StateQueryError> {
+  fn get_from_context(api: GLApi) -> Result<Self, StateQueryError> {
     unsafe {
       let binding_stack = BindingStack::new();
       let viewport = Cached::new(get_ctx_viewport()?);
       let clear_color = Cached::new(get_ctx_clear_color()?);
+      let clear_stencil = Cached::new(get_ctx_clear_stencil()?);
       let blending_state = Cached::new(get_ctx_blending_state()?);
       let blending_equations = Cached::new(get_ctx_blending_equations()?);
       let blending_funcs = Cached::new(get_ctx_blending_factors()?);
       let depth_test = Cached::new(get_ctx_depth_test()?);
       let depth_test_comparison = Cached::new(DepthComparison::Less);
       let depth_write = Cached::new(get_ctx_depth_write()?);
+      let stencil_test_state = Cached::new(get_ctx_stencil_test_state()?);
+      let stencil_test = Cached::new(StencilTest::new(StencilComparison::Always, 0, 0xFF));
+      let front_stencil_operations = Cached::new(StencilOperations::default());
+      let back_stencil_operations = Cached::new(StencilOperations::default());
+      let stencil_write_mask = Cached::new(get_ctx_stencil_write_mask()?);
       let face_culling_state = Cached::new(get_ctx_face_culling_state()?);
       let face_culling_order = Cached::new(get_ctx_face_culling_order()?);
       let face_culling_mode = Cached::new(get_ctx_face_culling_mode()?);
-      let vertex_restart = Cached::new(get_ctx_vertex_restart()?);
+      let vertex_restart = Cached::new(get_ctx_vertex_restart(api)?);
       let patch_vertex_nb = Cached::new(0);
       let current_texture_unit = Cached::new(get_ctx_current_texture_unit()?);
       let bound_textures = vec![(gl::TEXTURE_2D, 0); 48]; // 48 is the platform minimal requirement
       let texture_swimming_pool = Vec::new();
       let bound_uniform_buffers = vec![0; 36]; // 36 is the platform minimal requirement
+      let uniform_buffer_offset_alignment = get_ctx_uniform_buffer_offset_alignment()?;
+      let bound_storage_buffers = Vec::new(); // not supported on every platform
+      let active_queries = Vec::new();
       let bound_array_buffer = 0;
       let bound_element_array_buffer = 0;
       let bound_draw_framebuffer = Cached::new(get_ctx_bound_draw_framebuffer()?);
       let bound_vertex_array = get_ctx_bound_vertex_array()?;
       let current_program = get_ctx_current_program()?;
-      let srgb_framebuffer_enabled = Cached::new(get_ctx_srgb_framebuffer_enabled()?);
+      let srgb_framebuffer_enabled = Cached::new(get_ctx_srgb_framebuffer_enabled(api)?);
       let scissor_state = Cached::new(get_ctx_scissor_state()?);
       let scissor_region = Cached::new(get_ctx_scissor_region()?);
+      let extensions = get_ctx_extensions();
+      let has_extension = |name: &str| extensions.iter().any(|ext| ext == name);
+      let compression_families = get_ctx_compression_families(api, has_extension);
+      let max_anisotropy = get_ctx_max_anisotropy(has_extension);
+      let seamless_cubemap_per_texture =
+        api == GLApi::GL && has_extension("GL_ARB_seamless_cubemap_per_texture");
+      let seamless_cubemaps_enabled = Cached::new(get_ctx_seamless_cubemaps_enabled(api));
 
       Ok(GLState {
         _a: PhantomData,
+        api,
         binding_stack,
         viewport,
         clear_color,
+        clear_stencil,
         blending_state,
         blending_equations,
         blending_funcs,
         depth_test,
         depth_test_comparison,
         depth_write,
+        stencil_test_state,
+        stencil_test,
+        front_stencil_operations,
+        back_stencil_operations,
+        stencil_write_mask,
         face_culling_state,
         face_culling_order,
         face_culling_mode,
@@ -240,6 +313,9 @@ impl GLState {
         bound_textures,
         texture_swimming_pool,
         bound_uniform_buffers,
+        uniform_buffer_offset_alignment,
+        bound_storage_buffers,
+        active_queries,
         bound_array_buffer,
         bound_element_array_buffer,
         bound_draw_framebuffer,
@@ -248,6 +324,10 @@ impl GLState {
         srgb_framebuffer_enabled,
         scissor_state,
         scissor_region,
+        compression_families,
+        max_anisotropy,
+        seamless_cubemap_per_texture,
+        seamless_cubemaps_enabled,
       })
     }
   }
@@ -296,6 +376,13 @@ impl GLState {
     }
   }
 
+  /// Invalidate the shader storage buffer bindings.
+  pub fn invalidate_bound_storage_buffers(&mut self) {
+    for b in &mut self.bound_storage_buffers {
+      *b = 0;
+    }
+  }
+
   /// Invalidate the currently in-use viewport.
   pub fn invalidate_viewport(&mut self) {
     self.viewport.invalidate()
@@ -306,6 +393,11 @@ impl GLState {
     self.clear_color.invalidate()
   }
 
+  /// Invalidate the currently in-use clear stencil value.
+  pub fn invalidate_clear_stencil(&mut self) {
+    self.clear_stencil.invalidate()
+  }
+
   /// Invalidate the currently in-use blending state.
   pub fn invalidate_blending_state(&mut self) {
     self.blending_state.invalidate()
@@ -336,6 +428,27 @@ impl GLState {
     self.depth_write.invalidate()
   }
 
+  /// Invalidate the currently in-use stencil test state.
+  pub fn invalidate_stencil_test_state(&mut self) {
+    self.stencil_test_state.invalidate()
+  }
+
+  /// Invalidate the currently in-use stencil test.
+  pub fn invalidate_stencil_test(&mut self) {
+    self.stencil_test.invalidate()
+  }
+
+  /// Invalidate the currently in-use stencil operations.
+  pub fn invalidate_stencil_operations(&mut self) {
+    self.front_stencil_operations.invalidate();
+    self.back_stencil_operations.invalidate();
+  }
+
+  /// Invalidate the currently in-use stencil write mask.
+  pub fn invalidate_stencil_write_mask(&mut self) {
+    self.stencil_write_mask.invalidate()
+  }
+
   /// Invalidate the currently in-use face culling state.
   pub fn invalidate_face_culling_state(&mut self) {
     self.face_culling_state.invalidate()
@@ -366,6 +479,11 @@ impl GLState {
     self.srgb_framebuffer_enabled.invalidate()
   }
 
+  /// Invalidate the currently in-use seamless cubemap state.
+  pub fn invalidate_seamless_cubemaps_enabled(&mut self) {
+    self.seamless_cubemaps_enabled.invalidate()
+  }
+
   pub(crate) fn binding_stack_mut(&mut self) -> &mut BindingStack {
     &mut self.binding_stack
   }
@@ -413,6 +531,13 @@ impl GLState {
     }
   }
 
+  pub(crate) unsafe fn set_clear_stencil(&mut self, clear_stencil: u8) {
+    if self.clear_stencil.is_invalid(&clear_stencil) {
+      gl::ClearStencil(clear_stencil as GLint);
+      self.clear_stencil.set(clear_stencil);
+    }
+  }
+
   pub(crate) unsafe fn set_blending_state(&mut self, state: BlendingState) {
     if self.blending_state.is_invalid(&state) {
       match state {
@@ -559,6 +684,58 @@ impl GLState {
     }
   }
 
+  pub(crate) unsafe fn set_stencil_test_state(&mut self, state: StencilTestState) {
+    if self.stencil_test_state.is_invalid(&state) {
+      match state {
+        StencilTestState::On => gl::Enable(gl::STENCIL_TEST),
+        StencilTestState::Off => gl::Disable(gl::STENCIL_TEST),
+      }
+
+      self.stencil_test_state.set(state);
+    }
+  }
+
+  pub(crate) unsafe fn set_stencil_test(&mut self, stencil_test: StencilTest) {
+    if self.stencil_test.is_invalid(&stencil_test) {
+      gl::StencilFunc(
+        stencil_comparison_to_glenum(stencil_test.comparison),
+        stencil_test.reference as GLint,
+        stencil_test.mask as GLuint,
+      );
+
+      self.stencil_test.set(stencil_test);
+    }
+  }
+
+  pub(crate) unsafe fn set_stencil_operations(
+    &mut self,
+    front: StencilOperations,
+    back: StencilOperations,
+  ) {
+    for (face, ops, cached) in [
+      (gl::FRONT, front, &mut self.front_stencil_operations),
+      (gl::BACK, back, &mut self.back_stencil_operations),
+    ] {
+      if cached.is_invalid(&ops) {
+        gl::StencilOpSeparate(
+          face,
+          stencil_op_to_glenum(ops.stencil_fails),
+          stencil_op_to_glenum(ops.depth_fails),
+          stencil_op_to_glenum(ops.depth_passes),
+        );
+
+        cached.set(ops);
+      }
+    }
+  }
+
+  pub(crate) unsafe fn set_stencil_write_mask(&mut self, mask: u8) {
+    if self.stencil_write_mask.is_invalid(&mask) {
+      gl::StencilMask(mask as GLuint);
+      self.stencil_write_mask.set(mask);
+    }
+  }
+
   pub(crate) unsafe fn set_face_culling_state(&mut self, state: FaceCullingState) {
     if self.face_culling_state.is_invalid(&state) {
       match state {
@@ -595,15 +772,26 @@ impl GLState {
 
   pub(crate) unsafe fn set_vertex_restart(&mut self, state: VertexRestart) {
     if self.vertex_restart.is_invalid(&state) {
+      let cap = vertex_restart_cap(self.api);
+
       match state {
-        VertexRestart::On => gl::Enable(gl::PRIMITIVE_RESTART),
-        VertexRestart::Off => gl::Disable(gl::PRIMITIVE_RESTART),
+        VertexRestart::On => gl::Enable(cap),
+        VertexRestart::Off => gl::Disable(cap),
       }
 
       self.vertex_restart.set(state);
     }
   }
 
+  /// Set the index used to restart primitives.
+  ///
+  /// OpenGL ES always uses the maximum value of the index type, so this is a no-op there.
+  pub(crate) unsafe fn set_vertex_restart_index(&mut self, index: u32) {
+    if self.api == GLApi::GL {
+      gl::PrimitiveRestartIndex(index);
+    }
+  }
+
   pub(crate) unsafe fn set_patch_vertex_nb(&mut self, nb: usize) {
     if self.patch_vertex_nb.is_invalid(&nb) {
       gl::PatchParameteri(gl::PATCH_VERTICES, nb as GLint);
@@ -674,6 +862,109 @@ impl GLState {
     }
   }
 
+  /// Bind `size` bytes of a uniform buffer, starting at `offset` bytes.
+  ///
+  /// Ranges are not cached: the binding is always updated, and invalidated in the cache.
+  pub(crate) unsafe fn bind_buffer_range(
+    &mut self,
+    handle: GLuint,
+    binding: u32,
+    offset: usize,
+    size: usize,
+  ) {
+    let binding_ = binding as usize;
+
+    gl::BindBufferRange(
+      gl::UNIFORM_BUFFER,
+      binding as GLuint,
+      handle,
+      offset as GLintptr,
+      size as GLsizeiptr,
+    );
+
+    if binding_ >= self.bound_uniform_buffers.len() {
+      // not enough registered buffer bindings; let’s grow a bit more
+      self.bound_uniform_buffers.resize(binding_ + 1, 0);
+    }
+
+    // a whole-buffer binding must be re-issued after a range binding
+    self.bound_uniform_buffers[binding_] = 0;
+  }
+
+  /// Whether the compressed pixel formats of `family` are supported.
+  pub(crate) fn supports_compression(&self, family: CompressionFamily) -> bool {
+    self.compression_families.contains(&family)
+  }
+
+  /// Maximum degree of anisotropy, if anisotropic filtering is supported.
+  pub(crate) fn max_anisotropy(&self) -> Option<f32> {
+    self.max_anisotropy
+  }
+
+  /// Filter a cubemap across its faces, setting its texture parameters with `set_param`.
+  ///
+  /// Without `ARB_seamless_cubemap_per_texture`, seamless filtering is enabled for every cubemap.
+  pub(crate) unsafe fn enable_seamless_cubemap(&mut self, set_param: impl FnOnce(GLenum, GLint)) {
+    if self.seamless_cubemap_per_texture {
+      set_param(gl::TEXTURE_CUBE_MAP_SEAMLESS, gl::TRUE as GLint);
+    } else if self.seamless_cubemaps_enabled.is_invalid(&true) {
+      gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+      self.seamless_cubemaps_enabled.set(true);
+    }
+  }
+
+  /// Alignment, in bytes, of the offsets of uniform buffer ranges.
+  pub(crate) fn uniform_buffer_offset_alignment(&self) -> usize {
+    self.uniform_buffer_offset_alignment
+  }
+
+  #[cfg(feature = "gl45")]
+  pub(crate) unsafe fn bind_storage_buffer_base(&mut self, handle: GLuint, binding: u32) {
+    let binding_ = binding as usize;
+
+    match self.bound_storage_buffers.get(binding_) {
+      Some(&handle_) if handle != handle_ => {
+        gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding as GLuint, handle);
+        self.bound_storage_buffers[binding_] = handle;
+      }
+
+      None => {
+        gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding as GLuint, handle);
+
+        // not enough registered buffer bindings; let’s grow a bit more
+        self.bound_storage_buffers.resize(binding_ + 1, 0);
+        self.bound_storage_buffers[binding_] = handle;
+      }
+
+      _ => (), // cached
+    }
+  }
+
+  /// Begin the query `handle` on `target`.
+  ///
+  /// Return `false` if a query is already active on `target` — or on any occlusion query target if
+  /// `target` is one — in which case nothing happens.
+  pub(crate) unsafe fn begin_query(&mut self, target: GLenum, handle: GLuint) -> bool {
+    let slot = query_slot(target);
+
+    if self.active_queries.contains(&slot) {
+      return false;
+    }
+
+    gl::BeginQuery(target, handle);
+    self.active_queries.push(slot);
+
+    true
+  }
+
+  /// End the query active on `target`.
+  pub(crate) unsafe fn end_query(&mut self, target: GLenum) {
+    gl::EndQuery(target);
+
+    let slot = query_slot(target);
+    self.active_queries.retain(|&s| s != slot);
+  }
+
   pub(crate) unsafe fn bind_array_buffer(&mut self, handle: GLuint, bind: Bind) {
     if bind == Bind::Forced || self.bound_array_buffer != handle {
       gl::BindBuffer(gl::ARRAY_BUFFER, handle);
@@ -699,6 +990,12 @@ impl GLState {
       .find(|h| **h == handle)
     {
       *handle_ = 0;
+    } else if let Some(handle_) = self
+      .bound_storage_buffers
+      .iter_mut()
+      .find(|h| **h == handle)
+    {
+      *handle_ = 0;
     }
   }
 
@@ -728,6 +1025,11 @@ impl GLState {
   }
 
   pub(crate) unsafe fn enable_srgb_framebuffer(&mut self, srgb_framebuffer_enabled: bool) {
+    // OpenGL ES has no sRGB switch: writes to sRGB attachments are always encoded
+    if self.api == GLApi::ES {
+      return;
+    }
+
     if self
       .srgb_framebuffer_enabled
       .is_invalid(&srgb_framebuffer_enabled)
@@ -778,6 +1080,15 @@ fn from_blending_factor(factor: Factor) -> GLenum {
   }
 }
 
+// Slot a query target uses; occlusion queries share a single slot.
+#[inline]
+fn query_slot(target: GLenum) -> GLenum {
+  match target {
+    gl::ANY_SAMPLES_PASSED => gl::SAMPLES_PASSED,
+    _ => target,
+  }
+}
+
 /// An error that might happen when the context is queried.
 #[non_exhaustive]
 #[derive(Debug)]
@@ -799,6 +1110,8 @@ pub enum StateQueryError {
   UnknownDepthTestState(GLboolean),
   /// Corrupted depth write state.
   UnknownDepthWriteState(GLboolean),
+  /// Corrupted stencil test state.
+  UnknownStencilTestState(GLboolean),
   /// Corrupted face culling state.
   UnknownFaceCullingState(GLboolean),
   /// Corrupted face culling order.
@@ -831,6 +1144,9 @@ impl fmt::Display for StateQueryError {
       StateQueryError::UnknownDepthWriteState(ref s) => {
         write!(f, "unknown depth write state: {}", s)
       }
+      StateQueryError::UnknownStencilTestState(ref s) => {
+        write!(f, "unknown stencil test state: {}", s)
+      }
       StateQueryError::UnknownFaceCullingState(ref s) => {
         write!(f, "unknown face culling state: {}", s)
       }
@@ -865,6 +1181,12 @@ unsafe fn get_ctx_clear_color() -> Result<[GLfloat; 4], StateQueryError> {
   Ok(data)
 }
 
+unsafe fn get_ctx_clear_stencil() -> Result<u8, StateQueryError> {
+  let mut data = 0;
+  gl::GetIntegerv(gl::STENCIL_CLEAR_VALUE, &mut data);
+  Ok(data as u8)
+}
+
 unsafe fn get_ctx_blending_state() -> Result<BlendingState, StateQueryError> {
   let state = gl::IsEnabled(gl::BLEND);
 
@@ -990,6 +1312,22 @@ unsafe fn get_ctx_depth_write() -> Result<DepthWrite, StateQueryError> {
   }
 }
 
+unsafe fn get_ctx_stencil_test_state() -> Result<StencilTestState, StateQueryError> {
+  let state = gl::IsEnabled(gl::STENCIL_TEST);
+
+  match state {
+    gl::TRUE => Ok(StencilTestState::On),
+    gl::FALSE => Ok(StencilTestState::Off),
+    _ => Err(StateQueryError::UnknownStencilTestState(state)),
+  }
+}
+
+unsafe fn get_ctx_stencil_write_mask() -> Result<u8, StateQueryError> {
+  let mut mask = 0xFF;
+  gl::GetIntegerv(gl::STENCIL_WRITEMASK, &mut mask);
+  Ok(mask as u8)
+}
+
 unsafe fn get_ctx_face_culling_state() -> Result<FaceCullingState, StateQueryError> {
   let state = gl::IsEnabled(gl::CULL_FACE);
 
@@ -1025,8 +1363,8 @@ unsafe fn get_ctx_face_culling_mode() -> Result<FaceCullingMode, StateQueryError
   }
 }
 
-unsafe fn get_ctx_vertex_restart() -> Result<VertexRestart, StateQueryError> {
-  let state = gl::IsEnabled(gl::PRIMITIVE_RESTART);
+unsafe fn get_ctx_vertex_restart(api: GLApi) -> Result<VertexRestart, StateQueryError> {
+  let state = gl::IsEnabled(vertex_restart_cap(api));
 
   match state {
     gl::TRUE => Ok(VertexRestart::On),
@@ -1053,13 +1391,97 @@ unsafe fn get_ctx_bound_vertex_array() -> Result<GLuint, StateQueryError> {
   Ok(bound as GLuint)
 }
 
+unsafe fn get_ctx_uniform_buffer_offset_alignment() -> Result<usize, StateQueryError> {
+  let mut alignment = 0 as GLint;
+  gl::GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut alignment);
+  Ok(alignment.max(1) as usize)
+}
+
 unsafe fn get_ctx_current_program() -> Result<GLuint, StateQueryError> {
   let mut used = 0 as GLint;
   gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut used);
   Ok(used as GLuint)
 }
 
-unsafe fn get_ctx_srgb_framebuffer_enabled() -> Result<bool, StateQueryError> {
+unsafe fn get_ctx_extensions() -> Vec<String> {
+  let mut count = 0 as GLint;
+  gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+
+  (0..count.max(0) as GLuint)
+    .filter_map(|i| {
+      let name = gl::GetStringi(gl::EXTENSIONS, i);
+
+      if name.is_null() {
+        None
+      } else {
+        Some(
+          CStr::from_ptr(name as *const _)
+            .to_string_lossy()
+            .into_owned(),
+        )
+      }
+    })
+    .collect()
+}
+
+unsafe fn get_ctx_compression_families(
+  api: GLApi,
+  has: impl Fn(&str) -> bool,
+) -> Vec<CompressionFamily> {
+  let mut families = Vec::new();
+
+  if has("GL_EXT_texture_compression_s3tc") {
+    families.push(CompressionFamily::S3TC);
+  }
+
+  // RGTC is core since OpenGL 3.0
+  if api == GLApi::GL || has("GL_EXT_texture_compression_rgtc") {
+    families.push(CompressionFamily::RGTC);
+  }
+
+  if has("GL_ARB_texture_compression_bptc") || has("GL_EXT_texture_compression_bptc") {
+    families.push(CompressionFamily::BPTC);
+  }
+
+  // ETC2 is core since OpenGL ES 3.0
+  if api == GLApi::ES || has("GL_ARB_ES3_compatibility") {
+    families.push(CompressionFamily::ETC2);
+  }
+
+  if has("GL_KHR_texture_compression_astc_ldr") {
+    families.push(CompressionFamily::ASTC);
+  }
+
+  families
+}
+
+unsafe fn get_ctx_max_anisotropy(has: impl Fn(&str) -> bool) -> Option<f32> {
+  if has("GL_EXT_texture_filter_anisotropic") || has("GL_ARB_texture_filter_anisotropic") {
+    let mut max_anisotropy = 1.;
+    gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, &mut max_anisotropy);
+    Some(max_anisotropy)
+  } else {
+    None
+  }
+}
+
+unsafe fn get_ctx_seamless_cubemaps_enabled(api: GLApi) -> bool {
+  // cubemaps are always seamless with OpenGL ES
+  api == GLApi::ES || gl::IsEnabled(gl::TEXTURE_CUBE_MAP_SEAMLESS) == gl::TRUE
+}
+
+fn vertex_restart_cap(api: GLApi) -> GLenum {
+  match api {
+    GLApi::GL => gl::PRIMITIVE_RESTART,
+    GLApi::ES => gl::PRIMITIVE_RESTART_FIXED_INDEX,
+  }
+}
+
+unsafe fn get_ctx_srgb_framebuffer_enabled(api: GLApi) -> Result<bool, StateQueryError> {
+  if api == GLApi::ES {
+    return Ok(false);
+  }
+
   let state = gl::IsEnabled(gl::FRAMEBUFFER_SRGB);
 
   match state {
@@ -1101,6 +1523,15 @@ pub(crate) enum DepthTest {
   Off,
 }
 
+/// Whether or not stencil test should be enabled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum StencilTestState {
+  /// The stencil test is enabled.
+  On,
+  /// The stencil test is disabled.
+  Off,
+}
+
 /// Should face culling be enabled?
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub(crate) enum FaceCullingState {
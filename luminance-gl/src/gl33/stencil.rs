@@ -0,0 +1,29 @@
+use gl::types::*;
+
+use luminance::stencil::{StencilComparison, StencilOp};
+
+pub(crate) fn stencil_comparison_to_glenum(sc: StencilComparison) -> GLenum {
+  match sc {
+    StencilComparison::Never => gl::NEVER,
+    StencilComparison::Always => gl::ALWAYS,
+    StencilComparison::Equal => gl::EQUAL,
+    StencilComparison::NotEqual => gl::NOTEQUAL,
+    StencilComparison::Less => gl::LESS,
+    StencilComparison::LessOrEqual => gl::LEQUAL,
+    StencilComparison::Greater => gl::GREATER,
+    StencilComparison::GreaterOrEqual => gl::GEQUAL,
+  }
+}
+
+pub(crate) fn stencil_op_to_glenum(op: StencilOp) -> GLenum {
+  match op {
+    StencilOp::Keep => gl::KEEP,
+    StencilOp::Zero => gl::ZERO,
+    StencilOp::Replace => gl::REPLACE,
+    StencilOp::Increment => gl::INCR,
+    StencilOp::IncrementWrap => gl::INCR_WRAP,
+    StencilOp::Decrement => gl::DECR,
+    StencilOp::DecrementWrap => gl::DECR_WRAP,
+    StencilOp::Invert => gl::INVERT,
+  }
+}
@@ -2,6 +2,7 @@ use gl;
 use gl::types::*;
 use std::cell::RefCell;
 use std::marker::PhantomData;
+use std::mem;
 use std::os::raw::c_void;
 use std::ptr;
 use std::rc::Rc;
@@ -15,9 +16,10 @@ use luminance::backend::tess::{
   IndexSlice as IndexSliceBackend, InstanceSlice as InstanceSliceBackend, Tess as TessBackend,
   VertexSlice as VertexSliceBackend,
 };
+use luminance::buffer::{BufferUsage, PreserveContent};
 use luminance::tess::{
   Deinterleaved, DeinterleavedData, Interleaved, Mode, TessError, TessIndex, TessIndexType,
-  TessMapError, TessVertexData,
+  TessMapError, TessParams, TessVertexData,
 };
 use luminance::vertex::{
   Deinterleave, Normalized, Vertex, VertexAttribDesc, VertexAttribDim, VertexAttribType,
@@ -45,6 +47,7 @@ where
   inst_nb: usize,
   patch_vert_nb: usize,
   index_state: Option<IndexedDrawState<I>>,
+  usage: BufferUsage,
   state: Rc<RefCell<GLState>>,
 }
 
@@ -52,6 +55,47 @@ impl<I> TessRaw<I>
 where
   I: TessIndex,
 {
+  /// Bind the vertex array, so that newly created buffers get attached to it.
+  fn bind_vertex_array(&self) {
+    unsafe {
+      self
+        .state
+        .borrow_mut()
+        .bind_vertex_array(self.vao, Bind::Cached)
+    };
+  }
+
+  /// Update the number of vertices to render after the vertex storage got resized.
+  ///
+  /// Indexed tessellations render as many vertices as there are indices, so they are not impacted.
+  fn vertices_resized(&mut self, len: usize) {
+    if self.index_state.is_none() {
+      self.vert_nb = len;
+    }
+  }
+
+  unsafe fn resize_indices(
+    &mut self,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    match self.index_state {
+      Some(ref mut index_state) => {
+        GL33::resize(&mut index_state.buffer, len, mem::zeroed(), preserve)?
+      }
+
+      None => {
+        self.bind_vertex_array();
+        self.index_state =
+          build_index_buffer(&self.state, vec![mem::zeroed(); len], None, self.usage)?;
+      }
+    }
+
+    self.vert_nb = len;
+
+    Ok(())
+  }
+
   unsafe fn render(
     &self,
     start_index: usize,
@@ -75,7 +119,7 @@ where
 
         if let Some(restart_index) = index_state.restart_index {
           gfx_st.set_vertex_restart(VertexRestart::On);
-          gl::PrimitiveRestartIndex(restart_index.try_into_u32().unwrap_or(0));
+          gfx_st.set_vertex_restart_index(restart_index.try_into_u32().unwrap_or(0));
         } else {
           gfx_st.set_vertex_restart(VertexRestart::Off);
         }
@@ -146,11 +190,16 @@ where
     vertex_data: Option<V::Data>,
     index_data: Vec<I>,
     instance_data: Option<W::Data>,
-    mode: Mode,
-    vert_nb: usize,
-    inst_nb: usize,
-    restart_index: Option<I>,
+    params: TessParams<I>,
   ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      restart_index,
+      usage,
+    } = params;
+
     let mut vao: GLuint = 0;
 
     let patch_vert_nb = match mode {
@@ -164,12 +213,12 @@ where
     // handle) don’t prevent us from binding here
     self.state.borrow_mut().bind_vertex_array(vao, Bind::Forced);
 
-    let vertex_buffer = build_interleaved_vertex_buffer(self, vertex_data)?;
+    let vertex_buffer = build_interleaved_vertex_buffer(&self.state, vertex_data, usage)?;
 
     // in case of indexed render, create an index buffer
-    let index_state = build_index_buffer(self, index_data, restart_index)?;
+    let index_state = build_index_buffer(&self.state, index_data, restart_index, usage)?;
 
-    let instance_buffer = build_interleaved_vertex_buffer(self, instance_data)?;
+    let instance_buffer = build_interleaved_vertex_buffer(&self.state, instance_data, usage)?;
 
     let mode = opengl_mode(mode);
     let state = self.state.clone();
@@ -180,6 +229,7 @@ where
       inst_nb,
       patch_vert_nb,
       index_state,
+      usage,
       state,
     };
 
@@ -207,6 +257,36 @@ where
   ) -> Result<(), TessError> {
     tess.raw.render(start_index, vert_nb, inst_nb)
   }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved_vertex_buffer(&tess.raw, &mut tess.vertex_buffer, len, preserve)?;
+    tess.raw.vertices_resized(len);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved_vertex_buffer(&tess.raw, &mut tess.instance_buffer, len, preserve)?;
+    tess.raw.inst_nb = len;
+
+    Ok(())
+  }
 }
 
 unsafe impl<V, I, W> VertexSliceBackend<V, I, W, Interleaved, V> for GL33
@@ -313,11 +393,16 @@ where
     vertex_data: Option<V::Data>,
     index_data: Vec<I>,
     instance_data: Option<W::Data>,
-    mode: Mode,
-    vert_nb: usize,
-    inst_nb: usize,
-    restart_index: Option<I>,
+    params: TessParams<I>,
   ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      restart_index,
+      usage,
+    } = params;
+
     let mut vao: GLuint = 0;
 
     let patch_vert_nb = match mode {
@@ -331,12 +416,13 @@ where
     // handle) don’t prevent us from binding here
     self.state.borrow_mut().bind_vertex_array(vao, Bind::Forced);
 
-    let vertex_buffers = build_deinterleaved_vertex_buffers::<V>(self, vertex_data)?;
+    let vertex_buffers = build_deinterleaved_vertex_buffers::<V>(&self.state, vertex_data, usage)?;
 
     // in case of indexed render, create an index buffer
-    let index_state = build_index_buffer(self, index_data, restart_index)?;
+    let index_state = build_index_buffer(&self.state, index_data, restart_index, usage)?;
 
-    let instance_buffers = build_deinterleaved_vertex_buffers::<W>(self, instance_data)?;
+    let instance_buffers =
+      build_deinterleaved_vertex_buffers::<W>(&self.state, instance_data, usage)?;
 
     let mode = opengl_mode(mode);
     let state = self.state.clone();
@@ -347,6 +433,7 @@ where
       inst_nb,
       patch_vert_nb,
       index_state,
+      usage,
       state,
     };
 
@@ -375,6 +462,46 @@ where
   ) -> Result<(), TessError> {
     tess.raw.render(start_index, vert_nb, inst_nb)
   }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved_vertex_buffers::<V, I>(
+      &tess.raw,
+      &mut tess.vertex_buffers,
+      len,
+      preserve,
+    )?;
+    tess.raw.vertices_resized(len);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved_vertex_buffers::<W, I>(
+      &tess.raw,
+      &mut tess.instance_buffers,
+      len,
+      preserve,
+    )?;
+    tess.raw.inst_nb = len;
+
+    Ok(())
+  }
 }
 
 unsafe impl<V, I, W, T> VertexSliceBackend<V, I, W, Deinterleaved, T> for GL33
@@ -468,8 +595,9 @@ where
 }
 
 fn build_interleaved_vertex_buffer<V>(
-  gl33: &mut GL33,
+  state: &Rc<RefCell<GLState>>,
   vertices: Option<Vec<V>>,
+  usage: BufferUsage,
 ) -> Result<Option<Buffer<V>>, TessError>
 where
   V: Vertex,
@@ -481,12 +609,11 @@ where
       let vb = if vertices.is_empty() {
         None
       } else {
-        let vb = unsafe { gl33.from_vec(vertices)? };
+        let vb = Buffer::from_vec(state, vertices, usage);
 
         // force binding as it’s meaningful when a vao is bound
         unsafe {
-          gl33
-            .state
+          state
             .borrow_mut()
             .bind_array_buffer(vb.handle(), Bind::Forced)
         };
@@ -503,57 +630,126 @@ where
 }
 
 fn build_deinterleaved_vertex_buffers<V>(
-  gl33: &mut GL33,
+  state: &Rc<RefCell<GLState>>,
   vertices: Option<Vec<DeinterleavedData>>,
+  usage: BufferUsage,
 ) -> Result<Vec<Buffer<u8>>, TessError>
 where
   V: Vertex,
 {
   match vertices {
-    Some(attributes) => {
+    Some(attributes) => Ok(
       attributes
         .into_iter()
         .zip(V::vertex_desc())
-        .map(|(attribute, fmt)| {
-          let vb = unsafe { gl33.from_vec(attribute.into_vec())? };
-
-          // force binding as it’s meaningful when a vao is bound
-          unsafe {
-            gl33
-              .state
-              .borrow_mut()
-              .bind_array_buffer(vb.handle(), Bind::Forced);
-            set_vertex_pointers(&[fmt]);
-          }
-
-          Ok(vb)
-        })
-        .collect::<Result<Vec<_>, _>>()
-    }
+        .map(|(attribute, fmt)| build_attribute_buffer(state, attribute.into_vec(), fmt, usage))
+        .collect(),
+    ),
 
     None => Ok(Vec::new()),
   }
 }
 
+/// Create the buffer of a single deinterleaved attribute.
+fn build_attribute_buffer(
+  state: &Rc<RefCell<GLState>>,
+  bytes: Vec<u8>,
+  fmt: VertexBufferDesc,
+  usage: BufferUsage,
+) -> Buffer<u8> {
+  let vb = Buffer::from_vec(state, bytes, usage);
+
+  // force binding as it’s meaningful when a vao is bound
+  unsafe {
+    state
+      .borrow_mut()
+      .bind_array_buffer(vb.handle(), Bind::Forced);
+  }
+  set_vertex_pointers(&[fmt]);
+
+  vb
+}
+
+/// Resize the buffer of interleaved vertices or instances.
+///
+/// If the buffer doesn’t exist, it is created, unless the vertices are attributeless.
+unsafe fn resize_interleaved_vertex_buffer<V, I>(
+  raw: &TessRaw<I>,
+  buffer: &mut Option<Buffer<V>>,
+  len: usize,
+  preserve: PreserveContent,
+) -> Result<(), TessError>
+where
+  V: Vertex,
+  I: TessIndex,
+{
+  match buffer {
+    // vertices are made of plain numbers, for which zero bits are valid values
+    Some(ref mut vb) => GL33::resize(vb, len, mem::zeroed(), preserve)?,
+
+    None if !V::vertex_desc().is_empty() => {
+      raw.bind_vertex_array();
+      *buffer =
+        build_interleaved_vertex_buffer(&raw.state, Some(vec![mem::zeroed(); len]), raw.usage)?;
+    }
+
+    None => (),
+  }
+
+  Ok(())
+}
+
+/// Resize the buffers of deinterleaved vertices or instances.
+///
+/// If the buffers don’t exist, they are created, unless the vertices are attributeless.
+unsafe fn resize_deinterleaved_vertex_buffers<V, I>(
+  raw: &TessRaw<I>,
+  buffers: &mut Vec<Buffer<u8>>,
+  len: usize,
+  preserve: PreserveContent,
+) -> Result<(), TessError>
+where
+  V: Vertex,
+  I: TessIndex,
+{
+  if buffers.is_empty() {
+    raw.bind_vertex_array();
+
+    *buffers = V::vertex_desc()
+      .into_iter()
+      .map(|fmt| {
+        let bytes = vec![0; len * component_weight(&fmt.attrib_desc)];
+        build_attribute_buffer(&raw.state, bytes, fmt, raw.usage)
+      })
+      .collect();
+  } else {
+    for (vb, fmt) in buffers.iter_mut().zip(V::vertex_desc()) {
+      GL33::resize(vb, len * component_weight(&fmt.attrib_desc), 0, preserve)?;
+    }
+  }
+
+  Ok(())
+}
+
 /// Turn a [`Vec`] of indices to an [`IndexedDrawState`].
 fn build_index_buffer<I>(
-  gl33: &mut GL33,
+  state: &Rc<RefCell<GLState>>,
   data: Vec<I>,
   restart_index: Option<I>,
+  usage: BufferUsage,
 ) -> Result<Option<IndexedDrawState<I>>, TessError>
 where
   I: TessIndex,
 {
   let ids = if !data.is_empty() {
     let ib = IndexedDrawState {
-      buffer: unsafe { gl33.from_vec(data)? },
+      buffer: Buffer::from_vec(state, data, usage),
       restart_index,
     };
 
     // force binding as it’s meaningful when a vao is bound
     unsafe {
-      gl33
-        .state
+      state
         .borrow_mut()
         .bind_element_array_buffer(ib.buffer.handle(), Bind::Forced);
     }
@@ -1,7 +1,11 @@
 use gl;
 use gl::types::*;
-use luminance::backend::texture::{Texture as TextureBackend, TextureBase};
-use luminance::pixel::{Pixel, PixelFormat};
+use luminance::backend::texture::{
+  Texture as TextureBackend, TextureBase, TextureCompressed as TextureCompressedBackend,
+  TextureCompression, TextureReadback as TextureReadbackBackend,
+};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect};
+use luminance::pixel::{CompressedPixel, Compression, CompressionFamily, Pixel, PixelFormat};
 use luminance::texture::{
   Dim, Dimensionable, GenMipmaps, MagFilter, MinFilter, Sampler, TextureError, Wrap,
 };
@@ -12,15 +16,17 @@ use std::ptr;
 use std::rc::Rc;
 
 use crate::gl33::depth_test::depth_comparison_to_glenum;
-use crate::gl33::pixel::opengl_pixel_format;
+use crate::gl33::framebuffer::{blit, depth_attachment};
+use crate::gl33::pixel::{opengl_compressed_format, opengl_pixel_format};
+use crate::gl33::readback::Readback;
 use crate::gl33::state::GLState;
 use crate::gl33::GL33;
 
 pub struct Texture {
   pub(crate) handle: GLuint, // handle to the GPU texture object
   pub(crate) target: GLenum, // “type” of the texture; used for bindings
-  mipmaps: usize,
-  state: Rc<RefCell<GLState>>,
+  pub(crate) mipmaps: usize,
+  pub(crate) state: Rc<RefCell<GLState>>,
 }
 
 impl Drop for Texture {
@@ -51,10 +57,17 @@ where
 
     let mut state = self.state.borrow_mut();
 
+    let pf = P::pixel_format();
+    if let Some(compression) = pf.format.compression() {
+      if !state.supports_compression(compression.family()) {
+        return Err(TextureError::unsupported_pixel_format(pf));
+      }
+    }
+
     let handle = state.create_texture();
     state.bind_texture(target, handle);
 
-    create_texture::<D>(target, size, mipmaps, P::pixel_format(), sampler)?;
+    create_texture::<D>(&mut state, target, size, mipmaps, pf, sampler)?;
 
     let texture = Texture {
       handle,
@@ -106,7 +119,7 @@ where
 
     gfx_state.bind_texture(texture.target, texture.handle);
 
-    upload_texels::<D, P, P::Encoding>(texture.target, offset, size, texels)?;
+    upload_texels::<D, P, P::Encoding>(texture.target, 0, offset, size, texels)?;
 
     if gen_mipmaps == GenMipmaps::Yes {
       gl::GenerateMipmap(texture.target);
@@ -137,7 +150,7 @@ where
 
     gfx_state.bind_texture(texture.target, texture.handle);
 
-    upload_texels::<D, P, P::RawEncoding>(texture.target, offset, size, texels)?;
+    upload_texels::<D, P, P::RawEncoding>(texture.target, 0, offset, size, texels)?;
 
     if gen_mipmaps == GenMipmaps::Yes {
       gl::GenerateMipmap(texture.target);
@@ -171,39 +184,490 @@ where
     P::RawEncoding: Copy + Default,
   {
     let pf = P::pixel_format();
-    let (format, _, ty) = opengl_pixel_format(pf).unwrap();
 
-    let mut w = 0;
-    let mut h = 0;
+    let mut gfx_state = texture.state.borrow_mut();
+    gfx_state.bind_texture(texture.target, texture.handle);
+
+    // resize the vec to allocate enough space to host the returned texels
+    let extent = prepare_get_tex_image(texture.target, pf, 0);
+    let mut texels = vec![Default::default(); raw_texels_len(pf, extent)];
+    get_tex_image(texture.target, pf, 0, extent, texels.as_mut_ptr());
+
+    gfx_state.bind_texture(texture.target, 0);
+
+    Ok(texels)
+  }
+
+  unsafe fn copy_from(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    src: &Self::TextureRepr,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError> {
+    copy_texels::<D>(
+      texture,
+      src,
+      P::pixel_format(),
+      src_offset,
+      dst_offset,
+      size,
+    )?;
+
+    if gen_mipmaps == GenMipmaps::Yes {
+      let mut gfx_state = texture.state.borrow_mut();
+
+      gfx_state.bind_texture(texture.target, texture.handle);
+      gl::GenerateMipmap(texture.target);
+      gfx_state.bind_texture(texture.target, 0);
+    }
+
+    Ok(())
+  }
+
+  unsafe fn upload_level_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    let mut gfx_state = texture.state.borrow_mut();
+
+    gfx_state.bind_texture(texture.target, texture.handle);
+    let r = upload_texels::<D, P, P::Encoding>(texture.target, level, offset, size, texels);
+    gfx_state.bind_texture(texture.target, 0);
+
+    r
+  }
+
+  unsafe fn upload_level_part_raw(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    let mut gfx_state = texture.state.borrow_mut();
+
+    gfx_state.bind_texture(texture.target, texture.handle);
+    let r = upload_texels::<D, P, P::RawEncoding>(texture.target, level, offset, size, texels);
+    gfx_state.bind_texture(texture.target, 0);
+
+    r
+  }
+
+  unsafe fn get_level_part_raw_texels(
+    texture: &Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let pf = P::pixel_format();
+
+    let mut gfx_state = texture.state.borrow_mut();
+    gfx_state.bind_texture(texture.target, texture.handle);
+
+    // there’s no glGetTextureSubImage before OpenGL 4.5, so the whole level is read back and the
+    // region is cropped out of it
+    let extent = prepare_get_tex_image(texture.target, pf, level);
+    let mut texels = vec![Default::default(); raw_texels_len(pf, extent)];
+    get_tex_image(texture.target, pf, level, extent, texels.as_mut_ptr());
+
+    gfx_state.bind_texture(texture.target, 0);
+
+    let (offset, size) = region::<D>(offset, size);
+    crop_texels(&texels, extent, pf.canals_len(), offset, size)
+      .ok_or_else(|| TextureError::cannot_retrieve_texels("region out of the level"))
+  }
 
+  unsafe fn generate_mipmaps(texture: &mut Self::TextureRepr) -> Result<(), TextureError> {
     let mut gfx_state = texture.state.borrow_mut();
+
     gfx_state.bind_texture(texture.target, texture.handle);
+    gl::GenerateMipmap(texture.target);
+    gfx_state.bind_texture(texture.target, 0);
 
-    // retrieve the size of the texture (w and h)
-    gl::GetTexLevelParameteriv(texture.target, 0, gl::TEXTURE_WIDTH, &mut w);
-    gl::GetTexLevelParameteriv(texture.target, 0, gl::TEXTURE_HEIGHT, &mut h);
+    Ok(())
+  }
+}
 
-    // set the packing alignment based on the number of bytes to skip
-    let skip_bytes = (pf.format.size() * w as usize) % 8;
-    set_pack_alignment(skip_bytes);
+unsafe impl<D, P> TextureReadbackBackend<D, P> for GL33
+where
+  D: Dimensionable,
+  P: Pixel,
+  P::RawEncoding: Copy,
+{
+  unsafe fn get_raw_texels_async(
+    texture: &Self::TextureRepr,
+    _: D::Size,
+  ) -> Result<Self::ReadbackRepr, TextureError> {
+    let pf = P::pixel_format();
 
-    // resize the vec to allocate enough space to host the returned texels
-    let mut texels = vec![Default::default(); (w * h) as usize * pf.canals_len()];
+    let mut gfx_state = texture.state.borrow_mut();
+    gfx_state.bind_texture(texture.target, texture.handle);
 
-    gl::GetTexImage(
+    // the texels are packed to the staging buffer, the pointer being an offset in it
+    let extent = prepare_get_tex_image(texture.target, pf, 0);
+    let len = raw_texels_len(pf, extent);
+    let staging = Readback::<P::RawEncoding>::new_staging_buffer(gl::PIXEL_PACK_BUFFER, len);
+    get_tex_image(
       texture.target,
+      pf,
       0,
-      format,
-      ty,
-      texels.as_mut_ptr() as *mut c_void,
+      extent,
+      ptr::null_mut::<P::RawEncoding>(),
     );
+    gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
 
     gfx_state.bind_texture(texture.target, 0);
 
-    Ok(texels)
+    Readback::from_handle(staging, len)
+      .map_err(|_| TextureError::cannot_retrieve_texels("cannot create readback fence"))
+  }
+}
+
+unsafe impl TextureCompression for GL33 {
+  unsafe fn supports_compression(&mut self, family: CompressionFamily) -> bool {
+    self.state.borrow().supports_compression(family)
   }
 }
 
+unsafe impl<D, P> TextureCompressedBackend<D, P> for GL33
+where
+  D: Dimensionable,
+  P: CompressedPixel,
+{
+  unsafe fn upload_compressed_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    let mut gfx_state = texture.state.borrow_mut();
+
+    gfx_state.bind_texture(texture.target, texture.handle);
+
+    let r = upload_compressed_blocks::<D>(
+      texture.target,
+      P::pixel_format(),
+      level,
+      [
+        D::x_offset(offset),
+        D::y_offset(offset),
+        D::z_offset(offset),
+      ],
+      [D::width(size), D::height(size), D::depth(size)],
+      blocks,
+    );
+
+    gfx_state.bind_texture(texture.target, 0);
+
+    r
+  }
+
+  unsafe fn upload_compressed(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    let mut gfx_state = texture.state.borrow_mut();
+
+    gfx_state.bind_texture(texture.target, texture.handle);
+
+    let pf = P::pixel_format();
+    let [w, h] = compressed_level_size::<D>(size, level);
+    let r = match D::dim() {
+      Dim::Cubemap => {
+        blocks
+          .chunks(blocks.len() / 6)
+          .zip(0..6)
+          .try_for_each(|(face_blocks, face)| {
+            upload_compressed_blocks::<D>(
+              texture.target,
+              pf,
+              level,
+              [0, 0, face],
+              [w, h, 1],
+              face_blocks,
+            )
+          })
+      }
+
+      _ => upload_compressed_blocks::<D>(
+        texture.target,
+        pf,
+        level,
+        [0, 0, 0],
+        [w, h, D::depth(size)],
+        blocks,
+      ),
+    };
+
+    gfx_state.bind_texture(texture.target, 0);
+
+    r
+  }
+}
+
+/// Copy a region of the base level of `src` to the base level of `dst`.
+///
+/// There’s no glCopyImageSubData before OpenGL 4.3, so each layer of the region is blitted between
+/// two temporary framebuffers instead.
+pub(crate) unsafe fn copy_texels<D>(
+  dst: &Texture,
+  src: &Texture,
+  pf: PixelFormat,
+  src_offset: D::Offset,
+  dst_offset: D::Offset,
+  size: D::Size,
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let (attachment, mask) = if pf.is_depth_pixel() {
+    (depth_attachment(Some(pf)), BlitMask::DEPTH)
+  } else {
+    (gl::COLOR_ATTACHMENT0, BlitMask::COLOR)
+  };
+  let (src_rect, src_layer, layers) = blit_region::<D>(src_offset, size);
+  let (dst_rect, dst_layer, _) = blit_region::<D>(dst_offset, size);
+
+  let mut gfx_state = dst.state.borrow_mut();
+  let mut framebuffers = [0; 2];
+
+  gl::GenFramebuffers(2, framebuffers.as_mut_ptr());
+  gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffers[0]);
+  gfx_state.bind_draw_framebuffer(framebuffers[1]);
+
+  if pf.is_depth_pixel() {
+    gl::ReadBuffer(gl::NONE);
+    gl::DrawBuffers(1, &gl::NONE);
+  }
+
+  // texels are copied as-is, without any sRGB conversion
+  gfx_state.enable_srgb_framebuffer(false);
+
+  let mut result = Ok(());
+
+  for layer in 0..layers {
+    attach_layer::<D>(gl::READ_FRAMEBUFFER, attachment, src, src_layer + layer);
+    attach_layer::<D>(gl::DRAW_FRAMEBUFFER, attachment, dst, dst_layer + layer);
+
+    if gl::CheckFramebufferStatus(gl::READ_FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE
+      || gl::CheckFramebufferStatus(gl::DRAW_FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE
+    {
+      result = Err(TextureError::cannot_copy_texels(
+        "texture cannot be attached to a framebuffer",
+      ));
+      break;
+    }
+
+    blit(
+      &mut gfx_state,
+      src_rect,
+      dst_rect,
+      mask,
+      BlitFilter::Nearest,
+    );
+  }
+
+  gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+  gfx_state.bind_draw_framebuffer(0);
+  gl::DeleteFramebuffers(2, framebuffers.as_ptr());
+
+  result
+}
+
+/// Rectangle, first layer and number of layers of a region, as blitted by [`copy_texels`].
+///
+/// Cubemap regions only cover the face they are offset to, and 1D array textures have their layers
+/// along the Y axis.
+fn blit_region<D>(offset: D::Offset, size: D::Size) -> (BlitRect, u32, u32)
+where
+  D: Dimensionable,
+{
+  let x = D::x_offset(offset);
+  let w = D::width(size);
+
+  match D::dim() {
+    Dim::Dim1 => (BlitRect::new(x, 0, w, 1), 0, 1),
+    Dim::Dim1Array => (
+      BlitRect::new(x, 0, w, 1),
+      D::y_offset(offset),
+      D::height(size),
+    ),
+    Dim::Dim2 => (
+      BlitRect::new(x, D::y_offset(offset), w, D::height(size)),
+      0,
+      1,
+    ),
+    Dim::Cubemap => (
+      BlitRect::new(x, D::y_offset(offset), w, D::height(size)),
+      D::z_offset(offset),
+      1,
+    ),
+    Dim::Dim3 | Dim::Dim2Array => (
+      BlitRect::new(x, D::y_offset(offset), w, D::height(size)),
+      D::z_offset(offset),
+      D::depth(size),
+    ),
+  }
+}
+
+/// Attach a layer of the base level of `texture` to the framebuffer bound to `target`.
+unsafe fn attach_layer<D>(target: GLenum, attachment: GLenum, texture: &Texture, layer: u32)
+where
+  D: Dimensionable,
+{
+  match D::dim() {
+    Dim::Dim1 => gl::FramebufferTexture1D(target, attachment, texture.target, texture.handle, 0),
+    Dim::Dim2 => gl::FramebufferTexture2D(target, attachment, texture.target, texture.handle, 0),
+    Dim::Cubemap => gl::FramebufferTexture2D(
+      target,
+      attachment,
+      gl::TEXTURE_CUBE_MAP_POSITIVE_X + layer,
+      texture.handle,
+      0,
+    ),
+    _ => gl::FramebufferTextureLayer(target, attachment, texture.handle, 0, layer as GLint),
+  }
+}
+
+/// Set the packing alignment to read the mipmap `level` of the texture bound to `target`, and
+/// return its extent — the faces of cubemaps being along the third axis.
+unsafe fn prepare_get_tex_image(target: GLenum, pf: PixelFormat, level: usize) -> [u32; 3] {
+  // all the faces of a cubemap have the same size
+  let level_target = if target == gl::TEXTURE_CUBE_MAP {
+    gl::TEXTURE_CUBE_MAP_POSITIVE_X
+  } else {
+    target
+  };
+
+  let mut w = 0;
+  let mut h = 0;
+  let mut d = 0;
+
+  // retrieve the size of the level (w, h and d)
+  let level = level as GLint;
+  gl::GetTexLevelParameteriv(level_target, level, gl::TEXTURE_WIDTH, &mut w);
+  gl::GetTexLevelParameteriv(level_target, level, gl::TEXTURE_HEIGHT, &mut h);
+  gl::GetTexLevelParameteriv(level_target, level, gl::TEXTURE_DEPTH, &mut d);
+
+  if target == gl::TEXTURE_CUBE_MAP {
+    d = 6;
+  }
+
+  // set the packing alignment based on the number of bytes to skip
+  let skip_bytes = (pf.format.size() * w as usize) % 8;
+  set_pack_alignment(skip_bytes);
+
+  [w as u32, h as u32, d as u32]
+}
+
+/// Number of texels — in raw encoding — held by a level of extent `[w, h, d]`.
+fn raw_texels_len(pf: PixelFormat, [w, h, d]: [u32; 3]) -> usize {
+  w as usize * h as usize * d as usize * pf.canals_len()
+}
+
+/// Read the mipmap `level` of extent `extent` of the texture bound to `target` to `texels` — either
+/// client memory or an offset in the bound pixel pack buffer.
+///
+/// Cubemaps are read face after face.
+unsafe fn get_tex_image<T>(
+  target: GLenum,
+  pf: PixelFormat,
+  level: usize,
+  [w, h, _]: [u32; 3],
+  texels: *mut T,
+) {
+  let (format, _, ty) = opengl_pixel_format(pf).unwrap();
+  let level = level as GLint;
+
+  if target == gl::TEXTURE_CUBE_MAP {
+    let face_len = raw_texels_len(pf, [w, h, 1]);
+
+    for face in 0..6 {
+      // the pointer might be an offset, hence the wrapping arithmetic
+      gl::GetTexImage(
+        gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+        level,
+        format,
+        ty,
+        texels.wrapping_add(face as usize * face_len) as *mut c_void,
+      );
+    }
+  } else {
+    gl::GetTexImage(target, level, format, ty, texels as *mut c_void);
+  }
+}
+
+/// Crop the region of size `[w, h, d]` at `[x, y, z]` out of `texels`, which hold a level of extent
+/// `extent` made of texels of `texel_len` raw texels each.
+///
+/// Regions that don’t fit in `texels` yield [`None`].
+fn crop_texels<T>(
+  texels: &[T],
+  extent: [u32; 3],
+  texel_len: usize,
+  [x, y, z]: [u32; 3],
+  [w, h, d]: [u32; 3],
+) -> Option<Vec<T>>
+where
+  T: Copy,
+{
+  let row_len = (w as usize).checked_mul(texel_len)?;
+  let mut region = Vec::with_capacity(row_len.checked_mul(h as usize * d as usize)?);
+
+  for k in z..z.checked_add(d)? {
+    for j in y..y.checked_add(h)? {
+      let start = (k as usize)
+        .checked_mul(extent[1] as usize)
+        .and_then(|i| i.checked_add(j as usize))
+        .and_then(|i| i.checked_mul(extent[0] as usize))
+        .and_then(|i| i.checked_add(x as usize))
+        .and_then(|i| i.checked_mul(texel_len))?;
+      let end = start.checked_add(row_len)?;
+
+      region.extend_from_slice(texels.get(start..end)?);
+    }
+  }
+
+  Some(region)
+}
+
+/// Offset and size of the region described by `offset` and `size`, as three-dimensional vectors.
+///
+/// Cubemap regions only cover the face they are offset to, and 1D array textures have their layers
+/// along the Y axis.
+fn region<D>(offset: D::Offset, size: D::Size) -> ([u32; 3], [u32; 3])
+where
+  D: Dimensionable,
+{
+  let x = D::x_offset(offset);
+  let w = D::width(size);
+
+  match D::dim() {
+    Dim::Dim1 => ([x, 0, 0], [w, 1, 1]),
+    Dim::Dim2 | Dim::Dim1Array => ([x, D::y_offset(offset), 0], [w, D::height(size), 1]),
+    Dim::Dim3 | Dim::Dim2Array => (
+      [x, D::y_offset(offset), D::z_offset(offset)],
+      [w, D::height(size), D::depth(size)],
+    ),
+    Dim::Cubemap => ([x, D::y_offset(offset), D::z_offset(offset)], [w, w, 1]),
+  }
+}
+
+// anisotropic filtering, from EXT_texture_filter_anisotropic
+const TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FE;
+pub(crate) const MAX_TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FF;
+
 pub(crate) fn opengl_target(d: Dim) -> GLenum {
   match d {
     Dim::Dim1 => gl::TEXTURE_1D,
@@ -216,6 +680,7 @@ pub(crate) fn opengl_target(d: Dim) -> GLenum {
 }
 
 pub(crate) unsafe fn create_texture<D>(
+  state: &mut GLState,
   target: GLenum,
   size: D::Size,
   mipmaps: usize,
@@ -225,64 +690,117 @@ pub(crate) unsafe fn create_texture<D>(
 where
   D: Dimensionable,
 {
-  set_texture_levels(target, mipmaps);
-  apply_sampler_to_texture(target, sampler);
-  create_texture_storage::<D>(size, mipmaps, pf)
+  set_texture_levels(target, mipmaps, &sampler);
+  apply_sampler_to_texture(state, D::dim(), target, sampler);
+  match pf.format.compression() {
+    Some(compression) => create_compressed_texture_storage::<D>(size, mipmaps, pf, compression),
+    None => create_texture_storage::<D>(size, mipmaps, pf),
+  }
 }
 
-fn set_texture_levels(target: GLenum, mipmaps: usize) {
-  unsafe {
-    gl::TexParameteri(target, gl::TEXTURE_BASE_LEVEL, 0);
-    gl::TexParameteri(target, gl::TEXTURE_MAX_LEVEL, mipmaps as GLint - 1);
-  }
+fn set_texture_levels(target: GLenum, mipmaps: usize, sampler: &Sampler) {
+  set_texture_levels_with(mipmaps, sampler, |param, value| unsafe {
+    gl::TexParameteri(target, param, value)
+  });
 }
 
-fn apply_sampler_to_texture(target: GLenum, sampler: Sampler) {
-  unsafe {
-    gl::TexParameteri(
-      target,
-      gl::TEXTURE_WRAP_R,
-      opengl_wrap(sampler.wrap_r) as GLint,
-    );
-    gl::TexParameteri(
-      target,
-      gl::TEXTURE_WRAP_S,
-      opengl_wrap(sampler.wrap_s) as GLint,
-    );
-    gl::TexParameteri(
-      target,
-      gl::TEXTURE_WRAP_T,
-      opengl_wrap(sampler.wrap_t) as GLint,
-    );
-    gl::TexParameteri(
-      target,
-      gl::TEXTURE_MIN_FILTER,
-      opengl_min_filter(sampler.min_filter) as GLint,
-    );
-    gl::TexParameteri(
-      target,
-      gl::TEXTURE_MAG_FILTER,
-      opengl_mag_filter(sampler.mag_filter) as GLint,
-    );
+/// Set the mipmap levels of a texture with `set_param`, which sets a single texture parameter.
+///
+/// The levels are restricted to the base and max levels of `sampler`.
+pub(crate) fn set_texture_levels_with(
+  mipmaps: usize,
+  sampler: &Sampler,
+  mut set_param: impl FnMut(GLenum, GLint),
+) {
+  let max_level = sampler
+    .max_level
+    .map_or(mipmaps - 1, |level| level.min(mipmaps - 1));
 
-    match sampler.depth_comparison {
-      Some(fun) => {
-        gl::TexParameteri(
-          target,
-          gl::TEXTURE_COMPARE_FUNC,
-          depth_comparison_to_glenum(fun) as GLint,
-        );
-        gl::TexParameteri(
-          target,
-          gl::TEXTURE_COMPARE_MODE,
-          gl::COMPARE_REF_TO_TEXTURE as GLint,
-        );
-      }
-      None => {
-        gl::TexParameteri(target, gl::TEXTURE_COMPARE_MODE, gl::NONE as GLint);
-      }
+  set_param(gl::TEXTURE_BASE_LEVEL, sampler.base_level as GLint);
+  set_param(gl::TEXTURE_MAX_LEVEL, max_level as GLint);
+}
+
+unsafe fn apply_sampler_to_texture(
+  state: &mut GLState,
+  dim: Dim,
+  target: GLenum,
+  sampler: Sampler,
+) {
+  apply_sampler_with(
+    state,
+    dim,
+    sampler,
+    |param, value| gl::TexParameteri(target, param, value),
+    |param, values| gl::TexParameterfv(target, param, values.as_ptr()),
+  );
+}
+
+/// Apply a [`Sampler`] to a texture of dimension `dim` with `set_param` and `set_paramfv`, which
+/// set a single integer and floating texture parameter.
+pub(crate) unsafe fn apply_sampler_with(
+  state: &mut GLState,
+  dim: Dim,
+  sampler: Sampler,
+  mut set_param: impl FnMut(GLenum, GLint),
+  mut set_paramfv: impl FnMut(GLenum, &[GLfloat]),
+) {
+  set_param(gl::TEXTURE_WRAP_R, opengl_wrap(sampler.wrap_r) as GLint);
+  set_param(gl::TEXTURE_WRAP_S, opengl_wrap(sampler.wrap_s) as GLint);
+  set_param(gl::TEXTURE_WRAP_T, opengl_wrap(sampler.wrap_t) as GLint);
+  set_param(
+    gl::TEXTURE_MIN_FILTER,
+    opengl_min_filter(sampler.min_filter) as GLint,
+  );
+  set_param(
+    gl::TEXTURE_MAG_FILTER,
+    opengl_mag_filter(sampler.mag_filter) as GLint,
+  );
+
+  match sampler.depth_comparison {
+    Some(fun) => {
+      set_param(
+        gl::TEXTURE_COMPARE_FUNC,
+        depth_comparison_to_glenum(fun) as GLint,
+      );
+      set_param(
+        gl::TEXTURE_COMPARE_MODE,
+        gl::COMPARE_REF_TO_TEXTURE as GLint,
+      );
+    }
+    None => {
+      set_param(gl::TEXTURE_COMPARE_MODE, gl::NONE as GLint);
     }
   }
+
+  set_paramfv(gl::TEXTURE_MIN_LOD, &[sampler.min_lod]);
+  set_paramfv(gl::TEXTURE_MAX_LOD, &[sampler.max_lod]);
+
+  // OpenGL ES has no LOD bias nor border colors, so they’re only set when needed; GLES3 rejects
+  // samplers using them
+  if sampler.lod_bias != 0. {
+    set_paramfv(gl::TEXTURE_LOD_BIAS, &[sampler.lod_bias]);
+  }
+
+  if uses_border(&sampler) {
+    set_paramfv(gl::TEXTURE_BORDER_COLOR, &sampler.border_color);
+  }
+
+  // anisotropic filtering is silently disabled without EXT_texture_filter_anisotropic
+  if let Some(max_anisotropy) = state.max_anisotropy() {
+    set_paramfv(
+      TEXTURE_MAX_ANISOTROPY,
+      &[sampler.max_anisotropy.max(1.).min(max_anisotropy)],
+    );
+  }
+
+  if dim == Dim::Cubemap && sampler.seamless_cubemap {
+    state.enable_seamless_cubemap(set_param);
+  }
+}
+
+/// Whether any coordinate of a [`Sampler`] is wrapped with [`Wrap::ClampToBorder`].
+pub(crate) fn uses_border(sampler: &Sampler) -> bool {
+  [sampler.wrap_r, sampler.wrap_s, sampler.wrap_t].contains(&Wrap::ClampToBorder)
 }
 
 fn opengl_wrap(wrap: Wrap) -> GLenum {
@@ -290,6 +808,7 @@ fn opengl_wrap(wrap: Wrap) -> GLenum {
     Wrap::ClampToEdge => gl::CLAMP_TO_EDGE,
     Wrap::Repeat => gl::REPEAT,
     Wrap::MirroredRepeat => gl::MIRRORED_REPEAT,
+    Wrap::ClampToBorder => gl::CLAMP_TO_BORDER,
   }
 }
 
@@ -351,9 +870,7 @@ where
             format,
             iformat,
             encoding,
-            D::width(size),
-            D::height(size),
-            D::depth(size),
+            [D::width(size), D::height(size), D::depth(size)],
             mipmaps,
           );
           Ok(())
@@ -386,9 +903,7 @@ where
             format,
             iformat,
             encoding,
-            D::width(size),
-            D::height(size),
-            D::depth(size),
+            [D::width(size), D::height(size), D::depth(size)],
             mipmaps,
           );
           Ok(())
@@ -411,7 +926,7 @@ fn create_texture_1d_storage(
   mipmaps: usize,
 ) {
   for level in 0..mipmaps {
-    let w = w / (1 << level as u32);
+    let w = mipmap_length(w, level);
 
     unsafe {
       gl::TexImage1D(
@@ -438,9 +953,13 @@ fn create_texture_2d_storage(
   mipmaps: usize,
 ) {
   for level in 0..mipmaps {
-    let div = 1 << level as u32;
-    let w = w / div;
-    let h = h / div;
+    let w = mipmap_length(w, level);
+    // the height of 1D array textures is their number of layers
+    let h = if target == gl::TEXTURE_1D_ARRAY {
+      h
+    } else {
+      mipmap_length(h, level)
+    };
 
     unsafe {
       gl::TexImage2D(
@@ -463,16 +982,18 @@ fn create_texture_3d_storage(
   format: GLenum,
   iformat: GLenum,
   encoding: GLenum,
-  w: u32,
-  h: u32,
-  d: u32,
+  [w, h, d]: [u32; 3],
   mipmaps: usize,
 ) {
   for level in 0..mipmaps {
-    let div = 1 << level as u32;
-    let w = w / div;
-    let h = h / div;
-    let d = d / div;
+    let w = mipmap_length(w, level);
+    let h = mipmap_length(h, level);
+    // the depth of 2D array textures is their number of layers
+    let d = if target == gl::TEXTURE_2D_ARRAY {
+      d
+    } else {
+      mipmap_length(d, level)
+    };
 
     unsafe {
       gl::TexImage3D(
@@ -499,7 +1020,7 @@ fn create_cubemap_storage(
   mipmaps: usize,
 ) {
   for level in 0..mipmaps {
-    let s = s / (1 << level as u32);
+    let s = mipmap_length(s, level);
 
     for face in 0..6 {
       unsafe {
@@ -519,8 +1040,171 @@ fn create_cubemap_storage(
   }
 }
 
+/// Length of a side of length `len` in the mipmap `level`.
+fn mipmap_length(len: u32, level: usize) -> u32 {
+  len.checked_shr(level as u32).unwrap_or(0).max(1)
+}
+
+/// Width and height of the mipmap `level` of a compressed texture of size `size`.
+pub(crate) fn compressed_level_size<D>(size: D::Size, level: usize) -> [u32; 2]
+where
+  D: Dimensionable,
+{
+  let halve = |x: u32| x.checked_shr(level as u32).unwrap_or(0).max(1);
+  [halve(D::width(size)), halve(D::height(size))]
+}
+
+fn create_compressed_texture_storage<D>(
+  size: D::Size,
+  mipmaps: usize,
+  pf: PixelFormat,
+  compression: Compression,
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let iformat = opengl_compressed_format(pf).ok_or_else(|| {
+    TextureError::texture_storage_creation_failed(format!(
+      "unsupported texture pixel format: {:?}",
+      pf
+    ))
+  })?;
+
+  for level in 0..mipmaps {
+    let [w, h] = compressed_level_size::<D>(size, level);
+    let len = compression
+      .image_bytes(w, h)
+      .ok_or_else(|| TextureError::texture_storage_creation_failed("texture is too large"))?;
+
+    match D::dim() {
+      Dim::Dim2 => unsafe {
+        gl::CompressedTexImage2D(
+          gl::TEXTURE_2D,
+          level as GLint,
+          iformat,
+          w as GLsizei,
+          h as GLsizei,
+          0,
+          len as GLsizei,
+          ptr::null(),
+        )
+      },
+
+      Dim::Cubemap => {
+        for face in 0..6 {
+          unsafe {
+            gl::CompressedTexImage2D(
+              gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+              level as GLint,
+              iformat,
+              w as GLsizei,
+              h as GLsizei,
+              0,
+              len as GLsizei,
+              ptr::null(),
+            )
+          };
+        }
+      }
+
+      Dim::Dim2Array => unsafe {
+        let layers = D::depth(size);
+
+        gl::CompressedTexImage3D(
+          gl::TEXTURE_2D_ARRAY,
+          level as GLint,
+          iformat,
+          w as GLsizei,
+          h as GLsizei,
+          layers as GLsizei,
+          0,
+          (len * layers as usize) as GLsizei,
+          ptr::null(),
+        )
+      },
+
+      dim => {
+        return Err(TextureError::texture_storage_creation_failed(format!(
+          "compressed pixel formats are not supported with {} textures",
+          dim
+        )))
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Upload compressed blocks to the region of size `[w, h, depth]` at `[x, y, z]` in the mipmap
+/// `level` of the bound texture.
+///
+/// For cubemaps, `z` is the face and `depth` is ignored.
+fn upload_compressed_blocks<D>(
+  target: GLenum,
+  pf: PixelFormat,
+  level: usize,
+  [x, y, z]: [u32; 3],
+  [w, h, depth]: [u32; 3],
+  blocks: &[u8],
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let format = opengl_compressed_format(pf).ok_or(TextureError::unsupported_pixel_format(pf))?;
+
+  match D::dim() {
+    Dim::Dim2 => unsafe {
+      gl::CompressedTexSubImage2D(
+        target,
+        level as GLint,
+        x as GLint,
+        y as GLint,
+        w as GLsizei,
+        h as GLsizei,
+        format,
+        blocks.len() as GLsizei,
+        blocks.as_ptr() as *const c_void,
+      )
+    },
+
+    Dim::Cubemap => unsafe {
+      gl::CompressedTexSubImage2D(
+        gl::TEXTURE_CUBE_MAP_POSITIVE_X + z,
+        level as GLint,
+        x as GLint,
+        y as GLint,
+        w as GLsizei,
+        h as GLsizei,
+        format,
+        blocks.len() as GLsizei,
+        blocks.as_ptr() as *const c_void,
+      )
+    },
+
+    Dim::Dim2Array => unsafe {
+      gl::CompressedTexSubImage3D(
+        target,
+        level as GLint,
+        x as GLint,
+        y as GLint,
+        z as GLint,
+        w as GLsizei,
+        h as GLsizei,
+        depth as GLsizei,
+        format,
+        blocks.len() as GLsizei,
+        blocks.as_ptr() as *const c_void,
+      )
+    },
+
+    _ => return Err(TextureError::unsupported_pixel_format(pf)),
+  }
+
+  Ok(())
+}
+
 // set the unpack alignment for uploading aligned texels
-fn set_unpack_alignment(skip_bytes: usize) {
+pub(crate) fn set_unpack_alignment(skip_bytes: usize) {
   let unpack_alignment = match skip_bytes {
     0 => 8,
     2 => 2,
@@ -532,7 +1216,7 @@ fn set_unpack_alignment(skip_bytes: usize) {
 }
 
 // set the pack alignment for downloading aligned texels
-fn set_pack_alignment(skip_bytes: usize) {
+pub(crate) fn set_pack_alignment(skip_bytes: usize) {
   let pack_alignment = match skip_bytes {
     0 => 8,
     2 => 2,
@@ -543,9 +1227,11 @@ fn set_pack_alignment(skip_bytes: usize) {
   unsafe { gl::PixelStorei(gl::PACK_ALIGNMENT, pack_alignment) };
 }
 
-// Upload texels into the texture’s memory. Becareful of the type of texels you send down.
+// Upload texels into the mipmap `level` of the texture’s memory. Becareful of the type of texels
+// you send down.
 fn upload_texels<D, P, T>(
   target: GLenum,
+  level: usize,
   off: D::Offset,
   size: D::Size,
   texels: &[T],
@@ -576,7 +1262,7 @@ where
       Dim::Dim1 => unsafe {
         gl::TexSubImage1D(
           target,
-          0,
+          level as GLint,
           D::x_offset(off) as GLint,
           D::width(size) as GLsizei,
           format,
@@ -588,7 +1274,7 @@ where
       Dim::Dim2 => unsafe {
         gl::TexSubImage2D(
           target,
-          0,
+          level as GLint,
           D::x_offset(off) as GLint,
           D::y_offset(off) as GLint,
           D::width(size) as GLsizei,
@@ -602,7 +1288,7 @@ where
       Dim::Dim3 => unsafe {
         gl::TexSubImage3D(
           target,
-          0,
+          level as GLint,
           D::x_offset(off) as GLint,
           D::y_offset(off) as GLint,
           D::z_offset(off) as GLint,
@@ -618,7 +1304,7 @@ where
       Dim::Cubemap => unsafe {
         gl::TexSubImage2D(
           gl::TEXTURE_CUBE_MAP_POSITIVE_X + D::z_offset(off),
-          0,
+          level as GLint,
           D::x_offset(off) as GLint,
           D::y_offset(off) as GLint,
           D::width(size) as GLsizei,
@@ -632,7 +1318,7 @@ where
       Dim::Dim1Array => unsafe {
         gl::TexSubImage2D(
           target,
-          0,
+          level as GLint,
           D::x_offset(off) as GLint,
           D::y_offset(off) as GLint,
           D::width(size) as GLsizei,
@@ -646,7 +1332,7 @@ where
       Dim::Dim2Array => unsafe {
         gl::TexSubImage3D(
           target,
-          0,
+          level as GLint,
           D::x_offset(off) as GLint,
           D::y_offset(off) as GLint,
           D::z_offset(off) as GLint,
@@ -0,0 +1,79 @@
+//! OpenGL 4.5 backend.
+//!
+//! This backend uses direct state access (DSA) to create and edit buffers, textures, renderbuffers
+//! and framebuffers: objects are edited through their names instead of being bound to a target
+//! first. It reuses the [`GL33`] representations and drives the same [`GLState`], so everything
+//! else — shaders, tessellations, pipelines — behaves as with [`GL33`]. Differences are:
+//!
+//! - Shader stages are prefixed with `#version 450 core`. Double-precision uniforms are always
+//!   available, as they are core since OpenGL 4.0.
+//! - Textures have immutable storage (`glTextureStorage*`). Textures using unsized internal
+//!   formats (32-bit normalized formats) are then not supported.
+//! - Buffers have mutable storage (`glNamedBufferData`), except [`BufferUsage::Persistent`]
+//!   buffers, which get immutable storage (`glNamedBufferStorage`) mapped once for their whole
+//!   lifetime.
+//! - Textures are cleared with `glClearTexSubImage` instead of uploading a texel buffer.
+//! - Compute shaders are supported: [`GL45`] implements the [`Compute`] backend trait.
+//!
+//! [`BufferUsage::Persistent`]: luminance::buffer::BufferUsage::Persistent
+//! [`Compute`]: luminance::backend::compute::Compute
+
+mod buffer;
+mod compute;
+mod fence;
+mod framebuffer;
+mod pipeline;
+mod pixel;
+mod query;
+mod readback;
+mod renderbuffer;
+mod shader;
+mod tess;
+mod texture;
+
+use crate::gl33::state::GLApi;
+use crate::gl33::{GLState, StateQueryError, GL33};
+use gl::types::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The OpenGL 4.5 backend.
+#[derive(Debug)]
+pub struct GL45 {
+  pub(crate) gl33: GL33,
+  // maximum number of compute work groups in each dimension
+  pub(crate) max_compute_work_groups: [u32; 3],
+}
+
+impl GL45 {
+  /// Create the backend from the OpenGL 4.5 context current on the calling thread.
+  pub fn new() -> Result<Self, StateQueryError> {
+    GLState::new(GLApi::GL).map(|state| GL45 {
+      gl33: GL33 {
+        state: Rc::new(RefCell::new(state)),
+      },
+      max_compute_work_groups: unsafe { get_max_compute_work_groups() },
+    })
+  }
+
+  /// Access the underlying graphics state.
+  ///
+  /// # Safety
+  ///
+  /// Changing the OpenGL state without updating the [`GLState`] leads to undefined behavior.
+  pub unsafe fn state(&self) -> &Rc<RefCell<GLState>> {
+    self.gl33.state()
+  }
+}
+
+unsafe fn get_max_compute_work_groups() -> [u32; 3] {
+  let mut max = [0; 3];
+
+  for (i, max) in max.iter_mut().enumerate() {
+    let mut count: GLint = 0;
+    gl::GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_COUNT, i as GLuint, &mut count);
+    *max = count as u32;
+  }
+
+  max
+}
@@ -0,0 +1,300 @@
+//! OpenGL 4.5 buffer implementation.
+
+use gl;
+use gl::types::*;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::mem;
+use std::ptr::{self, NonNull};
+use std::rc::Rc;
+
+use crate::gl33::buffer::{opengl_usage, Buffer, BufferSlice, BufferSliceMut};
+use crate::gl33::readback::Readback;
+use crate::gl33::state::GLState;
+use crate::gl33::GL33;
+use crate::gl45::readback::new_named_staging_buffer;
+use crate::gl45::GL45;
+use luminance::backend::buffer::{
+  Buffer as BufferBackend, BufferReadback as BufferReadbackBackend,
+  BufferSlice as BufferSliceBackend,
+};
+use luminance::buffer::{BufferError, BufferUsage, PreserveContent};
+
+/// Create a buffer initialized with `buf`.
+///
+/// Persistent buffers get immutable storage, mapped once for all; other buffers get mutable
+/// storage with the usage hint.
+unsafe fn new_named_buffer<T>(
+  state: &Rc<RefCell<GLState>>,
+  buf: Vec<T>,
+  usage: BufferUsage,
+) -> Result<Buffer<T>, BufferError> {
+  let mut handle: GLuint = 0;
+  gl::CreateBuffers(1, &mut handle);
+
+  let bytes = mem::size_of::<T>() * buf.len();
+  let mut persistent = None;
+
+  if usage != BufferUsage::Persistent {
+    gl::NamedBufferData(
+      handle,
+      bytes as GLsizeiptr,
+      buf.as_ptr() as _,
+      opengl_usage(usage),
+    );
+  } else if bytes > 0 {
+    // empty storage is an error, so empty buffers don’t get any
+    let flags =
+      gl::MAP_READ_BIT | gl::MAP_WRITE_BIT | gl::MAP_PERSISTENT_BIT | gl::MAP_COHERENT_BIT;
+    gl::NamedBufferStorage(
+      handle,
+      bytes as GLsizeiptr,
+      buf.as_ptr() as _,
+      gl::DYNAMIC_STORAGE_BIT | flags,
+    );
+
+    let ptr = gl::MapNamedBufferRange(handle, 0, bytes as GLsizeiptr, flags) as *mut T;
+
+    match NonNull::new(ptr) {
+      Some(ptr) => persistent = Some(ptr),
+      None => {
+        gl::DeleteBuffers(1, &handle);
+        return Err(BufferError::map_failed());
+      }
+    }
+  }
+
+  Ok(Buffer::from_handle(
+    handle,
+    state.clone(),
+    buf,
+    persistent,
+    usage,
+  ))
+}
+
+unsafe impl<T> BufferBackend<T> for GL45
+where
+  T: Copy,
+{
+  type BufferRepr = Buffer<T>;
+
+  unsafe fn new_buffer(
+    &mut self,
+    len: usize,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>
+  where
+    T: Default,
+  {
+    new_named_buffer(&self.gl33.state, vec![T::default(); len], usage)
+  }
+
+  unsafe fn len(buffer: &Self::BufferRepr) -> usize {
+    <GL33 as BufferBackend<T>>::len(buffer)
+  }
+
+  unsafe fn from_vec(
+    &mut self,
+    vec: Vec<T>,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    new_named_buffer(&self.gl33.state, vec, usage)
+  }
+
+  unsafe fn repeat(
+    &mut self,
+    len: usize,
+    value: T,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    new_named_buffer(&self.gl33.state, vec![value; len], usage)
+  }
+
+  unsafe fn at(buffer: &Self::BufferRepr, i: usize) -> Option<T> {
+    GL33::at(buffer, i)
+  }
+
+  unsafe fn whole(buffer: &Self::BufferRepr) -> Vec<T> {
+    GL33::whole(buffer)
+  }
+
+  unsafe fn set(buffer: &mut Self::BufferRepr, i: usize, x: T) -> Result<(), BufferError> {
+    if i >= buffer.buf.len() {
+      Err(BufferError::overflow(i, buffer.buf.len()))
+    } else {
+      buffer.buf[i] = x;
+      write_buffer(buffer, i, &[x]);
+
+      Ok(())
+    }
+  }
+
+  unsafe fn write_whole(buffer: &mut Self::BufferRepr, values: &[T]) -> Result<(), BufferError> {
+    let provided_len = values.len();
+    let buffer_len = buffer.buf.len();
+
+    // error if we don’t pass the right number of items
+    match provided_len.cmp(&buffer_len) {
+      Ordering::Less => return Err(BufferError::too_few_values(provided_len, buffer_len)),
+
+      Ordering::Greater => return Err(BufferError::too_many_values(provided_len, buffer_len)),
+
+      _ => (),
+    }
+
+    write_buffer(buffer, 0, values);
+    buffer.buf.copy_from_slice(values);
+
+    Ok(())
+  }
+
+  unsafe fn write_range(
+    buffer: &mut Self::BufferRepr,
+    offset: usize,
+    values: &[T],
+  ) -> Result<(), BufferError> {
+    write_buffer(buffer, offset, values);
+    buffer.buf[offset..offset + values.len()].copy_from_slice(values);
+
+    Ok(())
+  }
+
+  unsafe fn read_range(
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Vec<T>, BufferError> {
+    let mut values = Vec::with_capacity(len);
+
+    // allowed on persistently mapped buffers too, and waits for the GPU to be done with them
+    gl::GetNamedBufferSubData(
+      buffer.handle(),
+      (start * mem::size_of::<T>()) as GLintptr,
+      (len * mem::size_of::<T>()) as GLsizeiptr,
+      values.as_mut_ptr() as _,
+    );
+    values.set_len(len);
+
+    Ok(values)
+  }
+
+  unsafe fn copy_from(
+    buffer: &mut Self::BufferRepr,
+    src: &Self::BufferRepr,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  ) -> Result<(), BufferError> {
+    let item_bytes = mem::size_of::<T>();
+
+    gl::CopyNamedBufferSubData(
+      src.handle(),
+      buffer.handle(),
+      (src_start * item_bytes) as GLintptr,
+      (dst_offset * item_bytes) as GLintptr,
+      (len * item_bytes) as GLsizeiptr,
+    );
+
+    buffer.buf[dst_offset..dst_offset + len].copy_from_slice(&src.buf[src_start..src_start + len]);
+
+    Ok(())
+  }
+
+  unsafe fn clear(buffer: &mut Self::BufferRepr, x: T) -> Result<(), BufferError> {
+    for item in &mut buffer.buf {
+      *item = x;
+    }
+
+    write_buffer(buffer, 0, &buffer.buf);
+
+    Ok(())
+  }
+
+  unsafe fn resize(
+    buffer: &mut Self::BufferRepr,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError> {
+    let mut buf = match preserve {
+      PreserveContent::Yes => GL45::read_range(buffer, 0, buffer.buf.len().min(len))?,
+      PreserveContent::No => Vec::with_capacity(len),
+    };
+    buf.resize(len, value);
+
+    if buffer.usage == BufferUsage::Persistent {
+      // immutable storage cannot be re-allocated, so a whole new buffer is required
+      *buffer = new_named_buffer(buffer.state(), buf, buffer.usage)?;
+    } else {
+      gl::NamedBufferData(
+        buffer.handle(),
+        mem::size_of_val(buf.as_slice()) as GLsizeiptr,
+        buf.as_ptr() as _,
+        opengl_usage(buffer.usage),
+      );
+
+      buffer.buf = buf;
+    }
+
+    Ok(())
+  }
+}
+
+unsafe impl<T> BufferReadbackBackend<T> for GL45
+where
+  T: Copy,
+{
+  unsafe fn whole_async(buffer: &Self::BufferRepr) -> Result<Self::ReadbackRepr, BufferError> {
+    let len = buffer.buf.len();
+    let staging = new_named_staging_buffer::<T>(len);
+
+    gl::CopyNamedBufferSubData(
+      buffer.handle(),
+      staging,
+      0,
+      0,
+      (len * mem::size_of::<T>()) as GLsizeiptr,
+    );
+
+    Readback::from_handle(staging, len).map_err(|_| BufferError::cannot_create())
+  }
+}
+
+/// Write `values` in `buffer`, starting at item `offset`.
+///
+/// Persistently mapped buffers are written directly through their mapped memory.
+unsafe fn write_buffer<T>(buffer: &Buffer<T>, offset: usize, values: &[T])
+where
+  T: Copy,
+{
+  match buffer.persistent {
+    Some(ptr) => ptr::copy_nonoverlapping(values.as_ptr(), ptr.as_ptr().add(offset), values.len()),
+
+    None => gl::NamedBufferSubData(
+      buffer.handle(),
+      (offset * mem::size_of::<T>()) as GLintptr,
+      mem::size_of_val(values) as GLsizeiptr,
+      values.as_ptr() as _,
+    ),
+  }
+}
+
+unsafe impl<T> BufferSliceBackend<T> for GL45
+where
+  T: Copy,
+{
+  type SliceRepr = BufferSlice<T>;
+
+  type SliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn slice_buffer(buffer: &Self::BufferRepr) -> Result<Self::SliceRepr, BufferError> {
+    GL33::slice_buffer(buffer)
+  }
+
+  unsafe fn slice_buffer_mut(
+    buffer: &mut Self::BufferRepr,
+  ) -> Result<Self::SliceMutRepr, BufferError> {
+    GL33::slice_buffer_mut(buffer)
+  }
+}
@@ -0,0 +1,31 @@
+use gl;
+
+use crate::gl33::shader::Program;
+use crate::gl45::GL45;
+use luminance::backend::compute::Compute;
+use luminance::compute::ComputeError;
+use luminance::shader::ProgramError;
+
+unsafe impl Compute for GL45 {
+  unsafe fn new_compute_program(
+    &mut self,
+    stage: &Self::StageRepr,
+  ) -> Result<Self::ProgramRepr, ProgramError> {
+    Program::from_compute_stage(stage)
+  }
+
+  unsafe fn dispatch(&mut self, workgroups: [u32; 3]) -> Result<(), ComputeError> {
+    let max = self.max_compute_work_groups;
+
+    if workgroups.iter().zip(&max).any(|(count, max)| count > max) {
+      return Err(ComputeError::too_many_work_groups(workgroups, max));
+    }
+
+    gl::DispatchCompute(workgroups[0], workgroups[1], workgroups[2]);
+
+    // make whatever the work groups wrote visible to any subsequent command
+    gl::MemoryBarrier(gl::ALL_BARRIER_BITS);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,229 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::framebuffer::{
+  blit_bounds, depth_attachment, framebuffer_status, opengl_blit_filter, opengl_blit_mask,
+  Framebuffer, Multisample,
+};
+use crate::gl33::state::{GLState, ScissorState};
+use crate::gl33::GL33;
+use crate::gl45::GL45;
+use luminance::backend::color_slot::ColorSlot;
+use luminance::backend::depth_slot::DepthSlot;
+use luminance::backend::framebuffer::{
+  Framebuffer as FramebufferBackend, FramebufferBackBuffer, FramebufferBlit, FramebufferMultisample,
+};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError};
+use luminance::pixel::PixelFormat;
+use luminance::texture::{Dim2, Dimensionable, Sampler};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+unsafe impl<D> FramebufferBackend<D> for GL45
+where
+  D: Dimensionable,
+{
+  type FramebufferRepr = Framebuffer<D>;
+
+  unsafe fn new_framebuffer<CS, DS>(
+    &mut self,
+    size: D::Size,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, D>,
+    DS: DepthSlot<Self, D>,
+  {
+    let depth_format = DS::depth_format();
+    let framebuffer = new_framebuffer(
+      &self.gl33.state,
+      size,
+      CS::color_formats().len(),
+      depth_format,
+      depth_format.is_none(),
+    );
+
+    Ok(framebuffer)
+  }
+
+  unsafe fn attach_color_texture(
+    framebuffer: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    gl::NamedFramebufferTexture(
+      framebuffer.handle,
+      gl::COLOR_ATTACHMENT0 + attachment_index as GLenum,
+      texture.handle,
+      0,
+    );
+
+    Ok(())
+  }
+
+  unsafe fn attach_depth_texture(
+    framebuffer: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+  ) -> Result<(), FramebufferError> {
+    gl::NamedFramebufferTexture(
+      framebuffer.handle,
+      framebuffer.depth_attachment,
+      texture.handle,
+      0,
+    );
+
+    Ok(())
+  }
+
+  unsafe fn validate_framebuffer(
+    framebuffer: Self::FramebufferRepr,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    let status = gl::CheckNamedFramebufferStatus(framebuffer.handle, gl::DRAW_FRAMEBUFFER);
+
+    framebuffer_status(status)
+      .map(move |_| framebuffer)
+      .map_err(FramebufferError::from)
+  }
+
+  unsafe fn framebuffer_size(framebuffer: &Self::FramebufferRepr) -> D::Size {
+    <GL33 as FramebufferBackend<D>>::framebuffer_size(framebuffer)
+  }
+}
+
+/// Create a framebuffer drawing to `color_nb` color attachments.
+///
+/// If `depth_renderbuffer` is `true`, a depth renderbuffer is attached.
+unsafe fn new_framebuffer<D>(
+  state: &Rc<RefCell<GLState>>,
+  size: D::Size,
+  color_nb: usize,
+  depth_format: Option<PixelFormat>,
+  depth_renderbuffer: bool,
+) -> Framebuffer<D>
+where
+  D: Dimensionable,
+{
+  let mut handle: GLuint = 0;
+  gl::CreateFramebuffers(1, &mut handle);
+
+  // specify the list of color buffers to draw to
+  let color_buf_nb = color_nb as GLsizei;
+
+  if color_buf_nb == 0 {
+    gl::NamedFramebufferDrawBuffers(handle, 1, &gl::NONE);
+    gl::NamedFramebufferReadBuffer(handle, gl::NONE);
+  } else {
+    let color_buffers: Vec<_> =
+      (gl::COLOR_ATTACHMENT0..gl::COLOR_ATTACHMENT0 + color_buf_nb as GLenum).collect();
+
+    gl::NamedFramebufferDrawBuffers(handle, color_buf_nb, color_buffers.as_ptr());
+  }
+
+  // depth renderbuffer, if no depth texture is used
+  let renderbuffer = if depth_renderbuffer {
+    let mut renderbuffer: GLuint = 0;
+
+    gl::CreateRenderbuffers(1, &mut renderbuffer);
+    gl::NamedRenderbufferStorage(
+      renderbuffer,
+      gl::DEPTH_COMPONENT32F,
+      D::width(size) as GLsizei,
+      D::height(size) as GLsizei,
+    );
+    gl::NamedFramebufferRenderbuffer(handle, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, renderbuffer);
+
+    Some(renderbuffer)
+  } else {
+    None
+  };
+
+  Framebuffer {
+    handle,
+    renderbuffer,
+    multisample: None,
+    depth_attachment: depth_attachment(depth_format),
+    size,
+    state: state.clone(),
+  }
+}
+
+unsafe impl FramebufferBackBuffer for GL45 {
+  unsafe fn back_buffer(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    self.gl33.back_buffer(size)
+  }
+}
+
+unsafe impl FramebufferBlit for GL45 {
+  unsafe fn blit_framebuffer(
+    src: &Self::FramebufferRepr,
+    dst: &mut Self::FramebufferRepr,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError> {
+    // blits are clipped by the scissor test
+    dst.state.borrow_mut().set_scissor_state(ScissorState::Off);
+
+    let [src_x0, src_y0, src_x1, src_y1] = blit_bounds(src_rect);
+    let [dst_x0, dst_y0, dst_x1, dst_y1] = blit_bounds(dst_rect);
+
+    gl::BlitNamedFramebuffer(
+      src.handle,
+      dst.handle,
+      src_x0,
+      src_y0,
+      src_x1,
+      src_y1,
+      dst_x0,
+      dst_y0,
+      dst_x1,
+      dst_y1,
+      opengl_blit_mask(mask),
+      opengl_blit_filter(filter),
+    );
+
+    Ok(())
+  }
+}
+
+unsafe impl FramebufferMultisample for GL45 {
+  unsafe fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, Dim2>,
+    DS: DepthSlot<Self, Dim2>,
+  {
+    let color_formats = CS::color_formats();
+    let depth_format = DS::depth_format();
+    let multisample = Multisample::new(
+      &self.gl33.state,
+      size,
+      samples,
+      &color_formats,
+      depth_format,
+    )?;
+
+    // the depth is rendered to the multisampled storage, so there’s no need for a depth
+    // renderbuffer
+    let mut framebuffer = new_framebuffer(
+      &self.gl33.state,
+      size,
+      color_formats.len(),
+      depth_format,
+      false,
+    );
+    framebuffer.multisample = Some(multisample);
+
+    Ok(framebuffer)
+  }
+}
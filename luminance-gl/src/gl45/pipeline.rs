@@ -0,0 +1,193 @@
+use luminance::backend::pipeline::{
+  Pipeline as PipelineBackend, PipelineBase, PipelineBuffer, PipelineStorageBuffer, PipelineTexture,
+};
+use luminance::backend::render_gate::RenderGate;
+use luminance::backend::shading_gate::ShadingGate;
+use luminance::backend::tess::Tess;
+use luminance::backend::tess_gate::TessGate;
+use luminance::pipeline::{PipelineError, PipelineState};
+use luminance::pixel::Pixel;
+use luminance::render_state::RenderState;
+use luminance::tess::{Deinterleaved, DeinterleavedData, Interleaved, TessIndex, TessVertexData};
+use luminance::texture::Dimensionable;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::gl33::pipeline::{BoundBuffer, BoundTexture, Pipeline};
+use crate::gl33::{GLState, GL33};
+use crate::gl45::GL45;
+
+pub struct BoundStorageBuffer {
+  pub(crate) binding: u32,
+  state: Rc<RefCell<GLState>>,
+}
+
+impl Drop for BoundStorageBuffer {
+  fn drop(&mut self) {
+    let mut state = self.state.borrow_mut();
+    state
+      .binding_stack_mut()
+      .free_storage_buffer_bindings
+      .push(self.binding);
+  }
+}
+
+unsafe impl PipelineBase for GL45 {
+  type PipelineRepr = Pipeline;
+
+  unsafe fn new_pipeline(&mut self) -> Result<Self::PipelineRepr, PipelineError> {
+    self.gl33.new_pipeline()
+  }
+}
+
+unsafe impl<D> PipelineBackend<D> for GL45
+where
+  D: Dimensionable,
+{
+  unsafe fn start_pipeline(
+    &mut self,
+    framebuffer: &Self::FramebufferRepr,
+    pipeline_state: &PipelineState,
+  ) {
+    PipelineBackend::<D>::start_pipeline(&mut self.gl33, framebuffer, pipeline_state)
+  }
+
+  unsafe fn end_pipeline(&mut self, framebuffer: &Self::FramebufferRepr) {
+    PipelineBackend::<D>::end_pipeline(&mut self.gl33, framebuffer)
+  }
+}
+
+unsafe impl<T> PipelineBuffer<T> for GL45
+where
+  T: Copy,
+{
+  type BoundBufferRepr = BoundBuffer;
+
+  unsafe fn bind_buffer(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    GL33::bind_buffer(pipeline, buffer)
+  }
+
+  unsafe fn bind_buffer_range(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    GL33::bind_buffer_range(pipeline, buffer, start, len)
+  }
+
+  unsafe fn buffer_binding(bound: &Self::BoundBufferRepr) -> u32 {
+    <GL33 as PipelineBuffer<T>>::buffer_binding(bound)
+  }
+
+  unsafe fn buffer_offset_alignment(&mut self) -> usize {
+    <GL33 as PipelineBuffer<T>>::buffer_offset_alignment(&mut self.gl33)
+  }
+}
+
+unsafe impl<T> PipelineStorageBuffer<T> for GL45
+where
+  T: Copy,
+{
+  type BoundStorageBufferRepr = BoundStorageBuffer;
+
+  unsafe fn bind_storage_buffer(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+  ) -> Result<Self::BoundStorageBufferRepr, PipelineError> {
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = state.binding_stack_mut();
+
+    let binding = bstack
+      .free_storage_buffer_bindings
+      .pop()
+      .unwrap_or_else(|| {
+        let binding = bstack.next_storage_buffer_binding;
+        bstack.next_storage_buffer_binding += 1;
+        binding
+      });
+
+    state.bind_storage_buffer_base(buffer.handle(), binding);
+
+    Ok(BoundStorageBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
+  unsafe fn storage_buffer_binding(bound: &Self::BoundStorageBufferRepr) -> u32 {
+    bound.binding
+  }
+}
+
+unsafe impl<D, P> PipelineTexture<D, P> for GL45
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  type BoundTextureRepr = BoundTexture<D, P>;
+
+  unsafe fn bind_texture(
+    pipeline: &Self::PipelineRepr,
+    texture: &Self::TextureRepr,
+  ) -> Result<Self::BoundTextureRepr, PipelineError>
+  where
+    D: Dimensionable,
+    P: Pixel,
+  {
+    GL33::bind_texture(pipeline, texture)
+  }
+
+  unsafe fn texture_binding(bound: &Self::BoundTextureRepr) -> u32 {
+    <GL33 as PipelineTexture<D, P>>::texture_binding(bound)
+  }
+}
+
+unsafe impl<V, I, W> TessGate<V, I, W, Interleaved> for GL45
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  unsafe fn render(
+    &mut self,
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) {
+    let _ = <Self as Tess<V, I, W, Interleaved>>::render(tess, start_index, vert_nb, inst_nb);
+  }
+}
+
+unsafe impl<V, I, W> TessGate<V, I, W, Deinterleaved> for GL45
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  unsafe fn render(
+    &mut self,
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) {
+    let _ = <Self as Tess<V, I, W, Deinterleaved>>::render(tess, start_index, vert_nb, inst_nb);
+  }
+}
+
+unsafe impl RenderGate for GL45 {
+  unsafe fn enter_render_state(&mut self, rdr_st: &RenderState) {
+    self.gl33.enter_render_state(rdr_st)
+  }
+}
+
+unsafe impl ShadingGate for GL45 {
+  unsafe fn apply_shader_program(&mut self, shader_program: &Self::ProgramRepr) {
+    self.gl33.apply_shader_program(shader_program)
+  }
+}
@@ -0,0 +1,13 @@
+use gl::types::*;
+
+use crate::gl33::pixel::opengl_pixel_format;
+use luminance::pixel::PixelFormat;
+
+// OpenGL format, internal sized-format and type.
+//
+// Immutable texture storage requires sized internal formats, which rules out the 32-bit
+// normalized formats OpenGL 3.3 stores with unsized internal formats.
+pub(crate) fn gl45_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GLenum)> {
+  opengl_pixel_format(pf)
+    .filter(|&(_, iformat, _)| !matches!(iformat, gl::RED | gl::RG | gl::RGB | gl::RGBA))
+}
@@ -0,0 +1,31 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::query::{opengl_query_target, Query};
+use crate::gl33::GL33;
+use crate::gl45::GL45;
+use luminance::backend::query::Query as QueryBackend;
+use luminance::query::{QueryError, QueryType};
+
+unsafe impl QueryBackend for GL45 {
+  type QueryRepr = Query;
+
+  unsafe fn new_query(&mut self, ty: QueryType) -> Result<Self::QueryRepr, QueryError> {
+    let mut handle: GLuint = 0;
+    gl::CreateQueries(opengl_query_target(ty), 1, &mut handle);
+
+    Ok(Query::from_handle(handle, ty))
+  }
+
+  unsafe fn begin_query(&mut self, query: &mut Self::QueryRepr) -> Result<(), QueryError> {
+    self.gl33.begin_query(query)
+  }
+
+  unsafe fn end_query(&mut self, query: &mut Self::QueryRepr) {
+    self.gl33.end_query(query)
+  }
+
+  unsafe fn query_result(query: &mut Self::QueryRepr) -> Result<Option<u64>, QueryError> {
+    GL33::query_result(query)
+  }
+}
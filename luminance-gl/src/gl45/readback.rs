@@ -0,0 +1,37 @@
+use gl;
+use gl::types::*;
+use std::mem;
+use std::ptr;
+
+use crate::gl33::readback::Readback;
+use crate::gl33::GL33;
+use crate::gl45::GL45;
+use luminance::backend::readback::Readback as ReadbackBackend;
+use luminance::readback::ReadbackError;
+
+/// Create a staging buffer able to hold `len` items, with immutable storage.
+pub(crate) unsafe fn new_named_staging_buffer<T>(len: usize) -> GLuint {
+  let mut handle: GLuint = 0;
+  gl::CreateBuffers(1, &mut handle);
+
+  // empty storage is an error, so empty staging buffers don’t get any
+  let bytes = len * mem::size_of::<T>();
+  if bytes > 0 {
+    gl::NamedBufferStorage(handle, bytes as GLsizeiptr, ptr::null(), gl::MAP_READ_BIT);
+  }
+
+  handle
+}
+
+unsafe impl<T> ReadbackBackend<T> for GL45
+where
+  T: Copy,
+{
+  type ReadbackRepr = Readback<T>;
+
+  unsafe fn readback_result(
+    readback: &mut Self::ReadbackRepr,
+  ) -> Result<Option<Vec<T>>, ReadbackError> {
+    GL33::readback_result(readback)
+  }
+}
@@ -0,0 +1,60 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::renderbuffer::{renderbuffer_format, Renderbuffer};
+use crate::gl45::pixel::gl45_pixel_format;
+use crate::gl45::GL45;
+use luminance::backend::renderbuffer::{Renderbuffer as RenderbufferBackend, RenderbufferBase};
+use luminance::framebuffer::FramebufferError;
+use luminance::pixel::Pixel;
+
+unsafe impl RenderbufferBase for GL45 {
+  type RenderbufferRepr = Renderbuffer;
+}
+
+unsafe impl<P> RenderbufferBackend<P> for GL45
+where
+  P: Pixel,
+{
+  unsafe fn new_renderbuffer(
+    &mut self,
+    [width, height]: [u32; 2],
+  ) -> Result<Self::RenderbufferRepr, FramebufferError> {
+    let iformat = renderbuffer_format(gl45_pixel_format(P::pixel_format()))?;
+    let mut handle: GLuint = 0;
+
+    gl::CreateRenderbuffers(1, &mut handle);
+    gl::NamedRenderbufferStorage(handle, iformat, width as GLsizei, height as GLsizei);
+
+    Ok(Renderbuffer { handle })
+  }
+
+  unsafe fn attach_color_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    gl::NamedFramebufferRenderbuffer(
+      framebuffer.handle,
+      gl::COLOR_ATTACHMENT0 + attachment_index as GLenum,
+      gl::RENDERBUFFER,
+      renderbuffer.handle,
+    );
+
+    Ok(())
+  }
+
+  unsafe fn attach_depth_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+  ) -> Result<(), FramebufferError> {
+    gl::NamedFramebufferRenderbuffer(
+      framebuffer.handle,
+      framebuffer.depth_attachment,
+      gl::RENDERBUFFER,
+      renderbuffer.handle,
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,111 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::shader::{impl_Uniformable, impl_Uniformables, Program, Stage, UniformBuilder};
+use crate::gl33::GL33;
+use crate::gl45::GL45;
+use luminance::backend::shader::{Shader, Uniformable};
+use luminance::pipeline::{BufferBinding, StorageBufferBinding, TextureBinding};
+use luminance::pixel::{SamplerType, Type as PixelType};
+use luminance::shader::{
+  ProgramError, StageError, StageType, TessellationStages, Uniform, UniformType, UniformWarning,
+  VertexAttribWarning,
+};
+use luminance::texture::{Dim, Dimensionable};
+use luminance::vertex::Semantics;
+
+unsafe impl Shader for GL45 {
+  type StageRepr = Stage;
+
+  type ProgramRepr = Program;
+
+  type UniformBuilderRepr = UniformBuilder;
+
+  unsafe fn new_stage(&mut self, ty: StageType, src: &str) -> Result<Self::StageRepr, StageError> {
+    Stage::new(ty, &glsl_pragma_src(src))
+  }
+
+  unsafe fn new_program(
+    &mut self,
+    vertex: &Self::StageRepr,
+    tess: Option<TessellationStages<Self::StageRepr>>,
+    geometry: Option<&Self::StageRepr>,
+    fragment: &Self::StageRepr,
+  ) -> Result<Self::ProgramRepr, ProgramError> {
+    self.gl33.new_program(vertex, tess, geometry, fragment)
+  }
+
+  unsafe fn apply_semantics<Sem>(
+    program: &mut Self::ProgramRepr,
+  ) -> Result<Vec<VertexAttribWarning>, ProgramError>
+  where
+    Sem: Semantics,
+  {
+    GL33::apply_semantics::<Sem>(program)
+  }
+
+  unsafe fn new_uniform_builder(
+    program: &mut Self::ProgramRepr,
+  ) -> Result<Self::UniformBuilderRepr, ProgramError> {
+    GL33::new_uniform_builder(program)
+  }
+
+  unsafe fn ask_uniform<T>(
+    uniform_builder: &mut Self::UniformBuilderRepr,
+    name: &str,
+  ) -> Result<Uniform<T>, UniformWarning>
+  where
+    T: Uniformable<Self>,
+  {
+    uniform_builder.ask(name, T::ty())
+  }
+
+  unsafe fn unbound<T>(_: &mut Self::UniformBuilderRepr) -> Uniform<T>
+  where
+    T: Uniformable<Self>,
+  {
+    Uniform::new(-1)
+  }
+}
+
+const GLSL_PRAGMA: &str = "#version 450 core\n";
+
+fn glsl_pragma_src(src: &str) -> String {
+  let mut pragma = String::from(GLSL_PRAGMA);
+  pragma.push_str(src);
+  pragma
+}
+
+impl_Uniformables!(GL45);
+
+unsafe impl<T> Uniformable<GL45> for StorageBufferBinding<T> {
+  unsafe fn ty() -> UniformType {
+    UniformType::StorageBufferBinding
+  }
+
+  unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+    gl::ShaderStorageBlockBinding(
+      program.handle,
+      uniform.index() as GLuint,
+      self.binding() as GLuint,
+    )
+  }
+}
+
+impl_Uniformable!(GL45, f64, Double, Uniform1d);
+impl_Uniformable!(GL45, [f64; 2], DVec2, Uniform2dv);
+impl_Uniformable!(GL45, [f64; 3], DVec3, Uniform3dv);
+impl_Uniformable!(GL45, [f64; 4], DVec4, Uniform4dv);
+impl_Uniformable!(GL45, &[f64], Double, Uniform1dv);
+impl_Uniformable!(GL45, &[[f64; 2]], DVec2, Uniform2dv);
+impl_Uniformable!(GL45, &[[f64; 3]], DVec3, Uniform3dv);
+impl_Uniformable!(GL45, &[[f64; 4]], DVec4, Uniform4dv);
+
+impl_Uniformable!(GL45, mat [[f64; 2]; 2], DM22, UniformMatrix2dv);
+impl_Uniformable!(GL45, mat & [[[f64; 2]; 2]], DM22, UniformMatrix2dv);
+
+impl_Uniformable!(GL45, mat [[f64; 3]; 3], DM33, UniformMatrix3dv);
+impl_Uniformable!(GL45, mat & [[[f64; 3]; 3]], DM33, UniformMatrix3dv);
+
+impl_Uniformable!(GL45, mat [[f64; 4]; 4], DM44, UniformMatrix4dv);
+impl_Uniformable!(GL45, mat & [[[f64; 4]; 4]], DM44, UniformMatrix4dv);
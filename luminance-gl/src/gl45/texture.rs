@@ -0,0 +1,543 @@
+use gl;
+use gl::types::*;
+use luminance::backend::texture::{
+  Texture as TextureBackend, TextureBase, TextureCompressed as TextureCompressedBackend,
+  TextureCompression, TextureReadback as TextureReadbackBackend,
+};
+use luminance::pixel::{CompressedPixel, CompressionFamily, Pixel, PixelFormat};
+use luminance::texture::{Dim, Dimensionable, GenMipmaps, Sampler, TextureError};
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+
+use crate::gl33::pixel::opengl_compressed_format;
+use crate::gl33::readback::Readback;
+use crate::gl33::texture::{
+  apply_sampler_with, compressed_level_size, opengl_target, set_pack_alignment,
+  set_texture_levels_with, set_unpack_alignment, Texture,
+};
+use crate::gl33::GL33;
+use crate::gl45::pixel::gl45_pixel_format;
+use crate::gl45::readback::new_named_staging_buffer;
+use crate::gl45::GL45;
+
+unsafe impl TextureBase for GL45 {
+  type TextureRepr = Texture;
+}
+
+unsafe impl<D, P> TextureBackend<D, P> for GL45
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  unsafe fn new_texture(
+    &mut self,
+    size: D::Size,
+    mipmaps: usize,
+    sampler: Sampler,
+  ) -> Result<Self::TextureRepr, TextureError> {
+    let mipmaps = mipmaps + 1; // + 1 prevent having 0 mipmaps
+    let target = opengl_target(D::dim());
+    let pf = P::pixel_format();
+
+    let iformat = match pf.format.compression() {
+      Some(compression) => match opengl_compressed_format(pf) {
+        Some(iformat) if self.supports_compression(compression.family()) => iformat,
+        _ => return Err(TextureError::unsupported_pixel_format(pf)),
+      },
+
+      None => match gl45_pixel_format(pf) {
+        Some((_, iformat, _)) => iformat,
+        None => return Err(TextureError::unsupported_pixel_format(pf)),
+      },
+    };
+
+    let mut handle: GLuint = 0;
+    gl::CreateTextures(target, 1, &mut handle);
+
+    set_texture_levels_with(mipmaps, &sampler, |param, value| {
+      gl::TextureParameteri(handle, param, value)
+    });
+    apply_sampler_with(
+      &mut self.gl33.state.borrow_mut(),
+      D::dim(),
+      sampler,
+      |param, value| gl::TextureParameteri(handle, param, value),
+      |param, values| gl::TextureParameterfv(handle, param, values.as_ptr()),
+    );
+    create_texture_storage::<D>(handle, size, mipmaps, iformat);
+
+    let texture = Texture {
+      handle,
+      target,
+      mipmaps,
+      state: self.gl33.state.clone(),
+    };
+
+    Ok(texture)
+  }
+
+  unsafe fn mipmaps(texture: &Self::TextureRepr) -> usize {
+    <GL33 as TextureBackend<D, P>>::mipmaps(texture)
+  }
+
+  unsafe fn clear_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    let pf = P::pixel_format();
+    let (format, _, encoding) =
+      gl45_pixel_format(pf).ok_or_else(|| TextureError::unsupported_pixel_format(pf))?;
+
+    let ([x, y, z], [w, h, d]) = region::<D>(offset, size);
+
+    gl::ClearTexSubImage(
+      texture.handle,
+      0,
+      x,
+      y,
+      z,
+      w,
+      h,
+      d,
+      format,
+      encoding,
+      &pixel as *const P::Encoding as *const c_void,
+    );
+
+    if gen_mipmaps == GenMipmaps::Yes {
+      gl::GenerateTextureMipmap(texture.handle);
+    }
+
+    Ok(())
+  }
+
+  unsafe fn clear(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::clear_part(texture, gen_mipmaps, D::ZERO_OFFSET, size, pixel)
+  }
+
+  unsafe fn upload_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, P, P::Encoding>(texture.handle, 0, offset, size, texels)?;
+
+    if gen_mipmaps == GenMipmaps::Yes {
+      gl::GenerateTextureMipmap(texture.handle);
+    }
+
+    Ok(())
+  }
+
+  unsafe fn upload(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::upload_part(texture, gen_mipmaps, D::ZERO_OFFSET, size, texels)
+  }
+
+  unsafe fn upload_part_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, P, P::RawEncoding>(texture.handle, 0, offset, size, texels)?;
+
+    if gen_mipmaps == GenMipmaps::Yes {
+      gl::GenerateTextureMipmap(texture.handle);
+    }
+
+    Ok(())
+  }
+
+  unsafe fn upload_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::upload_part_raw(
+      texture,
+      gen_mipmaps,
+      D::ZERO_OFFSET,
+      size,
+      texels,
+    )
+  }
+
+  unsafe fn get_raw_texels(
+    texture: &Self::TextureRepr,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let pf = P::pixel_format();
+    let len = prepare_get_texture_image(pf, base_extent::<D>(size))?;
+    let mut texels = vec![Default::default(); len];
+
+    get_texture_image(
+      texture.handle,
+      pf,
+      mem::size_of_val(texels.as_slice()),
+      texels.as_mut_ptr() as *mut c_void,
+    );
+
+    Ok(texels)
+  }
+
+  unsafe fn copy_from(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    src: &Self::TextureRepr,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError> {
+    let ([src_x, src_y, src_z], [w, h, d]) = region::<D>(src_offset, size);
+    let ([dst_x, dst_y, dst_z], _) = region::<D>(dst_offset, size);
+
+    gl::CopyImageSubData(
+      src.handle,
+      src.target,
+      0,
+      src_x,
+      src_y,
+      src_z,
+      texture.handle,
+      texture.target,
+      0,
+      dst_x,
+      dst_y,
+      dst_z,
+      w,
+      h,
+      d,
+    );
+
+    if gen_mipmaps == GenMipmaps::Yes {
+      gl::GenerateTextureMipmap(texture.handle);
+    }
+
+    Ok(())
+  }
+
+  unsafe fn upload_level_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, P, P::Encoding>(texture.handle, level, offset, size, texels)
+  }
+
+  unsafe fn upload_level_part_raw(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, P, P::RawEncoding>(texture.handle, level, offset, size, texels)
+  }
+
+  unsafe fn get_level_part_raw_texels(
+    texture: &Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let pf = P::pixel_format();
+    let ([x, y, z], [w, h, d]) = region::<D>(offset, size);
+    let len = prepare_get_texture_image(pf, [w as u32, h as u32, d as u32])?;
+    let mut texels = vec![Default::default(); len];
+    let (format, _, ty) = gl45_pixel_format(pf).unwrap();
+
+    gl::GetTextureSubImage(
+      texture.handle,
+      level as GLint,
+      x,
+      y,
+      z,
+      w,
+      h,
+      d,
+      format,
+      ty,
+      mem::size_of_val(texels.as_slice()) as GLsizei,
+      texels.as_mut_ptr() as *mut c_void,
+    );
+
+    Ok(texels)
+  }
+
+  unsafe fn generate_mipmaps(texture: &mut Self::TextureRepr) -> Result<(), TextureError> {
+    gl::GenerateTextureMipmap(texture.handle);
+    Ok(())
+  }
+}
+
+unsafe impl<D, P> TextureReadbackBackend<D, P> for GL45
+where
+  D: Dimensionable,
+  P: Pixel,
+  P::RawEncoding: Copy,
+{
+  unsafe fn get_raw_texels_async(
+    texture: &Self::TextureRepr,
+    size: D::Size,
+  ) -> Result<Self::ReadbackRepr, TextureError> {
+    let pf = P::pixel_format();
+    let len = prepare_get_texture_image(pf, base_extent::<D>(size))?;
+    let staging = new_named_staging_buffer::<P::RawEncoding>(len);
+
+    // the texels are packed to the staging buffer, the pointer being an offset in it
+    gl::BindBuffer(gl::PIXEL_PACK_BUFFER, staging);
+    get_texture_image(
+      texture.handle,
+      pf,
+      len * mem::size_of::<P::RawEncoding>(),
+      ptr::null_mut(),
+    );
+    gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+
+    Readback::from_handle(staging, len)
+      .map_err(|_| TextureError::cannot_retrieve_texels("cannot create readback fence"))
+  }
+}
+
+unsafe impl TextureCompression for GL45 {
+  unsafe fn supports_compression(&mut self, family: CompressionFamily) -> bool {
+    self.gl33.supports_compression(family)
+  }
+}
+
+unsafe impl<D, P> TextureCompressedBackend<D, P> for GL45
+where
+  D: Dimensionable,
+  P: CompressedPixel,
+{
+  unsafe fn upload_compressed_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    let (offset, size) = region::<D>(offset, size);
+    upload_compressed_blocks::<D>(
+      texture.handle,
+      P::pixel_format(),
+      level,
+      offset,
+      size,
+      blocks,
+    )
+  }
+
+  unsafe fn upload_compressed(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    let [w, h] = compressed_level_size::<D>(size, level);
+    let size = [w as GLsizei, h as GLsizei, D::depth(size) as GLsizei];
+    upload_compressed_blocks::<D>(
+      texture.handle,
+      P::pixel_format(),
+      level,
+      [0; 3],
+      size,
+      blocks,
+    )
+  }
+}
+
+/// Set the packing alignment to read a region of size `[w, h, d]`, and return the number of texels
+/// — in raw encoding — it holds.
+fn prepare_get_texture_image(pf: PixelFormat, [w, h, d]: [u32; 3]) -> Result<usize, TextureError> {
+  if gl45_pixel_format(pf).is_none() {
+    return Err(TextureError::unsupported_pixel_format(pf));
+  }
+
+  // set the packing alignment based on the number of bytes to skip
+  let skip_bytes = (pf.format.size() * w as usize) % 8;
+  set_pack_alignment(skip_bytes);
+
+  Ok((w * h * d) as usize * pf.canals_len())
+}
+
+/// Size of the base level of a texture of size `size`, as a three-dimensional vector.
+///
+/// Every layer — or face, for cubemaps — is included.
+fn base_extent<D>(size: D::Size) -> [u32; 3]
+where
+  D: Dimensionable,
+{
+  [D::width(size), D::height(size), D::depth(size)]
+}
+
+/// Read the first level of the texture `handle` to `texels` — either client memory or an offset in
+/// the bound pixel pack buffer — which can hold `bytes` bytes.
+unsafe fn get_texture_image(handle: GLuint, pf: PixelFormat, bytes: usize, texels: *mut c_void) {
+  let (format, _, ty) = gl45_pixel_format(pf).unwrap();
+  gl::GetTextureImage(handle, 0, format, ty, bytes as GLsizei, texels);
+}
+
+unsafe fn create_texture_storage<D>(handle: GLuint, size: D::Size, mipmaps: usize, iformat: GLenum)
+where
+  D: Dimensionable,
+{
+  let levels = mipmaps as GLsizei;
+  let w = D::width(size) as GLsizei;
+  let h = D::height(size) as GLsizei;
+  let d = D::depth(size) as GLsizei;
+
+  match D::dim() {
+    Dim::Dim1 => gl::TextureStorage1D(handle, levels, iformat, w),
+
+    // cubemaps get their six faces from a single 2D storage
+    Dim::Dim2 | Dim::Dim1Array | Dim::Cubemap => {
+      gl::TextureStorage2D(handle, levels, iformat, w, h)
+    }
+
+    Dim::Dim3 | Dim::Dim2Array => gl::TextureStorage3D(handle, levels, iformat, w, h, d),
+  }
+}
+
+// Upload texels into the mipmap `level` of the texture’s memory. Becareful of the type of texels
+// you send down.
+unsafe fn upload_texels<D, P, T>(
+  handle: GLuint,
+  level: usize,
+  off: D::Offset,
+  size: D::Size,
+  texels: &[T],
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  // number of bytes in the input texels argument
+  let input_bytes = mem::size_of_val(texels);
+  let pf = P::pixel_format();
+  let pf_size = pf.format.size();
+  let expected_bytes = D::count(size) * pf_size;
+
+  if input_bytes < expected_bytes {
+    // potential segfault / overflow; abort
+    return Err(TextureError::not_enough_pixels(expected_bytes, input_bytes));
+  }
+
+  let (format, _, encoding) =
+    gl45_pixel_format(pf).ok_or_else(|| TextureError::unsupported_pixel_format(pf))?;
+
+  // set the pixel row alignment to the required value for uploading data according to the width
+  // of the texture and the size of a single pixel
+  let skip_bytes = (D::width(size) as usize * pf_size) % 8;
+  set_unpack_alignment(skip_bytes);
+
+  let ([x, y, z], [w, h, d]) = region::<D>(off, size);
+  let level = level as GLint;
+  let ptr = texels.as_ptr() as *const c_void;
+
+  match D::dim() {
+    Dim::Dim1 => gl::TextureSubImage1D(handle, level, x, w, format, encoding, ptr),
+
+    Dim::Dim2 | Dim::Dim1Array => {
+      gl::TextureSubImage2D(handle, level, x, y, w, h, format, encoding, ptr)
+    }
+
+    // faces are layers of the cubemap, in the same order as Dimensionable::z_offset
+    Dim::Dim3 | Dim::Dim2Array | Dim::Cubemap => {
+      gl::TextureSubImage3D(handle, level, x, y, z, w, h, d, format, encoding, ptr)
+    }
+  }
+
+  Ok(())
+}
+
+// Upload compressed blocks to the region of size `[w, h, d]` at `[x, y, z]` in the mipmap `level`
+// of the texture.
+unsafe fn upload_compressed_blocks<D>(
+  handle: GLuint,
+  pf: PixelFormat,
+  level: usize,
+  [x, y, z]: [GLint; 3],
+  [w, h, d]: [GLsizei; 3],
+  blocks: &[u8],
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let format = opengl_compressed_format(pf).ok_or(TextureError::unsupported_pixel_format(pf))?;
+  let level = level as GLint;
+  let len = blocks.len() as GLsizei;
+  let ptr = blocks.as_ptr() as *const c_void;
+
+  match D::dim() {
+    Dim::Dim2 => gl::CompressedTextureSubImage2D(handle, level, x, y, w, h, format, len, ptr),
+
+    // faces are layers of the cubemap, in the same order as Dimensionable::z_offset
+    Dim::Dim2Array | Dim::Cubemap => {
+      gl::CompressedTextureSubImage3D(handle, level, x, y, z, w, h, d, format, len, ptr)
+    }
+
+    _ => return Err(TextureError::unsupported_pixel_format(pf)),
+  }
+
+  Ok(())
+}
+
+// Offset and size of the region described by `offset` and `size`, as three-dimensional vectors.
+//
+// Cubemap regions only cover the face they are offset to.
+fn region<D>(offset: D::Offset, size: D::Size) -> ([GLint; 3], [GLsizei; 3])
+where
+  D: Dimensionable,
+{
+  let x = D::x_offset(offset) as GLint;
+  let w = D::width(size) as GLsizei;
+
+  match D::dim() {
+    Dim::Dim1 => ([x, 0, 0], [w, 1, 1]),
+
+    Dim::Dim2 | Dim::Dim1Array => {
+      let y = D::y_offset(offset) as GLint;
+      ([x, y, 0], [w, D::height(size) as GLsizei, 1])
+    }
+
+    Dim::Dim3 | Dim::Dim2Array => {
+      let y = D::y_offset(offset) as GLint;
+      let z = D::z_offset(offset) as GLint;
+      let size = [w, D::height(size) as GLsizei, D::depth(size) as GLsizei];
+      ([x, y, z], size)
+    }
+
+    Dim::Cubemap => {
+      let y = D::y_offset(offset) as GLint;
+      let z = D::z_offset(offset) as GLint;
+      ([x, y, z], [w, w, 1])
+    }
+  }
+}
@@ -0,0 +1,61 @@
+//! OpenGL ES 3.0 backend.
+//!
+//! OpenGL ES 3.0 is, for what luminance needs, mostly a subset of OpenGL 3.3. This backend then
+//! reuses the [`GL33`] representations and drives the same [`GLState`]; only what differs is
+//! implemented here:
+//!
+//! - Shader stages are prefixed with `#version 300 es` and default precision qualifiers.
+//!   Tessellation and geometry shaders are not supported.
+//! - Patch primitives are not supported and the primitive restart index must be the maximum value
+//!   of the index type.
+//! - 1D textures are not supported, and neither are 16-bit and 32-bit normalized and signed sRGB
+//!   pixel formats.
+//! - Only 2D textures can be attached to framebuffers.
+//! - Texels are read back with a framebuffer, as there’s no way to get a texture image directly.
+//! - There’s no sRGB switch: [`PipelineState::enable_srgb`] has no effect, writes to sRGB
+//!   attachments are always encoded.
+//!
+//! [`PipelineState::enable_srgb`]: luminance::pipeline::PipelineState::enable_srgb
+
+mod buffer;
+mod fence;
+mod framebuffer;
+mod pipeline;
+mod pixel;
+mod query;
+mod readback;
+mod renderbuffer;
+mod shader;
+mod tess;
+mod texture;
+
+use crate::gl33::state::GLApi;
+use crate::gl33::{GLState, StateQueryError, GL33};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The OpenGL ES 3.0 backend.
+#[derive(Debug)]
+pub struct GLES3 {
+  pub(crate) gl33: GL33,
+}
+
+impl GLES3 {
+  /// Create the backend from the OpenGL ES context current on the calling thread.
+  pub fn new() -> Result<Self, StateQueryError> {
+    GLState::new(GLApi::ES).map(|state| GLES3 {
+      gl33: GL33 {
+        state: Rc::new(RefCell::new(state)),
+      },
+    })
+  }
+
+  /// Access the underlying graphics state.
+  ///
+  /// # Safety
+  ///
+  /// Changing the OpenGL state without updating the [`GLState`] leads to undefined behavior.
+  pub unsafe fn state(&self) -> &Rc<RefCell<GLState>> {
+    self.gl33.state()
+  }
+}
@@ -0,0 +1,132 @@
+//! OpenGL ES 3.0 buffer implementation.
+
+use crate::gl33::buffer::{Buffer, BufferSlice, BufferSliceMut};
+use crate::gl33::GL33;
+use crate::gles3::GLES3;
+use luminance::backend::buffer::{
+  Buffer as BufferBackend, BufferReadback as BufferReadbackBackend,
+  BufferSlice as BufferSliceBackend,
+};
+use luminance::buffer::{BufferError, BufferUsage, PreserveContent};
+
+unsafe impl<T> BufferBackend<T> for GLES3
+where
+  T: Copy,
+{
+  type BufferRepr = Buffer<T>;
+
+  unsafe fn new_buffer(
+    &mut self,
+    len: usize,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>
+  where
+    T: Default,
+  {
+    BufferBackend::<T>::new_buffer(&mut self.gl33, len, usage)
+  }
+
+  unsafe fn len(buffer: &Self::BufferRepr) -> usize {
+    <GL33 as BufferBackend<T>>::len(buffer)
+  }
+
+  unsafe fn from_vec(
+    &mut self,
+    vec: Vec<T>,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    self.gl33.from_vec(vec, usage)
+  }
+
+  unsafe fn repeat(
+    &mut self,
+    len: usize,
+    value: T,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    self.gl33.repeat(len, value, usage)
+  }
+
+  unsafe fn at(buffer: &Self::BufferRepr, i: usize) -> Option<T> {
+    GL33::at(buffer, i)
+  }
+
+  unsafe fn whole(buffer: &Self::BufferRepr) -> Vec<T> {
+    GL33::whole(buffer)
+  }
+
+  unsafe fn set(buffer: &mut Self::BufferRepr, i: usize, x: T) -> Result<(), BufferError> {
+    GL33::set(buffer, i, x)
+  }
+
+  unsafe fn write_whole(buffer: &mut Self::BufferRepr, values: &[T]) -> Result<(), BufferError> {
+    GL33::write_whole(buffer, values)
+  }
+
+  unsafe fn write_range(
+    buffer: &mut Self::BufferRepr,
+    offset: usize,
+    values: &[T],
+  ) -> Result<(), BufferError> {
+    GL33::write_range(buffer, offset, values)
+  }
+
+  unsafe fn read_range(
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Vec<T>, BufferError> {
+    GL33::read_range(buffer, start, len)
+  }
+
+  unsafe fn copy_from(
+    buffer: &mut Self::BufferRepr,
+    src: &Self::BufferRepr,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  ) -> Result<(), BufferError> {
+    GL33::copy_from(buffer, src, src_start, len, dst_offset)
+  }
+
+  unsafe fn clear(buffer: &mut Self::BufferRepr, x: T) -> Result<(), BufferError> {
+    GL33::clear(buffer, x)
+  }
+
+  unsafe fn resize(
+    buffer: &mut Self::BufferRepr,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError> {
+    GL33::resize(buffer, len, value, preserve)
+  }
+}
+
+unsafe impl<T> BufferReadbackBackend<T> for GLES3
+where
+  T: Copy,
+{
+  unsafe fn whole_async(buffer: &Self::BufferRepr) -> Result<Self::ReadbackRepr, BufferError> {
+    GL33::whole_async(buffer)
+  }
+}
+
+unsafe impl<T> BufferSliceBackend<T> for GLES3
+where
+  T: Copy,
+{
+  type SliceRepr = BufferSlice<T>;
+
+  type SliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn slice_buffer(buffer: &Self::BufferRepr) -> Result<Self::SliceRepr, BufferError> {
+    GL33::slice_buffer(buffer)
+  }
+
+  unsafe fn slice_buffer_mut(
+    buffer: &mut Self::BufferRepr,
+  ) -> Result<Self::SliceMutRepr, BufferError> {
+    GL33::slice_buffer_mut(buffer)
+  }
+}
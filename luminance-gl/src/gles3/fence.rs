@@ -0,0 +1,17 @@
+use crate::gl33::fence::Fence;
+use crate::gl33::GL33;
+use crate::gles3::GLES3;
+use luminance::backend::fence::Fence as FenceBackend;
+use luminance::fence::FenceError;
+
+unsafe impl FenceBackend for GLES3 {
+  type FenceRepr = Fence;
+
+  unsafe fn new_fence(&mut self) -> Result<Self::FenceRepr, FenceError> {
+    self.gl33.new_fence()
+  }
+
+  unsafe fn fence_signaled(fence: &mut Self::FenceRepr) -> bool {
+    GL33::fence_signaled(fence)
+  }
+}
@@ -0,0 +1,132 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::framebuffer::{new_multisample_framebuffer, Framebuffer};
+use crate::gl33::texture::Texture;
+use crate::gl33::GL33;
+use crate::gles3::GLES3;
+use luminance::backend::color_slot::ColorSlot;
+use luminance::backend::depth_slot::DepthSlot;
+use luminance::backend::framebuffer::{
+  Framebuffer as FramebufferBackend, FramebufferBackBuffer, FramebufferBlit, FramebufferMultisample,
+};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError};
+use luminance::texture::{Dim2, Dimensionable, Sampler};
+
+unsafe impl<D> FramebufferBackend<D> for GLES3
+where
+  D: Dimensionable,
+{
+  type FramebufferRepr = Framebuffer<D>;
+
+  unsafe fn new_framebuffer<CS, DS>(
+    &mut self,
+    size: D::Size,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, D>,
+    DS: DepthSlot<Self, D>,
+  {
+    let depth_format = DS::depth_format();
+    let framebuffer = Framebuffer::new(
+      &self.gl33.state,
+      size,
+      &CS::color_formats(),
+      depth_format,
+      depth_format.is_none(),
+    );
+
+    Ok(framebuffer)
+  }
+
+  unsafe fn attach_color_texture(
+    _: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    attach_texture(texture, gl::COLOR_ATTACHMENT0 + attachment_index as GLenum)
+  }
+
+  unsafe fn attach_depth_texture(
+    framebuffer: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+  ) -> Result<(), FramebufferError> {
+    attach_texture(texture, framebuffer.depth_attachment)
+  }
+
+  unsafe fn validate_framebuffer(
+    framebuffer: Self::FramebufferRepr,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    <GL33 as FramebufferBackend<D>>::validate_framebuffer(framebuffer)
+  }
+
+  unsafe fn framebuffer_size(framebuffer: &Self::FramebufferRepr) -> D::Size {
+    <GL33 as FramebufferBackend<D>>::framebuffer_size(framebuffer)
+  }
+}
+
+// There’s no glFramebufferTexture (layered attachments) in OpenGL ES 3.0, so only 2D textures can
+// be attached.
+unsafe fn attach_texture(texture: &Texture, attachment: GLenum) -> Result<(), FramebufferError> {
+  match texture.target {
+    gl::TEXTURE_2D => {
+      gl::FramebufferTexture2D(
+        gl::FRAMEBUFFER,
+        attachment,
+        texture.target,
+        texture.handle,
+        0,
+      );
+
+      Ok(())
+    }
+
+    _ => Err(FramebufferError::unsupported_attachment()),
+  }
+}
+
+unsafe impl FramebufferBackBuffer for GLES3 {
+  unsafe fn back_buffer(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    self.gl33.back_buffer(size)
+  }
+}
+
+unsafe impl FramebufferBlit for GLES3 {
+  unsafe fn blit_framebuffer(
+    src: &Self::FramebufferRepr,
+    dst: &mut Self::FramebufferRepr,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError> {
+    GL33::blit_framebuffer(src, dst, src_rect, dst_rect, mask, filter)
+  }
+}
+
+unsafe impl FramebufferMultisample for GLES3 {
+  unsafe fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, Dim2>,
+    DS: DepthSlot<Self, Dim2>,
+  {
+    new_multisample_framebuffer(
+      &self.gl33.state,
+      size,
+      samples,
+      &CS::color_formats(),
+      DS::depth_format(),
+    )
+  }
+}
@@ -0,0 +1,28 @@
+use gl::types::*;
+
+use crate::gl33::pixel::opengl_pixel_format;
+use luminance::pixel::{Format, PixelFormat, Type};
+
+// OpenGL format, internal sized-format and type.
+//
+// OpenGL ES 3.0 supports the same formats as OpenGL 3.3, but normalized formats with more than 8
+// bits per channel and signed sRGB formats.
+pub(crate) fn gles3_pixel_format(pf: PixelFormat) -> Option<(GLenum, GLenum, GLenum)> {
+  let wide_normalized = match (pf.format, pf.encoding) {
+    // packed formats’ size doesn’t tell the size of their channels
+    (format, _) if format.is_packed() => false,
+    (_, Type::NormIntegral | Type::NormUnsigned) => pf.format.size() > pf.canals_len(),
+    _ => false,
+  };
+
+  let signed_srgb = matches!(
+    (pf.format, pf.encoding),
+    (Format::SRGB(..), Type::NormIntegral) | (Format::SRGBA(..), Type::NormIntegral)
+  );
+
+  if wide_normalized || signed_srgb {
+    None
+  } else {
+    opengl_pixel_format(pf)
+  }
+}
@@ -0,0 +1,40 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::query::Query;
+use crate::gles3::GLES3;
+use luminance::backend::query::Query as QueryBackend;
+use luminance::query::{QueryError, QueryType};
+
+unsafe impl QueryBackend for GLES3 {
+  type QueryRepr = Query;
+
+  unsafe fn new_query(&mut self, ty: QueryType) -> Result<Self::QueryRepr, QueryError> {
+    // timer queries are only available through extensions and primitive queries since
+    // OpenGL ES 3.2
+    match ty {
+      QueryType::AnySamplesPassed => self.gl33.new_query(ty),
+      _ => Err(QueryError::unsupported_type(ty)),
+    }
+  }
+
+  unsafe fn begin_query(&mut self, query: &mut Self::QueryRepr) -> Result<(), QueryError> {
+    self.gl33.begin_query(query)
+  }
+
+  unsafe fn end_query(&mut self, query: &mut Self::QueryRepr) {
+    self.gl33.end_query(query)
+  }
+
+  unsafe fn query_result(query: &mut Self::QueryRepr) -> Result<Option<u64>, QueryError> {
+    if !query.is_available() {
+      return Ok(None);
+    }
+
+    // OpenGL ES has no 64-bit query results
+    let mut result: GLuint = 0;
+    gl::GetQueryObjectuiv(query.handle, gl::QUERY_RESULT, &mut result);
+
+    Ok(Some(result.into()))
+  }
+}
@@ -0,0 +1,18 @@
+use crate::gl33::readback::Readback;
+use crate::gl33::GL33;
+use crate::gles3::GLES3;
+use luminance::backend::readback::Readback as ReadbackBackend;
+use luminance::readback::ReadbackError;
+
+unsafe impl<T> ReadbackBackend<T> for GLES3
+where
+  T: Copy,
+{
+  type ReadbackRepr = Readback<T>;
+
+  unsafe fn readback_result(
+    readback: &mut Self::ReadbackRepr,
+  ) -> Result<Option<Vec<T>>, ReadbackError> {
+    GL33::readback_result(readback)
+  }
+}
@@ -0,0 +1,43 @@
+use crate::gl33::renderbuffer::{renderbuffer_format, Renderbuffer};
+use crate::gl33::GL33;
+use crate::gles3::pixel::gles3_pixel_format;
+use crate::gles3::GLES3;
+use luminance::backend::renderbuffer::{Renderbuffer as RenderbufferBackend, RenderbufferBase};
+use luminance::framebuffer::FramebufferError;
+use luminance::pixel::Pixel;
+
+unsafe impl RenderbufferBase for GLES3 {
+  type RenderbufferRepr = Renderbuffer;
+}
+
+unsafe impl<P> RenderbufferBackend<P> for GLES3
+where
+  P: Pixel,
+{
+  unsafe fn new_renderbuffer(
+    &mut self,
+    size: [u32; 2],
+  ) -> Result<Self::RenderbufferRepr, FramebufferError> {
+    let iformat = renderbuffer_format(gles3_pixel_format(P::pixel_format()))?;
+    Ok(Renderbuffer::new(iformat, size))
+  }
+
+  unsafe fn attach_color_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    <GL33 as RenderbufferBackend<P>>::attach_color_renderbuffer(
+      framebuffer,
+      renderbuffer,
+      attachment_index,
+    )
+  }
+
+  unsafe fn attach_depth_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+  ) -> Result<(), FramebufferError> {
+    <GL33 as RenderbufferBackend<P>>::attach_depth_renderbuffer(framebuffer, renderbuffer)
+  }
+}
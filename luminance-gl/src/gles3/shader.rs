@@ -0,0 +1,100 @@
+use gl;
+use gl::types::*;
+
+use crate::gl33::shader::{impl_Uniformable, impl_Uniformables, Program, Stage, UniformBuilder};
+use crate::gl33::GL33;
+use crate::gles3::GLES3;
+use luminance::backend::shader::{Shader, Uniformable};
+use luminance::pipeline::{BufferBinding, TextureBinding};
+use luminance::pixel::{SamplerType, Type as PixelType};
+use luminance::shader::{
+  ProgramError, StageError, StageType, TessellationStages, Uniform, UniformType, UniformWarning,
+  VertexAttribWarning,
+};
+use luminance::texture::{Dim, Dimensionable};
+use luminance::vertex::Semantics;
+
+unsafe impl Shader for GLES3 {
+  type StageRepr = Stage;
+
+  type ProgramRepr = Program;
+
+  type UniformBuilderRepr = UniformBuilder;
+
+  unsafe fn new_stage(&mut self, ty: StageType, src: &str) -> Result<Self::StageRepr, StageError> {
+    match ty {
+      StageType::VertexShader | StageType::FragmentShader => Stage::new(ty, &glsl_pragma_src(src)),
+
+      _ => Err(StageError::unsupported_type(ty)),
+    }
+  }
+
+  unsafe fn new_program(
+    &mut self,
+    vertex: &Self::StageRepr,
+    tess: Option<TessellationStages<Self::StageRepr>>,
+    geometry: Option<&Self::StageRepr>,
+    fragment: &Self::StageRepr,
+  ) -> Result<Self::ProgramRepr, ProgramError> {
+    self.gl33.new_program(vertex, tess, geometry, fragment)
+  }
+
+  unsafe fn apply_semantics<Sem>(
+    program: &mut Self::ProgramRepr,
+  ) -> Result<Vec<VertexAttribWarning>, ProgramError>
+  where
+    Sem: Semantics,
+  {
+    GL33::apply_semantics::<Sem>(program)
+  }
+
+  unsafe fn new_uniform_builder(
+    program: &mut Self::ProgramRepr,
+  ) -> Result<Self::UniformBuilderRepr, ProgramError> {
+    GL33::new_uniform_builder(program)
+  }
+
+  unsafe fn ask_uniform<T>(
+    uniform_builder: &mut Self::UniformBuilderRepr,
+    name: &str,
+  ) -> Result<Uniform<T>, UniformWarning>
+  where
+    T: Uniformable<Self>,
+  {
+    uniform_builder.ask(name, T::ty())
+  }
+
+  unsafe fn unbound<T>(_: &mut Self::UniformBuilderRepr) -> Uniform<T>
+  where
+    T: Uniformable<Self>,
+  {
+    Uniform::new(-1)
+  }
+}
+
+// Default precision qualifiers are required for floats in fragment shaders and for every sampler
+// type but sampler2D and samplerCube.
+const GLSL_PRAGMA: &str = "#version 300 es\n\
+                           precision highp float;\n\
+                           precision highp int;\n\
+                           precision highp sampler3D;\n\
+                           precision highp sampler2DArray;\n\
+                           precision highp sampler2DShadow;\n\
+                           precision highp samplerCubeShadow;\n\
+                           precision highp sampler2DArrayShadow;\n\
+                           precision highp isampler2D;\n\
+                           precision highp isampler3D;\n\
+                           precision highp isamplerCube;\n\
+                           precision highp isampler2DArray;\n\
+                           precision highp usampler2D;\n\
+                           precision highp usampler3D;\n\
+                           precision highp usamplerCube;\n\
+                           precision highp usampler2DArray;\n";
+
+fn glsl_pragma_src(src: &str) -> String {
+  let mut pragma = String::from(GLSL_PRAGMA);
+  pragma.push_str(src);
+  pragma
+}
+
+impl_Uniformables!(GLES3);
@@ -0,0 +1,304 @@
+use crate::gl33::buffer::{BufferSlice, BufferSliceMut};
+use crate::gl33::tess::{DeinterleavedTess, InterleavedTess};
+use crate::gl33::GL33;
+use crate::gles3::GLES3;
+use luminance::backend::tess::{
+  IndexSlice as IndexSliceBackend, InstanceSlice as InstanceSliceBackend, Tess as TessBackend,
+  VertexSlice as VertexSliceBackend,
+};
+use luminance::buffer::PreserveContent;
+use luminance::tess::{
+  Deinterleaved, DeinterleavedData, Interleaved, Mode, TessError, TessIndex, TessIndexType,
+  TessMapError, TessParams, TessVertexData,
+};
+use luminance::vertex::Deinterleave;
+
+unsafe impl<V, I, W> TessBackend<V, I, W, Interleaved> for GLES3
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type TessRepr = InterleavedTess<V, I, W>;
+
+  unsafe fn build(
+    &mut self,
+    vertex_data: Option<V::Data>,
+    index_data: Vec<I>,
+    instance_data: Option<W::Data>,
+    params: TessParams<I>,
+  ) -> Result<Self::TessRepr, TessError> {
+    check_mode(params.mode)?;
+    check_restart_index(params.restart_index)?;
+
+    TessBackend::<V, I, W, Interleaved>::build(
+      &mut self.gl33,
+      vertex_data,
+      index_data,
+      instance_data,
+      params,
+    )
+  }
+
+  unsafe fn tess_vertices_nb(tess: &Self::TessRepr) -> usize {
+    <GL33 as TessBackend<V, I, W, Interleaved>>::tess_vertices_nb(tess)
+  }
+
+  unsafe fn tess_instances_nb(tess: &Self::TessRepr) -> usize {
+    <GL33 as TessBackend<V, I, W, Interleaved>>::tess_instances_nb(tess)
+  }
+
+  unsafe fn render(
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Interleaved>>::render(tess, start_index, vert_nb, inst_nb)
+  }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Interleaved>>::resize_vertices(tess, len, preserve)
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Interleaved>>::resize_indices(tess, len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Interleaved>>::resize_instances(tess, len, preserve)
+  }
+}
+
+unsafe impl<V, I, W> VertexSliceBackend<V, I, W, Interleaved, V> for GLES3
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type VertexSliceRepr = BufferSlice<V>;
+  type VertexSliceMutRepr = BufferSliceMut<V>;
+
+  unsafe fn vertices(tess: &mut Self::TessRepr) -> Result<Self::VertexSliceRepr, TessMapError> {
+    <GL33 as VertexSliceBackend<V, I, W, Interleaved, V>>::vertices(tess)
+  }
+
+  unsafe fn vertices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::VertexSliceMutRepr, TessMapError> {
+    <GL33 as VertexSliceBackend<V, I, W, Interleaved, V>>::vertices_mut(tess)
+  }
+}
+
+unsafe impl<V, I, W> IndexSliceBackend<V, I, W, Interleaved> for GLES3
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type IndexSliceRepr = BufferSlice<I>;
+  type IndexSliceMutRepr = BufferSliceMut<I>;
+
+  unsafe fn indices(tess: &mut Self::TessRepr) -> Result<Self::IndexSliceRepr, TessMapError> {
+    <GL33 as IndexSliceBackend<V, I, W, Interleaved>>::indices(tess)
+  }
+
+  unsafe fn indices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::IndexSliceMutRepr, TessMapError> {
+    <GL33 as IndexSliceBackend<V, I, W, Interleaved>>::indices_mut(tess)
+  }
+}
+
+unsafe impl<V, I, W> InstanceSliceBackend<V, I, W, Interleaved, W> for GLES3
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type InstanceSliceRepr = BufferSlice<W>;
+  type InstanceSliceMutRepr = BufferSliceMut<W>;
+
+  unsafe fn instances(tess: &mut Self::TessRepr) -> Result<Self::InstanceSliceRepr, TessMapError> {
+    <GL33 as InstanceSliceBackend<V, I, W, Interleaved, W>>::instances(tess)
+  }
+
+  unsafe fn instances_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::InstanceSliceMutRepr, TessMapError> {
+    <GL33 as InstanceSliceBackend<V, I, W, Interleaved, W>>::instances_mut(tess)
+  }
+}
+
+unsafe impl<V, I, W> TessBackend<V, I, W, Deinterleaved> for GLES3
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type TessRepr = DeinterleavedTess<V, I, W>;
+
+  unsafe fn build(
+    &mut self,
+    vertex_data: Option<V::Data>,
+    index_data: Vec<I>,
+    instance_data: Option<W::Data>,
+    params: TessParams<I>,
+  ) -> Result<Self::TessRepr, TessError> {
+    check_mode(params.mode)?;
+    check_restart_index(params.restart_index)?;
+
+    TessBackend::<V, I, W, Deinterleaved>::build(
+      &mut self.gl33,
+      vertex_data,
+      index_data,
+      instance_data,
+      params,
+    )
+  }
+
+  unsafe fn tess_vertices_nb(tess: &Self::TessRepr) -> usize {
+    <GL33 as TessBackend<V, I, W, Deinterleaved>>::tess_vertices_nb(tess)
+  }
+
+  unsafe fn tess_instances_nb(tess: &Self::TessRepr) -> usize {
+    <GL33 as TessBackend<V, I, W, Deinterleaved>>::tess_instances_nb(tess)
+  }
+
+  unsafe fn render(
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Deinterleaved>>::render(tess, start_index, vert_nb, inst_nb)
+  }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Deinterleaved>>::resize_vertices(tess, len, preserve)
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Deinterleaved>>::resize_indices(tess, len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    <GL33 as TessBackend<V, I, W, Deinterleaved>>::resize_instances(tess, len, preserve)
+  }
+}
+
+unsafe impl<V, I, W, T> VertexSliceBackend<V, I, W, Deinterleaved, T> for GLES3
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>> + Deinterleave<T>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type VertexSliceRepr = BufferSlice<T>;
+  type VertexSliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn vertices(tess: &mut Self::TessRepr) -> Result<Self::VertexSliceRepr, TessMapError> {
+    <GL33 as VertexSliceBackend<V, I, W, Deinterleaved, T>>::vertices(tess)
+  }
+
+  unsafe fn vertices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::VertexSliceMutRepr, TessMapError> {
+    <GL33 as VertexSliceBackend<V, I, W, Deinterleaved, T>>::vertices_mut(tess)
+  }
+}
+
+unsafe impl<V, I, W> IndexSliceBackend<V, I, W, Deinterleaved> for GLES3
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type IndexSliceRepr = BufferSlice<I>;
+  type IndexSliceMutRepr = BufferSliceMut<I>;
+
+  unsafe fn indices(tess: &mut Self::TessRepr) -> Result<Self::IndexSliceRepr, TessMapError> {
+    <GL33 as IndexSliceBackend<V, I, W, Deinterleaved>>::indices(tess)
+  }
+
+  unsafe fn indices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::IndexSliceMutRepr, TessMapError> {
+    <GL33 as IndexSliceBackend<V, I, W, Deinterleaved>>::indices_mut(tess)
+  }
+}
+
+unsafe impl<V, I, W, T> InstanceSliceBackend<V, I, W, Deinterleaved, T> for GLES3
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>> + Deinterleave<T>,
+{
+  type InstanceSliceRepr = BufferSlice<T>;
+  type InstanceSliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn instances(tess: &mut Self::TessRepr) -> Result<Self::InstanceSliceRepr, TessMapError> {
+    <GL33 as InstanceSliceBackend<V, I, W, Deinterleaved, T>>::instances(tess)
+  }
+
+  unsafe fn instances_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::InstanceSliceMutRepr, TessMapError> {
+    <GL33 as InstanceSliceBackend<V, I, W, Deinterleaved, T>>::instances_mut(tess)
+  }
+}
+
+// There are no patches (tessellation) in OpenGL ES 3.0.
+fn check_mode(mode: Mode) -> Result<(), TessError> {
+  match mode {
+    Mode::Patch(_) => Err(TessError::forbidden_primitive_mode(mode)),
+    _ => Ok(()),
+  }
+}
+
+// OpenGL ES 3.0 only supports PRIMITIVE_RESTART_FIXED_INDEX, which restarts primitives on the
+// maximum value of the index type.
+fn check_restart_index<I>(restart_index: Option<I>) -> Result<(), TessError>
+where
+  I: TessIndex,
+{
+  if let (Some(index_ty), Some(restart_index)) = (I::INDEX_TYPE, restart_index) {
+    let max = match index_ty {
+      TessIndexType::U8 => u8::MAX.into(),
+      TessIndexType::U16 => u16::MAX.into(),
+      TessIndexType::U32 => u32::MAX,
+    };
+
+    if restart_index.try_into_u32() != Some(max) {
+      return Err(TessError::cannot_create(format!(
+        "primitive restart index must be {}",
+        max
+      )));
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,411 @@
+use gl;
+use gl::types::*;
+use luminance::backend::texture::{
+  Texture as TextureBackend, TextureBase, TextureCompressed as TextureCompressedBackend,
+  TextureCompression, TextureReadback as TextureReadbackBackend,
+};
+use luminance::pixel::{CompressedPixel, CompressionFamily, Pixel, PixelFormat};
+use luminance::texture::{Dim, Dimensionable, GenMipmaps, Sampler, TextureError};
+use std::os::raw::c_void;
+use std::ptr;
+
+use crate::gl33::readback::Readback;
+use crate::gl33::texture::{set_pack_alignment, uses_border, Texture};
+use crate::gl33::GL33;
+use crate::gles3::pixel::gles3_pixel_format;
+use crate::gles3::GLES3;
+
+unsafe impl TextureBase for GLES3 {
+  type TextureRepr = Texture;
+}
+
+unsafe impl<D, P> TextureBackend<D, P> for GLES3
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  unsafe fn new_texture(
+    &mut self,
+    size: D::Size,
+    mipmaps: usize,
+    sampler: Sampler,
+  ) -> Result<Self::TextureRepr, TextureError> {
+    if let Dim::Dim1 | Dim::Dim1Array = D::dim() {
+      return Err(TextureError::texture_storage_creation_failed(format!(
+        "unsupported texture dimension: {:?}",
+        D::dim()
+      )));
+    }
+
+    let pf = P::pixel_format();
+
+    // compressed pixel formats are checked against the supported families by OpenGL 3.3
+    if pf.format.compression().is_none() && gles3_pixel_format(pf).is_none() {
+      return Err(TextureError::unsupported_pixel_format(pf));
+    }
+
+    if sampler.lod_bias != 0. {
+      return Err(TextureError::texture_storage_creation_failed(
+        "LOD bias is not supported",
+      ));
+    }
+
+    if uses_border(&sampler) {
+      return Err(TextureError::texture_storage_creation_failed(
+        "border clamping is not supported",
+      ));
+    }
+
+    TextureBackend::<D, P>::new_texture(&mut self.gl33, size, mipmaps, sampler)
+  }
+
+  unsafe fn mipmaps(texture: &Self::TextureRepr) -> usize {
+    <GL33 as TextureBackend<D, P>>::mipmaps(texture)
+  }
+
+  unsafe fn clear_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::clear_part(texture, gen_mipmaps, offset, size, pixel)
+  }
+
+  unsafe fn clear(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::clear(texture, gen_mipmaps, size, pixel)
+  }
+
+  unsafe fn upload_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::upload_part(texture, gen_mipmaps, offset, size, texels)
+  }
+
+  unsafe fn upload(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::upload(texture, gen_mipmaps, size, texels)
+  }
+
+  unsafe fn upload_part_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::upload_part_raw(texture, gen_mipmaps, offset, size, texels)
+  }
+
+  unsafe fn upload_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::upload_raw(texture, gen_mipmaps, size, texels)
+  }
+
+  unsafe fn get_raw_texels(
+    texture: &Self::TextureRepr,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let pf = P::pixel_format();
+    let extent = base_extent::<D>(size);
+    let layer_len = prepare_read_pixels(pf, extent)?;
+
+    // resize the vec to allocate enough space to host the returned texels
+    let mut texels = vec![Default::default(); layer_len * extent[2] as usize];
+    read_pixels::<D, _>(
+      texture,
+      pf,
+      0,
+      [0; 3],
+      extent,
+      layer_len,
+      texels.as_mut_ptr(),
+    )?;
+
+    Ok(texels)
+  }
+
+  unsafe fn copy_from(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    src: &Self::TextureRepr,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::copy_from(
+      texture,
+      gen_mipmaps,
+      src,
+      src_offset,
+      dst_offset,
+      size,
+    )
+  }
+
+  unsafe fn upload_level_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::upload_level_part(texture, level, offset, size, texels)
+  }
+
+  unsafe fn upload_level_part_raw(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::upload_level_part_raw(texture, level, offset, size, texels)
+  }
+
+  unsafe fn get_level_part_raw_texels(
+    texture: &Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let pf = P::pixel_format();
+    let (offset, size) = region::<D>(offset, size);
+    let layer_len = prepare_read_pixels(pf, size)?;
+
+    let mut texels = vec![Default::default(); layer_len * size[2] as usize];
+    read_pixels::<D, _>(
+      texture,
+      pf,
+      level,
+      offset,
+      size,
+      layer_len,
+      texels.as_mut_ptr(),
+    )?;
+
+    Ok(texels)
+  }
+
+  unsafe fn generate_mipmaps(texture: &mut Self::TextureRepr) -> Result<(), TextureError> {
+    <GL33 as TextureBackend<D, P>>::generate_mipmaps(texture)
+  }
+}
+
+unsafe impl TextureCompression for GLES3 {
+  unsafe fn supports_compression(&mut self, family: CompressionFamily) -> bool {
+    self.gl33.supports_compression(family)
+  }
+}
+
+unsafe impl<D, P> TextureCompressedBackend<D, P> for GLES3
+where
+  D: Dimensionable,
+  P: CompressedPixel,
+{
+  unsafe fn upload_compressed_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureCompressedBackend<D, P>>::upload_compressed_part(
+      texture, level, offset, size, blocks,
+    )
+  }
+
+  unsafe fn upload_compressed(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    <GL33 as TextureCompressedBackend<D, P>>::upload_compressed(texture, level, size, blocks)
+  }
+}
+
+unsafe impl<D, P> TextureReadbackBackend<D, P> for GLES3
+where
+  D: Dimensionable,
+  P: Pixel,
+  P::RawEncoding: Copy,
+{
+  unsafe fn get_raw_texels_async(
+    texture: &Self::TextureRepr,
+    size: D::Size,
+  ) -> Result<Self::ReadbackRepr, TextureError> {
+    let pf = P::pixel_format();
+    let extent = base_extent::<D>(size);
+    let layer_len = prepare_read_pixels(pf, extent)?;
+    let len = layer_len * extent[2] as usize;
+
+    // the texels are packed to the staging buffer, the pointer being an offset in it
+    let staging = Readback::<P::RawEncoding>::new_staging_buffer(gl::PIXEL_PACK_BUFFER, len);
+    let result =
+      read_pixels::<D, P::RawEncoding>(texture, pf, 0, [0; 3], extent, layer_len, ptr::null_mut());
+    gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+
+    if let Err(e) = result {
+      gl::DeleteBuffers(1, &staging);
+      return Err(e);
+    }
+
+    Readback::from_handle(staging, len)
+      .map_err(|_| TextureError::cannot_retrieve_texels("cannot create readback fence"))
+  }
+}
+
+/// Extent of the base level of a texture of size `size`, the third axis being its layers — or
+/// faces, for cubemaps.
+fn base_extent<D>(size: D::Size) -> [u32; 3]
+where
+  D: Dimensionable,
+{
+  let layers = match D::dim() {
+    Dim::Dim2 => 1,
+    Dim::Cubemap => 6,
+    _ => D::depth(size),
+  };
+
+  [D::width(size), D::height(size), layers]
+}
+
+/// Offset and size of the region described by `offset` and `size`, as three-dimensional vectors —
+/// the third axis being layers, or faces for cubemaps.
+///
+/// Cubemap regions only cover the face they are offset to.
+fn region<D>(offset: D::Offset, size: D::Size) -> ([u32; 3], [u32; 3])
+where
+  D: Dimensionable,
+{
+  let xy = [D::x_offset(offset), D::y_offset(offset)];
+  let wh = [D::width(size), D::height(size)];
+
+  match D::dim() {
+    Dim::Dim2 => ([xy[0], xy[1], 0], [wh[0], wh[1], 1]),
+    Dim::Cubemap => ([xy[0], xy[1], D::z_offset(offset)], [wh[0], wh[1], 1]),
+    _ => (
+      [xy[0], xy[1], D::z_offset(offset)],
+      [wh[0], wh[1], D::depth(size)],
+    ),
+  }
+}
+
+/// Set the packing alignment to read regions of size `[w, h, _]`, and return the number of texels —
+/// in raw encoding — of each of their layers.
+fn prepare_read_pixels(pf: PixelFormat, [w, h, _]: [u32; 3]) -> Result<usize, TextureError> {
+  if pf.is_depth_pixel() {
+    return Err(TextureError::cannot_retrieve_texels(
+      "depth texels cannot be read back",
+    ));
+  }
+
+  // set the packing alignment based on the number of bytes to skip
+  let skip_bytes = (pf.format.size() * w as usize) % 8;
+  set_pack_alignment(skip_bytes);
+
+  Ok((w * h) as usize * pf.canals_len())
+}
+
+/// Read the region of size `[w, h, layers]` at `[x, y, z]` in the mipmap `level` of `texture`,
+/// each layer holding `layer_len` texels, to `texels` — either client memory or an offset in the
+/// bound pixel pack buffer.
+///
+/// There’s no glGetTexImage: each layer (or face) is attached to a framebuffer to be read back.
+unsafe fn read_pixels<D, T>(
+  texture: &Texture,
+  pf: PixelFormat,
+  level: usize,
+  [x, y, z]: [u32; 3],
+  [w, h, layers]: [u32; 3],
+  layer_len: usize,
+  texels: *mut T,
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let (format, _, ty) = gles3_pixel_format(pf).unwrap();
+  let level = level as GLint;
+
+  let mut framebuffer: GLuint = 0;
+  gl::GenFramebuffers(1, &mut framebuffer);
+  gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+
+  let mut result = Ok(());
+
+  for layer in 0..layers {
+    match D::dim() {
+      Dim::Dim2 => gl::FramebufferTexture2D(
+        gl::READ_FRAMEBUFFER,
+        gl::COLOR_ATTACHMENT0,
+        gl::TEXTURE_2D,
+        texture.handle,
+        level,
+      ),
+
+      Dim::Cubemap => gl::FramebufferTexture2D(
+        gl::READ_FRAMEBUFFER,
+        gl::COLOR_ATTACHMENT0,
+        gl::TEXTURE_CUBE_MAP_POSITIVE_X + z + layer,
+        texture.handle,
+        level,
+      ),
+
+      _ => gl::FramebufferTextureLayer(
+        gl::READ_FRAMEBUFFER,
+        gl::COLOR_ATTACHMENT0,
+        texture.handle,
+        level,
+        (z + layer) as GLint,
+      ),
+    }
+
+    if gl::CheckFramebufferStatus(gl::READ_FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+      result = Err(TextureError::cannot_retrieve_texels(
+        "texture cannot be attached to a framebuffer",
+      ));
+      break;
+    }
+
+    // the pointer might be an offset, hence the wrapping arithmetic
+    gl::ReadPixels(
+      x as GLint,
+      y as GLint,
+      w as GLsizei,
+      h as GLsizei,
+      format,
+      ty,
+      texels.wrapping_add(layer as usize * layer_len) as *mut c_void,
+    );
+  }
+
+  gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+  gl::DeleteFramebuffers(1, &framebuffer);
+
+  result
+}
@@ -1,5 +1,13 @@
 //! OpenGL backends.
 
 pub mod gl33;
+#[cfg(feature = "gl45")]
+pub mod gl45;
+#[cfg(feature = "gles3")]
+pub mod gles3;
 
 pub use gl33::GL33;
+#[cfg(feature = "gl45")]
+pub use gl45::GL45;
+#[cfg(feature = "gles3")]
+pub use gles3::GLES3;
@@ -0,0 +1,274 @@
+//! EGL display and OpenGL context management.
+
+use glutin_egl_sys::egl::{self, types::EGLint, Egl};
+use libloading::{Library, Symbol};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+
+use crate::HeadlessError;
+
+/// `EGL_PLATFORM_SURFACELESS_MESA`, from `EGL_MESA_platform_surfaceless`.
+const PLATFORM_SURFACELESS_MESA: egl::types::EGLenum = 0x31DD;
+
+/// Client API of an [`EglContext`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ClientApi {
+  /// OpenGL 3.3 core.
+  GL33,
+  /// OpenGL 4.5 core.
+  GL45,
+  /// OpenGL ES 3.0.
+  GLES3,
+}
+
+/// An OpenGL 3.3 core, OpenGL 4.5 core or OpenGL ES 3.0 context, current on the thread that created it.
+///
+/// The context is created on the surfaceless platform when `EGL_MESA_platform_surfaceless` is
+/// available, and on the default display otherwise. It’s made current without any surface if the
+/// display supports `EGL_KHR_surfaceless_context`, or with a 1×1 pbuffer otherwise.
+pub(crate) struct EglContext {
+  egl: Egl,
+  display: egl::types::EGLDisplay,
+  context: egl::types::EGLContext,
+  surface: egl::types::EGLSurface,
+  // must be dropped last, as the function pointers in egl point into it
+  _lib: Library,
+}
+
+impl EglContext {
+  /// Load EGL, create a context for `api` and make it current.
+  pub(crate) fn new(api: ClientApi) -> Result<Self, HeadlessError> {
+    let lib = Library::new("libEGL.so.1")
+      .or_else(|_| Library::new("libEGL.so"))
+      .map_err(HeadlessError::EglLoadingError)?;
+    let egl = unsafe { load_egl(&lib)? };
+
+    let display = unsafe { get_display(&egl)? };
+
+    // from now on, dropping ctx terminates the display and releases whatever was created
+    let mut ctx = EglContext {
+      egl,
+      display,
+      context: egl::NO_CONTEXT,
+      surface: egl::NO_SURFACE,
+      _lib: lib,
+    };
+
+    unsafe { ctx.init(api)? };
+
+    Ok(ctx)
+  }
+
+  unsafe fn init(&mut self, api: ClientApi) -> Result<(), HeadlessError> {
+    let egl = &self.egl;
+
+    let (mut major, mut minor) = (0, 0);
+    if egl.Initialize(self.display, &mut major, &mut minor) == egl::FALSE {
+      return Err(self.error("eglInitialize"));
+    }
+
+    let (egl_api, renderable_type) = match api {
+      ClientApi::GL33 | ClientApi::GL45 => (egl::OPENGL_API, egl::OPENGL_BIT),
+      ClientApi::GLES3 => (egl::OPENGL_ES_API, egl::OPENGL_ES3_BIT),
+    };
+
+    if egl.BindAPI(egl_api) == egl::FALSE {
+      return Err(self.error("eglBindAPI"));
+    }
+
+    let config_attribs = [
+      egl::SURFACE_TYPE as EGLint,
+      egl::PBUFFER_BIT as EGLint,
+      egl::RENDERABLE_TYPE as EGLint,
+      renderable_type as EGLint,
+      egl::RED_SIZE as EGLint,
+      8,
+      egl::GREEN_SIZE as EGLint,
+      8,
+      egl::BLUE_SIZE as EGLint,
+      8,
+      egl::ALPHA_SIZE as EGLint,
+      8,
+      egl::NONE as EGLint,
+    ];
+    let mut config = ptr::null();
+    let mut config_nb = 0;
+
+    if egl.ChooseConfig(
+      self.display,
+      config_attribs.as_ptr(),
+      &mut config,
+      1,
+      &mut config_nb,
+    ) == egl::FALSE
+    {
+      return Err(self.error("eglChooseConfig"));
+    }
+
+    if config_nb == 0 {
+      return Err(HeadlessError::NoConfig);
+    }
+
+    let context_attribs: &[EGLint] = match api {
+      ClientApi::GL33 => &[
+        egl::CONTEXT_MAJOR_VERSION as EGLint,
+        3,
+        egl::CONTEXT_MINOR_VERSION as EGLint,
+        3,
+        egl::CONTEXT_OPENGL_PROFILE_MASK as EGLint,
+        egl::CONTEXT_OPENGL_CORE_PROFILE_BIT as EGLint,
+        egl::NONE as EGLint,
+      ],
+
+      ClientApi::GL45 => &[
+        egl::CONTEXT_MAJOR_VERSION as EGLint,
+        4,
+        egl::CONTEXT_MINOR_VERSION as EGLint,
+        5,
+        egl::CONTEXT_OPENGL_PROFILE_MASK as EGLint,
+        egl::CONTEXT_OPENGL_CORE_PROFILE_BIT as EGLint,
+        egl::NONE as EGLint,
+      ],
+
+      ClientApi::GLES3 => &[
+        egl::CONTEXT_MAJOR_VERSION as EGLint,
+        3,
+        egl::CONTEXT_MINOR_VERSION as EGLint,
+        0,
+        egl::NONE as EGLint,
+      ],
+    };
+
+    self.context = egl.CreateContext(
+      self.display,
+      config,
+      egl::NO_CONTEXT,
+      context_attribs.as_ptr(),
+    );
+
+    if self.context == egl::NO_CONTEXT {
+      return Err(self.error("eglCreateContext"));
+    }
+
+    if !has_extension(
+      &extensions(egl, self.display),
+      "EGL_KHR_surfaceless_context",
+    ) {
+      let pbuffer_attribs = [
+        egl::WIDTH as EGLint,
+        1,
+        egl::HEIGHT as EGLint,
+        1,
+        egl::NONE as EGLint,
+      ];
+
+      self.surface = egl.CreatePbufferSurface(self.display, config, pbuffer_attribs.as_ptr());
+
+      if self.surface == egl::NO_SURFACE {
+        return Err(self.error("eglCreatePbufferSurface"));
+      }
+    }
+
+    if egl.MakeCurrent(self.display, self.surface, self.surface, self.context) == egl::FALSE {
+      return Err(self.error("eglMakeCurrent"));
+    }
+
+    Ok(())
+  }
+
+  /// Get the address of an OpenGL function.
+  pub(crate) fn get_proc_address(&self, name: &str) -> *const c_void {
+    let name = CString::new(name).unwrap();
+    unsafe { self.egl.GetProcAddress(name.as_ptr()) as *const c_void }
+  }
+
+  fn error(&self, call: &'static str) -> HeadlessError {
+    HeadlessError::EglError(call, unsafe { self.egl.GetError() })
+  }
+}
+
+impl Drop for EglContext {
+  fn drop(&mut self) {
+    unsafe {
+      let egl = &self.egl;
+
+      egl.MakeCurrent(
+        self.display,
+        egl::NO_SURFACE,
+        egl::NO_SURFACE,
+        egl::NO_CONTEXT,
+      );
+
+      if self.surface != egl::NO_SURFACE {
+        egl.DestroySurface(self.display, self.surface);
+      }
+
+      if self.context != egl::NO_CONTEXT {
+        egl.DestroyContext(self.display, self.context);
+      }
+
+      egl.Terminate(self.display);
+    }
+  }
+}
+
+/// Load the EGL functions from the EGL library.
+///
+/// Functions not exported by the library (extensions, mostly) are looked up with
+/// `eglGetProcAddress`.
+unsafe fn load_egl(lib: &Library) -> Result<Egl, HeadlessError> {
+  let get_proc_address: Symbol<unsafe extern "C" fn(*const c_char) -> *const c_void> = lib
+    .get(b"eglGetProcAddress\0")
+    .map_err(HeadlessError::EglLoadingError)?;
+
+  let egl = Egl::load_with(|name| {
+    let name = CString::new(name).unwrap();
+
+    match lib.get::<*const c_void>(name.as_bytes_with_nul()) {
+      Ok(sym) => *sym,
+      Err(_) => get_proc_address(name.as_ptr()),
+    }
+  });
+
+  Ok(egl)
+}
+
+/// Get the surfaceless display if available, or the default display.
+unsafe fn get_display(egl: &Egl) -> Result<egl::types::EGLDisplay, HeadlessError> {
+  // client extensions are queried without any display
+  let client_extensions = extensions(egl, egl::NO_DISPLAY);
+
+  let display = if has_extension(&client_extensions, "EGL_MESA_platform_surfaceless")
+    && egl.GetPlatformDisplayEXT.is_loaded()
+  {
+    egl.GetPlatformDisplayEXT(
+      PLATFORM_SURFACELESS_MESA,
+      egl::DEFAULT_DISPLAY as *mut _,
+      ptr::null(),
+    )
+  } else {
+    egl.GetDisplay(egl::DEFAULT_DISPLAY)
+  };
+
+  if display == egl::NO_DISPLAY {
+    Err(HeadlessError::NoDisplay)
+  } else {
+    Ok(display)
+  }
+}
+
+/// Get the space-separated list of extensions of a display.
+unsafe fn extensions(egl: &Egl, display: egl::types::EGLDisplay) -> String {
+  let extensions = egl.QueryString(display, egl::EXTENSIONS as EGLint);
+
+  if extensions.is_null() {
+    String::new()
+  } else {
+    CStr::from_ptr(extensions).to_string_lossy().into_owned()
+  }
+}
+
+fn has_extension(extensions: &str, name: &str) -> bool {
+  extensions.split_whitespace().any(|ext| ext == name)
+}
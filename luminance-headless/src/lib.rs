@@ -0,0 +1,219 @@
+//! Headless [luminance] surface, without any window system.
+//!
+//! This crate provides [`HeadlessSurface`], which creates an OpenGL 3.3, OpenGL 4.5 or OpenGL ES
+//! 3.0 context through [EGL], using the `EGL_MESA_platform_surfaceless` platform if available, or a
+//! pbuffer on the default display otherwise. It doesn’t require X11 nor Wayland, which makes it
+//! suitable to run luminance on render farms or CI machines, with a software implementation such
+//! as llvmpipe, or on embedded boards that only expose OpenGL ES.
+//!
+//! Because there’s no window, there’s no default framebuffer either: the “back buffer” of a
+//! [`HeadlessSurface`] is an offscreen [`Framebuffer`] with a color and a depth texture, that you
+//! can read back once you’re done rendering:
+//!
+//! ```no_run
+//! use luminance::context::GraphicsContext as _;
+//! use luminance::pipeline::{PipelineError, PipelineState};
+//! use luminance_headless::HeadlessSurface;
+//!
+//! let mut surface = HeadlessSurface::new_gl33([800, 600]).expect("headless surface");
+//! let mut back_buffer = surface.back_buffer().expect("back buffer");
+//!
+//! let render: Result<(), PipelineError> = surface
+//!   .new_pipeline_gate()
+//!   .pipeline(
+//!     &back_buffer,
+//!     &PipelineState::default().set_clear_color([1., 0., 0., 1.]),
+//!     |_, _| Ok(()),
+//!   )
+//!   .into_result();
+//! render.expect("render");
+//!
+//! let texels = back_buffer.color_slot().get_raw_texels();
+//! ```
+//!
+//! [luminance]: https://crates.io/crates/luminance
+//! [EGL]: https://www.khronos.org/egl
+
+#![deny(missing_docs)]
+
+mod egl;
+
+use luminance::backend::framebuffer::Framebuffer as FramebufferBackend;
+use luminance::backend::texture::Texture as TextureBackend;
+use luminance::context::GraphicsContext;
+use luminance::framebuffer::{Framebuffer, FramebufferError};
+use luminance::pixel::{Depth32F, NormRGBA8UI};
+use luminance::texture::{Dim2, Sampler};
+pub use luminance_gl::gl33::StateQueryError;
+use luminance_gl::{GL33, GL45, GLES3};
+use std::error;
+use std::fmt;
+
+use crate::egl::{ClientApi, EglContext};
+
+/// Error that might occur when creating a headless surface.
+#[derive(Debug)]
+pub enum HeadlessError {
+  /// The EGL library couldn’t be loaded.
+  EglLoadingError(libloading::Error),
+  /// An EGL call failed. Carries the name of the call and the EGL error code.
+  EglError(&'static str, i32),
+  /// No EGL display is available.
+  NoDisplay,
+  /// No EGL configuration supports the requested API with RGBA8 pbuffers.
+  NoConfig,
+  /// Graphics state error that might occur when querying the initial state.
+  GraphicsStateError(StateQueryError),
+}
+
+impl fmt::Display for HeadlessError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      HeadlessError::EglLoadingError(ref e) => write!(f, "cannot load EGL: {}", e),
+      HeadlessError::EglError(call, code) => write!(f, "{} failed: EGL error 0x{:x}", call, code),
+      HeadlessError::NoDisplay => f.write_str("no EGL display available"),
+      HeadlessError::NoConfig => f.write_str("no suitable EGL configuration"),
+      HeadlessError::GraphicsStateError(ref e) => {
+        write!(f, "OpenGL graphics state initialization error: {}", e)
+      }
+    }
+  }
+}
+
+impl error::Error for HeadlessError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      HeadlessError::EglLoadingError(e) => Some(e),
+      HeadlessError::GraphicsStateError(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<StateQueryError> for HeadlessError {
+  fn from(e: StateQueryError) -> Self {
+    HeadlessError::GraphicsStateError(e)
+  }
+}
+
+/// Back buffer of a [`HeadlessSurface`].
+pub type HeadlessBackBuffer<B = GL33> = Framebuffer<B, Dim2, NormRGBA8UI, Depth32F>;
+
+/// The headless surface.
+///
+/// You want to create such an object in order to use any [luminance] construct. `B` is the
+/// backend, either [`GL33`], [`GL45`] or [`GLES3`].
+///
+/// [luminance]: https://crates.io/crates/luminance
+pub struct HeadlessSurface<B = GL33> {
+  /// OpenGL state.
+  gl: B,
+  size: [u32; 2],
+  // dropped after the OpenGL state
+  _ctx: EglContext,
+}
+
+unsafe impl<B> GraphicsContext for HeadlessSurface<B> {
+  type Backend = B;
+
+  fn backend(&mut self) -> &mut Self::Backend {
+    &mut self.gl
+  }
+}
+
+impl HeadlessSurface<GL33> {
+  /// Create a new [`HeadlessSurface`] with an OpenGL 3.3 core context.
+  ///
+  /// `size` is the size (in pixels) of the back buffers created with
+  /// [`HeadlessSurface::back_buffer`].
+  ///
+  /// The OpenGL context is made current on the calling thread.
+  pub fn new_gl33(size: [u32; 2]) -> Result<Self, HeadlessError> {
+    let ctx = EglContext::new(ClientApi::GL33)?;
+
+    // init OpenGL
+    gl::load_with(|s| ctx.get_proc_address(s));
+
+    let gl = GL33::new()?;
+    let surface = HeadlessSurface {
+      gl,
+      size,
+      _ctx: ctx,
+    };
+
+    Ok(surface)
+  }
+}
+
+impl HeadlessSurface<GL45> {
+  /// Create a new [`HeadlessSurface`] with an OpenGL 4.5 core context.
+  ///
+  /// `size` is the size (in pixels) of the back buffers created with
+  /// [`HeadlessSurface::back_buffer`].
+  ///
+  /// The OpenGL context is made current on the calling thread.
+  pub fn new_gl45(size: [u32; 2]) -> Result<Self, HeadlessError> {
+    let ctx = EglContext::new(ClientApi::GL45)?;
+
+    // init OpenGL
+    gl::load_with(|s| ctx.get_proc_address(s));
+
+    let gl = GL45::new()?;
+    let surface = HeadlessSurface {
+      gl,
+      size,
+      _ctx: ctx,
+    };
+
+    Ok(surface)
+  }
+}
+
+impl HeadlessSurface<GLES3> {
+  /// Create a new [`HeadlessSurface`] with an OpenGL ES 3.0 context.
+  ///
+  /// `size` is the size (in pixels) of the back buffers created with
+  /// [`HeadlessSurface::back_buffer`].
+  ///
+  /// The OpenGL ES context is made current on the calling thread.
+  pub fn new_gles3(size: [u32; 2]) -> Result<Self, HeadlessError> {
+    let ctx = EglContext::new(ClientApi::GLES3)?;
+
+    // init OpenGL ES
+    gl::load_with(|s| ctx.get_proc_address(s));
+
+    let gl = GLES3::new()?;
+    let surface = HeadlessSurface {
+      gl,
+      size,
+      _ctx: ctx,
+    };
+
+    Ok(surface)
+  }
+}
+
+impl<B> HeadlessSurface<B>
+where
+  B: FramebufferBackend<Dim2> + TextureBackend<Dim2, NormRGBA8UI> + TextureBackend<Dim2, Depth32F>,
+{
+  /// Get the size of the surface.
+  pub fn size(&self) -> [u32; 2] {
+    self.size
+  }
+
+  /// Resize the surface.
+  ///
+  /// Only back buffers created afterwards are affected.
+  pub fn set_size(&mut self, size: [u32; 2]) {
+    self.size = size;
+  }
+
+  /// Create a back buffer.
+  ///
+  /// As there’s no default framebuffer, every call allocates a new offscreen framebuffer of the
+  /// size of the surface: create it once and keep it around.
+  pub fn back_buffer(&mut self) -> Result<HeadlessBackBuffer<B>, FramebufferError> {
+    Framebuffer::new(self, self.size, 0, Sampler::default())
+  }
+}
@@ -0,0 +1,827 @@
+use luminance::buffer::{Buffer, BufferUsage, PreserveContent};
+use luminance::compute::ComputeError;
+use luminance::context::GraphicsContext as _;
+use luminance::layout::Std430 as _;
+use luminance::pipeline::{
+  BufferBinding, PipelineError, PipelineState, StorageBufferBinding, TextureBinding,
+};
+use luminance::pixel::{
+  Depth32F, Depth32FStencil8, NormR32I, NormRGBA8UI, NormUnsigned, BC1, RGBA32F,
+};
+use luminance::query::QueryType;
+use luminance::render_state::RenderState;
+use luminance::renderbuffer::Renderbuffer;
+use luminance::shader::{ProgramError, Uniform};
+use luminance::stencil::{StencilComparison, StencilTest};
+use luminance::stream::StreamBuffer;
+use luminance::tess::Mode;
+use luminance::texture::{CubeFace, Cubemap, Dim2, Dim2Array, GenMipmaps, Sampler, TextureError};
+use luminance::{Std430, UniformInterface};
+use luminance_gl::GL45;
+use luminance_headless::HeadlessSurface;
+use std::error::Error;
+
+const VS: &str = "
+const vec2[4] POSITIONS = vec2[](vec2(-1., -1.), vec2(1., -1.), vec2(-1., 1.), vec2(1., 1.));
+
+void main() {
+  gl_Position = vec4(POSITIONS[gl_VertexID], 0., 1.);
+}";
+
+const FS: &str = "
+out vec4 frag;
+
+void main() {
+  frag = vec4(0., 1., 0., 1.);
+}";
+
+const BLOCK_FS: &str = "
+layout(std140) uniform Color {
+  vec4 color;
+};
+
+out vec4 frag;
+
+void main() {
+  frag = color;
+}";
+
+const LEVEL_FS: &str = "
+uniform sampler2D tex;
+
+out vec4 frag;
+
+void main() {
+  frag = textureLod(tex, vec2(.5), 1.);
+}";
+
+const CS: &str = "
+layout(local_size_x = 1) in;
+
+void main() {
+}";
+
+const SCALE_CS: &str = "
+layout(local_size_x = 2) in;
+
+layout(std430) buffer Values {
+  uint values[];
+};
+
+uniform uint factor;
+
+void main() {
+  values[gl_GlobalInvocationID.x] *= factor;
+}";
+
+const MOVE_CS: &str = "
+layout(local_size_x = 1) in;
+
+struct Particle {
+  vec3 position;
+  float speed;
+  vec2 direction;
+  bool alive;
+};
+
+layout(std430) buffer Particles {
+  Particle particles[];
+};
+
+void main() {
+  Particle p = particles[gl_GlobalInvocationID.x];
+
+  if (p.alive) {
+    particles[gl_GlobalInvocationID.x].position.xy += p.direction * p.speed;
+  }
+}";
+
+#[derive(Clone, Copy, Debug, PartialEq, Std430)]
+struct Particle {
+  position: [f32; 3],
+  speed: f32,
+  direction: [f32; 2],
+  alive: bool,
+}
+
+#[derive(UniformInterface)]
+struct ColorInterface {
+  #[uniform(name = "Color")]
+  color: Uniform<BufferBinding<[f32; 4]>>,
+}
+
+#[derive(UniformInterface)]
+struct TextureInterface {
+  tex: Uniform<TextureBinding<Dim2, NormUnsigned>>,
+}
+
+#[derive(UniformInterface)]
+struct MoveInterface {
+  #[uniform(name = "Particles")]
+  particles: Uniform<StorageBufferBinding<ParticleStd430>>,
+}
+
+#[derive(UniformInterface)]
+struct ScaleInterface {
+  factor: Uniform<u32>,
+  #[uniform(name = "Values")]
+  values: Uniform<StorageBufferBinding<u32>>,
+}
+
+#[test]
+fn render_attributeless_quad() {
+  let mut surface = HeadlessSurface::new_gl45([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+}
+
+#[test]
+fn render_from_stream_buffer_ranges() {
+  let mut surface = HeadlessSurface::new_gl45([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ColorInterface>()
+    .from_strings(VS, None, None, BLOCK_FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let mut stream = StreamBuffer::new(&mut surface, 2, 3).unwrap();
+
+  // fill a first frame with data that must not be rendered
+  stream.alloc(&[[1., 0., 0., 1.]; 2]).unwrap();
+  stream.next_frame(&mut surface).unwrap();
+
+  let _ = stream.alloc(&[[1., 0., 0., 1.]]).unwrap();
+  let green = stream.alloc(&[[0., 1., 0., 1.]]).unwrap();
+
+  assert_ne!(green.start, 0);
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |pipeline, mut shd_gate| {
+        let bound = pipeline.bind_buffer_range(stream.buffer(), green.clone())?;
+
+        shd_gate.shade(&mut program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.color, bound.binding());
+
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  stream.next_frame(&mut surface).unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+}
+
+#[test]
+fn query_samples_passed() {
+  let mut surface = HeadlessSurface::new_gl45([2, 2]).unwrap();
+  let back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let mut samples = surface.new_query(QueryType::SamplesPassed).unwrap();
+  assert_eq!(samples.try_get(), Ok(None));
+
+  let render: Result<(), Box<dyn Error>> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.query(&mut samples, |shd_gate| {
+          shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+            rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+              tess_gate.render(&tess)
+            })
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let result = loop {
+    if let Some(result) = samples.try_get().unwrap() {
+      break result;
+    }
+  };
+
+  assert_eq!(result, 2 * 2);
+}
+
+#[test]
+fn edit_buffers() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut buffer =
+    Buffer::from_vec(&mut surface, vec![1u32, 2, 3, 4], BufferUsage::Dynamic).unwrap();
+  assert_eq!(&*buffer.slice().unwrap(), [1, 2, 3, 4]);
+
+  buffer.set(2, 42).unwrap();
+  assert_eq!(&*buffer.slice().unwrap(), [1, 2, 42, 4]);
+
+  buffer.write_whole(&[5, 6, 7, 8]).unwrap();
+  assert_eq!(&*buffer.slice().unwrap(), [5, 6, 7, 8]);
+
+  buffer.clear(9).unwrap();
+  assert_eq!(&*buffer.slice().unwrap(), [9, 9, 9, 9]);
+
+  buffer.slice_mut().unwrap()[0] = 10;
+  assert_eq!(&*buffer.slice().unwrap(), [10, 9, 9, 9]);
+
+  let empty = Buffer::<_, u32>::new(&mut surface, 0, BufferUsage::Static).unwrap();
+  assert!(empty.is_empty());
+}
+
+#[test]
+fn edit_buffer_ranges() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  for &usage in &[BufferUsage::Dynamic, BufferUsage::Persistent] {
+    let mut buffer = Buffer::from_vec(&mut surface, vec![0u32; 6], usage).unwrap();
+    let other = Buffer::from_vec(&mut surface, vec![1u32, 2, 3, 4], BufferUsage::Static).unwrap();
+
+    buffer.write_range(1, &[5, 6]).unwrap();
+    assert_eq!(buffer.read_range(0..4).unwrap(), [0, 5, 6, 0]);
+
+    buffer.copy_from(&other, 2..4, 4).unwrap();
+    assert_eq!(buffer.read_range(3..6).unwrap(), [0, 3, 4]);
+
+    // writes through slices are read back as well
+    buffer.slice_mut().unwrap()[5] = 7;
+    assert_eq!(buffer.read_range(5..6).unwrap(), [7]);
+    assert!(buffer.read_range(6..6).unwrap().is_empty());
+  }
+}
+
+#[test]
+fn edit_persistent_buffers() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut buffer =
+    Buffer::from_vec(&mut surface, vec![1u32, 2, 3, 4], BufferUsage::Persistent).unwrap();
+  assert_eq!(&*buffer.slice().unwrap(), [1, 2, 3, 4]);
+
+  buffer.set(2, 42).unwrap();
+  assert_eq!(&*buffer.slice().unwrap(), [1, 2, 42, 4]);
+
+  buffer.write_whole(&[5, 6, 7, 8]).unwrap();
+  assert_eq!(&*buffer.slice().unwrap(), [5, 6, 7, 8]);
+
+  // slices can be alive at the same time, as the buffer stays mapped
+  buffer.slice_mut().unwrap()[0] = 10;
+  let slice = buffer.slice().unwrap();
+  assert_eq!(&*slice, [10, 6, 7, 8]);
+  drop(slice);
+
+  // writes through the mapped memory are visible to the GPU
+  let mut values = buffer.whole_async().unwrap();
+  let mut fence = surface.new_fence().unwrap();
+  while !fence.is_signaled() {}
+  assert_eq!(values.try_get().unwrap(), Some(vec![10, 6, 7, 8]));
+
+  let empty = Buffer::<_, u32>::new(&mut surface, 0, BufferUsage::Persistent).unwrap();
+  assert!(empty.is_empty());
+}
+
+#[test]
+fn resize_buffers() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  for &usage in &[BufferUsage::Dynamic, BufferUsage::Persistent] {
+    let mut buffer = Buffer::from_vec(&mut surface, vec![1u32, 2, 3, 4], usage).unwrap();
+
+    buffer.resize(6, 9, PreserveContent::Yes).unwrap();
+    assert_eq!(&*buffer.slice().unwrap(), [1, 2, 3, 4, 9, 9]);
+
+    buffer.resize(2, 0, PreserveContent::Yes).unwrap();
+    assert_eq!(&*buffer.slice().unwrap(), [1, 2]);
+
+    buffer.resize(3, 7, PreserveContent::No).unwrap();
+    assert_eq!(buffer.read_range(0..3).unwrap(), [7, 7, 7]);
+
+    // empty buffers can be grown
+    let mut empty = Buffer::<_, u32>::new(&mut surface, 0, usage).unwrap();
+    empty.resize(2, 5, PreserveContent::Yes).unwrap();
+    assert_eq!(&*empty.slice().unwrap(), [5, 5]);
+  }
+}
+
+#[test]
+fn edit_textures() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut texture = surface
+    .new_texture::<Dim2, RGBA32F>([2, 1], 0, Sampler::default())
+    .unwrap();
+  let texels = [1., 2., 3., 4., 5., 6., 7., 8.];
+  texture.upload_raw(GenMipmaps::No, &texels).unwrap();
+
+  assert_eq!(texture.get_raw_texels().unwrap(), texels);
+
+  texture
+    .clear_part(GenMipmaps::No, [1, 0], [1, 1], (0., 0., 0., 1.))
+    .unwrap();
+
+  assert_eq!(
+    texture.get_raw_texels().unwrap(),
+    [1., 2., 3., 4., 0., 0., 0., 1.]
+  );
+
+  let mut layers = surface
+    .new_texture::<Dim2Array, NormRGBA8UI>(([1, 1], 2), 0, Sampler::default())
+    .unwrap();
+  let texels = [1, 2, 3, 4, 5, 6, 7, 8];
+  layers.upload_raw(GenMipmaps::No, &texels).unwrap();
+
+  assert_eq!(layers.get_raw_texels().unwrap(), texels);
+
+  let mut cubemap = surface
+    .new_texture::<Cubemap, NormRGBA8UI>(1, 0, Sampler::default())
+    .unwrap();
+  cubemap.clear(GenMipmaps::No, (0, 0, 0, 0)).unwrap();
+  cubemap
+    .upload_part_raw(
+      GenMipmaps::No,
+      ([0, 0], CubeFace::NegativeX),
+      1,
+      &[1, 2, 3, 4],
+    )
+    .unwrap();
+
+  let mut faces = [0; 6 * 4];
+  faces[4..8].copy_from_slice(&[1, 2, 3, 4]);
+  assert_eq!(cubemap.get_raw_texels().unwrap(), faces);
+
+  let mut mipmapped = surface
+    .new_texture::<Dim2, NormRGBA8UI>([2, 2], 1, Sampler::default())
+    .unwrap();
+  mipmapped
+    .clear(GenMipmaps::Yes, (255, 255, 255, 255))
+    .unwrap();
+
+  assert_eq!(
+    mipmapped.get_raw_texels().unwrap(),
+    [255; 2 * 2 * 4].to_vec()
+  );
+
+  assert!(matches!(
+    surface.new_texture::<Dim2, NormR32I>([1, 1], 0, Sampler::default()),
+    Err(TextureError::UnsupportedPixelFormat(_))
+  ));
+}
+
+#[test]
+fn copy_textures() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut src = surface
+    .new_texture::<Dim2, RGBA32F>([2, 1], 0, Sampler::default())
+    .unwrap();
+  src
+    .upload_raw(GenMipmaps::No, &[1., 2., 3., 4., 5., 6., 7., 8.])
+    .unwrap();
+
+  let mut dst = surface
+    .new_texture::<Dim2, RGBA32F>([2, 1], 0, Sampler::default())
+    .unwrap();
+  dst.clear(GenMipmaps::No, (0., 0., 0., 0.)).unwrap();
+  dst
+    .copy_from(GenMipmaps::No, &src, [1, 0], [0, 0], [1, 1])
+    .unwrap();
+
+  assert_eq!(
+    dst.get_raw_texels().unwrap(),
+    [5., 6., 7., 8., 0., 0., 0., 0.]
+  );
+
+  let mut layers = surface
+    .new_texture::<Dim2Array, NormRGBA8UI>(([1, 1], 2), 0, Sampler::default())
+    .unwrap();
+  layers
+    .upload_raw(GenMipmaps::No, &[1, 2, 3, 4, 5, 6, 7, 8])
+    .unwrap();
+
+  let mut other_layers = surface
+    .new_texture::<Dim2Array, NormRGBA8UI>(([1, 1], 2), 0, Sampler::default())
+    .unwrap();
+  other_layers
+    .copy_from(
+      GenMipmaps::No,
+      &layers,
+      ([0, 0], 0),
+      ([0, 0], 0),
+      ([1, 1], 2),
+    )
+    .unwrap();
+
+  assert_eq!(
+    other_layers.get_raw_texels().unwrap(),
+    [1, 2, 3, 4, 5, 6, 7, 8]
+  );
+}
+
+#[test]
+fn dispatch_compute() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut program = surface
+    .new_compute_program::<()>(CS)
+    .unwrap()
+    .ignore_warnings();
+
+  surface
+    .dispatch::<ComputeError, _, _>(&mut program, [8, 1, 1], |_, _, _| Ok(()))
+    .unwrap();
+
+  assert!(matches!(
+    surface.dispatch::<ComputeError, _, _>(&mut program, [u32::MAX, 1, 1], |_, _, _| Ok(())),
+    Err(ComputeError::TooManyWorkGroups { .. })
+  ));
+
+  assert!(matches!(
+    surface.new_compute_program::<()>("void main() {"),
+    Err(ProgramError::StageError(_))
+  ));
+}
+
+#[test]
+fn dispatch_on_storage_buffers() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut program = surface
+    .new_compute_program::<ScaleInterface>(SCALE_CS)
+    .unwrap()
+    .ignore_warnings();
+  let mut values = surface
+    .new_buffer_from_vec(vec![1u32, 2, 3, 4], BufferUsage::ReadBack)
+    .unwrap();
+
+  surface
+    .dispatch::<ComputeError, _, _>(&mut program, [2, 1, 1], |pipeline, mut iface, uni| {
+      let bound = pipeline.bind_storage_buffer(&mut values)?;
+      iface.set(&uni.factor, 3);
+      iface.set(&uni.values, bound.binding());
+      Ok(())
+    })
+    .unwrap();
+
+  assert_eq!(&*values.slice().unwrap(), [3, 6, 9, 12]);
+}
+
+#[test]
+fn read_back_asynchronously() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut program = surface
+    .new_compute_program::<ScaleInterface>(SCALE_CS)
+    .unwrap()
+    .ignore_warnings();
+  let mut values = surface
+    .new_buffer_from_vec(vec![1u32, 2, 3, 4], BufferUsage::ReadBack)
+    .unwrap();
+
+  surface
+    .dispatch::<ComputeError, _, _>(&mut program, [2, 1, 1], |pipeline, mut iface, uni| {
+      let bound = pipeline.bind_storage_buffer(&mut values)?;
+      iface.set(&uni.factor, 2);
+      iface.set(&uni.values, bound.binding());
+      Ok(())
+    })
+    .unwrap();
+
+  let mut layers = surface
+    .new_texture::<Dim2Array, NormRGBA8UI>(([1, 1], 2), 0, Sampler::default())
+    .unwrap();
+  layers
+    .upload_raw(GenMipmaps::No, &[1, 2, 3, 4, 5, 6, 7, 8])
+    .unwrap();
+
+  let mut values = values.whole_async().unwrap();
+  let mut texels = layers.get_raw_texels_async().unwrap();
+  let mut fence = surface.new_fence().unwrap();
+
+  while !fence.is_signaled() {}
+
+  assert_eq!(values.try_get().unwrap(), Some(vec![2, 4, 6, 8]));
+  assert_eq!(
+    texels.try_get().unwrap(),
+    Some(vec![1, 2, 3, 4, 5, 6, 7, 8])
+  );
+}
+
+#[test]
+fn dispatch_on_std430_storage_buffers() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+
+  let mut program = surface
+    .new_compute_program::<MoveInterface>(MOVE_CS)
+    .unwrap()
+    .ignore_warnings();
+
+  let particle = Particle {
+    position: [1., 2., 3.],
+    speed: 2.,
+    direction: [1., -1.],
+    alive: true,
+  };
+  let dead = Particle {
+    alive: false,
+    ..particle
+  };
+  let mut particles = surface
+    .new_buffer_from_vec(
+      vec![particle.std430_encode(), dead.std430_encode()],
+      BufferUsage::Dynamic,
+    )
+    .unwrap();
+
+  surface
+    .dispatch::<ComputeError, _, _>(&mut program, [2, 1, 1], |pipeline, mut iface, uni| {
+      let bound = pipeline.bind_storage_buffer(&mut particles)?;
+      iface.set(&uni.particles, bound.binding());
+      Ok(())
+    })
+    .unwrap();
+
+  let particles: Vec<_> = particles
+    .slice()
+    .unwrap()
+    .iter()
+    .map(|&p| Particle::std430_decode(p))
+    .collect();
+
+  assert_eq!(
+    particles,
+    [
+      Particle {
+        position: [3., 0., 3.],
+        ..particle
+      },
+      dead
+    ]
+  );
+}
+
+#[test]
+fn render_multisample_framebuffer() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_multisample_framebuffer::<NormRGBA8UI, ()>([4, 4], 4, 0, Sampler::default())
+    .unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 255, 0, 255].repeat(4 * 4));
+}
+
+#[test]
+fn render_to_renderbuffers() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Renderbuffer<GL45, Depth32F>>(
+      [2, 2],
+      0,
+      Sampler::default(),
+    )
+    .unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+}
+
+#[test]
+fn render_with_stencil_renderbuffer() {
+  let mut surface = HeadlessSurface::new_gl45([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Renderbuffer<GL45, Depth32FStencil8>>(
+      [2, 2],
+      0,
+      Sampler::default(),
+    )
+    .unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  // without a stencil attachment, the stencil test would always pass
+  let render_state =
+    RenderState::default().set_stencil_test(StencilTest::new(StencilComparison::NotEqual, 1, 0xFF));
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default()
+        .set_clear_color([1., 0., 0., 1.])
+        .set_clear_stencil(1),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&render_state, |mut tess_gate| tess_gate.render(&tess))
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [255, 0, 0, 255].repeat(2 * 2));
+}
+
+#[test]
+fn upload_compressed_levels_and_faces() {
+  let mut surface = HeadlessSurface::new_gl45([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), TextureInterface>()
+    .from_strings(VS, None, None, LEVEL_FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  // solid BC1 blocks (RGB565 endpoints, all indices to the first one)
+  let red_block = [0x00, 0xF8, 0x00, 0xF8, 0, 0, 0, 0];
+  let green_block = [0xE0, 0x07, 0xE0, 0x07, 0, 0, 0, 0];
+
+  let mut texture = surface
+    .new_texture::<Dim2, BC1>([8, 8], 1, Sampler::default())
+    .unwrap();
+
+  texture
+    .upload_compressed_part(0, [0, 0], [8, 4], &red_block.repeat(2))
+    .unwrap();
+  texture
+    .upload_compressed_part(0, [0, 4], [8, 4], &red_block.repeat(2))
+    .unwrap();
+  texture.upload_compressed(1, &green_block).unwrap();
+
+  let mut cubemap = surface
+    .new_texture::<Cubemap, BC1>(4, 0, Sampler::default())
+    .unwrap();
+
+  cubemap
+    .upload_compressed(0, &green_block.repeat(6))
+    .unwrap();
+  cubemap
+    .upload_compressed_part(0, ([0, 0], CubeFace::NegativeZ), 4, &red_block)
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |pipeline, mut shd_gate| {
+        let bound = pipeline.bind_texture(&mut texture)?;
+
+        shd_gate.shade(&mut program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.tex, bound.binding());
+
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+}
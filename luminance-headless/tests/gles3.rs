@@ -0,0 +1,403 @@
+use luminance::context::GraphicsContext as _;
+use luminance::pipeline::{PipelineError, PipelineState};
+use luminance::pixel::{
+  CompressionFamily, Depth24Stencil8, Depth32F, NormR16UI, NormRGBA8UI, ETC2, RGBA32F,
+};
+use luminance::query::{QueryError, QueryType};
+use luminance::render_state::RenderState;
+use luminance::renderbuffer::Renderbuffer;
+use luminance::shader::{Stage, StageError, StageType};
+use luminance::stencil::{StencilComparison, StencilTest};
+use luminance::tess::{Mode, TessError};
+use luminance::texture::{Dim1, Dim2, Dim2Array, GenMipmaps, Sampler, TextureError, Wrap};
+use luminance_gl::GLES3;
+use luminance_headless::HeadlessSurface;
+use std::error::Error;
+
+const VS: &str = "
+const vec2[4] POSITIONS = vec2[](vec2(-1., -1.), vec2(1., -1.), vec2(-1., 1.), vec2(1., 1.));
+
+void main() {
+  gl_Position = vec4(POSITIONS[gl_VertexID], 0., 1.);
+}";
+
+const FS: &str = "
+out vec4 frag;
+
+void main() {
+  frag = vec4(0., 0., 1., 1.);
+}";
+
+#[test]
+fn render_attributeless_quad() {
+  let mut surface = HeadlessSurface::new_gles3([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  assert_eq!(texels, [0, 0, 255, 255].repeat(2 * 2));
+}
+
+#[test]
+fn query_any_samples_passed() {
+  let mut surface = HeadlessSurface::new_gles3([2, 2]).unwrap();
+  let back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let mut any_samples = surface.new_query(QueryType::AnySamplesPassed).unwrap();
+
+  let render: Result<(), Box<dyn Error>> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.query(&mut any_samples, |rdr_gate| {
+            rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+              tess_gate.render(&tess)
+            })
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let result = loop {
+    if let Some(result) = any_samples.try_get().unwrap() {
+      break result;
+    }
+  };
+
+  assert_eq!(result, 1);
+}
+
+#[test]
+fn read_back_textures() {
+  let mut surface = HeadlessSurface::new_gles3([1, 1]).unwrap();
+
+  let mut texture = surface
+    .new_texture::<Dim2, RGBA32F>([2, 1], 0, Sampler::default())
+    .unwrap();
+  let texels = [1., 2., 3., 4., 5., 6., 7., 8.];
+  texture.upload_raw(GenMipmaps::No, &texels).unwrap();
+
+  assert_eq!(texture.get_raw_texels().unwrap(), texels);
+
+  let mut layers = surface
+    .new_texture::<Dim2Array, NormRGBA8UI>(([1, 1], 2), 0, Sampler::default())
+    .unwrap();
+  let texels = [1, 2, 3, 4, 5, 6, 7, 8];
+  layers.upload_raw(GenMipmaps::No, &texels).unwrap();
+
+  assert_eq!(layers.get_raw_texels().unwrap(), texels);
+
+  let depth = surface
+    .new_texture::<Dim2, Depth32F>([1, 1], 0, Sampler::default())
+    .unwrap();
+
+  assert!(depth.get_raw_texels().is_err());
+}
+
+#[test]
+fn read_back_textures_asynchronously() {
+  let mut surface = HeadlessSurface::new_gles3([1, 1]).unwrap();
+
+  let mut layers = surface
+    .new_texture::<Dim2Array, NormRGBA8UI>(([1, 1], 2), 0, Sampler::default())
+    .unwrap();
+  let texels = [1, 2, 3, 4, 5, 6, 7, 8];
+  layers.upload_raw(GenMipmaps::No, &texels).unwrap();
+
+  let mut readback = layers.get_raw_texels_async().unwrap();
+  let mut fence = surface.new_fence().unwrap();
+
+  while !fence.is_signaled() {}
+
+  assert_eq!(readback.try_get().unwrap(), Some(texels.to_vec()));
+
+  let depth = surface
+    .new_texture::<Dim2, Depth32F>([1, 1], 0, Sampler::default())
+    .unwrap();
+
+  assert!(depth.get_raw_texels_async().is_err());
+}
+
+#[test]
+fn reject_unsupported_features() {
+  let mut surface = HeadlessSurface::new_gles3([1, 1]).unwrap();
+
+  assert!(matches!(
+    Stage::new(&mut surface, StageType::GeometryShader, ""),
+    Err(StageError::UnsupportedType(StageType::GeometryShader))
+  ));
+
+  assert!(matches!(
+    Stage::new(&mut surface, StageType::ComputeShader, ""),
+    Err(StageError::UnsupportedType(StageType::ComputeShader))
+  ));
+
+  for &ty in &[
+    QueryType::TimeElapsed,
+    QueryType::Timestamp,
+    QueryType::SamplesPassed,
+    QueryType::PrimitivesGenerated,
+  ] {
+    assert!(matches!(
+      surface.new_query(ty),
+      Err(QueryError::UnsupportedType(t)) if t == ty
+    ));
+  }
+
+  assert!(matches!(
+    surface.new_texture::<Dim1, NormRGBA8UI>(1, 0, Sampler::default()),
+    Err(TextureError::TextureStorageCreationFailed(_))
+  ));
+
+  assert!(matches!(
+    surface.new_texture::<Dim2, NormR16UI>([1, 1], 0, Sampler::default()),
+    Err(TextureError::UnsupportedPixelFormat(_))
+  ));
+
+  assert!(matches!(
+    surface.new_texture::<Dim2, NormRGBA8UI>(
+      [1, 1],
+      0,
+      Sampler {
+        lod_bias: 1.,
+        ..Sampler::default()
+      }
+    ),
+    Err(TextureError::TextureStorageCreationFailed(_))
+  ));
+
+  assert!(matches!(
+    surface.new_texture::<Dim2, NormRGBA8UI>(
+      [1, 1],
+      0,
+      Sampler {
+        wrap_s: Wrap::ClampToBorder,
+        ..Sampler::default()
+      }
+    ),
+    Err(TextureError::TextureStorageCreationFailed(_))
+  ));
+
+  assert!(matches!(
+    surface
+      .new_tess()
+      .set_vertex_nb(3)
+      .set_mode(Mode::Patch(3))
+      .build(),
+    Err(TessError::ForbiddenPrimitiveMode(Mode::Patch(3)))
+  ));
+
+  assert!(matches!(
+    surface
+      .new_tess()
+      .set_vertex_nb(3)
+      .set_indices(vec![0u16, 1, 2])
+      .set_mode(Mode::TriangleStrip)
+      .set_primitive_restart_index(0)
+      .build(),
+    Err(TessError::CannotCreate(_))
+  ));
+
+  assert!(surface
+    .new_tess()
+    .set_vertex_nb(3)
+    .set_indices(vec![0u16, 1, 2])
+    .set_mode(Mode::TriangleStrip)
+    .set_primitive_restart_index(u16::MAX)
+    .build()
+    .is_ok());
+}
+
+#[test]
+fn render_multisample_framebuffer() {
+  let mut surface = HeadlessSurface::new_gles3([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_multisample_framebuffer::<NormRGBA8UI, Depth32F>([4, 4], 4, 0, Sampler::default())
+    .unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 0, 255, 255].repeat(4 * 4));
+}
+
+#[test]
+fn render_to_renderbuffers() {
+  let mut surface = HeadlessSurface::new_gles3([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Renderbuffer<GLES3, Depth32F>>(
+      [2, 2],
+      0,
+      Sampler::default(),
+    )
+    .unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 0, 255, 255].repeat(2 * 2));
+}
+
+#[test]
+fn render_with_stencil() {
+  let mut surface = HeadlessSurface::new_gles3([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Depth24Stencil8>([2, 2], 0, Sampler::default())
+    .unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render_state =
+    RenderState::default().set_stencil_test(StencilTest::new(StencilComparison::Equal, 1, 0xFF));
+
+  // only the framebuffer cleared with the reference value gets drawn to
+  for (clear_stencil, expected) in [(0, [0, 0, 0, 255]), (1, [0, 0, 255, 255])] {
+    let render: Result<(), PipelineError> = surface
+      .new_pipeline_gate()
+      .pipeline(
+        &framebuffer,
+        &PipelineState::default().set_clear_stencil(clear_stencil),
+        |_, mut shd_gate| {
+          shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+            rdr_gate.render(&render_state, |mut tess_gate| tess_gate.render(&tess))
+          })
+        },
+      )
+      .into_result();
+    render.unwrap();
+
+    let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+    assert_eq!(texels, expected.repeat(2 * 2));
+  }
+}
+
+#[test]
+fn upload_etc2_texture() {
+  let mut surface = HeadlessSurface::new_gles3([1, 1]).unwrap();
+
+  // ETC2 is core in OpenGL ES 3.0
+  assert!(surface.supports_compression(CompressionFamily::ETC2));
+
+  let mut texture = surface
+    .new_texture::<Dim2Array, ETC2>(([4, 4], 3), 2, Sampler::default())
+    .unwrap();
+
+  assert!(texture.upload_compressed(0, &[0; 3 * 8]).is_ok());
+  assert!(texture.upload_compressed(2, &[0; 3 * 8]).is_ok());
+  assert!(matches!(
+    texture.upload_compressed(3, &[0; 3 * 8]),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+}
@@ -0,0 +1,1216 @@
+use image::{DynamicImage, RgbaImage};
+use luminance::backend::query::Query as QueryBackend;
+use luminance::backend::readback::Readback as ReadbackBackend;
+use luminance::buffer::{BufferUsage, PreserveContent};
+use luminance::context::GraphicsContext as _;
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect};
+use luminance::pipeline::{PipelineError, PipelineState, TextureBinding};
+use luminance::pixel::{
+  CompressionFamily, Depth24Stencil8, Depth32F, Half, NormRGBA8UI, NormUnsigned, BC1, RGB10A2,
+  RGB565, RGB9E5, RGBA16F,
+};
+use luminance::query::{Query, QueryError, QueryType};
+use luminance::readback::Readback;
+use luminance::render_state::RenderState;
+use luminance::renderbuffer::Renderbuffer;
+use luminance::shader::{Stage, StageError, StageType, Uniform};
+use luminance::stencil::{StencilComparison, StencilOp, StencilOperations, StencilTest};
+use luminance::tess::Mode;
+use luminance::texture::{
+  Cubemap, Dim2, GenMipmaps, MagFilter, MinFilter, Sampler, Texture, TextureError, Wrap,
+};
+use luminance::UniformInterface;
+use luminance_gl::GL33;
+use luminance_headless::HeadlessSurface;
+
+const VS: &str = "
+const vec2[4] POSITIONS = vec2[](vec2(-1., -1.), vec2(1., -1.), vec2(-1., 1.), vec2(1., 1.));
+
+void main() {
+  gl_Position = vec4(POSITIONS[gl_VertexID], 0., 1.);
+}";
+
+const FS: &str = "
+out vec4 frag;
+
+void main() {
+  frag = vec4(0., 1., 0., 1.);
+}";
+
+const TEXTURE_FS: &str = "
+uniform sampler2D tex;
+
+out vec4 frag;
+
+void main() {
+  frag = texture(tex, gl_FragCoord.xy / vec2(textureSize(tex, 0)));
+}";
+
+#[derive(UniformInterface)]
+struct TextureInterface {
+  tex: Uniform<TextureBinding<Dim2, NormUnsigned>>,
+}
+
+#[derive(Debug)]
+enum RenderError {
+  Pipeline(PipelineError),
+  Query(QueryError),
+}
+
+impl From<PipelineError> for RenderError {
+  fn from(e: PipelineError) -> Self {
+    RenderError::Pipeline(e)
+  }
+}
+
+impl From<QueryError> for RenderError {
+  fn from(e: QueryError) -> Self {
+    RenderError::Query(e)
+  }
+}
+
+// busy-wait for the result of a query
+fn wait_result<B>(query: &mut Query<B>) -> u64
+where
+  B: QueryBackend,
+{
+  loop {
+    if let Some(result) = query.try_get().unwrap() {
+      return result;
+    }
+  }
+}
+
+// busy-wait for the data of a readback
+fn wait_data<B, T>(readback: &mut Readback<B, T>) -> Vec<T>
+where
+  B: ReadbackBackend<T>,
+  T: Copy,
+{
+  loop {
+    if let Some(data) = readback.try_get().unwrap() {
+      return data;
+    }
+  }
+}
+
+#[test]
+fn clear_back_buffer() {
+  let mut surface = HeadlessSurface::new_gl33([4, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default().set_clear_color([1., 0., 0., 1.]),
+      |_, _| Ok(()),
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  assert_eq!(texels, [255, 0, 0, 255].repeat(4 * 2));
+}
+
+#[test]
+fn render_attributeless_quad() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+}
+
+#[test]
+fn reject_compute_stages() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+
+  assert!(matches!(
+    Stage::new(&mut surface, StageType::ComputeShader, ""),
+    Err(StageError::UnsupportedType(StageType::ComputeShader))
+  ));
+}
+
+#[test]
+fn query_render() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+  let back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let mut time_elapsed = surface.new_query(QueryType::TimeElapsed).unwrap();
+  let mut timestamp = surface.new_query(QueryType::Timestamp).unwrap();
+  let mut primitives = surface.new_query(QueryType::PrimitivesGenerated).unwrap();
+  let mut samples = surface.new_query(QueryType::SamplesPassed).unwrap();
+  let mut any_samples = surface.new_query(QueryType::AnySamplesPassed).unwrap();
+
+  assert_eq!(samples.query_type(), QueryType::SamplesPassed);
+  assert_eq!(samples.try_get(), Ok(None));
+
+  let mut pipeline_gate = surface.new_pipeline_gate();
+  let render: Result<(), RenderError> = pipeline_gate
+    .query(&mut time_elapsed, |pipeline_gate| {
+      pipeline_gate.query(&mut timestamp, |pipeline_gate| {
+        pipeline_gate.pipeline(
+          &back_buffer,
+          &PipelineState::default(),
+          |_, mut shd_gate| {
+            shd_gate.query(&mut primitives, |shd_gate| {
+              shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+                rdr_gate.query(&mut samples, |rdr_gate| {
+                  rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+                    tess_gate.render(&tess)
+                  })
+                })
+              })
+            })
+          },
+        )
+      })
+    })
+    .into_result();
+  render.unwrap();
+
+  // occlusion queries can’t be nested, so any samples passed gets its own pipeline
+  let render: Result<(), RenderError> = pipeline_gate
+    .query(&mut any_samples, |pipeline_gate| {
+      pipeline_gate.pipeline(
+        &back_buffer,
+        &PipelineState::default(),
+        |_, mut shd_gate| {
+          shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+            rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+              tess_gate.render(&tess)
+            })
+          })
+        },
+      )
+    })
+    .into_result();
+  render.unwrap();
+
+  assert_eq!(wait_result(&mut primitives), 2);
+  assert_eq!(wait_result(&mut samples), 2 * 2);
+  assert_eq!(wait_result(&mut any_samples), 1);
+  assert!(wait_result(&mut timestamp) > 0);
+  wait_result(&mut time_elapsed);
+}
+
+#[test]
+fn reject_nested_queries_of_the_same_type() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+  let back_buffer = surface.back_buffer().unwrap();
+
+  let mut outer = surface.new_query(QueryType::SamplesPassed).unwrap();
+  let mut inner = surface.new_query(QueryType::SamplesPassed).unwrap();
+  let mut any_samples = surface.new_query(QueryType::AnySamplesPassed).unwrap();
+  let buffer = surface
+    .new_buffer_from_vec(vec![0u32; 4], BufferUsage::Static)
+    .unwrap();
+
+  let mut pipeline_gate = surface.new_pipeline_gate();
+  let render: Result<(), RenderError> = pipeline_gate
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.query(&mut outer, |shd_gate| {
+          shd_gate.query(&mut inner, |_| Ok(()))
+        })
+      },
+    )
+    .into_result();
+
+  assert!(matches!(
+    render,
+    Err(RenderError::Query(QueryError::AlreadyActive(
+      QueryType::SamplesPassed
+    )))
+  ));
+
+  // occlusion queries share the same slot
+  let render: Result<(), RenderError> = pipeline_gate
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.query(&mut outer, |shd_gate| {
+          shd_gate.query(&mut any_samples, |_| Ok(()))
+        })
+      },
+    )
+    .into_result();
+
+  assert!(matches!(
+    render,
+    Err(RenderError::Query(QueryError::AlreadyActive(
+      QueryType::AnySamplesPassed
+    )))
+  ));
+
+  // the outer query still ended
+  assert_eq!(wait_result(&mut outer), 0);
+  assert_eq!(inner.try_get(), Ok(None));
+  assert_eq!(any_samples.try_get(), Ok(None));
+
+  // errors of the queried section are returned as they are, and the query still ends
+  let render: Result<(), RenderError> = pipeline_gate
+    .query(&mut any_samples, |pipeline_gate| {
+      pipeline_gate.pipeline(&back_buffer, &PipelineState::default(), |pipeline, _| {
+        pipeline.bind_buffer_range(&buffer, 2..6)?;
+        Ok(())
+      })
+    })
+    .into_result();
+
+  assert!(matches!(
+    render,
+    Err(RenderError::Pipeline(
+      PipelineError::BufferRangeOutOfBounds {
+        start: 2,
+        end: 6,
+        buffer_len: 4
+      }
+    ))
+  ));
+  assert_eq!(wait_result(&mut any_samples), 0);
+}
+
+#[test]
+fn edit_buffer_ranges() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+
+  let mut buffer = surface
+    .new_buffer_from_vec(vec![0u32; 6], BufferUsage::Dynamic)
+    .unwrap();
+  let other = surface
+    .new_buffer_from_vec(vec![1u32, 2, 3, 4], BufferUsage::Static)
+    .unwrap();
+
+  buffer.write_range(1, &[5, 6]).unwrap();
+  buffer.copy_from(&other, 2..4, 4).unwrap();
+
+  assert_eq!(buffer.read_range(0..6).unwrap(), [0, 5, 6, 0, 3, 4]);
+  assert_eq!(buffer.whole(), [0, 5, 6, 0, 3, 4]);
+  assert!(buffer.read_range(6..6).unwrap().is_empty());
+}
+
+#[test]
+fn render_resized_tess() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let mut tess = surface
+    .new_tess()
+    .set_indices(vec![0u32, 1, 2])
+    .set_mode(Mode::Triangle)
+    .build()
+    .unwrap();
+
+  // grow the index storage and complete the quad with a second triangle
+  tess.resize_indices(6, PreserveContent::Yes).unwrap();
+  assert_eq!(tess.vert_nb(), 6);
+  tess.indices_mut().unwrap()[3..].copy_from_slice(&[2, 1, 3]);
+  assert_eq!(&tess.indices().unwrap()[..], [0, 1, 2, 2, 1, 3]);
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+}
+
+#[test]
+fn read_back_asynchronously() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let mut texels = back_buffer.color_slot().get_raw_texels_async().unwrap();
+  let mut fence = surface.new_fence().unwrap();
+
+  let buffer = surface
+    .new_buffer_from_vec(vec![1u32, 2, 3], BufferUsage::ReadBack)
+    .unwrap();
+  let mut values = buffer.whole_async().unwrap();
+  let empty = surface
+    .new_buffer_from_vec(Vec::<u32>::new(), BufferUsage::ReadBack)
+    .unwrap();
+  let mut no_values = empty.whole_async().unwrap();
+
+  while !fence.is_signaled() {}
+
+  assert_eq!(wait_data(&mut texels), [0, 255, 0, 255].repeat(2 * 2));
+  assert_eq!(wait_data(&mut values), [1, 2, 3]);
+  assert_eq!(wait_data(&mut no_values), []);
+
+  // the data can be retrieved several times
+  assert_eq!(values.try_get().unwrap(), Some(vec![1, 2, 3]));
+}
+
+#[test]
+fn blit_to_back_buffer() {
+  let mut surface = HeadlessSurface::new_gl33([4, 4]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Depth32F>([2, 2], 0, Sampler::default())
+    .unwrap();
+  let colors = [
+    [255, 0, 0, 255],
+    [0, 255, 0, 255],
+    [0, 0, 255, 255],
+    [255, 255, 255, 255],
+  ];
+  framebuffer
+    .color_slot()
+    .upload_raw(GenMipmaps::No, &colors.concat())
+    .unwrap();
+
+  framebuffer
+    .blit_to(
+      &mut back_buffer,
+      BlitRect::whole([2, 2]),
+      BlitRect::whole([4, 4]),
+      BlitMask::COLOR,
+      BlitFilter::Nearest,
+    )
+    .unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  for y in 0..4 {
+    for x in 0..4 {
+      let i = (y * 4 + x) * 4;
+      assert_eq!(
+        texels[i..i + 4],
+        colors[y / 2 * 2 + x / 2],
+        "texel ({}, {})",
+        x,
+        y
+      );
+    }
+  }
+
+  // depth only framebuffers can be blitted to as well
+  let mut depth_only = surface
+    .new_framebuffer::<Dim2, (), Depth32F>([2, 2], 0, Sampler::default())
+    .unwrap();
+
+  framebuffer
+    .blit_to(
+      &mut depth_only,
+      BlitRect::whole([2, 2]),
+      BlitRect::whole([2, 2]),
+      BlitMask::DEPTH,
+      BlitFilter::Nearest,
+    )
+    .unwrap();
+}
+
+#[test]
+fn copy_textures() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+
+  let mut src = surface
+    .new_texture::<Dim2, NormRGBA8UI>([2, 2], 0, Sampler::default())
+    .unwrap();
+  src
+    .upload_raw(
+      GenMipmaps::No,
+      &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+    )
+    .unwrap();
+
+  let mut dst = surface
+    .new_texture::<Dim2, NormRGBA8UI>([3, 1], 0, Sampler::default())
+    .unwrap();
+  dst.clear(GenMipmaps::No, (0, 0, 0, 0)).unwrap();
+  dst
+    .copy_from(GenMipmaps::No, &src, [0, 1], [1, 0], [2, 1])
+    .unwrap();
+
+  assert_eq!(
+    dst.get_raw_texels().unwrap(),
+    [0, 0, 0, 0, 9, 10, 11, 12, 13, 14, 15, 16]
+  );
+}
+
+#[test]
+fn render_multisample_framebuffer() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_multisample_framebuffer::<NormRGBA8UI, Depth32F>([4, 4], 4, 0, Sampler::default())
+    .unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  // the slots hold the resolved samples as soon as the pipeline is over
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 255, 0, 255].repeat(4 * 4));
+
+  let depths = framebuffer.depth_slot().get_raw_texels().unwrap();
+  assert_eq!(depths, [0.5; 4 * 4]);
+}
+
+#[test]
+fn render_to_renderbuffers() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  // the depth test still works with a depth renderbuffer
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Renderbuffer<GL33, Depth32F>>(
+      [2, 2],
+      0,
+      Sampler::default(),
+    )
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+  assert_eq!(framebuffer.depth_slot().size(), [2, 2]);
+
+  // color renderbuffers cannot be read back, but they can be blitted
+  let color_only = surface
+    .new_framebuffer::<Dim2, Renderbuffer<GL33, NormRGBA8UI>, ()>([2, 2], 0, Sampler::default())
+    .unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(&color_only, &PipelineState::default(), |_, mut shd_gate| {
+      shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+        rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+          tess_gate.render(&tess)
+        })
+      })
+    })
+    .into_result();
+  render.unwrap();
+
+  color_only
+    .blit_to(
+      &mut back_buffer,
+      BlitRect::whole([2, 2]),
+      BlitRect::whole([2, 2]),
+      BlitMask::COLOR,
+      BlitFilter::Nearest,
+    )
+    .unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+}
+
+#[test]
+fn render_with_stencil() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Depth24Stencil8>([4, 4], 0, Sampler::default())
+    .unwrap();
+
+  // the mask covers the left half of the framebuffer in red
+  let mut mask_program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(
+      "
+const vec2[4] POSITIONS = vec2[](vec2(-1., -1.), vec2(0., -1.), vec2(-1., 1.), vec2(0., 1.));
+
+void main() {
+  gl_Position = vec4(POSITIONS[gl_VertexID], 0., 1.);
+}",
+      None,
+      None,
+      "
+out vec4 frag;
+
+void main() {
+  frag = vec4(1., 0., 0., 1.);
+}",
+    )
+    .unwrap()
+    .ignore_warnings();
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  // write 1 to the stencil of the left half, then draw only where the stencil isn’t 1
+  let mask_state = RenderState::default()
+    .set_depth_test(None)
+    .set_stencil_test(StencilTest::new(StencilComparison::Always, 1, 0xFF))
+    .set_stencil_operations(StencilOperations::new(
+      StencilOp::Keep,
+      StencilOp::Keep,
+      StencilOp::Replace,
+    ));
+  let outline_state = RenderState::default()
+    .set_depth_test(None)
+    .set_stencil_test(StencilTest::new(StencilComparison::NotEqual, 1, 0xFF));
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default().set_clear_stencil(2),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut mask_program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&mask_state, |mut tess_gate| tess_gate.render(&tess))
+        })?;
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&outline_state, |mut tess_gate| tess_gate.render(&tess))
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  let depth_stencil = framebuffer.depth_slot().get_raw_texels().unwrap();
+
+  for y in 0..4 {
+    for x in 0..4 {
+      let i = y * 4 + x;
+      let (expected, stencil) = if x < 2 {
+        ([255, 0, 0, 255], 1)
+      } else {
+        ([0, 255, 0, 255], 2)
+      };
+      assert_eq!(texels[i * 4..i * 4 + 4], expected, "texel ({}, {})", x, y);
+      assert_eq!(depth_stencil[i] & 0xFF, stencil, "stencil ({}, {})", x, y);
+    }
+  }
+}
+
+#[test]
+fn read_back_half_float_and_packed_texels() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+
+  let half_texels = [0.5, -2., 65504., 1. / 3.].map(Half::from_f32);
+  let mut half = surface
+    .new_texture::<Dim2, RGBA16F>([1, 1], 0, Sampler::default())
+    .unwrap();
+  half.upload_raw(GenMipmaps::No, &half_texels).unwrap();
+  assert_eq!(half.get_raw_texels().unwrap(), half_texels);
+
+  // 1.5 in red (mantissa 384, exponent 16), 0.75 in green (192) and 0 in blue
+  let shared_exponent = 16 << 27 | 192 << 9 | 384;
+  let mut rgb9e5 = surface
+    .new_texture::<Dim2, RGB9E5>([1, 1], 0, Sampler::default())
+    .unwrap();
+  rgb9e5
+    .upload_raw(GenMipmaps::No, &[shared_exponent])
+    .unwrap();
+  assert_eq!(rgb9e5.get_raw_texels().unwrap(), [shared_exponent]);
+
+  let mut rgb565 = surface
+    .new_texture::<Dim2, RGB565>([2, 1], 0, Sampler::default())
+    .unwrap();
+  rgb565
+    .upload_raw(GenMipmaps::No, &[0xF800, 0x07E0])
+    .unwrap();
+  assert_eq!(rgb565.get_raw_texels().unwrap(), [0xF800, 0x07E0]);
+}
+
+#[test]
+fn render_to_half_float_and_packed_framebuffers() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let mut half = surface
+    .new_framebuffer::<Dim2, RGBA16F, ()>([2, 2], 0, Sampler::default())
+    .unwrap();
+  let mut rgb10a2 = surface
+    .new_framebuffer::<Dim2, RGB10A2, ()>([2, 2], 0, Sampler::default())
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(&half, &PipelineState::default(), |_, mut shd_gate| {
+      shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+        rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+          tess_gate.render(&tess)
+        })
+      })
+    })
+    .into_result();
+  render.unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(&rgb10a2, &PipelineState::default(), |_, mut shd_gate| {
+      shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+        rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+          tess_gate.render(&tess)
+        })
+      })
+    })
+    .into_result();
+  render.unwrap();
+
+  let green = [0., 1., 0., 1.].map(Half::from_f32);
+  assert_eq!(
+    half.color_slot().get_raw_texels().unwrap(),
+    green.repeat(2 * 2)
+  );
+
+  // green on 10 bits and alpha on 2 bits
+  assert_eq!(
+    rgb10a2.color_slot().get_raw_texels().unwrap(),
+    [0b11 << 30 | 0x3FF << 10; 2 * 2]
+  );
+}
+
+#[test]
+fn render_compressed_texture() {
+  let mut surface = HeadlessSurface::new_gl33([4, 4]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  assert!(surface.supports_compression(CompressionFamily::S3TC));
+
+  let mut program = surface
+    .new_shader_program::<(), (), TextureInterface>()
+    .from_strings(VS, None, None, TEXTURE_FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let sampler = Sampler {
+    min_filter: MinFilter::Nearest,
+    mag_filter: MagFilter::Nearest,
+    ..Sampler::default()
+  };
+  let mut texture = surface
+    .new_texture::<Dim2, BC1>([4, 4], 0, sampler)
+    .unwrap();
+
+  // a single block of solid red (RGB565 endpoints, all indices to the first one)
+  let red_block = [0x00, 0xF8, 0x00, 0xF8, 0, 0, 0, 0];
+  texture.upload_compressed(0, &red_block).unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |pipeline, mut shd_gate| {
+        let bound = pipeline.bind_texture(&mut texture)?;
+
+        shd_gate.shade(&mut program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.tex, bound.binding());
+
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+
+  assert_eq!(texels, [255, 0, 0, 255].repeat(4 * 4));
+}
+
+#[test]
+fn render_image_texture() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), TextureInterface>()
+    .from_strings(VS, None, None, TEXTURE_FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  // a red top row and a blue bottom row
+  let image = RgbaImage::from_raw(
+    2,
+    2,
+    [[255, 0, 0, 255].repeat(2), [0, 0, 255, 255].repeat(2)].concat(),
+  )
+  .unwrap();
+
+  let sampler = Sampler {
+    min_filter: MinFilter::Nearest,
+    mag_filter: MagFilter::Nearest,
+    ..Sampler::default()
+  };
+  let mut texture = Texture::<_, Dim2, NormRGBA8UI>::from_image(
+    &mut surface,
+    &DynamicImage::ImageRgba8(image.clone()),
+    0,
+    sampler,
+  )
+  .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |pipeline, mut shd_gate| {
+        let bound = pipeline.bind_texture(&mut texture)?;
+
+        shd_gate.shade(&mut program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.tex, bound.binding());
+
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  // the bottom row is the first one of the framebuffer
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels[..8], [0, 0, 255, 255].repeat(2)[..]);
+
+  assert_eq!(back_buffer.color_slot().to_image().unwrap(), image);
+  assert_eq!(
+    back_buffer
+      .to_image::<_, NormRGBA8UI>(&mut surface)
+      .unwrap(),
+    image
+  );
+}
+
+#[test]
+fn generate_framebuffer_mipmaps() {
+  let mut surface = HeadlessSurface::new_gl33([4, 4]).unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings(VS, None, None, FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, ()>([4, 4], 2, Sampler::default())
+    .unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  // only the base level is rendered to; the other ones are downsampled from it
+  framebuffer.color_slot().generate_mipmaps().unwrap();
+
+  let texels = framebuffer.color_slot().get_level_raw_texels(1).unwrap();
+  assert_eq!(texels, [0, 255, 0, 255].repeat(2 * 2));
+
+  let texels = framebuffer.color_slot().get_level_raw_texels(2).unwrap();
+  assert_eq!(texels, [0, 255, 0, 255]);
+}
+
+#[test]
+fn sample_mipmap_ranges() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), TextureInterface>()
+    .from_strings(VS, None, None, TEXTURE_FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let red = [255, 0, 0, 255];
+  let blue = [0, 0, 255, 255];
+
+  // the 2×2 base level is red and the 1×1 mipmap blue; the texture is sampled 1:1
+  let samplers = [
+    (Sampler::default(), red),
+    (
+      Sampler {
+        base_level: 1,
+        ..Sampler::default()
+      },
+      blue,
+    ),
+    (
+      Sampler {
+        lod_bias: 1.,
+        ..Sampler::default()
+      },
+      blue,
+    ),
+    (
+      Sampler {
+        lod_bias: 1.,
+        max_lod: 0.,
+        ..Sampler::default()
+      },
+      red,
+    ),
+    (
+      Sampler {
+        lod_bias: 1.,
+        max_level: Some(0),
+        ..Sampler::default()
+      },
+      red,
+    ),
+  ];
+
+  for (sampler, expected) in samplers.iter() {
+    let sampler = Sampler {
+      min_filter: MinFilter::NearestMipmapNearest,
+      mag_filter: MagFilter::Nearest,
+      ..*sampler
+    };
+    let mut texture = surface
+      .new_texture::<Dim2, NormRGBA8UI>([2, 2], 1, sampler)
+      .unwrap();
+    texture.upload_level_raw(0, &red.repeat(2 * 2)).unwrap();
+    texture.upload_level_raw(1, &blue).unwrap();
+
+    let render: Result<(), PipelineError> = surface
+      .new_pipeline_gate()
+      .pipeline(
+        &back_buffer,
+        &PipelineState::default(),
+        |pipeline, mut shd_gate| {
+          let bound = pipeline.bind_texture(&mut texture)?;
+
+          shd_gate.shade(&mut program, |mut iface, uni, mut rdr_gate| {
+            iface.set(&uni.tex, bound.binding());
+
+            rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+              tess_gate.render(&tess)
+            })
+          })
+        },
+      )
+      .into_result();
+    render.unwrap();
+
+    let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+    assert_eq!(texels, expected.repeat(2 * 2), "{:?}", sampler);
+  }
+
+  assert!(matches!(
+    surface.new_texture::<Dim2, NormRGBA8UI>(
+      [2, 2],
+      1,
+      Sampler {
+        base_level: 2,
+        ..Sampler::default()
+      }
+    ),
+    Err(TextureError::TextureStorageCreationFailed(_))
+  ));
+}
+
+#[test]
+fn sample_border_colors() {
+  let mut surface = HeadlessSurface::new_gl33([2, 2]).unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  let mut program = surface
+    .new_shader_program::<(), (), TextureInterface>()
+    .from_strings(VS, None, None, TEXTURE_FS)
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(4)
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  let red = [255, 0, 0, 255];
+  let blue = [0, 0, 255, 255];
+
+  // the 1×1 texture only covers the bottom-left texel of the back buffer
+  let sampler = Sampler {
+    wrap_s: Wrap::ClampToBorder,
+    wrap_t: Wrap::ClampToBorder,
+    min_filter: MinFilter::Nearest,
+    mag_filter: MagFilter::Nearest,
+    border_color: [0., 0., 1., 1.],
+    ..Sampler::default()
+  };
+  let mut texture = surface
+    .new_texture::<Dim2, NormRGBA8UI>([1, 1], 0, sampler)
+    .unwrap();
+  texture.upload_raw(GenMipmaps::No, &red).unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |pipeline, mut shd_gate| {
+        let bound = pipeline.bind_texture(&mut texture)?;
+
+        shd_gate.shade(&mut program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.tex, bound.binding());
+
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+  render.unwrap();
+
+  let texels = back_buffer.color_slot().get_raw_texels().unwrap();
+  assert_eq!(texels, [red, blue, blue, blue].concat());
+
+  // anisotropy is clamped to what the context supports
+  let cubemap = surface.new_texture::<Cubemap, NormRGBA8UI>(
+    2,
+    0,
+    Sampler {
+      max_anisotropy: 16.,
+      seamless_cubemap: true,
+      ..Sampler::default()
+    },
+  );
+  assert!(cubemap.is_ok());
+}
+
+#[test]
+fn reject_invalid_compressed_uploads() {
+  let mut surface = HeadlessSurface::new_gl33([1, 1]).unwrap();
+
+  let mut texture = surface
+    .new_texture::<Dim2, BC1>([8, 6], 1, Sampler::default())
+    .unwrap();
+
+  // regions must be aligned on blocks, but for the edges of the level
+  assert!(matches!(
+    texture.upload_compressed_part(0, [2, 0], [4, 4], &[0; 8]),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+  assert!(texture
+    .upload_compressed_part(0, [4, 4], [4, 2], &[0; 8])
+    .is_ok());
+
+  // a 4×3 level is covered by a single block
+  assert!(matches!(
+    texture.upload_compressed(1, &[0; 16]),
+    Err(TextureError::NotEnoughPixels { .. })
+  ));
+  assert!(texture.upload_compressed(1, &[0; 8]).is_ok());
+
+  assert!(matches!(
+    texture.upload_compressed(2, &[0; 8]),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+  assert!(matches!(
+    texture.get_raw_texels(),
+    Err(TextureError::CannotRetrieveTexels(_))
+  ));
+}
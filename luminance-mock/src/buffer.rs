@@ -0,0 +1,334 @@
+//! Mock buffer implementation.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::slice;
+
+use crate::readback::Readback;
+use crate::{Command, Mock, MockState};
+use luminance::backend::buffer::{
+  Buffer as BufferBackend, BufferReadback as BufferReadbackBackend,
+  BufferSlice as BufferSliceBackend,
+};
+use luminance::buffer::{BufferError, BufferUsage, PreserveContent};
+
+/// Mock buffer.
+#[derive(Debug)]
+pub struct Buffer<T> {
+  pub(crate) id: usize,
+  pub(crate) buf: Vec<T>,
+  state: Rc<RefCell<MockState>>,
+}
+
+impl<T> Buffer<T> {
+  pub(crate) fn from_vec(mock: &mut Mock, buf: Vec<T>, usage: BufferUsage) -> Self {
+    let mut state = mock.state.borrow_mut();
+    let id = state.new_buffer_id();
+
+    state.record(Command::NewBuffer {
+      buffer: id,
+      len: buf.len(),
+      usage,
+    });
+
+    Buffer {
+      id,
+      buf,
+      state: mock.state.clone(),
+    }
+  }
+}
+
+unsafe impl<T> BufferBackend<T> for Mock
+where
+  T: Copy,
+{
+  type BufferRepr = Buffer<T>;
+
+  unsafe fn new_buffer(
+    &mut self,
+    len: usize,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>
+  where
+    T: Default,
+  {
+    Ok(Buffer::from_vec(self, vec![T::default(); len], usage))
+  }
+
+  unsafe fn len(buffer: &Self::BufferRepr) -> usize {
+    buffer.buf.len()
+  }
+
+  unsafe fn from_vec(
+    &mut self,
+    vec: Vec<T>,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Ok(Buffer::from_vec(self, vec, usage))
+  }
+
+  unsafe fn repeat(
+    &mut self,
+    len: usize,
+    value: T,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Ok(Buffer::from_vec(self, vec![value; len], usage))
+  }
+
+  unsafe fn at(buffer: &Self::BufferRepr, i: usize) -> Option<T> {
+    buffer.buf.get(i).copied()
+  }
+
+  unsafe fn whole(buffer: &Self::BufferRepr) -> Vec<T> {
+    buffer.buf.clone()
+  }
+
+  unsafe fn set(buffer: &mut Self::BufferRepr, i: usize, x: T) -> Result<(), BufferError> {
+    let len = buffer.buf.len();
+    let item = buffer
+      .buf
+      .get_mut(i)
+      .ok_or_else(|| BufferError::overflow(i, len))?;
+
+    *item = x;
+
+    buffer.state.borrow_mut().record(Command::SetBuffer {
+      buffer: buffer.id,
+      index: i,
+    });
+
+    Ok(())
+  }
+
+  unsafe fn write_whole(buffer: &mut Self::BufferRepr, values: &[T]) -> Result<(), BufferError> {
+    let provided_len = values.len();
+    let buffer_len = buffer.buf.len();
+
+    // error if we don’t pass the right number of items
+    match provided_len.cmp(&buffer_len) {
+      Ordering::Less => return Err(BufferError::too_few_values(provided_len, buffer_len)),
+
+      Ordering::Greater => return Err(BufferError::too_many_values(provided_len, buffer_len)),
+
+      _ => (),
+    }
+
+    buffer.buf.copy_from_slice(values);
+
+    buffer.state.borrow_mut().record(Command::WriteBuffer {
+      buffer: buffer.id,
+      len: provided_len,
+    });
+
+    Ok(())
+  }
+
+  unsafe fn write_range(
+    buffer: &mut Self::BufferRepr,
+    offset: usize,
+    values: &[T],
+  ) -> Result<(), BufferError> {
+    buffer.buf[offset..offset + values.len()].copy_from_slice(values);
+
+    buffer.state.borrow_mut().record(Command::WriteBufferRange {
+      buffer: buffer.id,
+      offset,
+      len: values.len(),
+    });
+
+    Ok(())
+  }
+
+  unsafe fn read_range(
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Vec<T>, BufferError> {
+    buffer.state.borrow_mut().record(Command::ReadBufferRange {
+      buffer: buffer.id,
+      start,
+      len,
+    });
+
+    Ok(buffer.buf[start..start + len].to_vec())
+  }
+
+  unsafe fn copy_from(
+    buffer: &mut Self::BufferRepr,
+    src: &Self::BufferRepr,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  ) -> Result<(), BufferError> {
+    buffer.buf[dst_offset..dst_offset + len].copy_from_slice(&src.buf[src_start..src_start + len]);
+
+    buffer.state.borrow_mut().record(Command::CopyBuffer {
+      src: src.id,
+      dst: buffer.id,
+      src_start,
+      len,
+      dst_offset,
+    });
+
+    Ok(())
+  }
+
+  unsafe fn clear(buffer: &mut Self::BufferRepr, x: T) -> Result<(), BufferError> {
+    for item in &mut buffer.buf {
+      *item = x;
+    }
+
+    buffer
+      .state
+      .borrow_mut()
+      .record(Command::ClearBuffer { buffer: buffer.id });
+
+    Ok(())
+  }
+
+  unsafe fn resize(
+    buffer: &mut Self::BufferRepr,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError> {
+    if preserve == PreserveContent::No {
+      buffer.buf.clear();
+    }
+
+    buffer.buf.resize(len, value);
+
+    buffer.state.borrow_mut().record(Command::ResizeBuffer {
+      buffer: buffer.id,
+      len,
+      preserve,
+    });
+
+    Ok(())
+  }
+}
+
+unsafe impl<T> BufferReadbackBackend<T> for Mock
+where
+  T: Copy,
+{
+  unsafe fn whole_async(buffer: &Self::BufferRepr) -> Result<Self::ReadbackRepr, BufferError> {
+    buffer
+      .state
+      .borrow_mut()
+      .record(Command::ReadBufferAsync { buffer: buffer.id });
+
+    Ok(Readback {
+      data: buffer.buf.clone(),
+    })
+  }
+}
+
+/// Buffer slice.
+pub struct BufferSlice<T> {
+  ptr: *const T,
+  len: usize,
+}
+
+impl<T> BufferSlice<T> {
+  /// Create a slice over some memory.
+  ///
+  /// The memory must outlive the slice.
+  pub(crate) fn new(slice: &[T]) -> Self {
+    BufferSlice {
+      ptr: slice.as_ptr(),
+      len: slice.len(),
+    }
+  }
+}
+
+impl BufferSlice<u8> {
+  /// Transmute to another type.
+  ///
+  /// This method is highly unsafe and should only be used when certain the target type is the
+  /// one actually represented by the raw bytes.
+  pub(crate) unsafe fn transmute<T>(self) -> BufferSlice<T> {
+    BufferSlice {
+      ptr: self.ptr as _,
+      len: self.len / mem::size_of::<T>(),
+    }
+  }
+}
+
+impl<T> Deref for BufferSlice<T> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    unsafe { slice::from_raw_parts(self.ptr, self.len) }
+  }
+}
+
+/// Mutable buffer slice.
+pub struct BufferSliceMut<T> {
+  ptr: *mut T,
+  len: usize,
+}
+
+impl<T> BufferSliceMut<T> {
+  /// Create a mutable slice over some memory.
+  ///
+  /// The memory must outlive the slice.
+  pub(crate) fn new(slice: &mut [T]) -> Self {
+    BufferSliceMut {
+      ptr: slice.as_mut_ptr(),
+      len: slice.len(),
+    }
+  }
+}
+
+impl BufferSliceMut<u8> {
+  /// Transmute to another type.
+  ///
+  /// This method is highly unsafe and should only be used when certain the target type is the
+  /// one actually represented by the raw bytes.
+  pub(crate) unsafe fn transmute<T>(self) -> BufferSliceMut<T> {
+    BufferSliceMut {
+      ptr: self.ptr as _,
+      len: self.len / mem::size_of::<T>(),
+    }
+  }
+}
+
+impl<T> Deref for BufferSliceMut<T> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    unsafe { slice::from_raw_parts(self.ptr as *const _, self.len) }
+  }
+}
+
+impl<T> DerefMut for BufferSliceMut<T> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+  }
+}
+
+unsafe impl<T> BufferSliceBackend<T> for Mock
+where
+  T: Copy,
+{
+  type SliceRepr = BufferSlice<T>;
+
+  type SliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn slice_buffer(buffer: &Self::BufferRepr) -> Result<Self::SliceRepr, BufferError> {
+    // the memory stays put as long as the slice lives, because the buffer is borrowed
+    Ok(BufferSlice::new(&buffer.buf))
+  }
+
+  unsafe fn slice_buffer_mut(
+    buffer: &mut Self::BufferRepr,
+  ) -> Result<Self::SliceMutRepr, BufferError> {
+    Ok(BufferSliceMut::new(&mut buffer.buf))
+  }
+}
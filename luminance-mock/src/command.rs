@@ -0,0 +1,271 @@
+//! Recorded commands.
+
+use luminance::buffer::{BufferUsage, PreserveContent};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect};
+use luminance::pipeline::PipelineState;
+use luminance::pixel::PixelFormat;
+use luminance::query::QueryType;
+use luminance::render_state::RenderState;
+use luminance::shader::{StageType, UniformType};
+use luminance::tess::Mode;
+use luminance::texture::{Dim, GenMipmaps};
+
+/// A command recorded by the [`Mock`] backend.
+///
+/// Resources are referred to by identifiers. Identifiers are allocated in creation order, starting
+/// from `0`, and each kind of resource (buffers, textures, framebuffers, stages, programs,
+/// tessellations, queries and fences) has its own sequence; compute programs share the sequence of
+/// programs. Texture and framebuffer sizes and offsets are given as `[width, height, depth]` and
+/// `[x, y, z]`, unused dimensions being `1` and `0`; for cubemaps, the depth is the number of faces
+/// and `z` is the index of the face.
+///
+/// [`Mock`]: crate::Mock
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+  /// A buffer was created.
+  NewBuffer {
+    buffer: usize,
+    len: usize,
+    usage: BufferUsage,
+  },
+  /// A single item of a buffer was set.
+  SetBuffer { buffer: usize, index: usize },
+  /// The whole content of a buffer was written.
+  WriteBuffer { buffer: usize, len: usize },
+  /// Items were written in a buffer, starting at item `offset`.
+  WriteBufferRange {
+    buffer: usize,
+    offset: usize,
+    len: usize,
+  },
+  /// Items of a buffer were read, starting at item `start`.
+  ReadBufferRange {
+    buffer: usize,
+    start: usize,
+    len: usize,
+  },
+  /// Items were copied from the buffer `src`, starting at item `src_start`, into the buffer `dst`,
+  /// starting at item `dst_offset`.
+  CopyBuffer {
+    src: usize,
+    dst: usize,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  },
+  /// A buffer was cleared with a single value.
+  ClearBuffer { buffer: usize },
+  /// A buffer was resized to `len` items.
+  ResizeBuffer {
+    buffer: usize,
+    len: usize,
+    preserve: PreserveContent,
+  },
+  /// A readback of the whole content of a buffer was started.
+  ReadBufferAsync { buffer: usize },
+  /// A texture was created.
+  NewTexture {
+    texture: usize,
+    dim: Dim,
+    size: [u32; 3],
+    mipmaps: usize,
+    pixel_format: PixelFormat,
+  },
+  /// A part of a texture was cleared with a single pixel.
+  ClearTexture {
+    texture: usize,
+    offset: [u32; 3],
+    size: [u32; 3],
+    gen_mipmaps: GenMipmaps,
+  },
+  /// Texels were uploaded to a part of a texture.
+  UploadTexture {
+    texture: usize,
+    offset: [u32; 3],
+    size: [u32; 3],
+    gen_mipmaps: GenMipmaps,
+  },
+  /// Texels were uploaded to a part of a mipmap level of a texture.
+  UploadTextureLevel {
+    texture: usize,
+    level: usize,
+    offset: [u32; 3],
+    size: [u32; 3],
+  },
+  /// Compressed blocks were uploaded to a part of a mipmap level of a texture.
+  UploadCompressedTexture {
+    texture: usize,
+    level: usize,
+    offset: [u32; 3],
+    size: [u32; 3],
+  },
+  /// A region of the texture `src` was copied to the texture `dst`.
+  CopyTexture {
+    src: usize,
+    dst: usize,
+    src_offset: [u32; 3],
+    dst_offset: [u32; 3],
+    size: [u32; 3],
+    gen_mipmaps: GenMipmaps,
+  },
+  /// The mipmaps of a texture were regenerated.
+  GenerateMipmaps { texture: usize },
+  /// The texels of a texture were read back.
+  GetTexels { texture: usize },
+  /// The texels of a part of a mipmap level of a texture were read back.
+  GetLevelTexels {
+    texture: usize,
+    level: usize,
+    offset: [u32; 3],
+    size: [u32; 3],
+  },
+  /// A readback of the texels of a texture was started.
+  GetTexelsAsync { texture: usize },
+  /// A framebuffer was created.
+  NewFramebuffer {
+    framebuffer: usize,
+    dim: Dim,
+    size: [u32; 3],
+    mipmaps: usize,
+  },
+  /// A multisampled framebuffer was created.
+  NewMultisampleFramebuffer {
+    framebuffer: usize,
+    size: [u32; 2],
+    samples: usize,
+    mipmaps: usize,
+  },
+  /// A color texture was attached to a framebuffer.
+  AttachColorTexture {
+    framebuffer: usize,
+    texture: usize,
+    index: usize,
+  },
+  /// A depth texture was attached to a framebuffer.
+  AttachDepthTexture { framebuffer: usize, texture: usize },
+  /// A renderbuffer was created.
+  NewRenderbuffer {
+    renderbuffer: usize,
+    size: [u32; 2],
+    pixel_format: PixelFormat,
+  },
+  /// A color renderbuffer was attached to a framebuffer.
+  AttachColorRenderbuffer {
+    framebuffer: usize,
+    renderbuffer: usize,
+    index: usize,
+  },
+  /// A depth renderbuffer was attached to a framebuffer.
+  AttachDepthRenderbuffer {
+    framebuffer: usize,
+    renderbuffer: usize,
+  },
+  /// The back buffer was requested.
+  BackBuffer { framebuffer: usize, size: [u32; 2] },
+  /// A rectangle of the framebuffer `src` was blitted to a rectangle of the framebuffer `dst`.
+  BlitFramebuffer {
+    src: usize,
+    dst: usize,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  },
+  /// A shader stage was created.
+  NewStage {
+    stage: usize,
+    ty: StageType,
+    src: String,
+  },
+  /// A shader program was created.
+  NewProgram {
+    program: usize,
+    vertex: usize,
+    tess: Option<(usize, usize)>,
+    geometry: Option<usize>,
+    fragment: usize,
+  },
+  /// A compute program was created.
+  NewComputeProgram { program: usize, stage: usize },
+  /// A uniform was mapped in a shader program.
+  NewUniform {
+    program: usize,
+    name: String,
+    ty: UniformType,
+  },
+  /// A uniform was set.
+  SetUniform {
+    program: usize,
+    name: String,
+    ty: UniformType,
+  },
+  /// A tessellation was created.
+  NewTess {
+    tess: usize,
+    mode: Mode,
+    vert_nb: usize,
+    inst_nb: usize,
+    indexed: bool,
+    usage: BufferUsage,
+  },
+  /// The vertex storage of a tessellation was resized to `len` vertices.
+  ResizeVertices {
+    tess: usize,
+    len: usize,
+    preserve: PreserveContent,
+  },
+  /// The index storage of a tessellation was resized to `len` indices.
+  ResizeIndices {
+    tess: usize,
+    len: usize,
+    preserve: PreserveContent,
+  },
+  /// The instance storage of a tessellation was resized to `len` instances.
+  ResizeInstances {
+    tess: usize,
+    len: usize,
+    preserve: PreserveContent,
+  },
+  /// A pipeline started on a framebuffer.
+  StartPipeline {
+    framebuffer: usize,
+    state: PipelineState,
+  },
+  /// A multisampled framebuffer was resolved into its slots, at the end of a pipeline.
+  ResolveFramebuffer { framebuffer: usize },
+  /// A buffer was bound.
+  BindBuffer { buffer: usize, binding: u32 },
+  /// A range of a buffer was bound; `start` and `len` are in items.
+  BindBufferRange {
+    buffer: usize,
+    binding: u32,
+    start: usize,
+    len: usize,
+  },
+  /// A buffer was bound as a storage buffer.
+  BindStorageBuffer { buffer: usize, binding: u32 },
+  /// A texture was bound.
+  BindTexture { texture: usize, unit: u32 },
+  /// A shader program was applied.
+  ApplyShaderProgram { program: usize },
+  /// A render state was entered.
+  EnterRenderState { state: RenderState },
+  /// A tessellation was rendered.
+  Render {
+    tess: usize,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  },
+  /// The applied compute program was dispatched.
+  Dispatch { workgroups: [u32; 3] },
+  /// A query was created.
+  NewQuery { query: usize, ty: QueryType },
+  /// A query started.
+  BeginQuery { query: usize },
+  /// A query ended.
+  EndQuery { query: usize },
+  /// A fence was inserted.
+  NewFence { fence: usize },
+}
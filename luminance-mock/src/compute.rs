@@ -0,0 +1,33 @@
+//! Mock compute implementation.
+
+use crate::shader::Program;
+use crate::{Command, Mock};
+use luminance::backend::compute::Compute;
+use luminance::compute::ComputeError;
+use luminance::shader::ProgramError;
+
+unsafe impl Compute for Mock {
+  unsafe fn new_compute_program(
+    &mut self,
+    stage: &Self::StageRepr,
+  ) -> Result<Self::ProgramRepr, ProgramError> {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_program_id();
+
+    state.record(Command::NewComputeProgram {
+      program: id,
+      stage: stage.id,
+    });
+
+    Ok(Program::new(id, self.state.clone()))
+  }
+
+  unsafe fn dispatch(&mut self, workgroups: [u32; 3]) -> Result<(), ComputeError> {
+    self
+      .state
+      .borrow_mut()
+      .record(Command::Dispatch { workgroups });
+
+    Ok(())
+  }
+}
@@ -0,0 +1,25 @@
+//! Mock fence implementation.
+
+use crate::{Command, Mock};
+use luminance::backend::fence::Fence as FenceBackend;
+use luminance::fence::FenceError;
+
+// fences are always signaled, so they don’t need to keep their identifier around
+pub struct Fence;
+
+unsafe impl FenceBackend for Mock {
+  type FenceRepr = Fence;
+
+  unsafe fn new_fence(&mut self) -> Result<Self::FenceRepr, FenceError> {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_fence_id();
+
+    state.record(Command::NewFence { fence: id });
+
+    Ok(Fence)
+  }
+
+  unsafe fn fence_signaled(_: &mut Self::FenceRepr) -> bool {
+    true
+  }
+}
@@ -0,0 +1,181 @@
+//! Mock framebuffer implementation.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::texture::extent;
+use crate::{Command, Mock, MockState, MAX_SAMPLES};
+use luminance::backend::color_slot::ColorSlot;
+use luminance::backend::depth_slot::DepthSlot;
+use luminance::backend::framebuffer::{
+  Framebuffer as FramebufferBackend, FramebufferBackBuffer, FramebufferBlit, FramebufferMultisample,
+};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError};
+use luminance::texture::{Dim2, Dimensionable, Sampler};
+
+/// Mock framebuffer.
+pub struct Framebuffer<D>
+where
+  D: Dimensionable,
+{
+  pub(crate) id: usize,
+  pub(crate) size: D::Size,
+  pub(crate) multisampled: bool,
+  state: Rc<RefCell<MockState>>,
+}
+
+unsafe impl<D> FramebufferBackend<D> for Mock
+where
+  D: Dimensionable,
+{
+  type FramebufferRepr = Framebuffer<D>;
+
+  unsafe fn new_framebuffer<CS, DS>(
+    &mut self,
+    size: D::Size,
+    mipmaps: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, D>,
+    DS: DepthSlot<Self, D>,
+  {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_framebuffer_id();
+
+    state.record(Command::NewFramebuffer {
+      framebuffer: id,
+      dim: D::dim(),
+      size: extent::<D>(size),
+      mipmaps: mipmaps + 1,
+    });
+
+    Ok(Framebuffer {
+      id,
+      size,
+      multisampled: false,
+      state: self.state.clone(),
+    })
+  }
+
+  unsafe fn attach_color_texture(
+    framebuffer: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    framebuffer
+      .state
+      .borrow_mut()
+      .record(Command::AttachColorTexture {
+        framebuffer: framebuffer.id,
+        texture: texture.id,
+        index: attachment_index,
+      });
+
+    Ok(())
+  }
+
+  unsafe fn attach_depth_texture(
+    framebuffer: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+  ) -> Result<(), FramebufferError> {
+    framebuffer
+      .state
+      .borrow_mut()
+      .record(Command::AttachDepthTexture {
+        framebuffer: framebuffer.id,
+        texture: texture.id,
+      });
+
+    Ok(())
+  }
+
+  unsafe fn validate_framebuffer(
+    framebuffer: Self::FramebufferRepr,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    Ok(framebuffer)
+  }
+
+  unsafe fn framebuffer_size(framebuffer: &Self::FramebufferRepr) -> D::Size {
+    framebuffer.size
+  }
+}
+
+unsafe impl FramebufferBackBuffer for Mock {
+  unsafe fn back_buffer(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_framebuffer_id();
+
+    state.record(Command::BackBuffer {
+      framebuffer: id,
+      size,
+    });
+
+    Ok(Framebuffer {
+      id,
+      size,
+      multisampled: false,
+      state: self.state.clone(),
+    })
+  }
+}
+
+unsafe impl FramebufferBlit for Mock {
+  unsafe fn blit_framebuffer(
+    src: &Self::FramebufferRepr,
+    dst: &mut Self::FramebufferRepr,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError> {
+    dst.state.borrow_mut().record(Command::BlitFramebuffer {
+      src: src.id,
+      dst: dst.id,
+      src_rect,
+      dst_rect,
+      mask,
+      filter,
+    });
+
+    Ok(())
+  }
+}
+
+unsafe impl FramebufferMultisample for Mock {
+  unsafe fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    mipmaps: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, Dim2>,
+    DS: DepthSlot<Self, Dim2>,
+  {
+    if samples > MAX_SAMPLES {
+      return Err(FramebufferError::unsupported_samples(samples));
+    }
+
+    let mut state = self.state.borrow_mut();
+    let id = state.new_framebuffer_id();
+
+    state.record(Command::NewMultisampleFramebuffer {
+      framebuffer: id,
+      size,
+      samples,
+      mipmaps: mipmaps + 1,
+    });
+
+    Ok(Framebuffer {
+      id,
+      size,
+      multisampled: true,
+      state: self.state.clone(),
+    })
+  }
+}
@@ -0,0 +1,198 @@
+//! Recording mock backend for [luminance].
+//!
+//! This crate provides [`Mock`], a backend implementing every backend trait of [luminance] without
+//! talking to any GPU. Instead, every call made to the backend is recorded as a [`Command`] in a
+//! log you can inspect afterwards. It is intended to be used in unit tests, to assert that a
+//! renderer issues exactly the expected sequence of resource creations, state changes and draws.
+//!
+//! [`MockSurface`] provides a [`GraphicsContext`] backed by [`Mock`]:
+//!
+//! ```
+//! use luminance::context::GraphicsContext as _;
+//! use luminance::pipeline::{PipelineError, PipelineState};
+//! use luminance_mock::{Command, MockSurface};
+//!
+//! let mut surface = MockSurface::new([800, 600]);
+//! let back_buffer = surface.back_buffer().unwrap();
+//!
+//! surface
+//!   .new_pipeline_gate()
+//!   .pipeline::<PipelineError, _, _, _, _>(&back_buffer, &PipelineState::default(), |_, _| Ok(()));
+//!
+//! assert_eq!(
+//!   surface.backend().take_commands(),
+//!   vec![
+//!     Command::BackBuffer {
+//!       framebuffer: 0,
+//!       size: [800, 600],
+//!     },
+//!     Command::StartPipeline {
+//!       framebuffer: 0,
+//!       state: PipelineState::default(),
+//!     },
+//!   ]
+//! );
+//! ```
+//!
+//! Resources keep their content in memory, so that reading back a buffer or the texels of a
+//! texture gives back what was written to it. Nothing is ever rendered though: framebuffer
+//! textures are only altered by uploads and clears.
+//!
+//! Shader stages always compile and shader programs always link, whatever their sources. Uniforms
+//! are always active and have the type they are asked with.
+//!
+//! Query results are available as soon as the queries end, and are always `0`. Fences are always
+//! signaled, and readbacks are always available right away.
+//!
+//! Buffer ranges must be bound at offsets aligned on [`BUFFER_OFFSET_ALIGNMENT`] bytes, the
+//! largest alignment real implementations are allowed to require.
+//!
+//! Multisampled framebuffers support up to [`MAX_SAMPLES`] samples, the smallest maximum real
+//! implementations are allowed to have. They are resolved at the end of every pipeline.
+//!
+//! Every compressed pixel format family is supported. Compressed textures don’t keep their blocks
+//! though, so uploading blocks is only recorded.
+//!
+//! [luminance]: https://crates.io/crates/luminance
+//! [`GraphicsContext`]: luminance::context::GraphicsContext
+
+mod buffer;
+mod command;
+mod compute;
+mod fence;
+mod framebuffer;
+mod pipeline;
+mod query;
+mod readback;
+mod renderbuffer;
+mod shader;
+mod surface;
+mod tess;
+mod texture;
+
+pub use crate::command::Command;
+pub use crate::surface::MockSurface;
+
+use luminance::query::QueryType;
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+
+/// Alignment, in bytes, of the offsets of bound buffer ranges.
+pub const BUFFER_OFFSET_ALIGNMENT: usize = 256;
+
+/// Maximum number of samples of multisampled framebuffers.
+pub const MAX_SAMPLES: usize = 4;
+
+/// The recording mock backend.
+#[derive(Debug)]
+pub struct Mock {
+  pub(crate) state: Rc<RefCell<MockState>>,
+}
+
+impl Mock {
+  /// Create a new mock backend with an empty log.
+  pub fn new() -> Self {
+    Mock {
+      state: Rc::new(RefCell::new(MockState::default())),
+    }
+  }
+
+  /// Get a copy of the commands recorded so far.
+  pub fn commands(&self) -> Vec<Command> {
+    self.state.borrow().commands.clone()
+  }
+
+  /// Take the commands recorded so far, leaving the log empty.
+  pub fn take_commands(&mut self) -> Vec<Command> {
+    mem::take(&mut self.state.borrow_mut().commands)
+  }
+}
+
+impl Default for Mock {
+  fn default() -> Self {
+    Mock::new()
+  }
+}
+
+/// The state of the mock backend.
+///
+/// It holds the log of commands and the resource identifier sequences.
+#[derive(Debug, Default)]
+pub(crate) struct MockState {
+  commands: Vec<Command>,
+  next_buffer: usize,
+  next_texture: usize,
+  next_framebuffer: usize,
+  next_renderbuffer: usize,
+  next_stage: usize,
+  next_program: usize,
+  next_tess: usize,
+  next_query: usize,
+  next_fence: usize,
+  pub(crate) bindings: BindingStack,
+  // slots of the active queries
+  pub(crate) active_queries: Vec<QueryType>,
+}
+
+/// Binding points.
+///
+/// Texture units, buffer bindings and storage buffer bindings are reused once the bound resources
+/// are dropped, like in the OpenGL backends.
+#[derive(Debug, Default)]
+pub(crate) struct BindingStack {
+  pub(crate) next_texture_unit: u32,
+  pub(crate) free_texture_units: Vec<u32>,
+  pub(crate) next_buffer_binding: u32,
+  pub(crate) free_buffer_bindings: Vec<u32>,
+  pub(crate) next_storage_buffer_binding: u32,
+  pub(crate) free_storage_buffer_bindings: Vec<u32>,
+}
+
+impl MockState {
+  pub(crate) fn record(&mut self, command: Command) {
+    self.commands.push(command);
+  }
+
+  pub(crate) fn new_buffer_id(&mut self) -> usize {
+    next_id(&mut self.next_buffer)
+  }
+
+  pub(crate) fn new_texture_id(&mut self) -> usize {
+    next_id(&mut self.next_texture)
+  }
+
+  pub(crate) fn new_framebuffer_id(&mut self) -> usize {
+    next_id(&mut self.next_framebuffer)
+  }
+
+  pub(crate) fn new_renderbuffer_id(&mut self) -> usize {
+    next_id(&mut self.next_renderbuffer)
+  }
+
+  pub(crate) fn new_stage_id(&mut self) -> usize {
+    next_id(&mut self.next_stage)
+  }
+
+  pub(crate) fn new_program_id(&mut self) -> usize {
+    next_id(&mut self.next_program)
+  }
+
+  pub(crate) fn new_tess_id(&mut self) -> usize {
+    next_id(&mut self.next_tess)
+  }
+
+  pub(crate) fn new_query_id(&mut self) -> usize {
+    next_id(&mut self.next_query)
+  }
+
+  pub(crate) fn new_fence_id(&mut self) -> usize {
+    next_id(&mut self.next_fence)
+  }
+}
+
+fn next_id(next: &mut usize) -> usize {
+  let id = *next;
+  *next += 1;
+  id
+}
@@ -0,0 +1,320 @@
+//! Mock pipeline implementation.
+
+use luminance::backend::pipeline::{
+  Pipeline as PipelineBackend, PipelineBase, PipelineBuffer, PipelineStorageBuffer, PipelineTexture,
+};
+use luminance::backend::render_gate::RenderGate;
+use luminance::backend::shading_gate::ShadingGate;
+use luminance::backend::tess::Tess;
+use luminance::backend::tess_gate::TessGate;
+use luminance::pipeline::{PipelineError, PipelineState};
+use luminance::pixel::Pixel;
+use luminance::render_state::RenderState;
+use luminance::tess::{Deinterleaved, DeinterleavedData, Interleaved, TessIndex, TessVertexData};
+use luminance::texture::Dimensionable;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+
+use crate::{Command, Mock, MockState, BUFFER_OFFSET_ALIGNMENT};
+
+pub struct Pipeline {
+  state: Rc<RefCell<MockState>>,
+}
+
+pub struct BoundBuffer {
+  pub(crate) binding: u32,
+  state: Rc<RefCell<MockState>>,
+}
+
+impl Drop for BoundBuffer {
+  fn drop(&mut self) {
+    // place the binding into the free list
+    let mut state = self.state.borrow_mut();
+    state.bindings.free_buffer_bindings.push(self.binding);
+  }
+}
+
+pub struct BoundStorageBuffer {
+  pub(crate) binding: u32,
+  state: Rc<RefCell<MockState>>,
+}
+
+impl Drop for BoundStorageBuffer {
+  fn drop(&mut self) {
+    // place the binding into the free list
+    let mut state = self.state.borrow_mut();
+    state
+      .bindings
+      .free_storage_buffer_bindings
+      .push(self.binding);
+  }
+}
+
+pub struct BoundTexture<D, P>
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  pub(crate) unit: u32,
+  state: Rc<RefCell<MockState>>,
+  _phantom: PhantomData<*const (D, P)>,
+}
+
+impl<D, P> Drop for BoundTexture<D, P>
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  fn drop(&mut self) {
+    // place the binding into the free list
+    let mut state = self.state.borrow_mut();
+    state.bindings.free_texture_units.push(self.unit);
+  }
+}
+
+unsafe impl PipelineBase for Mock {
+  type PipelineRepr = Pipeline;
+
+  unsafe fn new_pipeline(&mut self) -> Result<Self::PipelineRepr, PipelineError> {
+    let pipeline = Pipeline {
+      state: self.state.clone(),
+    };
+
+    Ok(pipeline)
+  }
+}
+
+unsafe impl<D> PipelineBackend<D> for Mock
+where
+  D: Dimensionable,
+{
+  unsafe fn start_pipeline(
+    &mut self,
+    framebuffer: &Self::FramebufferRepr,
+    pipeline_state: &PipelineState,
+  ) {
+    self.state.borrow_mut().record(Command::StartPipeline {
+      framebuffer: framebuffer.id,
+      state: pipeline_state.clone(),
+    });
+  }
+
+  unsafe fn end_pipeline(&mut self, framebuffer: &Self::FramebufferRepr) {
+    if framebuffer.multisampled {
+      self.state.borrow_mut().record(Command::ResolveFramebuffer {
+        framebuffer: framebuffer.id,
+      });
+    }
+  }
+}
+
+unsafe impl<T> PipelineBuffer<T> for Mock
+where
+  T: Copy,
+{
+  type BoundBufferRepr = BoundBuffer;
+
+  unsafe fn bind_buffer(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = &mut state.bindings;
+
+    let binding = bstack.free_buffer_bindings.pop().unwrap_or_else(|| {
+      // no more free bindings; reserve one
+      let binding = bstack.next_buffer_binding;
+      bstack.next_buffer_binding += 1;
+      binding
+    });
+
+    state.record(Command::BindBuffer {
+      buffer: buffer.id,
+      binding,
+    });
+
+    Ok(BoundBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
+  unsafe fn bind_buffer_range(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    let offset = start * mem::size_of::<T>();
+
+    if offset / BUFFER_OFFSET_ALIGNMENT * BUFFER_OFFSET_ALIGNMENT != offset {
+      return Err(PipelineError::unaligned_buffer_range(
+        offset,
+        BUFFER_OFFSET_ALIGNMENT,
+      ));
+    }
+
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = &mut state.bindings;
+
+    let binding = bstack.free_buffer_bindings.pop().unwrap_or_else(|| {
+      // no more free bindings; reserve one
+      let binding = bstack.next_buffer_binding;
+      bstack.next_buffer_binding += 1;
+      binding
+    });
+
+    state.record(Command::BindBufferRange {
+      buffer: buffer.id,
+      binding,
+      start,
+      len,
+    });
+
+    Ok(BoundBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
+  unsafe fn buffer_binding(bound: &Self::BoundBufferRepr) -> u32 {
+    bound.binding
+  }
+
+  unsafe fn buffer_offset_alignment(&mut self) -> usize {
+    BUFFER_OFFSET_ALIGNMENT
+  }
+}
+
+unsafe impl<T> PipelineStorageBuffer<T> for Mock
+where
+  T: Copy,
+{
+  type BoundStorageBufferRepr = BoundStorageBuffer;
+
+  unsafe fn bind_storage_buffer(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+  ) -> Result<Self::BoundStorageBufferRepr, PipelineError> {
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = &mut state.bindings;
+
+    let binding = bstack
+      .free_storage_buffer_bindings
+      .pop()
+      .unwrap_or_else(|| {
+        // no more free bindings; reserve one
+        let binding = bstack.next_storage_buffer_binding;
+        bstack.next_storage_buffer_binding += 1;
+        binding
+      });
+
+    state.record(Command::BindStorageBuffer {
+      buffer: buffer.id,
+      binding,
+    });
+
+    Ok(BoundStorageBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
+  unsafe fn storage_buffer_binding(bound: &Self::BoundStorageBufferRepr) -> u32 {
+    bound.binding
+  }
+}
+
+unsafe impl<D, P> PipelineTexture<D, P> for Mock
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  type BoundTextureRepr = BoundTexture<D, P>;
+
+  unsafe fn bind_texture(
+    pipeline: &Self::PipelineRepr,
+    texture: &Self::TextureRepr,
+  ) -> Result<Self::BoundTextureRepr, PipelineError>
+  where
+    D: Dimensionable,
+    P: Pixel,
+  {
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = &mut state.bindings;
+
+    let unit = bstack.free_texture_units.pop().unwrap_or_else(|| {
+      // no more free units; reserve one
+      let unit = bstack.next_texture_unit;
+      bstack.next_texture_unit += 1;
+      unit
+    });
+
+    state.record(Command::BindTexture {
+      texture: texture.id,
+      unit,
+    });
+
+    Ok(BoundTexture {
+      unit,
+      state: pipeline.state.clone(),
+      _phantom: PhantomData,
+    })
+  }
+
+  unsafe fn texture_binding(bound: &Self::BoundTextureRepr) -> u32 {
+    bound.unit
+  }
+}
+
+unsafe impl<V, I, W> TessGate<V, I, W, Interleaved> for Mock
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  unsafe fn render(
+    &mut self,
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) {
+    let _ = <Self as Tess<V, I, W, Interleaved>>::render(tess, start_index, vert_nb, inst_nb);
+  }
+}
+
+unsafe impl<V, I, W> TessGate<V, I, W, Deinterleaved> for Mock
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  unsafe fn render(
+    &mut self,
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) {
+    let _ = <Self as Tess<V, I, W, Deinterleaved>>::render(tess, start_index, vert_nb, inst_nb);
+  }
+}
+
+unsafe impl RenderGate for Mock {
+  unsafe fn enter_render_state(&mut self, rdr_st: &RenderState) {
+    self.state.borrow_mut().record(Command::EnterRenderState {
+      state: rdr_st.clone(),
+    });
+  }
+}
+
+unsafe impl ShadingGate for Mock {
+  unsafe fn apply_shader_program(&mut self, shader_program: &Self::ProgramRepr) {
+    self.state.borrow_mut().record(Command::ApplyShaderProgram {
+      program: shader_program.id,
+    });
+  }
+}
@@ -0,0 +1,68 @@
+//! Mock query implementation.
+
+use crate::{Command, Mock};
+use luminance::backend::query::Query as QueryBackend;
+use luminance::query::{QueryError, QueryType};
+
+pub struct Query {
+  id: usize,
+  ty: QueryType,
+  issued: bool,
+}
+
+unsafe impl QueryBackend for Mock {
+  type QueryRepr = Query;
+
+  unsafe fn new_query(&mut self, ty: QueryType) -> Result<Self::QueryRepr, QueryError> {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_query_id();
+
+    state.record(Command::NewQuery { query: id, ty });
+
+    Ok(Query {
+      id,
+      ty,
+      issued: false,
+    })
+  }
+
+  unsafe fn begin_query(&mut self, query: &mut Self::QueryRepr) -> Result<(), QueryError> {
+    let mut state = self.state.borrow_mut();
+
+    // timestamps are recorded when the query ends, so they can’t be active
+    if query.ty != QueryType::Timestamp {
+      let slot = query_slot(query.ty);
+
+      if state.active_queries.contains(&slot) {
+        return Err(QueryError::already_active(query.ty));
+      }
+
+      state.active_queries.push(slot);
+    }
+
+    state.record(Command::BeginQuery { query: query.id });
+
+    Ok(())
+  }
+
+  unsafe fn end_query(&mut self, query: &mut Self::QueryRepr) {
+    let mut state = self.state.borrow_mut();
+
+    let slot = query_slot(query.ty);
+    state.active_queries.retain(|&s| s != slot);
+    state.record(Command::EndQuery { query: query.id });
+    query.issued = true;
+  }
+
+  unsafe fn query_result(query: &mut Self::QueryRepr) -> Result<Option<u64>, QueryError> {
+    Ok(query.issued.then_some(0))
+  }
+}
+
+// Slot a query type uses; occlusion queries share a single slot, like in the OpenGL backends.
+fn query_slot(ty: QueryType) -> QueryType {
+  match ty {
+    QueryType::AnySamplesPassed => QueryType::SamplesPassed,
+    _ => ty,
+  }
+}
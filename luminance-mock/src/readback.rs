@@ -0,0 +1,22 @@
+//! Mock readback implementation.
+
+use crate::Mock;
+use luminance::backend::readback::Readback as ReadbackBackend;
+use luminance::readback::ReadbackError;
+
+pub struct Readback<T> {
+  pub(crate) data: Vec<T>,
+}
+
+unsafe impl<T> ReadbackBackend<T> for Mock
+where
+  T: Copy,
+{
+  type ReadbackRepr = Readback<T>;
+
+  unsafe fn readback_result(
+    readback: &mut Self::ReadbackRepr,
+  ) -> Result<Option<Vec<T>>, ReadbackError> {
+    Ok(Some(readback.data.clone()))
+  }
+}
@@ -0,0 +1,78 @@
+//! Mock renderbuffer implementation.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{Command, Mock, MockState};
+use luminance::backend::renderbuffer::{Renderbuffer as RenderbufferBackend, RenderbufferBase};
+use luminance::framebuffer::FramebufferError;
+use luminance::pixel::Pixel;
+
+/// Mock renderbuffer.
+///
+/// Renderbuffers have no content.
+#[derive(Debug)]
+pub struct Renderbuffer {
+  pub(crate) id: usize,
+  state: Rc<RefCell<MockState>>,
+}
+
+unsafe impl RenderbufferBase for Mock {
+  type RenderbufferRepr = Renderbuffer;
+}
+
+unsafe impl<P> RenderbufferBackend<P> for Mock
+where
+  P: Pixel,
+{
+  unsafe fn new_renderbuffer(
+    &mut self,
+    size: [u32; 2],
+  ) -> Result<Self::RenderbufferRepr, FramebufferError> {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_renderbuffer_id();
+
+    state.record(Command::NewRenderbuffer {
+      renderbuffer: id,
+      size,
+      pixel_format: P::pixel_format(),
+    });
+
+    Ok(Renderbuffer {
+      id,
+      state: self.state.clone(),
+    })
+  }
+
+  unsafe fn attach_color_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    renderbuffer
+      .state
+      .borrow_mut()
+      .record(Command::AttachColorRenderbuffer {
+        framebuffer: framebuffer.id,
+        renderbuffer: renderbuffer.id,
+        index: attachment_index,
+      });
+
+    Ok(())
+  }
+
+  unsafe fn attach_depth_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+  ) -> Result<(), FramebufferError> {
+    renderbuffer
+      .state
+      .borrow_mut()
+      .record(Command::AttachDepthRenderbuffer {
+        framebuffer: framebuffer.id,
+        renderbuffer: renderbuffer.id,
+      });
+
+    Ok(())
+  }
+}
@@ -0,0 +1,294 @@
+//! Mock shader implementation.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{Command, Mock, MockState};
+use luminance::backend::shader::{Shader, Uniformable};
+use luminance::pipeline::{BufferBinding, StorageBufferBinding, TextureBinding};
+use luminance::pixel::{SamplerType, Type as PixelType};
+use luminance::shader::{
+  ProgramError, StageError, StageType, TessellationStages, Uniform, UniformType, UniformWarning,
+  VertexAttribWarning,
+};
+use luminance::texture::{Dim, Dimensionable};
+use luminance::vertex::Semantics;
+
+/// Mock shader stage.
+#[derive(Debug)]
+pub struct Stage {
+  pub(crate) id: usize,
+}
+
+/// Mock shader program.
+#[derive(Debug)]
+pub struct Program {
+  pub(crate) id: usize,
+  // uniforms, indexed by their index
+  uniforms: Rc<RefCell<Vec<(String, UniformType)>>>,
+  state: Rc<RefCell<MockState>>,
+}
+
+impl Program {
+  pub(crate) fn new(id: usize, state: Rc<RefCell<MockState>>) -> Self {
+    Program {
+      id,
+      uniforms: Rc::new(RefCell::new(Vec::new())),
+      state,
+    }
+  }
+
+  fn set_uniform<T>(&mut self, uniform: &Uniform<T>)
+  where
+    T: Uniformable<Mock>,
+  {
+    // unbound uniforms have a negative index
+    if uniform.index() < 0 {
+      return;
+    }
+
+    let (name, ty) = self.uniforms.borrow()[uniform.index() as usize].clone();
+
+    self.state.borrow_mut().record(Command::SetUniform {
+      program: self.id,
+      name,
+      ty,
+    });
+  }
+}
+
+/// Mock uniform builder.
+#[derive(Debug)]
+pub struct UniformBuilder {
+  program: usize,
+  uniforms: Rc<RefCell<Vec<(String, UniformType)>>>,
+  state: Rc<RefCell<MockState>>,
+}
+
+unsafe impl Shader for Mock {
+  type StageRepr = Stage;
+
+  type ProgramRepr = Program;
+
+  type UniformBuilderRepr = UniformBuilder;
+
+  unsafe fn new_stage(&mut self, ty: StageType, src: &str) -> Result<Self::StageRepr, StageError> {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_stage_id();
+
+    state.record(Command::NewStage {
+      stage: id,
+      ty,
+      src: src.to_owned(),
+    });
+
+    Ok(Stage { id })
+  }
+
+  unsafe fn new_program(
+    &mut self,
+    vertex: &Self::StageRepr,
+    tess: Option<TessellationStages<Self::StageRepr>>,
+    geometry: Option<&Self::StageRepr>,
+    fragment: &Self::StageRepr,
+  ) -> Result<Self::ProgramRepr, ProgramError> {
+    let mut state = self.state.borrow_mut();
+    let id = state.new_program_id();
+
+    state.record(Command::NewProgram {
+      program: id,
+      vertex: vertex.id,
+      tess: tess.map(|stages| (stages.control.id, stages.evaluation.id)),
+      geometry: geometry.map(|stage| stage.id),
+      fragment: fragment.id,
+    });
+
+    Ok(Program::new(id, self.state.clone()))
+  }
+
+  unsafe fn apply_semantics<Sem>(
+    _: &mut Self::ProgramRepr,
+  ) -> Result<Vec<VertexAttribWarning>, ProgramError>
+  where
+    Sem: Semantics,
+  {
+    Ok(Vec::new())
+  }
+
+  unsafe fn new_uniform_builder(
+    program: &mut Self::ProgramRepr,
+  ) -> Result<Self::UniformBuilderRepr, ProgramError> {
+    Ok(UniformBuilder {
+      program: program.id,
+      uniforms: program.uniforms.clone(),
+      state: program.state.clone(),
+    })
+  }
+
+  unsafe fn ask_uniform<T>(
+    uniform_builder: &mut Self::UniformBuilderRepr,
+    name: &str,
+  ) -> Result<Uniform<T>, UniformWarning>
+  where
+    T: Uniformable<Self>,
+  {
+    let ty = T::ty();
+    let mut uniforms = uniform_builder.uniforms.borrow_mut();
+    let index = uniforms.len() as i32;
+
+    uniforms.push((name.to_owned(), ty));
+
+    uniform_builder
+      .state
+      .borrow_mut()
+      .record(Command::NewUniform {
+        program: uniform_builder.program,
+        name: name.to_owned(),
+        ty,
+      });
+
+    Ok(Uniform::new(index))
+  }
+
+  unsafe fn unbound<T>(_: &mut Self::UniformBuilderRepr) -> Uniform<T>
+  where
+    T: Uniformable<Self>,
+  {
+    Uniform::new(-1)
+  }
+}
+
+macro_rules! impl_Uniformable {
+  ($($t:ty => $uty:ident),* $(,)?) => {
+    $(
+      unsafe impl<'a> Uniformable<Mock> for $t {
+        unsafe fn ty() -> UniformType {
+          UniformType::$uty
+        }
+
+        unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+          program.set_uniform(uniform);
+        }
+      }
+    )*
+  };
+}
+
+impl_Uniformable! {
+  i32 => Int,
+  [i32; 2] => IVec2,
+  [i32; 3] => IVec3,
+  [i32; 4] => IVec4,
+  &'a [i32] => Int,
+  &'a [[i32; 2]] => IVec2,
+  &'a [[i32; 3]] => IVec3,
+  &'a [[i32; 4]] => IVec4,
+
+  u32 => UInt,
+  [u32; 2] => UIVec2,
+  [u32; 3] => UIVec3,
+  [u32; 4] => UIVec4,
+  &'a [u32] => UInt,
+  &'a [[u32; 2]] => UIVec2,
+  &'a [[u32; 3]] => UIVec3,
+  &'a [[u32; 4]] => UIVec4,
+
+  f32 => Float,
+  [f32; 2] => Vec2,
+  [f32; 3] => Vec3,
+  [f32; 4] => Vec4,
+  &'a [f32] => Float,
+  &'a [[f32; 2]] => Vec2,
+  &'a [[f32; 3]] => Vec3,
+  &'a [[f32; 4]] => Vec4,
+
+  f64 => Double,
+  [f64; 2] => DVec2,
+  [f64; 3] => DVec3,
+  [f64; 4] => DVec4,
+  &'a [f64] => Double,
+  &'a [[f64; 2]] => DVec2,
+  &'a [[f64; 3]] => DVec3,
+  &'a [[f64; 4]] => DVec4,
+
+  bool => Bool,
+  [bool; 2] => BVec2,
+  [bool; 3] => BVec3,
+  [bool; 4] => BVec4,
+  &'a [bool] => Bool,
+  &'a [[bool; 2]] => BVec2,
+  &'a [[bool; 3]] => BVec3,
+  &'a [[bool; 4]] => BVec4,
+
+  [[f32; 2]; 2] => M22,
+  [[f32; 3]; 3] => M33,
+  [[f32; 4]; 4] => M44,
+  &'a [[[f32; 2]; 2]] => M22,
+  &'a [[[f32; 3]; 3]] => M33,
+  &'a [[[f32; 4]; 4]] => M44,
+
+  [[f64; 2]; 2] => DM22,
+  [[f64; 3]; 3] => DM33,
+  [[f64; 4]; 4] => DM44,
+  &'a [[[f64; 2]; 2]] => DM22,
+  &'a [[[f64; 3]; 3]] => DM33,
+  &'a [[[f64; 4]; 4]] => DM44,
+}
+
+unsafe impl<T> Uniformable<Mock> for BufferBinding<T> {
+  unsafe fn ty() -> UniformType {
+    UniformType::BufferBinding
+  }
+
+  unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+    program.set_uniform(uniform);
+  }
+}
+
+unsafe impl<T> Uniformable<Mock> for StorageBufferBinding<T> {
+  unsafe fn ty() -> UniformType {
+    UniformType::StorageBufferBinding
+  }
+
+  unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+    program.set_uniform(uniform);
+  }
+}
+
+unsafe impl<D, S> Uniformable<Mock> for TextureBinding<D, S>
+where
+  D: Dimensionable,
+  S: SamplerType,
+{
+  unsafe fn ty() -> UniformType {
+    match (S::sample_type(), D::dim()) {
+      (PixelType::Integral, Dim::Dim1) => UniformType::ISampler1D,
+      (PixelType::Unsigned, Dim::Dim1) => UniformType::UISampler1D,
+      (_, Dim::Dim1) => UniformType::Sampler1D,
+
+      (PixelType::Integral, Dim::Dim2) => UniformType::ISampler2D,
+      (PixelType::Unsigned, Dim::Dim2) => UniformType::UISampler2D,
+      (_, Dim::Dim2) => UniformType::Sampler2D,
+
+      (PixelType::Integral, Dim::Dim3) => UniformType::ISampler3D,
+      (PixelType::Unsigned, Dim::Dim3) => UniformType::UISampler3D,
+      (_, Dim::Dim3) => UniformType::Sampler3D,
+
+      (PixelType::Integral, Dim::Cubemap) => UniformType::ICubemap,
+      (PixelType::Unsigned, Dim::Cubemap) => UniformType::UICubemap,
+      (_, Dim::Cubemap) => UniformType::Cubemap,
+
+      (PixelType::Integral, Dim::Dim1Array) => UniformType::ISampler1DArray,
+      (PixelType::Unsigned, Dim::Dim1Array) => UniformType::UISampler1DArray,
+      (_, Dim::Dim1Array) => UniformType::Sampler1DArray,
+
+      (PixelType::Integral, Dim::Dim2Array) => UniformType::ISampler2DArray,
+      (PixelType::Unsigned, Dim::Dim2Array) => UniformType::UISampler2DArray,
+      (_, Dim::Dim2Array) => UniformType::Sampler2DArray,
+    }
+  }
+
+  unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+    program.set_uniform(uniform);
+  }
+}
@@ -0,0 +1,49 @@
+//! Mock surface.
+
+use luminance::context::GraphicsContext;
+use luminance::framebuffer::{Framebuffer, FramebufferError};
+use luminance::texture::Dim2;
+
+use crate::Mock;
+
+/// A surface recording everything done with it.
+///
+/// Use [`GraphicsContext::backend`] to inspect the recorded commands.
+#[derive(Debug)]
+pub struct MockSurface {
+  backend: Mock,
+  size: [u32; 2],
+}
+
+unsafe impl GraphicsContext for MockSurface {
+  type Backend = Mock;
+
+  fn backend(&mut self) -> &mut Self::Backend {
+    &mut self.backend
+  }
+}
+
+impl MockSurface {
+  /// Create a new [`MockSurface`] with the given size (in pixels).
+  pub fn new(size: [u32; 2]) -> Self {
+    MockSurface {
+      backend: Mock::new(),
+      size,
+    }
+  }
+
+  /// Get the size of the surface.
+  pub fn size(&self) -> [u32; 2] {
+    self.size
+  }
+
+  /// Resize the surface.
+  pub fn set_size(&mut self, size: [u32; 2]) {
+    self.size = size;
+  }
+
+  /// Get access to the back buffer.
+  pub fn back_buffer(&mut self) -> Result<Framebuffer<Mock, Dim2, (), ()>, FramebufferError> {
+    Framebuffer::back_buffer(self, self.size)
+  }
+}
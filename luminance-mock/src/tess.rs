@@ -0,0 +1,509 @@
+//! Mock tessellation implementation.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+
+use crate::buffer::{BufferSlice, BufferSliceMut};
+use crate::{Command, Mock, MockState};
+use luminance::backend::tess::{
+  IndexSlice as IndexSliceBackend, InstanceSlice as InstanceSliceBackend, Tess as TessBackend,
+  VertexSlice as VertexSliceBackend,
+};
+use luminance::buffer::{BufferUsage, PreserveContent};
+use luminance::tess::{
+  Deinterleaved, DeinterleavedData, Interleaved, Mode, TessError, TessIndex, TessMapError,
+  TessParams, TessVertexData,
+};
+use luminance::vertex::{Deinterleave, Vertex, VertexAttribDim, VertexBufferDesc};
+
+#[derive(Debug)]
+struct TessRaw<I> {
+  id: usize,
+  vert_nb: usize,
+  inst_nb: usize,
+  indices: Vec<I>,
+  state: Rc<RefCell<MockState>>,
+}
+
+impl<I> TessRaw<I> {
+  fn new(
+    mock: &mut Mock,
+    indices: Vec<I>,
+    mode: Mode,
+    vert_nb: usize,
+    inst_nb: usize,
+    usage: BufferUsage,
+  ) -> Self {
+    let mut state = mock.state.borrow_mut();
+    let id = state.new_tess_id();
+
+    state.record(Command::NewTess {
+      tess: id,
+      mode,
+      vert_nb,
+      inst_nb,
+      indexed: !indices.is_empty(),
+      usage,
+    });
+
+    TessRaw {
+      id,
+      vert_nb,
+      inst_nb,
+      indices,
+      state: mock.state.clone(),
+    }
+  }
+
+  fn render(&self, start_index: usize, vert_nb: usize, inst_nb: usize) -> Result<(), TessError> {
+    self.state.borrow_mut().record(Command::Render {
+      tess: self.id,
+      start_index,
+      vert_nb,
+      inst_nb,
+    });
+
+    Ok(())
+  }
+
+  fn vertices_resized(&mut self, len: usize, preserve: PreserveContent) {
+    // indexed tessellations render as many vertices as there are indices
+    if self.indices.is_empty() {
+      self.vert_nb = len;
+    }
+
+    self.state.borrow_mut().record(Command::ResizeVertices {
+      tess: self.id,
+      len,
+      preserve,
+    });
+  }
+
+  fn resize_indices(&mut self, len: usize, preserve: PreserveContent)
+  where
+    I: TessIndex,
+  {
+    resize_vec(&mut self.indices, len, unsafe { mem::zeroed() }, preserve);
+    self.vert_nb = len;
+
+    self.state.borrow_mut().record(Command::ResizeIndices {
+      tess: self.id,
+      len,
+      preserve,
+    });
+  }
+
+  fn instances_resized(&mut self, len: usize, preserve: PreserveContent) {
+    self.inst_nb = len;
+
+    self.state.borrow_mut().record(Command::ResizeInstances {
+      tess: self.id,
+      len,
+      preserve,
+    });
+  }
+
+  fn indices(&mut self) -> Result<BufferSlice<I>, TessMapError> {
+    if self.indices.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSlice::new(&self.indices))
+    }
+  }
+
+  fn indices_mut(&mut self) -> Result<BufferSliceMut<I>, TessMapError> {
+    if self.indices.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSliceMut::new(&mut self.indices))
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct InterleavedTess<V, I, W> {
+  raw: TessRaw<I>,
+  vertices: Vec<V>,
+  instances: Vec<W>,
+}
+
+unsafe impl<V, I, W> TessBackend<V, I, W, Interleaved> for Mock
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type TessRepr = InterleavedTess<V, I, W>;
+
+  unsafe fn build(
+    &mut self,
+    vertex_data: Option<V::Data>,
+    index_data: Vec<I>,
+    instance_data: Option<W::Data>,
+    params: TessParams<I>,
+  ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      usage,
+      ..
+    } = params;
+
+    Ok(InterleavedTess {
+      raw: TessRaw::new(self, index_data, mode, vert_nb, inst_nb, usage),
+      vertices: vertex_data.unwrap_or_default(),
+      instances: instance_data.unwrap_or_default(),
+    })
+  }
+
+  unsafe fn tess_vertices_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.vert_nb
+  }
+
+  unsafe fn tess_instances_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.inst_nb
+  }
+
+  unsafe fn render(
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) -> Result<(), TessError> {
+    tess.raw.render(start_index, vert_nb, inst_nb)
+  }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved(&mut tess.vertices, len, preserve);
+    tess.raw.vertices_resized(len, preserve);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve);
+
+    Ok(())
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved(&mut tess.instances, len, preserve);
+    tess.raw.instances_resized(len, preserve);
+
+    Ok(())
+  }
+}
+
+unsafe impl<V, I, W> VertexSliceBackend<V, I, W, Interleaved, V> for Mock
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type VertexSliceRepr = BufferSlice<V>;
+  type VertexSliceMutRepr = BufferSliceMut<V>;
+
+  unsafe fn vertices(tess: &mut Self::TessRepr) -> Result<Self::VertexSliceRepr, TessMapError> {
+    if tess.vertices.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSlice::new(&tess.vertices))
+    }
+  }
+
+  unsafe fn vertices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::VertexSliceMutRepr, TessMapError> {
+    if tess.vertices.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSliceMut::new(&mut tess.vertices))
+    }
+  }
+}
+
+unsafe impl<V, I, W> IndexSliceBackend<V, I, W, Interleaved> for Mock
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type IndexSliceRepr = BufferSlice<I>;
+  type IndexSliceMutRepr = BufferSliceMut<I>;
+
+  unsafe fn indices(tess: &mut Self::TessRepr) -> Result<Self::IndexSliceRepr, TessMapError> {
+    tess.raw.indices()
+  }
+
+  unsafe fn indices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::IndexSliceMutRepr, TessMapError> {
+    tess.raw.indices_mut()
+  }
+}
+
+unsafe impl<V, I, W> InstanceSliceBackend<V, I, W, Interleaved, W> for Mock
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type InstanceSliceRepr = BufferSlice<W>;
+  type InstanceSliceMutRepr = BufferSliceMut<W>;
+
+  unsafe fn instances(tess: &mut Self::TessRepr) -> Result<Self::InstanceSliceRepr, TessMapError> {
+    if tess.instances.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSlice::new(&tess.instances))
+    }
+  }
+
+  unsafe fn instances_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::InstanceSliceMutRepr, TessMapError> {
+    if tess.instances.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSliceMut::new(&mut tess.instances))
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct DeinterleavedTess<V, I, W> {
+  raw: TessRaw<I>,
+  vertices: Vec<Vec<u8>>,
+  instances: Vec<Vec<u8>>,
+  _phantom: PhantomData<*const (V, W)>,
+}
+
+unsafe impl<V, I, W> TessBackend<V, I, W, Deinterleaved> for Mock
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type TessRepr = DeinterleavedTess<V, I, W>;
+
+  unsafe fn build(
+    &mut self,
+    vertex_data: Option<V::Data>,
+    index_data: Vec<I>,
+    instance_data: Option<W::Data>,
+    params: TessParams<I>,
+  ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      usage,
+      ..
+    } = params;
+
+    Ok(DeinterleavedTess {
+      raw: TessRaw::new(self, index_data, mode, vert_nb, inst_nb, usage),
+      vertices: into_attributes(vertex_data),
+      instances: into_attributes(instance_data),
+      _phantom: PhantomData,
+    })
+  }
+
+  unsafe fn tess_vertices_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.vert_nb
+  }
+
+  unsafe fn tess_instances_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.inst_nb
+  }
+
+  unsafe fn render(
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) -> Result<(), TessError> {
+    tess.raw.render(start_index, vert_nb, inst_nb)
+  }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved::<V>(&mut tess.vertices, len, preserve);
+    tess.raw.vertices_resized(len, preserve);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve);
+
+    Ok(())
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved::<W>(&mut tess.instances, len, preserve);
+    tess.raw.instances_resized(len, preserve);
+
+    Ok(())
+  }
+}
+
+unsafe impl<V, I, W, T> VertexSliceBackend<V, I, W, Deinterleaved, T> for Mock
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>> + Deinterleave<T>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type VertexSliceRepr = BufferSlice<T>;
+  type VertexSliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn vertices(tess: &mut Self::TessRepr) -> Result<Self::VertexSliceRepr, TessMapError> {
+    if tess.vertices.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSlice::new(&tess.vertices[V::RANK]).transmute())
+    }
+  }
+
+  unsafe fn vertices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::VertexSliceMutRepr, TessMapError> {
+    if tess.vertices.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSliceMut::new(&mut tess.vertices[V::RANK]).transmute())
+    }
+  }
+}
+
+unsafe impl<V, I, W> IndexSliceBackend<V, I, W, Deinterleaved> for Mock
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type IndexSliceRepr = BufferSlice<I>;
+  type IndexSliceMutRepr = BufferSliceMut<I>;
+
+  unsafe fn indices(tess: &mut Self::TessRepr) -> Result<Self::IndexSliceRepr, TessMapError> {
+    tess.raw.indices()
+  }
+
+  unsafe fn indices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::IndexSliceMutRepr, TessMapError> {
+    tess.raw.indices_mut()
+  }
+}
+
+unsafe impl<V, I, W, T> InstanceSliceBackend<V, I, W, Deinterleaved, T> for Mock
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>> + Deinterleave<T>,
+{
+  type InstanceSliceRepr = BufferSlice<T>;
+  type InstanceSliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn instances(tess: &mut Self::TessRepr) -> Result<Self::InstanceSliceRepr, TessMapError> {
+    if tess.instances.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSlice::new(&tess.instances[W::RANK]).transmute())
+    }
+  }
+
+  unsafe fn instances_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::InstanceSliceMutRepr, TessMapError> {
+    if tess.instances.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      Ok(BufferSliceMut::new(&mut tess.instances[W::RANK]).transmute())
+    }
+  }
+}
+
+fn into_attributes(data: Option<Vec<DeinterleavedData>>) -> Vec<Vec<u8>> {
+  data
+    .map(|attributes| attributes.into_iter().map(|a| a.into_vec()).collect())
+    .unwrap_or_default()
+}
+
+/// Resize `vec` to `len` items, new items being `value`.
+fn resize_vec<T>(vec: &mut Vec<T>, len: usize, value: T, preserve: PreserveContent)
+where
+  T: Copy,
+{
+  if preserve == PreserveContent::No {
+    vec.clear();
+  }
+
+  vec.resize(len, value);
+}
+
+/// Resize interleaved vertices or instances; attributeless vertices don’t have any storage.
+fn resize_interleaved<V>(vertices: &mut Vec<V>, len: usize, preserve: PreserveContent)
+where
+  V: Vertex,
+{
+  if !V::vertex_desc().is_empty() {
+    // vertices are made of plain numbers, for which zero bits are valid values
+    resize_vec(vertices, len, unsafe { mem::zeroed() }, preserve);
+  }
+}
+
+/// Resize deinterleaved vertices or instances, creating their attributes if needed.
+fn resize_deinterleaved<V>(attributes: &mut Vec<Vec<u8>>, len: usize, preserve: PreserveContent)
+where
+  V: Vertex,
+{
+  if attributes.is_empty() {
+    *attributes = V::vertex_desc()
+      .iter()
+      .map(|fmt| vec![0; len * attrib_weight(fmt)])
+      .collect();
+  } else {
+    for (attribute, fmt) in attributes.iter_mut().zip(V::vertex_desc()) {
+      resize_vec(attribute, len * attrib_weight(&fmt), 0, preserve);
+    }
+  }
+}
+
+/// Size in bytes of a vertex attribute.
+fn attrib_weight(desc: &VertexBufferDesc) -> usize {
+  let dim = match desc.attrib_desc.dim {
+    VertexAttribDim::Dim1 => 1,
+    VertexAttribDim::Dim2 => 2,
+    VertexAttribDim::Dim3 => 3,
+    VertexAttribDim::Dim4 => 4,
+  };
+
+  dim * desc.attrib_desc.unit_size
+}
@@ -0,0 +1,504 @@
+//! Mock texture implementation.
+
+use std::cell::RefCell;
+use std::mem;
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+use crate::readback::Readback;
+use crate::{Command, Mock, MockState};
+use luminance::backend::texture::{
+  Texture as TextureBackend, TextureBase, TextureCompressed as TextureCompressedBackend,
+  TextureCompression, TextureReadback as TextureReadbackBackend,
+};
+use luminance::pixel::{CompressedPixel, CompressionFamily, Pixel};
+use luminance::texture::{Dim, Dimensionable, GenMipmaps, Sampler, TextureError};
+
+/// Mock texture.
+///
+/// Every mipmap level is stored, but compressed textures don’t store their blocks.
+#[derive(Debug)]
+pub struct Texture {
+  pub(crate) id: usize,
+  dim: Dim,
+  extent: [u32; 3],
+  texel_size: usize,
+  levels: Vec<Vec<u8>>,
+  state: Rc<RefCell<MockState>>,
+}
+
+impl Texture {
+  /// Write bytes to a region of a mipmap level of the texture.
+  fn write_region(
+    &mut self,
+    level: usize,
+    offset: [u32; 3],
+    size: [u32; 3],
+    bytes: &[u8],
+  ) -> Result<(), TextureError> {
+    let extent = level_extent(self.dim, self.extent, level);
+    let [w, h, d] = extent;
+    let row_len = size[0] as usize * self.texel_size;
+    let expected_bytes = row_len * size[1] as usize * size[2] as usize;
+
+    if bytes.len() < expected_bytes {
+      return Err(TextureError::not_enough_pixels(expected_bytes, bytes.len()));
+    }
+
+    if offset[0] + size[0] > w || offset[1] + size[1] > h || offset[2] + size[2] > d {
+      return Err(TextureError::cannot_upload_texels(format!(
+        "region at {:?} of size {:?} is out of the {:?} texture",
+        offset, size, extent
+      )));
+    }
+
+    for (i, row) in bytes[..expected_bytes].chunks(row_len.max(1)).enumerate() {
+      let y = offset[1] as usize + i % size[1] as usize;
+      let z = offset[2] as usize + i / size[1] as usize;
+      let start = ((z * h as usize + y) * w as usize + offset[0] as usize) * self.texel_size;
+
+      self.levels[level][start..start + row_len].copy_from_slice(row);
+    }
+
+    Ok(())
+  }
+
+  /// Read bytes from a region of a mipmap level of the texture, which must be in bounds.
+  fn read_region(&self, level: usize, offset: [u32; 3], size: [u32; 3]) -> Vec<u8> {
+    let [w, h, _] = level_extent(self.dim, self.extent, level);
+    let row_len = size[0] as usize * self.texel_size;
+    let mut bytes = Vec::with_capacity(row_len * size[1] as usize * size[2] as usize);
+
+    for z in offset[2] as usize..(offset[2] + size[2]) as usize {
+      for y in offset[1] as usize..(offset[1] + size[1]) as usize {
+        let start = ((z * h as usize + y) * w as usize + offset[0] as usize) * self.texel_size;
+        bytes.extend_from_slice(&self.levels[level][start..start + row_len]);
+      }
+    }
+
+    bytes
+  }
+}
+
+/// Extent of a texture storage, as `[width, height, depth]`.
+///
+/// Array textures store layers as rows or slices, and cubemaps store faces as slices.
+pub(crate) fn extent<D>(size: D::Size) -> [u32; 3]
+where
+  D: Dimensionable,
+{
+  match D::dim() {
+    Dim::Dim1 => [D::width(size), 1, 1],
+    Dim::Dim2 | Dim::Dim1Array => [D::width(size), D::height(size), 1],
+    Dim::Dim3 | Dim::Dim2Array | Dim::Cubemap => [D::width(size), D::height(size), D::depth(size)],
+  }
+}
+
+/// Extent of the mipmap `level` of a texture storage of extent `extent`.
+///
+/// Layers of array textures and faces of cubemaps are kept along levels.
+fn level_extent(dim: Dim, [w, h, d]: [u32; 3], level: usize) -> [u32; 3] {
+  let halve = |x: u32| x.checked_shr(level as u32).unwrap_or(0).max(1);
+
+  match dim {
+    Dim::Dim1 | Dim::Dim1Array => [halve(w), h, d],
+    Dim::Dim2 | Dim::Dim2Array | Dim::Cubemap => [halve(w), halve(h), d],
+    Dim::Dim3 => [halve(w), halve(h), halve(d)],
+  }
+}
+
+/// Storage region covered by an offset and a size.
+///
+/// Cubemap regions only cover the face they are offset to.
+fn region<D>(offset: D::Offset, size: D::Size) -> ([u32; 3], [u32; 3])
+where
+  D: Dimensionable,
+{
+  match D::dim() {
+    Dim::Dim1 => ([D::x_offset(offset), 0, 0], extent::<D>(size)),
+    Dim::Dim2 | Dim::Dim1Array => (
+      [D::x_offset(offset), D::y_offset(offset), 0],
+      extent::<D>(size),
+    ),
+    Dim::Dim3 | Dim::Dim2Array => (
+      [
+        D::x_offset(offset),
+        D::y_offset(offset),
+        D::z_offset(offset),
+      ],
+      extent::<D>(size),
+    ),
+    Dim::Cubemap => (
+      [
+        D::x_offset(offset),
+        D::y_offset(offset),
+        D::z_offset(offset),
+      ],
+      [D::width(size), D::height(size), 1],
+    ),
+  }
+}
+
+unsafe fn as_bytes<T>(texels: &[T]) -> &[u8] {
+  slice::from_raw_parts(texels.as_ptr() as *const u8, mem::size_of_val(texels))
+}
+
+unsafe fn from_bytes<T>(bytes: &[u8]) -> Vec<T>
+where
+  T: Copy + Default,
+{
+  let mut texels = vec![T::default(); bytes.len() / mem::size_of::<T>()];
+
+  slice::from_raw_parts_mut(texels.as_mut_ptr() as *mut u8, bytes.len()).copy_from_slice(bytes);
+
+  texels
+}
+
+unsafe impl TextureBase for Mock {
+  type TextureRepr = Texture;
+}
+
+unsafe impl<D, P> TextureBackend<D, P> for Mock
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  unsafe fn new_texture(
+    &mut self,
+    size: D::Size,
+    mipmaps: usize,
+    _: Sampler,
+  ) -> Result<Self::TextureRepr, TextureError> {
+    let mipmaps = mipmaps + 1; // + 1 prevent having 0 mipmaps
+    let extent = extent::<D>(size);
+    let pixel_format = P::pixel_format();
+    let texel_size = match pixel_format.format.compression() {
+      Some(_) => 0,
+      None => pixel_format.format.size(),
+    };
+    let levels = (0..mipmaps)
+      .map(|level| {
+        let level_extent = level_extent(D::dim(), extent, level);
+        vec![0; level_extent.iter().product::<u32>() as usize * texel_size]
+      })
+      .collect();
+
+    let mut state = self.state.borrow_mut();
+    let id = state.new_texture_id();
+
+    state.record(Command::NewTexture {
+      texture: id,
+      dim: D::dim(),
+      size: extent,
+      mipmaps,
+      pixel_format,
+    });
+
+    Ok(Texture {
+      id,
+      dim: D::dim(),
+      extent,
+      texel_size,
+      levels,
+      state: self.state.clone(),
+    })
+  }
+
+  unsafe fn mipmaps(texture: &Self::TextureRepr) -> usize {
+    texture.levels.len()
+  }
+
+  unsafe fn clear_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    let (offset, size) = region::<D>(offset, size);
+    let texels = vec![pixel; size.iter().product::<u32>() as usize];
+
+    texture.write_region(0, offset, size, as_bytes(&texels))?;
+
+    texture.state.borrow_mut().record(Command::ClearTexture {
+      texture: texture.id,
+      offset,
+      size,
+      gen_mipmaps,
+    });
+
+    Ok(())
+  }
+
+  unsafe fn clear(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::clear_part(texture, gen_mipmaps, D::ZERO_OFFSET, size, pixel)
+  }
+
+  unsafe fn upload_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, _>(texture, gen_mipmaps, offset, size, texels)
+  }
+
+  unsafe fn upload(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::upload_part(texture, gen_mipmaps, D::ZERO_OFFSET, size, texels)
+  }
+
+  unsafe fn upload_part_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, _>(texture, gen_mipmaps, offset, size, texels)
+  }
+
+  unsafe fn upload_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::upload_part_raw(
+      texture,
+      gen_mipmaps,
+      D::ZERO_OFFSET,
+      size,
+      texels,
+    )
+  }
+
+  unsafe fn get_raw_texels(
+    texture: &Self::TextureRepr,
+    _: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    texture.state.borrow_mut().record(Command::GetTexels {
+      texture: texture.id,
+    });
+
+    Ok(from_bytes(&texture.levels[0]))
+  }
+
+  unsafe fn copy_from(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    src: &Self::TextureRepr,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError> {
+    let (dst_offset, _) = region::<D>(dst_offset, size);
+    let (src_offset, size) = region::<D>(src_offset, size);
+    let bytes = src.read_region(0, src_offset, size);
+
+    texture.write_region(0, dst_offset, size, &bytes)?;
+
+    texture.state.borrow_mut().record(Command::CopyTexture {
+      src: src.id,
+      dst: texture.id,
+      src_offset,
+      dst_offset,
+      size,
+      gen_mipmaps,
+    });
+
+    Ok(())
+  }
+
+  unsafe fn upload_level_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    upload_level_texels::<D, _>(texture, level, offset, size, texels)
+  }
+
+  unsafe fn upload_level_part_raw(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    upload_level_texels::<D, _>(texture, level, offset, size, texels)
+  }
+
+  unsafe fn get_level_part_raw_texels(
+    texture: &Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let (offset, size) = region::<D>(offset, size);
+
+    texture.state.borrow_mut().record(Command::GetLevelTexels {
+      texture: texture.id,
+      level,
+      offset,
+      size,
+    });
+
+    Ok(from_bytes(&texture.read_region(level, offset, size)))
+  }
+
+  unsafe fn generate_mipmaps(texture: &mut Self::TextureRepr) -> Result<(), TextureError> {
+    texture.state.borrow_mut().record(Command::GenerateMipmaps {
+      texture: texture.id,
+    });
+
+    Ok(())
+  }
+}
+
+unsafe impl<D, P> TextureReadbackBackend<D, P> for Mock
+where
+  D: Dimensionable,
+  P: Pixel,
+  P::RawEncoding: Copy,
+{
+  unsafe fn get_raw_texels_async(
+    texture: &Self::TextureRepr,
+    _: D::Size,
+  ) -> Result<Self::ReadbackRepr, TextureError> {
+    let bytes = &texture.levels[0];
+    let len = bytes.len() / mem::size_of::<P::RawEncoding>();
+    let mut data = Vec::with_capacity(len);
+
+    ptr::copy_nonoverlapping(bytes.as_ptr(), data.as_mut_ptr() as *mut u8, bytes.len());
+    data.set_len(len);
+
+    texture.state.borrow_mut().record(Command::GetTexelsAsync {
+      texture: texture.id,
+    });
+
+    Ok(Readback { data })
+  }
+}
+
+unsafe impl TextureCompression for Mock {
+  unsafe fn supports_compression(&mut self, _: CompressionFamily) -> bool {
+    true
+  }
+}
+
+unsafe impl<D, P> TextureCompressedBackend<D, P> for Mock
+where
+  D: Dimensionable,
+  P: CompressedPixel,
+{
+  unsafe fn upload_compressed_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    _: &[u8],
+  ) -> Result<(), TextureError> {
+    let (offset, size) = region::<D>(offset, size);
+
+    texture
+      .state
+      .borrow_mut()
+      .record(Command::UploadCompressedTexture {
+        texture: texture.id,
+        level,
+        offset,
+        size,
+      });
+
+    Ok(())
+  }
+
+  unsafe fn upload_compressed(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    size: D::Size,
+    _: &[u8],
+  ) -> Result<(), TextureError> {
+    texture
+      .state
+      .borrow_mut()
+      .record(Command::UploadCompressedTexture {
+        texture: texture.id,
+        level,
+        offset: [0, 0, 0],
+        size: level_extent(D::dim(), extent::<D>(size), level),
+      });
+
+    Ok(())
+  }
+}
+
+unsafe fn upload_texels<D, T>(
+  texture: &mut Texture,
+  gen_mipmaps: GenMipmaps,
+  offset: D::Offset,
+  size: D::Size,
+  texels: &[T],
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let (offset, size) = region::<D>(offset, size);
+
+  texture.write_region(0, offset, size, as_bytes(texels))?;
+
+  texture.state.borrow_mut().record(Command::UploadTexture {
+    texture: texture.id,
+    offset,
+    size,
+    gen_mipmaps,
+  });
+
+  Ok(())
+}
+
+unsafe fn upload_level_texels<D, T>(
+  texture: &mut Texture,
+  level: usize,
+  offset: D::Offset,
+  size: D::Size,
+  texels: &[T],
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let (offset, size) = region::<D>(offset, size);
+
+  texture.write_region(level, offset, size, as_bytes(texels))?;
+
+  texture
+    .state
+    .borrow_mut()
+    .record(Command::UploadTextureLevel {
+      texture: texture.id,
+      level,
+      offset,
+      size,
+    });
+
+  Ok(())
+}
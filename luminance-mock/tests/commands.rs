@@ -0,0 +1,1399 @@
+use image::{DynamicImage, GrayImage};
+use luminance::buffer::{BufferError, BufferUsage, PreserveContent};
+use luminance::compute::ComputeError;
+use luminance::context::GraphicsContext as _;
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError};
+use luminance::image::TextureImage;
+use luminance::loader::{LoaderError, TextureContainer};
+use luminance::pipeline::{PipelineError, PipelineState, StorageBufferBinding};
+use luminance::pixel::{
+  ASTC6x6, Depth24Stencil8, Depth32F, NormR8UI, NormRGBA8UI, Pixel as _, BC1, BC7, R32F, RGBA8UI,
+};
+use luminance::query::{QueryError, QueryType};
+use luminance::render_state::RenderState;
+use luminance::renderbuffer::Renderbuffer;
+use luminance::shader::{StageType, Uniform, UniformType};
+use luminance::stencil::{StencilComparison, StencilOp, StencilOperations, StencilTest};
+use luminance::stream::{StreamBuffer, StreamError};
+use luminance::tess::{Mode, TessError, View as _};
+use luminance::texture::{
+  CubeFace, Cubemap, Dim, Dim2, Dim2Array, Dim3, GenMipmaps, Sampler, Texture, TextureError,
+};
+use luminance::UniformInterface;
+use luminance_mock::{Command, Mock, MockSurface};
+use std::error::Error;
+
+#[derive(UniformInterface)]
+struct ShaderInterface {
+  time: Uniform<f32>,
+}
+
+#[test]
+fn record_draw() {
+  let mut surface = MockSurface::new([800, 600]);
+
+  let mut program = surface
+    .new_shader_program::<(), (), ShaderInterface>()
+    .from_strings("vs", None, None, "fs")
+    .unwrap()
+    .ignore_warnings();
+
+  let tess = surface
+    .new_tess()
+    .set_vertex_nb(3)
+    .set_mode(Mode::Triangle)
+    .build()
+    .unwrap();
+
+  let back_buffer = surface.back_buffer().unwrap();
+  let render_state = RenderState::default().set_depth_test(None);
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.time, 1.);
+
+          rdr_gate.render(&render_state, |mut tess_gate| {
+            tess_gate.render(&tess)?;
+            tess_gate.render(tess.view(1..).unwrap())
+          })
+        })
+      },
+    )
+    .into_result();
+
+  render.unwrap();
+
+  assert_eq!(
+    surface.backend().take_commands(),
+    vec![
+      Command::NewStage {
+        stage: 0,
+        ty: StageType::VertexShader,
+        src: "vs".to_owned(),
+      },
+      Command::NewStage {
+        stage: 1,
+        ty: StageType::FragmentShader,
+        src: "fs".to_owned(),
+      },
+      Command::NewProgram {
+        program: 0,
+        vertex: 0,
+        tess: None,
+        geometry: None,
+        fragment: 1,
+      },
+      Command::NewUniform {
+        program: 0,
+        name: "time".to_owned(),
+        ty: UniformType::Float,
+      },
+      Command::NewTess {
+        tess: 0,
+        mode: Mode::Triangle,
+        vert_nb: 3,
+        inst_nb: 0,
+        indexed: false,
+        usage: BufferUsage::Static,
+      },
+      Command::BackBuffer {
+        framebuffer: 0,
+        size: [800, 600],
+      },
+      Command::StartPipeline {
+        framebuffer: 0,
+        state: PipelineState::default(),
+      },
+      Command::ApplyShaderProgram { program: 0 },
+      Command::SetUniform {
+        program: 0,
+        name: "time".to_owned(),
+        ty: UniformType::Float,
+      },
+      Command::EnterRenderState {
+        state: render_state.clone(),
+      },
+      Command::Render {
+        tess: 0,
+        start_index: 0,
+        vert_nb: 3,
+        inst_nb: 0,
+      },
+      Command::Render {
+        tess: 0,
+        start_index: 1,
+        vert_nb: 2,
+        inst_nb: 0,
+      },
+    ]
+  );
+
+  assert!(surface.backend().commands().is_empty());
+}
+
+#[test]
+fn record_texture_upload() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut texture = surface
+    .new_texture::<Dim2, NormRGBA8UI>([2, 2], 0, Sampler::default())
+    .unwrap();
+
+  texture
+    .upload_part_raw(GenMipmaps::No, [1, 0], [1, 2], &[1, 2, 3, 4, 5, 6, 7, 8])
+    .unwrap();
+
+  assert_eq!(
+    texture.get_raw_texels().unwrap(),
+    vec![0, 0, 0, 0, 1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8]
+  );
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[0],
+    Command::NewTexture {
+      texture: 0,
+      dim: Dim::Dim2,
+      size: [2, 2, 1],
+      mipmaps: 1,
+      pixel_format: NormRGBA8UI::pixel_format(),
+    }
+  );
+  assert_eq!(
+    commands[1..],
+    [
+      Command::UploadTexture {
+        texture: 0,
+        offset: [1, 0, 0],
+        size: [1, 2, 1],
+        gen_mipmaps: GenMipmaps::No,
+      },
+      Command::GetTexels { texture: 0 },
+    ]
+  );
+}
+
+#[test]
+fn record_texture_level_upload() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut texture = surface
+    .new_texture::<Cubemap, NormR8UI>(4, 1, Sampler::default())
+    .unwrap();
+
+  // every face of the 2×2 level holds its index
+  let level = (0..6u8).flat_map(|face| vec![face; 4]).collect::<Vec<_>>();
+
+  texture.upload_level_raw(1, &level).unwrap();
+  texture
+    .upload_level_part_raw(0, ([1, 2], CubeFace::NegativeY), 2, &[1, 2, 3, 4])
+    .unwrap();
+
+  assert_eq!(texture.get_level_raw_texels(1).unwrap(), level);
+  assert_eq!(
+    texture
+      .get_level_part_raw_texels(1, ([1, 0], CubeFace::PositiveZ), 1)
+      .unwrap(),
+    vec![4]
+  );
+  assert_eq!(
+    texture
+      .get_level_part_raw_texels(0, ([1, 2], CubeFace::NegativeY), 2)
+      .unwrap(),
+    vec![1, 2, 3, 4]
+  );
+
+  assert!(matches!(
+    texture.upload_level_raw(2, &level),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+  assert!(matches!(
+    texture.upload_level_part_raw(1, ([1, 1], CubeFace::PositiveX), 2, &[0; 4]),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+  assert!(matches!(
+    texture.upload_level_part_raw(0, ([u32::MAX, 0], CubeFace::PositiveX), 2, &[0; 4]),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+  assert!(matches!(
+    texture.get_level_part_raw_texels(2, ([0, 0], CubeFace::PositiveX), 1),
+    Err(TextureError::CannotRetrieveTexels(_))
+  ));
+  assert!(matches!(
+    texture.upload_level_raw(1, &level[..12]),
+    Err(TextureError::NotEnoughPixels { .. })
+  ));
+  assert!(matches!(
+    texture.upload_level_raw(1, &[&level[..], &[0; 5]].concat()),
+    Err(TextureError::NotEnoughPixels {
+      expected_bytes: 30,
+      provided_bytes: 29
+    })
+  ));
+
+  let commands = surface.backend().take_commands();
+  let face_level = |face| Command::UploadTextureLevel {
+    texture: 0,
+    level: 1,
+    offset: [0, 0, face],
+    size: [2, 2, 1],
+  };
+  let get_face_level = |face| Command::GetLevelTexels {
+    texture: 0,
+    level: 1,
+    offset: [0, 0, face],
+    size: [2, 2, 1],
+  };
+
+  assert_eq!(
+    commands[1..],
+    [
+      face_level(0),
+      face_level(1),
+      face_level(2),
+      face_level(3),
+      face_level(4),
+      face_level(5),
+      Command::UploadTextureLevel {
+        texture: 0,
+        level: 0,
+        offset: [1, 2, 3],
+        size: [2, 2, 1],
+      },
+      get_face_level(0),
+      get_face_level(1),
+      get_face_level(2),
+      get_face_level(3),
+      get_face_level(4),
+      get_face_level(5),
+      Command::GetLevelTexels {
+        texture: 0,
+        level: 1,
+        offset: [1, 0, 4],
+        size: [1, 1, 1],
+      },
+      Command::GetLevelTexels {
+        texture: 0,
+        level: 0,
+        offset: [1, 2, 3],
+        size: [2, 2, 1],
+      },
+    ]
+  );
+}
+
+#[test]
+fn record_compressed_texture_upload() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut texture = surface
+    .new_texture::<Dim2Array, ASTC6x6>(([13, 7], 2), 1, Sampler::default())
+    .unwrap();
+
+  // 3×2 blocks per layer for the base level, a single one for the 6×3 level
+  texture
+    .upload_compressed_part(0, ([6, 0], 1), ([7, 7], 1), &[0; 2 * 2 * 16])
+    .unwrap();
+  texture.upload_compressed(1, &[0; 2 * 16]).unwrap();
+
+  assert!(matches!(
+    texture.upload_compressed_part(0, ([0, 0], 0), ([4, 6], 1), &[0; 16]),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+  assert!(matches!(
+    texture.upload_compressed(0, &[0; 3 * 2 * 16]),
+    Err(TextureError::NotEnoughPixels { .. })
+  ));
+  assert!(matches!(
+    texture.upload_compressed_part(0, ([0, 0], u32::MAX), ([6, 6], 2), &[0; 2 * 16]),
+    Err(TextureError::CannotUploadTexels(_))
+  ));
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[1..],
+    [
+      Command::UploadCompressedTexture {
+        texture: 0,
+        level: 0,
+        offset: [6, 0, 1],
+        size: [7, 7, 1],
+      },
+      Command::UploadCompressedTexture {
+        texture: 0,
+        level: 1,
+        offset: [0, 0, 0],
+        size: [6, 3, 2],
+      },
+    ]
+  );
+
+  // compressed pixel formats are restricted to 2D, cubemap and 2D array textures
+  assert!(matches!(
+    surface.new_texture::<Dim3, BC7>([4, 4, 4], 0, Sampler::default()),
+    Err(TextureError::UnsupportedPixelFormat(_))
+  ));
+}
+
+/// Build a KTX2 file out of its header fields and mipmap levels.
+fn ktx2_file(vk_format: u32, size: [u32; 3], layers: u32, faces: u32, levels: &[&[u8]]) -> Vec<u8> {
+  let mut file = vec![
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+  ];
+  let header = [
+    vk_format,
+    1,
+    size[0],
+    size[1],
+    size[2],
+    layers,
+    faces,
+    levels.len() as u32,
+    0,
+  ];
+  header
+    .iter()
+    .for_each(|x| file.extend_from_slice(&x.to_le_bytes()));
+  file.resize(80, 0);
+
+  let mut offset = 80 + levels.len() * 24;
+  for level in levels {
+    for x in &[offset, level.len(), level.len()] {
+      file.extend_from_slice(&(*x as u64).to_le_bytes());
+    }
+
+    offset += level.len();
+  }
+
+  levels
+    .iter()
+    .for_each(|level| file.extend_from_slice(level));
+  file
+}
+
+/// Build a legacy DDS file out of its header fields and texels.
+fn dds_file(four_cc: &[u8; 4], size: u32, levels: u32, caps2: u32, texels: &[u8]) -> Vec<u8> {
+  let mut file = vec![0; 128];
+  let mut write = |offset: usize, x: &[u8]| file[offset..offset + x.len()].copy_from_slice(x);
+
+  write(0, b"DDS ");
+  write(4, &124u32.to_le_bytes());
+  write(8, &0x2100fu32.to_le_bytes());
+  write(12, &size.to_le_bytes());
+  write(16, &size.to_le_bytes());
+  write(28, &levels.to_le_bytes());
+  write(76, &32u32.to_le_bytes());
+  write(80, &0x4u32.to_le_bytes());
+  write(84, four_cc);
+  write(112, &caps2.to_le_bytes());
+
+  file.extend_from_slice(texels);
+  file
+}
+
+#[test]
+fn load_ktx2_texture() {
+  let mut surface = MockSurface::new([800, 600]);
+  let texels = (0..16).collect::<Vec<u8>>();
+  // VK_FORMAT_R8G8B8A8_UNORM, 2×1 texture with 2 layers
+  let container = TextureContainer::from_ktx2(&ktx2_file(37, [2, 1, 0], 2, 1, &[&texels])).unwrap();
+
+  assert_eq!(container.dim(), Dim::Dim2Array);
+  assert_eq!(container.pixel_format(), NormRGBA8UI::pixel_format());
+  assert_eq!(container.mipmaps(), 1);
+
+  let texture = container
+    .new_texture::<_, Dim2Array, NormRGBA8UI>(&mut surface, Sampler::default())
+    .unwrap();
+
+  assert_eq!(texture.size(), ([2, 1], 2));
+  assert_eq!(texture.get_raw_texels().unwrap(), texels);
+
+  assert_eq!(
+    container
+      .new_texture::<_, Dim2, NormRGBA8UI>(&mut surface, Sampler::default())
+      .err(),
+    Some(LoaderError::DimMismatch {
+      expected: Dim::Dim2,
+      found: Dim::Dim2Array
+    })
+  );
+  assert!(matches!(
+    container.new_texture::<_, Dim2Array, RGBA8UI>(&mut surface, Sampler::default()),
+    Err(LoaderError::PixelFormatMismatch { .. })
+  ));
+
+  // hand-made mipmaps are uploaded as-is
+  let container =
+    TextureContainer::from_ktx2(&ktx2_file(9, [2, 2, 0], 0, 1, &[&[1, 2, 3, 4], &[42]])).unwrap();
+  let texture = container
+    .new_texture::<_, Dim2, NormR8UI>(&mut surface, Sampler::default())
+    .unwrap();
+
+  assert_eq!(texture.get_level_raw_texels(1).unwrap(), vec![42]);
+
+  // the level index points past the end of the file
+  let mut truncated = ktx2_file(37, [2, 1, 0], 2, 1, &[&texels]);
+  truncated.truncate(truncated.len() - 1);
+
+  assert!(matches!(
+    TextureContainer::from_ktx2(&truncated),
+    Err(LoaderError::InvalidData(_))
+  ));
+}
+
+#[test]
+fn load_dds_compressed_cubemap() {
+  let mut surface = MockSurface::new([800, 600]);
+
+  // every face holds its 2×2 blocks base level followed by its single block level
+  let texels = (0..6u8)
+    .flat_map(|face| vec![face; 5 * 8])
+    .collect::<Vec<_>>();
+  let container = TextureContainer::from_dds(&dds_file(b"DXT1", 8, 2, 0xFE00, &texels)).unwrap();
+
+  assert_eq!(container.dim(), Dim::Cubemap);
+  assert_eq!(container.pixel_format(), BC1::pixel_format());
+  assert_eq!(container.mipmaps(), 2);
+  assert_eq!(
+    container.level(1),
+    Some(&(0..6u8).flat_map(|face| vec![face; 8]).collect::<Vec<_>>()[..])
+  );
+
+  let texture = container
+    .new_compressed_texture::<_, Cubemap, BC1>(&mut surface, Sampler::default())
+    .unwrap();
+
+  assert_eq!(texture.size(), 8);
+  assert_eq!(
+    surface.backend().take_commands()[1..],
+    [
+      Command::UploadCompressedTexture {
+        texture: 0,
+        level: 0,
+        offset: [0, 0, 0],
+        size: [8, 8, 6],
+      },
+      Command::UploadCompressedTexture {
+        texture: 0,
+        level: 1,
+        offset: [0, 0, 0],
+        size: [4, 4, 6],
+      },
+    ]
+  );
+
+  // cubemaps must have their six faces
+  assert!(matches!(
+    TextureContainer::from_dds(&dds_file(b"DXT1", 8, 2, 0x0600, &texels)),
+    Err(LoaderError::UnsupportedFormat(_))
+  ));
+}
+
+#[test]
+fn reject_malformed_containers() {
+  // the second level of every face is missing a byte
+  let texels = vec![0; 6 * 5 * 8 - 1];
+  assert!(matches!(
+    TextureContainer::from_dds(&dds_file(b"DXT1", 8, 2, 0xFE00, &texels)),
+    Err(LoaderError::InvalidData(_))
+  ));
+
+  // headers declaring huge textures are rejected before anything is allocated
+  assert!(matches!(
+    TextureContainer::from_dds(&dds_file(b"DXT5", 1 << 20, 1, 0, &[])),
+    Err(LoaderError::InvalidData(_))
+  ));
+  assert!(matches!(
+    TextureContainer::from_dds(&dds_file(b"DXT5", u32::MAX, 1, 0, &[])),
+    Err(LoaderError::InvalidData(_))
+  ));
+  assert!(matches!(
+    TextureContainer::from_dds(&dds_file(b"DXT1", 8, u32::MAX, 0, &[])),
+    Err(LoaderError::InvalidData(_))
+  ));
+  assert!(matches!(
+    TextureContainer::from_ktx2(&ktx2_file(37, [u32::MAX; 3], 0, 1, &[&[0; 4]])),
+    Err(LoaderError::InvalidData(_))
+  ));
+
+  // level counts and level lengths are checked before any level is copied
+  let mut ktx2 = ktx2_file(9, [2, 2, 0], 0, 1, &[&[0; 4]]);
+  ktx2[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+  assert!(matches!(
+    TextureContainer::from_ktx2(&ktx2),
+    Err(LoaderError::InvalidData(_))
+  ));
+
+  let mut ktx2 = ktx2_file(9, [2, 2, 0], 0, 1, &[&[0; 4]]);
+  ktx2[88..96].copy_from_slice(&u64::MAX.to_le_bytes());
+  assert!(matches!(
+    TextureContainer::from_ktx2(&ktx2),
+    Err(LoaderError::InvalidData(_))
+  ));
+
+  assert!(matches!(
+    TextureContainer::from_ktx2(&ktx2_file(9, [2, 2, 0], 0, 1, &[&[0; 3]])),
+    Err(LoaderError::InvalidData(_))
+  ));
+}
+
+#[test]
+fn convert_images() {
+  let mut surface = MockSurface::new([800, 600]);
+  let image = GrayImage::from_raw(1, 2, vec![0, 255]).unwrap();
+
+  let texture = Texture::<_, Dim2, R32F>::from_image(
+    &mut surface,
+    &DynamicImage::ImageLuma8(image),
+    0,
+    Sampler::default(),
+  )
+  .unwrap();
+
+  // the first row of the texture is the bottom one of the image
+  assert_eq!(texture.size(), [1, 2]);
+  assert_eq!(texture.get_raw_texels().unwrap(), [1., 0.]);
+
+  let image: TextureImage<R32F> = texture.to_image().unwrap();
+
+  assert_eq!(image.dimensions(), (1, 2));
+  assert_eq!(image.into_raw(), [0., 1.]);
+}
+
+#[test]
+fn record_texture_copy() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut src = surface
+    .new_texture::<Dim2, NormRGBA8UI>([2, 2], 0, Sampler::default())
+    .unwrap();
+  let mut dst = surface
+    .new_texture::<Dim2, NormRGBA8UI>([3, 1], 0, Sampler::default())
+    .unwrap();
+
+  src
+    .upload_raw(
+      GenMipmaps::No,
+      &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+    )
+    .unwrap();
+  dst
+    .copy_from(GenMipmaps::No, &src, [1, 1], [1, 0], [1, 1])
+    .unwrap();
+
+  assert_eq!(
+    dst.get_raw_texels().unwrap(),
+    vec![0, 0, 0, 0, 13, 14, 15, 16, 0, 0, 0, 0]
+  );
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[3..],
+    [
+      Command::CopyTexture {
+        src: 0,
+        dst: 1,
+        src_offset: [1, 1, 0],
+        dst_offset: [1, 0, 0],
+        size: [1, 1, 1],
+        gen_mipmaps: GenMipmaps::No,
+      },
+      Command::GetTexels { texture: 1 },
+    ]
+  );
+
+  // regions must fit in both textures
+  assert!(matches!(
+    dst.copy_from(GenMipmaps::No, &src, [1, 1], [0, 0], [2, 1]),
+    Err(TextureError::CannotCopyTexels(_))
+  ));
+  assert!(matches!(
+    dst.copy_from(GenMipmaps::No, &src, [0, 0], [2, 0], [2, 1]),
+    Err(TextureError::CannotCopyTexels(_))
+  ));
+  assert!(surface.backend().commands().is_empty());
+}
+
+#[test]
+fn record_mipmap_generation() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut texture = surface
+    .new_texture::<Dim2, NormRGBA8UI>([4, 4], 2, Sampler::default())
+    .unwrap();
+
+  texture.generate_mipmaps().unwrap();
+
+  let commands = surface.backend().take_commands();
+  assert_eq!(commands[1..], [Command::GenerateMipmaps { texture: 0 }]);
+
+  // compressed mipmaps must be uploaded
+  let mut compressed = surface
+    .new_texture::<Dim2, BC1>([4, 4], 0, Sampler::default())
+    .unwrap();
+  surface.backend().take_commands();
+
+  assert!(matches!(
+    compressed.generate_mipmaps(),
+    Err(TextureError::CannotGenerateMipmaps(_))
+  ));
+
+  // the base level must be in the mipmap range
+  let sampler = Sampler {
+    base_level: 2,
+    max_level: Some(1),
+    ..Sampler::default()
+  };
+  assert!(matches!(
+    surface.new_texture::<Dim2, NormRGBA8UI>([4, 4], 2, sampler),
+    Err(TextureError::TextureStorageCreationFailed(_))
+  ));
+
+  let sampler = Sampler {
+    base_level: 3,
+    ..Sampler::default()
+  };
+  assert!(matches!(
+    surface.new_texture::<Dim2, NormRGBA8UI>([4, 4], 2, sampler),
+    Err(TextureError::TextureStorageCreationFailed(_))
+  ));
+  assert!(surface.backend().commands().is_empty());
+}
+
+#[test]
+fn record_framebuffer_blit() {
+  let mut surface = MockSurface::new([800, 600]);
+  let src = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Depth32F>([4, 4], 0, Sampler::default())
+    .unwrap();
+  let mut back_buffer = surface.back_buffer().unwrap();
+  let src_id = surface
+    .backend()
+    .take_commands()
+    .iter()
+    .fold(0, |id, command| match *command {
+      Command::NewFramebuffer { framebuffer, .. } => framebuffer,
+      _ => id,
+    });
+
+  src
+    .blit_to(
+      &mut back_buffer,
+      BlitRect::whole([4, 4]),
+      BlitRect::new(10, 20, 8, 8),
+      BlitMask::ALL,
+      BlitFilter::Nearest,
+    )
+    .unwrap();
+
+  assert_eq!(
+    surface.backend().take_commands(),
+    vec![Command::BlitFramebuffer {
+      src: src_id,
+      dst: src_id + 1,
+      src_rect: BlitRect::new(0, 0, 4, 4),
+      dst_rect: BlitRect::new(10, 20, 8, 8),
+      mask: BlitMask::ALL,
+      filter: BlitFilter::Nearest,
+    }]
+  );
+
+  // integral colors cannot be filtered nor mixed with non-integral ones; depth cannot be filtered
+  let integral = surface
+    .new_framebuffer::<Dim2, RGBA8UI, ()>([4, 4], 0, Sampler::default())
+    .unwrap();
+  let mut other_integral = surface
+    .new_framebuffer::<Dim2, RGBA8UI, ()>([4, 4], 0, Sampler::default())
+    .unwrap();
+  let mut normalized = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, ()>([4, 4], 0, Sampler::default())
+    .unwrap();
+  let rect = BlitRect::whole([4, 4]);
+
+  surface.backend().take_commands();
+
+  assert!(matches!(
+    integral.blit_to(
+      &mut other_integral,
+      rect,
+      rect,
+      BlitMask::COLOR,
+      BlitFilter::Linear
+    ),
+    Err(FramebufferError::IncompatibleBlit(_))
+  ));
+  assert!(matches!(
+    integral.blit_to(
+      &mut normalized,
+      rect,
+      rect,
+      BlitMask::COLOR,
+      BlitFilter::Nearest
+    ),
+    Err(FramebufferError::IncompatibleBlit(_))
+  ));
+  assert!(matches!(
+    src.blit_to(
+      &mut back_buffer,
+      rect,
+      rect,
+      BlitMask::DEPTH,
+      BlitFilter::Linear
+    ),
+    Err(FramebufferError::IncompatibleBlit(_))
+  ));
+  assert!(surface.backend().commands().is_empty());
+
+  integral
+    .blit_to(
+      &mut other_integral,
+      rect,
+      rect,
+      BlitMask::COLOR,
+      BlitFilter::Nearest,
+    )
+    .unwrap();
+  src
+    .blit_to(
+      &mut normalized,
+      rect,
+      rect,
+      BlitMask::COLOR,
+      BlitFilter::Linear,
+    )
+    .unwrap();
+
+  assert_eq!(surface.backend().take_commands().len(), 2);
+}
+
+#[test]
+fn record_multisample_framebuffer() {
+  let mut surface = MockSurface::new([800, 600]);
+  let framebuffer = surface
+    .new_multisample_framebuffer::<NormRGBA8UI, Depth32F>([4, 4], 4, 0, Sampler::default())
+    .unwrap();
+  let commands = surface.backend().take_commands();
+  let framebuffer_id = match commands[0] {
+    Command::NewMultisampleFramebuffer {
+      framebuffer,
+      size,
+      samples,
+      mipmaps,
+    } => {
+      assert_eq!((size, samples, mipmaps), ([4, 4], 4, 1));
+      framebuffer
+    }
+    ref command => panic!("unexpected command: {:?}", command),
+  };
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(&framebuffer, &PipelineState::default(), |_, _| Ok(()))
+    .into_result();
+  render.unwrap();
+
+  assert_eq!(
+    surface.backend().take_commands().last(),
+    Some(&Command::ResolveFramebuffer {
+      framebuffer: framebuffer_id
+    })
+  );
+
+  // single-sampled framebuffers are never resolved
+  let back_buffer = surface.back_buffer().unwrap();
+  surface.backend().take_commands();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(&back_buffer, &PipelineState::default(), |_, _| Ok(()))
+    .into_result();
+  render.unwrap();
+
+  assert!(!surface
+    .backend()
+    .take_commands()
+    .iter()
+    .any(|command| matches!(command, Command::ResolveFramebuffer { .. })));
+
+  for samples in [0, luminance_mock::MAX_SAMPLES + 1] {
+    assert!(matches!(
+      surface.new_multisample_framebuffer::<NormRGBA8UI, ()>(
+        [4, 4],
+        samples,
+        0,
+        Sampler::default()
+      ),
+      Err(FramebufferError::UnsupportedSamples(n)) if n == samples
+    ));
+  }
+}
+
+#[test]
+fn record_renderbuffers() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, Renderbuffer<Mock, NormRGBA8UI>, Renderbuffer<Mock, Depth32F>>(
+      [4, 4],
+      0,
+      Sampler::default(),
+    )
+    .unwrap();
+  let commands = surface.backend().take_commands();
+  let framebuffer_id = match commands[0] {
+    Command::NewFramebuffer { framebuffer, .. } => framebuffer,
+    ref command => panic!("unexpected command: {:?}", command),
+  };
+
+  assert_eq!(
+    commands[1..],
+    [
+      Command::NewRenderbuffer {
+        renderbuffer: 0,
+        size: [4, 4],
+        pixel_format: NormRGBA8UI::pixel_format(),
+      },
+      Command::AttachColorRenderbuffer {
+        framebuffer: framebuffer_id,
+        renderbuffer: 0,
+        index: 0,
+      },
+      Command::NewRenderbuffer {
+        renderbuffer: 1,
+        size: [4, 4],
+        pixel_format: Depth32F::pixel_format(),
+      },
+      Command::AttachDepthRenderbuffer {
+        framebuffer: framebuffer_id,
+        renderbuffer: 1,
+      },
+    ]
+  );
+  assert_eq!(framebuffer.depth_slot().size(), [4, 4]);
+}
+
+#[test]
+fn record_stencil() {
+  let mut surface = MockSurface::new([800, 600]);
+  let framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Depth24Stencil8>([4, 4], 0, Sampler::default())
+    .unwrap();
+  let commands = surface.backend().take_commands();
+
+  assert!(commands.iter().any(|command| matches!(
+    command,
+    Command::NewTexture { pixel_format, .. } if *pixel_format == Depth24Stencil8::pixel_format()
+  )));
+
+  let pipeline_state = PipelineState::default().set_clear_stencil(3);
+  let render_state = RenderState::default()
+    .set_stencil_test(StencilTest::new(StencilComparison::Equal, 1, 0x0F))
+    .set_stencil_operations_separate(
+      StencilOperations::new(StencilOp::Keep, StencilOp::Zero, StencilOp::Replace),
+      StencilOperations::default(),
+    )
+    .set_stencil_write_mask(0xF0);
+
+  let mut program = surface
+    .new_shader_program::<(), (), ()>()
+    .from_strings("vs", None, None, "fs")
+    .unwrap()
+    .ignore_warnings();
+  surface.backend().take_commands();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(&framebuffer, &pipeline_state, |_, mut shd_gate| {
+      shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+        rdr_gate.render(&render_state, |_| Ok(()))
+      })
+    })
+    .into_result();
+  render.unwrap();
+
+  let commands = surface.backend().take_commands();
+
+  assert!(commands.contains(&Command::StartPipeline {
+    framebuffer: 0,
+    state: pipeline_state,
+  }));
+  assert!(commands.contains(&Command::EnterRenderState {
+    state: render_state,
+  }));
+}
+
+#[derive(UniformInterface)]
+struct ComputeInterface {
+  time: Uniform<f32>,
+  particles: Uniform<StorageBufferBinding<u32>>,
+}
+
+#[test]
+fn record_dispatch() {
+  let mut surface = MockSurface::new([800, 600]);
+
+  let mut program = surface
+    .new_compute_program::<ComputeInterface>("cs")
+    .unwrap()
+    .ignore_warnings();
+  let mut buffer = surface
+    .new_buffer_from_vec(vec![0u32; 4], BufferUsage::Dynamic)
+    .unwrap();
+
+  surface
+    .dispatch::<ComputeError, _, _>(&mut program, [4, 1, 1], |pipeline, mut iface, uni| {
+      let bound = pipeline.bind_storage_buffer(&mut buffer)?;
+      iface.set(&uni.time, 1.);
+      iface.set(&uni.particles, bound.binding());
+      Ok(())
+    })
+    .unwrap();
+
+  assert_eq!(
+    surface.backend().take_commands(),
+    vec![
+      Command::NewStage {
+        stage: 0,
+        ty: StageType::ComputeShader,
+        src: "cs".to_owned(),
+      },
+      Command::NewComputeProgram {
+        program: 0,
+        stage: 0,
+      },
+      Command::NewUniform {
+        program: 0,
+        name: "time".to_owned(),
+        ty: UniformType::Float,
+      },
+      Command::NewUniform {
+        program: 0,
+        name: "particles".to_owned(),
+        ty: UniformType::StorageBufferBinding,
+      },
+      Command::NewBuffer {
+        buffer: 0,
+        len: 4,
+        usage: BufferUsage::Dynamic,
+      },
+      Command::ApplyShaderProgram { program: 0 },
+      Command::BindStorageBuffer {
+        buffer: 0,
+        binding: 0,
+      },
+      Command::SetUniform {
+        program: 0,
+        name: "time".to_owned(),
+        ty: UniformType::Float,
+      },
+      Command::SetUniform {
+        program: 0,
+        name: "particles".to_owned(),
+        ty: UniformType::StorageBufferBinding,
+      },
+      Command::Dispatch {
+        workgroups: [4, 1, 1],
+      },
+    ]
+  );
+}
+
+#[test]
+fn record_queries() {
+  let mut surface = MockSurface::new([800, 600]);
+  let back_buffer = surface.back_buffer().unwrap();
+
+  let mut time_elapsed = surface.new_query(QueryType::TimeElapsed).unwrap();
+  let mut samples = surface.new_query(QueryType::SamplesPassed).unwrap();
+  let mut any_samples = surface.new_query(QueryType::AnySamplesPassed).unwrap();
+
+  assert_eq!(time_elapsed.try_get(), Ok(None));
+
+  let mut pipeline_gate = surface.new_pipeline_gate();
+  let render: Result<(), Box<dyn Error>> = pipeline_gate
+    .query(&mut time_elapsed, |pipeline_gate| {
+      pipeline_gate.pipeline(
+        &back_buffer,
+        &PipelineState::default(),
+        |_, mut shd_gate| {
+          shd_gate.query(&mut samples, |shd_gate| {
+            shd_gate.query(&mut any_samples, |_| Ok(()))
+          })
+        },
+      )
+    })
+    .into_result();
+
+  assert_eq!(
+    render.unwrap_err().downcast_ref::<QueryError>(),
+    Some(&QueryError::AlreadyActive(QueryType::AnySamplesPassed))
+  );
+
+  assert_eq!(time_elapsed.try_get(), Ok(Some(0)));
+  assert_eq!(samples.try_get(), Ok(Some(0)));
+  assert_eq!(any_samples.try_get(), Ok(None));
+
+  assert_eq!(
+    surface.backend().take_commands(),
+    vec![
+      Command::BackBuffer {
+        framebuffer: 0,
+        size: [800, 600],
+      },
+      Command::NewQuery {
+        query: 0,
+        ty: QueryType::TimeElapsed,
+      },
+      Command::NewQuery {
+        query: 1,
+        ty: QueryType::SamplesPassed,
+      },
+      Command::NewQuery {
+        query: 2,
+        ty: QueryType::AnySamplesPassed,
+      },
+      Command::BeginQuery { query: 0 },
+      Command::StartPipeline {
+        framebuffer: 0,
+        state: PipelineState::default(),
+      },
+      Command::BeginQuery { query: 1 },
+      Command::EndQuery { query: 1 },
+      Command::EndQuery { query: 0 },
+    ]
+  );
+}
+
+#[test]
+fn record_readbacks() {
+  let mut surface = MockSurface::new([800, 600]);
+  let buffer = surface
+    .new_buffer_from_vec(vec![1u32, 2, 3], BufferUsage::ReadBack)
+    .unwrap();
+  let mut texture = surface
+    .new_texture::<Dim2, NormRGBA8UI>([1, 1], 0, Sampler::default())
+    .unwrap();
+  texture.upload_raw(GenMipmaps::No, &[1, 2, 3, 4]).unwrap();
+
+  let mut values = buffer.whole_async().unwrap();
+  let mut texels = texture.get_raw_texels_async().unwrap();
+  let mut fence = surface.new_fence().unwrap();
+
+  assert!(fence.is_signaled());
+  assert_eq!(values.try_get(), Ok(Some(vec![1, 2, 3])));
+  assert_eq!(texels.try_get(), Ok(Some(vec![1, 2, 3, 4])));
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[3..],
+    [
+      Command::ReadBufferAsync { buffer: 0 },
+      Command::GetTexelsAsync { texture: 0 },
+      Command::NewFence { fence: 0 },
+    ]
+  );
+}
+
+#[test]
+fn record_buffer_usages() {
+  let mut surface = MockSurface::new([800, 600]);
+  let _static = surface
+    .new_buffer_repeating(2, 0u32, BufferUsage::Static)
+    .unwrap();
+  let _stream = surface.new_buffer::<u32>(3, BufferUsage::Stream).unwrap();
+  let _tess = surface
+    .new_tess()
+    .set_vertex_nb(3)
+    .set_usage(BufferUsage::Persistent)
+    .build()
+    .unwrap();
+
+  assert_eq!(
+    surface.backend().take_commands(),
+    vec![
+      Command::NewBuffer {
+        buffer: 0,
+        len: 2,
+        usage: BufferUsage::Static,
+      },
+      Command::NewBuffer {
+        buffer: 1,
+        len: 3,
+        usage: BufferUsage::Stream,
+      },
+      Command::NewTess {
+        tess: 0,
+        mode: Mode::Point,
+        vert_nb: 3,
+        inst_nb: 0,
+        indexed: false,
+        usage: BufferUsage::Persistent,
+      },
+    ]
+  );
+}
+
+#[test]
+fn record_stream_buffer() {
+  let mut surface = MockSurface::new([800, 600]);
+  let back_buffer = surface.back_buffer().unwrap();
+
+  // 16 bytes per item, so that frames and ranges are aligned on 16 items
+  let mut stream = StreamBuffer::<_, [f32; 4]>::new(&mut surface, 20, 2).unwrap();
+
+  assert_eq!(stream.frame_len(), 32);
+  assert_eq!(stream.frames(), 2);
+  assert_eq!(stream.buffer().len(), 64);
+
+  let a = stream.alloc(&[[1., 2., 3., 4.]; 3]).unwrap();
+  let b = stream.alloc(&[[5., 6., 7., 8.]]).unwrap();
+
+  assert_eq!(a, 0..3);
+  assert_eq!(b, 16..17);
+  assert_eq!(
+    stream.alloc(&[[0.; 4]]),
+    Err(StreamError::out_of_memory(1, 0))
+  );
+
+  stream.next_frame(&mut surface).unwrap();
+
+  let c = stream.alloc(&[[9., 10., 11., 12.]; 2]).unwrap();
+
+  assert_eq!(c, 32..34);
+
+  surface
+    .new_pipeline_gate()
+    .pipeline(&back_buffer, &PipelineState::default(), |pipeline, _| {
+      let bound_b = pipeline.bind_buffer_range(stream.buffer(), b.clone())?;
+      let bound_c = pipeline.bind_buffer_range(stream.buffer(), c.clone())?;
+
+      assert_eq!(bound_b.binding().binding(), 0);
+      assert_eq!(bound_c.binding().binding(), 1);
+      assert_eq!(
+        pipeline.bind_buffer_range(stream.buffer(), 1..2).err(),
+        Some(PipelineError::unaligned_buffer_range(16, 256))
+      );
+      assert_eq!(
+        pipeline.bind_buffer_range(stream.buffer(), 48..80).err(),
+        Some(PipelineError::buffer_range_out_of_bounds(48, 80, 64))
+      );
+
+      Ok::<_, PipelineError>(())
+    })
+    .into_result()
+    .unwrap();
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[1..],
+    [
+      Command::NewBuffer {
+        buffer: 0,
+        len: 64,
+        usage: BufferUsage::Persistent,
+      },
+      Command::WriteBufferRange {
+        buffer: 0,
+        offset: 0,
+        len: 3,
+      },
+      Command::WriteBufferRange {
+        buffer: 0,
+        offset: 16,
+        len: 1,
+      },
+      Command::NewFence { fence: 0 },
+      Command::WriteBufferRange {
+        buffer: 0,
+        offset: 32,
+        len: 2,
+      },
+      Command::StartPipeline {
+        framebuffer: 0,
+        state: PipelineState::default(),
+      },
+      Command::BindBufferRange {
+        buffer: 0,
+        binding: 0,
+        start: 16,
+        len: 1,
+      },
+      Command::BindBufferRange {
+        buffer: 0,
+        binding: 1,
+        start: 32,
+        len: 2,
+      },
+    ]
+  );
+}
+
+#[test]
+fn record_buffer_ranges() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut a = surface
+    .new_buffer_from_vec(vec![0u32; 8], BufferUsage::Dynamic)
+    .unwrap();
+  let b = surface
+    .new_buffer_from_vec((0..8).collect::<Vec<u32>>(), BufferUsage::Static)
+    .unwrap();
+
+  a.write_range(2, &[1, 2, 3]).unwrap();
+  a.copy_from(&b, 6..8, 6).unwrap();
+
+  assert_eq!(a.read_range(1..8), Ok(vec![0, 1, 2, 3, 0, 6, 7]));
+  assert_eq!(
+    a.write_range(7, &[1, 2]),
+    Err(BufferError::range_out_of_bounds(7, 9, 8))
+  );
+  assert_eq!(
+    a.read_range(4..9),
+    Err(BufferError::range_out_of_bounds(4, 9, 8))
+  );
+  assert_eq!(
+    a.copy_from(&b, 5..8, 6),
+    Err(BufferError::range_out_of_bounds(6, 9, 8))
+  );
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[2..],
+    [
+      Command::WriteBufferRange {
+        buffer: 0,
+        offset: 2,
+        len: 3,
+      },
+      Command::CopyBuffer {
+        src: 1,
+        dst: 0,
+        src_start: 6,
+        len: 2,
+        dst_offset: 6,
+      },
+      Command::ReadBufferRange {
+        buffer: 0,
+        start: 1,
+        len: 7,
+      },
+    ]
+  );
+}
+
+#[test]
+fn record_buffer_resize() {
+  let mut surface = MockSurface::new([800, 600]);
+  let mut buffer = surface
+    .new_buffer_from_vec(vec![1u32, 2, 3, 4], BufferUsage::Dynamic)
+    .unwrap();
+
+  buffer.resize(6, 9, PreserveContent::Yes).unwrap();
+  assert_eq!(buffer.len(), 6);
+  assert_eq!(buffer.read_range(0..6), Ok(vec![1, 2, 3, 4, 9, 9]));
+
+  buffer.resize(2, 0, PreserveContent::Yes).unwrap();
+  assert_eq!(buffer.read_range(0..2), Ok(vec![1, 2]));
+
+  buffer.resize(3, 7, PreserveContent::No).unwrap();
+  assert_eq!(buffer.read_range(0..3), Ok(vec![7, 7, 7]));
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[1..4],
+    [
+      Command::ResizeBuffer {
+        buffer: 0,
+        len: 6,
+        preserve: PreserveContent::Yes,
+      },
+      Command::ReadBufferRange {
+        buffer: 0,
+        start: 0,
+        len: 6,
+      },
+      Command::ResizeBuffer {
+        buffer: 0,
+        len: 2,
+        preserve: PreserveContent::Yes,
+      },
+    ]
+  );
+}
+
+#[test]
+fn record_tess_resize() {
+  let mut surface = MockSurface::new([800, 600]);
+
+  let mut tess = surface
+    .new_tess()
+    .set_vertex_nb(3)
+    .set_indices(vec![0u32, 1, 2])
+    .set_mode(Mode::Triangle)
+    .build()
+    .unwrap();
+
+  tess.resize_indices(5, PreserveContent::Yes).unwrap();
+  assert_eq!(tess.vert_nb(), 5);
+  assert_eq!(&tess.indices().unwrap()[..], &[0, 1, 2, 0, 0]);
+
+  // indexed tessellations keep rendering as many vertices as there are indices
+  tess.resize_vertices(10, PreserveContent::No).unwrap();
+  assert_eq!(tess.vert_nb(), 5);
+
+  tess.resize_instances(4, PreserveContent::No).unwrap();
+  assert_eq!(tess.inst_nb(), 4);
+
+  let mut attributeless = surface
+    .new_tess()
+    .set_vertex_nb(3)
+    .set_mode(Mode::Triangle)
+    .build()
+    .unwrap();
+
+  attributeless
+    .resize_vertices(6, PreserveContent::Yes)
+    .unwrap();
+  assert_eq!(attributeless.vert_nb(), 6);
+  assert_eq!(
+    attributeless.resize_indices(6, PreserveContent::Yes),
+    Err(TessError::not_indexed())
+  );
+
+  let commands = surface.backend().take_commands();
+
+  assert_eq!(
+    commands[1..4],
+    [
+      Command::ResizeIndices {
+        tess: 0,
+        len: 5,
+        preserve: PreserveContent::Yes,
+      },
+      Command::ResizeVertices {
+        tess: 0,
+        len: 10,
+        preserve: PreserveContent::No,
+      },
+      Command::ResizeInstances {
+        tess: 0,
+        len: 4,
+        preserve: PreserveContent::No,
+      },
+    ]
+  );
+  assert_eq!(
+    commands[5..],
+    [Command::ResizeVertices {
+      tess: 1,
+      len: 6,
+      preserve: PreserveContent::Yes,
+    }]
+  );
+}
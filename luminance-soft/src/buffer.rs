@@ -0,0 +1,287 @@
+//! Software buffer implementation.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::slice;
+
+use crate::Soft;
+use luminance::backend::buffer::{Buffer as BufferBackend, BufferSlice as BufferSliceBackend};
+use luminance::buffer::{BufferError, BufferUsage, PreserveContent};
+
+/// Software buffer.
+///
+/// The memory is shared with the slices so that they remain valid even if the buffer is moved.
+#[derive(Debug)]
+pub struct Buffer<T> {
+  pub(crate) buf: Rc<RefCell<Vec<T>>>,
+}
+
+impl<T> Buffer<T> {
+  pub(crate) fn from_vec(vec: Vec<T>) -> Self {
+    Buffer {
+      buf: Rc::new(RefCell::new(vec)),
+    }
+  }
+
+  /// Raw bytes of the buffer.
+  pub(crate) fn bytes(&self) -> Vec<u8> {
+    let buf = self.buf.borrow();
+    let len = buf.len() * mem::size_of::<T>();
+
+    unsafe { slice::from_raw_parts(buf.as_ptr() as *const u8, len) }.to_vec()
+  }
+
+  /// Raw bytes of `len` items of the buffer, starting at item `start`.
+  pub(crate) fn range_bytes(&self, start: usize, len: usize) -> Vec<u8> {
+    let buf = self.buf.borrow();
+    let items = &buf[start..start + len];
+
+    unsafe { slice::from_raw_parts(items.as_ptr() as *const u8, mem::size_of_val(items)) }.to_vec()
+  }
+}
+
+unsafe impl<T> BufferBackend<T> for Soft
+where
+  T: Copy,
+{
+  type BufferRepr = Buffer<T>;
+
+  unsafe fn new_buffer(
+    &mut self,
+    len: usize,
+    _: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>
+  where
+    T: Default,
+  {
+    Ok(Buffer::from_vec(vec![T::default(); len]))
+  }
+
+  unsafe fn len(buffer: &Self::BufferRepr) -> usize {
+    buffer.buf.borrow().len()
+  }
+
+  unsafe fn from_vec(
+    &mut self,
+    vec: Vec<T>,
+    _: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Ok(Buffer::from_vec(vec))
+  }
+
+  unsafe fn repeat(
+    &mut self,
+    len: usize,
+    value: T,
+    _: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Ok(Buffer::from_vec(vec![value; len]))
+  }
+
+  unsafe fn at(buffer: &Self::BufferRepr, i: usize) -> Option<T> {
+    buffer.buf.borrow().get(i).copied()
+  }
+
+  unsafe fn whole(buffer: &Self::BufferRepr) -> Vec<T> {
+    buffer.buf.borrow().clone()
+  }
+
+  unsafe fn set(buffer: &mut Self::BufferRepr, i: usize, x: T) -> Result<(), BufferError> {
+    let mut buf = buffer.buf.borrow_mut();
+
+    match buf.get_mut(i) {
+      Some(item) => {
+        *item = x;
+        Ok(())
+      }
+
+      None => Err(BufferError::overflow(i, buf.len())),
+    }
+  }
+
+  unsafe fn write_whole(buffer: &mut Self::BufferRepr, values: &[T]) -> Result<(), BufferError> {
+    let mut buf = buffer.buf.borrow_mut();
+    let provided_len = values.len();
+    let buffer_len = buf.len();
+
+    // error if we don’t pass the right number of items
+    match provided_len.cmp(&buffer_len) {
+      Ordering::Less => return Err(BufferError::too_few_values(provided_len, buffer_len)),
+
+      Ordering::Greater => return Err(BufferError::too_many_values(provided_len, buffer_len)),
+
+      _ => (),
+    }
+
+    buf.copy_from_slice(values);
+
+    Ok(())
+  }
+
+  unsafe fn write_range(
+    buffer: &mut Self::BufferRepr,
+    offset: usize,
+    values: &[T],
+  ) -> Result<(), BufferError> {
+    buffer.buf.borrow_mut()[offset..offset + values.len()].copy_from_slice(values);
+    Ok(())
+  }
+
+  unsafe fn read_range(
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Vec<T>, BufferError> {
+    Ok(buffer.buf.borrow()[start..start + len].to_vec())
+  }
+
+  unsafe fn copy_from(
+    buffer: &mut Self::BufferRepr,
+    src: &Self::BufferRepr,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  ) -> Result<(), BufferError> {
+    let src = src.buf.borrow();
+    buffer.buf.borrow_mut()[dst_offset..dst_offset + len]
+      .copy_from_slice(&src[src_start..src_start + len]);
+    Ok(())
+  }
+
+  unsafe fn clear(buffer: &mut Self::BufferRepr, x: T) -> Result<(), BufferError> {
+    for item in buffer.buf.borrow_mut().iter_mut() {
+      *item = x;
+    }
+
+    Ok(())
+  }
+
+  unsafe fn resize(
+    buffer: &mut Self::BufferRepr,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError> {
+    let mut buf = buffer.buf.borrow_mut();
+
+    if preserve == PreserveContent::No {
+      buf.clear();
+    }
+
+    buf.resize(len, value);
+
+    Ok(())
+  }
+}
+
+/// Buffer slice.
+///
+/// `S` is the type of the items the memory was allocated with; it is different from `T` only for
+/// transmuted slices.
+pub struct BufferSlice<T, S = T> {
+  // keep the memory alive as long as the slice is
+  _buf: Rc<RefCell<Vec<S>>>,
+  len: usize,
+  ptr: *const T,
+}
+
+impl BufferSlice<u8> {
+  /// Transmute to another type.
+  ///
+  /// This method is highly unsafe and should only be used when certain the target type is the
+  /// one actually represented by the raw bytes.
+  pub(crate) unsafe fn transmute<T>(self) -> BufferSlice<T, u8> {
+    BufferSlice {
+      _buf: self._buf,
+      len: self.len / mem::size_of::<T>(),
+      ptr: self.ptr as _,
+    }
+  }
+}
+
+impl<T, S> Deref for BufferSlice<T, S> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    unsafe { slice::from_raw_parts(self.ptr, self.len) }
+  }
+}
+
+/// Mutable buffer slice.
+///
+/// `S` is the type of the items the memory was allocated with; it is different from `T` only for
+/// transmuted slices.
+pub struct BufferSliceMut<T, S = T> {
+  // keep the memory alive as long as the slice is
+  _buf: Rc<RefCell<Vec<S>>>,
+  len: usize,
+  ptr: *mut T,
+}
+
+impl BufferSliceMut<u8> {
+  /// Transmute to another type.
+  ///
+  /// This method is highly unsafe and should only be used when certain the target type is the
+  /// one actually represented by the raw bytes.
+  pub(crate) unsafe fn transmute<T>(self) -> BufferSliceMut<T, u8> {
+    BufferSliceMut {
+      _buf: self._buf,
+      len: self.len / mem::size_of::<T>(),
+      ptr: self.ptr as _,
+    }
+  }
+}
+
+impl<T, S> Deref for BufferSliceMut<T, S> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    unsafe { slice::from_raw_parts(self.ptr as *const _, self.len) }
+  }
+}
+
+impl<T, S> DerefMut for BufferSliceMut<T, S> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+  }
+}
+
+unsafe impl<T> BufferSliceBackend<T> for Soft
+where
+  T: Copy,
+{
+  type SliceRepr = BufferSlice<T>;
+
+  type SliceMutRepr = BufferSliceMut<T>;
+
+  unsafe fn slice_buffer(buffer: &Self::BufferRepr) -> Result<Self::SliceRepr, BufferError> {
+    let (ptr, len) = {
+      let buf = buffer.buf.borrow();
+      (buf.as_ptr(), buf.len())
+    };
+
+    Ok(BufferSlice {
+      _buf: buffer.buf.clone(),
+      len,
+      ptr,
+    })
+  }
+
+  unsafe fn slice_buffer_mut(
+    buffer: &mut Self::BufferRepr,
+  ) -> Result<Self::SliceMutRepr, BufferError> {
+    let (ptr, len) = {
+      let mut buf = buffer.buf.borrow_mut();
+      (buf.as_mut_ptr(), buf.len())
+    };
+
+    Ok(BufferSliceMut {
+      _buf: buffer.buf.clone(),
+      len,
+      ptr,
+    })
+  }
+}
@@ -0,0 +1,380 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::pixel::{decode, encode};
+use crate::state::{Target, SAMPLE_POSITIONS};
+use crate::texture::{extent, TextureData};
+use crate::Soft;
+use luminance::backend::color_slot::ColorSlot;
+use luminance::backend::depth_slot::DepthSlot;
+use luminance::backend::framebuffer::{
+  Framebuffer as FramebufferBackend, FramebufferBackBuffer, FramebufferBlit, FramebufferMultisample,
+};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError, IncompleteReason};
+use luminance::pixel::{Depth32F, Depth32FStencil8, NormRGBA8UI, Pixel as _};
+use luminance::texture::{Dim, Dim2, Dimensionable, Sampler};
+
+pub struct Framebuffer<D>
+where
+  D: Dimensionable,
+{
+  pub(crate) target: Target,
+  // single-sampled slots the target is resolved into, if it’s multisampled
+  resolve: Option<Target>,
+  pub(crate) size: D::Size,
+}
+
+impl<D> Framebuffer<D>
+where
+  D: Dimensionable,
+{
+  /// Target holding the slots of the framebuffer.
+  fn slots(&self) -> &Target {
+    self.resolve.as_ref().unwrap_or(&self.target)
+  }
+
+  fn slots_mut(&mut self) -> &mut Target {
+    self.resolve.as_mut().unwrap_or(&mut self.target)
+  }
+
+  /// Attach texture data as the color slot at `attachment_index`.
+  ///
+  /// Color slots must be attached in order.
+  pub(crate) fn attach_color(
+    &mut self,
+    data: &Rc<RefCell<TextureData>>,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    let colors = &mut self.slots_mut().colors;
+
+    if attachment_index != colors.len() {
+      return Err(FramebufferError::incomplete(
+        IncompleteReason::IncompleteAttachment,
+      ));
+    }
+
+    colors.push(data.clone());
+
+    Ok(())
+  }
+
+  /// Attach texture data as the depth slot.
+  pub(crate) fn attach_depth(&mut self, data: &Rc<RefCell<TextureData>>) {
+    self.slots_mut().depth = Some(data.clone());
+  }
+
+  /// Resolve the multisampled target, if any, into the slots.
+  ///
+  /// Colors are averaged, and the depth of the first sample is kept.
+  pub(crate) fn resolve(&self) {
+    let resolve = match self.resolve {
+      Some(ref resolve) => resolve,
+      None => return,
+    };
+
+    for (samples, color) in self.target.colors.iter().zip(&resolve.colors) {
+      resolve_texture(&samples.borrow(), &mut color.borrow_mut(), true);
+    }
+
+    if let (Some(samples), Some(depth)) = (&self.target.depth, &resolve.depth) {
+      resolve_texture(&samples.borrow(), &mut depth.borrow_mut(), false);
+    }
+  }
+}
+
+fn resolve_texture(samples: &TextureData, resolved: &mut TextureData, average: bool) {
+  let [w, h, sample_nb] = samples.size();
+  let pf = resolved.pf;
+  let sample_nb = if average { sample_nb } else { 1 };
+
+  for y in 0..h {
+    for x in 0..w {
+      let mut color = [0.; 4];
+
+      for s in 0..sample_nb {
+        let sample = decode(samples.pf, samples.texel([x, y, s]), false);
+        color.iter_mut().zip(sample).for_each(|(c, s)| *c += s);
+      }
+
+      color.iter_mut().for_each(|c| *c /= sample_nb as f32);
+      encode(pf, color, false, resolved.texel_mut([x, y, 0]));
+    }
+  }
+}
+
+unsafe impl<D> FramebufferBackend<D> for Soft
+where
+  D: Dimensionable,
+{
+  type FramebufferRepr = Framebuffer<D>;
+
+  unsafe fn new_framebuffer<CS, DS>(
+    &mut self,
+    size: D::Size,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, D>,
+    DS: DepthSlot<Self, D>,
+  {
+    // when there is no depth slot, a depth texture is created on the side so that depth test still
+    // works, akin to a depth renderbuffer
+    let depth = if DS::depth_format().is_none() {
+      Some(new_depth_texture(extent::<D>(size))?)
+    } else {
+      None
+    };
+
+    let target = Target::new(Vec::with_capacity(CS::color_formats().len()), depth);
+
+    Ok(Framebuffer {
+      target,
+      resolve: None,
+      size,
+    })
+  }
+
+  unsafe fn attach_color_texture(
+    framebuffer: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    framebuffer.attach_color(&texture.data, attachment_index)
+  }
+
+  unsafe fn attach_depth_texture(
+    framebuffer: &mut Self::FramebufferRepr,
+    texture: &Self::TextureRepr,
+  ) -> Result<(), FramebufferError> {
+    framebuffer.attach_depth(&texture.data);
+    Ok(())
+  }
+
+  unsafe fn validate_framebuffer(
+    framebuffer: Self::FramebufferRepr,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    Ok(framebuffer)
+  }
+
+  unsafe fn framebuffer_size(framebuffer: &Self::FramebufferRepr) -> D::Size {
+    framebuffer.size
+  }
+}
+
+unsafe impl FramebufferBackBuffer for Soft {
+  unsafe fn back_buffer(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+  ) -> Result<Self::FramebufferRepr, FramebufferError> {
+    let mut state = self.state.borrow_mut();
+
+    // reuse the current back buffer if it has the right size so that successive back buffers
+    // render to the same image
+    let target = match state.back_buffer {
+      Some(ref target) if target.colors[0].borrow().size() == [size[0], size[1], 1] => {
+        target.clone()
+      }
+
+      _ => {
+        let color = TextureData::new(
+          Dim::Dim2,
+          [size[0], size[1], 1],
+          1,
+          NormRGBA8UI::pixel_format(),
+          Sampler::default(),
+        )
+        .map_err(FramebufferError::texture_error)?;
+
+        // like most windowed contexts, the back buffer has a stencil buffer
+        let depth = TextureData::new(
+          Dim::Dim2,
+          [size[0], size[1], 1],
+          1,
+          Depth32FStencil8::pixel_format(),
+          Sampler::default(),
+        )
+        .map_err(FramebufferError::texture_error)?;
+
+        let target = Target::new(
+          vec![Rc::new(RefCell::new(color))],
+          Some(Rc::new(RefCell::new(depth))),
+        );
+
+        state.back_buffer = Some(target.clone());
+        target
+      }
+    };
+
+    Ok(Framebuffer {
+      target,
+      resolve: None,
+      size,
+    })
+  }
+}
+
+unsafe impl FramebufferBlit for Soft {
+  unsafe fn blit_framebuffer(
+    src: &Self::FramebufferRepr,
+    dst: &mut Self::FramebufferRepr,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError> {
+    if mask.color {
+      // like with OpenGL, the first color attachment is read and written to all the color
+      // attachments of the destination
+      if let Some(src_color) = src.slots().colors.first() {
+        for dst_color in &dst.slots().colors {
+          blit_texture(src_color, dst_color, src_rect, dst_rect, filter);
+        }
+      }
+    }
+
+    if mask.depth {
+      if let (Some(src_depth), Some(dst_depth)) = (&src.slots().depth, &dst.slots().depth) {
+        blit_texture(
+          src_depth,
+          dst_depth,
+          src_rect,
+          dst_rect,
+          BlitFilter::Nearest,
+        );
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Blit a rectangle of the base level of a texture to a rectangle of the base level of another.
+///
+/// Texels read or written out of bounds are ignored.
+fn blit_texture(
+  src: &RefCell<TextureData>,
+  dst: &RefCell<TextureData>,
+  src_rect: BlitRect,
+  dst_rect: BlitRect,
+  filter: BlitFilter,
+) {
+  // gather all the colors first, as both textures might be the same one
+  let colors = {
+    let src = src.borrow();
+    let [src_w, src_h, _] = src.size();
+    let scale_x = src_rect.width as f32 / dst_rect.width as f32;
+    let scale_y = src_rect.height as f32 / dst_rect.height as f32;
+    let fetch = |x: i32, y: i32| {
+      let x = x.clamp(0, src_w as i32 - 1) as u32;
+      let y = y.clamp(0, src_h as i32 - 1) as u32;
+      decode(src.pf, src.texel([x, y, 0]), false)
+    };
+
+    let mut colors = Vec::with_capacity((dst_rect.width * dst_rect.height) as usize);
+
+    for y in 0..dst_rect.height {
+      for x in 0..dst_rect.width {
+        // center of the destination texel, expressed in source texels
+        let u = src_rect.x as f32 + (x as f32 + 0.5) * scale_x;
+        let v = src_rect.y as f32 + (y as f32 + 0.5) * scale_y;
+
+        if u >= src_w as f32 || v >= src_h as f32 {
+          continue;
+        }
+
+        let color = match filter {
+          BlitFilter::Nearest => fetch(u as i32, v as i32),
+
+          BlitFilter::Linear => {
+            let (u, v) = (u - 0.5, v - 0.5);
+            let (x0, y0) = (u.floor(), v.floor());
+            let (fx, fy) = (u - x0, v - y0);
+            let (x0, y0) = (x0 as i32, y0 as i32);
+            let lerp = |a: [f32; 4], b: [f32; 4], t: f32| {
+              let mut c = a;
+              c.iter_mut().zip(b).for_each(|(c, b)| *c += (b - *c) * t);
+              c
+            };
+            let top = lerp(fetch(x0, y0), fetch(x0 + 1, y0), fx);
+            let bottom = lerp(fetch(x0, y0 + 1), fetch(x0 + 1, y0 + 1), fx);
+
+            lerp(top, bottom, fy)
+          }
+        };
+
+        colors.push(([dst_rect.x + x, dst_rect.y + y], color));
+      }
+    }
+
+    colors
+  };
+
+  let mut dst = dst.borrow_mut();
+  let [dst_w, dst_h, _] = dst.size();
+  let pf = dst.pf;
+
+  for ([x, y], color) in colors {
+    if x < dst_w && y < dst_h {
+      encode(pf, color, false, dst.texel_mut([x, y, 0]));
+    }
+  }
+}
+
+fn new_depth_texture(size: [u32; 3]) -> Result<Rc<RefCell<TextureData>>, FramebufferError> {
+  TextureData::new(
+    Dim::Dim2,
+    [size[0], size[1], 1],
+    1,
+    Depth32F::pixel_format(),
+    Sampler::default(),
+  )
+  .map(|data| Rc::new(RefCell::new(data)))
+  .map_err(FramebufferError::texture_error)
+}
+
+unsafe impl FramebufferMultisample for Soft {
+  unsafe fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, Dim2>,
+    DS: DepthSlot<Self, Dim2>,
+  {
+    // like OpenGL implementations, use the smallest supported sample count that is at least the
+    // requested one
+    let sample_positions = SAMPLE_POSITIONS
+      .iter()
+      .find(|positions| positions.len() >= samples)
+      .ok_or_else(|| FramebufferError::unsupported_samples(samples))?;
+    let samples_size = [size[0], size[1], sample_positions.len() as u32];
+    let new_samples = |pf| {
+      TextureData::new(Dim::Dim2Array, samples_size, 1, pf, Sampler::default())
+        .map(|data| Rc::new(RefCell::new(data)))
+        .map_err(FramebufferError::texture_error)
+    };
+
+    let colors = CS::color_formats()
+      .into_iter()
+      .map(new_samples)
+      .collect::<Result<_, _>>()?;
+    let depth = new_samples(DS::depth_format().unwrap_or_else(Depth32F::pixel_format))?;
+
+    let target = Target {
+      colors,
+      depth: Some(depth),
+      sample_positions,
+    };
+    let resolve = Target::new(Vec::with_capacity(CS::color_formats().len()), None);
+
+    Ok(Framebuffer {
+      target,
+      resolve: Some(resolve),
+      size,
+    })
+  }
+}
@@ -0,0 +1,122 @@
+//! CPU software rasterizer backend for [luminance].
+//!
+//! This crate provides [`Soft`], a backend implementing every backend trait of [luminance] on the
+//! CPU, without any GPU nor windowing system. It is mainly intended to run rendering code in
+//! environments lacking a GPU (CI machines, for instance) and to read back pixels
+//! deterministically.
+//!
+//! # Shaders
+//!
+//! Because there is no GLSL compiler around, shader stages are plain Rust closures. You register
+//! them on the backend under a name with [`Soft::add_vertex_shader`] and
+//! [`Soft::add_fragment_shader`], and you then use those names as the “sources” of your shader
+//! stages when building a [`Program`]:
+//!
+//! ```
+//! use luminance::context::GraphicsContext as _;
+//! use luminance_soft::{FragmentOutput, SoftSurface, VertexOutput};
+//!
+//! let mut surface = SoftSurface::new([4, 4]);
+//!
+//! surface
+//!   .backend()
+//!   .add_vertex_shader("passthrough", |input, _| VertexOutput::new(input.attrib(0)));
+//! surface
+//!   .backend()
+//!   .add_fragment_shader("red", |_, _| FragmentOutput::color([1., 0., 0., 1.]));
+//!
+//! let program = surface
+//!   .new_shader_program::<(), (), ()>()
+//!   .from_strings("passthrough", None, None, "red");
+//!
+//! assert!(program.is_ok());
+//! ```
+//!
+//! Vertex shaders get their vertex attributes via [`VertexInput::attrib`], indexed by the
+//! semantics index of the attributes. Uniforms, textures and uniform buffers are available via
+//! [`Uniforms`].
+//!
+//! Tessellation and geometry stages are not supported.
+//!
+//! # Limitations
+//!
+//! - Rendering always goes to the first layer of the framebuffer textures.
+//! - Sampling always happens on the base mipmap level.
+//! - Primitives are only clipped against the near plane.
+//! - Compressed pixel formats are not supported.
+//!
+//! [luminance]: https://crates.io/crates/luminance
+//! [`Program`]: luminance::shader::Program
+
+mod buffer;
+mod framebuffer;
+mod pipeline;
+mod pixel;
+mod raster;
+mod renderbuffer;
+mod shader;
+mod state;
+mod surface;
+mod tess;
+mod texture;
+
+pub use crate::shader::{
+  FragmentInput, FragmentOutput, FromUniformValue, UniformValue, Uniforms, VertexInput,
+  VertexOutput,
+};
+pub use crate::surface::SoftSurface;
+
+use crate::state::SoftState;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The software backend.
+#[derive(Debug)]
+pub struct Soft {
+  pub(crate) state: Rc<RefCell<SoftState>>,
+}
+
+impl Soft {
+  /// Create a new software backend.
+  pub fn new() -> Self {
+    Soft {
+      state: Rc::new(RefCell::new(SoftState::new())),
+    }
+  }
+
+  /// Register a vertex shader under a given name.
+  ///
+  /// The name can then be used as the source of a [`StageType::VertexShader`] stage.
+  ///
+  /// [`StageType::VertexShader`]: luminance::shader::StageType::VertexShader
+  pub fn add_vertex_shader<F>(&mut self, name: impl Into<String>, shader: F)
+  where
+    F: 'static + Fn(&VertexInput, &Uniforms) -> VertexOutput,
+  {
+    self
+      .state
+      .borrow_mut()
+      .add_vertex_shader(name.into(), shader);
+  }
+
+  /// Register a fragment shader under a given name.
+  ///
+  /// The name can then be used as the source of a [`StageType::FragmentShader`] stage.
+  ///
+  /// [`StageType::FragmentShader`]: luminance::shader::StageType::FragmentShader
+  pub fn add_fragment_shader<F>(&mut self, name: impl Into<String>, shader: F)
+  where
+    F: 'static + Fn(&FragmentInput, &Uniforms) -> FragmentOutput,
+  {
+    self
+      .state
+      .borrow_mut()
+      .add_fragment_shader(name.into(), shader);
+  }
+}
+
+impl Default for Soft {
+  fn default() -> Self {
+    Soft::new()
+  }
+}
@@ -0,0 +1,340 @@
+use luminance::backend::pipeline::{
+  Pipeline as PipelineBackend, PipelineBase, PipelineBuffer, PipelineTexture,
+};
+use luminance::backend::render_gate::RenderGate;
+use luminance::backend::shading_gate::ShadingGate;
+use luminance::backend::tess::Tess;
+use luminance::backend::tess_gate::TessGate;
+use luminance::pipeline::{PipelineError, PipelineState, Viewport};
+use luminance::pixel::Pixel;
+use luminance::render_state::RenderState;
+use luminance::tess::{Deinterleaved, DeinterleavedData, Interleaved, TessIndex, TessVertexData};
+use luminance::texture::Dimensionable;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::pixel::{decode, encode};
+use crate::state::SoftState;
+use crate::texture::TextureData;
+use crate::Soft;
+
+pub struct Pipeline {
+  state: Rc<RefCell<SoftState>>,
+}
+
+pub struct BoundBuffer {
+  pub(crate) binding: u32,
+  state: Rc<RefCell<SoftState>>,
+}
+
+impl Drop for BoundBuffer {
+  fn drop(&mut self) {
+    // place the binding into the free list
+    let mut state = self.state.borrow_mut();
+    let bstack = &mut state.binding_stack;
+    bstack.buffers[self.binding as usize] = None;
+    bstack.free_buffer_bindings.push(self.binding);
+  }
+}
+
+pub struct BoundTexture<D, P>
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  pub(crate) unit: u32,
+  state: Rc<RefCell<SoftState>>,
+  _phantom: PhantomData<*const (D, P)>,
+}
+
+impl<D, P> Drop for BoundTexture<D, P>
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  fn drop(&mut self) {
+    // place the binding into the free list
+    let mut state = self.state.borrow_mut();
+    let bstack = &mut state.binding_stack;
+    bstack.textures[self.unit as usize] = None;
+    bstack.free_texture_units.push(self.unit);
+  }
+}
+
+unsafe impl PipelineBase for Soft {
+  type PipelineRepr = Pipeline;
+
+  unsafe fn new_pipeline(&mut self) -> Result<Self::PipelineRepr, PipelineError> {
+    let pipeline = Pipeline {
+      state: self.state.clone(),
+    };
+
+    Ok(pipeline)
+  }
+}
+
+unsafe impl<D> PipelineBackend<D> for Soft
+where
+  D: Dimensionable,
+{
+  unsafe fn start_pipeline(
+    &mut self,
+    framebuffer: &Self::FramebufferRepr,
+    pipeline_state: &PipelineState,
+  ) {
+    let mut state = self.state.borrow_mut();
+    let size = framebuffer.size;
+    let samples = framebuffer.target.sample_positions.len() as u32;
+
+    state.target = Some(framebuffer.target.clone());
+    state.srgb_enabled = pipeline_state.srgb_enabled;
+
+    state.viewport = match pipeline_state.viewport {
+      Viewport::Whole => [0, 0, D::width(size) as i32, D::height(size) as i32],
+
+      Viewport::Specific {
+        x,
+        y,
+        width,
+        height,
+      } => [x as i32, y as i32, width as i32, height as i32],
+    };
+
+    let region = pipeline_state
+      .scissor()
+      .as_ref()
+      .map(|region| [region.x, region.y, region.width, region.height]);
+
+    if pipeline_state.clear_color_enabled {
+      for color in &framebuffer.target.colors {
+        clear(
+          &mut color.borrow_mut(),
+          samples,
+          region,
+          pipeline_state.clear_color,
+          state.srgb_enabled,
+        );
+      }
+    }
+
+    if let Some(ref depth) = framebuffer.target.depth {
+      let clear_depth = Some(1.).filter(|_| pipeline_state.clear_depth_enabled);
+      let clear_stencil =
+        Some(pipeline_state.clear_stencil as f32).filter(|_| pipeline_state.clear_stencil_enabled);
+
+      clear_depth_stencil(
+        &mut depth.borrow_mut(),
+        samples,
+        region,
+        clear_depth,
+        clear_stencil,
+      );
+    }
+  }
+
+  unsafe fn end_pipeline(&mut self, framebuffer: &Self::FramebufferRepr) {
+    framebuffer.resolve();
+  }
+}
+
+/// Clear the first `layers` layers of a texture, optionally restricted to a region.
+///
+/// Multisampled targets have a layer per sample.
+fn clear(
+  texture: &mut TextureData,
+  layers: u32,
+  region: Option<[u32; 4]>,
+  color: [f32; 4],
+  srgb: bool,
+) {
+  let [w, h, _] = texture.size();
+  let [x, y, width, height] = region.unwrap_or([0, 0, w, h]);
+  let pf = texture.pf;
+
+  for z in 0..layers {
+    for y in y..(y + height).min(h) {
+      for x in x..(x + width).min(w) {
+        encode(pf, color, srgb, texture.texel_mut([x, y, z]));
+      }
+    }
+  }
+}
+
+/// Clear the depth and stencil of the first `layers` layers of a depth texture, optionally
+/// restricted to a region.
+///
+/// The depth and stencil are cleared independently, as they share texels in depth-stencil
+/// formats.
+fn clear_depth_stencil(
+  texture: &mut TextureData,
+  layers: u32,
+  region: Option<[u32; 4]>,
+  depth: Option<f32>,
+  stencil: Option<f32>,
+) {
+  if depth.is_none() && stencil.is_none() {
+    return;
+  }
+
+  let [w, h, _] = texture.size();
+  let [x, y, width, height] = region.unwrap_or([0, 0, w, h]);
+  let pf = texture.pf;
+
+  for z in 0..layers {
+    for y in y..(y + height).min(h) {
+      for x in x..(x + width).min(w) {
+        let texel = texture.texel_mut([x, y, z]);
+        let mut value = decode(pf, texel, false);
+        value[0] = depth.unwrap_or(value[0]);
+        value[1] = stencil.unwrap_or(value[1]);
+        encode(pf, value, false, texel);
+      }
+    }
+  }
+}
+
+unsafe impl<T> PipelineBuffer<T> for Soft
+where
+  T: Copy,
+{
+  type BoundBufferRepr = BoundBuffer;
+
+  unsafe fn bind_buffer(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = &mut state.binding_stack;
+
+    let binding = bstack.free_buffer_bindings.pop().unwrap_or_else(|| {
+      // no more free bindings; reserve one
+      bstack.buffers.push(None);
+      bstack.buffers.len() as u32 - 1
+    });
+
+    // the buffer cannot be altered while bound, so a copy of its content is as good as the buffer
+    bstack.buffers[binding as usize] = Some(buffer.bytes());
+
+    Ok(BoundBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
+  unsafe fn bind_buffer_range(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = &mut state.binding_stack;
+
+    let binding = bstack.free_buffer_bindings.pop().unwrap_or_else(|| {
+      // no more free bindings; reserve one
+      bstack.buffers.push(None);
+      bstack.buffers.len() as u32 - 1
+    });
+
+    bstack.buffers[binding as usize] = Some(buffer.range_bytes(start, len));
+
+    Ok(BoundBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
+  unsafe fn buffer_binding(bound: &Self::BoundBufferRepr) -> u32 {
+    bound.binding
+  }
+
+  unsafe fn buffer_offset_alignment(&mut self) -> usize {
+    // ranges are copied, so they can start anywhere
+    1
+  }
+}
+
+unsafe impl<D, P> PipelineTexture<D, P> for Soft
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  type BoundTextureRepr = BoundTexture<D, P>;
+
+  unsafe fn bind_texture(
+    pipeline: &Self::PipelineRepr,
+    texture: &Self::TextureRepr,
+  ) -> Result<Self::BoundTextureRepr, PipelineError>
+  where
+    D: Dimensionable,
+    P: Pixel,
+  {
+    let mut state = pipeline.state.borrow_mut();
+    let bstack = &mut state.binding_stack;
+
+    let unit = bstack.free_texture_units.pop().unwrap_or_else(|| {
+      // no more free units; reserve one
+      bstack.textures.push(None);
+      bstack.textures.len() as u32 - 1
+    });
+
+    bstack.textures[unit as usize] = Some(texture.data.clone());
+
+    Ok(BoundTexture {
+      unit,
+      state: pipeline.state.clone(),
+      _phantom: PhantomData,
+    })
+  }
+
+  unsafe fn texture_binding(bound: &Self::BoundTextureRepr) -> u32 {
+    bound.unit
+  }
+}
+
+unsafe impl<V, I, W> TessGate<V, I, W, Interleaved> for Soft
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  unsafe fn render(
+    &mut self,
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) {
+    let _ = <Self as Tess<V, I, W, Interleaved>>::render(tess, start_index, vert_nb, inst_nb);
+  }
+}
+
+unsafe impl<V, I, W> TessGate<V, I, W, Deinterleaved> for Soft
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  unsafe fn render(
+    &mut self,
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) {
+    let _ = <Self as Tess<V, I, W, Deinterleaved>>::render(tess, start_index, vert_nb, inst_nb);
+  }
+}
+
+unsafe impl RenderGate for Soft {
+  unsafe fn enter_render_state(&mut self, rdr_st: &RenderState) {
+    self.state.borrow_mut().render_state = rdr_st.clone();
+  }
+}
+
+unsafe impl ShadingGate for Soft {
+  unsafe fn apply_shader_program(&mut self, shader_program: &Self::ProgramRepr) {
+    self.state.borrow_mut().program = Some(shader_program.inner.clone());
+  }
+}
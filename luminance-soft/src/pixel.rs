@@ -0,0 +1,213 @@
+use luminance::pixel::{Format, PixelFormat, Size, Type};
+use std::convert::TryInto;
+
+/// Number of channels and size (in bytes) of a single channel of a pixel format, if supported by
+/// the software backend.
+///
+/// All channels must share the same size and only 8-bit, 16-bit and 32-bit channels are
+/// supported. Depth-stencil formats are packed and have no such sizes; see [`is_supported`].
+pub(crate) fn channel_sizes(pf: PixelFormat) -> Option<(usize, usize)> {
+  let (size, channels) = match pf.format {
+    Format::R(r) | Format::Depth(r) => (r, 1),
+    Format::RG(r, g) if r == g => (r, 2),
+    Format::RGB(r, g, b) | Format::SRGB(r, g, b) if r == g && g == b => (r, 3),
+    Format::RGBA(r, g, b, a) | Format::SRGBA(r, g, b, a) if r == g && g == b && b == a => (r, 4),
+    _ => return None,
+  };
+
+  let bytes = match (size, pf.encoding) {
+    (Size::ThirtyTwo, _) => 4,
+    (_, Type::Floating) => return None,
+    (Size::Eight, _) => 1,
+    (Size::Sixteen, _) => 2,
+    _ => return None,
+  };
+
+  Some((channels, bytes))
+}
+
+/// Is the pixel format supported by the software backend?
+pub(crate) fn is_supported(pf: PixelFormat) -> bool {
+  channel_sizes(pf).is_some() || depth_stencil_format(pf).is_some()
+}
+
+/// Packed depth-stencil formats.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DepthStencilFormat {
+  /// 24-bit normalized depth in the most significant bits of a 32-bit word, followed by the
+  /// stencil.
+  D24S8,
+  /// 32-bit floating depth, followed by a 32-bit word holding the stencil in its least
+  /// significant bits.
+  D32FS8,
+}
+
+fn depth_stencil_format(pf: PixelFormat) -> Option<DepthStencilFormat> {
+  match (pf.format, pf.encoding) {
+    (Format::DepthStencil(Size::TwentyFour, Size::Eight), Type::NormUnsigned) => {
+      Some(DepthStencilFormat::D24S8)
+    }
+    (Format::DepthStencil(Size::ThirtyTwo, Size::Eight), Type::Floating) => {
+      Some(DepthStencilFormat::D32FS8)
+    }
+    _ => None,
+  }
+}
+
+/// Is the pixel format an sRGB one?
+pub(crate) fn is_srgb(pf: PixelFormat) -> bool {
+  matches!(pf.format, Format::SRGB(..) | Format::SRGBA(..))
+}
+
+/// Decode a single texel into floating-point channels.
+///
+/// Missing channels are set to `0`, except alpha, which is set to `1`. If `srgb` is `true` and the
+/// pixel format is an sRGB one, the color channels are converted to linear space.
+///
+/// Depth-stencil texels are decoded as the depth in the first channel and the stencil in the
+/// second one.
+pub(crate) fn decode(pf: PixelFormat, texel: &[u8], srgb: bool) -> [f32; 4] {
+  let mut output = [0., 0., 0., 1.];
+
+  if let Some(format) = depth_stencil_format(pf) {
+    let word = |i: usize| u32::from_ne_bytes(texel[i * 4..i * 4 + 4].try_into().unwrap());
+    let (depth, stencil) = match format {
+      DepthStencilFormat::D24S8 => ((word(0) >> 8) as f32 / 0xFF_FFFF as f32, word(0) & 0xFF),
+      DepthStencilFormat::D32FS8 => (f32::from_bits(word(0)), word(1) & 0xFF),
+    };
+
+    output[0] = depth;
+    output[1] = stencil as f32;
+    return output;
+  }
+
+  let (channels, bytes) = match channel_sizes(pf) {
+    Some(sizes) => sizes,
+    None => return output,
+  };
+
+  for (i, chunk) in texel.chunks_exact(bytes).take(channels).enumerate() {
+    output[i] = decode_channel(pf.encoding, chunk);
+  }
+
+  if srgb && is_srgb(pf) {
+    for c in &mut output[..3] {
+      *c = srgb_to_linear(*c);
+    }
+  }
+
+  output
+}
+
+/// Encode floating-point channels into a single texel.
+///
+/// If `srgb` is `true` and the pixel format is an sRGB one, the color channels are converted from
+/// linear space to sRGB.
+pub(crate) fn encode(pf: PixelFormat, mut color: [f32; 4], srgb: bool, texel: &mut [u8]) {
+  if let Some(format) = depth_stencil_format(pf) {
+    let stencil = color[1].clamp(0., 255.) as u32;
+
+    match format {
+      DepthStencilFormat::D24S8 => {
+        let depth = (color[0].clamp(0., 1.) * 0xFF_FFFF as f32).round() as u32;
+        texel[..4].copy_from_slice(&(depth << 8 | stencil).to_ne_bytes());
+      }
+
+      DepthStencilFormat::D32FS8 => {
+        texel[..4].copy_from_slice(&color[0].to_ne_bytes());
+        texel[4..8].copy_from_slice(&stencil.to_ne_bytes());
+      }
+    }
+
+    return;
+  }
+
+  let (channels, bytes) = match channel_sizes(pf) {
+    Some(sizes) => sizes,
+    None => return,
+  };
+
+  if srgb && is_srgb(pf) {
+    for c in &mut color[..3] {
+      *c = linear_to_srgb(*c);
+    }
+  }
+
+  for (chunk, &c) in texel.chunks_exact_mut(bytes).take(channels).zip(&color) {
+    encode_channel(pf.encoding, c, chunk);
+  }
+}
+
+fn decode_channel(ty: Type, bytes: &[u8]) -> f32 {
+  match (ty, bytes.len()) {
+    (Type::NormUnsigned, 1) => bytes[0] as f32 / u8::MAX as f32,
+    (Type::NormUnsigned, 2) => {
+      u16::from_ne_bytes(bytes.try_into().unwrap()) as f32 / u16::MAX as f32
+    }
+    (Type::NormUnsigned, _) => {
+      (u32::from_ne_bytes(bytes.try_into().unwrap()) as f64 / u32::MAX as f64) as f32
+    }
+
+    (Type::NormIntegral, 1) => (bytes[0] as i8 as f32 / i8::MAX as f32).max(-1.),
+    (Type::NormIntegral, 2) => {
+      (i16::from_ne_bytes(bytes.try_into().unwrap()) as f32 / i16::MAX as f32).max(-1.)
+    }
+    (Type::NormIntegral, _) => {
+      ((i32::from_ne_bytes(bytes.try_into().unwrap()) as f64 / i32::MAX as f64) as f32).max(-1.)
+    }
+
+    (Type::Unsigned, 1) => bytes[0] as f32,
+    (Type::Unsigned, 2) => u16::from_ne_bytes(bytes.try_into().unwrap()) as f32,
+    (Type::Unsigned, _) => u32::from_ne_bytes(bytes.try_into().unwrap()) as f32,
+
+    (Type::Integral, 1) => bytes[0] as i8 as f32,
+    (Type::Integral, 2) => i16::from_ne_bytes(bytes.try_into().unwrap()) as f32,
+    (Type::Integral, _) => i32::from_ne_bytes(bytes.try_into().unwrap()) as f32,
+
+    (Type::Floating, _) => f32::from_ne_bytes(bytes.try_into().unwrap()),
+  }
+}
+
+fn encode_channel(ty: Type, c: f32, bytes: &mut [u8]) {
+  match (ty, bytes.len()) {
+    (Type::NormUnsigned, 1) => bytes[0] = (c.clamp(0., 1.) * u8::MAX as f32).round() as u8,
+    (Type::NormUnsigned, 2) => {
+      bytes.copy_from_slice(&((c.clamp(0., 1.) * u16::MAX as f32).round() as u16).to_ne_bytes())
+    }
+    (Type::NormUnsigned, _) => bytes
+      .copy_from_slice(&((c.clamp(0., 1.) as f64 * u32::MAX as f64).round() as u32).to_ne_bytes()),
+
+    (Type::NormIntegral, 1) => bytes[0] = (c.clamp(-1., 1.) * i8::MAX as f32).round() as i8 as u8,
+    (Type::NormIntegral, 2) => {
+      bytes.copy_from_slice(&((c.clamp(-1., 1.) * i16::MAX as f32).round() as i16).to_ne_bytes())
+    }
+    (Type::NormIntegral, _) => bytes
+      .copy_from_slice(&((c.clamp(-1., 1.) as f64 * i32::MAX as f64).round() as i32).to_ne_bytes()),
+
+    (Type::Unsigned, 1) => bytes[0] = c as u8,
+    (Type::Unsigned, 2) => bytes.copy_from_slice(&(c as u16).to_ne_bytes()),
+    (Type::Unsigned, _) => bytes.copy_from_slice(&(c as u32).to_ne_bytes()),
+
+    (Type::Integral, 1) => bytes[0] = c as i8 as u8,
+    (Type::Integral, 2) => bytes.copy_from_slice(&(c as i16).to_ne_bytes()),
+    (Type::Integral, _) => bytes.copy_from_slice(&(c as i32).to_ne_bytes()),
+
+    (Type::Floating, _) => bytes.copy_from_slice(&c.to_ne_bytes()),
+  }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+  if c <= 0.04045 {
+    c / 12.92
+  } else {
+    ((c + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+  if c <= 0.003_130_8 {
+    c * 12.92
+  } else {
+    1.055 * c.powf(1. / 2.4) - 0.055
+  }
+}
@@ -0,0 +1,822 @@
+//! Rasterization of primitives.
+//!
+//! This module implements the fixed-function parts of the pipeline: vertex fetching, primitive
+//! assembly, near-plane clipping, face culling, rasterization, depth test and blending.
+
+use luminance::blending::{Blending, BlendingMode, Equation, Factor};
+use luminance::depth_test::DepthWrite;
+use luminance::face_culling::{FaceCullingMode, FaceCullingOrder};
+use luminance::render_state::RenderState;
+use luminance::stencil::{StencilComparison, StencilOp};
+use luminance::tess::Mode;
+use luminance::vertex::{Normalized, VertexAttribDim, VertexAttribType, VertexBufferDesc};
+use std::cell::RefMut;
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use crate::pixel::decode;
+use crate::pixel::encode;
+use crate::shader::{FragmentInput, FragmentShader, Uniforms, VertexInput, VertexOutput};
+use crate::state::SoftState;
+use crate::texture::{compare_depth, TextureData};
+
+/// A vertex attribute stored in a buffer.
+#[derive(Debug)]
+pub(crate) struct AttribSource {
+  pub(crate) bytes: Vec<u8>,
+  pub(crate) offset: usize,
+  pub(crate) stride: usize,
+  pub(crate) desc: VertexBufferDesc,
+}
+
+impl AttribSource {
+  /// Read the attribute of the `i`-th vertex (or instance).
+  fn read(&self, i: usize) -> Option<[f32; 4]> {
+    let attrib_desc = &self.desc.attrib_desc;
+    let unit_size = attrib_desc.unit_size;
+    let dim = match attrib_desc.dim {
+      VertexAttribDim::Dim1 => 1,
+      VertexAttribDim::Dim2 => 2,
+      VertexAttribDim::Dim3 => 3,
+      VertexAttribDim::Dim4 => 4,
+    };
+
+    let start = self.offset + i * self.stride;
+    let bytes = self.bytes.get(start..start + dim * unit_size)?;
+    let mut output = [0., 0., 0., 1.];
+
+    for (c, unit) in output.iter_mut().zip(bytes.chunks_exact(unit_size)) {
+      *c = decode_attrib(attrib_desc.ty, unit);
+    }
+
+    Some(output)
+  }
+}
+
+fn decode_attrib(ty: VertexAttribType, unit: &[u8]) -> f32 {
+  match (ty, unit.len()) {
+    (VertexAttribType::Floating, 4) => f32::from_ne_bytes(unit.try_into().unwrap()),
+    (VertexAttribType::Floating, 8) => f64::from_ne_bytes(unit.try_into().unwrap()) as f32,
+
+    (VertexAttribType::Integral(normalized), 1) => {
+      normalize_signed(unit[0] as i8 as f32, i8::MAX as f32, normalized)
+    }
+    (VertexAttribType::Integral(normalized), 2) => normalize_signed(
+      i16::from_ne_bytes(unit.try_into().unwrap()) as f32,
+      i16::MAX as f32,
+      normalized,
+    ),
+    (VertexAttribType::Integral(normalized), 4) => normalize_signed(
+      i32::from_ne_bytes(unit.try_into().unwrap()) as f32,
+      i32::MAX as f32,
+      normalized,
+    ),
+
+    (VertexAttribType::Unsigned(normalized), 1) => {
+      normalize_unsigned(unit[0] as f32, u8::MAX as f32, normalized)
+    }
+    (VertexAttribType::Unsigned(normalized), 2) => normalize_unsigned(
+      u16::from_ne_bytes(unit.try_into().unwrap()) as f32,
+      u16::MAX as f32,
+      normalized,
+    ),
+    (VertexAttribType::Unsigned(normalized), 4) => normalize_unsigned(
+      u32::from_ne_bytes(unit.try_into().unwrap()) as f32,
+      u32::MAX as f32,
+      normalized,
+    ),
+
+    (VertexAttribType::Boolean, _) => (unit.iter().any(|&b| b != 0) as u8).into(),
+
+    _ => 0.,
+  }
+}
+
+fn normalize_signed(x: f32, max: f32, normalized: Normalized) -> f32 {
+  match normalized {
+    Normalized::Yes => (x / max).max(-1.),
+    Normalized::No => x,
+  }
+}
+
+fn normalize_unsigned(x: f32, max: f32, normalized: Normalized) -> f32 {
+  match normalized {
+    Normalized::Yes => x / max,
+    Normalized::No => x,
+  }
+}
+
+/// All the vertex attributes of a draw call.
+#[derive(Debug, Default)]
+pub(crate) struct VertexFetcher {
+  pub(crate) sources: Vec<AttribSource>,
+}
+
+impl VertexFetcher {
+  fn fetch(&self, vertex_id: u32, instance_id: u32) -> Vec<Option<[f32; 4]>> {
+    let len = self
+      .sources
+      .iter()
+      .map(|source| source.desc.index + 1)
+      .max()
+      .unwrap_or(0);
+    let mut attribs = vec![None; len];
+
+    for source in &self.sources {
+      let i = match source.desc.instancing {
+        luminance::vertex::VertexInstancing::On => instance_id,
+        luminance::vertex::VertexInstancing::Off => vertex_id,
+      };
+
+      attribs[source.desc.index] = source.read(i as usize);
+    }
+
+    attribs
+  }
+}
+
+/// Size in bytes of a vertex attribute.
+pub(crate) fn attrib_weight(desc: &VertexBufferDesc) -> usize {
+  let dim = match desc.attrib_desc.dim {
+    VertexAttribDim::Dim1 => 1,
+    VertexAttribDim::Dim2 => 2,
+    VertexAttribDim::Dim3 => 3,
+    VertexAttribDim::Dim4 => 4,
+  };
+
+  dim * desc.attrib_desc.unit_size
+}
+
+/// Compute offsets and stride of vertex attributes stored in the same buffer.
+///
+/// This follows the exact same rules as in the OpenGL backends.
+pub(crate) fn attrib_sources(
+  bytes: Vec<u8>,
+  descriptors: &[VertexBufferDesc],
+) -> Vec<AttribSource> {
+  let align = |off: usize, align: usize| (off + align - 1) & !(align - 1);
+
+  let mut offsets = Vec::with_capacity(descriptors.len());
+  let mut off = 0;
+
+  for desc in descriptors {
+    off = align(off, desc.attrib_desc.align);
+    offsets.push(off);
+    off += attrib_weight(desc);
+  }
+
+  let stride = match descriptors.first() {
+    Some(first) => align(off, first.attrib_desc.align),
+    None => 0,
+  };
+
+  descriptors
+    .iter()
+    .zip(offsets)
+    .map(|(desc, offset)| AttribSource {
+      bytes: bytes.clone(),
+      offset,
+      stride,
+      desc: *desc,
+    })
+    .collect()
+}
+
+/// A vertex in clip space.
+#[derive(Clone, Debug)]
+struct ClipVertex {
+  position: [f32; 4],
+  varyings: Vec<f32>,
+}
+
+impl From<&VertexOutput> for ClipVertex {
+  fn from(output: &VertexOutput) -> Self {
+    ClipVertex {
+      position: output.position,
+      varyings: output.varyings.clone(),
+    }
+  }
+}
+
+impl ClipVertex {
+  fn lerp(&self, other: &Self, t: f32) -> Self {
+    let mut position = self.position;
+    for (p, q) in position.iter_mut().zip(&other.position) {
+      *p += (q - *p) * t;
+    }
+
+    let varyings = self
+      .varyings
+      .iter()
+      .zip(&other.varyings)
+      .map(|(a, b)| a + (b - a) * t)
+      .collect();
+
+    ClipVertex { position, varyings }
+  }
+}
+
+/// A vertex in window space.
+///
+/// Varyings are premultiplied by `inv_w` for perspective-correct interpolation.
+#[derive(Clone, Debug)]
+struct WindowVertex {
+  x: f32,
+  y: f32,
+  z: f32,
+  inv_w: f32,
+  varyings: Vec<f32>,
+}
+
+/// Minimal `w` value vertices are clipped against, preventing divisions by zero.
+const W_EPSILON: f32 = 1e-6;
+
+/// Signed distances to the clipping planes; a vertex is kept if the distance is positive.
+const CLIP_PLANES: [fn(&[f32; 4]) -> f32; 2] = [|p| p[2] + p[3], |p| p[3] - W_EPSILON];
+
+/// Clip a polygon against the near plane.
+fn clip_polygon(mut polygon: Vec<ClipVertex>) -> Vec<ClipVertex> {
+  for plane in &CLIP_PLANES {
+    if polygon.is_empty() {
+      break;
+    }
+
+    let mut output = Vec::with_capacity(polygon.len() + 1);
+
+    for i in 0..polygon.len() {
+      let a = &polygon[i];
+      let b = &polygon[(i + 1) % polygon.len()];
+      let da = plane(&a.position);
+      let db = plane(&b.position);
+
+      if da >= 0. {
+        output.push(a.clone());
+      }
+
+      if (da >= 0.) != (db >= 0.) {
+        output.push(a.lerp(b, da / (da - db)));
+      }
+    }
+
+    polygon = output;
+  }
+
+  polygon
+}
+
+/// Clip a segment against the near plane.
+fn clip_segment(mut a: ClipVertex, mut b: ClipVertex) -> Option<(ClipVertex, ClipVertex)> {
+  for plane in &CLIP_PLANES {
+    let da = plane(&a.position);
+    let db = plane(&b.position);
+
+    match (da >= 0., db >= 0.) {
+      (true, true) => (),
+      (false, false) => return None,
+      (true, false) => b = a.lerp(&b, da / (da - db)),
+      (false, true) => a = a.lerp(&b, da / (da - db)),
+    }
+  }
+
+  Some((a, b))
+}
+
+/// Everything needed to rasterize primitives into the target textures.
+struct Rasterizer<'a> {
+  viewport: [i32; 4],
+  bounds: [i32; 4],
+  render_state: &'a RenderState,
+  srgb: bool,
+  colors: Vec<RefMut<'a, TextureData>>,
+  depth: Option<RefMut<'a, TextureData>>,
+  samples: &'static [[f32; 2]],
+  fragment: &'a FragmentShader,
+  uniforms: &'a Uniforms<'a>,
+}
+
+impl<'a> Rasterizer<'a> {
+  fn to_window(&self, v: &ClipVertex) -> WindowVertex {
+    let [vx, vy, vw, vh] = self.viewport;
+    let inv_w = 1. / v.position[3];
+    let ndc = [
+      v.position[0] * inv_w,
+      v.position[1] * inv_w,
+      v.position[2] * inv_w,
+    ];
+
+    WindowVertex {
+      x: vx as f32 + (ndc[0] + 1.) * 0.5 * vw as f32,
+      y: vy as f32 + (ndc[1] + 1.) * 0.5 * vh as f32,
+      z: (ndc[2] + 1.) * 0.5,
+      inv_w,
+      varyings: v.varyings.iter().map(|x| x * inv_w).collect(),
+    }
+  }
+
+  fn point(&mut self, v: ClipVertex) {
+    if CLIP_PLANES.iter().any(|plane| plane(&v.position) < 0.) {
+      return;
+    }
+
+    let v = self.to_window(&v);
+    let varyings = v.varyings.iter().map(|x| x / v.inv_w).collect::<Vec<_>>();
+    let coverage = self.full_coverage(v.z);
+
+    self.fragment(
+      v.x.floor() as i32,
+      v.y.floor() as i32,
+      &coverage,
+      v.z,
+      v.inv_w,
+      true,
+      &varyings,
+    );
+  }
+
+  fn line(&mut self, a: ClipVertex, b: ClipVertex) {
+    let (a, b) = match clip_segment(a, b) {
+      Some(segment) => segment,
+      None => return,
+    };
+
+    let a = self.to_window(&a);
+    let b = self.to_window(&b);
+    let steps = (b.x - a.x).abs().max((b.y - a.y).abs()).ceil().max(1.) as u32;
+
+    for i in 0..=steps {
+      let t = i as f32 / steps as f32;
+      let x = a.x + (b.x - a.x) * t;
+      let y = a.y + (b.y - a.y) * t;
+      let z = a.z + (b.z - a.z) * t;
+      let inv_w = a.inv_w + (b.inv_w - a.inv_w) * t;
+      let varyings = a
+        .varyings
+        .iter()
+        .zip(&b.varyings)
+        .map(|(va, vb)| (va + (vb - va) * t) / inv_w)
+        .collect::<Vec<_>>();
+      let coverage = self.full_coverage(z);
+
+      self.fragment(
+        x.floor() as i32,
+        y.floor() as i32,
+        &coverage,
+        z,
+        inv_w,
+        true,
+        &varyings,
+      );
+    }
+  }
+
+  fn triangle(&mut self, a: ClipVertex, b: ClipVertex, c: ClipVertex) {
+    let polygon = clip_polygon(vec![a, b, c]);
+
+    if polygon.len() < 3 {
+      return;
+    }
+
+    let polygon = polygon
+      .iter()
+      .map(|v| self.to_window(v))
+      .collect::<Vec<_>>();
+
+    for i in 1..polygon.len() - 1 {
+      self.window_triangle(&polygon[0], &polygon[i], &polygon[i + 1]);
+    }
+  }
+
+  fn window_triangle(&mut self, v0: &WindowVertex, v1: &WindowVertex, v2: &WindowVertex) {
+    let area = edge(v0, v1, v2.x, v2.y);
+
+    if area == 0. || !area.is_finite() {
+      return;
+    }
+
+    // window space has its Y axis pointing up, so counter-clockwise triangles have a positive area
+    let front_facing = match self.render_state.face_culling() {
+      Some(face_culling) if face_culling.order == FaceCullingOrder::CW => area < 0.,
+      _ => area > 0.,
+    };
+
+    if let Some(face_culling) = self.render_state.face_culling() {
+      let culled = match face_culling.mode {
+        FaceCullingMode::Front => front_facing,
+        FaceCullingMode::Back => !front_facing,
+        FaceCullingMode::Both => true,
+      };
+
+      if culled {
+        return;
+      }
+    }
+
+    // make the triangle counter-clockwise so that the edge functions are positive inside
+    let (v1, v2, area) = if area < 0. {
+      (v2, v1, -area)
+    } else {
+      (v1, v2, area)
+    };
+
+    let min_x = v0.x.min(v1.x).min(v2.x).floor().max(self.bounds[0] as f32) as i32;
+    let min_y = v0.y.min(v1.y).min(v2.y).floor().max(self.bounds[1] as f32) as i32;
+    let max_x = v0.x.max(v1.x).max(v2.x).ceil().min(self.bounds[2] as f32) as i32;
+    let max_y = v0.y.max(v1.y).max(v2.y).ceil().min(self.bounds[3] as f32) as i32;
+
+    let mut varyings = vec![0.; v0.varyings.len()];
+    let mut coverage = Vec::with_capacity(self.samples.len());
+
+    for y in min_y..max_y {
+      for x in min_x..max_x {
+        // test every sample against the edges, but shade once per pixel, at its center
+        coverage.clear();
+
+        for (sample, [sx, sy]) in self.samples.iter().enumerate() {
+          let px = x as f32 + sx;
+          let py = y as f32 + sy;
+
+          let w0 = edge(v1, v2, px, py);
+          let w1 = edge(v2, v0, px, py);
+          let w2 = edge(v0, v1, px, py);
+
+          if covers(w0, v1, v2) && covers(w1, v2, v0) && covers(w2, v0, v1) {
+            let z = (w0 * v0.z + w1 * v1.z + w2 * v2.z) / area;
+            coverage.push((sample as u32, z));
+          }
+        }
+
+        if coverage.is_empty() {
+          continue;
+        }
+
+        let px = x as f32 + 0.5;
+        let py = y as f32 + 0.5;
+        let l0 = edge(v1, v2, px, py) / area;
+        let l1 = edge(v2, v0, px, py) / area;
+        let l2 = edge(v0, v1, px, py) / area;
+        let z = l0 * v0.z + l1 * v1.z + l2 * v2.z;
+        let inv_w = l0 * v0.inv_w + l1 * v1.inv_w + l2 * v2.inv_w;
+
+        for (i, varying) in varyings.iter_mut().enumerate() {
+          *varying = (l0 * v0.varyings[i] + l1 * v1.varyings[i] + l2 * v2.varyings[i]) / inv_w;
+        }
+
+        self.fragment(x, y, &coverage, z, inv_w, front_facing, &varyings);
+      }
+    }
+  }
+
+  /// Coverage of every sample of a pixel at depth `z`, used by points and lines.
+  fn full_coverage(&self, z: f32) -> Vec<(u32, f32)> {
+    (0..self.samples.len() as u32)
+      .map(|sample| (sample, z))
+      .collect()
+  }
+
+  /// Shade a fragment and write it to the covered samples of the target textures.
+  ///
+  /// `coverage` lists the covered samples along with their interpolated depth.
+  #[allow(clippy::too_many_arguments)]
+  fn fragment(
+    &mut self,
+    x: i32,
+    y: i32,
+    coverage: &[(u32, f32)],
+    z: f32,
+    inv_w: f32,
+    front_facing: bool,
+    varyings: &[f32],
+  ) {
+    let [min_x, min_y, max_x, max_y] = self.bounds;
+
+    if x < min_x || y < min_y || x >= max_x || y >= max_y {
+      return;
+    }
+
+    let input = FragmentInput {
+      frag_coord: [x as f32 + 0.5, y as f32 + 0.5, z, inv_w],
+      front_facing,
+      varyings,
+    };
+    let output = (self.fragment)(&input, self.uniforms);
+
+    if output.discarded {
+      return;
+    }
+
+    let blending = self.render_state.blending();
+
+    // the stencil test only applies if the depth slot has a stencil channel
+    let stencil_test = self
+      .depth
+      .as_ref()
+      .filter(|depth| depth.pf.is_depth_stencil_pixel())
+      .and(self.render_state.stencil_test());
+    let stencil_ops = if front_facing {
+      self.render_state.front_stencil_operations()
+    } else {
+      self.render_state.back_stencil_operations()
+    };
+    let stencil_write_mask = self.render_state.stencil_write_mask();
+
+    for &(sample, sample_z) in coverage {
+      let pos = [x as u32, y as u32, sample];
+
+      if let Some(ref mut depth) = self.depth {
+        // a depth written by the shader applies to every sample
+        let frag_depth = output.depth.unwrap_or(sample_z).clamp(0., 1.);
+        let pf = depth.pf;
+        let stored = decode(pf, depth.texel(pos), false);
+        let mut updated = stored;
+        let stored_stencil = stored[1] as u8;
+
+        let passes = match stencil_test {
+          Some(test)
+            if !compare_stencil(
+              test.comparison,
+              test.reference & test.mask,
+              stored_stencil & test.mask,
+            ) =>
+          {
+            Err(stencil_ops.stencil_fails)
+          }
+
+          _ => match self.render_state.depth_test() {
+            Some(comparison) if !compare_depth(comparison, frag_depth, stored[0]) => {
+              Err(stencil_ops.depth_fails)
+            }
+            _ => Ok(stencil_ops.depth_passes),
+          },
+        };
+
+        if let Some(test) = stencil_test {
+          let op = passes.unwrap_or_else(|op| op);
+          let stencil = apply_stencil_op(op, stored_stencil, test.reference);
+          updated[1] = (stored_stencil & !stencil_write_mask | stencil & stencil_write_mask) as f32;
+        }
+
+        if passes.is_ok() && self.render_state.depth_write() == DepthWrite::On {
+          updated[0] = frag_depth;
+        }
+
+        if updated != stored {
+          encode(pf, updated, false, depth.texel_mut(pos));
+        }
+
+        if passes.is_err() {
+          continue;
+        }
+      }
+
+      for (color, &src) in self.colors.iter_mut().zip(&output.colors) {
+        let pf = color.pf;
+        let texel = color.texel_mut(pos);
+
+        let src = match blending {
+          Some(blending) => blend(blending, src, decode(pf, texel, self.srgb)),
+          None => src,
+        };
+
+        encode(pf, src, self.srgb, texel);
+      }
+    }
+  }
+}
+
+/// Compare a masked reference stencil value `a` with a masked stored value `b`.
+fn compare_stencil(comparison: StencilComparison, a: u8, b: u8) -> bool {
+  match comparison {
+    StencilComparison::Never => false,
+    StencilComparison::Always => true,
+    StencilComparison::Equal => a == b,
+    StencilComparison::NotEqual => a != b,
+    StencilComparison::Less => a < b,
+    StencilComparison::LessOrEqual => a <= b,
+    StencilComparison::Greater => a > b,
+    StencilComparison::GreaterOrEqual => a >= b,
+  }
+}
+
+/// New stencil value after applying an operation to the stored one.
+fn apply_stencil_op(op: StencilOp, stored: u8, reference: u8) -> u8 {
+  match op {
+    StencilOp::Keep => stored,
+    StencilOp::Zero => 0,
+    StencilOp::Replace => reference,
+    StencilOp::Increment => stored.saturating_add(1),
+    StencilOp::IncrementWrap => stored.wrapping_add(1),
+    StencilOp::Decrement => stored.saturating_sub(1),
+    StencilOp::DecrementWrap => stored.wrapping_sub(1),
+    StencilOp::Invert => !stored,
+  }
+}
+
+/// Edge function of the edge `a -> b` evaluated at `(x, y)`.
+fn edge(a: &WindowVertex, b: &WindowVertex, x: f32, y: f32) -> f32 {
+  (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+/// Whether a sample with edge function `w` for the edge `a -> b` is covered.
+///
+/// Samples exactly on an edge are only covered for top and left edges, so that adjacent triangles
+/// never cover the same sample twice.
+fn covers(w: f32, a: &WindowVertex, b: &WindowVertex) -> bool {
+  if w != 0. {
+    return w > 0.;
+  }
+
+  let dx = b.x - a.x;
+  let dy = b.y - a.y;
+  (dy == 0. && dx < 0.) || dy < 0.
+}
+
+fn blend(blending: BlendingMode, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+  let (rgb, alpha) = match blending {
+    BlendingMode::Combined(blending) => (blending, blending),
+    BlendingMode::Separate { rgb, alpha } => (rgb, alpha),
+  };
+
+  let mut output = [0.; 4];
+
+  for (i, c) in output.iter_mut().enumerate() {
+    let Blending {
+      equation,
+      src: src_factor,
+      dst: dst_factor,
+    } = if i < 3 { rgb } else { alpha };
+
+    let s = src[i] * factor(src_factor, i, src, dst);
+    let d = dst[i] * factor(dst_factor, i, src, dst);
+
+    *c = match equation {
+      Equation::Additive => s + d,
+      Equation::Subtract => s - d,
+      Equation::ReverseSubtract => d - s,
+      Equation::Min => src[i].min(dst[i]),
+      Equation::Max => src[i].max(dst[i]),
+    };
+  }
+
+  output
+}
+
+fn factor(factor: Factor, i: usize, src: [f32; 4], dst: [f32; 4]) -> f32 {
+  match factor {
+    Factor::One => 1.,
+    Factor::Zero => 0.,
+    Factor::SrcColor => src[i],
+    Factor::SrcColorComplement => 1. - src[i],
+    Factor::DestColor => dst[i],
+    Factor::DestColorComplement => 1. - dst[i],
+    Factor::SrcAlpha => src[3],
+    Factor::SrcAlphaComplement => 1. - src[3],
+    Factor::DstAlpha => dst[3],
+    Factor::DstAlphaComplement => 1. - dst[3],
+    Factor::SrcAlphaSaturate if i == 3 => 1.,
+    Factor::SrcAlphaSaturate => src[3].min(1. - dst[3]),
+  }
+}
+
+/// Draw primitives with the current state of the backend.
+///
+/// `elements` are the vertex indices to draw, [`None`] being a primitive restart.
+pub(crate) fn draw(
+  state: &SoftState,
+  mode: Mode,
+  elements: &[Option<u32>],
+  inst_nb: usize,
+  fetcher: &VertexFetcher,
+) {
+  let (program, target) = match (&state.program, &state.target) {
+    (Some(program), Some(target)) => (program, target),
+    _ => return,
+  };
+
+  let values = program.uniforms.borrow();
+  let uniforms = Uniforms {
+    values: &values,
+    textures: &state.binding_stack.textures,
+    buffers: &state.binding_stack.buffers,
+  };
+
+  let colors = target
+    .colors
+    .iter()
+    .map(|color| color.borrow_mut())
+    .collect::<Vec<_>>();
+  let depth = target.depth.as_ref().map(|depth| depth.borrow_mut());
+
+  let [w, h, _] = colors
+    .first()
+    .or(depth.as_ref())
+    .map(|texture| texture.size())
+    .unwrap_or([0, 0, 0]);
+  let [vx, vy, vw, vh] = state.viewport;
+  let mut bounds = [
+    vx.max(0),
+    vy.max(0),
+    (vx + vw).min(w as i32),
+    (vy + vh).min(h as i32),
+  ];
+
+  if let Some(scissor) = state.render_state.scissor() {
+    bounds[0] = bounds[0].max(scissor.x as i32);
+    bounds[1] = bounds[1].max(scissor.y as i32);
+    bounds[2] = bounds[2].min((scissor.x + scissor.width) as i32);
+    bounds[3] = bounds[3].min((scissor.y + scissor.height) as i32);
+  }
+
+  let mut rasterizer = Rasterizer {
+    viewport: state.viewport,
+    bounds,
+    render_state: &state.render_state,
+    srgb: state.srgb_enabled,
+    colors,
+    depth,
+    samples: target.sample_positions,
+    fragment: &program.fragment,
+    uniforms: &uniforms,
+  };
+
+  for instance_id in 0..inst_nb.max(1) as u32 {
+    // shade every vertex once per instance
+    let mut cache = HashMap::new();
+    let vertices = elements
+      .iter()
+      .map(|element| {
+        element.map(|vertex_id| {
+          cache
+            .entry(vertex_id)
+            .or_insert_with(|| {
+              let attribs = fetcher.fetch(vertex_id, instance_id);
+              let input = VertexInput {
+                attribs: &attribs,
+                vertex_id,
+                instance_id,
+              };
+
+              ClipVertex::from(&(program.vertex)(&input, &uniforms))
+            })
+            .clone()
+        })
+      })
+      .collect::<Vec<_>>();
+
+    // primitive restart splits the vertices in several independent runs
+    for run in vertices.split(Option::is_none) {
+      let run = run.iter().flatten().cloned().collect::<Vec<_>>();
+      assemble(&mut rasterizer, mode, run);
+    }
+  }
+}
+
+/// Assemble primitives from a run of vertices and rasterize them.
+fn assemble(rasterizer: &mut Rasterizer, mode: Mode, run: Vec<ClipVertex>) {
+  match mode {
+    Mode::Point => {
+      for v in run {
+        rasterizer.point(v);
+      }
+    }
+
+    Mode::Line => {
+      for pair in run.chunks_exact(2) {
+        rasterizer.line(pair[0].clone(), pair[1].clone());
+      }
+    }
+
+    Mode::LineStrip => {
+      for pair in run.windows(2) {
+        rasterizer.line(pair[0].clone(), pair[1].clone());
+      }
+    }
+
+    Mode::Triangle => {
+      for tri in run.chunks_exact(3) {
+        rasterizer.triangle(tri[0].clone(), tri[1].clone(), tri[2].clone());
+      }
+    }
+
+    Mode::TriangleStrip => {
+      for (i, tri) in run.windows(3).enumerate() {
+        // keep the same winding for every other triangle
+        if i % 2 == 0 {
+          rasterizer.triangle(tri[0].clone(), tri[1].clone(), tri[2].clone());
+        } else {
+          rasterizer.triangle(tri[1].clone(), tri[0].clone(), tri[2].clone());
+        }
+      }
+    }
+
+    Mode::TriangleFan => {
+      if let Some((first, rest)) = run.split_first() {
+        for pair in rest.windows(2) {
+          rasterizer.triangle(first.clone(), pair[0].clone(), pair[1].clone());
+        }
+      }
+    }
+
+    // patches are rejected when building tessellations
+    Mode::Patch(_) => (),
+  }
+}
@@ -0,0 +1,57 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::texture::TextureData;
+use crate::Soft;
+use luminance::backend::renderbuffer::{Renderbuffer as RenderbufferBackend, RenderbufferBase};
+use luminance::framebuffer::FramebufferError;
+use luminance::pixel::Pixel;
+use luminance::texture::{Dim, Sampler};
+
+/// Renderbuffers are stored as single-level 2D textures that cannot be sampled.
+#[derive(Debug)]
+pub struct Renderbuffer {
+  data: Rc<RefCell<TextureData>>,
+}
+
+unsafe impl RenderbufferBase for Soft {
+  type RenderbufferRepr = Renderbuffer;
+}
+
+unsafe impl<P> RenderbufferBackend<P> for Soft
+where
+  P: Pixel,
+{
+  unsafe fn new_renderbuffer(
+    &mut self,
+    [width, height]: [u32; 2],
+  ) -> Result<Self::RenderbufferRepr, FramebufferError> {
+    let data = TextureData::new(
+      Dim::Dim2,
+      [width, height, 1],
+      1,
+      P::pixel_format(),
+      Sampler::default(),
+    )?;
+
+    Ok(Renderbuffer {
+      data: Rc::new(RefCell::new(data)),
+    })
+  }
+
+  unsafe fn attach_color_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    framebuffer.attach_color(&renderbuffer.data, attachment_index)
+  }
+
+  unsafe fn attach_depth_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+  ) -> Result<(), FramebufferError> {
+    framebuffer.attach_depth(&renderbuffer.data);
+    Ok(())
+  }
+}
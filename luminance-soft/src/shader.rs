@@ -0,0 +1,596 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::rc::Rc;
+
+use crate::texture::TextureData;
+use crate::Soft;
+use luminance::backend::shader::{Shader, Uniformable};
+use luminance::pipeline::{BufferBinding, TextureBinding};
+use luminance::pixel::{SamplerType, Type as PixelType};
+use luminance::shader::{
+  ProgramError, StageError, StageType, TessellationStages, Uniform, UniformType, UniformWarning,
+  VertexAttribWarning,
+};
+use luminance::texture::{Dim, Dimensionable};
+use luminance::vertex::Semantics;
+
+pub(crate) type VertexShader = Rc<dyn Fn(&VertexInput, &Uniforms) -> VertexOutput>;
+
+pub(crate) type FragmentShader = Rc<dyn Fn(&FragmentInput, &Uniforms) -> FragmentOutput>;
+
+/// A registered shader closure.
+#[derive(Clone)]
+pub(crate) enum ShaderFn {
+  Vertex(VertexShader),
+  Fragment(FragmentShader),
+}
+
+impl fmt::Debug for ShaderFn {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      ShaderFn::Vertex(_) => f.write_str("ShaderFn::Vertex"),
+      ShaderFn::Fragment(_) => f.write_str("ShaderFn::Fragment"),
+    }
+  }
+}
+
+/// Input of a vertex shader.
+#[derive(Debug)]
+pub struct VertexInput<'a> {
+  pub(crate) attribs: &'a [Option<[f32; 4]>],
+  pub(crate) vertex_id: u32,
+  pub(crate) instance_id: u32,
+}
+
+impl<'a> VertexInput<'a> {
+  /// Get a vertex attribute by its semantics index.
+  ///
+  /// Attributes are always widened to four floating-point components; missing components are set
+  /// to `0`, except the fourth one, which is set to `1`. Missing attributes are read as
+  /// `[0., 0., 0., 1.]`.
+  pub fn attrib(&self, index: usize) -> [f32; 4] {
+    self
+      .attribs
+      .get(index)
+      .copied()
+      .flatten()
+      .unwrap_or([0., 0., 0., 1.])
+  }
+
+  /// Index of the vertex being processed.
+  pub fn vertex_id(&self) -> u32 {
+    self.vertex_id
+  }
+
+  /// Index of the instance being processed.
+  pub fn instance_id(&self) -> u32 {
+    self.instance_id
+  }
+}
+
+/// Output of a vertex shader.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexOutput {
+  /// Clip-space position of the vertex.
+  pub position: [f32; 4],
+  /// Values interpolated across primitives and passed to the fragment shader.
+  pub varyings: Vec<f32>,
+}
+
+impl VertexOutput {
+  /// Create a vertex output with no varyings.
+  pub fn new(position: [f32; 4]) -> Self {
+    VertexOutput {
+      position,
+      varyings: Vec::new(),
+    }
+  }
+
+  /// Set the varyings of the vertex output.
+  pub fn with_varyings(self, varyings: impl IntoIterator<Item = f32>) -> Self {
+    VertexOutput {
+      varyings: varyings.into_iter().collect(),
+      ..self
+    }
+  }
+}
+
+/// Input of a fragment shader.
+#[derive(Debug)]
+pub struct FragmentInput<'a> {
+  pub(crate) frag_coord: [f32; 4],
+  pub(crate) front_facing: bool,
+  pub(crate) varyings: &'a [f32],
+}
+
+impl<'a> FragmentInput<'a> {
+  /// Window-space coordinates of the fragment, as in GLSL’s `gl_FragCoord`.
+  pub fn frag_coord(&self) -> [f32; 4] {
+    self.frag_coord
+  }
+
+  /// Whether the fragment belongs to a front-facing primitive.
+  pub fn front_facing(&self) -> bool {
+    self.front_facing
+  }
+
+  /// Interpolated varyings.
+  pub fn varyings(&self) -> &[f32] {
+    self.varyings
+  }
+}
+
+/// Output of a fragment shader.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FragmentOutput {
+  pub(crate) colors: Vec<[f32; 4]>,
+  pub(crate) depth: Option<f32>,
+  pub(crate) discarded: bool,
+}
+
+impl FragmentOutput {
+  /// Output a single color, written to the first color slot.
+  pub fn color(color: [f32; 4]) -> Self {
+    Self::colors(vec![color])
+  }
+
+  /// Output several colors, one per color slot.
+  pub fn colors(colors: Vec<[f32; 4]>) -> Self {
+    FragmentOutput {
+      colors,
+      depth: None,
+      discarded: false,
+    }
+  }
+
+  /// Discard the fragment.
+  pub fn discard() -> Self {
+    FragmentOutput {
+      colors: Vec::new(),
+      depth: None,
+      discarded: true,
+    }
+  }
+
+  /// Override the depth of the fragment, as in GLSL’s `gl_FragDepth`.
+  pub fn with_depth(self, depth: f32) -> Self {
+    FragmentOutput {
+      depth: Some(depth),
+      ..self
+    }
+  }
+}
+
+/// Value of a uniform, as set by a [`Uniformable`] implementation.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum UniformValue {
+  /// 32-bit signed integer.
+  Int(i32),
+  /// 32-bit unsigned integer.
+  UInt(u32),
+  /// 32-bit floating-point number.
+  Float(f32),
+  /// Boolean.
+  Bool(bool),
+  /// 2D signed integral vector.
+  IVec2([i32; 2]),
+  /// 3D signed integral vector.
+  IVec3([i32; 3]),
+  /// 4D signed integral vector.
+  IVec4([i32; 4]),
+  /// 2D unsigned integral vector.
+  UIVec2([u32; 2]),
+  /// 3D unsigned integral vector.
+  UIVec3([u32; 3]),
+  /// 4D unsigned integral vector.
+  UIVec4([u32; 4]),
+  /// 2D floating-point vector.
+  Vec2([f32; 2]),
+  /// 3D floating-point vector.
+  Vec3([f32; 3]),
+  /// 4D floating-point vector.
+  Vec4([f32; 4]),
+  /// 2D boolean vector.
+  BVec2([bool; 2]),
+  /// 3D boolean vector.
+  BVec3([bool; 3]),
+  /// 4D boolean vector.
+  BVec4([bool; 4]),
+  /// 2×2 floating-point matrix.
+  M22([[f32; 2]; 2]),
+  /// 3×3 floating-point matrix.
+  M33([[f32; 3]; 3]),
+  /// 4×4 floating-point matrix.
+  M44([[f32; 4]; 4]),
+  /// Array of values.
+  Array(Vec<UniformValue>),
+  /// Binding of a bound texture.
+  TextureBinding(u32),
+  /// Binding of a bound buffer.
+  BufferBinding(u32),
+}
+
+/// Types that can be extracted from a [`UniformValue`].
+pub trait FromUniformValue: Sized {
+  /// Extract the value, if it has the right type.
+  fn from_uniform_value(value: &UniformValue) -> Option<Self>;
+}
+
+impl<T> FromUniformValue for Vec<T>
+where
+  T: FromUniformValue,
+{
+  fn from_uniform_value(value: &UniformValue) -> Option<Self> {
+    match value {
+      UniformValue::Array(values) => values.iter().map(T::from_uniform_value).collect(),
+      _ => None,
+    }
+  }
+}
+
+/// Uniforms, textures and buffers available to shaders.
+pub struct Uniforms<'a> {
+  pub(crate) values: &'a UniformValues,
+  pub(crate) textures: &'a [Option<Rc<RefCell<TextureData>>>],
+  pub(crate) buffers: &'a [Option<Vec<u8>>],
+}
+
+impl<'a> Uniforms<'a> {
+  /// Get the raw value of a uniform by name.
+  ///
+  /// Returns [`None`] if the uniform was never set.
+  pub fn value(&self, name: &str) -> Option<&UniformValue> {
+    let index = self.values.names.get(name)?.0;
+    self.values.values.get(index as usize)?.as_ref()
+  }
+
+  /// Get the value of a uniform by name.
+  ///
+  /// Returns [`None`] if the uniform was never set or if it has a different type.
+  pub fn get<T>(&self, name: &str) -> Option<T>
+  where
+    T: FromUniformValue,
+  {
+    self.value(name).and_then(T::from_uniform_value)
+  }
+
+  /// Sample a texture bound to a uniform.
+  ///
+  /// Coordinates are normalized, except for the layer of array textures. Cubemaps expect a
+  /// direction vector. Depth textures with depth comparison expect the reference value as
+  /// last coordinate (third one for 1D, 2D and 1D array textures, fourth one otherwise).
+  ///
+  /// The sampled mipmap level is the base level of the sampler of the texture. With mipmap
+  /// minification filters, it is offset by the LOD bias, clamped to the LOD and mipmap ranges.
+  ///
+  /// Returns `[0., 0., 0., 0.]` if no texture is bound to the uniform.
+  pub fn sample(&self, name: &str, coords: [f32; 4]) -> [f32; 4] {
+    self
+      .texture(name)
+      .and_then(|texture| {
+        texture
+          .try_borrow()
+          .ok()
+          .map(|texture| texture.sample(coords))
+      })
+      .unwrap_or([0., 0., 0., 0.])
+  }
+
+  /// Fetch a single texel of a texture bound to a uniform, as in GLSL’s `texelFetch`.
+  ///
+  /// Returns `[0., 0., 0., 0.]` if no texture is bound to the uniform or if the texel is out of
+  /// bounds.
+  pub fn fetch(&self, name: &str, texel: [i32; 3]) -> [f32; 4] {
+    self
+      .texture(name)
+      .and_then(|texture| {
+        texture
+          .try_borrow()
+          .ok()
+          .map(|texture| texture.fetch(texel))
+      })
+      .unwrap_or([0., 0., 0., 0.])
+  }
+
+  /// Raw bytes of a buffer bound to a uniform.
+  pub fn buffer_bytes(&self, name: &str) -> Option<&[u8]> {
+    match self.value(name)? {
+      UniformValue::BufferBinding(binding) => self
+        .buffers
+        .get(*binding as usize)?
+        .as_ref()
+        .map(Vec::as_slice),
+      _ => None,
+    }
+  }
+
+  /// Read an item of a buffer bound to a uniform.
+  ///
+  /// `T` must be the type of the items of the bound buffer.
+  pub fn buffer_item<T>(&self, name: &str, index: usize) -> Option<T>
+  where
+    T: Copy,
+  {
+    let bytes = self.buffer_bytes(name)?;
+    let start = index * mem::size_of::<T>();
+
+    if start + mem::size_of::<T>() > bytes.len() {
+      None
+    } else {
+      Some(unsafe { ptr::read_unaligned(bytes[start..].as_ptr() as *const T) })
+    }
+  }
+
+  fn texture(&self, name: &str) -> Option<&Rc<RefCell<TextureData>>> {
+    match self.value(name)? {
+      UniformValue::TextureBinding(unit) => self.textures.get(*unit as usize)?.as_ref(),
+      _ => None,
+    }
+  }
+}
+
+/// Names, types and values of the uniforms of a program.
+#[derive(Debug, Default)]
+pub(crate) struct UniformValues {
+  names: HashMap<String, (i32, UniformType)>,
+  values: Vec<Option<UniformValue>>,
+}
+
+#[derive(Debug)]
+pub struct Stage {
+  shader: ShaderFn,
+}
+
+pub(crate) struct ProgramInner {
+  pub(crate) vertex: VertexShader,
+  pub(crate) fragment: FragmentShader,
+  pub(crate) uniforms: RefCell<UniformValues>,
+}
+
+impl fmt::Debug for ProgramInner {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("ProgramInner")
+      .field("uniforms", &self.uniforms)
+      .finish()
+  }
+}
+
+#[derive(Debug)]
+pub struct Program {
+  pub(crate) inner: Rc<ProgramInner>,
+}
+
+impl Program {
+  fn set_uniform<T>(&mut self, uniform: &Uniform<T>, value: UniformValue)
+  where
+    T: ?Sized,
+  {
+    let index = uniform.index();
+
+    if index < 0 {
+      return;
+    }
+
+    if let Some(slot) = self
+      .inner
+      .uniforms
+      .borrow_mut()
+      .values
+      .get_mut(index as usize)
+    {
+      *slot = Some(value);
+    }
+  }
+}
+
+pub struct UniformBuilder {
+  program: Rc<ProgramInner>,
+}
+
+unsafe impl Shader for Soft {
+  type StageRepr = Stage;
+
+  type ProgramRepr = Program;
+
+  type UniformBuilderRepr = UniformBuilder;
+
+  unsafe fn new_stage(&mut self, ty: StageType, src: &str) -> Result<Self::StageRepr, StageError> {
+    let shader = self.state.borrow().shader(src);
+
+    match (ty, shader) {
+      (StageType::VertexShader, Some(shader @ ShaderFn::Vertex(_)))
+      | (StageType::FragmentShader, Some(shader @ ShaderFn::Fragment(_))) => Ok(Stage { shader }),
+
+      (StageType::VertexShader, _) | (StageType::FragmentShader, _) => Err(
+        StageError::compilation_failed(ty, format!("no {} registered as {:?}", ty, src)),
+      ),
+
+      _ => Err(StageError::unsupported_type(ty)),
+    }
+  }
+
+  unsafe fn new_program(
+    &mut self,
+    vertex: &Self::StageRepr,
+    tess: Option<TessellationStages<Self::StageRepr>>,
+    geometry: Option<&Self::StageRepr>,
+    fragment: &Self::StageRepr,
+  ) -> Result<Self::ProgramRepr, ProgramError> {
+    if tess.is_some() || geometry.is_some() {
+      return Err(ProgramError::link_failed(
+        "tessellation and geometry stages are not supported",
+      ));
+    }
+
+    match (&vertex.shader, &fragment.shader) {
+      (ShaderFn::Vertex(vertex), ShaderFn::Fragment(fragment)) => Ok(Program {
+        inner: Rc::new(ProgramInner {
+          vertex: vertex.clone(),
+          fragment: fragment.clone(),
+          uniforms: RefCell::new(UniformValues::default()),
+        }),
+      }),
+
+      _ => Err(ProgramError::link_failed("mismatching stage types")),
+    }
+  }
+
+  unsafe fn apply_semantics<Sem>(
+    _: &mut Self::ProgramRepr,
+  ) -> Result<Vec<VertexAttribWarning>, ProgramError>
+  where
+    Sem: Semantics,
+  {
+    // vertex attributes are looked up by semantics index directly
+    Ok(Vec::new())
+  }
+
+  unsafe fn new_uniform_builder(
+    program: &mut Self::ProgramRepr,
+  ) -> Result<Self::UniformBuilderRepr, ProgramError> {
+    Ok(UniformBuilder {
+      program: program.inner.clone(),
+    })
+  }
+
+  unsafe fn ask_uniform<T>(
+    uniform_builder: &mut Self::UniformBuilderRepr,
+    name: &str,
+  ) -> Result<Uniform<T>, UniformWarning>
+  where
+    T: Uniformable<Self>,
+  {
+    let ty = T::ty();
+    let mut uniforms = uniform_builder.program.uniforms.borrow_mut();
+    let UniformValues { names, values } = &mut *uniforms;
+
+    // there is no way to know which uniforms closures use, so every asked uniform is active
+    let &mut (index, expected_ty) = names.entry(name.to_owned()).or_insert_with(|| {
+      values.push(None);
+      (values.len() as i32 - 1, ty)
+    });
+
+    if expected_ty == ty {
+      Ok(Uniform::new(index))
+    } else {
+      Err(UniformWarning::type_mismatch(name, ty))
+    }
+  }
+
+  unsafe fn unbound<T>(_: &mut Self::UniformBuilderRepr) -> Uniform<T>
+  where
+    T: Uniformable<Self>,
+  {
+    Uniform::new(-1)
+  }
+}
+
+macro_rules! impl_Uniformable {
+  ($t:ty, $uty:tt) => {
+    unsafe impl Uniformable<Soft> for $t {
+      unsafe fn ty() -> UniformType {
+        UniformType::$uty
+      }
+
+      unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+        program.set_uniform(uniform, UniformValue::$uty(self));
+      }
+    }
+
+    unsafe impl<'a> Uniformable<Soft> for &'a [$t] {
+      unsafe fn ty() -> UniformType {
+        UniformType::$uty
+      }
+
+      unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+        let values = self.iter().map(|&x| UniformValue::$uty(x)).collect();
+        program.set_uniform(uniform, UniformValue::Array(values));
+      }
+    }
+
+    impl FromUniformValue for $t {
+      fn from_uniform_value(value: &UniformValue) -> Option<Self> {
+        match *value {
+          UniformValue::$uty(x) => Some(x),
+          _ => None,
+        }
+      }
+    }
+  };
+}
+
+impl_Uniformable!(i32, Int);
+impl_Uniformable!([i32; 2], IVec2);
+impl_Uniformable!([i32; 3], IVec3);
+impl_Uniformable!([i32; 4], IVec4);
+
+impl_Uniformable!(u32, UInt);
+impl_Uniformable!([u32; 2], UIVec2);
+impl_Uniformable!([u32; 3], UIVec3);
+impl_Uniformable!([u32; 4], UIVec4);
+
+impl_Uniformable!(f32, Float);
+impl_Uniformable!([f32; 2], Vec2);
+impl_Uniformable!([f32; 3], Vec3);
+impl_Uniformable!([f32; 4], Vec4);
+
+impl_Uniformable!(bool, Bool);
+impl_Uniformable!([bool; 2], BVec2);
+impl_Uniformable!([bool; 3], BVec3);
+impl_Uniformable!([bool; 4], BVec4);
+
+impl_Uniformable!([[f32; 2]; 2], M22);
+impl_Uniformable!([[f32; 3]; 3], M33);
+impl_Uniformable!([[f32; 4]; 4], M44);
+
+unsafe impl<T> Uniformable<Soft> for BufferBinding<T> {
+  unsafe fn ty() -> UniformType {
+    UniformType::BufferBinding
+  }
+
+  unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+    program.set_uniform(uniform, UniformValue::BufferBinding(self.binding()));
+  }
+}
+
+unsafe impl<D, S> Uniformable<Soft> for TextureBinding<D, S>
+where
+  D: Dimensionable,
+  S: SamplerType,
+{
+  unsafe fn ty() -> UniformType {
+    match (S::sample_type(), D::dim()) {
+      (PixelType::Integral, Dim::Dim1) => UniformType::ISampler1D,
+      (PixelType::Unsigned, Dim::Dim1) => UniformType::UISampler1D,
+      (_, Dim::Dim1) => UniformType::Sampler1D,
+
+      (PixelType::Integral, Dim::Dim2) => UniformType::ISampler2D,
+      (PixelType::Unsigned, Dim::Dim2) => UniformType::UISampler2D,
+      (_, Dim::Dim2) => UniformType::Sampler2D,
+
+      (PixelType::Integral, Dim::Dim3) => UniformType::ISampler3D,
+      (PixelType::Unsigned, Dim::Dim3) => UniformType::UISampler3D,
+      (_, Dim::Dim3) => UniformType::Sampler3D,
+
+      (PixelType::Integral, Dim::Cubemap) => UniformType::ICubemap,
+      (PixelType::Unsigned, Dim::Cubemap) => UniformType::UICubemap,
+      (_, Dim::Cubemap) => UniformType::Cubemap,
+
+      (PixelType::Integral, Dim::Dim1Array) => UniformType::ISampler1DArray,
+      (PixelType::Unsigned, Dim::Dim1Array) => UniformType::UISampler1DArray,
+      (_, Dim::Dim1Array) => UniformType::Sampler1DArray,
+
+      (PixelType::Integral, Dim::Dim2Array) => UniformType::ISampler2DArray,
+      (PixelType::Unsigned, Dim::Dim2Array) => UniformType::UISampler2DArray,
+      (_, Dim::Dim2Array) => UniformType::Sampler2DArray,
+    }
+  }
+
+  unsafe fn update(self, program: &mut Program, uniform: &Uniform<Self>) {
+    program.set_uniform(uniform, UniformValue::TextureBinding(self.binding()));
+  }
+}
@@ -0,0 +1,119 @@
+use luminance::render_state::RenderState;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::shader::{FragmentInput, FragmentOutput, ProgramInner, ShaderFn, Uniforms};
+use crate::shader::{VertexInput, VertexOutput};
+use crate::texture::TextureData;
+
+/// Textures a pipeline renders to.
+///
+/// Multisampled targets store their samples along the third axis of their textures.
+#[derive(Clone, Debug)]
+pub(crate) struct Target {
+  pub(crate) colors: Vec<Rc<RefCell<TextureData>>>,
+  pub(crate) depth: Option<Rc<RefCell<TextureData>>>,
+  pub(crate) sample_positions: &'static [[f32; 2]],
+}
+
+impl Target {
+  /// A single-sampled target.
+  pub(crate) fn new(
+    colors: Vec<Rc<RefCell<TextureData>>>,
+    depth: Option<Rc<RefCell<TextureData>>>,
+  ) -> Self {
+    Target {
+      colors,
+      depth,
+      sample_positions: SAMPLE_POSITIONS[0],
+    }
+  }
+}
+
+/// Positions of the samples in a pixel, for 1, 2, 4 and 8 samples.
+///
+/// These are the standard Direct3D sample patterns, which most OpenGL implementations use as well.
+pub(crate) const SAMPLE_POSITIONS: [&[[f32; 2]]; 4] = [
+  &[[0.5, 0.5]],
+  &[[0.75, 0.75], [0.25, 0.25]],
+  &[
+    [0.375, 0.125],
+    [0.875, 0.375],
+    [0.125, 0.625],
+    [0.625, 0.875],
+  ],
+  &[
+    [0.5625, 0.3125],
+    [0.4375, 0.6875],
+    [0.8125, 0.5625],
+    [0.3125, 0.1875],
+    [0.1875, 0.8125],
+    [0.0625, 0.4375],
+    [0.6875, 0.9375],
+    [0.9375, 0.0625],
+  ],
+];
+
+/// Binding points.
+///
+/// Texture units and buffer bindings are reused once the bound resources are dropped, like in the
+/// OpenGL backends.
+#[derive(Debug, Default)]
+pub(crate) struct BindingStack {
+  pub(crate) textures: Vec<Option<Rc<RefCell<TextureData>>>>,
+  pub(crate) free_texture_units: Vec<u32>,
+  pub(crate) buffers: Vec<Option<Vec<u8>>>,
+  pub(crate) free_buffer_bindings: Vec<u32>,
+}
+
+/// The state of the software backend.
+///
+/// It holds the registered shaders and everything a draw call needs: the target textures, the
+/// viewport, the render state, the current program and the bound resources.
+#[derive(Debug)]
+pub(crate) struct SoftState {
+  shaders: HashMap<String, ShaderFn>,
+  pub(crate) target: Option<Target>,
+  pub(crate) viewport: [i32; 4],
+  pub(crate) srgb_enabled: bool,
+  pub(crate) render_state: RenderState,
+  pub(crate) program: Option<Rc<ProgramInner>>,
+  pub(crate) binding_stack: BindingStack,
+  pub(crate) back_buffer: Option<Target>,
+}
+
+impl SoftState {
+  pub(crate) fn new() -> Self {
+    SoftState {
+      shaders: HashMap::new(),
+      target: None,
+      viewport: [0, 0, 0, 0],
+      srgb_enabled: false,
+      render_state: RenderState::default(),
+      program: None,
+      binding_stack: BindingStack::default(),
+      back_buffer: None,
+    }
+  }
+
+  pub(crate) fn add_vertex_shader<F>(&mut self, name: String, shader: F)
+  where
+    F: 'static + Fn(&VertexInput, &Uniforms) -> VertexOutput,
+  {
+    self.shaders.insert(name, ShaderFn::Vertex(Rc::new(shader)));
+  }
+
+  pub(crate) fn add_fragment_shader<F>(&mut self, name: String, shader: F)
+  where
+    F: 'static + Fn(&FragmentInput, &Uniforms) -> FragmentOutput,
+  {
+    self
+      .shaders
+      .insert(name, ShaderFn::Fragment(Rc::new(shader)));
+  }
+
+  pub(crate) fn shader(&self, name: &str) -> Option<ShaderFn> {
+    self.shaders.get(name).cloned()
+  }
+}
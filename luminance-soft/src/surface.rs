@@ -0,0 +1,65 @@
+//! Software surface.
+
+use luminance::context::GraphicsContext;
+use luminance::framebuffer::{Framebuffer, FramebufferError};
+use luminance::texture::Dim2;
+
+use crate::Soft;
+
+/// A surface rendering to memory.
+///
+/// The back buffer is an RGBA image with 8-bit normalized channels, whose content can be read
+/// back with [`SoftSurface::back_buffer_texels`].
+#[derive(Debug)]
+pub struct SoftSurface {
+  backend: Soft,
+  size: [u32; 2],
+}
+
+unsafe impl GraphicsContext for SoftSurface {
+  type Backend = Soft;
+
+  fn backend(&mut self) -> &mut Self::Backend {
+    &mut self.backend
+  }
+}
+
+impl SoftSurface {
+  /// Create a new [`SoftSurface`] with the given size (in pixels).
+  pub fn new(size: [u32; 2]) -> Self {
+    SoftSurface {
+      backend: Soft::new(),
+      size,
+    }
+  }
+
+  /// Get the size of the surface.
+  pub fn size(&self) -> [u32; 2] {
+    self.size
+  }
+
+  /// Resize the surface.
+  ///
+  /// The next back buffer will have the new size and a fresh content.
+  pub fn set_size(&mut self, size: [u32; 2]) {
+    self.size = size;
+  }
+
+  /// Get access to the back buffer.
+  pub fn back_buffer(&mut self) -> Result<Framebuffer<Soft, Dim2, (), ()>, FramebufferError> {
+    Framebuffer::back_buffer(self, self.size)
+  }
+
+  /// Read the texels of the back buffer.
+  ///
+  /// Texels are RGBA with 8-bit channels, rows going from the bottom of the image to its top, like
+  /// in the OpenGL backends. If no back buffer was ever requested, the texels are all zero.
+  pub fn back_buffer_texels(&self) -> Vec<u8> {
+    let state = self.backend.state.borrow();
+
+    match state.back_buffer {
+      Some(ref target) => target.colors[0].borrow().levels[0].texels.clone(),
+      None => vec![0; self.size[0] as usize * self.size[1] as usize * 4],
+    }
+  }
+}
@@ -0,0 +1,626 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+
+use crate::buffer::{Buffer, BufferSlice, BufferSliceMut};
+use crate::raster::{attrib_sources, attrib_weight, draw, VertexFetcher};
+use crate::state::SoftState;
+use crate::Soft;
+use luminance::backend::buffer::{Buffer as _, BufferSlice as _};
+use luminance::backend::tess::{
+  IndexSlice as IndexSliceBackend, InstanceSlice as InstanceSliceBackend, Tess as TessBackend,
+  VertexSlice as VertexSliceBackend,
+};
+use luminance::buffer::{BufferUsage, PreserveContent};
+use luminance::tess::{
+  Deinterleaved, DeinterleavedData, Interleaved, Mode, TessError, TessIndex, TessMapError,
+  TessParams, TessVertexData,
+};
+use luminance::vertex::{Deinterleave, Vertex};
+
+/// All the extra data required when doing indexed drawing.
+#[derive(Debug)]
+struct IndexedDrawState<I>
+where
+  I: TessIndex,
+{
+  buffer: Buffer<I>,
+  restart_index: Option<I>,
+}
+
+#[derive(Debug)]
+struct TessRaw<I>
+where
+  I: TessIndex,
+{
+  mode: Mode,
+  vert_nb: usize,
+  inst_nb: usize,
+  index_state: Option<IndexedDrawState<I>>,
+  state: Rc<RefCell<SoftState>>,
+}
+
+impl<I> TessRaw<I>
+where
+  I: TessIndex,
+{
+  fn new(
+    soft: &Soft,
+    index_state: Option<IndexedDrawState<I>>,
+    mode: Mode,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) -> Result<Self, TessError> {
+    if let Mode::Patch(_) = mode {
+      return Err(TessError::forbidden_primitive_mode(mode));
+    }
+
+    Ok(TessRaw {
+      mode,
+      vert_nb,
+      inst_nb,
+      index_state,
+      state: soft.state.clone(),
+    })
+  }
+
+  fn render(
+    &self,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+    fetcher: &VertexFetcher,
+  ) -> Result<(), TessError> {
+    let elements = match self.index_state {
+      Some(ref index_state) => {
+        // indexed render
+        let restart_index = index_state.restart_index.and_then(I::try_into_u32);
+        let indices = index_state.buffer.buf.borrow();
+        let end = (start_index + vert_nb).min(indices.len());
+
+        indices
+          .get(start_index..end)
+          .unwrap_or(&[])
+          .iter()
+          .map(|&index| index.try_into_u32().filter(|&i| Some(i) != restart_index))
+          .collect::<Vec<_>>()
+      }
+
+      // direct render
+      None => (start_index as u32..(start_index + vert_nb) as u32)
+        .map(Some)
+        .collect(),
+    };
+
+    draw(&self.state.borrow(), self.mode, &elements, inst_nb, fetcher);
+
+    Ok(())
+  }
+
+  /// Update the number of vertices to render after the vertex storage got resized.
+  ///
+  /// Indexed tessellations render as many vertices as there are indices, so they are not impacted.
+  fn vertices_resized(&mut self, len: usize) {
+    if self.index_state.is_none() {
+      self.vert_nb = len;
+    }
+  }
+
+  unsafe fn resize_indices(
+    &mut self,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    match self.index_state {
+      Some(ref mut index_state) => {
+        Soft::resize(&mut index_state.buffer, len, mem::zeroed(), preserve)?
+      }
+
+      None if len > 0 => {
+        self.index_state = Some(IndexedDrawState {
+          buffer: Buffer::from_vec(vec![mem::zeroed(); len]),
+          restart_index: None,
+        });
+      }
+
+      None => (),
+    }
+
+    self.vert_nb = len;
+
+    Ok(())
+  }
+}
+
+#[derive(Debug)]
+pub struct InterleavedTess<V, I, W>
+where
+  V: Vertex,
+  I: TessIndex,
+  W: Vertex,
+{
+  raw: TessRaw<I>,
+  vertex_buffer: Option<Buffer<V>>,
+  instance_buffer: Option<Buffer<W>>,
+}
+
+unsafe impl<V, I, W> TessBackend<V, I, W, Interleaved> for Soft
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type TessRepr = InterleavedTess<V, I, W>;
+
+  unsafe fn build(
+    &mut self,
+    vertex_data: Option<V::Data>,
+    index_data: Vec<I>,
+    instance_data: Option<W::Data>,
+    params: TessParams<I>,
+  ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      restart_index,
+      usage,
+    } = params;
+
+    let vertex_buffer = build_interleaved_vertex_buffer(self, vertex_data, usage)?;
+
+    // in case of indexed render, create an index buffer
+    let index_state = build_index_buffer(self, index_data, restart_index, usage)?;
+
+    let instance_buffer = build_interleaved_vertex_buffer(self, instance_data, usage)?;
+
+    let raw = TessRaw::new(self, index_state, mode, vert_nb, inst_nb)?;
+
+    Ok(InterleavedTess {
+      raw,
+      vertex_buffer,
+      instance_buffer,
+    })
+  }
+
+  unsafe fn tess_vertices_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.vert_nb
+  }
+
+  unsafe fn tess_instances_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.inst_nb
+  }
+
+  unsafe fn render(
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) -> Result<(), TessError> {
+    let mut fetcher = VertexFetcher::default();
+
+    if let Some(ref vb) = tess.vertex_buffer {
+      fetcher
+        .sources
+        .extend(attrib_sources(vb.bytes(), &V::vertex_desc()));
+    }
+
+    if let Some(ref ib) = tess.instance_buffer {
+      fetcher
+        .sources
+        .extend(attrib_sources(ib.bytes(), &W::vertex_desc()));
+    }
+
+    tess.raw.render(start_index, vert_nb, inst_nb, &fetcher)
+  }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved_vertex_buffer(&mut tess.vertex_buffer, len, preserve)?;
+    tess.raw.vertices_resized(len);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved_vertex_buffer(&mut tess.instance_buffer, len, preserve)?;
+    tess.raw.inst_nb = len;
+
+    Ok(())
+  }
+}
+
+unsafe impl<V, I, W> VertexSliceBackend<V, I, W, Interleaved, V> for Soft
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type VertexSliceRepr = BufferSlice<V>;
+  type VertexSliceMutRepr = BufferSliceMut<V>;
+
+  unsafe fn vertices(tess: &mut Self::TessRepr) -> Result<Self::VertexSliceRepr, TessMapError> {
+    match tess.vertex_buffer {
+      Some(ref vb) => Ok(Soft::slice_buffer(vb)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+
+  unsafe fn vertices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::VertexSliceMutRepr, TessMapError> {
+    match tess.vertex_buffer {
+      Some(ref mut vb) => Ok(Soft::slice_buffer_mut(vb)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+}
+
+unsafe impl<V, I, W> IndexSliceBackend<V, I, W, Interleaved> for Soft
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type IndexSliceRepr = BufferSlice<I>;
+  type IndexSliceMutRepr = BufferSliceMut<I>;
+
+  unsafe fn indices(tess: &mut Self::TessRepr) -> Result<Self::IndexSliceRepr, TessMapError> {
+    match tess.raw.index_state {
+      Some(ref state) => Ok(Soft::slice_buffer(&state.buffer)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+
+  unsafe fn indices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::IndexSliceMutRepr, TessMapError> {
+    match tess.raw.index_state {
+      Some(ref mut state) => Ok(Soft::slice_buffer_mut(&mut state.buffer)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+}
+
+unsafe impl<V, I, W> InstanceSliceBackend<V, I, W, Interleaved, W> for Soft
+where
+  V: TessVertexData<Interleaved, Data = Vec<V>>,
+  I: TessIndex,
+  W: TessVertexData<Interleaved, Data = Vec<W>>,
+{
+  type InstanceSliceRepr = BufferSlice<W>;
+  type InstanceSliceMutRepr = BufferSliceMut<W>;
+
+  unsafe fn instances(tess: &mut Self::TessRepr) -> Result<Self::InstanceSliceRepr, TessMapError> {
+    match tess.instance_buffer {
+      Some(ref vb) => Ok(Soft::slice_buffer(vb)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+
+  unsafe fn instances_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::InstanceSliceMutRepr, TessMapError> {
+    match tess.instance_buffer {
+      Some(ref mut vb) => Ok(Soft::slice_buffer_mut(vb)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct DeinterleavedTess<V, I, W>
+where
+  V: Vertex,
+  I: TessIndex,
+  W: Vertex,
+{
+  raw: TessRaw<I>,
+  vertex_buffers: Vec<Buffer<u8>>,
+  instance_buffers: Vec<Buffer<u8>>,
+  _phantom: PhantomData<*const (V, W)>,
+}
+
+unsafe impl<V, I, W> TessBackend<V, I, W, Deinterleaved> for Soft
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type TessRepr = DeinterleavedTess<V, I, W>;
+
+  unsafe fn build(
+    &mut self,
+    vertex_data: Option<V::Data>,
+    index_data: Vec<I>,
+    instance_data: Option<W::Data>,
+    params: TessParams<I>,
+  ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      restart_index,
+      usage,
+    } = params;
+
+    let vertex_buffers = build_deinterleaved_vertex_buffers(self, vertex_data, usage)?;
+
+    // in case of indexed render, create an index buffer
+    let index_state = build_index_buffer(self, index_data, restart_index, usage)?;
+
+    let instance_buffers = build_deinterleaved_vertex_buffers(self, instance_data, usage)?;
+
+    let raw = TessRaw::new(self, index_state, mode, vert_nb, inst_nb)?;
+
+    Ok(DeinterleavedTess {
+      raw,
+      vertex_buffers,
+      instance_buffers,
+      _phantom: PhantomData,
+    })
+  }
+
+  unsafe fn tess_vertices_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.vert_nb
+  }
+
+  unsafe fn tess_instances_nb(tess: &Self::TessRepr) -> usize {
+    tess.raw.inst_nb
+  }
+
+  unsafe fn render(
+    tess: &Self::TessRepr,
+    start_index: usize,
+    vert_nb: usize,
+    inst_nb: usize,
+  ) -> Result<(), TessError> {
+    let mut fetcher = VertexFetcher::default();
+
+    // each attribute lives in its own buffer
+    for (vb, desc) in tess.vertex_buffers.iter().zip(V::vertex_desc()) {
+      fetcher.sources.extend(attrib_sources(vb.bytes(), &[desc]));
+    }
+
+    for (ib, desc) in tess.instance_buffers.iter().zip(W::vertex_desc()) {
+      fetcher.sources.extend(attrib_sources(ib.bytes(), &[desc]));
+    }
+
+    tess.raw.render(start_index, vert_nb, inst_nb, &fetcher)
+  }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved_vertex_buffers::<V>(&mut tess.vertex_buffers, len, preserve)?;
+    tess.raw.vertices_resized(len);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved_vertex_buffers::<W>(&mut tess.instance_buffers, len, preserve)?;
+    tess.raw.inst_nb = len;
+
+    Ok(())
+  }
+}
+
+unsafe impl<V, I, W, T> VertexSliceBackend<V, I, W, Deinterleaved, T> for Soft
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>> + Deinterleave<T>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type VertexSliceRepr = BufferSlice<T, u8>;
+  type VertexSliceMutRepr = BufferSliceMut<T, u8>;
+
+  unsafe fn vertices(tess: &mut Self::TessRepr) -> Result<Self::VertexSliceRepr, TessMapError> {
+    if tess.vertex_buffers.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      let buffer = &tess.vertex_buffers[V::RANK];
+      let slice = Soft::slice_buffer(buffer)?.transmute();
+      Ok(slice)
+    }
+  }
+
+  unsafe fn vertices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::VertexSliceMutRepr, TessMapError> {
+    if tess.vertex_buffers.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      let buffer = &mut tess.vertex_buffers[V::RANK];
+      let slice = Soft::slice_buffer_mut(buffer)?.transmute();
+      Ok(slice)
+    }
+  }
+}
+
+unsafe impl<V, I, W> IndexSliceBackend<V, I, W, Deinterleaved> for Soft
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+{
+  type IndexSliceRepr = BufferSlice<I>;
+  type IndexSliceMutRepr = BufferSliceMut<I>;
+
+  unsafe fn indices(tess: &mut Self::TessRepr) -> Result<Self::IndexSliceRepr, TessMapError> {
+    match tess.raw.index_state {
+      Some(ref state) => Ok(Soft::slice_buffer(&state.buffer)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+
+  unsafe fn indices_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::IndexSliceMutRepr, TessMapError> {
+    match tess.raw.index_state {
+      Some(ref mut state) => Ok(Soft::slice_buffer_mut(&mut state.buffer)?),
+      None => Err(TessMapError::forbidden_attributeless_mapping()),
+    }
+  }
+}
+
+unsafe impl<V, I, W, T> InstanceSliceBackend<V, I, W, Deinterleaved, T> for Soft
+where
+  V: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>>,
+  I: TessIndex,
+  W: TessVertexData<Deinterleaved, Data = Vec<DeinterleavedData>> + Deinterleave<T>,
+{
+  type InstanceSliceRepr = BufferSlice<T, u8>;
+  type InstanceSliceMutRepr = BufferSliceMut<T, u8>;
+
+  unsafe fn instances(tess: &mut Self::TessRepr) -> Result<Self::InstanceSliceRepr, TessMapError> {
+    if tess.instance_buffers.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      let buffer = &tess.instance_buffers[W::RANK];
+      let slice = Soft::slice_buffer(buffer)?.transmute();
+      Ok(slice)
+    }
+  }
+
+  unsafe fn instances_mut(
+    tess: &mut Self::TessRepr,
+  ) -> Result<Self::InstanceSliceMutRepr, TessMapError> {
+    if tess.instance_buffers.is_empty() {
+      Err(TessMapError::forbidden_attributeless_mapping())
+    } else {
+      let buffer = &mut tess.instance_buffers[W::RANK];
+      let slice = Soft::slice_buffer_mut(buffer)?.transmute();
+      Ok(slice)
+    }
+  }
+}
+
+fn build_interleaved_vertex_buffer<V>(
+  soft: &mut Soft,
+  vertices: Option<Vec<V>>,
+  usage: BufferUsage,
+) -> Result<Option<Buffer<V>>, TessError>
+where
+  V: Vertex,
+{
+  match vertices {
+    Some(vertices) if !vertices.is_empty() => Ok(Some(unsafe { soft.from_vec(vertices, usage)? })),
+    _ => Ok(None),
+  }
+}
+
+fn build_deinterleaved_vertex_buffers(
+  soft: &mut Soft,
+  vertices: Option<Vec<DeinterleavedData>>,
+  usage: BufferUsage,
+) -> Result<Vec<Buffer<u8>>, TessError> {
+  match vertices {
+    Some(attributes) => attributes
+      .into_iter()
+      .map(|attribute| Ok(unsafe { soft.from_vec(attribute.into_vec(), usage)? }))
+      .collect(),
+
+    None => Ok(Vec::new()),
+  }
+}
+
+/// Resize the buffer of interleaved vertices or instances.
+///
+/// If the buffer doesn’t exist, it is created, unless the vertices are attributeless.
+unsafe fn resize_interleaved_vertex_buffer<V>(
+  buffer: &mut Option<Buffer<V>>,
+  len: usize,
+  preserve: PreserveContent,
+) -> Result<(), TessError>
+where
+  V: Vertex,
+{
+  match buffer {
+    // vertices are made of plain numbers, for which zero bits are valid values
+    Some(ref mut vb) => Soft::resize(vb, len, mem::zeroed(), preserve)?,
+
+    None if len > 0 && !V::vertex_desc().is_empty() => {
+      *buffer = Some(Buffer::from_vec(vec![mem::zeroed(); len]));
+    }
+
+    None => (),
+  }
+
+  Ok(())
+}
+
+/// Resize the buffers of deinterleaved vertices or instances.
+///
+/// If the buffers don’t exist, they are created, unless the vertices are attributeless.
+unsafe fn resize_deinterleaved_vertex_buffers<V>(
+  buffers: &mut Vec<Buffer<u8>>,
+  len: usize,
+  preserve: PreserveContent,
+) -> Result<(), TessError>
+where
+  V: Vertex,
+{
+  if buffers.is_empty() {
+    *buffers = V::vertex_desc()
+      .iter()
+      .map(|fmt| Buffer::from_vec(vec![0; len * attrib_weight(fmt)]))
+      .collect();
+  } else {
+    for (vb, fmt) in buffers.iter_mut().zip(V::vertex_desc()) {
+      Soft::resize(vb, len * attrib_weight(&fmt), 0, preserve)?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Turn a [`Vec`] of indices to an [`IndexedDrawState`].
+fn build_index_buffer<I>(
+  soft: &mut Soft,
+  data: Vec<I>,
+  restart_index: Option<I>,
+  usage: BufferUsage,
+) -> Result<Option<IndexedDrawState<I>>, TessError>
+where
+  I: TessIndex,
+{
+  if data.is_empty() {
+    return Ok(None);
+  }
+
+  Ok(Some(IndexedDrawState {
+    buffer: unsafe { soft.from_vec(data, usage)? },
+    restart_index,
+  }))
+}
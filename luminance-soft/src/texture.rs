@@ -0,0 +1,697 @@
+use luminance::backend::texture::{Texture as TextureBackend, TextureBase, TextureCompression};
+use luminance::depth_test::DepthComparison;
+use luminance::pixel::{CompressionFamily, Pixel, PixelFormat};
+use luminance::texture::{
+  Dim, Dimensionable, GenMipmaps, MagFilter, MinFilter, Sampler, TextureError, Wrap,
+};
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+use std::slice;
+
+use crate::pixel::{decode, encode, is_supported};
+use crate::Soft;
+
+/// A single mipmap level of a texture.
+#[derive(Debug)]
+pub(crate) struct Level {
+  pub(crate) size: [u32; 3],
+  pub(crate) texels: Vec<u8>,
+}
+
+/// CPU-side storage of a texture.
+///
+/// Every texture is stored as a 3D box of texels: layers of array textures and faces of cubemaps
+/// are stored along the third axis.
+#[derive(Debug)]
+pub(crate) struct TextureData {
+  pub(crate) dim: Dim,
+  pub(crate) pf: PixelFormat,
+  pub(crate) texel_size: usize,
+  pub(crate) levels: Vec<Level>,
+  pub(crate) sampler: Sampler,
+}
+
+impl TextureData {
+  pub(crate) fn new(
+    dim: Dim,
+    size: [u32; 3],
+    mipmaps: usize,
+    pf: PixelFormat,
+    sampler: Sampler,
+  ) -> Result<Self, TextureError> {
+    if !is_supported(pf) {
+      return Err(TextureError::unsupported_pixel_format(pf));
+    }
+
+    let texel_size = pf.format.size();
+    let levels = (0..mipmaps)
+      .map(|level| {
+        let size = level_size(dim, size, level);
+        let texels = vec![0; size.iter().product::<u32>() as usize * texel_size];
+        Level { size, texels }
+      })
+      .collect();
+
+    Ok(TextureData {
+      dim,
+      pf,
+      texel_size,
+      levels,
+      sampler,
+    })
+  }
+
+  /// Size of the base level.
+  pub(crate) fn size(&self) -> [u32; 3] {
+    self.levels[0].size
+  }
+
+  /// Byte offset of a texel in the base level.
+  fn texel_offset(&self, pos: [u32; 3]) -> usize {
+    self.level_texel_offset(0, pos)
+  }
+
+  /// Byte offset of a texel in a mipmap level.
+  fn level_texel_offset(&self, level: usize, [x, y, z]: [u32; 3]) -> usize {
+    let [w, h, _] = self.levels[level].size;
+    ((z * h + y) * w + x) as usize * self.texel_size
+  }
+
+  /// Write a region of texels into a mipmap level.
+  pub(crate) fn write_region(
+    &mut self,
+    level: usize,
+    offset: [u32; 3],
+    size: [u32; 3],
+    bytes: &[u8],
+  ) -> Result<(), TextureError> {
+    let expected_bytes = size.iter().product::<u32>() as usize * self.texel_size;
+
+    if bytes.len() < expected_bytes {
+      return Err(TextureError::not_enough_pixels(expected_bytes, bytes.len()));
+    }
+
+    let tex_size = self.levels[level].size;
+    if (0..3).any(|i| offset[i] + size[i] > tex_size[i]) {
+      return Err(TextureError::cannot_upload_texels(format!(
+        "region {:?} at {:?} is out of bounds of texture of size {:?}",
+        size, offset, tex_size
+      )));
+    }
+
+    let row_bytes = size[0] as usize * self.texel_size;
+
+    for z in 0..size[2] {
+      for y in 0..size[1] {
+        let src = ((z * size[1] + y) as usize) * row_bytes;
+        let dst = self.level_texel_offset(level, [offset[0], offset[1] + y, offset[2] + z]);
+        self.levels[level].texels[dst..dst + row_bytes]
+          .copy_from_slice(&bytes[src..src + row_bytes]);
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Read a region of texels of a mipmap level, which must be in bounds.
+  pub(crate) fn read_region(&self, level: usize, offset: [u32; 3], size: [u32; 3]) -> Vec<u8> {
+    let row_bytes = size[0] as usize * self.texel_size;
+    let mut bytes = Vec::with_capacity(row_bytes * (size[1] * size[2]) as usize);
+
+    for z in 0..size[2] {
+      for y in 0..size[1] {
+        let src = self.level_texel_offset(level, [offset[0], offset[1] + y, offset[2] + z]);
+        bytes.extend_from_slice(&self.levels[level].texels[src..src + row_bytes]);
+      }
+    }
+
+    bytes
+  }
+
+  /// Get the bytes of a texel of the base level.
+  pub(crate) fn texel(&self, pos: [u32; 3]) -> &[u8] {
+    let off = self.texel_offset(pos);
+    &self.levels[0].texels[off..off + self.texel_size]
+  }
+
+  /// Get the bytes of a texel of the base level, mutably.
+  pub(crate) fn texel_mut(&mut self, pos: [u32; 3]) -> &mut [u8] {
+    let off = self.texel_offset(pos);
+    &mut self.levels[0].texels[off..off + self.texel_size]
+  }
+
+  /// Read a texel of the base level as floating-point channels.
+  ///
+  /// Out of bounds texels are read as zero.
+  pub(crate) fn fetch(&self, pos: [i32; 3]) -> [f32; 4] {
+    self.fetch_level(0, pos).unwrap_or([0., 0., 0., 0.])
+  }
+
+  /// Read a texel of a mipmap level as floating-point channels, if in bounds.
+  fn fetch_level(&self, level: usize, [x, y, z]: [i32; 3]) -> Option<[f32; 4]> {
+    let [w, h, d] = self.levels[level].size;
+
+    if x < 0 || y < 0 || z < 0 || x as u32 >= w || y as u32 >= h || z as u32 >= d {
+      None
+    } else {
+      let off = self.level_texel_offset(level, [x as u32, y as u32, z as u32]);
+      Some(decode(
+        self.pf,
+        &self.levels[level].texels[off..off + self.texel_size],
+        true,
+      ))
+    }
+  }
+
+  /// Read a texel of a mipmap level while filtering.
+  ///
+  /// Texels are only out of bounds with [`Wrap::ClampToBorder`], and are read as the border color.
+  fn filter_texel(&self, level: usize, pos: [i32; 3]) -> [f32; 4] {
+    self
+      .fetch_level(level, pos)
+      .unwrap_or(self.sampler.border_color)
+  }
+
+  /// Base and max levels of the sampler, clamped to the allocated levels.
+  fn level_range(&self) -> (usize, usize) {
+    let last_level = self.levels.len() - 1;
+    let max_level = self
+      .sampler
+      .max_level
+      .map_or(last_level, |level| level.min(last_level));
+
+    (self.sampler.base_level.min(max_level), max_level)
+  }
+
+  /// Mipmap level sampled by [`TextureData::sample`].
+  ///
+  /// There are no derivatives to compute the level of detail from, so it is the LOD bias alone,
+  /// clamped to the LOD range of the sampler. Levels above the base level are only selected with
+  /// mipmap minification filters.
+  fn sampled_level(&self) -> usize {
+    let (base_level, max_level) = self.level_range();
+    let lod = self
+      .sampler
+      .lod_bias
+      .max(self.sampler.min_lod)
+      .min(self.sampler.max_lod);
+
+    match self.sampler.min_filter {
+      MinFilter::Nearest | MinFilter::Linear => base_level,
+      _ if lod <= 0. => base_level,
+      _ => (base_level + lod.round() as usize).min(max_level),
+    }
+  }
+
+  /// Sample the texture with its sampler.
+  ///
+  /// For cubemaps, `coords` is a direction vector. For array textures, the last used coordinate
+  /// is the layer. For depth textures with depth comparison enabled, the last coordinate is used
+  /// as the reference value.
+  pub(crate) fn sample(&self, coords: [f32; 4]) -> [f32; 4] {
+    let level = self.sampled_level();
+    let [_, h, d] = self.levels[level].size;
+
+    let texel = match self.dim {
+      Dim::Dim1 => self.filter(level, [coords[0], 0.5 / h as f32], 0),
+      Dim::Dim2 => self.filter(level, [coords[0], coords[1]], 0),
+      Dim::Dim3 => {
+        let z = wrap(
+          self.sampler.wrap_r,
+          (coords[2] * d as f32).floor() as i32,
+          d,
+        );
+        self.filter(level, [coords[0], coords[1]], z)
+      }
+      Dim::Dim1Array => {
+        let layer = (coords[1].round() as i32).max(0).min(h as i32 - 1);
+        self.filter(level, [coords[0], (layer as f32 + 0.5) / h as f32], 0)
+      }
+      Dim::Dim2Array => {
+        let layer = (coords[2].round() as i32).max(0).min(d as i32 - 1);
+        self.filter(level, [coords[0], coords[1]], layer)
+      }
+      Dim::Cubemap => {
+        let (face, uv) = cube_face([coords[0], coords[1], coords[2]]);
+        self.filter(level, uv, face)
+      }
+    };
+
+    match self.sampler.depth_comparison {
+      Some(comparison) => {
+        let reference = match self.dim {
+          Dim::Dim1 | Dim::Dim2 | Dim::Dim1Array => coords[2],
+          _ => coords[3],
+        };
+        let passed = compare_depth(comparison, reference, texel[0]) as u32 as f32;
+
+        [passed, passed, passed, 1.]
+      }
+
+      None => texel,
+    }
+  }
+
+  fn filter(&self, level: usize, [u, v]: [f32; 2], z: i32) -> [f32; 4] {
+    let [w, h, _] = self.levels[level].size;
+    let x = u * w as f32;
+    let y = v * h as f32;
+
+    match self.sampler.mag_filter {
+      MagFilter::Nearest => {
+        let x = wrap(self.sampler.wrap_s, x.floor() as i32, w);
+        let y = wrap(self.sampler.wrap_t, y.floor() as i32, h);
+        self.filter_texel(level, [x, y, z])
+      }
+
+      MagFilter::Linear => {
+        let x = x - 0.5;
+        let y = y - 0.5;
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+        let x0 = wrap(self.sampler.wrap_s, x.floor() as i32, w);
+        let x1 = wrap(self.sampler.wrap_s, x.floor() as i32 + 1, w);
+        let y0 = wrap(self.sampler.wrap_t, y.floor() as i32, h);
+        let y1 = wrap(self.sampler.wrap_t, y.floor() as i32 + 1, h);
+
+        let a = self.filter_texel(level, [x0, y0, z]);
+        let b = self.filter_texel(level, [x1, y0, z]);
+        let c = self.filter_texel(level, [x0, y1, z]);
+        let d = self.filter_texel(level, [x1, y1, z]);
+
+        let mut output = [0.; 4];
+        for i in 0..4 {
+          let top = a[i] + (b[i] - a[i]) * fx;
+          let bottom = c[i] + (d[i] - c[i]) * fx;
+          output[i] = top + (bottom - top) * fy;
+        }
+
+        output
+      }
+    }
+  }
+
+  /// Regenerate the mipmap levels from the base level of the sampler up to its max level, with a
+  /// box filter.
+  pub(crate) fn generate_mipmaps(&mut self) {
+    let (base_level, max_level) = self.level_range();
+
+    for level in base_level + 1..=max_level {
+      let (previous, current) = self.levels.split_at_mut(level);
+      let src = &previous[level - 1];
+      let dst = &mut current[0];
+      let scale = [
+        (src.size[0] / dst.size[0]).max(1),
+        (src.size[1] / dst.size[1]).max(1),
+        (src.size[2] / dst.size[2]).max(1),
+      ];
+
+      for z in 0..dst.size[2] {
+        for y in 0..dst.size[1] {
+          for x in 0..dst.size[0] {
+            let mut sum = [0.; 4];
+            let mut count = 0.;
+
+            for sz in 0..scale[2] {
+              for sy in 0..scale[1] {
+                for sx in 0..scale[0] {
+                  let pos = [x * scale[0] + sx, y * scale[1] + sy, z * scale[2] + sz];
+                  let off = ((pos[2] * src.size[1] + pos[1]) * src.size[0] + pos[0]) as usize
+                    * self.texel_size;
+                  let texel = decode(self.pf, &src.texels[off..off + self.texel_size], false);
+
+                  for i in 0..4 {
+                    sum[i] += texel[i];
+                  }
+
+                  count += 1.;
+                }
+              }
+            }
+
+            for c in &mut sum {
+              *c /= count;
+            }
+
+            let off = ((z * dst.size[1] + y) * dst.size[0] + x) as usize * self.texel_size;
+            encode(
+              self.pf,
+              sum,
+              false,
+              &mut dst.texels[off..off + self.texel_size],
+            );
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Size of a given mipmap level.
+fn level_size(dim: Dim, [w, h, d]: [u32; 3], level: usize) -> [u32; 3] {
+  let shrink = |x: u32| (x >> level).max(1);
+
+  match dim {
+    Dim::Dim1 | Dim::Dim1Array => [shrink(w), h, d],
+    Dim::Dim2 | Dim::Dim2Array | Dim::Cubemap => [shrink(w), shrink(h), d],
+    Dim::Dim3 => [shrink(w), shrink(h), shrink(d)],
+  }
+}
+
+/// Apply a wrapping mode to an integer texel coordinate.
+///
+/// Coordinates wrapped with [`Wrap::ClampToBorder`] are left out of bounds.
+fn wrap(mode: Wrap, x: i32, len: u32) -> i32 {
+  let len = len as i32;
+
+  match mode {
+    Wrap::ClampToEdge => x.max(0).min(len - 1),
+    Wrap::ClampToBorder => x,
+    Wrap::Repeat => x.rem_euclid(len),
+    Wrap::MirroredRepeat => {
+      let x = x.rem_euclid(2 * len);
+
+      if x >= len {
+        2 * len - 1 - x
+      } else {
+        x
+      }
+    }
+  }
+}
+
+/// Select the face of a cubemap and the coordinates on that face a direction points to.
+fn cube_face([x, y, z]: [f32; 3]) -> (i32, [f32; 2]) {
+  let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+
+  let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+    if x >= 0. {
+      (0, -z, -y, ax)
+    } else {
+      (1, z, -y, ax)
+    }
+  } else if ay >= az {
+    if y >= 0. {
+      (2, x, z, ay)
+    } else {
+      (3, x, -z, ay)
+    }
+  } else if z >= 0. {
+    (4, x, -y, az)
+  } else {
+    (5, -x, -y, az)
+  };
+
+  let ma = if ma == 0. { 1. } else { ma };
+
+  (face, [(sc / ma + 1.) * 0.5, (tc / ma + 1.) * 0.5])
+}
+
+pub(crate) fn compare_depth(comparison: DepthComparison, a: f32, b: f32) -> bool {
+  match comparison {
+    DepthComparison::Never => false,
+    DepthComparison::Always => true,
+    DepthComparison::Equal => a == b,
+    DepthComparison::NotEqual => a != b,
+    DepthComparison::Less => a < b,
+    DepthComparison::LessOrEqual => a <= b,
+    DepthComparison::Greater => a > b,
+    DepthComparison::GreaterOrEqual => a >= b,
+  }
+}
+
+/// Storage extent of a texture of a given size.
+pub(crate) fn extent<D>(size: D::Size) -> [u32; 3]
+where
+  D: Dimensionable,
+{
+  match D::dim() {
+    Dim::Dim1 => [D::width(size), 1, 1],
+    Dim::Dim2 | Dim::Dim1Array => [D::width(size), D::height(size), 1],
+    Dim::Dim3 | Dim::Dim2Array | Dim::Cubemap => [D::width(size), D::height(size), D::depth(size)],
+  }
+}
+
+/// Storage region covered by an offset and a size.
+///
+/// Cubemap regions only cover the face they are offset to.
+fn region<D>(offset: D::Offset, size: D::Size) -> ([u32; 3], [u32; 3])
+where
+  D: Dimensionable,
+{
+  match D::dim() {
+    Dim::Dim1 => ([D::x_offset(offset), 0, 0], extent::<D>(size)),
+    Dim::Dim2 | Dim::Dim1Array => (
+      [D::x_offset(offset), D::y_offset(offset), 0],
+      extent::<D>(size),
+    ),
+    Dim::Dim3 | Dim::Dim2Array => (
+      [
+        D::x_offset(offset),
+        D::y_offset(offset),
+        D::z_offset(offset),
+      ],
+      extent::<D>(size),
+    ),
+    Dim::Cubemap => (
+      [
+        D::x_offset(offset),
+        D::y_offset(offset),
+        D::z_offset(offset),
+      ],
+      [D::width(size), D::height(size), 1],
+    ),
+  }
+}
+
+unsafe fn as_bytes<T>(texels: &[T]) -> &[u8] {
+  slice::from_raw_parts(texels.as_ptr() as *const u8, mem::size_of_val(texels))
+}
+
+unsafe fn from_bytes<T>(bytes: &[u8]) -> Vec<T>
+where
+  T: Copy + Default,
+{
+  let mut texels = vec![T::default(); bytes.len() / mem::size_of::<T>()];
+
+  slice::from_raw_parts_mut(texels.as_mut_ptr() as *mut u8, bytes.len()).copy_from_slice(bytes);
+
+  texels
+}
+
+#[derive(Debug)]
+pub struct Texture {
+  pub(crate) data: Rc<RefCell<TextureData>>,
+}
+
+unsafe impl TextureBase for Soft {
+  type TextureRepr = Texture;
+}
+
+unsafe impl<D, P> TextureBackend<D, P> for Soft
+where
+  D: Dimensionable,
+  P: Pixel,
+{
+  unsafe fn new_texture(
+    &mut self,
+    size: D::Size,
+    mipmaps: usize,
+    sampler: Sampler,
+  ) -> Result<Self::TextureRepr, TextureError> {
+    let mipmaps = mipmaps + 1; // + 1 prevent having 0 mipmaps
+    let data = TextureData::new(
+      D::dim(),
+      extent::<D>(size),
+      mipmaps,
+      P::pixel_format(),
+      sampler,
+    )?;
+
+    Ok(Texture {
+      data: Rc::new(RefCell::new(data)),
+    })
+  }
+
+  unsafe fn mipmaps(texture: &Self::TextureRepr) -> usize {
+    texture.data.borrow().levels.len()
+  }
+
+  unsafe fn clear_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::upload_part(
+      texture,
+      gen_mipmaps,
+      offset,
+      size,
+      &vec![pixel; D::count(size)],
+    )
+  }
+
+  unsafe fn clear(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    pixel: P::Encoding,
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::clear_part(texture, gen_mipmaps, D::ZERO_OFFSET, size, pixel)
+  }
+
+  unsafe fn upload_part(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, _>(texture, gen_mipmaps, offset, size, texels)
+  }
+
+  unsafe fn upload(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::upload_part(texture, gen_mipmaps, D::ZERO_OFFSET, size, texels)
+  }
+
+  unsafe fn upload_part_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    upload_texels::<D, _>(texture, gen_mipmaps, offset, size, texels)
+  }
+
+  unsafe fn upload_raw(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    <Self as TextureBackend<D, P>>::upload_part_raw(
+      texture,
+      gen_mipmaps,
+      D::ZERO_OFFSET,
+      size,
+      texels,
+    )
+  }
+
+  unsafe fn get_raw_texels(
+    texture: &Self::TextureRepr,
+    _: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    Ok(from_bytes(&texture.data.borrow().levels[0].texels))
+  }
+
+  unsafe fn copy_from(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    src: &Self::TextureRepr,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError> {
+    let (dst_offset, _) = region::<D>(dst_offset, size);
+    let (src_offset, size) = region::<D>(src_offset, size);
+    let bytes = src.data.borrow().read_region(0, src_offset, size);
+    let mut data = texture.data.borrow_mut();
+
+    data.write_region(0, dst_offset, size, &bytes)?;
+
+    if gen_mipmaps == GenMipmaps::Yes {
+      data.generate_mipmaps();
+    }
+
+    Ok(())
+  }
+
+  unsafe fn upload_level_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    let (offset, size) = region::<D>(offset, size);
+    texture
+      .data
+      .borrow_mut()
+      .write_region(level, offset, size, as_bytes(texels))
+  }
+
+  unsafe fn upload_level_part_raw(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    let (offset, size) = region::<D>(offset, size);
+    texture
+      .data
+      .borrow_mut()
+      .write_region(level, offset, size, as_bytes(texels))
+  }
+
+  unsafe fn get_level_part_raw_texels(
+    texture: &Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let (offset, size) = region::<D>(offset, size);
+    Ok(from_bytes(
+      &texture.data.borrow().read_region(level, offset, size),
+    ))
+  }
+
+  unsafe fn generate_mipmaps(texture: &mut Self::TextureRepr) -> Result<(), TextureError> {
+    texture.data.borrow_mut().generate_mipmaps();
+    Ok(())
+  }
+}
+
+// compressed textures would have to be decompressed on the CPU to be sampled
+unsafe impl TextureCompression for Soft {
+  unsafe fn supports_compression(&mut self, _: CompressionFamily) -> bool {
+    false
+  }
+}
+
+unsafe fn upload_texels<D, T>(
+  texture: &mut Texture,
+  gen_mipmaps: GenMipmaps,
+  offset: D::Offset,
+  size: D::Size,
+  texels: &[T],
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let (offset, size) = region::<D>(offset, size);
+  let mut data = texture.data.borrow_mut();
+
+  data.write_region(0, offset, size, as_bytes(texels))?;
+
+  if gen_mipmaps == GenMipmaps::Yes {
+    data.generate_mipmaps();
+  }
+
+  Ok(())
+}
@@ -0,0 +1,499 @@
+use luminance::buffer::PreserveContent;
+use luminance::context::GraphicsContext as _;
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect};
+use luminance::pipeline::{PipelineError, PipelineState};
+use luminance::pixel::{Depth24Stencil8, Depth32F, NormRGBA8UI};
+use luminance::render_state::RenderState;
+use luminance::renderbuffer::Renderbuffer;
+use luminance::stencil::{StencilComparison, StencilOp, StencilOperations, StencilTest};
+use luminance::tess::{Mode, Tess};
+use luminance::texture::{Dim2, GenMipmaps, Sampler};
+use luminance::{Semantics, Vertex};
+use luminance_soft::{FragmentOutput, Soft, SoftSurface, VertexOutput};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Semantics)]
+pub enum Semantics {
+  #[sem(name = "position", repr = "[f32; 2]", wrapper = "VertexPosition")]
+  Position,
+  #[sem(name = "color", repr = "[f32; 3]", wrapper = "VertexColor")]
+  Color,
+}
+
+#[derive(Clone, Copy, Debug, Vertex)]
+#[repr(C)]
+#[vertex(sem = "Semantics")]
+struct Vertex {
+  pos: VertexPosition,
+  col: VertexColor,
+}
+
+fn vertex(pos: [f32; 2], col: [f32; 3]) -> Vertex {
+  Vertex::new(VertexPosition::new(pos), VertexColor::new(col))
+}
+
+fn surface() -> SoftSurface {
+  let mut surface = SoftSurface::new([4, 4]);
+
+  surface.backend().add_vertex_shader("vs", |input, _| {
+    let [x, y, _, _] = input.attrib(0);
+    let [r, g, b, _] = input.attrib(1);
+    VertexOutput::new([x, y, 0., 1.]).with_varyings(vec![r, g, b])
+  });
+
+  surface.backend().add_fragment_shader("fs", |input, _| {
+    let v = input.varyings();
+    FragmentOutput::color([v[0], v[1], v[2], 1.])
+  });
+
+  surface
+}
+
+fn render(surface: &mut SoftSurface, vertices: &[Vertex], indices: &[u8], mode: Mode) {
+  let tess = surface
+    .new_tess()
+    .set_vertices(vertices)
+    .set_indices(indices)
+    .set_mode(mode)
+    .build()
+    .unwrap();
+
+  render_tess(surface, &tess);
+}
+
+fn render_tess(surface: &mut SoftSurface, tess: &Tess<Soft, Vertex, u8>) {
+  let mut program = surface
+    .new_shader_program::<Semantics, (), ()>()
+    .from_strings("vs", None, None, "fs")
+    .unwrap()
+    .ignore_warnings();
+
+  let back_buffer = surface.back_buffer().unwrap();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &back_buffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(tess)
+          })
+        })
+      },
+    )
+    .into_result();
+
+  render.unwrap();
+}
+
+/// Texel at `(x, y)`, `y` going up.
+fn texel(texels: &[u8], x: usize, y: usize) -> [u8; 4] {
+  let i = (y * 4 + x) * 4;
+  [texels[i], texels[i + 1], texels[i + 2], texels[i + 3]]
+}
+
+#[test]
+fn render_quad() {
+  let mut surface = surface();
+  let red = [1., 0., 0.];
+  let vertices = [
+    vertex([-1., -1.], red),
+    vertex([0., -1.], red),
+    vertex([0., 1.], red),
+    vertex([-1., 1.], red),
+  ];
+
+  render(&mut surface, &vertices, &[0, 1, 2, 0, 2, 3], Mode::Triangle);
+
+  let texels = surface.back_buffer_texels();
+
+  for y in 0..4 {
+    for x in 0..4 {
+      let expected = if x < 2 {
+        [255, 0, 0, 255]
+      } else {
+        [0, 0, 0, 255]
+      };
+      assert_eq!(texel(&texels, x, y), expected, "texel ({}, {})", x, y);
+    }
+  }
+}
+
+#[test]
+fn render_interpolated_strip() {
+  let mut surface = surface();
+  let vertices = [
+    vertex([-1., -1.], [0., 0., 0.]),
+    vertex([1., -1.], [1., 0., 0.]),
+    vertex([-1., 1.], [0., 0., 0.]),
+    vertex([1., 1.], [1., 0., 0.]),
+  ];
+
+  render(&mut surface, &vertices, &[], Mode::TriangleStrip);
+
+  let texels = surface.back_buffer_texels();
+
+  // the red channel goes from 0 to 1 from left to right, sampled at the center of texels
+  for y in 0..4 {
+    for (x, &red) in [32, 96, 159, 223].iter().enumerate() {
+      assert_eq!(
+        texel(&texels, x, y),
+        [red, 0, 0, 255],
+        "texel ({}, {})",
+        x,
+        y
+      );
+    }
+  }
+}
+
+#[test]
+fn render_resized_strip() {
+  let mut surface = surface();
+  let red = [1., 0., 0.];
+  let vertices = [
+    vertex([-1., -1.], red),
+    vertex([1., -1.], red),
+    vertex([-1., 1.], red),
+  ];
+
+  let mut tess = surface
+    .new_tess()
+    .set_vertices(&vertices[..])
+    .set_indices(&[][..])
+    .set_mode(Mode::TriangleStrip)
+    .build()
+    .unwrap();
+
+  // grow the strip into a quad covering the whole surface
+  tess.resize_vertices(4, PreserveContent::Yes).unwrap();
+  assert_eq!(tess.vert_nb(), 4);
+  tess.vertices_mut().unwrap()[3] = vertex([1., 1.], red);
+
+  render_tess(&mut surface, &tess);
+
+  let texels = surface.back_buffer_texels();
+
+  assert_eq!(texels, [255, 0, 0, 255].repeat(4 * 4));
+}
+
+#[test]
+fn blit_stretched_framebuffer() {
+  let mut surface = surface();
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, ()>([2, 2], 0, Sampler::default())
+    .unwrap();
+  let colors = [
+    [255, 0, 0, 255],
+    [0, 255, 0, 255],
+    [0, 0, 255, 255],
+    [255, 255, 255, 255],
+  ];
+
+  framebuffer
+    .color_slot()
+    .upload_raw(GenMipmaps::No, &colors.concat())
+    .unwrap();
+
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  framebuffer
+    .blit_to(
+      &mut back_buffer,
+      BlitRect::whole([2, 2]),
+      BlitRect::whole([4, 4]),
+      BlitMask::COLOR,
+      BlitFilter::Nearest,
+    )
+    .unwrap();
+
+  let texels = surface.back_buffer_texels();
+
+  for y in 0..4 {
+    for x in 0..4 {
+      let expected = colors[y / 2 * 2 + x / 2];
+      assert_eq!(texel(&texels, x, y), expected, "texel ({}, {})", x, y);
+    }
+  }
+}
+
+#[test]
+fn copy_texture_region() {
+  let mut surface = surface();
+  let mut src = surface
+    .new_texture::<Dim2, NormRGBA8UI>([2, 2], 0, Sampler::default())
+    .unwrap();
+  let mut dst = surface
+    .new_texture::<Dim2, NormRGBA8UI>([2, 1], 0, Sampler::default())
+    .unwrap();
+
+  src
+    .upload_raw(
+      GenMipmaps::No,
+      &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+    )
+    .unwrap();
+  dst
+    .copy_from(GenMipmaps::No, &src, [0, 1], [0, 0], [2, 1])
+    .unwrap();
+
+  assert_eq!(
+    dst.get_raw_texels().unwrap(),
+    [9, 10, 11, 12, 13, 14, 15, 16]
+  );
+}
+
+#[test]
+fn generate_mipmaps_from_base_level() {
+  let mut surface = surface();
+  let mut texture = surface
+    .new_texture::<Dim2, NormRGBA8UI>([2, 2], 1, Sampler::default())
+    .unwrap();
+
+  texture
+    .upload_level_raw(
+      0,
+      &[
+        0, 0, 0, 0, 40, 40, 40, 40, 80, 80, 80, 80, 120, 120, 120, 120,
+      ],
+    )
+    .unwrap();
+  texture.generate_mipmaps().unwrap();
+
+  assert_eq!(texture.get_level_raw_texels(1).unwrap(), [60, 60, 60, 60]);
+
+  // levels below the base level are left untouched
+  let sampler = Sampler {
+    base_level: 1,
+    ..Sampler::default()
+  };
+  let mut texture = surface
+    .new_texture::<Dim2, NormRGBA8UI>([4, 4], 2, sampler)
+    .unwrap();
+
+  texture.upload_level_raw(1, &[200; 2 * 2 * 4]).unwrap();
+  texture.generate_mipmaps().unwrap();
+
+  assert_eq!(texture.get_level_raw_texels(0).unwrap(), [0; 4 * 4 * 4]);
+  assert_eq!(texture.get_level_raw_texels(2).unwrap(), [200; 4]);
+}
+
+#[test]
+fn render_multisample_framebuffer() {
+  let mut surface = surface();
+  let mut framebuffer = surface
+    .new_multisample_framebuffer::<NormRGBA8UI, ()>([4, 4], 4, 0, Sampler::default())
+    .unwrap();
+  let red = [1., 0., 0.];
+  let tess = surface
+    .new_tess()
+    .set_vertices([
+      vertex([-1., -1.], red),
+      vertex([1., -1.], red),
+      vertex([-1., 1.], red),
+    ])
+    .set_mode(Mode::Triangle)
+    .build()
+    .unwrap();
+  let mut program = surface
+    .new_shader_program::<Semantics, (), ()>()
+    .from_strings("vs", None, None, "fs")
+    .unwrap()
+    .ignore_warnings();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+
+  // pixels fully inside or outside of the triangle get a plain color
+  assert_eq!(texel(&texels, 0, 0), [255, 0, 0, 255]);
+  assert_eq!(texel(&texels, 3, 3), [0, 0, 0, 255]);
+
+  // the hypotenuse covers half the samples of the pixels it crosses
+  for (x, y) in [(0, 3), (1, 2), (2, 1), (3, 0)] {
+    let [r, g, b, a] = texel(&texels, x, y);
+    assert!((127..=128).contains(&r), "texel ({}, {}): {}", x, y, r);
+    assert_eq!([g, b, a], [0, 0, 255]);
+  }
+}
+
+#[test]
+fn reject_unsupported_samples() {
+  let mut surface = surface();
+
+  for samples in [0, 16] {
+    assert!(surface
+      .new_multisample_framebuffer::<NormRGBA8UI, ()>([4, 4], samples, 0, Sampler::default())
+      .is_err());
+  }
+}
+
+#[test]
+fn render_to_renderbuffers() {
+  let mut surface = surface();
+  let framebuffer = surface
+    .new_framebuffer::<Dim2, Renderbuffer<Soft, NormRGBA8UI>, Renderbuffer<Soft, Depth32F>>(
+      [4, 4],
+      0,
+      Sampler::default(),
+    )
+    .unwrap();
+  let red = [1., 0., 0.];
+  let tess = surface
+    .new_tess()
+    .set_vertices([
+      vertex([-1., -1.], red),
+      vertex([0., -1.], red),
+      vertex([0., 1.], red),
+      vertex([-1., 1.], red),
+    ])
+    .set_indices(&[0u8, 1, 2, 0, 2, 3][..])
+    .set_mode(Mode::Triangle)
+    .build()
+    .unwrap();
+  let mut program = surface
+    .new_shader_program::<Semantics, (), ()>()
+    .from_strings("vs", None, None, "fs")
+    .unwrap()
+    .ignore_warnings();
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default(),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&RenderState::default(), |mut tess_gate| {
+            tess_gate.render(&tess)
+          })
+        })
+      },
+    )
+    .into_result();
+
+  render.unwrap();
+
+  // renderbuffers cannot be read back, but they can be blitted
+  let mut back_buffer = surface.back_buffer().unwrap();
+
+  framebuffer
+    .blit_to(
+      &mut back_buffer,
+      BlitRect::whole([4, 4]),
+      BlitRect::whole([4, 4]),
+      BlitMask::COLOR,
+      BlitFilter::Nearest,
+    )
+    .unwrap();
+
+  let texels = surface.back_buffer_texels();
+
+  for y in 0..4 {
+    for x in 0..4 {
+      let expected = if x < 2 {
+        [255, 0, 0, 255]
+      } else {
+        [0, 0, 0, 255]
+      };
+      assert_eq!(texel(&texels, x, y), expected, "texel ({}, {})", x, y);
+    }
+  }
+}
+
+#[test]
+fn render_with_stencil() {
+  let mut surface = surface();
+  let mut framebuffer = surface
+    .new_framebuffer::<Dim2, NormRGBA8UI, Depth24Stencil8>([4, 4], 0, Sampler::default())
+    .unwrap();
+  let quad = |surface: &mut SoftSurface, x_max: f32, col: [f32; 3]| {
+    surface
+      .new_tess()
+      .set_vertices([
+        vertex([-1., -1.], col),
+        vertex([x_max, -1.], col),
+        vertex([x_max, 1.], col),
+        vertex([-1., 1.], col),
+      ])
+      .set_indices(&[0u8, 1, 2, 0, 2, 3][..])
+      .set_mode(Mode::Triangle)
+      .build()
+      .unwrap()
+  };
+  let mask = quad(&mut surface, 0., [1., 0., 0.]);
+  let fullscreen = quad(&mut surface, 1., [0., 1., 0.]);
+  let mut program = surface
+    .new_shader_program::<Semantics, (), ()>()
+    .from_strings("vs", None, None, "fs")
+    .unwrap()
+    .ignore_warnings();
+
+  // write 1 to the stencil of the left half, then draw only where the stencil isn’t 1
+  let mask_state = RenderState::default()
+    .set_depth_test(None)
+    .set_stencil_test(StencilTest::new(StencilComparison::Always, 1, 0xFF))
+    .set_stencil_operations(StencilOperations::new(
+      StencilOp::Keep,
+      StencilOp::Keep,
+      StencilOp::Replace,
+    ));
+  let outline_state = RenderState::default()
+    .set_depth_test(None)
+    .set_stencil_test(StencilTest::new(StencilComparison::NotEqual, 1, 0xFF));
+
+  let render: Result<(), PipelineError> = surface
+    .new_pipeline_gate()
+    .pipeline(
+      &framebuffer,
+      &PipelineState::default().set_clear_stencil(2),
+      |_, mut shd_gate| {
+        shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+          rdr_gate.render(&mask_state, |mut tess_gate| tess_gate.render(&mask))?;
+          rdr_gate.render(&outline_state, |mut tess_gate| {
+            tess_gate.render(&fullscreen)
+          })
+        })
+      },
+    )
+    .into_result();
+
+  render.unwrap();
+
+  let texels = framebuffer.color_slot().get_raw_texels().unwrap();
+  let depth_stencil = framebuffer.depth_slot().get_raw_texels().unwrap();
+
+  for y in 0..4 {
+    for x in 0..4 {
+      let (expected, stencil) = if x < 2 {
+        ([255, 0, 0, 255], 1)
+      } else {
+        ([0, 255, 0, 255], 2)
+      };
+      assert_eq!(texel(&texels, x, y), expected, "texel ({}, {})", x, y);
+      assert_eq!(
+        depth_stencil[y * 4 + x] & 0xFF,
+        stencil,
+        "stencil ({}, {})",
+        x,
+        y
+      );
+    }
+  }
+}
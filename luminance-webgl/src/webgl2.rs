@@ -2,9 +2,13 @@
 
 mod array_buffer;
 pub mod buffer;
+pub mod fence;
 pub mod framebuffer;
 pub mod pipeline;
 pub mod pixel;
+pub mod query;
+pub mod readback;
+pub mod renderbuffer;
 pub mod shader;
 pub mod state;
 pub mod tess;
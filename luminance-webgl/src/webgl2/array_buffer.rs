@@ -1,6 +1,8 @@
 //! A collection of utilities used to perform conversion between immutable slices and JavaScript’s
 //! various array types.
 
+use luminance::pixel::Half;
+
 /// Unsafe coercion to a `js_sys::Object` for immutable slices.
 ///
 /// This trait provides the [`into_array_buffer`] method, which is an unsafe operation, as
@@ -25,14 +27,14 @@ macro_rules! impl_IntoArrayBuffer {
       }
     }
 
-    impl_tuple_IntoArrayBuffer!($t, ($t, $t), 2, $buffer);
-    impl_tuple_IntoArrayBuffer!($t, ($t, $t, $t), 3, $buffer);
-    impl_tuple_IntoArrayBuffer!($t, ($t, $t, $t, $t), 4, $buffer);
+    impl_tuple_IntoArrayBuffer!($t, ($t, $t), 2);
+    impl_tuple_IntoArrayBuffer!($t, ($t, $t, $t), 3);
+    impl_tuple_IntoArrayBuffer!($t, ($t, $t, $t, $t), 4);
   };
 }
 
 macro_rules! impl_tuple_IntoArrayBuffer {
-  ($t:ty, $tuple:ty, $n:literal, $buffer:ty) => {
+  ($t:ty, $tuple:ty, $n:literal) => {
     // statically assert that [T; 3] has the same size as (T, T, T)
     // this checks that the from_raw_parts cast has the correct value for $n and $tuple
     const _: fn() = || {
@@ -44,7 +46,7 @@ macro_rules! impl_tuple_IntoArrayBuffer {
         let slice: &[$t] =
           std::slice::from_raw_parts(texels.as_ptr() as *const $t, texels.len() * $n);
 
-        <$buffer>::view(slice).into()
+        <$t>::into_array_buffer(slice)
       }
     }
   };
@@ -59,3 +61,15 @@ impl_IntoArrayBuffer!(i32, js_sys::Int32Array);
 
 impl_IntoArrayBuffer!(f32, js_sys::Float32Array);
 impl_IntoArrayBuffer!(f64, js_sys::Float64Array);
+
+// half-floats are viewed as their raw bits
+impl IntoArrayBuffer for Half {
+  unsafe fn into_array_buffer(texels: &[Self]) -> js_sys::Object {
+    let bits: &[u16] = std::slice::from_raw_parts(texels.as_ptr() as *const u16, texels.len());
+    u16::into_array_buffer(bits)
+  }
+}
+
+impl_tuple_IntoArrayBuffer!(Half, (Half, Half), 2);
+impl_tuple_IntoArrayBuffer!(Half, (Half, Half, Half), 3);
+impl_tuple_IntoArrayBuffer!(Half, (Half, Half, Half, Half), 4);
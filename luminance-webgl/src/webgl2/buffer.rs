@@ -9,10 +9,14 @@ use std::rc::Rc;
 use std::slice;
 use web_sys::{WebGl2RenderingContext, WebGlBuffer};
 
+use crate::webgl2::readback::Readback;
 use crate::webgl2::state::{Bind, WebGL2State};
 use crate::webgl2::WebGL2;
-use luminance::backend::buffer::{Buffer as BufferBackend, BufferSlice as BufferSliceBackend};
-use luminance::buffer::BufferError;
+use luminance::backend::buffer::{
+  Buffer as BufferBackend, BufferReadback as BufferReadbackBackend,
+  BufferSlice as BufferSliceBackend,
+};
+use luminance::buffer::{BufferError, BufferUsage, PreserveContent};
 
 /// Wrapped WebGL buffer.
 ///
@@ -38,6 +42,8 @@ pub struct Buffer<T> {
   /// A cached version of the GPU buffer; emulate persistent mapping.
   pub(crate) buf: Vec<T>,
   gl_buf: BufferWrapper,
+  /// Usage the buffer was created with; required to re-allocate it.
+  usage: BufferUsage,
 }
 
 impl<T> Buffer<T> {
@@ -46,7 +52,13 @@ impl<T> Buffer<T> {
   /// The `target` parameter allows to create the buffer with
   /// [`WebGl2RenderingContext::ARRAY_BUFFER`] or [`WebGl2RenderingContext::ELEMENT_ARRAY_BUFFER`]
   /// directly, as WebGL2 doesn’t support changing the target type after the buffer is created.
-  fn new(webgl2: &mut WebGL2, len: usize, clear_value: T, target: u32) -> Result<Self, BufferError>
+  fn new(
+    webgl2: &mut WebGL2,
+    len: usize,
+    clear_value: T,
+    target: u32,
+    usage: BufferUsage,
+  ) -> Result<Self, BufferError>
   where
     T: Copy,
   {
@@ -66,22 +78,23 @@ impl<T> Buffer<T> {
     let bytes = mem::size_of::<T>() * len;
     state
       .ctx
-      .buffer_data_with_i32(target, bytes as i32, WebGl2RenderingContext::STREAM_DRAW);
+      .buffer_data_with_i32(target, bytes as i32, webgl_usage(usage));
 
     let gl_buf = BufferWrapper {
       handle,
       state: webgl2.state.clone(),
     };
 
-    Ok(Buffer { buf, gl_buf })
+    Ok(Buffer { buf, gl_buf, usage })
   }
 
   pub(crate) fn from_vec(
-    webgl2: &mut WebGL2,
+    webgl2_state: &Rc<RefCell<WebGL2State>>,
     vec: Vec<T>,
     target: u32,
+    usage: BufferUsage,
   ) -> Result<Self, BufferError> {
-    let mut state = webgl2.state.borrow_mut();
+    let mut state = webgl2_state.borrow_mut();
     let len = vec.len();
 
     let handle = state
@@ -94,14 +107,18 @@ impl<T> Buffer<T> {
     let data = unsafe { slice::from_raw_parts(vec.as_ptr() as *const _, bytes) };
     state
       .ctx
-      .buffer_data_with_u8_array(target, data, WebGl2RenderingContext::STREAM_DRAW);
+      .buffer_data_with_u8_array(target, data, webgl_usage(usage));
 
     let gl_buf = BufferWrapper {
       handle,
-      state: webgl2.state.clone(),
+      state: webgl2_state.clone(),
     };
 
-    Ok(Buffer { gl_buf, buf: vec })
+    Ok(Buffer {
+      gl_buf,
+      buf: vec,
+      usage,
+    })
   }
 
   /// Bind a buffer to a given state regarding the input target.
@@ -132,7 +149,11 @@ where
 {
   type BufferRepr = Buffer<T>;
 
-  unsafe fn new_buffer(&mut self, len: usize) -> Result<Self::BufferRepr, BufferError>
+  unsafe fn new_buffer(
+    &mut self,
+    len: usize,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>
   where
     T: Default,
   {
@@ -141,6 +162,7 @@ where
       len,
       T::default(),
       WebGl2RenderingContext::ARRAY_BUFFER,
+      usage,
     )
   }
 
@@ -148,12 +170,32 @@ where
     buffer.buf.len()
   }
 
-  unsafe fn from_vec(&mut self, vec: Vec<T>) -> Result<Self::BufferRepr, BufferError> {
-    Buffer::from_vec(self, vec, WebGl2RenderingContext::ARRAY_BUFFER)
+  unsafe fn from_vec(
+    &mut self,
+    vec: Vec<T>,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Buffer::from_vec(
+      &self.state,
+      vec,
+      WebGl2RenderingContext::ARRAY_BUFFER,
+      usage,
+    )
   }
 
-  unsafe fn repeat(&mut self, len: usize, value: T) -> Result<Self::BufferRepr, BufferError> {
-    Buffer::<T>::new(self, len, value, WebGl2RenderingContext::ARRAY_BUFFER)
+  unsafe fn repeat(
+    &mut self,
+    len: usize,
+    value: T,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError> {
+    Buffer::<T>::new(
+      self,
+      len,
+      value,
+      WebGl2RenderingContext::ARRAY_BUFFER,
+      usage,
+    )
   }
 
   unsafe fn at(buffer: &Self::BufferRepr, i: usize) -> Option<T> {
@@ -175,13 +217,13 @@ where
 
       // then update the WebGL buffer
       let mut state = buffer.gl_buf.state.borrow_mut();
-      let bytes = mem::size_of::<T>() * buffer_len;
+      let bytes = mem::size_of::<T>();
       update_webgl_buffer(
         &mut state,
         &buffer.gl_buf.handle,
-        buffer.buf.as_ptr() as *const u8,
+        buffer.buf[i..].as_ptr() as *const u8,
         bytes,
-        i,
+        i * bytes,
       );
 
       Ok(())
@@ -220,6 +262,70 @@ where
     Ok(())
   }
 
+  unsafe fn write_range(
+    buffer: &mut Self::BufferRepr,
+    offset: usize,
+    values: &[T],
+  ) -> Result<(), BufferError> {
+    let range = offset..offset + values.len();
+    buffer.buf[range.clone()].copy_from_slice(values);
+
+    // only upload the written items
+    let mut state = buffer.gl_buf.state.borrow_mut();
+    let item_bytes = mem::size_of::<T>();
+    update_webgl_buffer(
+      &mut state,
+      &buffer.gl_buf.handle,
+      buffer.buf[range].as_ptr() as *const u8,
+      mem::size_of_val(values),
+      offset * item_bytes,
+    );
+
+    Ok(())
+  }
+
+  unsafe fn read_range(
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Vec<T>, BufferError> {
+    // the cache holds everything written to the buffer
+    Ok(buffer.buf[start..start + len].to_vec())
+  }
+
+  unsafe fn copy_from(
+    buffer: &mut Self::BufferRepr,
+    src: &Self::BufferRepr,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  ) -> Result<(), BufferError> {
+    let item_bytes = mem::size_of::<T>();
+
+    {
+      let state = buffer.gl_buf.state.borrow();
+
+      state
+        .ctx
+        .bind_buffer(WebGl2RenderingContext::COPY_READ_BUFFER, Some(src.handle()));
+      state.ctx.bind_buffer(
+        WebGl2RenderingContext::COPY_WRITE_BUFFER,
+        Some(buffer.handle()),
+      );
+      state.ctx.copy_buffer_sub_data_with_i32_and_i32_and_i32(
+        WebGl2RenderingContext::COPY_READ_BUFFER,
+        WebGl2RenderingContext::COPY_WRITE_BUFFER,
+        (src_start * item_bytes) as i32,
+        (dst_offset * item_bytes) as i32,
+        (len * item_bytes) as i32,
+      );
+    }
+
+    buffer.buf[dst_offset..dst_offset + len].copy_from_slice(&src.buf[src_start..src_start + len]);
+
+    Ok(())
+  }
+
   unsafe fn clear(buffer: &mut Self::BufferRepr, x: T) -> Result<(), BufferError> {
     // copy the value everywhere in the buffer, then simply update the WebGL buffer
     for item in &mut buffer.buf {
@@ -238,6 +344,74 @@ where
 
     Ok(())
   }
+
+  unsafe fn resize(
+    buffer: &mut Self::BufferRepr,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError> {
+    if preserve == PreserveContent::No {
+      buffer.buf.clear();
+    }
+
+    buffer.buf.resize(len, value);
+
+    // re-allocate the storage of the same buffer, so that vertex arrays using it remain valid; the
+    // copy target is used as it accepts both vertex and index buffers
+    let state = buffer.gl_buf.state.borrow();
+    let bytes = mem::size_of_val(buffer.buf.as_slice());
+    let data = slice::from_raw_parts(buffer.buf.as_ptr() as *const u8, bytes);
+
+    state.ctx.bind_buffer(
+      WebGl2RenderingContext::COPY_WRITE_BUFFER,
+      Some(buffer.handle()),
+    );
+    state.ctx.buffer_data_with_u8_array(
+      WebGl2RenderingContext::COPY_WRITE_BUFFER,
+      data,
+      webgl_usage(buffer.usage),
+    );
+
+    Ok(())
+  }
+}
+
+unsafe impl<T> BufferReadbackBackend<T> for WebGL2
+where
+  T: Copy,
+{
+  unsafe fn whole_async(buffer: &Self::BufferRepr) -> Result<Self::ReadbackRepr, BufferError> {
+    let len = buffer.buf.len();
+    let state = &buffer.gl_buf.state;
+
+    // the state must not be borrowed anymore when inserting the fence
+    let staging = {
+      let mut state = state.borrow_mut();
+      let staging = Readback::<T>::new_staging_buffer(
+        &mut state,
+        WebGl2RenderingContext::COPY_WRITE_BUFFER,
+        len,
+      )
+      .ok_or_else(BufferError::cannot_create)?;
+
+      state.ctx.bind_buffer(
+        WebGl2RenderingContext::COPY_READ_BUFFER,
+        Some(buffer.handle()),
+      );
+      state.ctx.copy_buffer_sub_data_with_i32_and_i32_and_i32(
+        WebGl2RenderingContext::COPY_READ_BUFFER,
+        WebGl2RenderingContext::COPY_WRITE_BUFFER,
+        0,
+        0,
+        (len * mem::size_of::<T>()) as i32,
+      );
+
+      staging
+    };
+
+    Readback::from_handle(state, staging, len).map_err(|_| BufferError::cannot_create())
+  }
 }
 
 pub struct BufferSlice<T> {
@@ -389,3 +563,15 @@ fn update_webgl_buffer(
       0,
     );
 }
+
+/// Get the WebGL usage hint of a [`BufferUsage`].
+///
+/// WebGL doesn’t support persistent mapping, so persistent buffers are hinted as dynamic ones.
+fn webgl_usage(usage: BufferUsage) -> u32 {
+  match usage {
+    BufferUsage::Static => WebGl2RenderingContext::STATIC_DRAW,
+    BufferUsage::Dynamic | BufferUsage::Persistent => WebGl2RenderingContext::DYNAMIC_DRAW,
+    BufferUsage::Stream => WebGl2RenderingContext::STREAM_DRAW,
+    BufferUsage::ReadBack => WebGl2RenderingContext::DYNAMIC_READ,
+  }
+}
@@ -0,0 +1,71 @@
+//! WebGL2 fence implementation.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{WebGl2RenderingContext, WebGlSync};
+
+use crate::webgl2::state::WebGL2State;
+use crate::webgl2::WebGL2;
+use luminance::backend::fence::Fence as FenceBackend;
+use luminance::fence::FenceError;
+
+/// WebGL fence.
+#[derive(Debug)]
+pub struct Fence {
+  sync: WebGlSync,
+  // once signaled, a sync object stays signaled
+  signaled: bool,
+  state: Rc<RefCell<WebGL2State>>,
+}
+
+impl Drop for Fence {
+  fn drop(&mut self) {
+    self.state.borrow().ctx.delete_sync(Some(&self.sync));
+  }
+}
+
+impl Fence {
+  /// Insert a fence after all the commands issued so far.
+  pub(crate) fn new(state: &Rc<RefCell<WebGL2State>>) -> Result<Self, FenceError> {
+    let sync = state
+      .borrow()
+      .ctx
+      .fence_sync(WebGl2RenderingContext::SYNC_GPU_COMMANDS_COMPLETE, 0)
+      .ok_or_else(FenceError::cannot_create)?;
+
+    Ok(Fence {
+      sync,
+      signaled: false,
+      state: state.clone(),
+    })
+  }
+
+  /// Whether the GPU has executed all the commands issued before the fence, without blocking.
+  pub(crate) fn is_signaled(&mut self) -> bool {
+    if !self.signaled {
+      // flush the commands, so that the fence eventually gets signaled even if nothing else does
+      let status = self.state.borrow().ctx.client_wait_sync_with_u32(
+        &self.sync,
+        WebGl2RenderingContext::SYNC_FLUSH_COMMANDS_BIT,
+        0,
+      );
+
+      self.signaled = status == WebGl2RenderingContext::ALREADY_SIGNALED
+        || status == WebGl2RenderingContext::CONDITION_SATISFIED;
+    }
+
+    self.signaled
+  }
+}
+
+unsafe impl FenceBackend for WebGL2 {
+  type FenceRepr = Fence;
+
+  unsafe fn new_fence(&mut self) -> Result<Self::FenceRepr, FenceError> {
+    Fence::new(&self.state)
+  }
+
+  unsafe fn fence_signaled(fence: &mut Self::FenceRepr) -> bool {
+    fence.is_signaled()
+  }
+}
@@ -3,14 +3,18 @@
 use js_sys::Uint32Array;
 use luminance::backend::color_slot::ColorSlot;
 use luminance::backend::depth_slot::DepthSlot;
-use luminance::backend::framebuffer::{Framebuffer as FramebufferBackend, FramebufferBackBuffer};
-use luminance::framebuffer::{FramebufferError, IncompleteReason};
+use luminance::backend::framebuffer::{
+  Framebuffer as FramebufferBackend, FramebufferBackBuffer, FramebufferBlit, FramebufferMultisample,
+};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError, IncompleteReason};
+use luminance::pixel::PixelFormat;
 use luminance::texture::{Dim2, Dimensionable, Sampler};
 use std::cell::RefCell;
 use std::rc::Rc;
 use web_sys::{WebGl2RenderingContext, WebGlFramebuffer, WebGlRenderbuffer};
 
-use crate::webgl2::state::WebGL2State;
+use crate::webgl2::pixel::webgl_pixel_format;
+use crate::webgl2::state::{ScissorState, WebGL2State};
 use crate::webgl2::WebGL2;
 
 pub struct Framebuffer<D>
@@ -20,6 +24,9 @@ where
   // None is the default framebuffer…
   pub(crate) handle: Option<WebGlFramebuffer>,
   renderbuffer: Option<WebGlRenderbuffer>,
+  multisample: Option<Multisample>,
+  // attachment point of the depth slot, which depends on whether it has a stencil channel
+  pub(crate) depth_attachment: u32,
   pub(crate) size: D::Size,
   state: Rc<RefCell<WebGL2State>>,
 }
@@ -36,56 +43,191 @@ where
   }
 }
 
-unsafe impl<D> FramebufferBackend<D> for WebGL2
+impl<D> Framebuffer<D>
 where
   D: Dimensionable,
 {
-  type FramebufferRepr = Framebuffer<D>;
+  /// Handle of the framebuffer pipelines render to.
+  pub(crate) fn draw_handle(&self) -> Option<&WebGlFramebuffer> {
+    match self.multisample {
+      Some(ref multisample) => Some(&multisample.handle),
+      None => self.handle.as_ref(),
+    }
+  }
 
-  unsafe fn new_framebuffer<CS, DS>(
-    &mut self,
-    size: D::Size,
-    _: usize,
-    _: &Sampler,
-  ) -> Result<Self::FramebufferRepr, FramebufferError>
-  where
-    CS: ColorSlot<Self, D>,
-    DS: DepthSlot<Self, D>,
-  {
-    let color_formats = CS::color_formats();
-    let depth_format = DS::depth_format();
-    let mut depth_renderbuffer = None;
+  /// Resolve the multisampled storage, if any, into the attached textures.
+  pub(crate) unsafe fn resolve(&self) {
+    let multisample = match self.multisample {
+      Some(ref multisample) => multisample,
+      None => return,
+    };
 
     let mut state = self.state.borrow_mut();
+    let (width, height) = (D::width(self.size) as i32, D::height(self.size) as i32);
+
+    state.bind_draw_framebuffer(self.handle.as_ref());
+    state.bind_read_framebuffer(Some(&multisample.handle));
+
+    // blits are clipped by the scissor test
+    state.set_scissor_state(ScissorState::Off);
+
+    let resolve_blit = |state: &WebGL2State, mask| {
+      state.ctx.blit_framebuffer(
+        0,
+        0,
+        width,
+        height,
+        0,
+        0,
+        width,
+        height,
+        mask,
+        WebGl2RenderingContext::NEAREST,
+      );
+    };
+
+    // only the read buffer is blitted, to all the draw buffers; resolve the color attachments one
+    // by one
+    let color_buffers: Vec<_> = (WebGl2RenderingContext::COLOR_ATTACHMENT0
+      ..WebGl2RenderingContext::COLOR_ATTACHMENT0 + multisample.color_nb as u32)
+      .collect();
+
+    for &color_buffer in &color_buffers {
+      let draw_buffers: Vec<_> = color_buffers
+        .iter()
+        .map(|&b| {
+          if b == color_buffer {
+            b
+          } else {
+            WebGl2RenderingContext::NONE
+          }
+        })
+        .collect();
+
+      state.ctx.read_buffer(color_buffer);
+      state
+        .ctx
+        .draw_buffers(Uint32Array::view(&draw_buffers).as_ref());
+      resolve_blit(&state, WebGl2RenderingContext::COLOR_BUFFER_BIT);
+    }
+
+    if !color_buffers.is_empty() {
+      state
+        .ctx
+        .read_buffer(WebGl2RenderingContext::COLOR_ATTACHMENT0);
+      state
+        .ctx
+        .draw_buffers(Uint32Array::view(&color_buffers).as_ref());
+    }
+
+    if multisample.resolve_depth {
+      resolve_blit(
+        &state,
+        WebGl2RenderingContext::DEPTH_BUFFER_BIT | WebGl2RenderingContext::STENCIL_BUFFER_BIT,
+      );
+    }
+  }
+}
+
+/// Multisampled storage of a framebuffer.
+///
+/// Pipelines render to it instead of the attached textures, which receive the resolved samples
+/// once the pipelines are done.
+struct Multisample {
+  handle: WebGlFramebuffer,
+  renderbuffers: Vec<WebGlRenderbuffer>,
+  color_nb: usize,
+  resolve_depth: bool,
+  state: Rc<RefCell<WebGL2State>>,
+}
+
+impl Drop for Multisample {
+  fn drop(&mut self) {
+    let mut state = self.state.borrow_mut();
+
+    for renderbuffer in &self.renderbuffers {
+      state.ctx.delete_renderbuffer(Some(renderbuffer));
+    }
+
+    state.bind_draw_framebuffer(None);
+    state.ctx.delete_framebuffer(Some(&self.handle));
+  }
+}
+
+impl Multisample {
+  /// Create a multisampled framebuffer with a renderbuffer per color format, and a depth
+  /// renderbuffer.
+  ///
+  /// The depth is resolved only if `depth_format` is known.
+  unsafe fn new(
+    state: &Rc<RefCell<WebGL2State>>,
+    size: [u32; 2],
+    samples: usize,
+    color_formats: &[PixelFormat],
+    depth_format: Option<PixelFormat>,
+  ) -> Result<Self, FramebufferError> {
+    let max_samples = state
+      .borrow()
+      .ctx
+      .get_parameter(WebGl2RenderingContext::MAX_SAMPLES)
+      .ok()
+      .and_then(|x| x.as_f64())
+      .unwrap_or(0.) as usize;
+
+    if samples > max_samples {
+      return Err(FramebufferError::unsupported_samples(samples));
+    }
 
     let handle = state
+      .borrow_mut()
       .create_framebuffer()
       .ok_or_else(|| FramebufferError::cannot_create())?;
-    state.bind_draw_framebuffer(Some(&handle));
 
-    // reserve textures to speed up slots creation
-    let textures_needed = color_formats.len() + depth_format.map_or(0, |_| 1);
-    state.reserve_textures(textures_needed);
+    let mut multisample = Multisample {
+      handle,
+      renderbuffers: Vec::with_capacity(color_formats.len() + 1),
+      color_nb: color_formats.len(),
+      resolve_depth: depth_format.is_some(),
+      state: state.clone(),
+    };
 
-    // color textures
-    if color_formats.is_empty() {
-      state.ctx.draw_buffers(&WebGl2RenderingContext::NONE.into());
-    } else {
-      // Specify the list of color buffers to draw to; to do so, we need to generate a temporary
-      // list (Vec) of 32-bit integers and turn it into a Uint32Array to pass it across WASM
-      // boundary.
-      let color_buf_nb = color_formats.len() as u32;
-      let color_buffers: Vec<_> = (WebGl2RenderingContext::COLOR_ATTACHMENT0
-        ..WebGl2RenderingContext::COLOR_ATTACHMENT0 + color_buf_nb)
-        .collect();
+    multisample.init_storage(size, samples, color_formats, depth_format)?;
 
-      let buffers = Uint32Array::view(&color_buffers);
+    Ok(multisample)
+  }
 
-      state.ctx.draw_buffers(buffers.as_ref());
+  unsafe fn init_storage(
+    &mut self,
+    [width, height]: [u32; 2],
+    samples: usize,
+    color_formats: &[PixelFormat],
+    depth_format: Option<PixelFormat>,
+  ) -> Result<(), FramebufferError> {
+    let iformat = |pf| {
+      webgl_pixel_format(pf)
+        .map(|(_, iformat, _)| iformat)
+        .ok_or_else(|| FramebufferError::unsupported_attachment())
+    };
+    let depth_iformat = match depth_format {
+      Some(pf) => iformat(pf)?,
+      None => WebGl2RenderingContext::DEPTH_COMPONENT32F,
+    };
+    let mut attachments = Vec::with_capacity(color_formats.len() + 1);
+
+    for (i, &pf) in color_formats.iter().enumerate() {
+      attachments.push((
+        WebGl2RenderingContext::COLOR_ATTACHMENT0 + i as u32,
+        iformat(pf)?,
+      ));
     }
 
-    // depth texture
-    if depth_format.is_none() {
+    attachments.push((depth_attachment(depth_format), depth_iformat));
+
+    let mut state = self.state.borrow_mut();
+
+    state.bind_draw_framebuffer(Some(&self.handle));
+
+    for (attachment, iformat) in attachments {
       let renderbuffer = state
         .ctx
         .create_renderbuffer()
@@ -94,31 +236,67 @@ where
       state
         .ctx
         .bind_renderbuffer(WebGl2RenderingContext::RENDERBUFFER, Some(&renderbuffer));
-
-      state.ctx.renderbuffer_storage(
+      state.ctx.renderbuffer_storage_multisample(
         WebGl2RenderingContext::RENDERBUFFER,
-        WebGl2RenderingContext::DEPTH_COMPONENT32F,
-        D::width(size) as i32,
-        D::height(size) as i32,
+        samples as i32,
+        iformat,
+        width as i32,
+        height as i32,
       );
       state.ctx.framebuffer_renderbuffer(
         WebGl2RenderingContext::FRAMEBUFFER,
-        WebGl2RenderingContext::DEPTH_ATTACHMENT,
+        attachment,
         WebGl2RenderingContext::RENDERBUFFER,
         Some(&renderbuffer),
       );
 
-      depth_renderbuffer = Some(renderbuffer);
+      self.renderbuffers.push(renderbuffer);
     }
 
-    let framebuffer = Framebuffer {
-      handle: Some(handle),
-      renderbuffer: depth_renderbuffer,
-      size,
-      state: self.state.clone(),
-    };
+    if color_formats.is_empty() {
+      state.ctx.draw_buffers(&WebGl2RenderingContext::NONE.into());
+      state.ctx.read_buffer(WebGl2RenderingContext::NONE);
+    } else {
+      let color_buffers: Vec<_> = (WebGl2RenderingContext::COLOR_ATTACHMENT0
+        ..WebGl2RenderingContext::COLOR_ATTACHMENT0 + color_formats.len() as u32)
+        .collect();
 
-    Ok(framebuffer)
+      state
+        .ctx
+        .draw_buffers(Uint32Array::view(&color_buffers).as_ref());
+    }
+
+    get_framebuffer_status(&mut state)?;
+
+    Ok(())
+  }
+}
+
+unsafe impl<D> FramebufferBackend<D> for WebGL2
+where
+  D: Dimensionable,
+{
+  type FramebufferRepr = Framebuffer<D>;
+
+  unsafe fn new_framebuffer<CS, DS>(
+    &mut self,
+    size: D::Size,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, D>,
+    DS: DepthSlot<Self, D>,
+  {
+    let depth_format = DS::depth_format();
+
+    new_framebuffer(
+      &self.state,
+      size,
+      &CS::color_formats(),
+      depth_format,
+      depth_format.is_none(),
+    )
   }
 
   unsafe fn attach_color_texture(
@@ -153,7 +331,7 @@ where
         let state = framebuffer.state.borrow();
         state.ctx.framebuffer_texture_2d(
           WebGl2RenderingContext::FRAMEBUFFER,
-          WebGl2RenderingContext::DEPTH_ATTACHMENT,
+          framebuffer.depth_attachment,
           texture.target,
           Some(&texture.handle),
           0,
@@ -178,6 +356,98 @@ where
   }
 }
 
+/// Create a framebuffer drawing to as many color attachments as `color_formats`.
+///
+/// If `depth_renderbuffer` is `true`, a depth renderbuffer is attached.
+unsafe fn new_framebuffer<D>(
+  state_rc: &Rc<RefCell<WebGL2State>>,
+  size: D::Size,
+  color_formats: &[PixelFormat],
+  depth_format: Option<PixelFormat>,
+  depth_renderbuffer: bool,
+) -> Result<Framebuffer<D>, FramebufferError>
+where
+  D: Dimensionable,
+{
+  let mut state = state_rc.borrow_mut();
+
+  let handle = state
+    .create_framebuffer()
+    .ok_or_else(|| FramebufferError::cannot_create())?;
+  state.bind_draw_framebuffer(Some(&handle));
+
+  // reserve textures to speed up slots creation
+  let textures_needed = color_formats.len() + depth_format.map_or(0, |_| 1);
+  state.reserve_textures(textures_needed);
+
+  // color textures
+  if color_formats.is_empty() {
+    state.ctx.draw_buffers(&WebGl2RenderingContext::NONE.into());
+
+    // there is no color to read either, which would make the framebuffer incomplete when read
+    // from, while blitting its depth for instance
+    state.ctx.read_buffer(WebGl2RenderingContext::NONE);
+  } else {
+    // Specify the list of color buffers to draw to; to do so, we need to generate a temporary
+    // list (Vec) of 32-bit integers and turn it into a Uint32Array to pass it across WASM
+    // boundary.
+    let color_buf_nb = color_formats.len() as u32;
+    let color_buffers: Vec<_> = (WebGl2RenderingContext::COLOR_ATTACHMENT0
+      ..WebGl2RenderingContext::COLOR_ATTACHMENT0 + color_buf_nb)
+      .collect();
+
+    let buffers = Uint32Array::view(&color_buffers);
+
+    state.ctx.draw_buffers(buffers.as_ref());
+  }
+
+  // depth renderbuffer, if no depth texture is used
+  let renderbuffer = if depth_renderbuffer {
+    let renderbuffer = state
+      .ctx
+      .create_renderbuffer()
+      .ok_or_else(|| FramebufferError::cannot_create())?;
+
+    state
+      .ctx
+      .bind_renderbuffer(WebGl2RenderingContext::RENDERBUFFER, Some(&renderbuffer));
+
+    state.ctx.renderbuffer_storage(
+      WebGl2RenderingContext::RENDERBUFFER,
+      WebGl2RenderingContext::DEPTH_COMPONENT32F,
+      D::width(size) as i32,
+      D::height(size) as i32,
+    );
+    state.ctx.framebuffer_renderbuffer(
+      WebGl2RenderingContext::FRAMEBUFFER,
+      WebGl2RenderingContext::DEPTH_ATTACHMENT,
+      WebGl2RenderingContext::RENDERBUFFER,
+      Some(&renderbuffer),
+    );
+
+    Some(renderbuffer)
+  } else {
+    None
+  };
+
+  Ok(Framebuffer {
+    handle: Some(handle),
+    renderbuffer,
+    multisample: None,
+    depth_attachment: depth_attachment(depth_format),
+    size,
+    state: state_rc.clone(),
+  })
+}
+
+/// Attachment point of a depth slot of the given pixel format.
+pub(crate) fn depth_attachment(depth_format: Option<PixelFormat>) -> u32 {
+  match depth_format {
+    Some(pf) if pf.is_depth_stencil_pixel() => WebGl2RenderingContext::DEPTH_STENCIL_ATTACHMENT,
+    _ => WebGl2RenderingContext::DEPTH_ATTACHMENT,
+  }
+}
+
 fn get_framebuffer_status(state: &mut WebGL2State) -> Result<(), IncompleteReason> {
   let status = state
     .ctx
@@ -210,8 +480,114 @@ unsafe impl FramebufferBackBuffer for WebGL2 {
     Ok(Framebuffer {
       handle: None, // None is the default framebuffer in WebGL
       renderbuffer: None,
+      multisample: None,
+      depth_attachment: WebGl2RenderingContext::DEPTH_ATTACHMENT,
       size,
       state: self.state.clone(),
     })
   }
 }
+
+unsafe impl FramebufferBlit for WebGL2 {
+  unsafe fn blit_framebuffer(
+    src: &Self::FramebufferRepr,
+    dst: &mut Self::FramebufferRepr,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError> {
+    let mut state = dst.state.borrow_mut();
+
+    state.bind_draw_framebuffer(dst.handle.as_ref());
+    state.bind_read_framebuffer(src.handle.as_ref());
+    blit(&mut state, src_rect, dst_rect, mask, filter);
+
+    Ok(())
+  }
+}
+
+/// Blit the read framebuffer to the draw framebuffer.
+pub(crate) fn blit(
+  state: &mut WebGL2State,
+  src_rect: BlitRect,
+  dst_rect: BlitRect,
+  mask: BlitMask,
+  filter: BlitFilter,
+) {
+  // blits are clipped by the scissor test
+  state.set_scissor_state(ScissorState::Off);
+
+  let [src_x0, src_y0, src_x1, src_y1] = blit_bounds(src_rect);
+  let [dst_x0, dst_y0, dst_x1, dst_y1] = blit_bounds(dst_rect);
+
+  state.ctx.blit_framebuffer(
+    src_x0,
+    src_y0,
+    src_x1,
+    src_y1,
+    dst_x0,
+    dst_y0,
+    dst_x1,
+    dst_y1,
+    webgl_blit_mask(mask),
+    webgl_blit_filter(filter),
+  );
+}
+
+/// Bounds of a [`BlitRect`], as `[x0, y0, x1, y1]`.
+fn blit_bounds(rect: BlitRect) -> [i32; 4] {
+  [
+    rect.x as i32,
+    rect.y as i32,
+    (rect.x + rect.width) as i32,
+    (rect.y + rect.height) as i32,
+  ]
+}
+
+fn webgl_blit_mask(mask: BlitMask) -> u32 {
+  let mut bits = 0;
+
+  if mask.color {
+    bits |= WebGl2RenderingContext::COLOR_BUFFER_BIT;
+  }
+
+  // the stencil is blitted along with the depth; it’s ignored by framebuffers without stencil
+  if mask.depth {
+    bits |= WebGl2RenderingContext::DEPTH_BUFFER_BIT | WebGl2RenderingContext::STENCIL_BUFFER_BIT;
+  }
+
+  bits
+}
+
+fn webgl_blit_filter(filter: BlitFilter) -> u32 {
+  match filter {
+    BlitFilter::Nearest => WebGl2RenderingContext::NEAREST,
+    BlitFilter::Linear => WebGl2RenderingContext::LINEAR,
+  }
+}
+
+unsafe impl FramebufferMultisample for WebGL2 {
+  unsafe fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    _: usize,
+    _: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, Dim2>,
+    DS: DepthSlot<Self, Dim2>,
+  {
+    let color_formats = CS::color_formats();
+    let depth_format = DS::depth_format();
+    let multisample = Multisample::new(&self.state, size, samples, &color_formats, depth_format)?;
+
+    // the depth is rendered to the multisampled storage, so there’s no need for a depth
+    // renderbuffer; the resolve framebuffer is left bound so that the slots are attached to it
+    let mut framebuffer = new_framebuffer(&self.state, size, &color_formats, depth_format, false)?;
+    framebuffer.multisample = Some(multisample);
+
+    Ok(framebuffer)
+  }
+}
@@ -15,12 +15,15 @@ use luminance::tess::{Deinterleaved, DeinterleavedData, Interleaved, TessIndex,
 use luminance::texture::Dimensionable;
 use std::cell::RefCell;
 use std::marker::PhantomData;
+use std::mem;
 use std::rc::Rc;
 use web_sys::WebGl2RenderingContext;
 
 use crate::webgl2::{
   array_buffer::IntoArrayBuffer,
-  state::{BlendingState, DepthTest, FaceCullingState, ScissorState, WebGL2State},
+  state::{
+    BlendingState, DepthTest, FaceCullingState, ScissorState, StencilTestState, WebGL2State,
+  },
   WebGL2,
 };
 
@@ -94,7 +97,7 @@ where
   ) {
     let mut state = self.state.borrow_mut();
 
-    state.bind_draw_framebuffer(framebuffer.handle.as_ref());
+    state.bind_draw_framebuffer(framebuffer.draw_handle());
 
     let clear_color = pipeline_state.clear_color;
     state.set_clear_color(clear_color);
@@ -113,7 +116,12 @@ where
 
     state.set_viewport([x as _, y as _, w as _, h as _]);
 
-    if pipeline_state.clear_color_enabled || pipeline_state.clear_depth_enabled {
+    state.set_clear_stencil(pipeline_state.clear_stencil);
+
+    if pipeline_state.clear_color_enabled
+      || pipeline_state.clear_depth_enabled
+      || pipeline_state.clear_stencil_enabled
+    {
       let color_bit = if pipeline_state.clear_color_enabled {
         WebGl2RenderingContext::COLOR_BUFFER_BIT
       } else {
@@ -126,6 +134,15 @@ where
         0
       };
 
+      let stencil_bit = if pipeline_state.clear_stencil_enabled {
+        // clears are masked by the stencil write mask, which might have been changed by a render
+        // state
+        state.set_stencil_write_mask(0xFF);
+        WebGl2RenderingContext::STENCIL_BUFFER_BIT
+      } else {
+        0
+      };
+
       // scissor test
       match pipeline_state.scissor() {
         Some(region) => {
@@ -138,9 +155,13 @@ where
         }
       }
 
-      state.ctx.clear(color_bit | depth_bit);
+      state.ctx.clear(color_bit | depth_bit | stencil_bit);
     }
   }
+
+  unsafe fn end_pipeline(&mut self, framebuffer: &Self::FramebufferRepr) {
+    framebuffer.resolve();
+  }
 }
 
 unsafe impl<T> PipelineBuffer<T> for WebGL2
@@ -171,9 +192,44 @@ where
     })
   }
 
+  unsafe fn bind_buffer_range(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Self::BoundBufferRepr, PipelineError> {
+    let mut state = pipeline.state.borrow_mut();
+    let alignment = state.uniform_buffer_offset_alignment();
+    let offset = start * mem::size_of::<T>();
+
+    if offset / alignment * alignment != offset {
+      return Err(PipelineError::unaligned_buffer_range(offset, alignment));
+    }
+
+    let bstack = state.binding_stack_mut();
+
+    let binding = bstack.free_buffer_bindings.pop().unwrap_or_else(|| {
+      // no more free bindings; reserve one
+      let binding = bstack.next_buffer_binding;
+      bstack.next_buffer_binding += 1;
+      binding
+    });
+
+    state.bind_buffer_range(buffer.handle(), binding, offset, len * mem::size_of::<T>());
+
+    Ok(BoundBuffer {
+      binding,
+      state: pipeline.state.clone(),
+    })
+  }
+
   unsafe fn buffer_binding(bound: &Self::BoundBufferRepr) -> u32 {
     bound.binding
   }
+
+  unsafe fn buffer_offset_alignment(&mut self) -> usize {
+    self.state.borrow().uniform_buffer_offset_alignment()
+  }
 }
 
 unsafe impl<D, P> PipelineTexture<D, P> for WebGL2
@@ -287,6 +343,20 @@ unsafe impl RenderGate for WebGL2 {
 
     state.set_depth_write(rdr_st.depth_write());
 
+    // stencil-related state
+    if let Some(stencil_test) = rdr_st.stencil_test() {
+      state.set_stencil_test_state(StencilTestState::On);
+      state.set_stencil_test(stencil_test);
+      state.set_stencil_operations(
+        rdr_st.front_stencil_operations(),
+        rdr_st.back_stencil_operations(),
+      );
+    } else {
+      state.set_stencil_test_state(StencilTestState::Off);
+    }
+
+    state.set_stencil_write_mask(rdr_st.stencil_write_mask());
+
     // face culling state
     match rdr_st.face_culling() {
       Some(face_culling) => {
@@ -1,4 +1,4 @@
-use luminance::pixel::{Format, PixelFormat, Size, Type};
+use luminance::pixel::{Compression, Format, PixelFormat, Size, Type};
 use web_sys::WebGl2RenderingContext;
 
 // WebGL format, internal sized-format and type.
@@ -36,6 +36,11 @@ pub(crate) fn webgl_pixel_format(pf: PixelFormat) -> Option<(u32, u32, u32)> {
       WebGl2RenderingContext::R16UI,
       WebGl2RenderingContext::UNSIGNED_SHORT,
     )),
+    (Format::R(Size::Sixteen), Type::Floating) => Some((
+      WebGl2RenderingContext::RED,
+      WebGl2RenderingContext::R16F,
+      WebGl2RenderingContext::HALF_FLOAT,
+    )),
 
     (Format::R(Size::ThirtyTwo), Type::NormUnsigned) => Some((
       WebGl2RenderingContext::RED_INTEGER,
@@ -95,6 +100,11 @@ pub(crate) fn webgl_pixel_format(pf: PixelFormat) -> Option<(u32, u32, u32)> {
       WebGl2RenderingContext::RG16UI,
       WebGl2RenderingContext::UNSIGNED_SHORT,
     )),
+    (Format::RG(Size::Sixteen, Size::Sixteen), Type::Floating) => Some((
+      WebGl2RenderingContext::RG,
+      WebGl2RenderingContext::RG16F,
+      WebGl2RenderingContext::HALF_FLOAT,
+    )),
 
     (Format::RG(Size::ThirtyTwo, Size::ThirtyTwo), Type::NormUnsigned) => Some((
       WebGl2RenderingContext::RG,
@@ -161,6 +171,18 @@ pub(crate) fn webgl_pixel_format(pf: PixelFormat) -> Option<(u32, u32, u32)> {
       WebGl2RenderingContext::FLOAT,
     )),
 
+    (Format::RGB(Size::Five, Size::Six, Size::Five), Type::NormUnsigned) => Some((
+      WebGl2RenderingContext::RGB,
+      WebGl2RenderingContext::RGB565,
+      WebGl2RenderingContext::UNSIGNED_SHORT_5_6_5,
+    )),
+
+    (Format::RGBE(Size::Nine, Size::Nine, Size::Nine, Size::Five), Type::Floating) => Some((
+      WebGl2RenderingContext::RGB,
+      WebGl2RenderingContext::RGB9_E5,
+      WebGl2RenderingContext::UNSIGNED_INT_5_9_9_9_REV,
+    )),
+
     (Format::RGB(Size::ThirtyTwo, Size::ThirtyTwo, Size::ThirtyTwo), Type::NormUnsigned) => Some((
       WebGl2RenderingContext::RGB,
       WebGl2RenderingContext::RGB,
@@ -227,6 +249,19 @@ pub(crate) fn webgl_pixel_format(pf: PixelFormat) -> Option<(u32, u32, u32)> {
         WebGl2RenderingContext::UNSIGNED_SHORT,
       ))
     }
+    (Format::RGBA(Size::Sixteen, Size::Sixteen, Size::Sixteen, Size::Sixteen), Type::Floating) => {
+      Some((
+        WebGl2RenderingContext::RGBA,
+        WebGl2RenderingContext::RGBA16F,
+        WebGl2RenderingContext::HALF_FLOAT,
+      ))
+    }
+
+    (Format::RGBA(Size::Ten, Size::Ten, Size::Ten, Size::Two), Type::NormUnsigned) => Some((
+      WebGl2RenderingContext::RGBA,
+      WebGl2RenderingContext::RGB10_A2,
+      WebGl2RenderingContext::UNSIGNED_INT_2_10_10_10_REV,
+    )),
 
     (
       Format::RGBA(Size::ThirtyTwo, Size::ThirtyTwo, Size::ThirtyTwo, Size::ThirtyTwo),
@@ -301,6 +336,125 @@ pub(crate) fn webgl_pixel_format(pf: PixelFormat) -> Option<(u32, u32, u32)> {
       WebGl2RenderingContext::FLOAT,
     )),
 
+    // depth and stencil
+    (Format::DepthStencil(Size::TwentyFour, Size::Eight), Type::NormUnsigned) => Some((
+      WebGl2RenderingContext::DEPTH_STENCIL,
+      WebGl2RenderingContext::DEPTH24_STENCIL8,
+      WebGl2RenderingContext::UNSIGNED_INT_24_8,
+    )),
+    (Format::DepthStencil(Size::ThirtyTwo, Size::Eight), Type::Floating) => Some((
+      WebGl2RenderingContext::DEPTH_STENCIL,
+      WebGl2RenderingContext::DEPTH32F_STENCIL8,
+      WebGl2RenderingContext::FLOAT_32_UNSIGNED_INT_24_8_REV,
+    )),
+
     _ => None,
   }
 }
+
+// Compressed formats, from the WebGL compressed texture extensions.
+const COMPRESSED_RGB_S3TC_DXT1: u32 = 0x83F0;
+const COMPRESSED_RGBA_S3TC_DXT1: u32 = 0x83F1;
+const COMPRESSED_RGBA_S3TC_DXT3: u32 = 0x83F2;
+const COMPRESSED_RGBA_S3TC_DXT5: u32 = 0x83F3;
+const COMPRESSED_SRGB_S3TC_DXT1: u32 = 0x8C4C;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT1: u32 = 0x8C4D;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT3: u32 = 0x8C4E;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT5: u32 = 0x8C4F;
+const COMPRESSED_RED_RGTC1: u32 = 0x8DBB;
+const COMPRESSED_SIGNED_RED_RGTC1: u32 = 0x8DBC;
+const COMPRESSED_RED_GREEN_RGTC2: u32 = 0x8DBD;
+const COMPRESSED_SIGNED_RED_GREEN_RGTC2: u32 = 0x8DBE;
+const COMPRESSED_RGBA_BPTC_UNORM: u32 = 0x8E8C;
+const COMPRESSED_SRGB_ALPHA_BPTC_UNORM: u32 = 0x8E8D;
+const COMPRESSED_RGB_BPTC_SIGNED_FLOAT: u32 = 0x8E8E;
+const COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT: u32 = 0x8E8F;
+const COMPRESSED_R11_EAC: u32 = 0x9270;
+const COMPRESSED_SIGNED_R11_EAC: u32 = 0x9271;
+const COMPRESSED_RG11_EAC: u32 = 0x9272;
+const COMPRESSED_SIGNED_RG11_EAC: u32 = 0x9273;
+const COMPRESSED_RGB8_ETC2: u32 = 0x9274;
+const COMPRESSED_SRGB8_ETC2: u32 = 0x9275;
+const COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9276;
+const COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9277;
+const COMPRESSED_RGBA8_ETC2_EAC: u32 = 0x9278;
+const COMPRESSED_SRGB8_ALPHA8_ETC2_EAC: u32 = 0x9279;
+
+// ASTC block sizes are consecutive from these.
+const COMPRESSED_RGBA_ASTC_4X4: u32 = 0x93B0;
+const COMPRESSED_SRGB8_ALPHA8_ASTC_4X4: u32 = 0x93D0;
+
+// WebGL compressed internal format.
+pub(crate) fn webgl_compressed_format(pf: PixelFormat) -> Option<u32> {
+  match (pf.format, pf.encoding) {
+    (Format::Compressed(Compression::BC1), _) => Some(COMPRESSED_RGB_S3TC_DXT1),
+    (Format::Compressed(Compression::BC1A), _) => Some(COMPRESSED_RGBA_S3TC_DXT1),
+    (Format::Compressed(Compression::BC2), _) => Some(COMPRESSED_RGBA_S3TC_DXT3),
+    (Format::Compressed(Compression::BC3), _) => Some(COMPRESSED_RGBA_S3TC_DXT5),
+    (Format::SRGBCompressed(Compression::BC1), _) => Some(COMPRESSED_SRGB_S3TC_DXT1),
+    (Format::SRGBCompressed(Compression::BC1A), _) => Some(COMPRESSED_SRGB_ALPHA_S3TC_DXT1),
+    (Format::SRGBCompressed(Compression::BC2), _) => Some(COMPRESSED_SRGB_ALPHA_S3TC_DXT3),
+    (Format::SRGBCompressed(Compression::BC3), _) => Some(COMPRESSED_SRGB_ALPHA_S3TC_DXT5),
+
+    (Format::Compressed(Compression::BC4), Type::NormUnsigned) => Some(COMPRESSED_RED_RGTC1),
+    (Format::Compressed(Compression::BC4), Type::NormIntegral) => Some(COMPRESSED_SIGNED_RED_RGTC1),
+    (Format::Compressed(Compression::BC5), Type::NormUnsigned) => Some(COMPRESSED_RED_GREEN_RGTC2),
+    (Format::Compressed(Compression::BC5), Type::NormIntegral) => {
+      Some(COMPRESSED_SIGNED_RED_GREEN_RGTC2)
+    }
+
+    (Format::Compressed(Compression::BC6H), _) => Some(COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT),
+    (Format::Compressed(Compression::BC6HSigned), _) => Some(COMPRESSED_RGB_BPTC_SIGNED_FLOAT),
+    (Format::Compressed(Compression::BC7), _) => Some(COMPRESSED_RGBA_BPTC_UNORM),
+    (Format::SRGBCompressed(Compression::BC7), _) => Some(COMPRESSED_SRGB_ALPHA_BPTC_UNORM),
+
+    (Format::Compressed(Compression::ETC2), _) => Some(COMPRESSED_RGB8_ETC2),
+    (Format::Compressed(Compression::ETC2A1), _) => Some(COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2),
+    (Format::Compressed(Compression::ETC2EAC), _) => Some(COMPRESSED_RGBA8_ETC2_EAC),
+    (Format::SRGBCompressed(Compression::ETC2), _) => Some(COMPRESSED_SRGB8_ETC2),
+    (Format::SRGBCompressed(Compression::ETC2A1), _) => {
+      Some(COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2)
+    }
+    (Format::SRGBCompressed(Compression::ETC2EAC), _) => Some(COMPRESSED_SRGB8_ALPHA8_ETC2_EAC),
+    (Format::Compressed(Compression::EACR11), Type::NormUnsigned) => Some(COMPRESSED_R11_EAC),
+    (Format::Compressed(Compression::EACR11), Type::NormIntegral) => {
+      Some(COMPRESSED_SIGNED_R11_EAC)
+    }
+    (Format::Compressed(Compression::EACRG11), Type::NormUnsigned) => Some(COMPRESSED_RG11_EAC),
+    (Format::Compressed(Compression::EACRG11), Type::NormIntegral) => {
+      Some(COMPRESSED_SIGNED_RG11_EAC)
+    }
+
+    (Format::Compressed(c @ Compression::ASTC { .. }), _) => {
+      astc_block_index(c).map(|i| COMPRESSED_RGBA_ASTC_4X4 + i)
+    }
+    (Format::SRGBCompressed(c @ Compression::ASTC { .. }), _) => {
+      astc_block_index(c).map(|i| COMPRESSED_SRGB8_ALPHA8_ASTC_4X4 + i)
+    }
+
+    _ => None,
+  }
+}
+
+// Index of the block size of an ASTC compression, in the order of the ASTC formats.
+fn astc_block_index(compression: Compression) -> Option<u32> {
+  let index = match compression.block_size() {
+    [4, 4] => 0,
+    [5, 4] => 1,
+    [5, 5] => 2,
+    [6, 5] => 3,
+    [6, 6] => 4,
+    [8, 5] => 5,
+    [8, 6] => 6,
+    [8, 8] => 7,
+    [10, 5] => 8,
+    [10, 6] => 9,
+    [10, 8] => 10,
+    [10, 10] => 11,
+    [12, 10] => 12,
+    [12, 12] => 13,
+    _ => return None,
+  };
+
+  Some(index)
+}
@@ -0,0 +1,100 @@
+//! WebGL2 query implementation.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{WebGl2RenderingContext, WebGlQuery};
+
+use crate::webgl2::state::WebGL2State;
+use crate::webgl2::WebGL2;
+use luminance::backend::query::Query as QueryBackend;
+use luminance::query::{QueryError, QueryType};
+
+/// WebGL query.
+#[derive(Debug)]
+pub struct Query {
+  handle: WebGlQuery,
+  ty: QueryType,
+  // whether the query has been ended at least once
+  issued: bool,
+  state: Rc<RefCell<WebGL2State>>,
+}
+
+impl Drop for Query {
+  fn drop(&mut self) {
+    self.state.borrow().ctx.delete_query(Some(&self.handle));
+  }
+}
+
+unsafe impl QueryBackend for WebGL2 {
+  type QueryRepr = Query;
+
+  unsafe fn new_query(&mut self, ty: QueryType) -> Result<Self::QueryRepr, QueryError> {
+    // timer queries are only available through extensions and primitive queries are not
+    // available at all
+    if ty != QueryType::AnySamplesPassed {
+      return Err(QueryError::unsupported_type(ty));
+    }
+
+    let handle = self
+      .state
+      .borrow()
+      .ctx
+      .create_query()
+      .ok_or_else(QueryError::cannot_create)?;
+
+    Ok(Query {
+      handle,
+      ty,
+      issued: false,
+      state: self.state.clone(),
+    })
+  }
+
+  unsafe fn begin_query(&mut self, query: &mut Self::QueryRepr) -> Result<(), QueryError> {
+    if self
+      .state
+      .borrow_mut()
+      .begin_query(WebGl2RenderingContext::ANY_SAMPLES_PASSED, &query.handle)
+    {
+      Ok(())
+    } else {
+      Err(QueryError::already_active(query.ty))
+    }
+  }
+
+  unsafe fn end_query(&mut self, query: &mut Self::QueryRepr) {
+    self
+      .state
+      .borrow_mut()
+      .end_query(WebGl2RenderingContext::ANY_SAMPLES_PASSED);
+    query.issued = true;
+  }
+
+  unsafe fn query_result(query: &mut Self::QueryRepr) -> Result<Option<u64>, QueryError> {
+    if !query.issued {
+      return Ok(None);
+    }
+
+    let state = query.state.borrow();
+    let available = state
+      .ctx
+      .get_query_parameter(
+        &query.handle,
+        WebGl2RenderingContext::QUERY_RESULT_AVAILABLE,
+      )
+      .as_bool()
+      .unwrap_or(false);
+
+    if !available {
+      return Ok(None);
+    }
+
+    let result = state
+      .ctx
+      .get_query_parameter(&query.handle, WebGl2RenderingContext::QUERY_RESULT)
+      .as_f64()
+      .unwrap_or(0.);
+
+    Ok(Some(result as u64))
+  }
+}
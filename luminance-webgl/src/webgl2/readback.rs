@@ -0,0 +1,116 @@
+//! WebGL2 readback implementation.
+//!
+//! Readbacks copy the read data to a staging buffer — with a buffer copy or a pixel pack — and
+//! insert a fence right after. The staging buffer is read once the fence is signaled. Staging
+//! buffers are bound to the copy and pixel pack targets only, which are not tracked by the graphics
+//! state.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::rc::Rc;
+use web_sys::{WebGl2RenderingContext, WebGlBuffer};
+
+use crate::webgl2::fence::Fence;
+use crate::webgl2::state::WebGL2State;
+use crate::webgl2::WebGL2;
+use luminance::backend::readback::Readback as ReadbackBackend;
+use luminance::fence::FenceError;
+use luminance::readback::ReadbackError;
+
+/// WebGL readback.
+#[derive(Debug)]
+pub struct Readback<T> {
+  handle: WebGlBuffer, // staging buffer
+  len: usize,
+  fence: Fence,
+  state: Rc<RefCell<WebGL2State>>,
+  _t: PhantomData<T>,
+}
+
+impl<T> Drop for Readback<T> {
+  fn drop(&mut self) {
+    self.state.borrow().ctx.delete_buffer(Some(&self.handle));
+  }
+}
+
+impl<T> Readback<T> {
+  /// Create a staging buffer able to hold `len` items, and bind it to `target`.
+  pub(crate) fn new_staging_buffer(
+    state: &mut WebGL2State,
+    target: u32,
+    len: usize,
+  ) -> Option<WebGlBuffer> {
+    let handle = state.create_buffer()?;
+
+    state.ctx.bind_buffer(target, Some(&handle));
+    state.ctx.buffer_data_with_i32(
+      target,
+      (len * mem::size_of::<T>()) as i32,
+      WebGl2RenderingContext::STREAM_READ,
+    );
+
+    Some(handle)
+  }
+
+  /// Wrap the staging buffer `handle` holding `len` items, once the commands writing to it are
+  /// issued.
+  ///
+  /// The staging buffer is deleted if the fence cannot be inserted.
+  pub(crate) fn from_handle(
+    state: &Rc<RefCell<WebGL2State>>,
+    handle: WebGlBuffer,
+    len: usize,
+  ) -> Result<Self, FenceError> {
+    match Fence::new(state) {
+      Ok(fence) => Ok(Readback {
+        handle,
+        len,
+        fence,
+        state: state.clone(),
+        _t: PhantomData,
+      }),
+
+      Err(e) => {
+        state.borrow().ctx.delete_buffer(Some(&handle));
+        Err(e)
+      }
+    }
+  }
+}
+
+unsafe impl<T> ReadbackBackend<T> for WebGL2
+where
+  T: Copy,
+{
+  type ReadbackRepr = Readback<T>;
+
+  unsafe fn readback_result(
+    readback: &mut Self::ReadbackRepr,
+  ) -> Result<Option<Vec<T>>, ReadbackError> {
+    if !readback.fence.is_signaled() {
+      return Ok(None);
+    }
+
+    // there’s no buffer mapping in WebGL2; the staging buffer is copied to client memory instead
+    let mut bytes = vec![0u8; readback.len * mem::size_of::<T>()];
+
+    let state = readback.state.borrow();
+    state.ctx.bind_buffer(
+      WebGl2RenderingContext::COPY_READ_BUFFER,
+      Some(&readback.handle),
+    );
+    state.ctx.get_buffer_sub_data_with_i32_and_u8_array(
+      WebGl2RenderingContext::COPY_READ_BUFFER,
+      0,
+      &mut bytes,
+    );
+
+    let mut data = Vec::with_capacity(readback.len);
+    ptr::copy_nonoverlapping(bytes.as_ptr(), data.as_mut_ptr() as *mut u8, bytes.len());
+    data.set_len(readback.len);
+
+    Ok(Some(data))
+  }
+}
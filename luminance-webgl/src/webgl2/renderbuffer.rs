@@ -0,0 +1,95 @@
+//! Renderbuffer support for WebGL2.
+
+use luminance::backend::renderbuffer::{Renderbuffer as RenderbufferBackend, RenderbufferBase};
+use luminance::framebuffer::FramebufferError;
+use luminance::pixel::Pixel;
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{WebGl2RenderingContext, WebGlRenderbuffer};
+
+use crate::webgl2::pixel::webgl_pixel_format;
+use crate::webgl2::state::WebGL2State;
+use crate::webgl2::WebGL2;
+
+pub struct Renderbuffer {
+  handle: WebGlRenderbuffer,
+  state: Rc<RefCell<WebGL2State>>,
+}
+
+impl Drop for Renderbuffer {
+  fn drop(&mut self) {
+    self
+      .state
+      .borrow()
+      .ctx
+      .delete_renderbuffer(Some(&self.handle));
+  }
+}
+
+impl Renderbuffer {
+  fn attach(&self, attachment: u32) {
+    self.state.borrow().ctx.framebuffer_renderbuffer(
+      WebGl2RenderingContext::FRAMEBUFFER,
+      attachment,
+      WebGl2RenderingContext::RENDERBUFFER,
+      Some(&self.handle),
+    );
+  }
+}
+
+unsafe impl RenderbufferBase for WebGL2 {
+  type RenderbufferRepr = Renderbuffer;
+}
+
+unsafe impl<P> RenderbufferBackend<P> for WebGL2
+where
+  P: Pixel,
+{
+  unsafe fn new_renderbuffer(
+    &mut self,
+    [width, height]: [u32; 2],
+  ) -> Result<Self::RenderbufferRepr, FramebufferError> {
+    let (_, iformat, _) =
+      webgl_pixel_format(P::pixel_format()).ok_or_else(FramebufferError::unsupported_attachment)?;
+    let state = self.state.borrow();
+    let handle = state
+      .ctx
+      .create_renderbuffer()
+      .ok_or_else(FramebufferError::cannot_create)?;
+
+    state
+      .ctx
+      .bind_renderbuffer(WebGl2RenderingContext::RENDERBUFFER, Some(&handle));
+    state.ctx.renderbuffer_storage(
+      WebGl2RenderingContext::RENDERBUFFER,
+      iformat,
+      width as i32,
+      height as i32,
+    );
+    state
+      .ctx
+      .bind_renderbuffer(WebGl2RenderingContext::RENDERBUFFER, None);
+
+    Ok(Renderbuffer {
+      handle,
+      state: self.state.clone(),
+    })
+  }
+
+  unsafe fn attach_color_renderbuffer(
+    _: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError> {
+    renderbuffer.attach(WebGl2RenderingContext::COLOR_ATTACHMENT0 + attachment_index as u32);
+    Ok(())
+  }
+
+  unsafe fn attach_depth_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+  ) -> Result<(), FramebufferError> {
+    renderbuffer.attach(framebuffer.depth_attachment);
+    Ok(())
+  }
+}
@@ -5,14 +5,18 @@ use luminance::{
   blending::{Equation, Factor},
   depth_test::{DepthComparison, DepthWrite},
   face_culling::{FaceCullingMode, FaceCullingOrder},
+  pixel::CompressionFamily,
   scissor::ScissorRegion,
+  stencil::{StencilComparison, StencilOp, StencilOperations, StencilTest},
 };
 use std::{fmt, marker::PhantomData};
 use web_sys::{
-  WebGl2RenderingContext, WebGlBuffer, WebGlFramebuffer, WebGlProgram, WebGlTexture,
+  WebGl2RenderingContext, WebGlBuffer, WebGlFramebuffer, WebGlProgram, WebGlQuery, WebGlTexture,
   WebGlVertexArrayObject,
 };
 
+use crate::webgl2::texture::MAX_TEXTURE_MAX_ANISOTROPY;
+
 #[derive(Debug)]
 pub(crate) struct BindingStack {
   pub(crate) next_texture_unit: u32,
@@ -54,6 +58,7 @@ pub struct WebGL2State {
 
   // clear buffers
   clear_color: [f32; 4],
+  clear_stencil: u8,
 
   // blending
   blending_state: BlendingState,
@@ -67,6 +72,13 @@ pub struct WebGL2State {
   // depth write
   depth_write: DepthWrite,
 
+  // stencil test
+  stencil_test_state: StencilTestState,
+  stencil_test: StencilTest,
+  front_stencil_operations: StencilOperations,
+  back_stencil_operations: StencilOperations,
+  stencil_write_mask: u8,
+
   // face culling
   face_culling_state: FaceCullingState,
   face_culling_order: FaceCullingOrder,
@@ -90,9 +102,13 @@ pub struct WebGL2State {
 
   // uniform buffer
   bound_uniform_buffers: Vec<Option<WebGlBuffer>>,
+  uniform_buffer_offset_alignment: usize,
 
   // array buffer
   bound_array_buffer: Option<WebGlBuffer>,
+
+  // targets of the active queries
+  active_queries: Vec<u32>,
   // element buffer
   bound_element_array_buffer: Option<WebGlBuffer>,
 
@@ -109,6 +125,12 @@ pub struct WebGL2State {
   bound_vertex_array: Option<WebGlVertexArrayObject>,
   // shader program
   current_program: Option<WebGlProgram>,
+
+  // supported compressed pixel format families
+  compression_families: Vec<CompressionFamily>,
+
+  // maximum degree of anisotropy, if anisotropic filtering is supported
+  max_anisotropy: Option<f32>,
 }
 
 impl WebGL2State {
@@ -124,16 +146,24 @@ impl WebGL2State {
   /// Get a `GraphicsContext` from the current OpenGL context.
   fn get_from_context(mut ctx: WebGl2RenderingContext) -> Result<Self, StateQueryError> {
     load_webgl2_extensions(&mut ctx)?;
+    let compression_families = load_webgl2_compression_extensions(&mut ctx);
+    let max_anisotropy = load_webgl2_anisotropy_extension(&mut ctx);
 
     let binding_stack = BindingStack::new();
     let viewport = get_ctx_viewport(&mut ctx)?;
     let clear_color = get_ctx_clear_color(&mut ctx)?;
+    let clear_stencil = get_ctx_clear_stencil(&mut ctx)?;
     let blending_state = get_ctx_blending_state(&mut ctx);
     let blending_equations = get_ctx_blending_equations(&mut ctx)?;
     let blending_funcs = get_ctx_blending_factors(&mut ctx)?;
     let depth_test = get_ctx_depth_test(&mut ctx);
     let depth_test_comparison = DepthComparison::Less;
     let depth_write = get_ctx_depth_write(&mut ctx)?;
+    let stencil_test_state = get_ctx_stencil_test_state(&mut ctx);
+    let stencil_test = StencilTest::new(StencilComparison::Always, 0, 0xFF);
+    let front_stencil_operations = StencilOperations::default();
+    let back_stencil_operations = StencilOperations::default();
+    let stencil_write_mask = get_ctx_stencil_write_mask(&mut ctx)?;
     let face_culling_state = get_ctx_face_culling_state(&mut ctx);
     let face_culling_order = get_ctx_face_culling_order(&mut ctx)?;
     let face_culling_mode = get_ctx_face_culling_mode(&mut ctx)?;
@@ -144,7 +174,9 @@ impl WebGL2State {
     let bound_textures = vec![(WebGl2RenderingContext::TEXTURE0, None); 48]; // 48 is the platform minimal requirement
     let texture_swimming_pool = Vec::new();
     let bound_uniform_buffers = vec![None; 36]; // 36 is the platform minimal requirement
+    let uniform_buffer_offset_alignment = get_ctx_uniform_buffer_offset_alignment(&mut ctx);
     let bound_array_buffer = None;
+    let active_queries = Vec::new();
     let bound_element_array_buffer = None;
     let bound_draw_framebuffer = None;
     let bound_read_framebuffer = None;
@@ -158,12 +190,18 @@ impl WebGL2State {
       binding_stack,
       viewport,
       clear_color,
+      clear_stencil,
       blending_state,
       blending_equations,
       blending_funcs,
       depth_test,
       depth_test_comparison,
       depth_write,
+      stencil_test_state,
+      stencil_test,
+      front_stencil_operations,
+      back_stencil_operations,
+      stencil_write_mask,
       face_culling_state,
       face_culling_order,
       face_culling_mode,
@@ -173,13 +211,17 @@ impl WebGL2State {
       bound_textures,
       texture_swimming_pool,
       bound_uniform_buffers,
+      uniform_buffer_offset_alignment,
       bound_array_buffer,
+      active_queries,
       bound_element_array_buffer,
       bound_draw_framebuffer,
       bound_read_framebuffer,
       readback_framebuffer,
       bound_vertex_array,
       current_program,
+      compression_families,
+      max_anisotropy,
     })
   }
 
@@ -220,6 +262,70 @@ impl WebGL2State {
     }
   }
 
+  /// Bind `size` bytes of a uniform buffer, starting at `offset` bytes.
+  ///
+  /// Ranges are not cached: the binding is always updated, and invalidated in the cache.
+  pub(crate) fn bind_buffer_range(
+    &mut self,
+    handle: &WebGlBuffer,
+    binding: u32,
+    offset: usize,
+    size: usize,
+  ) {
+    self.ctx.bind_buffer_range_with_i32_and_i32(
+      WebGl2RenderingContext::UNIFORM_BUFFER,
+      binding,
+      Some(handle),
+      offset as i32,
+      size as i32,
+    );
+
+    if binding as usize >= self.bound_uniform_buffers.len() {
+      // not enough registered buffer bindings; let’s grow a bit more
+      self
+        .bound_uniform_buffers
+        .resize(binding as usize + 1, None);
+    }
+
+    // a whole-buffer binding must be re-issued after a range binding
+    self.bound_uniform_buffers[binding as usize] = None;
+  }
+
+  /// Alignment, in bytes, of the offsets of uniform buffer ranges.
+  pub(crate) fn uniform_buffer_offset_alignment(&self) -> usize {
+    self.uniform_buffer_offset_alignment
+  }
+
+  /// Whether the compressed pixel formats of `family` are supported.
+  pub(crate) fn supports_compression(&self, family: CompressionFamily) -> bool {
+    self.compression_families.contains(&family)
+  }
+
+  /// Maximum degree of anisotropy, if anisotropic filtering is supported.
+  pub(crate) fn max_anisotropy(&self) -> Option<f32> {
+    self.max_anisotropy
+  }
+
+  /// Begin the query `query` on `target`.
+  ///
+  /// Return `false` if a query is already active on `target`, in which case nothing happens.
+  pub(crate) fn begin_query(&mut self, target: u32, query: &WebGlQuery) -> bool {
+    if self.active_queries.contains(&target) {
+      return false;
+    }
+
+    self.ctx.begin_query(target, query);
+    self.active_queries.push(target);
+
+    true
+  }
+
+  /// End the query active on `target`.
+  pub(crate) fn end_query(&mut self, target: u32) {
+    self.ctx.end_query(target);
+    self.active_queries.retain(|&t| t != target);
+  }
+
   pub(crate) fn bind_array_buffer(&mut self, buffer: Option<&WebGlBuffer>, bind: Bind) {
     if bind == Bind::Forced || self.bound_array_buffer.as_ref() != buffer {
       self
@@ -336,10 +442,12 @@ impl WebGL2State {
 
   pub(crate) fn bind_draw_framebuffer(&mut self, handle: Option<&WebGlFramebuffer>) {
     if self.bound_draw_framebuffer.as_ref() != handle {
+      // FRAMEBUFFER is both the draw and read framebuffer targets
       self
         .ctx
         .bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, handle);
       self.bound_draw_framebuffer = handle.cloned();
+      self.bound_read_framebuffer = handle.cloned();
     }
   }
 
@@ -380,6 +488,13 @@ impl WebGL2State {
     }
   }
 
+  pub(crate) fn set_clear_stencil(&mut self, clear_stencil: u8) {
+    if self.clear_stencil != clear_stencil {
+      self.ctx.clear_stencil(clear_stencil as i32);
+      self.clear_stencil = clear_stencil;
+    }
+  }
+
   pub(crate) fn set_blending_state(&mut self, state: BlendingState) {
     if self.blending_state != state {
       match state {
@@ -501,6 +616,66 @@ impl WebGL2State {
     }
   }
 
+  pub(crate) fn set_stencil_test_state(&mut self, state: StencilTestState) {
+    if self.stencil_test_state != state {
+      match state {
+        StencilTestState::On => self.ctx.enable(WebGl2RenderingContext::STENCIL_TEST),
+        StencilTestState::Off => self.ctx.disable(WebGl2RenderingContext::STENCIL_TEST),
+      }
+
+      self.stencil_test_state = state;
+    }
+  }
+
+  pub(crate) fn set_stencil_test(&mut self, stencil_test: StencilTest) {
+    if self.stencil_test != stencil_test {
+      self.ctx.stencil_func(
+        stencil_comparison_to_webgl(stencil_test.comparison),
+        stencil_test.reference as i32,
+        stencil_test.mask as u32,
+      );
+
+      self.stencil_test = stencil_test;
+    }
+  }
+
+  pub(crate) fn set_stencil_operations(
+    &mut self,
+    front: StencilOperations,
+    back: StencilOperations,
+  ) {
+    for (face, ops, cached) in [
+      (
+        WebGl2RenderingContext::FRONT,
+        front,
+        &mut self.front_stencil_operations,
+      ),
+      (
+        WebGl2RenderingContext::BACK,
+        back,
+        &mut self.back_stencil_operations,
+      ),
+    ] {
+      if *cached != ops {
+        self.ctx.stencil_op_separate(
+          face,
+          stencil_op_to_webgl(ops.stencil_fails),
+          stencil_op_to_webgl(ops.depth_fails),
+          stencil_op_to_webgl(ops.depth_passes),
+        );
+
+        *cached = ops;
+      }
+    }
+  }
+
+  pub(crate) fn set_stencil_write_mask(&mut self, mask: u8) {
+    if self.stencil_write_mask != mask {
+      self.ctx.stencil_mask(mask as u32);
+      self.stencil_write_mask = mask;
+    }
+  }
+
   pub(crate) fn set_face_culling_state(&mut self, state: FaceCullingState) {
     if self.face_culling_state != state {
       match state {
@@ -591,6 +766,10 @@ pub enum StateQueryError {
   UnknownClearColorInitialState,
   /// Unknown depth write mask initial state.
   UnknownDepthWriteMaskState,
+  /// Unknown clear stencil initial state.
+  UnknownClearStencilInitialState,
+  /// Unknown stencil write mask initial state.
+  UnknownStencilWriteMaskState,
   /// Corrupted blending equation.
   UnknownBlendingEquation(u32),
   /// RGB blending equation couldn’t be retrieved when initializing the WebGL2 state.
@@ -640,6 +819,14 @@ impl fmt::Display for StateQueryError {
 
       StateQueryError::UnknownDepthWriteMaskState => f.write_str("unkonwn depth write mask state"),
 
+      StateQueryError::UnknownClearStencilInitialState => {
+        f.write_str("unknown clear stencil initial state")
+      }
+
+      StateQueryError::UnknownStencilWriteMaskState => {
+        f.write_str("unknown stencil write mask state")
+      }
+
       StateQueryError::UnknownBlendingEquation(ref e) => {
         write!(f, "unknown blending equation: {}", e)
       }
@@ -845,6 +1032,32 @@ fn get_ctx_depth_write(ctx: &mut WebGl2RenderingContext) -> Result<DepthWrite, S
   }
 }
 
+fn get_ctx_clear_stencil(ctx: &mut WebGl2RenderingContext) -> Result<u8, StateQueryError> {
+  let clear_stencil: u32 = ctx
+    .get_webgl_param(WebGl2RenderingContext::STENCIL_CLEAR_VALUE)
+    .ok_or_else(|| StateQueryError::UnknownClearStencilInitialState)?;
+
+  Ok(clear_stencil as u8)
+}
+
+fn get_ctx_stencil_test_state(ctx: &mut WebGl2RenderingContext) -> StencilTestState {
+  let enabled = ctx.is_enabled(WebGl2RenderingContext::STENCIL_TEST);
+
+  if enabled {
+    StencilTestState::On
+  } else {
+    StencilTestState::Off
+  }
+}
+
+fn get_ctx_stencil_write_mask(ctx: &mut WebGl2RenderingContext) -> Result<u8, StateQueryError> {
+  let mask: u32 = ctx
+    .get_webgl_param(WebGl2RenderingContext::STENCIL_WRITEMASK)
+    .ok_or_else(|| StateQueryError::UnknownStencilWriteMaskState)?;
+
+  Ok(mask as u8)
+}
+
 fn get_ctx_face_culling_state(ctx: &mut WebGl2RenderingContext) -> FaceCullingState {
   let enabled = ctx.is_enabled(WebGl2RenderingContext::CULL_FACE);
 
@@ -943,6 +1156,46 @@ fn load_webgl2_extensions(ctx: &mut WebGl2RenderingContext) -> Result<(), StateQ
   Ok(())
 }
 
+/// Enable the optional compressed texture extensions, and return the supported compressed pixel
+/// format families.
+fn load_webgl2_compression_extensions(ctx: &mut WebGl2RenderingContext) -> Vec<CompressionFamily> {
+  let enable = |ext: &str| matches!(ctx.get_extension(ext), Ok(Some(_)));
+
+  let mut families = Vec::new();
+
+  if enable("WEBGL_compressed_texture_s3tc") {
+    // sRGB S3TC formats live in their own extension
+    enable("WEBGL_compressed_texture_s3tc_srgb");
+    families.push(CompressionFamily::S3TC);
+  }
+
+  if enable("EXT_texture_compression_rgtc") {
+    families.push(CompressionFamily::RGTC);
+  }
+
+  if enable("EXT_texture_compression_bptc") {
+    families.push(CompressionFamily::BPTC);
+  }
+
+  if enable("WEBGL_compressed_texture_etc") {
+    families.push(CompressionFamily::ETC2);
+  }
+
+  if enable("WEBGL_compressed_texture_astc") {
+    families.push(CompressionFamily::ASTC);
+  }
+
+  families
+}
+
+/// Enable anisotropic filtering if available, returning the maximum degree of anisotropy.
+fn load_webgl2_anisotropy_extension(ctx: &mut WebGl2RenderingContext) -> Option<f32> {
+  match ctx.get_extension("EXT_texture_filter_anisotropic") {
+    Ok(Some(_)) => ctx.get_webgl_param(MAX_TEXTURE_MAX_ANISOTROPY),
+    _ => None,
+  }
+}
+
 /// Should the binding be cached or forced to the provided value?
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub(crate) enum Bind {
@@ -982,6 +1235,15 @@ pub(crate) enum DepthTest {
   Off,
 }
 
+/// Whether or not stencil test should be enabled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum StencilTestState {
+  /// The stencil test is enabled.
+  On,
+  /// The stencil test is disabled.
+  Off,
+}
+
 /// Should face culling be enabled?
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub(crate) enum FaceCullingState {
@@ -1005,6 +1267,34 @@ fn depth_comparison_to_webgl(dc: DepthComparison) -> u32 {
   }
 }
 
+#[inline]
+fn stencil_comparison_to_webgl(sc: StencilComparison) -> u32 {
+  match sc {
+    StencilComparison::Never => WebGl2RenderingContext::NEVER,
+    StencilComparison::Always => WebGl2RenderingContext::ALWAYS,
+    StencilComparison::Equal => WebGl2RenderingContext::EQUAL,
+    StencilComparison::NotEqual => WebGl2RenderingContext::NOTEQUAL,
+    StencilComparison::Less => WebGl2RenderingContext::LESS,
+    StencilComparison::LessOrEqual => WebGl2RenderingContext::LEQUAL,
+    StencilComparison::Greater => WebGl2RenderingContext::GREATER,
+    StencilComparison::GreaterOrEqual => WebGl2RenderingContext::GEQUAL,
+  }
+}
+
+#[inline]
+fn stencil_op_to_webgl(op: StencilOp) -> u32 {
+  match op {
+    StencilOp::Keep => WebGl2RenderingContext::KEEP,
+    StencilOp::Zero => WebGl2RenderingContext::ZERO,
+    StencilOp::Replace => WebGl2RenderingContext::REPLACE,
+    StencilOp::Increment => WebGl2RenderingContext::INCR,
+    StencilOp::IncrementWrap => WebGl2RenderingContext::INCR_WRAP,
+    StencilOp::Decrement => WebGl2RenderingContext::DECR,
+    StencilOp::DecrementWrap => WebGl2RenderingContext::DECR_WRAP,
+    StencilOp::Invert => WebGl2RenderingContext::INVERT,
+  }
+}
+
 #[inline]
 fn blending_equation_to_webgl(equation: Equation) -> u32 {
   match equation {
@@ -1033,6 +1323,13 @@ fn blending_factor_to_webgl(factor: Factor) -> u32 {
   }
 }
 
+fn get_ctx_uniform_buffer_offset_alignment(ctx: &mut WebGl2RenderingContext) -> usize {
+  // 256 is the largest alignment allowed by the specification
+  let alignment: Option<u32> =
+    ctx.get_webgl_param(WebGl2RenderingContext::UNIFORM_BUFFER_OFFSET_ALIGNMENT);
+  alignment.unwrap_or(256).max(1) as usize
+}
+
 // Workaround around the lack of implementor for [`TryFrom`] on [`JsValue`].
 trait GetWebGLParam<T> {
   fn get_webgl_param(&mut self, param: u32) -> Option<T>;
@@ -1048,6 +1345,16 @@ impl GetWebGLParam<u32> for WebGl2RenderingContext {
   }
 }
 
+impl GetWebGLParam<f32> for WebGl2RenderingContext {
+  fn get_webgl_param(&mut self, param: u32) -> Option<f32> {
+    self
+      .get_parameter(param)
+      .ok()
+      .and_then(|x| x.as_f64())
+      .map(|x| x as f32)
+  }
+}
+
 impl GetWebGLParam<bool> for WebGl2RenderingContext {
   fn get_webgl_param(&mut self, param: u32) -> Option<bool> {
     self.get_parameter(param).ok().and_then(|x| x.as_bool())
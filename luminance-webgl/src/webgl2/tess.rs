@@ -1,13 +1,14 @@
 //! WebGL2 tessellation implementation.
 
-use luminance::backend::buffer::BufferSlice as _;
+use luminance::backend::buffer::{Buffer as _, BufferSlice as _};
 use luminance::backend::tess::{
   IndexSlice as IndexSliceBackend, InstanceSlice as InstanceSliceBackend, Tess as TessBackend,
   VertexSlice as VertexSliceBackend,
 };
+use luminance::buffer::{BufferUsage, PreserveContent};
 use luminance::tess::{
   Deinterleaved, DeinterleavedData, Interleaved, Mode, TessError, TessIndex, TessIndexType,
-  TessMapError, TessVertexData,
+  TessMapError, TessParams, TessVertexData,
 };
 use luminance::vertex::{
   Deinterleave, Normalized, Vertex, VertexAttribDesc, VertexAttribDim, VertexAttribType,
@@ -15,6 +16,7 @@ use luminance::vertex::{
 };
 use std::cell::RefCell;
 use std::marker::PhantomData;
+use std::mem;
 use std::rc::Rc;
 use web_sys::WebGlVertexArrayObject;
 
@@ -34,6 +36,7 @@ where
   // A small note: WebGL2 doesn’t support custom primitive restart index; it assumes the maximum
   // value of I as being that restart index.
   index_buffer: Option<Buffer<I>>,
+  usage: BufferUsage,
   state: Rc<RefCell<WebGL2State>>,
 }
 
@@ -41,6 +44,42 @@ impl<I> TessRaw<I>
 where
   I: TessIndex,
 {
+  /// Bind the vertex array, so that newly created buffers get attached to it.
+  fn bind_vertex_array(&self) {
+    self
+      .state
+      .borrow_mut()
+      .bind_vertex_array(Some(&self.vao), Bind::Cached);
+  }
+
+  /// Update the number of vertices to render after the vertex storage got resized.
+  ///
+  /// Indexed tessellations render as many vertices as there are indices, so they are not impacted.
+  fn vertices_resized(&mut self, len: usize) {
+    if self.index_buffer.is_none() {
+      self.vert_nb = len;
+    }
+  }
+
+  unsafe fn resize_indices(
+    &mut self,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    match self.index_buffer {
+      Some(ref mut buffer) => WebGL2::resize(buffer, len, mem::zeroed(), preserve)?,
+
+      None => {
+        self.bind_vertex_array();
+        self.index_buffer = build_index_buffer(&self.state, vec![mem::zeroed(); len], self.usage)?;
+      }
+    }
+
+    self.vert_nb = len;
+
+    Ok(())
+  }
+
   unsafe fn render(
     &self,
     start_index: usize,
@@ -130,11 +169,16 @@ where
     vertex_data: Option<V::Data>,
     index_data: Vec<I>,
     instance_data: Option<W::Data>,
-    mode: Mode,
-    vert_nb: usize,
-    inst_nb: usize,
-    _: Option<I>,
+    params: TessParams<I>,
   ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      usage,
+      ..
+    } = params;
+
     let vao = self
       .state
       .borrow_mut()
@@ -148,9 +192,9 @@ where
       .borrow_mut()
       .bind_vertex_array(Some(&vao), Bind::Forced);
 
-    let vertex_buffer = build_interleaved_vertex_buffer(self, vertex_data)?;
-    let index_buffer = build_index_buffer(self, index_data)?;
-    let instance_buffer = build_interleaved_vertex_buffer(self, instance_data)?;
+    let vertex_buffer = build_interleaved_vertex_buffer(&self.state, vertex_data, usage)?;
+    let index_buffer = build_index_buffer(&self.state, index_data, usage)?;
+    let instance_buffer = build_interleaved_vertex_buffer(&self.state, instance_data, usage)?;
 
     let mode = webgl_mode(mode).ok_or_else(|| TessError::ForbiddenPrimitiveMode(mode))?;
     let state = self.state.clone();
@@ -160,6 +204,7 @@ where
       vert_nb,
       inst_nb,
       index_buffer,
+      usage,
       state,
     };
 
@@ -186,6 +231,36 @@ where
   ) -> Result<(), TessError> {
     tess.raw.render(start_index, vert_nb, inst_nb)
   }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved_vertex_buffer(&tess.raw, &mut tess.vertex_buffer, len, preserve)?;
+    tess.raw.vertices_resized(len);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_interleaved_vertex_buffer(&tess.raw, &mut tess.instance_buffer, len, preserve)?;
+    tess.raw.inst_nb = len;
+
+    Ok(())
+  }
 }
 
 unsafe impl<V, I, W> VertexSliceBackend<V, I, W, Interleaved, V> for WebGL2
@@ -292,11 +367,16 @@ where
     vertex_data: Option<V::Data>,
     index_data: Vec<I>,
     instance_data: Option<W::Data>,
-    mode: Mode,
-    vert_nb: usize,
-    inst_nb: usize,
-    _: Option<I>,
+    params: TessParams<I>,
   ) -> Result<Self::TessRepr, TessError> {
+    let TessParams {
+      mode,
+      vert_nb,
+      inst_nb,
+      usage,
+      ..
+    } = params;
+
     let vao = self
       .state
       .borrow_mut()
@@ -310,9 +390,10 @@ where
       .borrow_mut()
       .bind_vertex_array(Some(&vao), Bind::Forced);
 
-    let vertex_buffers = build_deinterleaved_vertex_buffers::<V>(self, vertex_data)?;
-    let index_buffer = build_index_buffer(self, index_data)?;
-    let instance_buffers = build_deinterleaved_vertex_buffers::<W>(self, instance_data)?;
+    let vertex_buffers = build_deinterleaved_vertex_buffers::<V>(&self.state, vertex_data, usage)?;
+    let index_buffer = build_index_buffer(&self.state, index_data, usage)?;
+    let instance_buffers =
+      build_deinterleaved_vertex_buffers::<W>(&self.state, instance_data, usage)?;
 
     let mode = webgl_mode(mode).ok_or_else(|| TessError::ForbiddenPrimitiveMode(mode))?;
     let state = self.state.clone();
@@ -322,6 +403,7 @@ where
       vert_nb,
       inst_nb,
       index_buffer,
+      usage,
       state,
     };
 
@@ -349,6 +431,46 @@ where
   ) -> Result<(), TessError> {
     tess.raw.render(start_index, vert_nb, inst_nb)
   }
+
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved_vertex_buffers::<V, I>(
+      &tess.raw,
+      &mut tess.vertex_buffers,
+      len,
+      preserve,
+    )?;
+    tess.raw.vertices_resized(len);
+
+    Ok(())
+  }
+
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    tess.raw.resize_indices(len, preserve)
+  }
+
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    resize_deinterleaved_vertex_buffers::<W, I>(
+      &tess.raw,
+      &mut tess.instance_buffers,
+      len,
+      preserve,
+    )?;
+    tess.raw.inst_nb = len;
+
+    Ok(())
+  }
 }
 
 unsafe impl<V, I, W, T> VertexSliceBackend<V, I, W, Deinterleaved, T> for WebGL2
@@ -442,8 +564,9 @@ where
 }
 
 fn build_interleaved_vertex_buffer<V>(
-  webgl2: &mut WebGL2,
+  state: &Rc<RefCell<WebGL2State>>,
   vertices: Option<Vec<V>>,
+  usage: BufferUsage,
 ) -> Result<Option<Buffer<V>>, TessError>
 where
   V: Vertex,
@@ -455,14 +578,12 @@ where
       let vb = if vertices.is_empty() {
         None
       } else {
-        let vb = Buffer::from_vec(webgl2, vertices, WebGl2RenderingContext::ARRAY_BUFFER)?;
+        let vb = Buffer::from_vec(state, vertices, WebGl2RenderingContext::ARRAY_BUFFER, usage)?;
 
         // force binding as it’s meaningful when a vao is bound
-        webgl2
-          .state
-          .borrow_mut()
-          .bind_array_buffer(Some(vb.handle()), Bind::Forced);
-        set_vertex_pointers(&mut webgl2.state.borrow_mut().ctx, &fmt);
+        let mut state = state.borrow_mut();
+        state.bind_array_buffer(Some(vb.handle()), Bind::Forced);
+        set_vertex_pointers(&mut state.ctx, &fmt);
 
         Some(vb)
       };
@@ -475,51 +596,121 @@ where
 }
 
 fn build_deinterleaved_vertex_buffers<V>(
-  webgl2: &mut WebGL2,
+  state: &Rc<RefCell<WebGL2State>>,
   vertices: Option<Vec<DeinterleavedData>>,
+  usage: BufferUsage,
 ) -> Result<Vec<Buffer<u8>>, TessError>
 where
   V: Vertex,
 {
   match vertices {
-    Some(attributes) => {
-      attributes
-        .into_iter()
-        .zip(V::vertex_desc())
-        .map(|(attribute, fmt)| {
-          let vb = Buffer::from_vec(
-            webgl2,
-            attribute.into_vec(),
-            WebGl2RenderingContext::ARRAY_BUFFER,
-          )?;
-
-          // force binding as it’s meaningful when a vao is bound
-          webgl2
-            .state
-            .borrow_mut()
-            .bind_array_buffer(Some(vb.handle()), Bind::Forced);
-          set_vertex_pointers(&mut webgl2.state.borrow_mut().ctx, &[fmt]);
-
-          Ok(vb)
-        })
-        .collect::<Result<Vec<_>, _>>()
-    }
+    Some(attributes) => attributes
+      .into_iter()
+      .zip(V::vertex_desc())
+      .map(|(attribute, fmt)| build_attribute_buffer(state, attribute.into_vec(), fmt, usage))
+      .collect::<Result<Vec<_>, _>>(),
 
     None => Ok(Vec::new()),
   }
 }
 
+/// Create the buffer of a single deinterleaved attribute.
+fn build_attribute_buffer(
+  state: &Rc<RefCell<WebGL2State>>,
+  bytes: Vec<u8>,
+  fmt: VertexBufferDesc,
+  usage: BufferUsage,
+) -> Result<Buffer<u8>, TessError> {
+  let vb = Buffer::from_vec(state, bytes, WebGl2RenderingContext::ARRAY_BUFFER, usage)?;
+
+  // force binding as it’s meaningful when a vao is bound
+  let mut state = state.borrow_mut();
+  state.bind_array_buffer(Some(vb.handle()), Bind::Forced);
+  set_vertex_pointers(&mut state.ctx, &[fmt]);
+
+  Ok(vb)
+}
+
+/// Resize the buffer of interleaved vertices or instances.
+///
+/// If the buffer doesn’t exist, it is created, unless the vertices are attributeless.
+unsafe fn resize_interleaved_vertex_buffer<V, I>(
+  raw: &TessRaw<I>,
+  buffer: &mut Option<Buffer<V>>,
+  len: usize,
+  preserve: PreserveContent,
+) -> Result<(), TessError>
+where
+  V: Vertex,
+  I: TessIndex,
+{
+  match buffer {
+    // vertices are made of plain numbers, for which zero bits are valid values
+    Some(ref mut vb) => WebGL2::resize(vb, len, mem::zeroed(), preserve)?,
+
+    None if !V::vertex_desc().is_empty() => {
+      raw.bind_vertex_array();
+      *buffer =
+        build_interleaved_vertex_buffer(&raw.state, Some(vec![mem::zeroed(); len]), raw.usage)?;
+    }
+
+    None => (),
+  }
+
+  Ok(())
+}
+
+/// Resize the buffers of deinterleaved vertices or instances.
+///
+/// If the buffers don’t exist, they are created, unless the vertices are attributeless.
+unsafe fn resize_deinterleaved_vertex_buffers<V, I>(
+  raw: &TessRaw<I>,
+  buffers: &mut Vec<Buffer<u8>>,
+  len: usize,
+  preserve: PreserveContent,
+) -> Result<(), TessError>
+where
+  V: Vertex,
+  I: TessIndex,
+{
+  if buffers.is_empty() {
+    raw.bind_vertex_array();
+
+    *buffers = V::vertex_desc()
+      .into_iter()
+      .map(|fmt| {
+        let bytes = vec![0; len * component_weight(&fmt.attrib_desc)];
+        build_attribute_buffer(&raw.state, bytes, fmt, raw.usage)
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+  } else {
+    for (vb, fmt) in buffers.iter_mut().zip(V::vertex_desc()) {
+      WebGL2::resize(vb, len * component_weight(&fmt.attrib_desc), 0, preserve)?;
+    }
+  }
+
+  Ok(())
+}
+
 /// Turn a [`Vec`] of indices to a [`Buffer`], if indices are present.
-fn build_index_buffer<I>(webgl2: &mut WebGL2, data: Vec<I>) -> Result<Option<Buffer<I>>, TessError>
+fn build_index_buffer<I>(
+  state: &Rc<RefCell<WebGL2State>>,
+  data: Vec<I>,
+  usage: BufferUsage,
+) -> Result<Option<Buffer<I>>, TessError>
 where
   I: TessIndex,
 {
   let ib = if !data.is_empty() {
-    let ib = Buffer::from_vec(webgl2, data, WebGl2RenderingContext::ELEMENT_ARRAY_BUFFER)?;
+    let ib = Buffer::from_vec(
+      state,
+      data,
+      WebGl2RenderingContext::ELEMENT_ARRAY_BUFFER,
+      usage,
+    )?;
 
     // force binding as it’s meaningful when a vao is bound
-    webgl2
-      .state
+    state
       .borrow_mut()
       .bind_element_array_buffer(Some(ib.handle()), Bind::Forced);
 
@@ -1,6 +1,10 @@
-use luminance::backend::texture::{Texture as TextureBackend, TextureBase};
+use luminance::backend::texture::{
+  Texture as TextureBackend, TextureBase, TextureCompressed as TextureCompressedBackend,
+  TextureCompression, TextureReadback as TextureReadbackBackend,
+};
 use luminance::depth_test::DepthComparison;
-use luminance::pixel::{Pixel, PixelFormat};
+use luminance::framebuffer::{BlitFilter, BlitMask, BlitRect};
+use luminance::pixel::{CompressedPixel, CompressionFamily, Pixel, PixelFormat};
 use luminance::texture::{
   Dim, Dimensionable, GenMipmaps, MagFilter, MinFilter, Sampler, TextureError, Wrap,
 };
@@ -8,10 +12,13 @@ use std::cell::RefCell;
 use std::mem;
 use std::rc::Rc;
 use std::slice;
+use wasm_bindgen::JsValue;
 use web_sys::{WebGl2RenderingContext, WebGlTexture};
 
 use crate::webgl2::array_buffer::IntoArrayBuffer;
-use crate::webgl2::pixel::webgl_pixel_format;
+use crate::webgl2::framebuffer::{blit, depth_attachment};
+use crate::webgl2::pixel::{webgl_compressed_format, webgl_pixel_format};
+use crate::webgl2::readback::Readback;
 use crate::webgl2::state::WebGL2State;
 use crate::webgl2::WebGL2;
 
@@ -63,19 +70,31 @@ where
 
     let mut state = self.state.borrow_mut();
 
+    let pf = P::pixel_format();
+    if let Some(compression) = pf.format.compression() {
+      if !state.supports_compression(compression.family()) {
+        return Err(TextureError::unsupported_pixel_format(pf));
+      }
+    }
+
+    if sampler.lod_bias != 0. {
+      return Err(TextureError::texture_storage_creation_failed(
+        "LOD bias is not supported",
+      ));
+    }
+
+    if [sampler.wrap_r, sampler.wrap_s, sampler.wrap_t].contains(&Wrap::ClampToBorder) {
+      return Err(TextureError::texture_storage_creation_failed(
+        "border clamping is not supported",
+      ));
+    }
+
     let handle = state.create_texture().ok_or_else(|| {
       TextureError::TextureStorageCreationFailed("cannot create texture".to_owned())
     })?;
     state.bind_texture(target, Some(&handle));
 
-    setup_texture::<D>(
-      &mut state,
-      target,
-      size,
-      mipmaps,
-      P::pixel_format(),
-      sampler,
-    )?;
+    setup_texture::<D>(&mut state, target, size, mipmaps, pf, sampler)?;
 
     let texture = Texture {
       handle,
@@ -127,7 +146,7 @@ where
 
     gfx_state.bind_texture(texture.target, Some(&texture.handle));
 
-    upload_texels::<D, P, P::Encoding>(&mut gfx_state, texture.target, offset, size, texels)?;
+    upload_texels::<D, P, P::Encoding>(&mut gfx_state, texture.target, 0, offset, size, texels)?;
 
     if gen_mipmaps == GenMipmaps::Yes {
       gfx_state.ctx.generate_mipmap(texture.target);
@@ -156,7 +175,7 @@ where
 
     gfx_state.bind_texture(texture.target, Some(&texture.handle));
 
-    upload_texels::<D, P, P::RawEncoding>(&mut gfx_state, texture.target, offset, size, texels)?;
+    upload_texels::<D, P, P::RawEncoding>(&mut gfx_state, texture.target, 0, offset, size, texels)?;
 
     if gen_mipmaps == GenMipmaps::Yes {
       gfx_state.ctx.generate_mipmap(texture.target);
@@ -188,78 +207,554 @@ where
     P::RawEncoding: Copy + Default,
   {
     let pf = P::pixel_format();
-    let (format, _, ty) = webgl_pixel_format(pf).ok_or(TextureError::UnsupportedPixelFormat(pf))?;
 
+    // WebGL2 doesn’t support the glGetTexLevelParameteriv function, so the size of the texture is
+    // taken from the CPU side
+    read_raw_texels::<D, _>(texture, pf, 0, [0; 3], base_extent::<D>(size))
+  }
+
+  unsafe fn copy_from(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    src: &Self::TextureRepr,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError> {
+    let mut gfx_state = texture.state.borrow_mut();
+
+    copy_texels::<D>(
+      &mut gfx_state,
+      texture,
+      src,
+      P::pixel_format(),
+      src_offset,
+      dst_offset,
+      size,
+    )?;
+
+    if gen_mipmaps == GenMipmaps::Yes {
+      gfx_state.bind_texture(texture.target, Some(&texture.handle));
+      gfx_state.ctx.generate_mipmap(texture.target);
+    }
+
+    Ok(())
+  }
+
+  unsafe fn upload_level_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
     let mut gfx_state = texture.state.borrow_mut();
+
     gfx_state.bind_texture(texture.target, Some(&texture.handle));
 
-    // Retrieve the size of the texture (w and h); WebGL2 doesn’t support the
-    // glGetTexLevelParameteriv function (I know it’s fucking surprising), so we have to implement
-    // a workaround and store that value on the CPU side.
-    let w = D::width(size);
-    let h = D::height(size);
-
-    // set the packing alignment based on the number of bytes to skip
-    let skip_bytes = (pf.format.size() * w as usize) % 8;
-    set_pack_alignment(&mut gfx_state, skip_bytes);
-
-    // We need a workaround to get the texel data, because WebGL2 doesn’t support the glGetTexImage
-    // function. The idea is that we are using a special read framebuffer that is always around and
-    // on which we can attach the texture we want to read the texels from.
-    match gfx_state.create_or_get_readback_framebuffer() {
-      Some(ref readback_fb) => {
-        // Resize the vec to allocate enough space to host the returned texels.
-        let texels_nb = (w * h) as usize * pf.canals_len();
-        let mut texels = vec![Default::default(); texels_nb];
-
-        // Attach the texture so that we can read from the framebuffer; careful here, since we are
-        // reading from a 2D texture while the attached texture might not be compatible.
-        gfx_state.bind_read_framebuffer(Some(readback_fb));
-        gfx_state.ctx.framebuffer_texture_2d(
-          WebGl2RenderingContext::READ_FRAMEBUFFER,
-          WebGl2RenderingContext::COLOR_ATTACHMENT0,
-          texture.target,
-          Some(&texture.handle),
-          0,
-        );
+    upload_texels::<D, P, P::Encoding>(&mut gfx_state, texture.target, level, offset, size, texels)
+  }
 
-        // Read from the framebuffer.
-        gfx_state
-          .ctx
-          .read_pixels_with_u8_array_and_dst_offset(
-            0,
-            0,
-            w as i32,
-            h as i32,
-            format,
-            ty,
-            slice::from_raw_parts_mut(
-              texels.as_mut_ptr() as *mut u8,
-              texels_nb * mem::size_of::<P::RawEncoding>(),
-            ),
-            0,
-          )
-          .map_err(|e| TextureError::CannotRetrieveTexels(format!("{:?}", e)))?;
-
-        // Detach the texture from the framebuffer.
-        gfx_state.ctx.framebuffer_texture_2d(
-          WebGl2RenderingContext::READ_FRAMEBUFFER,
-          WebGl2RenderingContext::COLOR_ATTACHMENT0,
-          texture.target,
-          None,
-          0,
-        );
+  unsafe fn upload_level_part_raw(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    let mut gfx_state = texture.state.borrow_mut();
+
+    gfx_state.bind_texture(texture.target, Some(&texture.handle));
+
+    upload_texels::<D, P, P::RawEncoding>(
+      &mut gfx_state,
+      texture.target,
+      level,
+      offset,
+      size,
+      texels,
+    )
+  }
+
+  unsafe fn get_level_part_raw_texels(
+    texture: &Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let (offset, size) = region::<D>(offset, size);
+    read_raw_texels::<D, _>(texture, P::pixel_format(), level, offset, size)
+  }
+
+  unsafe fn generate_mipmaps(texture: &mut Self::TextureRepr) -> Result<(), TextureError> {
+    let mut gfx_state = texture.state.borrow_mut();
+
+    gfx_state.bind_texture(texture.target, Some(&texture.handle));
+    gfx_state.ctx.generate_mipmap(texture.target);
+
+    Ok(())
+  }
+}
+
+unsafe impl<D, P> TextureReadbackBackend<D, P> for WebGL2
+where
+  D: Dimensionable,
+  P: Pixel,
+  P::Encoding: IntoArrayBuffer,
+  P::RawEncoding: Copy + IntoArrayBuffer,
+{
+  unsafe fn get_raw_texels_async(
+    texture: &Self::TextureRepr,
+    size: D::Size,
+  ) -> Result<Self::ReadbackRepr, TextureError> {
+    let pf = P::pixel_format();
+    let extent = base_extent::<D>(size);
+    let layer_len = (extent[0] * extent[1]) as usize * pf.canals_len();
+    let layer_bytes = layer_len * mem::size_of::<P::RawEncoding>();
+    let len = layer_len * extent[2] as usize;
+
+    // the state must not be borrowed anymore when inserting the fence
+    let staging = {
+      let mut gfx_state = texture.state.borrow_mut();
+      let staging = Readback::<P::RawEncoding>::new_staging_buffer(
+        &mut gfx_state,
+        WebGl2RenderingContext::PIXEL_PACK_BUFFER,
+        len,
+      )
+      .ok_or_else(|| TextureError::cannot_retrieve_texels("cannot create readback buffer"))?;
+
+      // the texels are packed to the staging buffer, layer after layer
+      let result = read_texels::<D>(
+        &mut gfx_state,
+        texture,
+        pf,
+        0,
+        [0; 3],
+        extent,
+        |gfx_state, [x, y, w, h], format, ty, layer| {
+          gfx_state
+            .ctx
+            .read_pixels_with_i32(x, y, w, h, format, ty, (layer * layer_bytes) as i32)
+        },
+      );
+
+      gfx_state
+        .ctx
+        .bind_buffer(WebGl2RenderingContext::PIXEL_PACK_BUFFER, None);
+
+      if let Err(e) = result {
+        gfx_state.ctx.delete_buffer(Some(&staging));
+        return Err(e);
+      }
+
+      staging
+    };
+
+    Readback::from_handle(&texture.state, staging, len)
+      .map_err(|_| TextureError::cannot_retrieve_texels("cannot create readback fence"))
+  }
+}
 
-        Ok(texels)
+unsafe impl TextureCompression for WebGL2 {
+  unsafe fn supports_compression(&mut self, family: CompressionFamily) -> bool {
+    self.state.borrow().supports_compression(family)
+  }
+}
+
+unsafe impl<D, P> TextureCompressedBackend<D, P> for WebGL2
+where
+  D: Dimensionable,
+  P: CompressedPixel,
+  P::Encoding: IntoArrayBuffer,
+  P::RawEncoding: IntoArrayBuffer,
+{
+  unsafe fn upload_compressed_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    let mut state = texture.state.borrow_mut();
+
+    state.bind_texture(texture.target, Some(&texture.handle));
+
+    let r = upload_compressed_blocks::<D>(
+      &mut state,
+      texture.target,
+      P::pixel_format(),
+      level,
+      [
+        D::x_offset(offset),
+        D::y_offset(offset),
+        D::z_offset(offset),
+      ],
+      [D::width(size), D::height(size), D::depth(size)],
+      blocks,
+    );
+
+    state.bind_texture(texture.target, None);
+
+    r
+  }
+
+  unsafe fn upload_compressed(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    let mut state = texture.state.borrow_mut();
+
+    state.bind_texture(texture.target, Some(&texture.handle));
+
+    let pf = P::pixel_format();
+    let [w, h] = compressed_level_size::<D>(size, level);
+    let r = match D::dim() {
+      Dim::Cubemap => {
+        blocks
+          .chunks(blocks.len() / 6)
+          .zip(0..6)
+          .try_for_each(|(face_blocks, face)| {
+            upload_compressed_blocks::<D>(
+              &mut state,
+              texture.target,
+              pf,
+              level,
+              [0, 0, face],
+              [w, h, 1],
+              face_blocks,
+            )
+          })
       }
 
-      None => Err(TextureError::cannot_retrieve_texels(
-        "unavailable readback framebuffer",
-      )),
+      _ => upload_compressed_blocks::<D>(
+        &mut state,
+        texture.target,
+        pf,
+        level,
+        [0, 0, 0],
+        [w, h, D::depth(size)],
+        blocks,
+      ),
+    };
+
+    state.bind_texture(texture.target, None);
+
+    r
+  }
+}
+
+/// Read the texels of the region of size `[w, h, layers]` at `[x, y, z]` in the mipmap `level` of
+/// `texture` to client memory.
+fn read_raw_texels<D, T>(
+  texture: &Texture,
+  pf: PixelFormat,
+  level: usize,
+  offset: [u32; 3],
+  size: [u32; 3],
+) -> Result<Vec<T>, TextureError>
+where
+  D: Dimensionable,
+  T: Copy + Default,
+{
+  let layer_len = (size[0] * size[1]) as usize * pf.canals_len();
+  let layer_bytes = layer_len * mem::size_of::<T>();
+  let mut texels = vec![T::default(); layer_len * size[2] as usize];
+  let bytes = unsafe {
+    slice::from_raw_parts_mut(
+      texels.as_mut_ptr() as *mut u8,
+      mem::size_of_val(&texels[..]),
+    )
+  };
+
+  let mut gfx_state = texture.state.borrow_mut();
+  read_texels::<D>(
+    &mut gfx_state,
+    texture,
+    pf,
+    level,
+    offset,
+    size,
+    |gfx_state, [x, y, w, h], format, ty, layer| {
+      gfx_state.ctx.read_pixels_with_u8_array_and_dst_offset(
+        x,
+        y,
+        w,
+        h,
+        format,
+        ty,
+        bytes,
+        (layer * layer_bytes) as u32,
+      )
+    },
+  )?;
+
+  Ok(texels)
+}
+
+/// Read the texels of the region of size `[w, h, layers]` at `[x, y, z]` in the mipmap `level` of
+/// `texture` with `read_pixels`, which gets the rectangle to read in a layer along with its format
+/// and type, and the index of the layer in the region.
+///
+/// The third axis is the layers of array textures and the faces of cubemaps.
+fn read_texels<D>(
+  gfx_state: &mut WebGL2State,
+  texture: &Texture,
+  pf: PixelFormat,
+  level: usize,
+  [x, y, z]: [u32; 3],
+  [w, h, layers]: [u32; 3],
+  mut read_pixels: impl FnMut(&mut WebGL2State, [i32; 4], u32, u32, usize) -> Result<(), JsValue>,
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let (format, _, ty) = webgl_pixel_format(pf).ok_or(TextureError::UnsupportedPixelFormat(pf))?;
+
+  gfx_state.bind_texture(texture.target, Some(&texture.handle));
+
+  // set the packing alignment based on the number of bytes to skip
+  let skip_bytes = (pf.format.size() * w as usize) % 8;
+  set_pack_alignment(gfx_state, skip_bytes);
+
+  // We need a workaround to get the texel data, because WebGL2 doesn’t support the glGetTexImage
+  // function. The idea is that we are using a special read framebuffer that is always around and
+  // on which we can attach the layers of the texture we want to read the texels from.
+  let readback_fb = gfx_state
+    .create_or_get_readback_framebuffer()
+    .ok_or_else(|| TextureError::cannot_retrieve_texels("unavailable readback framebuffer"))?;
+
+  gfx_state.bind_read_framebuffer(Some(&readback_fb));
+
+  let mut result = Ok(());
+
+  for layer in 0..layers {
+    attach_layer::<D>(
+      gfx_state,
+      WebGl2RenderingContext::READ_FRAMEBUFFER,
+      WebGl2RenderingContext::COLOR_ATTACHMENT0,
+      Some(texture),
+      level,
+      z + layer,
+    );
+
+    // Read from the framebuffer.
+    let rect = [x as i32, y as i32, w as i32, h as i32];
+    result = read_pixels(gfx_state, rect, format, ty, layer as usize)
+      .map_err(|e| TextureError::CannotRetrieveTexels(format!("{:?}", e)));
+
+    if result.is_err() {
+      break;
+    }
+  }
+
+  // Detach the texture from the framebuffer.
+  attach_layer::<D>(
+    gfx_state,
+    WebGl2RenderingContext::READ_FRAMEBUFFER,
+    WebGl2RenderingContext::COLOR_ATTACHMENT0,
+    None,
+    0,
+    z,
+  );
+
+  result
+}
+
+/// Copy a region of the base level of `src` to the base level of `dst`.
+///
+/// There’s no glCopyImageSubData in WebGL2, so each layer of the region is blitted between two
+/// temporary framebuffers instead.
+fn copy_texels<D>(
+  gfx_state: &mut WebGL2State,
+  dst: &Texture,
+  src: &Texture,
+  pf: PixelFormat,
+  src_offset: D::Offset,
+  dst_offset: D::Offset,
+  size: D::Size,
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let (attachment, mask) = if pf.is_depth_pixel() {
+    (depth_attachment(Some(pf)), BlitMask::DEPTH)
+  } else {
+    (WebGl2RenderingContext::COLOR_ATTACHMENT0, BlitMask::COLOR)
+  };
+  let (src_rect, src_layer, layers) = blit_region::<D>(src_offset, size);
+  let (dst_rect, dst_layer, _) = blit_region::<D>(dst_offset, size);
+
+  let (read_fb, draw_fb) = match (
+    gfx_state.create_framebuffer(),
+    gfx_state.create_framebuffer(),
+  ) {
+    (Some(read_fb), Some(draw_fb)) => (read_fb, draw_fb),
+    _ => {
+      return Err(TextureError::cannot_copy_texels(
+        "cannot create copy framebuffers",
+      ))
+    }
+  };
+
+  gfx_state.bind_draw_framebuffer(Some(&draw_fb));
+  gfx_state.bind_read_framebuffer(Some(&read_fb));
+
+  if pf.is_depth_pixel() {
+    gfx_state.ctx.read_buffer(WebGl2RenderingContext::NONE);
+    gfx_state
+      .ctx
+      .draw_buffers(&WebGl2RenderingContext::NONE.into());
+  }
+
+  let mut result = Ok(());
+
+  for layer in 0..layers {
+    attach_layer::<D>(
+      gfx_state,
+      WebGl2RenderingContext::READ_FRAMEBUFFER,
+      attachment,
+      Some(src),
+      0,
+      src_layer + layer,
+    );
+    attach_layer::<D>(
+      gfx_state,
+      WebGl2RenderingContext::DRAW_FRAMEBUFFER,
+      attachment,
+      Some(dst),
+      0,
+      dst_layer + layer,
+    );
+
+    let complete = |gfx_state: &WebGL2State, target| {
+      gfx_state.ctx.check_framebuffer_status(target) == WebGl2RenderingContext::FRAMEBUFFER_COMPLETE
+    };
+
+    if !complete(gfx_state, WebGl2RenderingContext::READ_FRAMEBUFFER)
+      || !complete(gfx_state, WebGl2RenderingContext::DRAW_FRAMEBUFFER)
+    {
+      result = Err(TextureError::cannot_copy_texels(
+        "texture cannot be attached to a framebuffer",
+      ));
+      break;
     }
+
+    blit(gfx_state, src_rect, dst_rect, mask, BlitFilter::Nearest);
+  }
+
+  gfx_state.bind_draw_framebuffer(None);
+  gfx_state.ctx.delete_framebuffer(Some(&read_fb));
+  gfx_state.ctx.delete_framebuffer(Some(&draw_fb));
+
+  result
+}
+
+/// Rectangle, first layer and number of layers of a region, as blitted by [`copy_texels`].
+///
+/// Cubemap regions only cover the face they are offset to.
+fn blit_region<D>(offset: D::Offset, size: D::Size) -> (BlitRect, u32, u32)
+where
+  D: Dimensionable,
+{
+  let rect = BlitRect::new(
+    D::x_offset(offset),
+    D::y_offset(offset),
+    D::width(size),
+    D::height(size),
+  );
+
+  match D::dim() {
+    Dim::Cubemap => (rect, D::z_offset(offset), 1),
+    Dim::Dim3 | Dim::Dim2Array => (rect, D::z_offset(offset), D::depth(size)),
+    _ => (rect, 0, 1),
+  }
+}
+
+/// Attach a layer of the mipmap `level` of `texture` to the framebuffer bound to `target`, or
+/// detach the texture attached to it if `texture` is `None`.
+fn attach_layer<D>(
+  gfx_state: &mut WebGL2State,
+  target: u32,
+  attachment: u32,
+  texture: Option<&Texture>,
+  level: usize,
+  layer: u32,
+) where
+  D: Dimensionable,
+{
+  let handle = texture.map(Texture::handle);
+  let level = level as i32;
+
+  match D::dim() {
+    Dim::Cubemap => gfx_state.ctx.framebuffer_texture_2d(
+      target,
+      attachment,
+      WebGl2RenderingContext::TEXTURE_CUBE_MAP_POSITIVE_X + layer,
+      handle,
+      level,
+    ),
+
+    Dim::Dim3 | Dim::Dim2Array => {
+      gfx_state
+        .ctx
+        .framebuffer_texture_layer(target, attachment, handle, level, layer as i32)
+    }
+
+    _ => gfx_state.ctx.framebuffer_texture_2d(
+      target,
+      attachment,
+      WebGl2RenderingContext::TEXTURE_2D,
+      handle,
+      level,
+    ),
+  }
+}
+
+/// Offset and size of the region described by `offset` and `size`, as three-dimensional vectors —
+/// the third axis being layers, or faces for cubemaps.
+///
+/// Cubemap regions only cover the face they are offset to.
+fn region<D>(offset: D::Offset, size: D::Size) -> ([u32; 3], [u32; 3])
+where
+  D: Dimensionable,
+{
+  let xy = [D::x_offset(offset), D::y_offset(offset)];
+  let wh = [D::width(size), D::height(size)];
+
+  match D::dim() {
+    Dim::Dim2 => ([xy[0], xy[1], 0], [wh[0], wh[1], 1]),
+    Dim::Cubemap => ([xy[0], xy[1], D::z_offset(offset)], [wh[0], wh[1], 1]),
+    _ => (
+      [xy[0], xy[1], D::z_offset(offset)],
+      [wh[0], wh[1], D::depth(size)],
+    ),
   }
 }
 
+/// Extent of the base level of a texture of size `size`, the third axis being its layers — or
+/// faces, for cubemaps.
+fn base_extent<D>(size: D::Size) -> [u32; 3]
+where
+  D: Dimensionable,
+{
+  let layers = match D::dim() {
+    Dim::Dim2 => 1,
+    Dim::Cubemap => 6,
+    _ => D::depth(size),
+  };
+
+  [D::width(size), D::height(size), layers]
+}
+
+// anisotropic filtering, from EXT_texture_filter_anisotropic
+const TEXTURE_MAX_ANISOTROPY: u32 = 0x84FE;
+pub(crate) const MAX_TEXTURE_MAX_ANISOTROPY: u32 = 0x84FF;
+
 pub(crate) fn opengl_target(d: Dim) -> Option<u32> {
   match d {
     Dim::Dim2 => Some(WebGl2RenderingContext::TEXTURE_2D),
@@ -282,20 +777,30 @@ pub(crate) unsafe fn setup_texture<D>(
 where
   D: Dimensionable,
 {
-  set_texture_levels(state, target, mipmaps);
+  set_texture_levels(state, target, mipmaps, &sampler);
   apply_sampler_to_texture(state, target, sampler);
-  create_texture_storage::<D>(state, size, mipmaps, pf)
+
+  match pf.format.compression() {
+    Some(_) => create_compressed_texture_storage::<D>(state, size, mipmaps, pf),
+    None => create_texture_storage::<D>(state, size, mipmaps, pf),
+  }
 }
 
-fn set_texture_levels(state: &mut WebGL2State, target: u32, mipmaps: usize) {
-  state
-    .ctx
-    .tex_parameteri(target, WebGl2RenderingContext::TEXTURE_BASE_LEVEL, 0);
+fn set_texture_levels(state: &mut WebGL2State, target: u32, mipmaps: usize, sampler: &Sampler) {
+  let max_level = sampler
+    .max_level
+    .map_or(mipmaps - 1, |level| level.min(mipmaps - 1));
+
+  state.ctx.tex_parameteri(
+    target,
+    WebGl2RenderingContext::TEXTURE_BASE_LEVEL,
+    sampler.base_level as i32,
+  );
 
   state.ctx.tex_parameteri(
     target,
     WebGl2RenderingContext::TEXTURE_MAX_LEVEL,
-    mipmaps as i32 - 1,
+    max_level as i32,
   );
 }
 
@@ -348,6 +853,28 @@ fn apply_sampler_to_texture(state: &mut WebGL2State, target: u32, sampler: Sampl
       );
     }
   }
+
+  state.ctx.tex_parameterf(
+    target,
+    WebGl2RenderingContext::TEXTURE_MIN_LOD,
+    sampler.min_lod,
+  );
+  state.ctx.tex_parameterf(
+    target,
+    WebGl2RenderingContext::TEXTURE_MAX_LOD,
+    sampler.max_lod,
+  );
+
+  // anisotropic filtering is silently disabled without EXT_texture_filter_anisotropic
+  if let Some(max_anisotropy) = state.max_anisotropy() {
+    state.ctx.tex_parameterf(
+      target,
+      TEXTURE_MAX_ANISOTROPY,
+      sampler.max_anisotropy.max(1.).min(max_anisotropy),
+    );
+  }
+
+  // cubemaps are always seamless with WebGL2, so there is nothing to do with seamless_cubemap
 }
 
 fn webgl_wrap(wrap: Wrap) -> u32 {
@@ -355,6 +882,8 @@ fn webgl_wrap(wrap: Wrap) -> u32 {
     Wrap::ClampToEdge => WebGl2RenderingContext::CLAMP_TO_EDGE,
     Wrap::Repeat => WebGl2RenderingContext::REPEAT,
     Wrap::MirroredRepeat => WebGl2RenderingContext::MIRRORED_REPEAT,
+    // rejected when creating textures
+    Wrap::ClampToBorder => WebGl2RenderingContext::CLAMP_TO_EDGE,
   }
 }
 
@@ -445,6 +974,42 @@ where
   }
 }
 
+fn create_compressed_texture_storage<D>(
+  state: &mut WebGL2State,
+  size: D::Size,
+  mipmaps: usize,
+  pf: PixelFormat,
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let iformat = webgl_compressed_format(pf).ok_or(TextureError::unsupported_pixel_format(pf))?;
+
+  match D::dim() {
+    // cubemaps get their six faces from a single 2D storage
+    Dim::Dim2 | Dim::Cubemap => create_texture_2d_storage(
+      state,
+      opengl_target(D::dim()).unwrap(),
+      iformat,
+      D::width(size),
+      D::height(size),
+      mipmaps,
+    ),
+
+    Dim::Dim2Array => create_texture_3d_storage(
+      state,
+      WebGl2RenderingContext::TEXTURE_2D_ARRAY,
+      iformat,
+      D::width(size),
+      D::height(size),
+      D::depth(size),
+      mipmaps,
+    ),
+
+    _ => Err(TextureError::unsupported_pixel_format(pf)),
+  }
+}
+
 fn create_texture_2d_storage(
   state: &mut WebGL2State,
   target: u32,
@@ -490,7 +1055,7 @@ fn create_cubemap_storage(
   mipmaps: usize,
 ) -> Result<(), TextureError> {
   for level in 0..mipmaps {
-    let s = s / (1 << level as u32);
+    let s = (s >> level).max(1);
 
     for face in 0..6 {
       state
@@ -540,10 +1105,12 @@ fn set_pack_alignment(state: &mut WebGL2State, skip_bytes: usize) {
     .ctx
     .pixel_storei(WebGl2RenderingContext::PACK_ALIGNMENT, pack_alignment);
 }
-// Upload texels into the texture’s memory. Becareful of the type of texels you send down.
+// Upload texels into the mipmap `level` of the texture’s memory. Becareful of the type of texels
+// you send down.
 fn upload_texels<D, P, T>(
   state: &mut WebGL2State,
   target: u32,
+  level: usize,
   off: D::Offset,
   size: D::Size,
   texels: &[T],
@@ -584,7 +1151,7 @@ where
           .ctx
           .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_array_buffer_view_and_src_offset(
             target,
-            0,
+            level as i32,
             D::x_offset(off) as i32,
             D::y_offset(off) as i32,
             D::width(size) as i32,
@@ -602,7 +1169,7 @@ where
           .ctx
           .tex_sub_image_3d_with_opt_array_buffer_view(
             target,
-            0,
+            level as i32,
             D::x_offset(off) as i32,
             D::y_offset(off) as i32,
             D::z_offset(off) as i32,
@@ -621,7 +1188,7 @@ where
           .ctx
           .tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_array_buffer_view_and_src_offset(
             WebGl2RenderingContext::TEXTURE_CUBE_MAP_POSITIVE_X + D::z_offset(off),
-            0,
+            level as i32,
             D::x_offset(off) as i32,
             D::y_offset(off) as i32,
             D::width(size) as i32,
@@ -639,7 +1206,7 @@ where
           .ctx
           .tex_sub_image_3d_with_opt_array_buffer_view(
             target,
-            0,
+            level as i32,
             D::x_offset(off) as i32,
             D::y_offset(off) as i32,
             D::z_offset(off) as i32,
@@ -662,6 +1229,82 @@ where
   Ok(())
 }
 
+/// Width and height of the mipmap `level` of a compressed texture of size `size`.
+fn compressed_level_size<D>(size: D::Size, level: usize) -> [u32; 2]
+where
+  D: Dimensionable,
+{
+  let halve = |x: u32| x.checked_shr(level as u32).unwrap_or(0).max(1);
+  [halve(D::width(size)), halve(D::height(size))]
+}
+
+/// Upload compressed blocks to the region of size `[w, h, depth]` at `[x, y, z]` in the mipmap
+/// `level` of the bound texture.
+///
+/// For cubemaps, `z` is the face and `depth` is ignored.
+fn upload_compressed_blocks<D>(
+  state: &mut WebGL2State,
+  target: u32,
+  pf: PixelFormat,
+  level: usize,
+  [x, y, z]: [u32; 3],
+  [w, h, depth]: [u32; 3],
+  blocks: &[u8],
+) -> Result<(), TextureError>
+where
+  D: Dimensionable,
+{
+  let format = webgl_compressed_format(pf).ok_or(TextureError::unsupported_pixel_format(pf))?;
+  let array_buffer = unsafe { u8::into_array_buffer(blocks) };
+
+  match D::dim() {
+    Dim::Dim2 => state
+      .ctx
+      .compressed_tex_sub_image_2d_with_array_buffer_view(
+        target,
+        level as i32,
+        x as i32,
+        y as i32,
+        w as i32,
+        h as i32,
+        format,
+        &array_buffer,
+      ),
+
+    Dim::Cubemap => state
+      .ctx
+      .compressed_tex_sub_image_2d_with_array_buffer_view(
+        WebGl2RenderingContext::TEXTURE_CUBE_MAP_POSITIVE_X + z,
+        level as i32,
+        x as i32,
+        y as i32,
+        w as i32,
+        h as i32,
+        format,
+        &array_buffer,
+      ),
+
+    Dim::Dim2Array => state
+      .ctx
+      .compressed_tex_sub_image_3d_with_array_buffer_view(
+        target,
+        level as i32,
+        x as i32,
+        y as i32,
+        z as i32,
+        w as i32,
+        h as i32,
+        depth as i32,
+        format,
+        &array_buffer,
+      ),
+
+    _ => return Err(TextureError::unsupported_pixel_format(pf)),
+  }
+
+  Ok(())
+}
+
 // Capacity of the dimension, which is the product of the width, height and depth.
 fn dim_capacity<D>(size: D::Size) -> u32
 where
@@ -70,10 +70,15 @@
 
 pub mod buffer;
 pub mod color_slot;
+pub mod compute;
 pub mod depth_slot;
+pub mod fence;
 pub mod framebuffer;
 pub mod pipeline;
+pub mod query;
+pub mod readback;
 pub mod render_gate;
+pub mod renderbuffer;
 pub mod shader;
 pub mod shading_gate;
 pub mod tess;
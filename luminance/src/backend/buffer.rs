@@ -4,7 +4,8 @@
 
 use std::ops::{Deref, DerefMut};
 
-use crate::buffer::BufferError;
+use crate::backend::readback::Readback;
+use crate::buffer::{BufferError, BufferUsage, PreserveContent};
 
 pub unsafe trait Buffer<T>
 where
@@ -14,15 +15,28 @@ where
   type BufferRepr;
 
   /// Create a new buffer with a given number of uninitialized elements.
-  unsafe fn new_buffer(&mut self, len: usize) -> Result<Self::BufferRepr, BufferError>
+  unsafe fn new_buffer(
+    &mut self,
+    len: usize,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>
   where
     T: Default;
 
   unsafe fn len(buffer: &Self::BufferRepr) -> usize;
 
-  unsafe fn from_vec(&mut self, vec: Vec<T>) -> Result<Self::BufferRepr, BufferError>;
+  unsafe fn from_vec(
+    &mut self,
+    vec: Vec<T>,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>;
 
-  unsafe fn repeat(&mut self, len: usize, value: T) -> Result<Self::BufferRepr, BufferError>;
+  unsafe fn repeat(
+    &mut self,
+    len: usize,
+    value: T,
+    usage: BufferUsage,
+  ) -> Result<Self::BufferRepr, BufferError>;
 
   unsafe fn at(buffer: &Self::BufferRepr, i: usize) -> Option<T>;
 
@@ -32,7 +46,40 @@ where
 
   unsafe fn write_whole(buffer: &mut Self::BufferRepr, values: &[T]) -> Result<(), BufferError>;
 
+  /// Write `values` starting at item `offset`. The items are guaranteed to fit in the buffer.
+  unsafe fn write_range(
+    buffer: &mut Self::BufferRepr,
+    offset: usize,
+    values: &[T],
+  ) -> Result<(), BufferError>;
+
+  /// Read `len` items starting at item `start`. The range is guaranteed to fit in the buffer.
+  unsafe fn read_range(
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Vec<T>, BufferError>;
+
+  /// Copy `len` items of `src` starting at item `src_start` into `buffer`, starting at item
+  /// `dst_offset`. Both ranges are guaranteed to fit in their buffers.
+  unsafe fn copy_from(
+    buffer: &mut Self::BufferRepr,
+    src: &Self::BufferRepr,
+    src_start: usize,
+    len: usize,
+    dst_offset: usize,
+  ) -> Result<(), BufferError>;
+
   unsafe fn clear(buffer: &mut Self::BufferRepr, x: T) -> Result<(), BufferError>;
+
+  /// Resize `buffer` to `len` items. New items — or all of them if the content is not preserved —
+  /// are set to `value`.
+  unsafe fn resize(
+    buffer: &mut Self::BufferRepr,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError>;
 }
 
 pub unsafe trait BufferSlice<T>: Buffer<T>
@@ -50,6 +97,13 @@ where
   ) -> Result<Self::SliceMutRepr, BufferError>;
 }
 
+pub unsafe trait BufferReadback<T>: Buffer<T> + Readback<T>
+where
+  T: Copy,
+{
+  unsafe fn whole_async(buffer: &Self::BufferRepr) -> Result<Self::ReadbackRepr, BufferError>;
+}
+
 pub unsafe trait UniformBlock {}
 
 unsafe impl UniformBlock for u8 {}
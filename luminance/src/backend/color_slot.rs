@@ -3,12 +3,14 @@
 //! This interface defines the low-level API color slots must implement to be usable.
 
 use crate::backend::framebuffer::Framebuffer;
+use crate::backend::renderbuffer::Renderbuffer as RenderbufferBackend;
 use crate::backend::texture::Texture as TextureBackend;
 use crate::context::GraphicsContext;
 use crate::framebuffer::FramebufferError;
 use crate::pixel::{ColorPixel, PixelFormat, RenderablePixel};
-use crate::texture::{Dimensionable, Sampler};
+use crate::texture::{Dim2, Dimensionable, Sampler};
 
+use crate::renderbuffer::Renderbuffer;
 use crate::texture::Texture;
 
 pub trait ColorSlot<B, D>
@@ -91,6 +93,35 @@ where
   }
 }
 
+impl<B, P> ColorSlot<B, Dim2> for Renderbuffer<B, P>
+where
+  B: ?Sized + RenderbufferBackend<P>,
+  P: ColorPixel + RenderablePixel,
+{
+  type ColorTextures = Renderbuffer<B, P>;
+
+  fn color_formats() -> Vec<PixelFormat> {
+    vec![P::pixel_format()]
+  }
+
+  fn reify_color_textures<C>(
+    ctx: &mut C,
+    size: <Dim2 as Dimensionable>::Size,
+    _: usize,
+    _: &Sampler,
+    framebuffer: &mut B::FramebufferRepr,
+    attachment_index: usize,
+  ) -> Result<Self::ColorTextures, FramebufferError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    let renderbuffer = Renderbuffer::new(ctx, size)?;
+    unsafe { B::attach_color_renderbuffer(framebuffer, &renderbuffer.repr, attachment_index)? };
+
+    Ok(renderbuffer)
+  }
+}
+
 macro_rules! impl_color_slot_tuple {
   ($($pf:ident),*) => {
     impl<B, D, $($pf),*> ColorSlot<B, D> for ($($pf),*)
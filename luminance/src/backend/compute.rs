@@ -0,0 +1,16 @@
+//! Compute backend interface.
+//!
+//! This interface defines the low-level API compute programs must implement to be usable.
+
+use crate::backend::pipeline::PipelineBase;
+use crate::compute::ComputeError;
+use crate::shader::ProgramError;
+
+pub unsafe trait Compute: PipelineBase {
+  unsafe fn new_compute_program(
+    &mut self,
+    stage: &Self::StageRepr,
+  ) -> Result<Self::ProgramRepr, ProgramError>;
+
+  unsafe fn dispatch(&mut self, workgroups: [u32; 3]) -> Result<(), ComputeError>;
+}
@@ -3,12 +3,14 @@
 //! This interface defines the low-level API depth slots must implement to be usable.
 
 use crate::backend::framebuffer::Framebuffer;
+use crate::backend::renderbuffer::Renderbuffer as RenderbufferBackend;
 use crate::backend::texture::Texture as TextureBackend;
 use crate::context::GraphicsContext;
 use crate::framebuffer::FramebufferError;
 use crate::pixel::{DepthPixel, PixelFormat};
-use crate::texture::{Dimensionable, Sampler};
+use crate::texture::{Dim2, Dimensionable, Sampler};
 
+use crate::renderbuffer::Renderbuffer;
 use crate::texture::Texture;
 
 pub trait DepthSlot<B, D>
@@ -90,3 +92,31 @@ where
     Ok(texture)
   }
 }
+
+impl<B, P> DepthSlot<B, Dim2> for Renderbuffer<B, P>
+where
+  B: ?Sized + RenderbufferBackend<P>,
+  P: DepthPixel,
+{
+  type DepthTexture = Renderbuffer<B, P>;
+
+  fn depth_format() -> Option<PixelFormat> {
+    Some(P::pixel_format())
+  }
+
+  fn reify_depth_texture<C>(
+    ctx: &mut C,
+    size: <Dim2 as Dimensionable>::Size,
+    _: usize,
+    _: &Sampler,
+    framebuffer: &mut B::FramebufferRepr,
+  ) -> Result<Self::DepthTexture, FramebufferError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    let renderbuffer = Renderbuffer::new(ctx, size)?;
+    unsafe { B::attach_depth_renderbuffer(framebuffer, &renderbuffer.repr)? };
+
+    Ok(renderbuffer)
+  }
+}
@@ -0,0 +1,13 @@
+//! Fence backend interface.
+//!
+//! This interface defines the low-level API fences must implement to be usable.
+
+use crate::fence::FenceError;
+
+pub unsafe trait Fence {
+  type FenceRepr;
+
+  unsafe fn new_fence(&mut self) -> Result<Self::FenceRepr, FenceError>;
+
+  unsafe fn fence_signaled(fence: &mut Self::FenceRepr) -> bool;
+}
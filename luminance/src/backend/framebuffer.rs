@@ -5,7 +5,7 @@
 use crate::backend::color_slot::ColorSlot;
 use crate::backend::depth_slot::DepthSlot;
 use crate::backend::texture::TextureBase;
-use crate::framebuffer::FramebufferError;
+use crate::framebuffer::{BlitFilter, BlitMask, BlitRect, FramebufferError};
 use crate::texture::{Dim2, Dimensionable, Sampler};
 
 pub unsafe trait Framebuffer<D>: TextureBase
@@ -48,3 +48,33 @@ pub unsafe trait FramebufferBackBuffer: Framebuffer<Dim2> {
     size: <Dim2 as Dimensionable>::Size,
   ) -> Result<Self::FramebufferRepr, FramebufferError>;
 }
+
+pub unsafe trait FramebufferBlit: Framebuffer<Dim2> {
+  unsafe fn blit_framebuffer(
+    src: &Self::FramebufferRepr,
+    dst: &mut Self::FramebufferRepr,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError>;
+}
+
+pub unsafe trait FramebufferMultisample: Framebuffer<Dim2> {
+  /// Create a framebuffer which pipelines render to `samples` samples per pixel.
+  ///
+  /// The color and depth textures attached afterwards receive the resolved samples when
+  /// [`Pipeline::end_pipeline`] is called.
+  ///
+  /// [`Pipeline::end_pipeline`]: crate::backend::pipeline::Pipeline::end_pipeline
+  unsafe fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    mipmaps: usize,
+    sampler: &Sampler,
+  ) -> Result<Self::FramebufferRepr, FramebufferError>
+  where
+    CS: ColorSlot<Self, Dim2>,
+    DS: DepthSlot<Self, Dim2>;
+}
@@ -25,6 +25,9 @@ where
     framebuffer: &Self::FramebufferRepr,
     pipeline_state: &PipelineState,
   );
+
+  /// End the pipeline started on `framebuffer`, resolving it if it’s multisampled.
+  unsafe fn end_pipeline(&mut self, framebuffer: &Self::FramebufferRepr);
 }
 
 pub unsafe trait PipelineBuffer<T>: PipelineBase + Buffer<T>
@@ -38,7 +41,32 @@ where
     buffer: &Self::BufferRepr,
   ) -> Result<Self::BoundBufferRepr, PipelineError>;
 
+  /// Bind `len` items of `buffer`, starting at item `start`.
+  unsafe fn bind_buffer_range(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+    start: usize,
+    len: usize,
+  ) -> Result<Self::BoundBufferRepr, PipelineError>;
+
   unsafe fn buffer_binding(bound: &Self::BoundBufferRepr) -> u32;
+
+  /// Alignment, in bytes, of the offsets of bound buffer ranges.
+  unsafe fn buffer_offset_alignment(&mut self) -> usize;
+}
+
+pub unsafe trait PipelineStorageBuffer<T>: PipelineBase + Buffer<T>
+where
+  T: Copy,
+{
+  type BoundStorageBufferRepr;
+
+  unsafe fn bind_storage_buffer(
+    pipeline: &Self::PipelineRepr,
+    buffer: &Self::BufferRepr,
+  ) -> Result<Self::BoundStorageBufferRepr, PipelineError>;
+
+  unsafe fn storage_buffer_binding(bound: &Self::BoundStorageBufferRepr) -> u32;
 }
 
 pub unsafe trait PipelineTexture<D, P>: PipelineBase + Texture<D, P>
@@ -0,0 +1,17 @@
+//! Query backend interface.
+//!
+//! This interface defines the low-level API queries must implement to be usable.
+
+use crate::query::{QueryError, QueryType};
+
+pub unsafe trait Query {
+  type QueryRepr;
+
+  unsafe fn new_query(&mut self, ty: QueryType) -> Result<Self::QueryRepr, QueryError>;
+
+  unsafe fn begin_query(&mut self, query: &mut Self::QueryRepr) -> Result<(), QueryError>;
+
+  unsafe fn end_query(&mut self, query: &mut Self::QueryRepr);
+
+  unsafe fn query_result(query: &mut Self::QueryRepr) -> Result<Option<u64>, QueryError>;
+}
@@ -0,0 +1,16 @@
+//! Readback backend interface.
+//!
+//! This interface defines the low-level API readbacks must implement to be usable.
+
+use crate::readback::ReadbackError;
+
+pub unsafe trait Readback<T>
+where
+  T: Copy,
+{
+  type ReadbackRepr;
+
+  unsafe fn readback_result(
+    readback: &mut Self::ReadbackRepr,
+  ) -> Result<Option<Vec<T>>, ReadbackError>;
+}
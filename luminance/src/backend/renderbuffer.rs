@@ -0,0 +1,34 @@
+//! Renderbuffer backend interface.
+//!
+//! This interface defines the low-level API renderbuffers must implement to be usable.
+
+use crate::backend::framebuffer::Framebuffer;
+use crate::framebuffer::FramebufferError;
+use crate::pixel::Pixel;
+use crate::texture::Dim2;
+
+/// The base renderbuffer trait.
+pub unsafe trait RenderbufferBase {
+  type RenderbufferRepr;
+}
+
+pub unsafe trait Renderbuffer<P>: RenderbufferBase + Framebuffer<Dim2>
+where
+  P: Pixel,
+{
+  unsafe fn new_renderbuffer(
+    &mut self,
+    size: [u32; 2],
+  ) -> Result<Self::RenderbufferRepr, FramebufferError>;
+
+  unsafe fn attach_color_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+    attachment_index: usize,
+  ) -> Result<(), FramebufferError>;
+
+  unsafe fn attach_depth_renderbuffer(
+    framebuffer: &mut Self::FramebufferRepr,
+    renderbuffer: &Self::RenderbufferRepr,
+  ) -> Result<(), FramebufferError>;
+}
@@ -4,7 +4,8 @@
 
 use std::ops::{Deref, DerefMut};
 
-use crate::tess::{Mode, TessError, TessIndex, TessMapError, TessVertexData};
+use crate::buffer::PreserveContent;
+use crate::tess::{TessError, TessIndex, TessMapError, TessParams, TessVertexData};
 
 pub unsafe trait Tess<V, I, W, S>
 where
@@ -20,10 +21,7 @@ where
     vertex_data: Option<V::Data>,
     index_data: Vec<I>,
     instance_data: Option<W::Data>,
-    mode: Mode,
-    vert_nb: usize,
-    inst_nb: usize,
-    restart_index: Option<I>,
+    params: TessParams<I>,
   ) -> Result<Self::TessRepr, TessError>;
 
   unsafe fn tess_vertices_nb(tess: &Self::TessRepr) -> usize;
@@ -36,6 +34,30 @@ where
     vert_nb: usize,
     inst_nb: usize,
   ) -> Result<(), TessError>;
+
+  /// Resize the vertex storage. New vertices are zero-initialized and the number of vertices to
+  /// render is set to `len` if the tessellation is not indexed.
+  unsafe fn resize_vertices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError>;
+
+  /// Resize the index storage. New indices are zero-initialized and the number of vertices to
+  /// render is set to `len`. `I` is guaranteed not to be `()`.
+  unsafe fn resize_indices(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError>;
+
+  /// Resize the instance storage. New instances are zero-initialized and the number of instances
+  /// to render is set to `len`.
+  unsafe fn resize_instances(
+    tess: &mut Self::TessRepr,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError>;
 }
 
 pub unsafe trait VertexSlice<V, I, W, S, T>: Tess<V, I, W, S>
@@ -2,7 +2,8 @@
 //!
 //! This interface defines the low-level API textures must implement to be usable.
 
-use crate::pixel::Pixel;
+use crate::backend::readback::Readback;
+use crate::pixel::{CompressedPixel, CompressionFamily, Pixel};
 use crate::texture::{Dimensionable, GenMipmaps, Sampler, TextureError};
 
 /// The base texture trait.
@@ -75,4 +76,109 @@ where
   ) -> Result<Vec<P::RawEncoding>, TextureError>
   where
     P::RawEncoding: Copy + Default;
+
+  /// Copy the region of size `size` at `src_offset` in the base level of `src` to `dst_offset` in
+  /// the base level of `texture`.
+  ///
+  /// Both regions are guaranteed to be in bounds. Cubemap regions only cover the face they are
+  /// offset to.
+  unsafe fn copy_from(
+    texture: &mut Self::TextureRepr,
+    gen_mipmaps: GenMipmaps,
+    src: &Self::TextureRepr,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError>;
+
+  /// Upload texels to the region of size `size` at `offset` in the mipmap `level` of `texture`.
+  ///
+  /// The region is guaranteed to fit in the level. Cubemap regions only cover the face they are
+  /// offset to. The other levels are left untouched.
+  unsafe fn upload_level_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError>;
+
+  /// Upload raw texels to the region of size `size` at `offset` in the mipmap `level` of `texture`.
+  ///
+  /// See [`Texture::upload_level_part`].
+  unsafe fn upload_level_part_raw(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError>;
+
+  /// Get a copy of the texels of the region of size `size` at `offset` in the mipmap `level` of
+  /// `texture`.
+  ///
+  /// The region is guaranteed to fit in the level. Cubemap regions only cover the face they are
+  /// offset to.
+  unsafe fn get_level_part_raw_texels(
+    texture: &Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default;
+
+  /// Regenerate the mipmaps of `texture` out of its base level, up to its max level.
+  ///
+  /// The pixel format is guaranteed not to be compressed.
+  unsafe fn generate_mipmaps(texture: &mut Self::TextureRepr) -> Result<(), TextureError>;
+}
+
+pub unsafe trait TextureReadback<D, P>: Texture<D, P> + Readback<P::RawEncoding>
+where
+  D: Dimensionable,
+  P: Pixel,
+  P::RawEncoding: Copy,
+{
+  unsafe fn get_raw_texels_async(
+    texture: &Self::TextureRepr,
+    size: D::Size,
+  ) -> Result<Self::ReadbackRepr, TextureError>;
+}
+
+/// Compressed pixel formats support.
+pub unsafe trait TextureCompression: TextureBase {
+  /// Whether the compressed pixel formats of `family` are supported.
+  unsafe fn supports_compression(&mut self, family: CompressionFamily) -> bool;
+}
+
+pub unsafe trait TextureCompressed<D, P>: Texture<D, P> + TextureCompression
+where
+  D: Dimensionable,
+  P: CompressedPixel,
+{
+  /// Upload pre-compressed blocks to the region of size `size` at `offset` in the mipmap `level`
+  /// of `texture`.
+  ///
+  /// The region is guaranteed to fit in the level and to be aligned on blocks, and `blocks` is
+  /// guaranteed to hold exactly the blocks of the region. Cubemap regions only cover the face they
+  /// are offset to.
+  unsafe fn upload_compressed_part(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError>;
+
+  /// Upload pre-compressed blocks to the whole mipmap `level` of `texture`, which base level is of
+  /// size `size`.
+  ///
+  /// `blocks` is guaranteed to hold exactly the blocks of the level; for cubemaps, face after face.
+  unsafe fn upload_compressed(
+    texture: &mut Self::TextureRepr,
+    level: usize,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError>;
 }
@@ -17,14 +17,27 @@
 //! # Buffer creation, reading, writing and getting information
 //!
 //! Buffers are created with the [`Buffer::new`], [`Buffer::from_vec`] and [`Buffer::repeat`]
-//! methods. All these methods are fallible — they might fail with [`BufferError`].
+//! methods. All these methods are fallible — they might fail with [`BufferError`]. They all take a
+//! [`BufferUsage`], which tells the backend how the buffer is going to be used, so that it can
+//! place its memory appropriately — static level geometry doesn’t have the same needs as data
+//! streamed every frame, for instance.
 //!
 //! Once you have a [`Buffer`], you can read from it and write to it.
 //! Writing is done with [`Buffer::set`] — which allows to set a value at a given index in the
 //! buffer, [`Buffer::write_whole`] — which writes a whole slice to the buffer — and
 //! [`Buffer::clear`] — which sets the same value to all items in a buffer. Reading is performed
 //! with [`Buffer::at`] — which retrieves the item at a given index and [`Buffer::whole`] which
-//! retrieves the whole buffer by copying it to a `Vec<T>`.
+//! retrieves the whole buffer by copying it to a `Vec<T>`. Those functions block until the GPU is
+//! done writing to the buffer; [`Buffer::whole_async`] starts a [`Readback`] instead, which
+//! retrieves the whole buffer without blocking, once the GPU is done.
+//!
+//! Parts of a buffer can be written and read with [`Buffer::write_range`] and
+//! [`Buffer::read_range`], and copied from another buffer with [`Buffer::copy_from`]. Only the
+//! items in the range are transferred, which is much cheaper than writing or reading a whole
+//! large buffer to update a few items.
+//!
+//! A buffer can be grown or shrunk with [`Buffer::resize`], which can either keep the items that
+//! still fit in the buffer or reset all of them — see [`PreserveContent`].
 //!
 //! It’s possible to get data via several methods, such as [`Buffer::len`] to get the number of
 //! items in the buffer.
@@ -42,16 +55,21 @@
 //! borrowing must be enforced.
 //!
 //! [`backend::buffer::Buffer`]: crate::backend::buffer::Buffer
+//! [`Readback`]: crate::readback::Readback
 
 use crate::{
-  backend::buffer::{Buffer as BufferBackend, BufferSlice as BufferSliceBackend},
+  backend::buffer::{
+    Buffer as BufferBackend, BufferReadback as BufferReadbackBackend,
+    BufferSlice as BufferSliceBackend,
+  },
   context::GraphicsContext,
+  readback::Readback,
 };
 
 use std::{
   error, fmt,
   marker::PhantomData,
-  ops::{Deref, DerefMut},
+  ops::{Deref, DerefMut, Range},
 };
 
 /// A GPU buffer.
@@ -96,12 +114,12 @@ where
   ///
   /// You might be interested in the [`GraphicsContext::new_buffer`] function instead, which
   /// is the exact same function, but benefits from more type inference (based on `&mut C`).
-  pub fn new<C>(ctx: &mut C, len: usize) -> Result<Self, BufferError>
+  pub fn new<C>(ctx: &mut C, len: usize, usage: BufferUsage) -> Result<Self, BufferError>
   where
     C: GraphicsContext<Backend = B>,
     T: Default,
   {
-    let repr = unsafe { ctx.backend().new_buffer(len)? };
+    let repr = unsafe { ctx.backend().new_buffer(len, usage)? };
 
     Ok(Buffer {
       repr,
@@ -124,12 +142,12 @@ where
   ///
   /// You might be interested in the [`GraphicsContext::new_buffer_from_vec`] function instead,
   /// which is the exact same function, but benefits from more type inference (based on `&mut C`).
-  pub fn from_vec<C, X>(ctx: &mut C, vec: X) -> Result<Self, BufferError>
+  pub fn from_vec<C, X>(ctx: &mut C, vec: X, usage: BufferUsage) -> Result<Self, BufferError>
   where
     C: GraphicsContext<Backend = B>,
     X: Into<Vec<T>>,
   {
-    let repr = unsafe { ctx.backend().from_vec(vec.into())? };
+    let repr = unsafe { ctx.backend().from_vec(vec.into(), usage)? };
 
     Ok(Buffer {
       repr,
@@ -151,11 +169,16 @@ where
   ///
   /// You might be interested in the [`GraphicsContext::new_buffer_repeating`] function instead,
   /// which is the exact same function, but benefits from more type inference (based on `&mut C`).
-  pub fn repeat<C>(ctx: &mut C, len: usize, value: T) -> Result<Self, BufferError>
+  pub fn repeat<C>(
+    ctx: &mut C,
+    len: usize,
+    value: T,
+    usage: BufferUsage,
+  ) -> Result<Self, BufferError>
   where
     C: GraphicsContext<Backend = B>,
   {
-    let repr = unsafe { ctx.backend().repeat(len, value)? };
+    let repr = unsafe { ctx.backend().repeat(len, value, usage)? };
 
     Ok(Buffer {
       repr,
@@ -196,11 +219,91 @@ where
     unsafe { B::write_whole(&mut self.repr, values) }
   }
 
+  /// Write `values` in the buffer, starting at index `offset`.
+  ///
+  /// Only the written items are uploaded, so that updating a few items of a large buffer is cheap.
+  ///
+  /// # Errors
+  ///
+  /// [`BufferError::RangeOutOfBounds`] is returned if the items don’t fit in the buffer.
+  pub fn write_range(&mut self, offset: usize, values: &[T]) -> Result<(), BufferError> {
+    check_range(offset, offset.saturating_add(values.len()), self.len())?;
+
+    unsafe { B::write_range(&mut self.repr, offset, values) }
+  }
+
+  /// Get the items in `range` and store them inside a [`Vec`].
+  ///
+  /// Only the items of the range are read.
+  ///
+  /// # Errors
+  ///
+  /// [`BufferError::RangeOutOfBounds`] is returned if the range doesn’t fit in the buffer. Other
+  /// errors are possible; please consider reading the documentation of [`BufferError`] for further
+  /// information.
+  pub fn read_range(&self, range: Range<usize>) -> Result<Vec<T>, BufferError> {
+    check_range(range.start, range.end, self.len())?;
+
+    unsafe { B::read_range(&self.repr, range.start, range.len()) }
+  }
+
+  /// Copy the items in `src_range` of `src` into the buffer, starting at index `dst_offset`.
+  ///
+  /// The copy is performed by the backend without going through the CPU when possible.
+  ///
+  /// # Errors
+  ///
+  /// [`BufferError::RangeOutOfBounds`] is returned if `src_range` doesn’t fit in `src` or if the
+  /// copied items don’t fit in the buffer.
+  pub fn copy_from(
+    &mut self,
+    src: &Self,
+    src_range: Range<usize>,
+    dst_offset: usize,
+  ) -> Result<(), BufferError> {
+    check_range(src_range.start, src_range.end, src.len())?;
+    check_range(
+      dst_offset,
+      dst_offset.saturating_add(src_range.len()),
+      self.len(),
+    )?;
+
+    unsafe {
+      B::copy_from(
+        &mut self.repr,
+        &src.repr,
+        src_range.start,
+        src_range.len(),
+        dst_offset,
+      )
+    }
+  }
+
   /// Clear the content of the buffer by copying the same value everywhere.
   pub fn clear(&mut self, x: T) -> Result<(), BufferError> {
     unsafe { B::clear(&mut self.repr, x) }
   }
 
+  /// Resize the buffer so that it holds `len` items.
+  ///
+  /// With [`PreserveContent::Yes`], the items that still fit in the buffer are kept and the new
+  /// items, if any, are set to `value`. With [`PreserveContent::No`], all the items are set to
+  /// `value`.
+  ///
+  /// # Errors
+  ///
+  /// That function can fail re-allocating the buffer for various reasons, in which case it returns
+  /// `Err(BufferError::_)`. Feel free to read the documentation of [`BufferError`] for further
+  /// information.
+  pub fn resize(
+    &mut self,
+    len: usize,
+    value: T,
+    preserve: PreserveContent,
+  ) -> Result<(), BufferError> {
+    unsafe { B::resize(&mut self.repr, len, value, preserve) }
+  }
+
   /// Return the length of the buffer (i.e. the number of elements).
   #[inline(always)]
   pub fn len(&self) -> usize {
@@ -211,15 +314,32 @@ where
   ///
   /// # Note
   ///
-  /// Since right now, it is not possible to grow vectors, it is highly recommended not to create
-  /// empty buffers. That function is there only for convenience and demonstration; you shouldn’t
-  /// really have to use it.
+  /// Empty buffers can be grown with [`Buffer::resize`].
   #[inline(always)]
   pub fn is_empty(&self) -> bool {
     self.len() == 0
   }
 }
 
+impl<B, T> Buffer<B, T>
+where
+  B: ?Sized + BufferReadbackBackend<T>,
+  T: Copy,
+{
+  /// Start reading back the whole content of the buffer.
+  ///
+  /// The returned [`Readback`] gets the content of the buffer once the GPU is done with all the
+  /// commands issued so far, without blocking.
+  ///
+  /// # Errors
+  ///
+  /// [`BufferError::CannotCreate`] is returned if the staging area of the readback cannot be
+  /// created.
+  pub fn whole_async(&self) -> Result<Readback<B, T>, BufferError> {
+    unsafe { B::whole_async(&self.repr).map(Readback::from_repr) }
+  }
+}
+
 impl<B, T> Buffer<B, T>
 where
   B: ?Sized + BufferSliceBackend<T>,
@@ -296,6 +416,18 @@ pub enum BufferError {
 
   /// Buffer mapping failed.
   MapFailed,
+
+  /// A range of items doesn’t fit in a buffer.
+  ///
+  /// Contains the range and the size of the buffer.
+  RangeOutOfBounds {
+    /// Index of the first item of the range.
+    start: usize,
+    /// Index of the item following the last one of the range.
+    end: usize,
+    /// Actual buffer length.
+    buffer_len: usize,
+  },
 }
 
 impl BufferError {
@@ -329,6 +461,15 @@ impl BufferError {
   pub fn map_failed() -> Self {
     BufferError::MapFailed
   }
+
+  /// A range of items doesn’t fit in a buffer.
+  pub fn range_out_of_bounds(start: usize, end: usize, buffer_len: usize) -> Self {
+    BufferError::RangeOutOfBounds {
+      start,
+      end,
+      buffer_len,
+    }
+  }
 }
 
 impl fmt::Display for BufferError {
@@ -361,12 +502,64 @@ impl fmt::Display for BufferError {
       ),
 
       BufferError::MapFailed => f.write_str("buffer mapping failed"),
+
+      BufferError::RangeOutOfBounds {
+        start,
+        end,
+        buffer_len,
+      } => write!(
+        f,
+        "buffer range out of bounds (range = {}..{}, size = {})",
+        start, end, buffer_len
+      ),
     }
   }
 }
 
 impl error::Error for BufferError {}
 
+/// Check that the range `start..end` fits in a buffer of `buffer_len` items.
+fn check_range(start: usize, end: usize, buffer_len: usize) -> Result<(), BufferError> {
+  if start > end || end > buffer_len {
+    Err(BufferError::range_out_of_bounds(start, end, buffer_len))
+  } else {
+    Ok(())
+  }
+}
+
+/// Usage of a [`Buffer`].
+///
+/// The usage is a hint given to the backend when creating a buffer. It doesn’t restrict what can
+/// be done with the buffer, but using a buffer in a way that doesn’t match its usage might be
+/// slower.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BufferUsage {
+  /// The content is written once and used many times, such as static level geometry.
+  Static,
+  /// The content is written repeatedly and used many times.
+  Dynamic,
+  /// The content is written once and used at most a few times, such as per-frame data.
+  Stream,
+  /// The content is written by the GPU and read back by the application, such as the output of a
+  /// compute shader.
+  ReadBack,
+  /// The content is written repeatedly and the buffer stays mapped for its whole lifetime.
+  ///
+  /// Slicing such a buffer doesn’t map nor unmap it, and writes through a mutable slice are
+  /// visible to the GPU without any further synchronization. Backends not supporting persistent
+  /// mapping treat it as [`BufferUsage::Dynamic`].
+  Persistent,
+}
+
+/// Whether the content of a [`Buffer`] is kept when resizing it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PreserveContent {
+  /// Keep the items that still fit in the resized buffer.
+  Yes,
+  /// Don’t keep anything; the whole resized buffer is reset.
+  No,
+}
+
 /// A buffer slice, allowing to get `&[T]`.
 #[derive(Debug)]
 pub struct BufferSlice<'a, B, T>
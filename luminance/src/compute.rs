@@ -0,0 +1,325 @@
+//! Compute programs and dispatching.
+//!
+//! Compute programs are shader programs that don’t take part in graphics pipelines: they are made
+//! of a single [`StageType::ComputeShader`] [`Stage`] and run arbitrary work on the GPU, in
+//! parallel. That work is split in _work groups_, and the number of invocations in each work group
+//! is declared in the stage itself, with a GLSL `layout(local_size_x = …) in;` declaration.
+//!
+//! Like shader [`Program`]s, [`ComputeProgram`]s are typed with a [`UniformInterface`]. They don’t
+//! have vertex semantics nor render targets, though.
+//!
+//! A [`ComputeProgram`] is run by dispatching it through a [`ComputeGate`], giving the number of
+//! work groups to run in each dimension. Before the work groups are dispatched, you are handed
+//! a [`Pipeline`], a [`ProgramInterface`] and the [`UniformInterface`] of the program, so that you
+//! can bind buffers and textures and pass them — along with any other uniform — to the program.
+//!
+//! Not all backends support compute programs: the ones that do implement the [`Compute`] backend
+//! trait.
+//!
+//! [`Program`]: crate::shader::Program
+//! [`Compute`]: crate::backend::compute::Compute
+
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::backend::compute::Compute as ComputeBackend;
+use crate::backend::shader::Shader;
+use crate::context::GraphicsContext;
+use crate::pipeline::{Pipeline, PipelineError};
+use crate::shader::{
+  ProgramError, ProgramInterface, ProgramWarning, Stage, StageType, UniformBuilder,
+  UniformInterface,
+};
+
+/// Errors that might occur when dispatching a [`ComputeProgram`].
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ComputeError {
+  /// More work groups were requested than the backend can dispatch at once.
+  TooManyWorkGroups {
+    /// Requested number of work groups in each dimension.
+    requested: [u32; 3],
+    /// Maximum number of work groups in each dimension.
+    max: [u32; 3],
+  },
+  /// Error occurring while using the pipeline of a dispatch.
+  PipelineError(PipelineError),
+}
+
+impl ComputeError {
+  /// More work groups were requested than the backend can dispatch at once.
+  pub fn too_many_work_groups(requested: [u32; 3], max: [u32; 3]) -> Self {
+    ComputeError::TooManyWorkGroups { requested, max }
+  }
+
+  /// Error occurring while using the pipeline of a dispatch.
+  pub fn pipeline_error(e: PipelineError) -> Self {
+    ComputeError::PipelineError(e)
+  }
+}
+
+impl fmt::Display for ComputeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      ComputeError::TooManyWorkGroups { requested, max } => write!(
+        f,
+        "too many work groups: {:?} requested, at most {:?} supported",
+        requested, max
+      ),
+
+      ComputeError::PipelineError(ref e) => write!(f, "compute pipeline error: {}", e),
+    }
+  }
+}
+
+impl error::Error for ComputeError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      ComputeError::PipelineError(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<PipelineError> for ComputeError {
+  fn from(e: PipelineError) -> Self {
+    ComputeError::pipeline_error(e)
+  }
+}
+
+/// A built compute program with potential warnings.
+///
+/// The sole purpose of this type is to be destructured when a compute program is built.
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+/// - `Uni` is the [`UniformInterface`] type.
+pub struct BuiltComputeProgram<B, Uni>
+where
+  B: ?Sized + Shader,
+{
+  /// Built compute program.
+  pub program: ComputeProgram<B, Uni>,
+  /// Potential warnings.
+  pub warnings: Vec<ProgramError>,
+}
+
+impl<B, Uni> BuiltComputeProgram<B, Uni>
+where
+  B: ?Sized + Shader,
+{
+  /// Get the compute program and ignore the warnings.
+  pub fn ignore_warnings(self) -> ComputeProgram<B, Uni> {
+    self.program
+  }
+}
+
+/// A compute program.
+///
+/// Compute programs are GPU binaries that execute when dispatched with a [`ComputeGate`].
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+/// - `Uni` is the [`UniformInterface`] type.
+pub struct ComputeProgram<B, Uni>
+where
+  B: ?Sized + Shader,
+{
+  pub(crate) repr: B::ProgramRepr,
+  pub(crate) uni: Uni,
+}
+
+impl<B, Uni> ComputeProgram<B, Uni>
+where
+  B: ?Sized + ComputeBackend,
+{
+  /// Create a [`ComputeProgram`] by linking a compute [`Stage`] and accessing a mutable
+  /// environment variable.
+  ///
+  /// # Parametricity
+  ///
+  /// - `C` is the graphics context.
+  /// - `E` is the mutable environment variable.
+  ///
+  /// # Notes
+  ///
+  /// Feel free to look at the documentation of [`GraphicsContext::new_compute_program`] for
+  /// a simpler interface.
+  pub fn from_stage_env<C, E>(
+    ctx: &mut C,
+    stage: &Stage<B>,
+    env: &mut E,
+  ) -> Result<BuiltComputeProgram<B, Uni>, ProgramError>
+  where
+    C: GraphicsContext<Backend = B>,
+    Uni: UniformInterface<B, E>,
+  {
+    unsafe {
+      let mut repr = ctx.backend().new_compute_program(&stage.repr)?;
+
+      let mut uniform_builder = B::new_uniform_builder(&mut repr).map(|repr| UniformBuilder {
+        repr,
+        warnings: Vec::new(),
+        _a: PhantomData,
+      })?;
+
+      let uni =
+        Uni::uniform_interface(&mut uniform_builder, env).map_err(ProgramWarning::Uniform)?;
+
+      let warnings = uniform_builder
+        .warnings
+        .into_iter()
+        .map(|w| ProgramError::Warning(w.into()))
+        .collect();
+
+      let program = ComputeProgram { repr, uni };
+
+      Ok(BuiltComputeProgram { program, warnings })
+    }
+  }
+
+  /// Create a [`ComputeProgram`] by linking a compute [`Stage`].
+  ///
+  /// # Notes
+  ///
+  /// Feel free to look at the documentation of [`GraphicsContext::new_compute_program`] for
+  /// a simpler interface.
+  pub fn from_stage<C>(
+    ctx: &mut C,
+    stage: &Stage<B>,
+  ) -> Result<BuiltComputeProgram<B, Uni>, ProgramError>
+  where
+    C: GraphicsContext<Backend = B>,
+    Uni: UniformInterface<B>,
+  {
+    Self::from_stage_env(ctx, stage, &mut ())
+  }
+
+  /// Create a [`ComputeProgram`] by compiling and linking the source of a compute stage and
+  /// accessing a mutable environment variable.
+  ///
+  /// # Parametricity
+  ///
+  /// - `C` is the graphics context.
+  /// - `E` is the mutable environment variable.
+  ///
+  /// # Notes
+  ///
+  /// Feel free to look at the documentation of [`GraphicsContext::new_compute_program`] for
+  /// a simpler interface.
+  pub fn from_string_env<C, E>(
+    ctx: &mut C,
+    src: &str,
+    env: &mut E,
+  ) -> Result<BuiltComputeProgram<B, Uni>, ProgramError>
+  where
+    C: GraphicsContext<Backend = B>,
+    Uni: UniformInterface<B, E>,
+  {
+    let stage = Stage::new(ctx, StageType::ComputeShader, src)?;
+    Self::from_stage_env(ctx, &stage, env)
+  }
+
+  /// Create a [`ComputeProgram`] by compiling and linking the source of a compute stage.
+  ///
+  /// # Notes
+  ///
+  /// Feel free to look at the documentation of [`GraphicsContext::new_compute_program`] for
+  /// a simpler interface.
+  pub fn from_string<C>(ctx: &mut C, src: &str) -> Result<BuiltComputeProgram<B, Uni>, ProgramError>
+  where
+    C: GraphicsContext<Backend = B>,
+    Uni: UniformInterface<B>,
+  {
+    Self::from_string_env(ctx, src, &mut ())
+  }
+}
+
+/// Entry-point of compute work.
+///
+/// A [`ComputeGate`] dispatches [`ComputeProgram`]s. It is obtained from a [`GraphicsContext`]
+/// with [`GraphicsContext::new_compute_gate`].
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+pub struct ComputeGate<'a, B>
+where
+  B: ?Sized,
+{
+  backend: &'a mut B,
+}
+
+impl<'a, B> ComputeGate<'a, B>
+where
+  B: ?Sized,
+{
+  /// Create a new [`ComputeGate`].
+  pub fn new<C>(ctx: &'a mut C) -> Self
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    ComputeGate {
+      backend: ctx.backend(),
+    }
+  }
+
+  /// Dispatch a [`ComputeProgram`] with `workgroups` work groups in each dimension.
+  ///
+  /// The argument closure is run before the work groups are dispatched. It is given three
+  /// arguments:
+  ///
+  /// - A [`Pipeline`], to bind the buffers and textures the program uses.
+  /// - A [`ProgramInterface`], that allows to pass values (via [`ProgramInterface::set`]) to the
+  ///   program and/or perform dynamic lookup of uniforms.
+  /// - The [`UniformInterface`] of the program.
+  ///
+  /// The uniforms and bindings set in the closure are the ones the work groups run with.
+  /// Memory written by the program is visible to any subsequent command — renders, dispatches,
+  /// buffer reads, etc.
+  ///
+  /// # Errors
+  ///
+  /// [`ComputeError`] might be thrown for various reasons, depending on the backend you use.
+  /// However, this method doesn’t return [`ComputeError`] directly: instead, it returns
+  /// `E: From<ComputeError>`. This allows you to inject your own error type in the argument
+  /// closure, allowing for a grainer control of errors. If the closure fails, nothing is
+  /// dispatched.
+  pub fn dispatch<E, Uni, F>(
+    &mut self,
+    program: &mut ComputeProgram<B, Uni>,
+    workgroups: [u32; 3],
+    f: F,
+  ) -> Result<(), E>
+  where
+    B: ComputeBackend,
+    F: for<'b> FnOnce(Pipeline<'b, B>, ProgramInterface<'b, B>, &'b Uni) -> Result<(), E>,
+    E: From<ComputeError>,
+  {
+    unsafe {
+      self.backend.apply_shader_program(&program.repr);
+    }
+
+    let pipeline = unsafe {
+      self
+        .backend
+        .new_pipeline()
+        .map(|repr| Pipeline {
+          repr,
+          _phantom: PhantomData,
+        })
+        .map_err(ComputeError::from)?
+    };
+
+    let program_interface = ProgramInterface {
+      program: &mut program.repr,
+    };
+
+    f(pipeline, program_interface, &program.uni)?;
+
+    unsafe { self.backend.dispatch(workgroups).map_err(E::from) }
+  }
+}
@@ -30,33 +30,43 @@
 //!
 //! ```ignore
 //! use luminance::context::GraphicsContext as _;
-//! use luminance::buffer::Buffer;
+//! use luminance::buffer::{Buffer, BufferUsage};
 //!
-//! let buffer: Buffer<SomeBackendType, u8> = Buffer::from_slice(&mut context, slice).unwrap();
+//! let buffer: Buffer<SomeBackendType, u8> =
+//!   Buffer::from_vec(&mut context, vec, BufferUsage::Static).unwrap();
 //! ```
 //!
 //! You can simply do:
 //!
 //! ```ignore
+//! use luminance::buffer::BufferUsage;
 //! use luminance::context::GraphicsContext as _;
 //!
-//! let buffer = context.new_buffer_from_slice(slice).unwrap();
+//! let buffer = context.new_buffer_from_vec(vec, BufferUsage::Static).unwrap();
 //! ```
 
 use crate::backend::buffer::Buffer as BufferBackend;
 use crate::backend::color_slot::ColorSlot;
+use crate::backend::compute::Compute;
 use crate::backend::depth_slot::DepthSlot;
-use crate::backend::framebuffer::Framebuffer as FramebufferBackend;
+use crate::backend::fence::Fence as FenceBackend;
+use crate::backend::framebuffer::{Framebuffer as FramebufferBackend, FramebufferMultisample};
+use crate::backend::query::Query as QueryBackend;
 use crate::backend::shader::Shader;
 use crate::backend::tess::Tess as TessBackend;
-use crate::backend::texture::Texture as TextureBackend;
-use crate::buffer::{Buffer, BufferError};
+use crate::backend::texture::{Texture as TextureBackend, TextureCompression};
+use crate::buffer::{Buffer, BufferError, BufferUsage};
+use crate::compute::{BuiltComputeProgram, ComputeError, ComputeGate, ComputeProgram};
+use crate::fence::{Fence, FenceError};
 use crate::framebuffer::{Framebuffer, FramebufferError};
-use crate::pipeline::PipelineGate;
-use crate::pixel::Pixel;
-use crate::shader::{ProgramBuilder, Stage, StageError, StageType};
+use crate::pipeline::{Pipeline, PipelineGate};
+use crate::pixel::{CompressionFamily, Pixel};
+use crate::query::{Query, QueryError, QueryType};
+use crate::shader::{
+  ProgramBuilder, ProgramError, ProgramInterface, Stage, StageError, StageType, UniformInterface,
+};
 use crate::tess::{Deinterleaved, Interleaved, TessBuilder, TessVertexData};
-use crate::texture::{Dimensionable, Sampler, Texture, TextureError};
+use crate::texture::{Dim2, Dimensionable, Sampler, Texture, TextureError};
 use crate::vertex::Semantics;
 
 /// Class of graphics context.
@@ -76,27 +86,40 @@ pub unsafe trait GraphicsContext: Sized {
     PipelineGate::new(self)
   }
 
+  /// Create a new compute gate.
+  fn new_compute_gate(&mut self) -> ComputeGate<'_, Self::Backend> {
+    ComputeGate::new(self)
+  }
+
   /// Create a new buffer.
   ///
   /// See the documentation of [`Buffer::new`] for further details.
-  fn new_buffer<T>(&mut self, len: usize) -> Result<Buffer<Self::Backend, T>, BufferError>
+  fn new_buffer<T>(
+    &mut self,
+    len: usize,
+    usage: BufferUsage,
+  ) -> Result<Buffer<Self::Backend, T>, BufferError>
   where
     Self::Backend: BufferBackend<T>,
     T: Copy + Default,
   {
-    Buffer::new(self, len)
+    Buffer::new(self, len, usage)
   }
 
   /// Create a new buffer from a slice.
   ///
   /// See the documentation of [`Buffer::from_vec`] for further details.
-  fn new_buffer_from_vec<T, X>(&mut self, vec: X) -> Result<Buffer<Self::Backend, T>, BufferError>
+  fn new_buffer_from_vec<T, X>(
+    &mut self,
+    vec: X,
+    usage: BufferUsage,
+  ) -> Result<Buffer<Self::Backend, T>, BufferError>
   where
     Self::Backend: BufferBackend<T>,
     X: Into<Vec<T>>,
     T: Copy,
   {
-    Buffer::from_vec(self, vec)
+    Buffer::from_vec(self, vec, usage)
   }
 
   /// Create a new buffer by repeating a value.
@@ -106,12 +129,13 @@ pub unsafe trait GraphicsContext: Sized {
     &mut self,
     len: usize,
     value: T,
+    usage: BufferUsage,
   ) -> Result<Buffer<Self::Backend, T>, BufferError>
   where
     Self::Backend: BufferBackend<T>,
     T: Copy,
   {
-    Buffer::repeat(self, len, value)
+    Buffer::repeat(self, len, value, usage)
   }
 
   /// Create a new framebuffer.
@@ -132,6 +156,24 @@ pub unsafe trait GraphicsContext: Sized {
     Framebuffer::new(self, size, mipmaps, sampler)
   }
 
+  /// Create a new multisampled framebuffer.
+  ///
+  /// See the documentation of [`Framebuffer::new_multisample`] for further details.
+  fn new_multisample_framebuffer<CS, DS>(
+    &mut self,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    mipmaps: usize,
+    sampler: Sampler,
+  ) -> Result<Framebuffer<Self::Backend, Dim2, CS, DS>, FramebufferError>
+  where
+    Self::Backend: FramebufferMultisample,
+    CS: ColorSlot<Self::Backend, Dim2>,
+    DS: DepthSlot<Self::Backend, Dim2>,
+  {
+    Framebuffer::new_multisample(self, size, samples, mipmaps, sampler)
+  }
+
   /// Create a new shader stage.
   ///
   /// See the documentation of [`Stage::new`] for further details.
@@ -158,6 +200,61 @@ pub unsafe trait GraphicsContext: Sized {
     ProgramBuilder::new(self)
   }
 
+  /// Create a new query.
+  ///
+  /// See the documentation of [`Query::new`] for further details.
+  fn new_query(&mut self, ty: QueryType) -> Result<Query<Self::Backend>, QueryError>
+  where
+    Self::Backend: QueryBackend,
+  {
+    Query::new(self, ty)
+  }
+
+  /// Insert a new fence.
+  ///
+  /// See the documentation of [`Fence::new`] for further details.
+  fn new_fence(&mut self) -> Result<Fence<Self::Backend>, FenceError>
+  where
+    Self::Backend: FenceBackend,
+  {
+    Fence::new(self)
+  }
+
+  /// Create a new compute program from the source of a compute stage.
+  ///
+  /// See the documentation of [`ComputeProgram::from_string`] for further details.
+  fn new_compute_program<Uni>(
+    &mut self,
+    src: &str,
+  ) -> Result<BuiltComputeProgram<Self::Backend, Uni>, ProgramError>
+  where
+    Self::Backend: Compute,
+    Uni: UniformInterface<Self::Backend>,
+  {
+    ComputeProgram::from_string(self, src)
+  }
+
+  /// Dispatch a compute program.
+  ///
+  /// See the documentation of [`ComputeGate::dispatch`] for further details.
+  fn dispatch<E, Uni, F>(
+    &mut self,
+    program: &mut ComputeProgram<Self::Backend, Uni>,
+    workgroups: [u32; 3],
+    f: F,
+  ) -> Result<(), E>
+  where
+    Self::Backend: Compute,
+    F: for<'b> FnOnce(
+      Pipeline<'b, Self::Backend>,
+      ProgramInterface<'b, Self::Backend>,
+      &'b Uni,
+    ) -> Result<(), E>,
+    E: From<ComputeError>,
+  {
+    self.new_compute_gate().dispatch(program, workgroups, f)
+  }
+
   /// Create a [`TessBuilder`].
   ///
   /// See the documentation of [`TessBuilder::new`] for further details.
@@ -196,4 +293,14 @@ pub unsafe trait GraphicsContext: Sized {
   {
     Texture::new(self, size, mipmaps, sampler)
   }
+
+  /// Whether the compressed pixel formats of `family` are supported.
+  ///
+  /// Textures of unsupported compressed pixel formats cannot be created.
+  fn supports_compression(&mut self, family: CompressionFamily) -> bool
+  where
+    Self::Backend: TextureCompression,
+  {
+    unsafe { self.backend().supports_compression(family) }
+  }
 }
@@ -0,0 +1,95 @@
+//! GPU fences.
+//!
+//! A [`Fence`] is a marker inserted in the stream of commands sent to the GPU. It gets _signaled_
+//! once the GPU has executed all the commands issued before it, which allows to know — without
+//! blocking — when the work of a pipeline is done:
+//!
+//! ```ignore
+//! use luminance::context::GraphicsContext as _;
+//!
+//! context
+//!   .new_pipeline_gate()
+//!   .pipeline(&back_buffer, &PipelineState::default(), |_, mut shd_gate| {
+//!     // …
+//!   });
+//!
+//! let mut fence = context.new_fence()?;
+//!
+//! // later on
+//! if fence.is_signaled() {
+//!   println!("the pipeline is done");
+//! }
+//! ```
+//!
+//! Fences are also used internally by readbacks; see the [`readback`] module.
+//!
+//! [`readback`]: crate::readback
+
+use std::error;
+use std::fmt;
+
+use crate::backend::fence::Fence as FenceBackend;
+use crate::context::GraphicsContext;
+
+/// Errors that might occur when using fences.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FenceError {
+  /// Cannot create a fence.
+  CannotCreate,
+}
+
+impl FenceError {
+  /// Cannot create a fence.
+  pub fn cannot_create() -> Self {
+    FenceError::CannotCreate
+  }
+}
+
+impl fmt::Display for FenceError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      FenceError::CannotCreate => f.write_str("cannot create fence"),
+    }
+  }
+}
+
+impl error::Error for FenceError {}
+
+/// A GPU fence.
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+pub struct Fence<B>
+where
+  B: ?Sized + FenceBackend,
+{
+  repr: B::FenceRepr,
+}
+
+impl<B> Fence<B>
+where
+  B: ?Sized + FenceBackend,
+{
+  /// Insert a new [`Fence`] after all the commands issued so far.
+  ///
+  /// # Notes
+  ///
+  /// Feel free to look at the documentation of [`GraphicsContext::new_fence`] for a simpler
+  /// interface.
+  pub fn new<C>(ctx: &mut C) -> Result<Self, FenceError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    let repr = unsafe { ctx.backend().new_fence()? };
+    Ok(Fence { repr })
+  }
+
+  /// Check whether the GPU has executed all the commands issued before the fence.
+  ///
+  /// This function doesn’t block.
+  pub fn is_signaled(&mut self) -> bool {
+    unsafe { B::fence_signaled(&mut self.repr) }
+  }
+}
@@ -42,6 +42,26 @@
 //! slot via [`Framebuffer::depth_slot`]. Once you get textures from the color slots, you can use
 //! them as regular textures as input of next renders, for instance.
 //!
+//! Slots that are never sampled nor read back — typically, depth buffers — can use a
+//! [`Renderbuffer`] instead of a pixel format, such as `Renderbuffer<B, Depth32F>`. The slot is then
+//! an opaque [`Renderbuffer`] rather than a texture, which backends store more efficiently. Only 2D
+//! framebuffers support renderbuffers.
+//!
+//! # Multisampling
+//!
+//! 2D framebuffers can be multisampled by creating them with [`Framebuffer::new_multisample`].
+//! Pipelines then render to several samples per pixel, which are resolved into the color and depth
+//! slots at the end of every pipeline. The slots are regular single-sampled textures, so that
+//! anti-aliased renders can be used as input of next renders, such as post-processing passes.
+//!
+//! # Blitting
+//!
+//! The content of a 2D framebuffer can be copied to another one — the back buffer, for instance —
+//! with [`Framebuffer::blit_to`]. Rectangles of different sizes can be used, in which case the
+//! copied pixels are scaled with a [`BlitFilter`]. The [`BlitMask`] selects which of the color and
+//! depth buffers are copied. The color and depth formats of both framebuffers are checked against
+//! each other when they are known, i.e. when their slots are not `()`.
+//!
 //! ## Note on type generation
 //!
 //! Because framebuffers are highly subject to refinement typing, types are transformed at
@@ -59,14 +79,18 @@
 //! [backend::color_slot]: crate::backend::color_slot
 //! [backend::depth_slot]: crate::backend::depth_slot
 //! [`PipelineGate`]: crate::pipeline::PipelineGate
+//! [`Renderbuffer`]: crate::renderbuffer::Renderbuffer
 
 use std::error;
 use std::fmt;
 
 use crate::backend::color_slot::ColorSlot;
 use crate::backend::depth_slot::DepthSlot;
-use crate::backend::framebuffer::{Framebuffer as FramebufferBackend, FramebufferBackBuffer};
+use crate::backend::framebuffer::{
+  Framebuffer as FramebufferBackend, FramebufferBackBuffer, FramebufferBlit, FramebufferMultisample,
+};
 use crate::context::GraphicsContext;
+use crate::pixel::{PixelFormat, Type};
 use crate::texture::{Dim2, Dimensionable, Sampler, TextureError};
 
 /// Typed framebuffers.
@@ -125,22 +149,37 @@ where
     C: GraphicsContext<Backend = B>,
   {
     unsafe {
-      let mut repr = ctx
+      let repr = ctx
         .backend()
         .new_framebuffer::<CS, DS>(size, mipmaps, &sampler)?;
-      let color_slot = CS::reify_color_textures(ctx, size, mipmaps, &sampler, &mut repr, 0)?;
-      let depth_slot = DS::reify_depth_texture(ctx, size, mipmaps, &sampler, &mut repr)?;
-
-      let repr = B::validate_framebuffer(repr)?;
 
-      Ok(Framebuffer {
-        repr,
-        color_slot,
-        depth_slot,
-      })
+      Self::reify_slots(ctx, repr, size, mipmaps, sampler)
     }
   }
 
+  /// Create the color and depth slots of a framebuffer and validate it.
+  unsafe fn reify_slots<C>(
+    ctx: &mut C,
+    mut repr: B::FramebufferRepr,
+    size: D::Size,
+    mipmaps: usize,
+    sampler: Sampler,
+  ) -> Result<Self, FramebufferError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    let color_slot = CS::reify_color_textures(ctx, size, mipmaps, &sampler, &mut repr, 0)?;
+    let depth_slot = DS::reify_depth_texture(ctx, size, mipmaps, &sampler, &mut repr)?;
+
+    let repr = B::validate_framebuffer(repr)?;
+
+    Ok(Framebuffer {
+      repr,
+      color_slot,
+      depth_slot,
+    })
+  }
+
   /// Get the size of the framebuffer.
   pub fn size(&self) -> D::Size {
     unsafe { B::framebuffer_size(&self.repr) }
@@ -192,6 +231,223 @@ where
   }
 }
 
+impl<B, CS, DS> Framebuffer<B, Dim2, CS, DS>
+where
+  B: ?Sized + FramebufferMultisample,
+  CS: ColorSlot<B, Dim2>,
+  DS: DepthSlot<B, Dim2>,
+{
+  /// Create a new multisampled [`Framebuffer`].
+  ///
+  /// Pipelines render to `samples` samples per pixel, which are resolved into the color and depth
+  /// slots at the end of every pipeline. The slots hold regular single-sampled textures, created
+  /// with `mipmaps` and `sampler` as with [`Framebuffer::new`].
+  ///
+  /// Blits read from and write to the resolved slots.
+  ///
+  /// # Errors
+  ///
+  /// [`FramebufferError::UnsupportedSamples`] is returned if `samples` is zero or greater than
+  /// what the backend supports.
+  ///
+  /// # Notes
+  ///
+  /// You might be interested in the [`GraphicsContext::new_multisample_framebuffer`] function
+  /// instead, which is the exact same function, but benefits from more type inference (based on
+  /// `&mut C`).
+  pub fn new_multisample<C>(
+    ctx: &mut C,
+    size: <Dim2 as Dimensionable>::Size,
+    samples: usize,
+    mipmaps: usize,
+    sampler: Sampler,
+  ) -> Result<Self, FramebufferError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    if samples == 0 {
+      return Err(FramebufferError::unsupported_samples(samples));
+    }
+
+    unsafe {
+      let repr = ctx
+        .backend()
+        .new_multisample_framebuffer::<CS, DS>(size, samples, mipmaps, &sampler)?;
+
+      Self::reify_slots(ctx, repr, size, mipmaps, sampler)
+    }
+  }
+}
+
+impl<B, CS, DS> Framebuffer<B, Dim2, CS, DS>
+where
+  B: ?Sized + FramebufferBlit,
+  CS: ColorSlot<B, Dim2>,
+  DS: DepthSlot<B, Dim2>,
+{
+  /// Copy the pixels in `src_rect` of this framebuffer to `dst_rect` in `dst`.
+  ///
+  /// The color is read from the first color slot and written to all the color slots of `dst`. If
+  /// the rectangles don’t have the same size, the pixels are scaled with `filter`.
+  ///
+  /// # Errors
+  ///
+  /// [`FramebufferError::IncompatibleBlit`] is returned if the known formats of both framebuffers
+  /// cannot be blitted:
+  ///
+  /// - Integral colors can only be blitted to integral colors, and with [`BlitFilter::Nearest`].
+  /// - Depth can only be blitted between identical depth formats, and with [`BlitFilter::Nearest`].
+  ///
+  /// Formats are unknown for slots set to `()`, such as the ones of the back buffer; the backend
+  /// is then responsible for the blit.
+  pub fn blit_to<CS2, DS2>(
+    &self,
+    dst: &mut Framebuffer<B, Dim2, CS2, DS2>,
+    src_rect: BlitRect,
+    dst_rect: BlitRect,
+    mask: BlitMask,
+    filter: BlitFilter,
+  ) -> Result<(), FramebufferError>
+  where
+    CS2: ColorSlot<B, Dim2>,
+    DS2: DepthSlot<B, Dim2>,
+  {
+    if mask.color {
+      check_color_blit(&CS::color_formats(), &CS2::color_formats(), filter)?;
+    }
+
+    if mask.depth {
+      check_depth_blit(DS::depth_format(), DS2::depth_format(), filter)?;
+    }
+
+    unsafe { B::blit_framebuffer(&self.repr, &mut dst.repr, src_rect, dst_rect, mask, filter) }
+  }
+}
+
+fn is_integral(pf: PixelFormat) -> bool {
+  matches!(pf.encoding, Type::Integral | Type::Unsigned)
+}
+
+fn check_color_blit(
+  src: &[PixelFormat],
+  dst: &[PixelFormat],
+  filter: BlitFilter,
+) -> Result<(), FramebufferError> {
+  // the color is read from the first color slot only
+  let src = match src.first() {
+    Some(&src) => src,
+    None => return Ok(()),
+  };
+
+  if is_integral(src) && filter == BlitFilter::Linear {
+    return Err(FramebufferError::incompatible_blit(format!(
+      "integral colors ({:?}) cannot be blitted with linear filtering",
+      src
+    )));
+  }
+
+  match dst
+    .iter()
+    .find(|&&dst| is_integral(dst) != is_integral(src))
+  {
+    Some(dst) => Err(FramebufferError::incompatible_blit(format!(
+      "{:?} colors cannot be blitted to {:?} colors",
+      src, dst
+    ))),
+    None => Ok(()),
+  }
+}
+
+fn check_depth_blit(
+  src: Option<PixelFormat>,
+  dst: Option<PixelFormat>,
+  filter: BlitFilter,
+) -> Result<(), FramebufferError> {
+  if filter == BlitFilter::Linear {
+    return Err(FramebufferError::incompatible_blit(
+      "depth cannot be blitted with linear filtering",
+    ));
+  }
+
+  match (src, dst) {
+    (Some(src), Some(dst)) if src != dst => Err(FramebufferError::incompatible_blit(format!(
+      "{:?} depth cannot be blitted to {:?} depth",
+      src, dst
+    ))),
+    _ => Ok(()),
+  }
+}
+
+/// A rectangle of pixels in a framebuffer, used when blitting.
+///
+/// The origin is the lower-left corner of the framebuffer.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlitRect {
+  /// The lower position on the X axis of the rectangle.
+  pub x: u32,
+  /// The lower position on the Y axis of the rectangle.
+  pub y: u32,
+  /// The width of the rectangle.
+  pub width: u32,
+  /// The height of the rectangle.
+  pub height: u32,
+}
+
+impl BlitRect {
+  /// Create a new [`BlitRect`].
+  pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+    BlitRect {
+      x,
+      y,
+      width,
+      height,
+    }
+  }
+
+  /// A [`BlitRect`] covering a whole framebuffer of size `size`.
+  pub fn whole(size: [u32; 2]) -> Self {
+    BlitRect::new(0, 0, size[0], size[1])
+  }
+}
+
+/// Buffers to copy when blitting.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlitMask {
+  /// Whether the color is copied.
+  pub color: bool,
+  /// Whether the depth is copied.
+  pub depth: bool,
+}
+
+impl BlitMask {
+  /// Copy only the color.
+  pub const COLOR: Self = BlitMask {
+    color: true,
+    depth: false,
+  };
+
+  /// Copy only the depth.
+  pub const DEPTH: Self = BlitMask {
+    color: false,
+    depth: true,
+  };
+
+  /// Copy everything.
+  pub const ALL: Self = BlitMask {
+    color: true,
+    depth: true,
+  };
+}
+
+/// Filter used to scale pixels when blitting rectangles of different sizes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlitFilter {
+  /// Take the nearest pixel.
+  Nearest,
+  /// Linearly interpolate the nearest pixels. Only available for non-integral colors.
+  Linear,
+}
+
 /// Framebuffer error.
 #[non_exhaustive]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -208,6 +464,12 @@ pub enum FramebufferError {
   Incomplete(IncompleteReason),
   /// Cannot attach something to a framebuffer.
   UnsupportedAttachment,
+  /// Cannot blit between two framebuffers.
+  ///
+  /// The carried [`String`] gives the reason of the failure.
+  IncompatibleBlit(String),
+  /// Multisampling with the carried number of samples is not supported.
+  UnsupportedSamples(usize),
 }
 
 impl FramebufferError {
@@ -230,6 +492,16 @@ impl FramebufferError {
   pub fn unsupported_attachment() -> Self {
     FramebufferError::UnsupportedAttachment
   }
+
+  /// Cannot blit between two framebuffers.
+  pub fn incompatible_blit(reason: impl Into<String>) -> Self {
+    FramebufferError::IncompatibleBlit(reason.into())
+  }
+
+  /// Multisampling with the given number of samples is not supported.
+  pub fn unsupported_samples(samples: usize) -> Self {
+    FramebufferError::UnsupportedSamples(samples)
+  }
 }
 
 impl fmt::Display for FramebufferError {
@@ -244,6 +516,12 @@ impl fmt::Display for FramebufferError {
       FramebufferError::Incomplete(ref e) => write!(f, "incomplete framebuffer: {}", e),
 
       FramebufferError::UnsupportedAttachment => f.write_str("unsupported framebuffer attachment"),
+
+      FramebufferError::IncompatibleBlit(ref e) => write!(f, "incompatible blit: {}", e),
+
+      FramebufferError::UnsupportedSamples(samples) => {
+        write!(f, "unsupported multisampling with {} samples", samples)
+      }
     }
   }
 }
@@ -255,6 +533,8 @@ impl std::error::Error for FramebufferError {
       FramebufferError::TextureError(e) => Some(e),
       FramebufferError::Incomplete(e) => Some(e),
       FramebufferError::UnsupportedAttachment => None,
+      FramebufferError::IncompatibleBlit(_) => None,
+      FramebufferError::UnsupportedSamples(_) => None,
     }
   }
 }
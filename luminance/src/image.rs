@@ -0,0 +1,196 @@
+//! [image](https://crates.io/crates/image) crate integration.
+//!
+//! This module converts images from the `image` crate to 2D textures and back. Textures are
+//! created out of a [`DynamicImage`] with [`Texture::from_image`], converting its pixels to the
+//! pixel format of the texture; their texels are read back into an [`ImageBuffer`] with
+//! [`Texture::to_image`]. The color of any 2D framebuffer — including the back buffer — is read
+//! with [`Framebuffer::to_image`], which is typically used to take screenshots.
+//!
+//! Only the pixel formats implementing [`ImagePixel`] can be converted from and to images.
+//!
+//! Images are stored top to bottom while the first row of a texture is at the bottom of it: rows
+//! are flipped vertically both ways, so that textures are sampled and images are displayed the
+//! right way up.
+//!
+//! That module is only available with the `"image"` feature.
+
+use ::image::{DynamicImage, GenericImageView as _, ImageBuffer, Luma, LumaA, Rgb, Rgba};
+
+use crate::backend::color_slot::ColorSlot;
+use crate::backend::depth_slot::DepthSlot;
+use crate::backend::framebuffer::FramebufferBlit;
+use crate::backend::texture::Texture as TextureBackend;
+use crate::context::GraphicsContext;
+use crate::framebuffer::{BlitFilter, BlitMask, BlitRect, Framebuffer, FramebufferError};
+use crate::pixel::{
+  ColorPixel, NormR16UI, NormR8UI, NormRG16UI, NormRG8UI, NormRGB16UI, NormRGB8UI, NormRGBA16UI,
+  NormRGBA8UI, Pixel, RenderablePixel, R32F, RG32F, RGB32F, RGBA32F, SRGB8UI, SRGBA8UI,
+};
+use crate::texture::{Dim2, GenMipmaps, Sampler, Texture, TextureError};
+
+/// Image buffer holding pixels of the pixel format `P`.
+pub type TextureImage<P> = ImageBuffer<<P as ImagePixel>::Image, Vec<<P as Pixel>::RawEncoding>>;
+
+/// Pixel formats that can be converted from and to images.
+pub trait ImagePixel: Pixel {
+  /// Pixel type of the image buffers holding pixels of this pixel format.
+  type Image: ::image::Pixel<Subpixel = Self::RawEncoding> + 'static;
+
+  /// Convert the pixels of an image to this pixel format.
+  fn from_dynamic_image(image: &DynamicImage) -> TextureImage<Self>;
+}
+
+macro_rules! impl_ImagePixel {
+  ($t:ty, $image:ty, $convert:ident) => {
+    impl ImagePixel for $t {
+      type Image = $image;
+
+      fn from_dynamic_image(image: &DynamicImage) -> TextureImage<Self> {
+        image.$convert()
+      }
+    }
+  };
+
+  // floating pixel formats are normalized out of their 8-bit or 16-bit counterparts, depending on
+  // the depth of the image
+  ($t:ty, $image:ty, $convert8:ident, $convert16:ident) => {
+    impl ImagePixel for $t {
+      type Image = $image;
+
+      fn from_dynamic_image(image: &DynamicImage) -> TextureImage<Self> {
+        let (width, height) = image.dimensions();
+        let texels = match image {
+          DynamicImage::ImageLuma16(_)
+          | DynamicImage::ImageLumaA16(_)
+          | DynamicImage::ImageRgb16(_)
+          | DynamicImage::ImageRgba16(_) => normalize(image.$convert16().into_raw(), u16::MAX),
+          _ => normalize(image.$convert8().into_raw(), u8::MAX),
+        };
+
+        // the number of texels is kept
+        ImageBuffer::from_raw(width, height, texels).unwrap()
+      }
+    }
+  };
+}
+
+impl_ImagePixel!(NormR8UI, Luma<u8>, to_luma8);
+impl_ImagePixel!(NormRG8UI, LumaA<u8>, to_luma_alpha8);
+impl_ImagePixel!(NormRGB8UI, Rgb<u8>, to_rgb8);
+impl_ImagePixel!(NormRGBA8UI, Rgba<u8>, to_rgba8);
+impl_ImagePixel!(SRGB8UI, Rgb<u8>, to_rgb8);
+impl_ImagePixel!(SRGBA8UI, Rgba<u8>, to_rgba8);
+impl_ImagePixel!(NormR16UI, Luma<u16>, to_luma16);
+impl_ImagePixel!(NormRG16UI, LumaA<u16>, to_luma_alpha16);
+impl_ImagePixel!(NormRGB16UI, Rgb<u16>, to_rgb16);
+impl_ImagePixel!(NormRGBA16UI, Rgba<u16>, to_rgba16);
+impl_ImagePixel!(R32F, Luma<f32>, to_luma8, to_luma16);
+impl_ImagePixel!(RG32F, LumaA<f32>, to_luma_alpha8, to_luma_alpha16);
+impl_ImagePixel!(RGB32F, Rgb<f32>, to_rgb8, to_rgb16);
+impl_ImagePixel!(RGBA32F, Rgba<f32>, to_rgba8, to_rgba16);
+
+impl<B, P> Texture<B, Dim2, P>
+where
+  B: ?Sized + TextureBackend<Dim2, P>,
+  P: ImagePixel,
+  P::RawEncoding: 'static,
+{
+  /// Create a new [`Texture`] out of an image.
+  ///
+  /// The pixels of `image` are converted to `P`, and its rows are flipped vertically. `mipmaps` is
+  /// the number of extra mipmaps to allocate with the texture; they are generated out of the image.
+  pub fn from_image<C>(
+    ctx: &mut C,
+    image: &DynamicImage,
+    mipmaps: usize,
+    sampler: Sampler,
+  ) -> Result<Self, TextureError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    let image = P::from_dynamic_image(image);
+    let (width, height) = image.dimensions();
+    let mut texels = image.into_raw();
+    flip_rows(&mut texels, height);
+
+    let gen_mipmaps = if mipmaps > 0 {
+      GenMipmaps::Yes
+    } else {
+      GenMipmaps::No
+    };
+
+    let mut texture = Texture::new(ctx, [width, height], mipmaps, sampler)?;
+    texture.upload_raw(gen_mipmaps, &texels)?;
+
+    Ok(texture)
+  }
+
+  /// Get a copy of the base level of the texture as an image.
+  ///
+  /// The rows of the texture are flipped vertically.
+  pub fn to_image(&self) -> Result<TextureImage<P>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let [width, height] = self.size();
+    let mut texels = self.get_raw_texels()?;
+    flip_rows(&mut texels, height);
+
+    ImageBuffer::from_raw(width, height, texels).ok_or_else(|| {
+      TextureError::cannot_retrieve_texels("texels don’t fit the size of the texture")
+    })
+  }
+}
+
+impl<B, CS, DS> Framebuffer<B, Dim2, CS, DS>
+where
+  B: ?Sized + FramebufferBlit,
+  CS: ColorSlot<B, Dim2>,
+  DS: DepthSlot<B, Dim2>,
+{
+  /// Read the color of the framebuffer as an image.
+  ///
+  /// The color of the first color slot is blitted to a temporary framebuffer of pixel format `P`,
+  /// which is then read back. That works with any framebuffer — typically, the back buffer, to take
+  /// screenshots. The rows of the framebuffer are flipped vertically.
+  pub fn to_image<C, P>(&self, ctx: &mut C) -> Result<TextureImage<P>, FramebufferError>
+  where
+    C: GraphicsContext<Backend = B>,
+    B: TextureBackend<Dim2, P>,
+    P: ColorPixel + RenderablePixel + ImagePixel,
+    P::RawEncoding: Copy + Default + 'static,
+  {
+    let size = self.size();
+    let rect = BlitRect::whole(size);
+    let mut dst = Framebuffer::<B, Dim2, P, ()>::new(ctx, size, 0, Sampler::default())?;
+
+    self.blit_to(&mut dst, rect, rect, BlitMask::COLOR, BlitFilter::Nearest)?;
+
+    Ok(dst.color_slot().to_image()?)
+  }
+}
+
+/// Normalize unsigned channels to floating channels in *[0; 1]*.
+fn normalize<T>(channels: Vec<T>, max: T) -> Vec<f32>
+where
+  T: Into<f32>,
+{
+  let max = max.into();
+  channels.into_iter().map(|x| x.into() / max).collect()
+}
+
+/// Flip the rows of texels vertically.
+fn flip_rows<T>(texels: &mut [T], height: u32) {
+  let height = height as usize;
+
+  if height == 0 {
+    return;
+  }
+
+  let row_len = texels.len() / height;
+
+  for y in 0..height / 2 {
+    let (top, bottom) = texels.split_at_mut((height - 1 - y) * row_len);
+    top[y * row_len..(y + 1) * row_len].swap_with_slice(&mut bottom[..row_len]);
+  }
+}
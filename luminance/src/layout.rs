@@ -0,0 +1,500 @@
+//! Memory layouts of shader blocks.
+//!
+//! Uniform blocks and storage blocks are declared in shaders with an explicit memory layout, that
+//! tells where each member of the block lives in memory. Two standard layouts exist:
+//!
+//! - `std140`, usable with both uniform blocks and storage blocks.
+//! - `std430`, usable with storage blocks only. It is the same as `std140`, except that arrays and
+//!   structures are not rounded up to the alignment of a `vec4`, making it more compact.
+//!
+//! Those layouts impose alignment rules that Rust types don’t follow: a `vec3`, for instance, is
+//! aligned on 16 bytes, while a `[f32; 3]` is aligned on 4 bytes. The [`Std140`] and [`Std430`]
+//! traits map a type to its _encoded_ type — a type that has the exact memory representation the
+//! layout expects — so that you can store the encoded values in a [`Buffer`] and bind it to
+//! a block.
+//!
+//! Those traits are implemented for:
+//!
+//! - Scalars: `f32`, `f64`, `i32`, `u32` and `bool`.
+//! - Vectors: arrays of two, three or four scalars, like `[f32; 3]` for a `vec3`.
+//! - Arrays: `[T; N]` of vectors, arrays or structures. Matrices are arrays of column vectors —
+//!   `[[f32; 4]; 4]` for a `mat4` — and share their layout.
+//! - Arrays of scalars: [`Scalars`], like `Scalars<f32, 8>` for a `float[8]`. Because `[f32; 2]`,
+//!   `[f32; 3]` and `[f32; 4]` are vectors, arrays of scalars must be wrapped to get the layout of
+//!   arrays. The derive proc-macros do it for fields of type `[T; N]` where `T` is a scalar and `N`
+//!   is not `2`, `3` or `4`.
+//! - Structures, via the `Std140` and `Std430` derive proc-macros.
+//!
+//! [`Buffer`]: crate::buffer::Buffer
+
+use std::mem;
+
+/// Types that can be stored in shader blocks with the `std140` layout.
+///
+/// # Safety
+///
+/// [`Std140::Encoded`] must have the size `std140` gives to the type, and [`Std140::ALIGNMENT`]
+/// must be its `std140` base alignment.
+pub unsafe trait Std140: Copy {
+  /// Encoded representation of the type.
+  type Encoded: Copy;
+
+  /// Base alignment of the type, in bytes.
+  const ALIGNMENT: usize;
+
+  /// Encode a value.
+  fn std140_encode(self) -> Self::Encoded;
+
+  /// Decode an encoded value.
+  fn std140_decode(encoded: Self::Encoded) -> Self;
+}
+
+/// Types that can be stored in arrays with the `std140` layout.
+///
+/// # Safety
+///
+/// [`Std140ArrayElement::Element`] must be the type encoded with its trailing padding, so that
+/// its size is the `std140` array stride of the type.
+pub unsafe trait Std140ArrayElement: Std140 {
+  /// Encoded representation of the type when stored in an array.
+  type Element: Copy;
+
+  /// Encode a value as an array element.
+  fn std140_encode_element(self) -> Self::Element;
+
+  /// Decode an encoded array element.
+  fn std140_decode_element(element: Self::Element) -> Self;
+}
+
+/// Types that can be stored in shader blocks with the `std430` layout.
+///
+/// # Safety
+///
+/// [`Std430::Encoded`] must have the size `std430` gives to the type, and [`Std430::ALIGNMENT`]
+/// must be its `std430` base alignment.
+pub unsafe trait Std430: Copy {
+  /// Encoded representation of the type.
+  type Encoded: Copy;
+
+  /// Base alignment of the type, in bytes.
+  const ALIGNMENT: usize;
+
+  /// Encode a value.
+  fn std430_encode(self) -> Self::Encoded;
+
+  /// Decode an encoded value.
+  fn std430_decode(encoded: Self::Encoded) -> Self;
+}
+
+/// Types that can be stored in arrays with the `std430` layout.
+///
+/// # Safety
+///
+/// [`Std430ArrayElement::Element`] must be the type encoded with its trailing padding, so that
+/// its size is the `std430` array stride of the type.
+pub unsafe trait Std430ArrayElement: Std430 {
+  /// Encoded representation of the type when stored in an array.
+  type Element: Copy;
+
+  /// Encode a value as an array element.
+  fn std430_encode_element(self) -> Self::Element;
+
+  /// Decode an encoded array element.
+  fn std430_decode_element(element: Self::Element) -> Self;
+}
+
+/// Scalar types, which can be stored in arrays of scalars with [`Scalars`].
+///
+/// # Safety
+///
+/// [`Scalar::Std140Element`] must be the `std140` encoded scalar followed by the padding required
+/// to reach 16 bytes, the `std140` array stride of scalars.
+pub unsafe trait Scalar: Std140 + Std430 {
+  /// Encoded representation of the scalar when stored in a `std140` array.
+  type Std140Element: Copy;
+
+  /// Encode a scalar as a `std140` array element.
+  fn std140_encode_scalar(self) -> Self::Std140Element;
+
+  /// Decode a `std140` array element.
+  fn std140_decode_scalar(element: Self::Std140Element) -> Self;
+}
+
+/// Array of scalars, such as a `float[N]`.
+///
+/// Arrays of scalars are rounded up to the alignment of a `vec4` with `std140`, both for their own
+/// alignment and for the stride of their elements; `std430` packs them tightly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct Scalars<T, const N: usize>(pub [T; N]);
+
+/// Round `offset` up to the next multiple of `alignment`.
+///
+/// `alignment` must be a power of two.
+pub const fn align_up(offset: usize, alignment: usize) -> usize {
+  (offset + alignment - 1) & !(alignment - 1)
+}
+
+/// Largest alignment of `alignments`, or `min` if they are all smaller.
+///
+/// This is the base alignment of a structure which members have the `alignments` alignments.
+pub const fn max_alignment(min: usize, alignments: &[usize]) -> usize {
+  let mut max = min;
+  let mut i = 0;
+
+  while i < alignments.len() {
+    if alignments[i] > max {
+      max = alignments[i];
+    }
+
+    i += 1;
+  }
+
+  max
+}
+
+// Macro to implement the layout traits for a scalar type.
+//
+// The std140 array stride of scalars is 16 bytes, so their array elements are made of
+// `$std140_element_n` encoded scalars.
+macro_rules! impl_scalar {
+  ($t:ty, $e:ty, $encode:expr, $decode:expr, $std140_element_n:literal) => {
+    unsafe impl Std140 for $t {
+      type Encoded = $e;
+
+      const ALIGNMENT: usize = mem::size_of::<$e>();
+
+      fn std140_encode(self) -> Self::Encoded {
+        $encode(self)
+      }
+
+      fn std140_decode(encoded: Self::Encoded) -> Self {
+        $decode(encoded)
+      }
+    }
+
+    unsafe impl Std430 for $t {
+      type Encoded = $e;
+
+      const ALIGNMENT: usize = mem::size_of::<$e>();
+
+      fn std430_encode(self) -> Self::Encoded {
+        $encode(self)
+      }
+
+      fn std430_decode(encoded: Self::Encoded) -> Self {
+        $decode(encoded)
+      }
+    }
+
+    unsafe impl Scalar for $t {
+      type Std140Element = [$e; $std140_element_n];
+
+      fn std140_encode_scalar(self) -> Self::Std140Element {
+        let mut element = [<$e>::default(); $std140_element_n];
+        element[0] = self.std140_encode();
+        element
+      }
+
+      fn std140_decode_scalar(element: Self::Std140Element) -> Self {
+        Self::std140_decode(element[0])
+      }
+    }
+  };
+
+  ($t:ty, $std140_element_n:literal) => {
+    impl_scalar!($t, $t, |x| x, |x| x, $std140_element_n);
+  };
+}
+
+impl_scalar!(f32, 4);
+impl_scalar!(f64, 2);
+impl_scalar!(i32, 4);
+impl_scalar!(u32, 4);
+impl_scalar!(bool, u32, u32::from, |x| x != 0, 4);
+
+// Macro to implement a layout trait for a vector type.
+//
+// Vectors of two components are aligned on twice the size of their components; vectors of three
+// and four components on four times that size. The array stride of a vector is its alignment,
+// rounded up to 16 bytes for std140.
+macro_rules! impl_vector_layout {
+  (
+    $layout:ident,
+    $element_trait:ident,
+    $encode:ident,
+    $decode:ident,
+    $encode_element:ident,
+    $decode_element:ident,
+    $t:ty,
+    $e:ty,
+    $n:literal,
+    $align_n:literal,
+    $element_n:literal
+  ) => {
+    unsafe impl $layout for [$t; $n] {
+      type Encoded = [$e; $n];
+
+      const ALIGNMENT: usize = $align_n * mem::size_of::<$e>();
+
+      fn $encode(self) -> Self::Encoded {
+        let mut encoded = [<$e>::default(); $n];
+
+        for (e, x) in encoded.iter_mut().zip(self.iter()) {
+          *e = x.$encode();
+        }
+
+        encoded
+      }
+
+      fn $decode(encoded: Self::Encoded) -> Self {
+        let mut decoded = [<$t>::default(); $n];
+
+        for (x, e) in decoded.iter_mut().zip(encoded.iter()) {
+          *x = <$t>::$decode(*e);
+        }
+
+        decoded
+      }
+    }
+
+    unsafe impl $element_trait for [$t; $n] {
+      type Element = [$e; $element_n];
+
+      fn $encode_element(self) -> Self::Element {
+        let mut element = [<$e>::default(); $element_n];
+        element[..$n].copy_from_slice(&self.$encode());
+        element
+      }
+
+      fn $decode_element(element: Self::Element) -> Self {
+        let mut encoded = [<$e>::default(); $n];
+        encoded.copy_from_slice(&element[..$n]);
+        Self::$decode(encoded)
+      }
+    }
+  };
+}
+
+// Macro to implement the layout traits for the vectors of a scalar type.
+macro_rules! impl_vectors {
+  ($t:ty, $e:ty, $std140_vec2_element_n:literal) => {
+    impl_vector_layout!(
+      Std140,
+      Std140ArrayElement,
+      std140_encode,
+      std140_decode,
+      std140_encode_element,
+      std140_decode_element,
+      $t,
+      $e,
+      2,
+      2,
+      $std140_vec2_element_n
+    );
+    impl_vector_layout!(
+      Std140,
+      Std140ArrayElement,
+      std140_encode,
+      std140_decode,
+      std140_encode_element,
+      std140_decode_element,
+      $t,
+      $e,
+      3,
+      4,
+      4
+    );
+    impl_vector_layout!(
+      Std140,
+      Std140ArrayElement,
+      std140_encode,
+      std140_decode,
+      std140_encode_element,
+      std140_decode_element,
+      $t,
+      $e,
+      4,
+      4,
+      4
+    );
+    impl_vector_layout!(
+      Std430,
+      Std430ArrayElement,
+      std430_encode,
+      std430_decode,
+      std430_encode_element,
+      std430_decode_element,
+      $t,
+      $e,
+      2,
+      2,
+      2
+    );
+    impl_vector_layout!(
+      Std430,
+      Std430ArrayElement,
+      std430_encode,
+      std430_decode,
+      std430_encode_element,
+      std430_decode_element,
+      $t,
+      $e,
+      3,
+      4,
+      4
+    );
+    impl_vector_layout!(
+      Std430,
+      Std430ArrayElement,
+      std430_encode,
+      std430_decode,
+      std430_encode_element,
+      std430_decode_element,
+      $t,
+      $e,
+      4,
+      4,
+      4
+    );
+  };
+}
+
+// a vec2 of 32-bit scalars is 8 bytes long, padded to 16 in std140 arrays; a dvec2 already is 16
+impl_vectors!(f32, f32, 4);
+impl_vectors!(f64, f64, 2);
+impl_vectors!(i32, i32, 4);
+impl_vectors!(u32, u32, 4);
+impl_vectors!(bool, u32, 4);
+
+unsafe impl<T, const N: usize> Std140 for [T; N]
+where
+  T: Std140ArrayElement,
+{
+  type Encoded = [T::Element; N];
+
+  // the alignment of arrays is rounded up to the alignment of a vec4
+  const ALIGNMENT: usize = align_up(T::ALIGNMENT, 16);
+
+  fn std140_encode(self) -> Self::Encoded {
+    self.map(T::std140_encode_element)
+  }
+
+  fn std140_decode(encoded: Self::Encoded) -> Self {
+    encoded.map(T::std140_decode_element)
+  }
+}
+
+// an array is already a multiple of its alignment, so it doesn’t need any trailing padding
+unsafe impl<T, const N: usize> Std140ArrayElement for [T; N]
+where
+  T: Std140ArrayElement,
+{
+  type Element = Self::Encoded;
+
+  fn std140_encode_element(self) -> Self::Element {
+    self.std140_encode()
+  }
+
+  fn std140_decode_element(element: Self::Element) -> Self {
+    Self::std140_decode(element)
+  }
+}
+
+unsafe impl<T, const N: usize> Std430 for [T; N]
+where
+  T: Std430ArrayElement,
+{
+  type Encoded = [T::Element; N];
+
+  const ALIGNMENT: usize = T::ALIGNMENT;
+
+  fn std430_encode(self) -> Self::Encoded {
+    self.map(T::std430_encode_element)
+  }
+
+  fn std430_decode(encoded: Self::Encoded) -> Self {
+    encoded.map(T::std430_decode_element)
+  }
+}
+
+unsafe impl<T, const N: usize> Std430ArrayElement for [T; N]
+where
+  T: Std430ArrayElement,
+{
+  type Element = Self::Encoded;
+
+  fn std430_encode_element(self) -> Self::Element {
+    self.std430_encode()
+  }
+
+  fn std430_decode_element(element: Self::Element) -> Self {
+    Self::std430_decode(element)
+  }
+}
+
+unsafe impl<T, const N: usize> Std140 for Scalars<T, N>
+where
+  T: Scalar,
+{
+  type Encoded = [T::Std140Element; N];
+
+  // the alignment of arrays is rounded up to the alignment of a vec4
+  const ALIGNMENT: usize = align_up(<T as Std140>::ALIGNMENT, 16);
+
+  fn std140_encode(self) -> Self::Encoded {
+    self.0.map(T::std140_encode_scalar)
+  }
+
+  fn std140_decode(encoded: Self::Encoded) -> Self {
+    Scalars(encoded.map(T::std140_decode_scalar))
+  }
+}
+
+// elements are 16 bytes long, so the array doesn’t need any trailing padding
+unsafe impl<T, const N: usize> Std140ArrayElement for Scalars<T, N>
+where
+  T: Scalar,
+{
+  type Element = Self::Encoded;
+
+  fn std140_encode_element(self) -> Self::Element {
+    self.std140_encode()
+  }
+
+  fn std140_decode_element(element: Self::Element) -> Self {
+    Self::std140_decode(element)
+  }
+}
+
+unsafe impl<T, const N: usize> Std430 for Scalars<T, N>
+where
+  T: Scalar,
+{
+  type Encoded = [<T as Std430>::Encoded; N];
+
+  const ALIGNMENT: usize = <T as Std430>::ALIGNMENT;
+
+  fn std430_encode(self) -> Self::Encoded {
+    self.0.map(<T as Std430>::std430_encode)
+  }
+
+  fn std430_decode(encoded: Self::Encoded) -> Self {
+    Scalars(encoded.map(<T as Std430>::std430_decode))
+  }
+}
+
+unsafe impl<T, const N: usize> Std430ArrayElement for Scalars<T, N>
+where
+  T: Scalar,
+{
+  type Element = Self::Encoded;
+
+  fn std430_encode_element(self) -> Self::Element {
+    self.std430_encode()
+  }
+
+  fn std430_decode_element(element: Self::Element) -> Self {
+    Self::std430_decode(element)
+  }
+}
@@ -42,12 +42,13 @@
 //! - **Framebuffers**: framebuffers are used to hold renders. Each time you want to perform a
 //!   render, you need to perform it into a framebuffer. Framebuffers can then be combined with
 //!   each other to produce effects and design render layers — this is called compositing.
-//! - **Shaders**: luminance supports five kinds of shader stages:
+//! - **Shaders**: luminance supports six kinds of shader stages:
 //!     - Vertex shaders.
 //!     - Tessellation control shaders.
 //!     - Tessellation evaluation shaders.
 //!     - Geometry shaders.
 //!     - Fragment shaders.
+//!     - Compute shaders, which run outside of graphics pipelines.
 //! - **Vertices, indices, primitives and tessellations**: those are used to define a shape you
 //!   can render into a framebuffer with a shader. They are mandatory when it comes to rendering.
 //!   Even if you don’t need vertex data, you still need tessellations to issue draw calls.
@@ -187,17 +188,29 @@ pub use luminance_derive::*;
 pub mod backend;
 pub mod blending;
 pub mod buffer;
+pub mod compute;
 pub mod context;
 pub mod depth_test;
 pub mod face_culling;
+pub mod fence;
 pub mod framebuffer;
+#[cfg(feature = "image")]
+pub mod image;
+pub mod layout;
+#[cfg(feature = "loader")]
+pub mod loader;
 pub mod pipeline;
 pub mod pixel;
+pub mod query;
+pub mod readback;
 pub mod render_gate;
 pub mod render_state;
+pub mod renderbuffer;
 pub mod scissor;
 pub mod shader;
 pub mod shading_gate;
+pub mod stencil;
+pub mod stream;
 pub mod tess;
 pub mod tess_gate;
 pub mod texture;
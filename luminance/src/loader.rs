@@ -0,0 +1,401 @@
+//! Texture container loading.
+//!
+//! Textures are often shipped in _container_ files, holding the texels of every mipmap level,
+//! layer and face of a texture along with its pixel format, ready to be uploaded. This module
+//! parses the [KTX2] and [DDS] container formats into a [`TextureContainer`], which is then turned
+//! into a [`Texture`] of the matching [`Dimensionable`] type and [`Pixel`] format.
+//!
+//! Because the dimension and pixel format of a texture are part of its type, they must be known
+//! when creating it. [`TextureContainer::dim`] and [`TextureContainer::pixel_format`] give the
+//! ones of a container, so that you can pick the right types; asking for other ones fails with a
+//! [`LoaderError`].
+//!
+//! That module is only available with the `"loader"` feature.
+//!
+//! [KTX2]: https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html
+//! [DDS]: https://learn.microsoft.com/en-us/windows/win32/direct3ddds/dx-graphics-dds-pguide
+
+mod dds;
+mod ktx2;
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::ptr;
+
+use crate::backend::texture::{
+  Texture as TextureBackend, TextureCompressed as TextureCompressedBackend,
+};
+use crate::context::GraphicsContext;
+use crate::pixel::{CompressedPixel, Format, Pixel, PixelFormat};
+use crate::texture::{Dim, Dimensionable, Sampler, Texture, TextureError};
+
+/// Errors that might happen when loading textures.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LoaderError {
+  /// The container is malformed.
+  ///
+  /// The carried [`String`] gives the reason of the failure.
+  InvalidData(String),
+  /// The container holds a texture luminance doesn’t support, such as an unknown pixel format or
+  /// a supercompressed texture.
+  UnsupportedFormat(String),
+  /// The dimension of the container isn’t the one of the texture to create.
+  DimMismatch {
+    /// Dimension of the texture to create.
+    expected: Dim,
+    /// Dimension of the container.
+    found: Dim,
+  },
+  /// The pixel format of the container isn’t the one of the texture to create.
+  PixelFormatMismatch {
+    /// Pixel format of the texture to create.
+    expected: PixelFormat,
+    /// Pixel format of the container.
+    found: PixelFormat,
+  },
+  /// Error occurring while creating or uploading the texture.
+  TextureError(TextureError),
+}
+
+impl LoaderError {
+  /// The container is malformed.
+  pub fn invalid_data(reason: impl Into<String>) -> Self {
+    LoaderError::InvalidData(reason.into())
+  }
+
+  /// The container holds a texture luminance doesn’t support.
+  pub fn unsupported_format(reason: impl Into<String>) -> Self {
+    LoaderError::UnsupportedFormat(reason.into())
+  }
+
+  /// The dimension of the container isn’t the one of the texture to create.
+  pub fn dim_mismatch(expected: Dim, found: Dim) -> Self {
+    LoaderError::DimMismatch { expected, found }
+  }
+
+  /// The pixel format of the container isn’t the one of the texture to create.
+  pub fn pixel_format_mismatch(expected: PixelFormat, found: PixelFormat) -> Self {
+    LoaderError::PixelFormatMismatch { expected, found }
+  }
+
+  /// Error occurring while creating or uploading the texture.
+  pub fn texture_error(e: TextureError) -> Self {
+    LoaderError::TextureError(e)
+  }
+}
+
+impl fmt::Display for LoaderError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      LoaderError::InvalidData(ref e) => write!(f, "invalid texture container: {}", e),
+
+      LoaderError::UnsupportedFormat(ref e) => {
+        write!(f, "unsupported texture container: {}", e)
+      }
+
+      LoaderError::DimMismatch { expected, found } => write!(
+        f,
+        "dimension mismatch: expected {:?}, found {:?}",
+        expected, found
+      ),
+
+      LoaderError::PixelFormatMismatch { expected, found } => write!(
+        f,
+        "pixel format mismatch: expected {:?}, found {:?}",
+        expected, found
+      ),
+
+      LoaderError::TextureError(ref e) => write!(f, "cannot load texture: {}", e),
+    }
+  }
+}
+
+impl error::Error for LoaderError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      LoaderError::TextureError(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<TextureError> for LoaderError {
+  fn from(e: TextureError) -> Self {
+    LoaderError::texture_error(e)
+  }
+}
+
+/// A texture parsed out of a container file.
+///
+/// The texels of every mipmap level are stored the way [`Texture`] expects them: the layers of
+/// array textures and the faces of cubemaps — in [`CubeFace`] order — follow each other in every
+/// level.
+///
+/// [`CubeFace`]: crate::texture::CubeFace
+#[derive(Clone, Debug)]
+pub struct TextureContainer {
+  dim: Dim,
+  pixel_format: PixelFormat,
+  size: [u32; 3],
+  levels: Vec<Vec<u8>>,
+}
+
+impl TextureContainer {
+  /// Parse a [KTX2](https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html) file.
+  ///
+  /// Supercompressed files and cubemap arrays are not supported.
+  pub fn from_ktx2(bytes: &[u8]) -> Result<Self, LoaderError> {
+    ktx2::parse(bytes)
+  }
+
+  /// Parse a [DDS](https://learn.microsoft.com/en-us/windows/win32/direct3ddds/dx-graphics-dds-pguide)
+  /// file.
+  ///
+  /// Both legacy files and files with a DX10 header are supported; cubemap arrays and partial
+  /// cubemaps are not.
+  pub fn from_dds(bytes: &[u8]) -> Result<Self, LoaderError> {
+    dds::parse(bytes)
+  }
+
+  /// Create a [`TextureContainer`] out of the texels of its mipmap levels, checking that every
+  /// level holds the right amount of texels.
+  fn new(
+    dim: Dim,
+    pixel_format: PixelFormat,
+    size: [u32; 3],
+    levels: Vec<Vec<u8>>,
+  ) -> Result<Self, LoaderError> {
+    if size.contains(&0) {
+      return Err(LoaderError::invalid_data("empty texture"));
+    }
+
+    if dim == Dim::Cubemap && size[0] != size[1] {
+      return Err(LoaderError::invalid_data("cubemap faces are not square"));
+    }
+
+    if levels.is_empty() {
+      return Err(LoaderError::invalid_data("no mipmap level"));
+    }
+
+    for (level, texels) in levels.iter().enumerate() {
+      let expected_bytes = level_bytes(dim, pixel_format, size, level)?;
+
+      if texels.len() != expected_bytes {
+        return Err(LoaderError::invalid_data(format!(
+          "mipmap level {} holds {} bytes instead of {}",
+          level,
+          texels.len(),
+          expected_bytes
+        )));
+      }
+    }
+
+    Ok(TextureContainer {
+      dim,
+      pixel_format,
+      size,
+      levels,
+    })
+  }
+
+  /// Dimension of the texture.
+  pub fn dim(&self) -> Dim {
+    self.dim
+  }
+
+  /// Pixel format of the texture.
+  pub fn pixel_format(&self) -> PixelFormat {
+    self.pixel_format
+  }
+
+  /// Width of the base level.
+  pub fn width(&self) -> u32 {
+    self.size[0]
+  }
+
+  /// Height of the base level, or number of layers of 1D array textures.
+  pub fn height(&self) -> u32 {
+    self.size[1]
+  }
+
+  /// Depth of the base level, number of layers of 2D array textures or number of faces of
+  /// cubemaps.
+  pub fn depth(&self) -> u32 {
+    self.size[2]
+  }
+
+  /// Return the number of mipmap levels, including the base level.
+  pub fn mipmaps(&self) -> usize {
+    self.levels.len()
+  }
+
+  /// Texels of a mipmap level, `0` being the base level.
+  pub fn level(&self, level: usize) -> Option<&[u8]> {
+    self.levels.get(level).map(Vec::as_slice)
+  }
+
+  /// Size of the texture, as a [`Dimensionable::Size`].
+  pub fn size<D>(&self) -> Result<D::Size, LoaderError>
+  where
+    D: Dimensionable,
+  {
+    if D::dim() != self.dim {
+      return Err(LoaderError::dim_mismatch(D::dim(), self.dim));
+    }
+
+    Ok(D::new_size(self.size[0], self.size[1], self.size[2]))
+  }
+
+  /// Create a [`Texture`] out of the container.
+  ///
+  /// `D` and `P` must be the dimension and pixel format of the container; they are checked against
+  /// [`TextureContainer::dim`] and [`TextureContainer::pixel_format`].
+  ///
+  /// The texture has as many mipmap levels as the container, and all of them are uploaded. Use
+  /// [`TextureContainer::new_compressed_texture`] for compressed pixel formats.
+  pub fn new_texture<C, D, P>(
+    &self,
+    ctx: &mut C,
+    sampler: Sampler,
+  ) -> Result<Texture<C::Backend, D, P>, LoaderError>
+  where
+    C: GraphicsContext,
+    C::Backend: TextureBackend<D, P>,
+    D: Dimensionable,
+    P: Pixel,
+    P::RawEncoding: Copy + Default,
+  {
+    let size = self.size::<D>()?;
+    self.check_pixel_format::<P>()?;
+
+    if let Some(compression) = self.pixel_format.format.compression() {
+      return Err(LoaderError::unsupported_format(format!(
+        "{:?} compressed texels must be loaded with new_compressed_texture",
+        compression
+      )));
+    }
+
+    let mut texture = Texture::new(ctx, size, self.mipmaps() - 1, sampler)?;
+
+    for (level, texels) in self.levels.iter().enumerate() {
+      let mut raw =
+        vec![P::RawEncoding::default(); texels.len() / mem::size_of::<P::RawEncoding>()];
+
+      // the length of the levels is checked against the pixel format while parsing
+      unsafe {
+        ptr::copy_nonoverlapping(texels.as_ptr(), raw.as_mut_ptr() as *mut u8, texels.len());
+      }
+
+      texture.upload_level_raw(level, &raw)?;
+    }
+
+    Ok(texture)
+  }
+
+  /// Create a [`Texture`] of a compressed pixel format out of the container, uploading all of its
+  /// mipmap levels.
+  ///
+  /// `D` and `P` must be the dimension and pixel format of the container; they are checked against
+  /// [`TextureContainer::dim`] and [`TextureContainer::pixel_format`].
+  pub fn new_compressed_texture<C, D, P>(
+    &self,
+    ctx: &mut C,
+    sampler: Sampler,
+  ) -> Result<Texture<C::Backend, D, P>, LoaderError>
+  where
+    C: GraphicsContext,
+    C::Backend: TextureCompressedBackend<D, P>,
+    D: Dimensionable,
+    P: CompressedPixel,
+  {
+    let size = self.size::<D>()?;
+    self.check_pixel_format::<P>()?;
+
+    let mut texture = Texture::new(ctx, size, self.mipmaps() - 1, sampler)?;
+
+    for (level, blocks) in self.levels.iter().enumerate() {
+      texture.upload_compressed(level, blocks)?;
+    }
+
+    Ok(texture)
+  }
+
+  fn check_pixel_format<P>(&self) -> Result<(), LoaderError>
+  where
+    P: Pixel,
+  {
+    if P::pixel_format() != self.pixel_format {
+      return Err(LoaderError::pixel_format_mismatch(
+        P::pixel_format(),
+        self.pixel_format,
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+/// Maximum number of mipmap levels, reached by textures of extent [`u32::MAX`].
+const MAX_LEVELS: u32 = 32;
+
+/// Width, height and depth of a mipmap level.
+///
+/// Only the depth of 3D textures shrinks with levels; the other dimensions use it for layers or
+/// faces. The same goes for the height of 1D array textures.
+fn level_extent(dim: Dim, [w, h, d]: [u32; 3], level: usize) -> [u32; 3] {
+  let shrink = |x: u32| x.checked_shr(level as u32).unwrap_or(0).max(1);
+
+  match dim {
+    Dim::Dim1 | Dim::Dim1Array => [shrink(w), h, d],
+    Dim::Dim3 => [shrink(w), shrink(h), shrink(d)],
+    _ => [shrink(w), shrink(h), d],
+  }
+}
+
+/// Number of bytes of a mipmap level, for all its layers or faces.
+///
+/// Headers declaring levels too large to be addressed are rejected.
+fn level_bytes(
+  dim: Dim,
+  pf: PixelFormat,
+  size: [u32; 3],
+  level: usize,
+) -> Result<usize, LoaderError> {
+  let [w, h, d] = level_extent(dim, size, level);
+
+  let bytes = match pf.format {
+    Format::Compressed(c) | Format::SRGBCompressed(c) => c
+      .image_bytes(w, h)
+      .and_then(|len| len.checked_mul(d as usize)),
+    format => (w as usize)
+      .checked_mul(h as usize)
+      .and_then(|len| len.checked_mul(d as usize))
+      .and_then(|len| len.checked_mul(format.size())),
+  };
+
+  bytes.ok_or_else(|| LoaderError::invalid_data(format!("mipmap level {} is too large", level)))
+}
+
+/// Slice `len` bytes at `offset`, failing if they’re out of `bytes`.
+fn read_bytes(bytes: &[u8], offset: usize, len: usize) -> Result<&[u8], LoaderError> {
+  offset
+    .checked_add(len)
+    .and_then(|end| bytes.get(offset..end))
+    .ok_or_else(|| LoaderError::invalid_data("unexpected end of file"))
+}
+
+/// Read a little-endian [`u32`] at `offset`.
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, LoaderError> {
+  let mut word = [0; 4];
+  word.copy_from_slice(read_bytes(bytes, offset, 4)?);
+  Ok(u32::from_le_bytes(word))
+}
+
+/// Read a little-endian [`u64`] at `offset`.
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, LoaderError> {
+  let mut word = [0; 8];
+  word.copy_from_slice(read_bytes(bytes, offset, 8)?);
+  Ok(u64::from_le_bytes(word))
+}
@@ -0,0 +1,255 @@
+//! DDS parsing.
+//!
+//! Texels of a DDS file are stored array element by array element — layers or cubemap faces —
+//! each with its whole mipmap chain, so they are interleaved back into levels.
+
+use super::{level_bytes, read_bytes, read_u32, LoaderError, TextureContainer, MAX_LEVELS};
+use crate::pixel::{self, Pixel, PixelFormat};
+use crate::texture::Dim;
+
+const MAGIC: &[u8; 4] = b"DDS ";
+
+/// Offset of the texels in files without a DX10 header.
+const DATA_OFFSET: usize = 128;
+
+/// Offset of the texels in files with a DX10 header.
+const DX10_DATA_OFFSET: usize = 148;
+
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xFC00;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+
+const D3D10_RESOURCE_DIMENSION_TEXTURE1D: u32 = 2;
+const D3D10_RESOURCE_DIMENSION_TEXTURE2D: u32 = 3;
+const D3D10_RESOURCE_DIMENSION_TEXTURE3D: u32 = 4;
+const D3D10_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+pub(super) fn parse(bytes: &[u8]) -> Result<TextureContainer, LoaderError> {
+  if bytes.get(..MAGIC.len()) != Some(&MAGIC[..]) {
+    return Err(LoaderError::invalid_data("not a DDS file"));
+  }
+
+  let flags = read_u32(bytes, 8)?;
+  let height = read_u32(bytes, 12)?;
+  let width = read_u32(bytes, 16)?;
+  let depth = read_u32(bytes, 24)?;
+  let level_count = if flags & DDSD_MIPMAPCOUNT != 0 {
+    read_u32(bytes, 28)?.max(1)
+  } else {
+    1
+  };
+
+  if level_count > MAX_LEVELS {
+    return Err(LoaderError::invalid_data(format!(
+      "{} mipmap levels",
+      level_count
+    )));
+  }
+  let pf_flags = read_u32(bytes, 80)?;
+  let four_cc = read_bytes(bytes, 84, 4)?;
+  let caps2 = read_u32(bytes, 112)?;
+
+  let (pixel_format, dim, size, data_offset) = if pf_flags & DDPF_FOURCC != 0 && four_cc == b"DX10"
+  {
+    let dxgi_format = read_u32(bytes, 128)?;
+    let resource_dim = read_u32(bytes, 132)?;
+    let misc_flags = read_u32(bytes, 136)?;
+    let array_size = read_u32(bytes, 140)?;
+
+    let pixel_format = dxgi_pixel_format(dxgi_format)
+      .ok_or_else(|| LoaderError::unsupported_format(format!("DXGI format {}", dxgi_format)))?;
+
+    let cubemap = misc_flags & D3D10_RESOURCE_MISC_TEXTURECUBE != 0;
+    let (dim, size) = match (resource_dim, cubemap, array_size) {
+      (D3D10_RESOURCE_DIMENSION_TEXTURE1D, _, 1) => (Dim::Dim1, [width, 1, 1]),
+      (D3D10_RESOURCE_DIMENSION_TEXTURE1D, _, _) => (Dim::Dim1Array, [width, array_size, 1]),
+      (D3D10_RESOURCE_DIMENSION_TEXTURE2D, false, 1) => (Dim::Dim2, [width, height, 1]),
+      (D3D10_RESOURCE_DIMENSION_TEXTURE2D, false, _) => {
+        (Dim::Dim2Array, [width, height, array_size])
+      }
+      (D3D10_RESOURCE_DIMENSION_TEXTURE2D, true, 1) => (Dim::Cubemap, [width, height, 6]),
+      (D3D10_RESOURCE_DIMENSION_TEXTURE3D, _, _) => (Dim::Dim3, [width, height, depth]),
+      _ => {
+        return Err(LoaderError::unsupported_format(format!(
+          "resource dimension {} with {} elements",
+          resource_dim, array_size
+        )))
+      }
+    };
+
+    (pixel_format, dim, size, DX10_DATA_OFFSET)
+  } else {
+    let pixel_format = legacy_pixel_format(bytes, pf_flags, four_cc)?;
+
+    let (dim, size) = if caps2 & DDSCAPS2_CUBEMAP != 0 {
+      if caps2 & DDSCAPS2_CUBEMAP_ALLFACES != DDSCAPS2_CUBEMAP_ALLFACES {
+        return Err(LoaderError::unsupported_format("partial cubemap"));
+      }
+
+      (Dim::Cubemap, [width, height, 6])
+    } else if caps2 & DDSCAPS2_VOLUME != 0 {
+      (Dim::Dim3, [width, height, depth])
+    } else {
+      (Dim::Dim2, [width, height, 1])
+    };
+
+    (pixel_format, dim, size, DATA_OFFSET)
+  };
+
+  // 3D textures are a single element, the other ones have one element per layer or face
+  let (elements, element_size) = match dim {
+    Dim::Dim3 => (1, size),
+    Dim::Dim1Array => (size[1], [size[0], 1, 1]),
+    _ => (size[2], [size[0], size[1], 1]),
+  };
+
+  let element_levels = (0..level_count as usize)
+    .map(|level| level_bytes(dim, pixel_format, element_size, level))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  // the file must hold every level before they are allocated, as the header cannot be trusted
+  let len = element_levels
+    .iter()
+    .try_fold(0usize, |len, &level_len| len.checked_add(level_len))
+    .and_then(|len| len.checked_mul(elements as usize))
+    .ok_or_else(|| LoaderError::invalid_data("texture is too large"))?;
+  read_bytes(bytes, data_offset, len)?;
+
+  let mut levels = element_levels
+    .iter()
+    .map(|level_len| Vec::with_capacity(level_len * elements as usize))
+    .collect::<Vec<_>>();
+
+  let mut offset = data_offset;
+
+  for _ in 0..elements {
+    for (level, &level_len) in levels.iter_mut().zip(&element_levels) {
+      level.extend_from_slice(read_bytes(bytes, offset, level_len)?);
+      offset += level_len;
+    }
+  }
+
+  TextureContainer::new(dim, pixel_format, size, levels)
+}
+
+/// Pixel format of a file without DX10 header.
+fn legacy_pixel_format(
+  bytes: &[u8],
+  pf_flags: u32,
+  four_cc: &[u8],
+) -> Result<PixelFormat, LoaderError> {
+  if pf_flags & DDPF_FOURCC != 0 {
+    let pf = match four_cc {
+      b"DXT1" if pf_flags & DDPF_ALPHAPIXELS != 0 => pixel::BC1A::pixel_format(),
+      b"DXT1" => pixel::BC1::pixel_format(),
+      b"DXT2" | b"DXT3" => pixel::BC2::pixel_format(),
+      b"DXT4" | b"DXT5" => pixel::BC3::pixel_format(),
+      b"ATI1" | b"BC4U" => pixel::BC4::pixel_format(),
+      b"BC4S" => pixel::BC4Signed::pixel_format(),
+      b"ATI2" | b"BC5U" => pixel::BC5::pixel_format(),
+      b"BC5S" => pixel::BC5Signed::pixel_format(),
+      _ => {
+        return Err(LoaderError::unsupported_format(format!(
+          "FourCC {}",
+          String::from_utf8_lossy(four_cc)
+        )))
+      }
+    };
+
+    return Ok(pf);
+  }
+
+  let bits = read_u32(bytes, 88)?;
+  let masks = [
+    read_u32(bytes, 92)?,
+    read_u32(bytes, 96)?,
+    read_u32(bytes, 100)?,
+    read_u32(bytes, 104)?,
+  ];
+  let alpha = pf_flags & DDPF_ALPHAPIXELS != 0;
+
+  // only layouts matching luminance pixel formats are supported; BGR ones are not
+  match (pf_flags & DDPF_RGB != 0, bits, masks, alpha) {
+    (true, 32, [0xFF, 0xFF00, 0xFF0000, 0xFF000000], true) => {
+      Ok(pixel::NormRGBA8UI::pixel_format())
+    }
+    (true, 24, [0xFF, 0xFF00, 0xFF0000, _], false) => Ok(pixel::NormRGB8UI::pixel_format()),
+    (true, 16, [0xF800, 0x7E0, 0x1F, _], false) => Ok(pixel::RGB565::pixel_format()),
+    _ => Err(LoaderError::unsupported_format(format!(
+      "{}-bit pixels with masks {:#X?}",
+      bits, masks
+    ))),
+  }
+}
+
+/// Map a `DXGI_FORMAT` to a [`PixelFormat`].
+fn dxgi_pixel_format(dxgi_format: u32) -> Option<PixelFormat> {
+  let pf = match dxgi_format {
+    2 => pixel::RGBA32F::pixel_format(),
+    3 => pixel::RGBA32UI::pixel_format(),
+    4 => pixel::RGBA32I::pixel_format(),
+    6 => pixel::RGB32F::pixel_format(),
+    7 => pixel::RGB32UI::pixel_format(),
+    8 => pixel::RGB32I::pixel_format(),
+    10 => pixel::RGBA16F::pixel_format(),
+    11 => pixel::NormRGBA16UI::pixel_format(),
+    12 => pixel::RGBA16UI::pixel_format(),
+    13 => pixel::NormRGBA16I::pixel_format(),
+    14 => pixel::RGBA16I::pixel_format(),
+    16 => pixel::RG32F::pixel_format(),
+    17 => pixel::RG32UI::pixel_format(),
+    18 => pixel::RG32I::pixel_format(),
+    24 => pixel::RGB10A2::pixel_format(),
+    28 => pixel::NormRGBA8UI::pixel_format(),
+    29 => pixel::SRGBA8UI::pixel_format(),
+    30 => pixel::RGBA8UI::pixel_format(),
+    31 => pixel::NormRGBA8I::pixel_format(),
+    32 => pixel::RGBA8I::pixel_format(),
+    34 => pixel::RG16F::pixel_format(),
+    35 => pixel::NormRG16UI::pixel_format(),
+    36 => pixel::RG16UI::pixel_format(),
+    37 => pixel::NormRG16I::pixel_format(),
+    38 => pixel::RG16I::pixel_format(),
+    40 => pixel::Depth32F::pixel_format(),
+    41 => pixel::R32F::pixel_format(),
+    42 => pixel::R32UI::pixel_format(),
+    43 => pixel::R32I::pixel_format(),
+    49 => pixel::NormRG8UI::pixel_format(),
+    50 => pixel::RG8UI::pixel_format(),
+    51 => pixel::NormRG8I::pixel_format(),
+    52 => pixel::RG8I::pixel_format(),
+    54 => pixel::R16F::pixel_format(),
+    56 => pixel::NormR16UI::pixel_format(),
+    57 => pixel::R16UI::pixel_format(),
+    58 => pixel::NormR16I::pixel_format(),
+    59 => pixel::R16I::pixel_format(),
+    61 => pixel::NormR8UI::pixel_format(),
+    62 => pixel::R8UI::pixel_format(),
+    63 => pixel::NormR8I::pixel_format(),
+    64 => pixel::R8I::pixel_format(),
+    67 => pixel::RGB9E5::pixel_format(),
+    // Direct3D BC1 textures always have a 1-bit alpha channel
+    71 => pixel::BC1A::pixel_format(),
+    72 => pixel::SRGBBC1A::pixel_format(),
+    74 => pixel::BC2::pixel_format(),
+    75 => pixel::SRGBBC2::pixel_format(),
+    77 => pixel::BC3::pixel_format(),
+    78 => pixel::SRGBBC3::pixel_format(),
+    80 => pixel::BC4::pixel_format(),
+    81 => pixel::BC4Signed::pixel_format(),
+    83 => pixel::BC5::pixel_format(),
+    84 => pixel::BC5Signed::pixel_format(),
+    85 => pixel::RGB565::pixel_format(),
+    95 => pixel::BC6H::pixel_format(),
+    96 => pixel::BC6HSigned::pixel_format(),
+    98 => pixel::BC7::pixel_format(),
+    99 => pixel::SRGBBC7::pixel_format(),
+    _ => return None,
+  };
+
+  Some(pf)
+}
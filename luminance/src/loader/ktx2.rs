@@ -0,0 +1,214 @@
+//! KTX2 parsing.
+//!
+//! Texels of a KTX2 level are stored layer by layer, face by face, which is already the order of
+//! luminance textures.
+
+use super::{
+  level_bytes, read_bytes, read_u32, read_u64, LoaderError, TextureContainer, MAX_LEVELS,
+};
+use crate::pixel::{self, Compression, Format, Pixel, PixelFormat, Type};
+use crate::texture::Dim;
+
+const IDENTIFIER: [u8; 12] = [
+  0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+/// Offset of the level index, right after the header and the data indices.
+const LEVEL_INDEX_OFFSET: usize = 80;
+
+/// Size of an entry of the level index.
+const LEVEL_INDEX_ENTRY_LEN: usize = 24;
+
+pub(super) fn parse(bytes: &[u8]) -> Result<TextureContainer, LoaderError> {
+  if bytes.get(..IDENTIFIER.len()) != Some(&IDENTIFIER[..]) {
+    return Err(LoaderError::invalid_data("not a KTX2 file"));
+  }
+
+  let vk_format = read_u32(bytes, 12)?;
+  let width = read_u32(bytes, 20)?;
+  let height = read_u32(bytes, 24)?;
+  let depth = read_u32(bytes, 28)?;
+  let layers = read_u32(bytes, 32)?;
+  let faces = read_u32(bytes, 36)?;
+  // a level count of 0 asks to generate the mipmaps out of the only stored level
+  let level_count = read_u32(bytes, 40)?.max(1);
+  let supercompression = read_u32(bytes, 44)?;
+
+  if level_count > MAX_LEVELS {
+    return Err(LoaderError::invalid_data(format!(
+      "{} mipmap levels",
+      level_count
+    )));
+  }
+
+  if supercompression != 0 {
+    return Err(LoaderError::unsupported_format(format!(
+      "supercompression scheme {}",
+      supercompression
+    )));
+  }
+
+  let pixel_format = vk_pixel_format(vk_format)
+    .ok_or_else(|| LoaderError::unsupported_format(format!("VkFormat {}", vk_format)))?;
+
+  let (dim, size) = match (height, depth, faces, layers) {
+    (0, 0, 1, 0) => (Dim::Dim1, [width, 1, 1]),
+    (0, 0, 1, _) => (Dim::Dim1Array, [width, layers, 1]),
+    (_, 0, 1, 0) => (Dim::Dim2, [width, height, 1]),
+    (_, 0, 1, _) => (Dim::Dim2Array, [width, height, layers]),
+    (_, 0, 6, 0) => (Dim::Cubemap, [width, height, 6]),
+    (_, _, 1, 0) => (Dim::Dim3, [width, height, depth]),
+    _ => {
+      return Err(LoaderError::unsupported_format(format!(
+        "{}×{}×{} texture with {} faces and {} layers",
+        width, height, depth, faces, layers
+      )))
+    }
+  };
+
+  let levels = (0..level_count as usize)
+    .map(|level| {
+      let entry = LEVEL_INDEX_OFFSET + level * LEVEL_INDEX_ENTRY_LEN;
+      let offset = read_u64(bytes, entry)? as usize;
+      let len = read_u64(bytes, entry + 8)? as usize;
+      let expected = level_bytes(dim, pixel_format, size, level)?;
+
+      if len != expected {
+        return Err(LoaderError::invalid_data(format!(
+          "mipmap level {} holds {} bytes instead of {}",
+          level, len, expected
+        )));
+      }
+
+      read_bytes(bytes, offset, len).map(<[u8]>::to_vec)
+    })
+    .collect::<Result<_, _>>()?;
+
+  TextureContainer::new(dim, pixel_format, size, levels)
+}
+
+/// Map a `VkFormat` to a [`PixelFormat`].
+fn vk_pixel_format(vk_format: u32) -> Option<PixelFormat> {
+  let pf = match vk_format {
+    4 => pixel::RGB565::pixel_format(),
+    9 => pixel::NormR8UI::pixel_format(),
+    10 => pixel::NormR8I::pixel_format(),
+    13 => pixel::R8UI::pixel_format(),
+    14 => pixel::R8I::pixel_format(),
+    16 => pixel::NormRG8UI::pixel_format(),
+    17 => pixel::NormRG8I::pixel_format(),
+    20 => pixel::RG8UI::pixel_format(),
+    21 => pixel::RG8I::pixel_format(),
+    23 => pixel::NormRGB8UI::pixel_format(),
+    24 => pixel::NormRGB8I::pixel_format(),
+    27 => pixel::RGB8UI::pixel_format(),
+    28 => pixel::RGB8I::pixel_format(),
+    29 => pixel::SRGB8UI::pixel_format(),
+    37 => pixel::NormRGBA8UI::pixel_format(),
+    38 => pixel::NormRGBA8I::pixel_format(),
+    41 => pixel::RGBA8UI::pixel_format(),
+    42 => pixel::RGBA8I::pixel_format(),
+    43 => pixel::SRGBA8UI::pixel_format(),
+    64 => pixel::RGB10A2::pixel_format(),
+    70 => pixel::NormR16UI::pixel_format(),
+    71 => pixel::NormR16I::pixel_format(),
+    74 => pixel::R16UI::pixel_format(),
+    75 => pixel::R16I::pixel_format(),
+    76 => pixel::R16F::pixel_format(),
+    77 => pixel::NormRG16UI::pixel_format(),
+    78 => pixel::NormRG16I::pixel_format(),
+    81 => pixel::RG16UI::pixel_format(),
+    82 => pixel::RG16I::pixel_format(),
+    83 => pixel::RG16F::pixel_format(),
+    84 => pixel::NormRGB16UI::pixel_format(),
+    85 => pixel::NormRGB16I::pixel_format(),
+    88 => pixel::RGB16UI::pixel_format(),
+    89 => pixel::RGB16I::pixel_format(),
+    91 => pixel::NormRGBA16UI::pixel_format(),
+    92 => pixel::NormRGBA16I::pixel_format(),
+    95 => pixel::RGBA16UI::pixel_format(),
+    96 => pixel::RGBA16I::pixel_format(),
+    97 => pixel::RGBA16F::pixel_format(),
+    98 => pixel::R32UI::pixel_format(),
+    99 => pixel::R32I::pixel_format(),
+    100 => pixel::R32F::pixel_format(),
+    101 => pixel::RG32UI::pixel_format(),
+    102 => pixel::RG32I::pixel_format(),
+    103 => pixel::RG32F::pixel_format(),
+    104 => pixel::RGB32UI::pixel_format(),
+    105 => pixel::RGB32I::pixel_format(),
+    106 => pixel::RGB32F::pixel_format(),
+    107 => pixel::RGBA32UI::pixel_format(),
+    108 => pixel::RGBA32I::pixel_format(),
+    109 => pixel::RGBA32F::pixel_format(),
+    123 => pixel::RGB9E5::pixel_format(),
+    126 => pixel::Depth32F::pixel_format(),
+    131 => pixel::BC1::pixel_format(),
+    132 => pixel::SRGBBC1::pixel_format(),
+    133 => pixel::BC1A::pixel_format(),
+    134 => pixel::SRGBBC1A::pixel_format(),
+    135 => pixel::BC2::pixel_format(),
+    136 => pixel::SRGBBC2::pixel_format(),
+    137 => pixel::BC3::pixel_format(),
+    138 => pixel::SRGBBC3::pixel_format(),
+    139 => pixel::BC4::pixel_format(),
+    140 => pixel::BC4Signed::pixel_format(),
+    141 => pixel::BC5::pixel_format(),
+    142 => pixel::BC5Signed::pixel_format(),
+    143 => pixel::BC6H::pixel_format(),
+    144 => pixel::BC6HSigned::pixel_format(),
+    145 => pixel::BC7::pixel_format(),
+    146 => pixel::SRGBBC7::pixel_format(),
+    147 => pixel::ETC2::pixel_format(),
+    148 => pixel::SRGBETC2::pixel_format(),
+    149 => pixel::ETC2A1::pixel_format(),
+    150 => pixel::SRGBETC2A1::pixel_format(),
+    151 => pixel::ETC2EAC::pixel_format(),
+    152 => pixel::SRGBETC2EAC::pixel_format(),
+    153 => pixel::EACR11::pixel_format(),
+    154 => pixel::EACR11Signed::pixel_format(),
+    155 => pixel::EACRG11::pixel_format(),
+    156 => pixel::EACRG11Signed::pixel_format(),
+    // ASTC formats come in UNORM / SRGB pairs, ordered by block size
+    157..=184 => {
+      let index = (vk_format - 157) / 2;
+      let [block_width, block_height] = ASTC_BLOCKS[index as usize];
+      let compression = Compression::ASTC {
+        block_width,
+        block_height,
+      };
+
+      let format = if (vk_format - 157) % 2 == 1 {
+        Format::SRGBCompressed(compression)
+      } else {
+        Format::Compressed(compression)
+      };
+
+      PixelFormat {
+        encoding: Type::NormUnsigned,
+        format,
+      }
+    }
+    _ => return None,
+  };
+
+  Some(pf)
+}
+
+/// ASTC block sizes, in `VkFormat` order.
+const ASTC_BLOCKS: [[u8; 2]; 14] = [
+  [4, 4],
+  [5, 4],
+  [5, 5],
+  [6, 5],
+  [6, 6],
+  [8, 5],
+  [8, 6],
+  [8, 8],
+  [10, 5],
+  [10, 6],
+  [10, 8],
+  [10, 10],
+  [12, 10],
+  [12, 12],
+];
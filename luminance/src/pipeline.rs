@@ -225,7 +225,7 @@
 use std::{
   error, fmt,
   marker::PhantomData,
-  ops::{Deref, DerefMut},
+  ops::{Deref, DerefMut, Range},
 };
 
 use crate::{
@@ -233,12 +233,17 @@ use crate::{
     color_slot::ColorSlot,
     depth_slot::DepthSlot,
     framebuffer::Framebuffer as FramebufferBackend,
-    pipeline::{Pipeline as PipelineBackend, PipelineBase, PipelineBuffer, PipelineTexture},
+    pipeline::{
+      Pipeline as PipelineBackend, PipelineBase, PipelineBuffer, PipelineStorageBuffer,
+      PipelineTexture,
+    },
+    query::Query as QueryBackend,
   },
   buffer::Buffer,
   context::GraphicsContext,
   framebuffer::Framebuffer,
   pixel::Pixel,
+  query::{Query, QueryError},
   scissor::ScissorRegion,
   shading_gate::ShadingGate,
   texture::{Dimensionable, Texture},
@@ -246,12 +251,61 @@ use crate::{
 
 /// Possible errors that might occur in a graphics [`Pipeline`].
 #[non_exhaustive]
-#[derive(Debug, Eq, PartialEq)]
-pub enum PipelineError {}
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PipelineError {
+  /// A bound buffer range doesn’t fit in its buffer.
+  BufferRangeOutOfBounds {
+    /// Index of the first item of the range.
+    start: usize,
+    /// Index of the item following the last one of the range.
+    end: usize,
+    /// Length of the buffer.
+    buffer_len: usize,
+  },
+  /// A bound buffer range doesn’t start at an offset the backend can bind.
+  UnalignedBufferRange {
+    /// Offset of the range, in bytes.
+    offset: usize,
+    /// Alignment required by the backend, in bytes.
+    alignment: usize,
+  },
+}
+
+impl PipelineError {
+  /// A bound buffer range doesn’t fit in its buffer.
+  pub fn buffer_range_out_of_bounds(start: usize, end: usize, buffer_len: usize) -> Self {
+    PipelineError::BufferRangeOutOfBounds {
+      start,
+      end,
+      buffer_len,
+    }
+  }
+
+  /// A bound buffer range doesn’t start at an offset the backend can bind.
+  pub fn unaligned_buffer_range(offset: usize, alignment: usize) -> Self {
+    PipelineError::UnalignedBufferRange { offset, alignment }
+  }
+}
 
 impl fmt::Display for PipelineError {
-  fn fmt(&self, _: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-    Ok(())
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      PipelineError::BufferRangeOutOfBounds {
+        start,
+        end,
+        buffer_len,
+      } => write!(
+        f,
+        "buffer range {}..{} out of bounds (buffer length is {})",
+        start, end, buffer_len
+      ),
+
+      PipelineError::UnalignedBufferRange { offset, alignment } => write!(
+        f,
+        "buffer range offset {} is not aligned on {} bytes",
+        offset, alignment
+      ),
+    }
   }
 }
 
@@ -278,7 +332,7 @@ pub enum Viewport {
 
 /// Various customization options for pipelines.
 #[non_exhaustive]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PipelineState {
   /// Color to use when clearing buffers.
   pub clear_color: [f32; 4],
@@ -286,6 +340,10 @@ pub struct PipelineState {
   pub clear_color_enabled: bool,
   /// Whether clearing depth buffers.
   pub clear_depth_enabled: bool,
+  /// Value to use when clearing stencil buffers.
+  pub clear_stencil: u8,
+  /// Whether clearing stencil buffers.
+  pub clear_stencil_enabled: bool,
   /// Viewport to use when rendering.
   pub viewport: Viewport,
   /// Whether [sRGB](https://en.wikipedia.org/wiki/SRGB) should be enabled.
@@ -300,6 +358,8 @@ impl Default for PipelineState {
   /// - Clear color is `[0, 0, 0, 1]`.
   /// - Color is always cleared.
   /// - Depth is always cleared.
+  /// - Clear stencil is `0`.
+  /// - Stencil is always cleared.
   /// - The viewport uses the whole framebuffer’s.
   /// - sRGB encoding is disabled.
   /// - No scissor test is performed.
@@ -308,6 +368,8 @@ impl Default for PipelineState {
       clear_color: [0., 0., 0., 1.],
       clear_color_enabled: true,
       clear_depth_enabled: true,
+      clear_stencil: 0,
+      clear_stencil_enabled: true,
       viewport: Viewport::Whole,
       srgb_enabled: false,
       clear_scissor: None,
@@ -362,6 +424,32 @@ impl PipelineState {
     }
   }
 
+  /// Get the clear stencil value.
+  pub fn clear_stencil(&self) -> u8 {
+    self.clear_stencil
+  }
+
+  /// Set the clear stencil value.
+  pub fn set_clear_stencil(self, clear_stencil: u8) -> Self {
+    Self {
+      clear_stencil,
+      ..self
+    }
+  }
+
+  /// Check whether the pipeline’s framebuffer’s stencil buffer will be cleared.
+  pub fn is_clear_stencil_enabled(&self) -> bool {
+    self.clear_stencil_enabled
+  }
+
+  /// Enable clearing stencil buffers.
+  pub fn enable_clear_stencil(self, clear_stencil_enabled: bool) -> Self {
+    Self {
+      clear_stencil_enabled,
+      ..self
+    }
+  }
+
   /// Get the viewport.
   pub fn viewport(&self) -> Viewport {
     self.viewport
@@ -413,8 +501,8 @@ pub struct Pipeline<'a, B>
 where
   B: ?Sized + PipelineBase,
 {
-  repr: B::PipelineRepr,
-  _phantom: PhantomData<&'a mut ()>,
+  pub(crate) repr: B::PipelineRepr,
+  pub(crate) _phantom: PhantomData<&'a mut ()>,
 }
 
 impl<'a, B> Pipeline<'a, B>
@@ -441,6 +529,71 @@ where
     }
   }
 
+  /// Bind a range of a buffer.
+  ///
+  /// The range is given in items. Only the items of the range are visible to shaders, the first
+  /// item of the range being at offset `0` in the uniform block. The offset of the range in the
+  /// buffer must be a multiple of the alignment of the backend, which [`StreamBuffer`] takes care
+  /// of.
+  ///
+  /// Contrary to [`Pipeline::bind_buffer`], the buffer is borrowed immutably, so that several
+  /// ranges of the same buffer can be bound at the same time.
+  ///
+  /// # Errors
+  ///
+  /// [`PipelineError::BufferRangeOutOfBounds`] is returned if the range doesn’t fit in the
+  /// buffer, and [`PipelineError::UnalignedBufferRange`] if its offset isn’t aligned.
+  ///
+  /// [`StreamBuffer`]: crate::stream::StreamBuffer
+  pub fn bind_buffer_range<T>(
+    &'a self,
+    buffer: &'a Buffer<B, T>,
+    range: Range<usize>,
+  ) -> Result<BoundBuffer<'a, B, T>, PipelineError>
+  where
+    B: PipelineBuffer<T>,
+    T: Copy,
+  {
+    let buffer_len = buffer.len();
+
+    if range.start > range.end || range.end > buffer_len {
+      return Err(PipelineError::buffer_range_out_of_bounds(
+        range.start,
+        range.end,
+        buffer_len,
+      ));
+    }
+
+    unsafe {
+      B::bind_buffer_range(&self.repr, &buffer.repr, range.start, range.len()).map(|repr| {
+        BoundBuffer {
+          repr,
+          _phantom: PhantomData,
+        }
+      })
+    }
+  }
+
+  /// Bind a buffer as a storage buffer.
+  ///
+  /// Storage buffers can be read from and written to by shaders. Once the buffer is bound, the
+  /// [`BoundStorageBuffer`] object has to be dropped / die in order to bind the buffer again.
+  pub fn bind_storage_buffer<T>(
+    &'a self,
+    buffer: &'a mut Buffer<B, T>,
+  ) -> Result<BoundStorageBuffer<'a, B, T>, PipelineError>
+  where
+    B: PipelineStorageBuffer<T>,
+    T: Copy,
+  {
+    unsafe {
+      B::bind_storage_buffer(&self.repr, &buffer.repr).map(|repr| BoundStorageBuffer {
+        repr,
+        _phantom: PhantomData,
+      })
+    }
+  }
+
   /// Bind a texture.
   ///
   /// Once the texture is bound, the [`BoundTexture`] object has to be dropped / die in order to
@@ -541,7 +694,37 @@ where
       f(pipeline, shading_gate)
     };
 
-    Render(render())
+    let render = render();
+
+    // multisampled framebuffers are resolved even if the pipeline failed, so that their slots
+    // always reflect what was rendered
+    unsafe { self.backend.end_pipeline(&framebuffer.repr) };
+
+    Render(render)
+  }
+
+  /// Wrap a section of the pipeline in a [`Query`].
+  ///
+  /// The argument closure is given this gate back, to issue the pipelines the query gathers
+  /// information about.
+  ///
+  /// # Errors
+  ///
+  /// If the query cannot start — for instance, because a query of the same type is already
+  /// active — the closure isn’t run and the [`QueryError`] is returned.
+  pub fn query<E, F>(&mut self, query: &mut Query<B>, f: F) -> Render<E>
+  where
+    B: QueryBackend,
+    F: FnOnce(&mut Self) -> Render<E>,
+    E: From<QueryError>,
+  {
+    if let Err(e) = unsafe { self.backend.begin_query(&mut query.repr) } {
+      return Render(Err(e.into()));
+    }
+
+    let render = f(self);
+    unsafe { self.backend.end_query(&mut query.repr) };
+    render
   }
 }
 
@@ -672,6 +855,86 @@ where
   }
 }
 
+/// Opaque storage buffer binding.
+///
+/// This type represents a bound [`Buffer`] via [`BoundStorageBuffer`]. It can be used along with a
+/// [`Uniform`] to customize a shader’s behavior.
+///
+/// # Parametricity
+///
+/// - `T` is the type of the carried item by the [`Buffer`].
+///
+/// # Notes
+///
+/// You shouldn’t try to do store / cache or do anything special with that value. Consider it
+/// an opaque object.
+///
+/// [`Uniform`]: crate::shader::Uniform
+#[derive(Debug)]
+pub struct StorageBufferBinding<T> {
+  binding: u32,
+  _phantom: PhantomData<*const T>,
+}
+
+impl<T> StorageBufferBinding<T> {
+  /// Access the underlying binding value.
+  ///
+  /// # Notes
+  ///
+  /// That value shouldn’t be read nor store, as it’s only meaningful for backend implementations.
+  pub fn binding(self) -> u32 {
+    self.binding
+  }
+}
+
+/// A [`Buffer`] _bound_ as a storage buffer.
+///
+/// # Parametricity
+///
+/// - `B` is the backend type. It must implement [`PipelineStorageBuffer`].
+/// - `T` is the type of the carried item by the [`Buffer`].
+///
+/// # Notes
+///
+/// Once a [`Buffer`] is bound, it can be used and passed around to shaders. In order to do so,
+/// you will need to pass a [`StorageBufferBinding`] to your [`ProgramInterface`]. That value is
+/// unique to each [`BoundStorageBuffer`] and should always be asked — you shouldn’t cache them,
+/// for instance.
+///
+/// Getting a [`StorageBufferBinding`] is a cheap operation and is performed via the
+/// [`BoundStorageBuffer::binding`] method.
+///
+/// [`ProgramInterface`]: crate::shader::ProgramInterface
+pub struct BoundStorageBuffer<'a, B, T>
+where
+  B: PipelineStorageBuffer<T>,
+  T: Copy,
+{
+  pub(crate) repr: B::BoundStorageBufferRepr,
+  _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, B, T> BoundStorageBuffer<'a, B, T>
+where
+  B: PipelineStorageBuffer<T>,
+  T: Copy,
+{
+  /// Obtain a [`StorageBufferBinding`] object that can be used to refer to this bound buffer in
+  /// shader stages.
+  ///
+  /// # Notes
+  ///
+  /// You shouldn’t try to do store / cache or do anything special with that value. Consider it
+  /// an opaque object.
+  pub fn binding(&self) -> StorageBufferBinding<T> {
+    let binding = unsafe { B::storage_buffer_binding(&self.repr) };
+    StorageBufferBinding {
+      binding,
+      _phantom: PhantomData,
+    }
+  }
+}
+
 /// Opaque texture binding.
 ///
 /// This type represents a bound [`Texture`] via [`BoundTexture`]. It can be used along with a
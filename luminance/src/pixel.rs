@@ -10,6 +10,10 @@
 //!   format on the GPU / in shaders.
 //! - [`Pixel::pixel_format`], a function returning the [`PixelFormat`], reified version of the
 //!   type at runtime.
+//!
+//! Compressed pixel formats — implementing [`CompressedPixel`] — store blocks of texels instead of
+//! single texels. Their textures are uploaded as pre-compressed blocks, mipmap level by mipmap level,
+//! and their support depends on the backend; see [`CompressionFamily`].
 
 /// Reify a static pixel format at runtime.
 pub unsafe trait Pixel {
@@ -37,6 +41,11 @@ pub unsafe trait DepthPixel: Pixel {}
 /// Constaint on [`Pixel`] for renderable ones.
 pub unsafe trait RenderablePixel: Pixel {}
 
+/// Constraint on [`Pixel`] for compressed ones.
+///
+/// Their [`Pixel::Encoding`] and [`Pixel::RawEncoding`] are bytes of compressed blocks.
+pub unsafe trait CompressedPixel: Pixel {}
+
 /// Reify a static sample type at runtime.
 ///
 /// That trait is used to allow sampling with different types than the actual encoding of the
@@ -59,7 +68,7 @@ impl PixelFormat {
   /// Does a [`PixelFormat`] represent a color?
   pub fn is_color_pixel(self) -> bool {
     match self.format {
-      Format::Depth(_) => false,
+      Format::Depth(_) | Format::DepthStencil(_, _) => false,
       _ => true,
     }
   }
@@ -69,9 +78,22 @@ impl PixelFormat {
     !self.is_color_pixel()
   }
 
+  /// Does a [`PixelFormat`] represent both depth and stencil information?
+  pub fn is_depth_stencil_pixel(self) -> bool {
+    matches!(self.format, Format::DepthStencil(_, _))
+  }
+
   /// Return the number of canals.
+  ///
+  /// Packed formats — see [`Format::is_packed`] — return the number of words their canals are
+  /// packed in instead, and compressed formats the number of bytes of a block.
   pub fn canals_len(self) -> usize {
     match self.format {
+      Format::Compressed(c) | Format::SRGBCompressed(c) => c.block_bytes(),
+      // packed in a single 16-bit or 32-bit word
+      Format::RGB(Size::Five, Size::Six, Size::Five)
+      | Format::RGBA(Size::Ten, Size::Ten, Size::Ten, Size::Two)
+      | Format::RGBE(_, _, _, _) => 1,
       Format::R(_) => 1,
       Format::RG(_, _) => 2,
       Format::RGB(_, _, _) => 3,
@@ -79,6 +101,8 @@ impl PixelFormat {
       Format::SRGB(_, _, _) => 3,
       Format::SRGBA(_, _, _, _) => 4,
       Format::Depth(_) => 1,
+      // depth and stencil are packed in 32-bit words
+      Format::DepthStencil(_, _) => self.format.size() / 4,
     }
   }
 }
@@ -124,38 +148,203 @@ pub enum Format {
   SRGB(Size, Size, Size),
   /// Holds a red, green and blue channels in sRGB colorspace, plus an alpha channel.
   SRGBA(Size, Size, Size, Size),
+  /// Holds red, green and blue channels sharing an exponent channel.
+  RGBE(Size, Size, Size, Size),
   /// Holds a depth channel.
   Depth(Size),
+  /// Holds a depth channel and a stencil channel.
+  DepthStencil(Size, Size),
+  /// Holds blocks of compressed texels.
+  Compressed(Compression),
+  /// Holds blocks of compressed texels in sRGB colorspace.
+  SRGBCompressed(Compression),
 }
 
 impl Format {
   /// Size (in bytes) of a pixel that a format represents.
+  ///
+  /// For compressed formats, it is the size of a whole block of pixels.
   pub fn size(self) -> usize {
     let bits = match self {
+      Format::Compressed(c) | Format::SRGBCompressed(c) => c.block_bytes() * 8,
       Format::R(r) => r.bits(),
       Format::RG(r, g) => r.bits() + g.bits(),
       Format::RGB(r, g, b) => r.bits() + g.bits() + b.bits(),
       Format::RGBA(r, g, b, a) => r.bits() + g.bits() + b.bits() + a.bits(),
       Format::SRGB(r, g, b) => r.bits() + g.bits() + b.bits(),
       Format::SRGBA(r, g, b, a) => r.bits() + g.bits() + b.bits() + a.bits(),
+      Format::RGBE(r, g, b, e) => r.bits() + g.bits() + b.bits() + e.bits(),
       Format::Depth(d) => d.bits(),
+      // packed in 32-bit words
+      Format::DepthStencil(d, s) => (d.bits() + s.bits()).div_ceil(32) * 32,
     };
 
     bits / 8
   }
+
+  /// Are the channels of the format packed together in 16-bit or 32-bit words?
+  ///
+  /// Texels of packed formats are uploaded and read back as whole words rather than channel by
+  /// channel.
+  pub fn is_packed(self) -> bool {
+    matches!(
+      self,
+      Format::RGB(Size::Five, Size::Six, Size::Five)
+        | Format::RGBA(Size::Ten, Size::Ten, Size::Ten, Size::Two)
+        | Format::RGBE(_, _, _, _)
+        | Format::DepthStencil(_, _)
+    )
+  }
+
+  /// Compression of the format, if it is a compressed one.
+  pub fn compression(self) -> Option<Compression> {
+    match self {
+      Format::Compressed(c) | Format::SRGBCompressed(c) => Some(c),
+      _ => None,
+    }
+  }
+}
+
+/// Block compression of a compressed pixel format.
+///
+/// Compressed formats store blocks of texels — which footprint is given by
+/// [`Compression::block_size`] — in a fixed number of bytes — given by
+/// [`Compression::block_bytes`]. Whether normalized channels are signed is given by the [`Type`]
+/// of the [`PixelFormat`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Compression {
+  /// BC1 (DXT1), holding red, green and blue channels.
+  BC1,
+  /// BC1 (DXT1), holding red, green and blue channels and a 1-bit alpha channel.
+  BC1A,
+  /// BC2 (DXT3), holding red, green and blue channels and an explicit 4-bit alpha channel.
+  BC2,
+  /// BC3 (DXT5), holding red, green, blue and alpha channels.
+  BC3,
+  /// BC4 (RGTC1), holding a red channel.
+  BC4,
+  /// BC5 (RGTC2), holding red and green channels.
+  BC5,
+  /// BC6H (BPTC), holding unsigned floating red, green and blue channels.
+  BC6H,
+  /// BC6H (BPTC), holding signed floating red, green and blue channels.
+  BC6HSigned,
+  /// BC7 (BPTC), holding red, green, blue and alpha channels.
+  BC7,
+  /// ETC2, holding red, green and blue channels.
+  ETC2,
+  /// ETC2, holding red, green and blue channels and a 1-bit alpha channel.
+  ETC2A1,
+  /// ETC2 with an EAC alpha channel, holding red, green, blue and alpha channels.
+  ETC2EAC,
+  /// EAC, holding an 11-bit red channel.
+  EACR11,
+  /// EAC, holding 11-bit red and green channels.
+  EACRG11,
+  /// ASTC (LDR profile), holding red, green, blue and alpha channels.
+  ASTC {
+    /// Width of a block, in texels.
+    block_width: u8,
+    /// Height of a block, in texels.
+    block_height: u8,
+  },
+}
+
+impl Compression {
+  /// Family of the compression.
+  pub fn family(self) -> CompressionFamily {
+    match self {
+      Compression::BC1 | Compression::BC1A | Compression::BC2 | Compression::BC3 => {
+        CompressionFamily::S3TC
+      }
+      Compression::BC4 | Compression::BC5 => CompressionFamily::RGTC,
+      Compression::BC6H | Compression::BC6HSigned | Compression::BC7 => CompressionFamily::BPTC,
+      Compression::ETC2
+      | Compression::ETC2A1
+      | Compression::ETC2EAC
+      | Compression::EACR11
+      | Compression::EACRG11 => CompressionFamily::ETC2,
+      Compression::ASTC { .. } => CompressionFamily::ASTC,
+    }
+  }
+
+  /// Width and height (in texels) of a block.
+  pub fn block_size(self) -> [u32; 2] {
+    match self {
+      Compression::ASTC {
+        block_width,
+        block_height,
+      } => [block_width as u32, block_height as u32],
+      _ => [4, 4],
+    }
+  }
+
+  /// Size (in bytes) of the blocks covering an image of `width`×`height` texels, or `None` if it
+  /// overflows [`usize`].
+  ///
+  /// Partial blocks, on the right and bottom edges of the image, count as whole blocks.
+  pub fn image_bytes(self, width: u32, height: u32) -> Option<usize> {
+    let [bw, bh] = self.block_size();
+
+    (width.div_ceil(bw) as usize)
+      .checked_mul(height.div_ceil(bh) as usize)?
+      .checked_mul(self.block_bytes())
+  }
+
+  /// Size (in bytes) of a block.
+  pub fn block_bytes(self) -> usize {
+    match self {
+      Compression::BC1
+      | Compression::BC1A
+      | Compression::BC4
+      | Compression::ETC2
+      | Compression::ETC2A1
+      | Compression::EACR11 => 8,
+      _ => 16,
+    }
+  }
+}
+
+/// Families of compressions, which backends support as a whole.
+///
+/// Use [`GraphicsContext::supports_compression`] to know whether a family is supported.
+///
+/// [`GraphicsContext::supports_compression`]: crate::context::GraphicsContext::supports_compression
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompressionFamily {
+  /// S3TC: BC1, BC2 and BC3.
+  S3TC,
+  /// RGTC: BC4 and BC5.
+  RGTC,
+  /// BPTC: BC6H and BC7.
+  BPTC,
+  /// ETC2 and EAC.
+  ETC2,
+  /// ASTC (LDR profile).
+  ASTC,
 }
 
 /// Size in bits a pixel channel can be.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Size {
+  /// 2-bit.
+  Two,
+  /// 5-bit.
+  Five,
+  /// 6-bit.
+  Six,
   /// 8-bit.
   Eight,
+  /// 9-bit.
+  Nine,
   /// 10-bit.
   Ten,
   /// 11-bit.
   Eleven,
   /// 16-bit.
   Sixteen,
+  /// 24-bit.
+  TwentyFour,
   /// 32-bit.
   ThirtyTwo,
 }
@@ -164,10 +353,15 @@ impl Size {
   /// Size (in bits).
   pub fn bits(self) -> usize {
     match self {
+      Size::Two => 2,
+      Size::Five => 5,
+      Size::Six => 6,
       Size::Eight => 8,
+      Size::Nine => 9,
       Size::Ten => 10,
       Size::Eleven => 11,
       Size::Sixteen => 16,
+      Size::TwentyFour => 24,
       Size::ThirtyTwo => 32,
     }
   }
@@ -223,6 +417,106 @@ unsafe impl SamplerType for Floating {
   }
 }
 
+/// A 16-bit (half-precision) floating-point number, stored as its IEEE 754 binary16 bits.
+///
+/// It is the encoding of half-float pixel formats, such as [`RGBA16F`]. Conversions from and to
+/// [`f32`] round to the nearest representable value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Half(u16);
+
+impl Half {
+  /// Create a [`Half`] from its raw bits.
+  pub const fn from_bits(bits: u16) -> Self {
+    Half(bits)
+  }
+
+  /// Raw bits of a [`Half`].
+  pub const fn to_bits(self) -> u16 {
+    self.0
+  }
+
+  /// Convert a [`f32`] to the nearest [`Half`].
+  ///
+  /// Values too large to be represented become infinities, and values too small become zeros.
+  pub fn from_f32(x: f32) -> Self {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xFF) as i32;
+    let man = bits & 0x7F_FFFF;
+
+    // infinities and NaNs, which keep a non-zero mantissa
+    if exp == 0xFF {
+      let nan = if man == 0 { 0 } else { 0x200 };
+      return Half(sign | 0x7C00 | nan);
+    }
+
+    let exp = exp - 127 + 15;
+
+    if exp >= 0x1F {
+      return Half(sign | 0x7C00);
+    }
+
+    if exp <= 0 {
+      if exp < -10 {
+        return Half(sign);
+      }
+
+      // subnormal; the implicit bit becomes explicit
+      let man = man | 0x80_0000;
+      let shift = (14 - exp) as u32;
+      let half_man = man >> shift;
+      let rem = man & ((1 << shift) - 1);
+      let halfway = 1 << (shift - 1);
+      let round = rem > halfway || (rem == halfway && half_man & 1 == 1);
+
+      return Half(sign | (half_man + round as u32) as u16);
+    }
+
+    // rounding up might carry into the exponent, which is still correct, up to infinity
+    let half = (exp as u32) << 10 | man >> 13;
+    let rem = man & 0x1FFF;
+    let round = rem > 0x1000 || (rem == 0x1000 && half & 1 == 1);
+
+    Half(sign | (half + round as u32) as u16)
+  }
+
+  /// Convert a [`Half`] to a [`f32`], which is always exact.
+  pub fn to_f32(self) -> f32 {
+    let sign = ((self.0 & 0x8000) as u32) << 16;
+    let exp = ((self.0 >> 10) & 0x1F) as u32;
+    let man = (self.0 & 0x3FF) as u32;
+
+    let bits = match exp {
+      0 if man == 0 => sign,
+
+      // subnormal; normalize it
+      0 => {
+        let shift = man.leading_zeros() - 21;
+        sign | (113 - shift) << 23 | ((man << shift) & 0x3FF) << 13
+      }
+
+      0x1F => sign | 0x7F80_0000 | man << 13,
+
+      _ => sign | (exp + 127 - 15) << 23 | man << 13,
+    };
+
+    f32::from_bits(bits)
+  }
+}
+
+impl From<f32> for Half {
+  fn from(x: f32) -> Self {
+    Half::from_f32(x)
+  }
+}
+
+impl From<Half> for f32 {
+  fn from(x: Half) -> Self {
+    x.to_f32()
+  }
+}
+
 macro_rules! impl_Pixel {
   ($t:ty, $encoding:ty, $raw_encoding:ty, $encoding_ty:ident, $format:expr) => {
     unsafe impl Pixel for $t {
@@ -258,6 +552,13 @@ macro_rules! impl_RenderablePixel {
   };
 }
 
+macro_rules! impl_CompressedPixel {
+  ($t:ty, $encoding_ty:ident, $format:expr) => {
+    impl_Pixel!($t, u8, u8, $encoding_ty, $format);
+    unsafe impl CompressedPixel for $t {}
+  };
+}
+
 /// A red 8-bit signed integral pixel format.
 #[derive(Clone, Copy, Debug)]
 pub struct R8I;
@@ -322,6 +623,14 @@ impl_Pixel!(NormR16UI, u16, u16, NormUnsigned, Format::R(Size::Sixteen));
 impl_ColorPixel!(NormR16UI);
 impl_RenderablePixel!(NormR16UI);
 
+/// A red 16-bit floating pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct R16F;
+
+impl_Pixel!(R16F, Half, Half, Floating, Format::R(Size::Sixteen));
+impl_ColorPixel!(R16F);
+impl_RenderablePixel!(R16F);
+
 /// A red 32-bit signed integral pixel format.
 #[derive(Clone, Copy, Debug)]
 pub struct R32I;
@@ -480,6 +789,20 @@ impl_Pixel!(
 impl_ColorPixel!(NormRG16UI);
 impl_RenderablePixel!(NormRG16UI);
 
+/// A red and green 16-bit floating pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct RG16F;
+
+impl_Pixel!(
+  RG16F,
+  (Half, Half),
+  Half,
+  Floating,
+  Format::RG(Size::Sixteen, Size::Sixteen)
+);
+impl_ColorPixel!(RG16F);
+impl_RenderablePixel!(RG16F);
+
 /// A red and green 32-bit signed integral pixel format.
 #[derive(Clone, Copy, Debug)]
 pub struct RG32I;
@@ -854,6 +1177,20 @@ impl_Pixel!(
 impl_ColorPixel!(NormRGBA16UI);
 impl_RenderablePixel!(NormRGBA16UI);
 
+/// A red, green, blue and alpha 16-bit floating pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct RGBA16F;
+
+impl_Pixel!(
+  RGBA16F,
+  (Half, Half, Half, Half),
+  Half,
+  Floating,
+  Format::RGBA(Size::Sixteen, Size::Sixteen, Size::Sixteen, Size::Sixteen)
+);
+impl_ColorPixel!(RGBA16F);
+impl_RenderablePixel!(RGBA16F);
+
 /// A red, green, blue and alpha 32-bit signed integral pixel format.
 #[derive(Clone, Copy, Debug)]
 pub struct RGBA32I;
@@ -969,6 +1306,67 @@ impl_Pixel!(
 impl_ColorPixel!(R11G11B10F);
 impl_RenderablePixel!(R11G11B10F);
 
+/// A red, green, blue and alpha pixel format, accessed as normalized floating pixels, in which:
+///
+///   - The red, green and blue channels are on 10 bits.
+///   - The alpha channel is on 2 bits.
+///
+/// All channels are packed in a single 32-bit word, the red channel being in the least
+/// significant bits.
+#[derive(Clone, Copy, Debug)]
+pub struct RGB10A2;
+
+impl_Pixel!(
+  RGB10A2,
+  u32,
+  u32,
+  NormUnsigned,
+  Format::RGBA(Size::Ten, Size::Ten, Size::Ten, Size::Two)
+);
+impl_ColorPixel!(RGB10A2);
+impl_RenderablePixel!(RGB10A2);
+
+/// A red, green and blue floating pixel format in which:
+///
+///   - The red, green and blue mantissas are on 9 bits.
+///   - The three channels share a 5-bit exponent.
+///
+/// All channels are packed in a single 32-bit word, the red mantissa being in the least
+/// significant bits and the exponent in the most significant ones. That format cannot be
+/// rendered to.
+#[derive(Clone, Copy, Debug)]
+pub struct RGB9E5;
+
+impl_Pixel!(
+  RGB9E5,
+  u32,
+  u32,
+  Floating,
+  Format::RGBE(Size::Nine, Size::Nine, Size::Nine, Size::Five)
+);
+impl_ColorPixel!(RGB9E5);
+
+/// A red, green and blue pixel format, accessed as normalized floating pixels, in which:
+///
+///   - The red channel is on 5 bits.
+///   - The green channel is on 6 bits.
+///   - The blue channel is on 5 bits.
+///
+/// All channels are packed in a single 16-bit word, the red channel being in the most
+/// significant bits.
+#[derive(Clone, Copy, Debug)]
+pub struct RGB565;
+
+impl_Pixel!(
+  RGB565,
+  u16,
+  u16,
+  NormUnsigned,
+  Format::RGB(Size::Five, Size::Six, Size::Five)
+);
+impl_ColorPixel!(RGB565);
+impl_RenderablePixel!(RGB565);
+
 /// An 8-bit unsigned integral red, green and blue pixel format in sRGB colorspace.
 #[derive(Clone, Copy, Debug)]
 pub struct SRGB8UI;
@@ -1003,3 +1401,329 @@ pub struct Depth32F;
 
 impl_Pixel!(Depth32F, f32, f32, Floating, Format::Depth(Size::ThirtyTwo));
 impl_DepthPixel!(Depth32F);
+
+/// A depth 24-bit normalized and stencil 8-bit unsigned pixel format.
+///
+/// Both channels are packed in a single 32-bit word, the depth being in the 24 most significant
+/// bits.
+#[derive(Clone, Copy, Debug)]
+pub struct Depth24Stencil8;
+
+impl_Pixel!(
+  Depth24Stencil8,
+  u32,
+  u32,
+  NormUnsigned,
+  Format::DepthStencil(Size::TwentyFour, Size::Eight)
+);
+impl_DepthPixel!(Depth24Stencil8);
+
+/// A depth 32-bit floating and stencil 8-bit unsigned pixel format.
+///
+/// The depth is stored in a first 32-bit word and the stencil in the 8 least significant bits of
+/// a second one.
+#[derive(Clone, Copy, Debug)]
+pub struct Depth32FStencil8;
+
+impl_Pixel!(
+  Depth32FStencil8,
+  (f32, u32),
+  u32,
+  Floating,
+  Format::DepthStencil(Size::ThirtyTwo, Size::Eight)
+);
+impl_DepthPixel!(Depth32FStencil8);
+
+/// A BC1 (DXT1) compressed red, green and blue pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct BC1;
+
+impl_CompressedPixel!(BC1, NormUnsigned, Format::Compressed(Compression::BC1));
+
+/// A BC1 (DXT1) compressed red, green and blue pixel format in sRGB colorspace.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBBC1;
+
+impl_CompressedPixel!(
+  SRGBBC1,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::BC1)
+);
+
+/// A BC1 (DXT1) compressed red, green and blue pixel format, with a 1-bit alpha channel.
+#[derive(Clone, Copy, Debug)]
+pub struct BC1A;
+
+impl_CompressedPixel!(BC1A, NormUnsigned, Format::Compressed(Compression::BC1A));
+
+/// A BC1 (DXT1) compressed red, green and blue pixel format in sRGB colorspace, with a 1-bit
+/// linear alpha channel.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBBC1A;
+
+impl_CompressedPixel!(
+  SRGBBC1A,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::BC1A)
+);
+
+/// A BC2 (DXT3) compressed red, green, blue and alpha pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct BC2;
+
+impl_CompressedPixel!(BC2, NormUnsigned, Format::Compressed(Compression::BC2));
+
+/// A BC2 (DXT3) compressed red, green and blue pixel format in sRGB colorspace, with linear alpha
+/// channel.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBBC2;
+
+impl_CompressedPixel!(
+  SRGBBC2,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::BC2)
+);
+
+/// A BC3 (DXT5) compressed red, green, blue and alpha pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct BC3;
+
+impl_CompressedPixel!(BC3, NormUnsigned, Format::Compressed(Compression::BC3));
+
+/// A BC3 (DXT5) compressed red, green and blue pixel format in sRGB colorspace, with linear alpha
+/// channel.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBBC3;
+
+impl_CompressedPixel!(
+  SRGBBC3,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::BC3)
+);
+
+/// A BC4 (RGTC1) compressed red pixel format, accessed as normalized floating pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct BC4;
+
+impl_CompressedPixel!(BC4, NormUnsigned, Format::Compressed(Compression::BC4));
+
+/// A BC4 (RGTC1) compressed signed red pixel format, accessed as normalized floating pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct BC4Signed;
+
+impl_CompressedPixel!(
+  BC4Signed,
+  NormIntegral,
+  Format::Compressed(Compression::BC4)
+);
+
+/// A BC5 (RGTC2) compressed red and green pixel format, accessed as normalized floating pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct BC5;
+
+impl_CompressedPixel!(BC5, NormUnsigned, Format::Compressed(Compression::BC5));
+
+/// A BC5 (RGTC2) compressed signed red and green pixel format, accessed as normalized floating
+/// pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct BC5Signed;
+
+impl_CompressedPixel!(
+  BC5Signed,
+  NormIntegral,
+  Format::Compressed(Compression::BC5)
+);
+
+/// A BC6H (BPTC) compressed unsigned floating red, green and blue pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct BC6H;
+
+impl_CompressedPixel!(BC6H, Floating, Format::Compressed(Compression::BC6H));
+
+/// A BC6H (BPTC) compressed signed floating red, green and blue pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct BC6HSigned;
+
+impl_CompressedPixel!(
+  BC6HSigned,
+  Floating,
+  Format::Compressed(Compression::BC6HSigned)
+);
+
+/// A BC7 (BPTC) compressed red, green, blue and alpha pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct BC7;
+
+impl_CompressedPixel!(BC7, NormUnsigned, Format::Compressed(Compression::BC7));
+
+/// A BC7 (BPTC) compressed red, green and blue pixel format in sRGB colorspace, with linear alpha
+/// channel.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBBC7;
+
+impl_CompressedPixel!(
+  SRGBBC7,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::BC7)
+);
+
+/// An ETC2 compressed red, green and blue pixel format.
+#[derive(Clone, Copy, Debug)]
+pub struct ETC2;
+
+impl_CompressedPixel!(ETC2, NormUnsigned, Format::Compressed(Compression::ETC2));
+
+/// An ETC2 compressed red, green and blue pixel format in sRGB colorspace.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBETC2;
+
+impl_CompressedPixel!(
+  SRGBETC2,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::ETC2)
+);
+
+/// An ETC2 compressed red, green and blue pixel format, with a 1-bit alpha channel.
+#[derive(Clone, Copy, Debug)]
+pub struct ETC2A1;
+
+impl_CompressedPixel!(
+  ETC2A1,
+  NormUnsigned,
+  Format::Compressed(Compression::ETC2A1)
+);
+
+/// An ETC2 compressed red, green and blue pixel format in sRGB colorspace, with a 1-bit linear
+/// alpha channel.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBETC2A1;
+
+impl_CompressedPixel!(
+  SRGBETC2A1,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::ETC2A1)
+);
+
+/// An ETC2 compressed red, green and blue pixel format, with an EAC compressed alpha channel.
+#[derive(Clone, Copy, Debug)]
+pub struct ETC2EAC;
+
+impl_CompressedPixel!(
+  ETC2EAC,
+  NormUnsigned,
+  Format::Compressed(Compression::ETC2EAC)
+);
+
+/// An ETC2 compressed red, green and blue pixel format in sRGB colorspace, with an EAC compressed
+/// linear alpha channel.
+#[derive(Clone, Copy, Debug)]
+pub struct SRGBETC2EAC;
+
+impl_CompressedPixel!(
+  SRGBETC2EAC,
+  NormUnsigned,
+  Format::SRGBCompressed(Compression::ETC2EAC)
+);
+
+/// An EAC compressed 11-bit red pixel format, accessed as normalized floating pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct EACR11;
+
+impl_CompressedPixel!(
+  EACR11,
+  NormUnsigned,
+  Format::Compressed(Compression::EACR11)
+);
+
+/// An EAC compressed 11-bit signed red pixel format, accessed as normalized floating pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct EACR11Signed;
+
+impl_CompressedPixel!(
+  EACR11Signed,
+  NormIntegral,
+  Format::Compressed(Compression::EACR11)
+);
+
+/// An EAC compressed 11-bit red and green pixel format, accessed as normalized floating pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct EACRG11;
+
+impl_CompressedPixel!(
+  EACRG11,
+  NormUnsigned,
+  Format::Compressed(Compression::EACRG11)
+);
+
+/// An EAC compressed 11-bit signed red and green pixel format, accessed as normalized floating
+/// pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct EACRG11Signed;
+
+impl_CompressedPixel!(
+  EACRG11Signed,
+  NormIntegral,
+  Format::Compressed(Compression::EACRG11)
+);
+
+macro_rules! impl_ASTC {
+  ($($t:ident, $srgb_t:ident, $w:literal x $h:literal;)*) => {
+    $(
+      #[doc = concat!(
+        "An ASTC compressed red, green, blue and alpha pixel format, in blocks of ",
+        $w,
+        "×",
+        $h,
+        " texels."
+      )]
+      #[derive(Clone, Copy, Debug)]
+      pub struct $t;
+
+      impl_CompressedPixel!(
+        $t,
+        NormUnsigned,
+        Format::Compressed(Compression::ASTC {
+          block_width: $w,
+          block_height: $h
+        })
+      );
+
+      #[doc = concat!(
+        "An ASTC compressed red, green and blue pixel format in sRGB colorspace, with linear ",
+        "alpha channel, in blocks of ",
+        $w,
+        "×",
+        $h,
+        " texels."
+      )]
+      #[derive(Clone, Copy, Debug)]
+      pub struct $srgb_t;
+
+      impl_CompressedPixel!(
+        $srgb_t,
+        NormUnsigned,
+        Format::SRGBCompressed(Compression::ASTC {
+          block_width: $w,
+          block_height: $h
+        })
+      );
+    )*
+  };
+}
+
+impl_ASTC! {
+  ASTC4x4, SRGBASTC4x4, 4 x 4;
+  ASTC5x4, SRGBASTC5x4, 5 x 4;
+  ASTC5x5, SRGBASTC5x5, 5 x 5;
+  ASTC6x5, SRGBASTC6x5, 6 x 5;
+  ASTC6x6, SRGBASTC6x6, 6 x 6;
+  ASTC8x5, SRGBASTC8x5, 8 x 5;
+  ASTC8x6, SRGBASTC8x6, 8 x 6;
+  ASTC8x8, SRGBASTC8x8, 8 x 8;
+  ASTC10x5, SRGBASTC10x5, 10 x 5;
+  ASTC10x6, SRGBASTC10x6, 10 x 6;
+  ASTC10x8, SRGBASTC10x8, 10 x 8;
+  ASTC10x10, SRGBASTC10x10, 10 x 10;
+  ASTC12x10, SRGBASTC12x10, 12 x 10;
+  ASTC12x12, SRGBASTC12x12, 12 x 12;
+}
@@ -0,0 +1,177 @@
+//! GPU queries.
+//!
+//! A [`Query`] gathers information about the GPU work issued in a section of a graphics pipeline —
+//! how long it took to execute, how many samples it rendered, etc. Because the GPU runs
+//! asynchronously, the result of a query is not available right after the section is issued:
+//! instead, it is retrieved without blocking with [`Query::try_get`], typically in a later frame.
+//!
+//! Queries wrap a section of a [`PipelineGate::pipeline`], [`ShadingGate::shade`] or
+//! [`RenderGate::render`] call, with respectively [`PipelineGate::query`], [`ShadingGate::query`]
+//! and [`RenderGate::query`]:
+//!
+//! ```ignore
+//! use luminance::context::GraphicsContext as _;
+//! use luminance::query::QueryType;
+//!
+//! let mut query = context.new_query(QueryType::SamplesPassed)?;
+//!
+//! context
+//!   .new_pipeline_gate()
+//!   .pipeline(&back_buffer, &PipelineState::default(), |_, mut shd_gate| {
+//!     shd_gate.shade(&mut program, |_, _, mut rdr_gate| {
+//!       rdr_gate.query(&mut query, |rdr_gate| {
+//!         rdr_gate.render(&RenderState::default(), |mut tess_gate| tess_gate.render(&tess))
+//!       })
+//!     })
+//!   });
+//!
+//! // later on
+//! if let Some(samples) = query.try_get()? {
+//!   println!("{} samples passed", samples);
+//! }
+//! ```
+//!
+//! Only one query of a given [`QueryType`] can be active at a time: nesting queries of the same
+//! type fails with [`QueryError::AlreadyActive`]. [`QueryType::SamplesPassed`] and
+//! [`QueryType::AnySamplesPassed`] — the occlusion queries — count as the same type there. Queries
+//! of different types can be nested freely.
+//!
+//! Not all backends support queries — the ones that do implement the [`backend::query::Query`]
+//! trait — and not all of them support every [`QueryType`].
+//!
+//! [`PipelineGate::pipeline`]: crate::pipeline::PipelineGate::pipeline
+//! [`PipelineGate::query`]: crate::pipeline::PipelineGate::query
+//! [`ShadingGate::shade`]: crate::shading_gate::ShadingGate::shade
+//! [`ShadingGate::query`]: crate::shading_gate::ShadingGate::query
+//! [`RenderGate::render`]: crate::render_gate::RenderGate::render
+//! [`RenderGate::query`]: crate::render_gate::RenderGate::query
+//! [`backend::query::Query`]: crate::backend::query::Query
+
+use std::error;
+use std::fmt;
+
+use crate::backend::query::Query as QueryBackend;
+use crate::context::GraphicsContext;
+
+/// Type of a [`Query`], defining what it gathers.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum QueryType {
+  /// Time it took for the GPU to execute the section, in nanoseconds.
+  TimeElapsed,
+  /// Time at which the GPU has completed the section, in nanoseconds.
+  ///
+  /// The origin of the timestamps is implementation-defined, so they are only meaningful when
+  /// compared to each other.
+  Timestamp,
+  /// Number of samples that passed the depth test in the section.
+  SamplesPassed,
+  /// Whether any sample passed the depth test in the section: `1` if so, `0` otherwise.
+  AnySamplesPassed,
+  /// Number of primitives generated in the section.
+  PrimitivesGenerated,
+}
+
+impl fmt::Display for QueryType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      QueryType::TimeElapsed => f.write_str("time elapsed"),
+      QueryType::Timestamp => f.write_str("timestamp"),
+      QueryType::SamplesPassed => f.write_str("samples passed"),
+      QueryType::AnySamplesPassed => f.write_str("any samples passed"),
+      QueryType::PrimitivesGenerated => f.write_str("primitives generated"),
+    }
+  }
+}
+
+/// Errors that might occur when using queries.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum QueryError {
+  /// Cannot create a query.
+  CannotCreate,
+  /// The backend doesn’t support this type of query.
+  UnsupportedType(QueryType),
+  /// A query of this type — or another occlusion query, for occlusion queries — is already
+  /// active.
+  AlreadyActive(QueryType),
+}
+
+impl QueryError {
+  /// Cannot create a query.
+  pub fn cannot_create() -> Self {
+    QueryError::CannotCreate
+  }
+
+  /// The backend doesn’t support this type of query.
+  pub fn unsupported_type(ty: QueryType) -> Self {
+    QueryError::UnsupportedType(ty)
+  }
+
+  /// A query of this type — or another occlusion query, for occlusion queries — is already
+  /// active.
+  pub fn already_active(ty: QueryType) -> Self {
+    QueryError::AlreadyActive(ty)
+  }
+}
+
+impl fmt::Display for QueryError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      QueryError::CannotCreate => f.write_str("cannot create query"),
+      QueryError::UnsupportedType(ty) => write!(f, "unsupported query type: {}", ty),
+      QueryError::AlreadyActive(ty) => write!(f, "a {} query is already active", ty),
+    }
+  }
+}
+
+impl error::Error for QueryError {}
+
+/// A GPU query.
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+pub struct Query<B>
+where
+  B: ?Sized + QueryBackend,
+{
+  pub(crate) repr: B::QueryRepr,
+  ty: QueryType,
+}
+
+impl<B> Query<B>
+where
+  B: ?Sized + QueryBackend,
+{
+  /// Create a new [`Query`] of type `ty`.
+  ///
+  /// # Errors
+  ///
+  /// [`QueryError::UnsupportedType`] is returned if the backend doesn’t support `ty`.
+  ///
+  /// # Notes
+  ///
+  /// Feel free to look at the documentation of [`GraphicsContext::new_query`] for a simpler
+  /// interface.
+  pub fn new<C>(ctx: &mut C, ty: QueryType) -> Result<Self, QueryError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    let repr = unsafe { ctx.backend().new_query(ty)? };
+    Ok(Query { repr, ty })
+  }
+
+  /// Type of the query.
+  pub fn query_type(&self) -> QueryType {
+    self.ty
+  }
+
+  /// Get the result of the query, if available.
+  ///
+  /// This function doesn’t block: it returns `Ok(None)` if the GPU hasn’t finished executing the
+  /// last section the query wrapped, or if the query hasn’t wrapped any section yet. Wrapping a
+  /// section again before the result is available discards that result.
+  pub fn try_get(&mut self) -> Result<Option<u64>, QueryError> {
+    unsafe { B::query_result(&mut self.repr) }
+  }
+}
@@ -0,0 +1,92 @@
+//! Non-blocking readbacks.
+//!
+//! Reading data back from the GPU — with [`Buffer::whole`] or [`Texture::get_raw_texels`], for
+//! instance — stalls the CPU until the GPU has executed all the commands writing to that data.
+//! A [`Readback`] avoids that stall: it is started right away — with [`Buffer::whole_async`] or
+//! [`Texture::get_raw_texels_async`] — and copies the data to a staging area on the GPU side. The
+//! data is then retrieved without blocking with [`Readback::try_get`], typically in a later frame:
+//!
+//! ```ignore
+//! let mut readback = color_texture.get_raw_texels_async()?;
+//!
+//! // later on
+//! if let Some(texels) = readback.try_get()? {
+//!   save_screenshot(&texels);
+//! }
+//! ```
+//!
+//! Not all backends support readbacks — the ones that do implement the
+//! [`backend::readback::Readback`] trait.
+//!
+//! [`Buffer::whole`]: crate::buffer::Buffer::whole
+//! [`Buffer::whole_async`]: crate::buffer::Buffer::whole_async
+//! [`Texture::get_raw_texels`]: crate::texture::Texture::get_raw_texels
+//! [`Texture::get_raw_texels_async`]: crate::texture::Texture::get_raw_texels_async
+//! [`backend::readback::Readback`]: crate::backend::readback::Readback
+
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::backend::readback::Readback as ReadbackBackend;
+
+/// Errors that might occur when retrieving the data of readbacks.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReadbackError {
+  /// The staging area holding the data cannot be mapped.
+  MapFailed,
+}
+
+impl ReadbackError {
+  /// The staging area holding the data cannot be mapped.
+  pub fn map_failed() -> Self {
+    ReadbackError::MapFailed
+  }
+}
+
+impl fmt::Display for ReadbackError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      ReadbackError::MapFailed => f.write_str("readback mapping failed"),
+    }
+  }
+}
+
+impl error::Error for ReadbackError {}
+
+/// A pending readback of GPU data.
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+/// - `T` is the type of the read items.
+pub struct Readback<B, T>
+where
+  B: ?Sized + ReadbackBackend<T>,
+  T: Copy,
+{
+  repr: B::ReadbackRepr,
+  _t: PhantomData<T>,
+}
+
+impl<B, T> Readback<B, T>
+where
+  B: ?Sized + ReadbackBackend<T>,
+  T: Copy,
+{
+  pub(crate) fn from_repr(repr: B::ReadbackRepr) -> Self {
+    Readback {
+      repr,
+      _t: PhantomData,
+    }
+  }
+
+  /// Get the read data, if available.
+  ///
+  /// This function doesn’t block: it returns `Ok(None)` if the GPU hasn’t finished copying the data
+  /// yet.
+  pub fn try_get(&mut self) -> Result<Option<Vec<T>>, ReadbackError> {
+    unsafe { B::readback_result(&mut self.repr) }
+  }
+}
@@ -5,7 +5,9 @@
 //!
 //! [`Tess`]: crate::tess::Tess
 
+use crate::backend::query::Query as QueryBackend;
 use crate::backend::render_gate::RenderGate as RenderGateBackend;
+use crate::query::{Query, QueryError};
 use crate::render_state::RenderState;
 use crate::tess_gate::TessGate;
 
@@ -40,4 +42,25 @@ where
 
     f(tess_gate)
   }
+
+  /// Wrap a section of the render in a [`Query`].
+  ///
+  /// The argument closure is given this gate back, to issue the section of the render the query
+  /// gathers information about.
+  ///
+  /// # Errors
+  ///
+  /// If the query cannot start — for instance, because a query of the same type is already
+  /// active — the closure isn’t run and the [`QueryError`] is returned.
+  pub fn query<E, F>(&mut self, query: &mut Query<B>, f: F) -> Result<(), E>
+  where
+    B: QueryBackend,
+    F: FnOnce(&mut Self) -> Result<(), E>,
+    E: From<QueryError>,
+  {
+    unsafe { self.backend.begin_query(&mut query.repr)? };
+    let r = f(self);
+    unsafe { self.backend.end_query(&mut query.repr) };
+    r
+  }
 }
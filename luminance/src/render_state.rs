@@ -1,12 +1,13 @@
 //! GPU render state.
 //!
 //! Such a state controls how the GPU must operate some fixed pipeline functionality, such as the
-//! blending, depth test or face culling operations.
+//! blending, depth test, stencil test or face culling operations.
 
 use crate::blending::{Blending, BlendingMode};
 use crate::depth_test::{DepthComparison, DepthWrite};
 use crate::face_culling::FaceCulling;
 use crate::scissor::ScissorRegion;
+use crate::stencil::{StencilOperations, StencilTest};
 
 /// GPU render state.
 ///
@@ -20,6 +21,14 @@ pub struct RenderState {
   depth_test: Option<DepthComparison>,
   /// Depth write configuration.
   depth_write: DepthWrite,
+  /// Stencil test configuration.
+  stencil_test: Option<StencilTest>,
+  /// Stencil operations configuration for front-facing primitives.
+  front_stencil_operations: StencilOperations,
+  /// Stencil operations configuration for back-facing primitives.
+  back_stencil_operations: StencilOperations,
+  /// Stencil write mask.
+  stencil_write_mask: u8,
   /// Face culling configuration.
   face_culling: Option<FaceCulling>,
   /// Scissor region configuration.
@@ -81,6 +90,71 @@ impl RenderState {
     self.depth_write
   }
 
+  /// Override the stencil test configuration.
+  pub fn set_stencil_test<S>(self, stencil_test: S) -> Self
+  where
+    S: Into<Option<StencilTest>>,
+  {
+    RenderState {
+      stencil_test: stencil_test.into(),
+      ..self
+    }
+  }
+
+  /// Stencil test configuration.
+  pub fn stencil_test(&self) -> Option<StencilTest> {
+    self.stencil_test
+  }
+
+  /// Override the stencil operations configuration of both front-facing and back-facing
+  /// primitives.
+  pub fn set_stencil_operations(self, stencil_operations: StencilOperations) -> Self {
+    RenderState {
+      front_stencil_operations: stencil_operations,
+      back_stencil_operations: stencil_operations,
+      ..self
+    }
+  }
+
+  /// Override the stencil operations configuration using separate operations for front-facing and
+  /// back-facing primitives.
+  pub fn set_stencil_operations_separate(
+    self,
+    front_stencil_operations: StencilOperations,
+    back_stencil_operations: StencilOperations,
+  ) -> Self {
+    RenderState {
+      front_stencil_operations,
+      back_stencil_operations,
+      ..self
+    }
+  }
+
+  /// Stencil operations configuration for front-facing primitives.
+  pub fn front_stencil_operations(&self) -> StencilOperations {
+    self.front_stencil_operations
+  }
+
+  /// Stencil operations configuration for back-facing primitives.
+  pub fn back_stencil_operations(&self) -> StencilOperations {
+    self.back_stencil_operations
+  }
+
+  /// Override the stencil write mask.
+  ///
+  /// Only the bits set in the mask are written to the stencil buffer.
+  pub fn set_stencil_write_mask(self, stencil_write_mask: u8) -> Self {
+    RenderState {
+      stencil_write_mask,
+      ..self
+    }
+  }
+
+  /// Stencil write mask.
+  pub fn stencil_write_mask(&self) -> u8 {
+    self.stencil_write_mask
+  }
+
   /// Override the face culling configuration.
   pub fn set_face_culling<FC>(self, face_culling: FC) -> Self
   where
@@ -120,6 +194,10 @@ impl Default for RenderState {
   ///   - `blending`: `None`
   ///   - `depth_test`: `Some(DepthComparison::Less)`
   ///   - `depth_write`: `DepthWrite::On`
+  ///   - `stencil_test`: `None`
+  ///   - `front_stencil_operations`: `StencilOperations::default()`
+  ///   - `back_stencil_operations`: `StencilOperations::default()`
+  ///   - `stencil_write_mask`: `0xFF`
   ///   - `face_culling`: `None`
   ///   - 'scissor_region`: `None`
   fn default() -> Self {
@@ -127,6 +205,10 @@ impl Default for RenderState {
       blending: None,
       depth_test: Some(DepthComparison::Less),
       depth_write: DepthWrite::On,
+      stencil_test: None,
+      front_stencil_operations: StencilOperations::default(),
+      back_stencil_operations: StencilOperations::default(),
+      stencil_write_mask: 0xFF,
       face_culling: None,
       scissor: None,
     }
@@ -0,0 +1,81 @@
+//! Renderbuffers.
+//!
+//! A [`Renderbuffer`] is a 2D image a framebuffer renders to, like a texture, but that cannot be
+//! sampled in shaders nor have its texels read back. Backends are then free to store it in the
+//! most efficient way, which saves memory when a slot is only used while rendering — typically,
+//! a depth buffer that is never sampled.
+//!
+//! Renderbuffers are never created directly: they are used as color or depth slots of 2D
+//! framebuffers, and created along with them:
+//!
+//! ```ignore
+//! use luminance::context::GraphicsContext as _;
+//! use luminance::pixel::{Depth32F, NormRGBA8UI};
+//! use luminance::renderbuffer::Renderbuffer;
+//! use luminance::texture::{Dim2, Sampler};
+//!
+//! let framebuffer = context.new_framebuffer::<Dim2, NormRGBA8UI, Renderbuffer<_, Depth32F>>(
+//!   [800, 600],
+//!   0,
+//!   Sampler::default(),
+//! )?;
+//! ```
+//!
+//! [`Framebuffer::color_slot`] and [`Framebuffer::depth_slot`] then return the [`Renderbuffer`],
+//! which is an opaque handle. Its content can still be copied to another framebuffer with
+//! [`Framebuffer::blit_to`].
+//!
+//! [`Framebuffer::color_slot`]: crate::framebuffer::Framebuffer::color_slot
+//! [`Framebuffer::depth_slot`]: crate::framebuffer::Framebuffer::depth_slot
+//! [`Framebuffer::blit_to`]: crate::framebuffer::Framebuffer::blit_to
+
+use std::marker::PhantomData;
+
+use crate::backend::renderbuffer::Renderbuffer as RenderbufferBackend;
+use crate::context::GraphicsContext;
+use crate::framebuffer::FramebufferError;
+use crate::pixel::Pixel;
+
+/// A renderbuffer.
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+/// - `P` is the pixel type.
+pub struct Renderbuffer<B, P>
+where
+  B: ?Sized + RenderbufferBackend<P>,
+  P: Pixel,
+{
+  pub(crate) repr: B::RenderbufferRepr,
+  size: [u32; 2],
+  _phantom: PhantomData<*const P>,
+}
+
+impl<B, P> Renderbuffer<B, P>
+where
+  B: ?Sized + RenderbufferBackend<P>,
+  P: Pixel,
+{
+  /// Create a new [`Renderbuffer`] of size `size`.
+  pub(crate) fn new<C>(ctx: &mut C, size: [u32; 2]) -> Result<Self, FramebufferError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    unsafe {
+      ctx
+        .backend()
+        .new_renderbuffer(size)
+        .map(|repr| Renderbuffer {
+          repr,
+          size,
+          _phantom: PhantomData,
+        })
+    }
+  }
+
+  /// Return the size of the renderbuffer.
+  pub fn size(&self) -> [u32; 2] {
+    self.size
+  }
+}
@@ -28,6 +28,9 @@
 //! Those are not all mandatory: only the _vertex_ stage and _fragment_ stages are mandatory. If
 //! you want tessellation shaders, you have to provide both of them.
 //!
+//! A sixth kind of stage, [`StageType::ComputeShader`], is not part of the graphics pipeline. It is
+//! linked alone into a [`ComputeProgram`], which is dispatched instead of being used to render.
+//!
 //! Shader stages — [`Stage`] — are compiled independently at runtime by your GPU driver, and then
 //! _linked_ into a shader program. The creation of a [`Stage`] implies using an input string,
 //! representing the _source code_ of the stage. This is an opaque [`String`] that must represent
@@ -124,6 +127,13 @@
 //! `Uniform<BufferBinding<YourType>>`, telling your shader program where to grab the data — from
 //! the bound buffer.
 //!
+//! Buffers bound that way are read-only in shaders (_uniform blocks_). If you need shaders to write
+//! to a buffer — or to read a buffer which size is not known in advance — bind it as a _storage
+//! buffer_ instead with [`Pipeline::bind_storage_buffer`], if your backend supports it. You then
+//! get a [`StorageBufferBinding`] to set on a `Uniform<StorageBufferBinding<YourType>>`, named
+//! after the storage block. The buffer is exposed in full to the storage block, so that the block
+//! can be declared with an unsized array of `YourType` as last member.
+//!
 //! This way of doing is very practical and powerful but currently, in this version of the crate,
 //! very unsafe. A better API will be available in a next release to make all this simpler and
 //! safer.
@@ -137,9 +147,12 @@
 //!
 //! [`Vertex`]: crate::vertex::Vertex
 //! [`Buffer`]: crate::buffer::Buffer
+//! [`ComputeProgram`]: crate::compute::ComputeProgram
 //! [`Pipeline`]: crate::pipeline::Pipeline
 //! [`BoundBuffer`]: crate::pipeline::BoundBuffer
 //! [`BufferBinding`]: crate::pipeline::BufferBinding
+//! [`Pipeline::bind_storage_buffer`]: crate::pipeline::Pipeline::bind_storage_buffer
+//! [`StorageBufferBinding`]: crate::pipeline::StorageBufferBinding
 
 use std::error;
 use std::fmt;
@@ -162,6 +175,8 @@ pub enum StageType {
   GeometryShader,
   /// Fragment shader.
   FragmentShader,
+  /// Compute shader.
+  ComputeShader,
 }
 
 impl fmt::Display for StageType {
@@ -172,6 +187,7 @@ impl fmt::Display for StageType {
       StageType::TessellationEvaluationShader => f.write_str("tessellation evaluation shader"),
       StageType::GeometryShader => f.write_str("geometry shader"),
       StageType::FragmentShader => f.write_str("fragment shader"),
+      StageType::ComputeShader => f.write_str("compute shader"),
     }
   }
 }
@@ -591,6 +607,8 @@ pub enum UniformType {
   // buffer
   /// Buffer binding; used for UBOs.
   BufferBinding,
+  /// Storage buffer binding; used for SSBOs.
+  StorageBufferBinding,
 }
 
 impl fmt::Display for UniformType {
@@ -641,6 +659,7 @@ impl fmt::Display for UniformType {
       UniformType::UICubemap => f.write_str("usamplerCube"),
       UniformType::Cubemap => f.write_str("samplerCube"),
       UniformType::BufferBinding => f.write_str("buffer binding"),
+      UniformType::StorageBufferBinding => f.write_str("storage buffer binding"),
     }
   }
 }
@@ -656,7 +675,7 @@ pub struct Stage<B>
 where
   B: ?Sized + Shader,
 {
-  repr: B::StageRepr,
+  pub(crate) repr: B::StageRepr,
 }
 
 impl<B> Stage<B>
@@ -702,9 +721,9 @@ pub struct UniformBuilder<'a, B>
 where
   B: ?Sized + Shader,
 {
-  repr: B::UniformBuilderRepr,
-  warnings: Vec<UniformWarning>,
-  _a: PhantomData<&'a mut ()>,
+  pub(crate) repr: B::UniformBuilderRepr,
+  pub(crate) warnings: Vec<UniformWarning>,
+  pub(crate) _a: PhantomData<&'a mut ()>,
 }
 
 impl<'a, B> UniformBuilder<'a, B>
@@ -4,7 +4,9 @@
 //!
 //! [`Program`]: crate::shader::Program
 
+use crate::backend::query::Query as QueryBackend;
 use crate::backend::shading_gate::ShadingGate as ShadingGateBackend;
+use crate::query::{Query, QueryError};
 use crate::render_gate::RenderGate;
 use crate::shader::{Program, ProgramInterface, UniformInterface};
 use crate::vertex::Semantics;
@@ -59,4 +61,25 @@ where
 
     f(program_interface, &program.uni, render_gate)
   }
+
+  /// Wrap a section of the shading in a [`Query`].
+  ///
+  /// The argument closure is given this gate back, to issue the section of the shading the query
+  /// gathers information about.
+  ///
+  /// # Errors
+  ///
+  /// If the query cannot start — for instance, because a query of the same type is already
+  /// active — the closure isn’t run and the [`QueryError`] is returned.
+  pub fn query<E, F>(&mut self, query: &mut Query<B>, f: F) -> Result<(), E>
+  where
+    B: QueryBackend,
+    F: FnOnce(&mut Self) -> Result<(), E>,
+    E: From<QueryError>,
+  {
+    unsafe { self.backend.begin_query(&mut query.repr)? };
+    let r = f(self);
+    unsafe { self.backend.end_query(&mut query.repr) };
+    r
+  }
 }
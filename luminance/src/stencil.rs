@@ -0,0 +1,108 @@
+//! Stencil test related features.
+//!
+//! The stencil test compares a reference value with the value stored in the stencil buffer for
+//! every fragment, and discards the fragments failing the comparison. Depending on the outcome of
+//! both the stencil and depth tests, the stored value is then updated with a [`StencilOp`]. That
+//! allows to mask areas of a framebuffer, which is the base of outlines, portals or decals.
+//!
+//! The stencil buffer is the stencil channel of a depth-stencil pixel format, such as
+//! [`Depth24Stencil8`], used as the depth slot of a framebuffer.
+//!
+//! [`Depth24Stencil8`]: crate::pixel::Depth24Stencil8
+
+/// Stencil comparison to perform while stencil test. `a` is the reference value and `b` is the
+/// value that is already stored, both masked with the [`StencilTest`] mask.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StencilComparison {
+  /// Stencil test never succeeds.
+  Never,
+  /// Stencil test always succeeds.
+  Always,
+  /// Stencil test succeeds if `a == b`.
+  Equal,
+  /// Stencil test succeeds if `a != b`.
+  NotEqual,
+  /// Stencil test succeeds if `a < b`.
+  Less,
+  /// Stencil test succeeds if `a <= b`.
+  LessOrEqual,
+  /// Stencil test succeeds if `a > b`.
+  Greater,
+  /// Stencil test succeeds if `a >= b`.
+  GreaterOrEqual,
+}
+
+/// Stencil test setup.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StencilTest {
+  /// Comparison to perform.
+  pub comparison: StencilComparison,
+  /// Reference value to compare with the stored value.
+  pub reference: u8,
+  /// Mask applied to both the reference and stored values before comparing them.
+  pub mask: u8,
+}
+
+impl StencilTest {
+  /// Create a new [`StencilTest`].
+  pub fn new(comparison: StencilComparison, reference: u8, mask: u8) -> Self {
+    StencilTest {
+      comparison,
+      reference,
+      mask,
+    }
+  }
+}
+
+/// Operation to perform on the stored stencil value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StencilOp {
+  /// Keep the stored value.
+  Keep,
+  /// Set the stored value to `0`.
+  Zero,
+  /// Replace the stored value with the reference value.
+  Replace,
+  /// Increment the stored value, clamping it to the maximum value.
+  Increment,
+  /// Increment the stored value, wrapping it to `0` on overflow.
+  IncrementWrap,
+  /// Decrement the stored value, clamping it to `0`.
+  Decrement,
+  /// Decrement the stored value, wrapping it to the maximum value on underflow.
+  DecrementWrap,
+  /// Bitwise invert the stored value.
+  Invert,
+}
+
+/// Operations to perform on the stored stencil value, depending on the outcome of the stencil and
+/// depth tests.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StencilOperations {
+  /// Operation to perform when the stencil test fails.
+  pub stencil_fails: StencilOp,
+  /// Operation to perform when the stencil test passes but the depth test fails.
+  pub depth_fails: StencilOp,
+  /// Operation to perform when both the stencil and depth tests pass.
+  pub depth_passes: StencilOp,
+}
+
+impl StencilOperations {
+  /// Create a new [`StencilOperations`].
+  pub fn new(stencil_fails: StencilOp, depth_fails: StencilOp, depth_passes: StencilOp) -> Self {
+    StencilOperations {
+      stencil_fails,
+      depth_fails,
+      depth_passes,
+    }
+  }
+}
+
+/// Default implementation of [`StencilOperations`].
+///
+/// All operations are [`StencilOp::Keep`].
+impl Default for StencilOperations {
+  fn default() -> Self {
+    StencilOperations::new(StencilOp::Keep, StencilOp::Keep, StencilOp::Keep)
+  }
+}
@@ -0,0 +1,298 @@
+//! Streaming buffers.
+//!
+//! Re-creating buffers or writing whole buffers every frame to upload small pieces of data —
+//! per-draw transforms, uniform block chunks, etc. — is slow. A [`StreamBuffer`] is a single, large
+//! buffer split into several _frames_. Each frame, data is sub-allocated from the current frame
+//! with [`StreamBuffer::alloc`], which gives a range of the buffer that can be bound with
+//! [`Pipeline::bind_buffer_range`]. Once the frame is done, [`StreamBuffer::next_frame`] moves to
+//! the next frame, so that the data of the previous frames can still be used by the GPU while the
+//! data of the new frame is being written.
+//!
+//! Reusing a frame is guarded by a [`Fence`]: a frame cannot be written to before the GPU is done
+//! with the commands issued before the frame was left. With enough frames — typically, the number
+//! of frames the GPU can lag behind, plus one — that never happens.
+//!
+//! ```ignore
+//! let mut stream = StreamBuffer::new(&mut context, 1024, 3)?;
+//!
+//! // every frame
+//! let ranges = transforms
+//!   .iter()
+//!   .map(|transform| stream.alloc(&[transform.std140_encode()]))
+//!   .collect::<Result<Vec<_>, _>>()?;
+//!
+//! context
+//!   .new_pipeline_gate()
+//!   .pipeline(&back_buffer, &PipelineState::default(), |pipeline, mut shd_gate| {
+//!     for (range, tess) in ranges.iter().zip(&tesses) {
+//!       let bound = pipeline.bind_buffer_range(stream.buffer(), range.clone())?;
+//!       // …
+//!     }
+//!
+//!     Ok(())
+//!   });
+//!
+//! stream.next_frame(&mut context)?;
+//! ```
+//!
+//! The buffer of a [`StreamBuffer`] is created with [`BufferUsage::Persistent`], so that writing
+//! to it is just a copy on backends supporting persistent mapping. Other backends only upload the
+//! allocated items.
+//!
+//! [`Pipeline::bind_buffer_range`]: crate::pipeline::Pipeline::bind_buffer_range
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::ops::Range;
+
+use crate::backend::buffer::Buffer as BufferBackend;
+use crate::backend::fence::Fence as FenceBackend;
+use crate::backend::pipeline::PipelineBuffer;
+use crate::buffer::{Buffer, BufferError, BufferUsage};
+use crate::context::GraphicsContext;
+use crate::fence::{Fence, FenceError};
+
+/// Errors that might occur when using [`StreamBuffer`]s.
+#[non_exhaustive]
+#[derive(Debug, Eq, PartialEq)]
+pub enum StreamError {
+  /// Not enough room is left in the current frame.
+  OutOfMemory {
+    /// Number of requested items.
+    requested: usize,
+    /// Number of items left in the current frame.
+    available: usize,
+  },
+  /// The GPU might still be using the data of the current frame.
+  FrameInFlight,
+  /// Error occurring with the underlying buffer.
+  BufferError(BufferError),
+  /// Error occurring with the fences guarding the frames.
+  FenceError(FenceError),
+}
+
+impl StreamError {
+  /// Not enough room is left in the current frame.
+  pub fn out_of_memory(requested: usize, available: usize) -> Self {
+    StreamError::OutOfMemory {
+      requested,
+      available,
+    }
+  }
+
+  /// The GPU might still be using the data of the current frame.
+  pub fn frame_in_flight() -> Self {
+    StreamError::FrameInFlight
+  }
+
+  /// Error occurring with the underlying buffer.
+  pub fn buffer_error(e: BufferError) -> Self {
+    StreamError::BufferError(e)
+  }
+
+  /// Error occurring with the fences guarding the frames.
+  pub fn fence_error(e: FenceError) -> Self {
+    StreamError::FenceError(e)
+  }
+}
+
+impl fmt::Display for StreamError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+    match *self {
+      StreamError::OutOfMemory {
+        requested,
+        available,
+      } => write!(
+        f,
+        "stream buffer out of memory: {} items requested, {} available",
+        requested, available
+      ),
+
+      StreamError::FrameInFlight => f.write_str("stream buffer frame still in flight"),
+
+      StreamError::BufferError(ref e) => write!(f, "stream buffer error: {}", e),
+
+      StreamError::FenceError(ref e) => write!(f, "stream buffer fence error: {}", e),
+    }
+  }
+}
+
+impl error::Error for StreamError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      StreamError::BufferError(e) => Some(e),
+      StreamError::FenceError(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<BufferError> for StreamError {
+  fn from(e: BufferError) -> Self {
+    StreamError::buffer_error(e)
+  }
+}
+
+impl From<FenceError> for StreamError {
+  fn from(e: FenceError) -> Self {
+    StreamError::fence_error(e)
+  }
+}
+
+/// A ring of frames to stream data from.
+///
+/// # Parametricity
+///
+/// - `B` is the backend type.
+/// - `T` is the type of the streamed items.
+pub struct StreamBuffer<B, T>
+where
+  B: ?Sized + BufferBackend<T> + FenceBackend,
+  T: Copy,
+{
+  buffer: Buffer<B, T>,
+  frame_len: usize,
+  alignment: usize,
+  fences: Vec<Option<Fence<B>>>,
+  frame: usize,
+  cursor: usize,
+}
+
+impl<B, T> StreamBuffer<B, T>
+where
+  B: ?Sized + BufferBackend<T> + FenceBackend,
+  T: Copy,
+{
+  /// Create a new [`StreamBuffer`] of `frames` frames, each able to hold at least `frame_len`
+  /// items. At least one frame is created.
+  ///
+  /// The length of the frames is rounded up so that every frame starts at an offset that can be
+  /// bound with [`Pipeline::bind_buffer_range`].
+  ///
+  /// [`Pipeline::bind_buffer_range`]: crate::pipeline::Pipeline::bind_buffer_range
+  pub fn new<C>(ctx: &mut C, frame_len: usize, frames: usize) -> Result<Self, StreamError>
+  where
+    C: GraphicsContext<Backend = B>,
+    B: PipelineBuffer<T>,
+    T: Default,
+  {
+    let alignment_bytes =
+      unsafe { <B as PipelineBuffer<T>>::buffer_offset_alignment(ctx.backend()) };
+    let alignment = item_alignment::<T>(alignment_bytes);
+    let frame_len = round_up(frame_len, alignment);
+    let frames = frames.max(1);
+    let buffer = Buffer::new(ctx, frame_len * frames, BufferUsage::Persistent)?;
+    let fences = (0..frames).map(|_| None).collect();
+
+    Ok(StreamBuffer {
+      buffer,
+      frame_len,
+      alignment,
+      fences,
+      frame: 0,
+      cursor: 0,
+    })
+  }
+
+  /// Copy `values` into the current frame.
+  ///
+  /// The returned range, in items, is aligned so that it can be bound with
+  /// [`Pipeline::bind_buffer_range`].
+  ///
+  /// # Errors
+  ///
+  /// [`StreamError::FrameInFlight`] is returned if the GPU might still be using the data of the
+  /// current frame — see [`StreamBuffer::is_ready`] — and [`StreamError::OutOfMemory`] if not
+  /// enough room is left in the current frame.
+  ///
+  /// [`Pipeline::bind_buffer_range`]: crate::pipeline::Pipeline::bind_buffer_range
+  pub fn alloc(&mut self, values: &[T]) -> Result<Range<usize>, StreamError> {
+    if !self.is_ready() {
+      return Err(StreamError::frame_in_flight());
+    }
+
+    let start = round_up(self.cursor, self.alignment).min(self.frame_len);
+    let available = self.frame_len - start;
+
+    if values.len() > available {
+      return Err(StreamError::out_of_memory(values.len(), available));
+    }
+
+    let start = self.frame * self.frame_len + start;
+    let range = start..start + values.len();
+    self.buffer.write_range(range.start, values)?;
+    self.cursor = range.end - self.frame * self.frame_len;
+
+    Ok(range)
+  }
+
+  /// Check whether the current frame can be written to.
+  ///
+  /// A frame can be written to once the GPU is done with the commands issued before the frame was
+  /// last left with [`StreamBuffer::next_frame`]. This function doesn’t block.
+  pub fn is_ready(&mut self) -> bool {
+    let fence = &mut self.fences[self.frame];
+
+    if let Some(ref mut f) = fence {
+      if !f.is_signaled() {
+        return false;
+      }
+    }
+
+    *fence = None;
+    true
+  }
+
+  /// Leave the current frame and move to the next one.
+  ///
+  /// Call this function once all the commands using the data of the current frame are issued,
+  /// typically at the end of a frame.
+  pub fn next_frame<C>(&mut self, ctx: &mut C) -> Result<(), StreamError>
+  where
+    C: GraphicsContext<Backend = B>,
+  {
+    self.fences[self.frame] = Some(Fence::new(ctx)?);
+    self.frame = (self.frame + 1) % self.fences.len();
+    self.cursor = 0;
+
+    Ok(())
+  }
+
+  /// Underlying buffer, to bind the allocated ranges with.
+  pub fn buffer(&self) -> &Buffer<B, T> {
+    &self.buffer
+  }
+
+  /// Number of items each frame can hold.
+  pub fn frame_len(&self) -> usize {
+    self.frame_len
+  }
+
+  /// Number of frames.
+  pub fn frames(&self) -> usize {
+    self.fences.len()
+  }
+}
+
+/// Number of items between two offsets aligned on `alignment` bytes.
+fn item_alignment<T>(alignment: usize) -> usize {
+  let item_bytes = mem::size_of::<T>().max(1);
+  let alignment = alignment.max(1);
+
+  alignment / gcd(alignment, item_bytes)
+}
+
+/// Greatest common divisor of `a` and `b`.
+fn gcd(a: usize, b: usize) -> usize {
+  if b == 0 {
+    a
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+/// Round `x` up to a multiple of `m`.
+fn round_up(x: usize, m: usize) -> usize {
+  x.div_ceil(m) * m
+}
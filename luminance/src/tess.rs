@@ -17,6 +17,8 @@
 //! - For indexed configuration, an optional _primitive restart index_ can be specified. That
 //!   index, when present in the indexed set, will make some primitive modes _“restart”_ and create
 //!   new primitives. More on this on the documentation of [`Mode`].
+//! - The [`BufferUsage`] of the GPU buffers holding its data, which defaults to
+//!   [`BufferUsage::Static`].
 //!
 //! # Tessellation creation
 //!
@@ -59,7 +61,9 @@
 //! - [`Tess::instances`] [`Tess::instances_mut`] to map tessellations’ instances.
 //!
 //! > Note: because of their slice nature, mapping a tessellation (vertices, indices or instances)
-//! > will not help you with resizing a [`Tess`], as this is not currently supported.
+//! > will not help you with resizing a [`Tess`]. Use [`Tess::resize_vertices`],
+//! > [`Tess::resize_indices`] and [`Tess::resize_instances`] instead, which keep the vertex layout
+//! > of the [`Tess`] intact.
 //!
 //! [`TessGate`]: crate::tess_gate::TessGate
 
@@ -68,7 +72,7 @@ use crate::{
     IndexSlice as IndexSliceBackend, InstanceSlice as InstanceSliceBackend, Tess as TessBackend,
     VertexSlice as VertexSliceBackend,
   },
-  buffer::BufferError,
+  buffer::{BufferError, BufferUsage, PreserveContent},
   context::GraphicsContext,
   vertex::{Deinterleave, Vertex, VertexDesc},
 };
@@ -263,6 +267,8 @@ pub enum TessError {
   ForbiddenPrimitiveMode(Mode),
   /// No data provided and empty tessellation.
   NoData,
+  /// The tessellation cannot hold indices.
+  NotIndexed,
 }
 
 impl TessError {
@@ -295,6 +301,11 @@ impl TessError {
   pub fn no_data() -> Self {
     TessError::NoData
   }
+
+  /// The tessellation cannot hold indices.
+  pub fn not_indexed() -> Self {
+    TessError::NotIndexed
+  }
 }
 
 impl fmt::Display for TessError {
@@ -308,6 +319,7 @@ impl fmt::Display for TessError {
       TessError::InternalBufferError(ref e) => write!(f, "internal buffer error: {}", e),
       TessError::ForbiddenPrimitiveMode(ref e) => write!(f, "forbidden primitive mode: {}", e),
       TessError::NoData => f.write_str("no data or empty tessellation"),
+      TessError::NotIndexed => f.write_str("tessellation cannot hold indices"),
     }
   }
 }
@@ -461,6 +473,23 @@ where
   }
 }
 
+/// Parameters of a [`Tess`] to build, besides its vertex, index and instance data.
+///
+/// They are given to backends when building tessellations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TessParams<I> {
+  /// [`Mode`] connecting the vertices.
+  pub mode: Mode,
+  /// Default number of vertices to render.
+  pub vert_nb: usize,
+  /// Default number of instances to render.
+  pub inst_nb: usize,
+  /// Primitive restart index, if any.
+  pub restart_index: Option<I>,
+  /// Usage of the buffers of the tessellation.
+  pub usage: BufferUsage,
+}
+
 /// Deinterleaved data.
 #[derive(Debug, Clone)]
 pub struct DeinterleavedData {
@@ -538,6 +567,7 @@ where
   vert_nb: usize,
   inst_nb: usize,
   restart_index: Option<I>,
+  usage: BufferUsage,
   _phantom: PhantomData<&'a mut ()>,
 }
 
@@ -580,6 +610,17 @@ where
     self.restart_index = Some(restart_index);
     self
   }
+
+  /// Set the [`BufferUsage`] of the buffers holding the vertices, indices and instances.
+  ///
+  /// Tessellations use [`BufferUsage::Static`] by default; use [`BufferUsage::Dynamic`] or
+  /// [`BufferUsage::Stream`] if you plan to map and update them often.
+  ///
+  /// Calling that function twice replaces the previously set value.
+  pub fn set_usage(mut self, usage: BufferUsage) -> Self {
+    self.usage = usage;
+    self
+  }
 }
 
 impl<'a, B, V, I, W, S> TessBuilder<'a, B, V, I, W, S>
@@ -610,6 +651,7 @@ where
       vert_nb: 0,
       inst_nb: 0,
       restart_index: None,
+      usage: BufferUsage::Static,
       _phantom: PhantomData,
     }
   }
@@ -640,6 +682,7 @@ where
       vert_nb: self.vert_nb,
       inst_nb: self.inst_nb,
       restart_index: None,
+      usage: self.usage,
       _phantom: PhantomData,
     }
   }
@@ -669,6 +712,7 @@ where
       vert_nb: self.vert_nb,
       inst_nb: self.inst_nb,
       restart_index: self.restart_index,
+      usage: self.usage,
       _phantom: PhantomData,
     }
   }
@@ -697,6 +741,7 @@ where
       vert_nb: self.vert_nb,
       inst_nb: self.inst_nb,
       restart_index: self.restart_index,
+      usage: self.usage,
       _phantom: PhantomData,
     }
   }
@@ -811,10 +856,13 @@ where
           self.vertex_data,
           self.index_data,
           self.instance_data,
-          self.mode,
-          vert_nb,
-          inst_nb,
-          self.restart_index,
+          TessParams {
+            mode: self.mode,
+            vert_nb,
+            inst_nb,
+            restart_index: self.restart_index,
+            usage: self.usage,
+          },
         )
         .map(|repr| Tess {
           repr,
@@ -930,6 +978,54 @@ where
     unsafe { B::tess_instances_nb(&self.repr) }
   }
 
+  /// Resize the vertex storage so that it holds `len` vertices.
+  ///
+  /// With [`PreserveContent::Yes`], the vertices that still fit are kept; other vertices are
+  /// zero-initialized. The vertex layout is kept, so the [`Tess`] doesn’t have to be rebuilt. If the
+  /// [`Tess`] was built without vertex storage, it gets some, unless it is attributeless.
+  ///
+  /// If the [`Tess`] is not indexed, the number of vertices to render is set to `len`.
+  pub fn resize_vertices(
+    &mut self,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    unsafe { B::resize_vertices(&mut self.repr, len, preserve) }
+  }
+
+  /// Resize the index storage so that it holds `len` indices.
+  ///
+  /// With [`PreserveContent::Yes`], the indices that still fit are kept; other indices are set to
+  /// `0`. If the [`Tess`] was built without indices, it gets an index storage and becomes indexed.
+  ///
+  /// The number of vertices to render is set to `len`.
+  ///
+  /// # Errors
+  ///
+  /// [`TessError::NotIndexed`] is returned if the index type is `()`.
+  pub fn resize_indices(&mut self, len: usize, preserve: PreserveContent) -> Result<(), TessError> {
+    if I::INDEX_TYPE.is_none() {
+      return Err(TessError::not_indexed());
+    }
+
+    unsafe { B::resize_indices(&mut self.repr, len, preserve) }
+  }
+
+  /// Resize the instance storage so that it holds `len` instances.
+  ///
+  /// With [`PreserveContent::Yes`], the instances that still fit are kept; other instances are
+  /// zero-initialized. If the [`Tess`] was built without instance storage, it gets some, unless the
+  /// instances are attributeless.
+  ///
+  /// The number of instances to render is set to `len`.
+  pub fn resize_instances(
+    &mut self,
+    len: usize,
+    preserve: PreserveContent,
+  ) -> Result<(), TessError> {
+    unsafe { B::resize_instances(&mut self.repr, len, preserve) }
+  }
+
   /// Slice the [`Tess`] in order to read its content via usual slices.
   ///
   /// This method gives access to the underlying _index storage_.
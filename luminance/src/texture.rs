@@ -32,11 +32,16 @@
 use std::error;
 use std::fmt;
 use std::marker::PhantomData;
+use std::mem;
 
-use crate::backend::texture::Texture as TextureBackend;
+use crate::backend::texture::{
+  Texture as TextureBackend, TextureCompressed as TextureCompressedBackend,
+  TextureReadback as TextureReadbackBackend,
+};
 use crate::context::GraphicsContext;
 use crate::depth_test::DepthComparison;
-use crate::pixel::{Pixel, PixelFormat};
+use crate::pixel::{CompressedPixel, Pixel, PixelFormat};
+use crate::readback::Readback;
 
 /// How to wrap texture coordinates while sampling textures?
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -53,6 +58,10 @@ pub enum Wrap {
   Repeat,
   /// Same as `Repeat` but it will alternatively repeat between *[0;1]* and *[1;0]*.
   MirroredRepeat,
+  /// Textures coordinates outside of *[0;1]* sample the border color of the [`Sampler`].
+  ///
+  /// Not all backends support it; the ones that don’t fail to create textures using it.
+  ClampToBorder,
 }
 
 /// Minification filter.
@@ -112,6 +121,12 @@ pub trait Dimensionable {
     1
   }
 
+  /// Build a [`Dimensionable::Size`] out of a width, a height and a depth.
+  ///
+  /// That is the converse of [`Dimensionable::width`], [`Dimensionable::height`] and
+  /// [`Dimensionable::depth`]: the components the size doesn’t have are ignored.
+  fn new_size(width: u32, height: u32, depth: u32) -> Self::Size;
+
   /// X offset.
   fn x_offset(offset: Self::Offset) -> u32;
 
@@ -125,6 +140,14 @@ pub trait Dimensionable {
     1
   }
 
+  /// Build a [`Dimensionable::Offset`] out of a X, a Y and a Z offsets.
+  ///
+  /// That is the converse of [`Dimensionable::x_offset`], [`Dimensionable::y_offset`] and
+  /// [`Dimensionable::z_offset`]: the components the offset doesn’t have are ignored. For cubemaps,
+  /// `z` is the index of the face in the order of [`CubeFace`]; past the last face, the last face
+  /// is selected.
+  fn new_offset(x: u32, y: u32, z: u32) -> Self::Offset;
+
   /// Amount of pixels this size represents.
   ///
   /// For 2D sizes, it represents the area; for 3D sizes, the volume; etc.
@@ -180,10 +203,18 @@ impl Dimensionable for Dim1 {
     w
   }
 
+  fn new_size(width: u32, _: u32, _: u32) -> Self::Size {
+    width
+  }
+
   fn x_offset(off: Self::Offset) -> u32 {
     off
   }
 
+  fn new_offset(x: u32, _: u32, _: u32) -> Self::Offset {
+    x
+  }
+
   fn count(size: Self::Size) -> usize {
     size as usize
   }
@@ -211,6 +242,10 @@ impl Dimensionable for Dim2 {
     size[1]
   }
 
+  fn new_size(width: u32, height: u32, _: u32) -> Self::Size {
+    [width, height]
+  }
+
   fn x_offset(off: Self::Offset) -> u32 {
     off[0]
   }
@@ -219,6 +254,10 @@ impl Dimensionable for Dim2 {
     off[1]
   }
 
+  fn new_offset(x: u32, y: u32, _: u32) -> Self::Offset {
+    [x, y]
+  }
+
   fn count([width, height]: Self::Size) -> usize {
     width as usize * height as usize
   }
@@ -250,6 +289,10 @@ impl Dimensionable for Dim3 {
     size[2]
   }
 
+  fn new_size(width: u32, height: u32, depth: u32) -> Self::Size {
+    [width, height, depth]
+  }
+
   fn x_offset(off: Self::Offset) -> u32 {
     off[0]
   }
@@ -262,6 +305,10 @@ impl Dimensionable for Dim3 {
     off[2]
   }
 
+  fn new_offset(x: u32, y: u32, z: u32) -> Self::Offset {
+    [x, y, z]
+  }
+
   fn count([width, height, depth]: Self::Size) -> usize {
     width as usize * height as usize * depth as usize
   }
@@ -293,6 +340,10 @@ impl Dimensionable for Cubemap {
     6
   }
 
+  fn new_size(width: u32, _: u32, _: u32) -> Self::Size {
+    width
+  }
+
   fn x_offset(off: Self::Offset) -> u32 {
     off.0[0]
   }
@@ -312,6 +363,19 @@ impl Dimensionable for Cubemap {
     }
   }
 
+  fn new_offset(x: u32, y: u32, z: u32) -> Self::Offset {
+    let face = match z {
+      0 => CubeFace::PositiveX,
+      1 => CubeFace::NegativeX,
+      2 => CubeFace::PositiveY,
+      3 => CubeFace::NegativeY,
+      4 => CubeFace::PositiveZ,
+      _ => CubeFace::NegativeZ,
+    };
+
+    ([x, y], face)
+  }
+
   fn count(size: Self::Size) -> usize {
     let size = size as usize;
     size * size
@@ -357,6 +421,10 @@ impl Dimensionable for Dim1Array {
     size.1
   }
 
+  fn new_size(width: u32, layers: u32, _: u32) -> Self::Size {
+    (width, layers)
+  }
+
   fn x_offset(off: Self::Offset) -> u32 {
     off.0
   }
@@ -365,6 +433,10 @@ impl Dimensionable for Dim1Array {
     off.1
   }
 
+  fn new_offset(x: u32, layer: u32, _: u32) -> Self::Offset {
+    (x, layer)
+  }
+
   fn count((width, layer): Self::Size) -> usize {
     width as usize * layer as usize
   }
@@ -396,6 +468,10 @@ impl Dimensionable for Dim2Array {
     size.1
   }
 
+  fn new_size(width: u32, height: u32, layers: u32) -> Self::Size {
+    ([width, height], layers)
+  }
+
   fn x_offset(off: Self::Offset) -> u32 {
     off.0[0]
   }
@@ -408,6 +484,10 @@ impl Dimensionable for Dim2Array {
     off.1
   }
 
+  fn new_offset(x: u32, y: u32, layer: u32) -> Self::Offset {
+    ([x, y], layer)
+  }
+
   fn count(([width, height], layer): Self::Size) -> usize {
     width as usize * height as usize * layer as usize
   }
@@ -427,7 +507,38 @@ pub struct Sampler {
   /// Magnification filter.
   pub mag_filter: MagFilter,
   /// For depth textures, should we perform depth comparison and if so, how?
+  ///
+  /// `None` samples the depth values themselves instead of the results of the comparison.
   pub depth_comparison: Option<DepthComparison>,
+  /// Lowest mipmap level that can be sampled.
+  ///
+  /// It is also the level mipmaps are generated from.
+  pub base_level: usize,
+  /// Highest mipmap level that can be sampled.
+  ///
+  /// `None` means the last allocated level. Levels above the last allocated level are clamped to
+  /// it.
+  pub max_level: Option<usize>,
+  /// Lowest level of detail that can be selected.
+  pub min_lod: f32,
+  /// Highest level of detail that can be selected.
+  pub max_lod: f32,
+  /// Bias added to the level of detail before it’s clamped to the *[min_lod; max_lod]* range.
+  ///
+  /// Not all backends support it; the ones that don’t fail to create textures with a non-zero
+  /// bias.
+  pub lod_bias: f32,
+  /// Color sampled outside of the texture with [`Wrap::ClampToBorder`].
+  pub border_color: [f32; 4],
+  /// Maximum degree of anisotropy used when filtering.
+  ///
+  /// `1.` disables anisotropic filtering. It is clamped to the maximum degree supported by the
+  /// backend, and ignored by backends not supporting anisotropic filtering.
+  pub max_anisotropy: f32,
+  /// Whether cubemaps are filtered across their faces.
+  ///
+  /// Some backends always filter across faces, whatever this field.
+  pub seamless_cubemap: bool,
 }
 
 /// Default value is as following:
@@ -440,6 +551,14 @@ impl Default for Sampler {
       min_filter: MinFilter::NearestMipmapLinear,
       mag_filter: MagFilter::Linear,
       depth_comparison: None,
+      base_level: 0,
+      max_level: None,
+      min_lod: -1000.,
+      max_lod: 1000.,
+      lod_bias: 0.,
+      border_color: [0., 0., 0., 0.],
+      max_anisotropy: 1.,
+      seamless_cubemap: false,
     }
   }
 }
@@ -485,6 +604,10 @@ pub enum TextureError {
   CannotRetrieveTexels(String),
   /// Failed to upload texels.
   CannotUploadTexels(String),
+  /// Failed to copy texels from a texture to another.
+  CannotCopyTexels(String),
+  /// Failed to generate the mipmaps of a texture.
+  CannotGenerateMipmaps(String),
 }
 
 impl TextureError {
@@ -515,6 +638,16 @@ impl TextureError {
   pub fn cannot_upload_texels(reason: impl Into<String>) -> Self {
     TextureError::CannotUploadTexels(reason.into())
   }
+
+  /// Failed to copy texels from a texture to another.
+  pub fn cannot_copy_texels(reason: impl Into<String>) -> Self {
+    TextureError::CannotCopyTexels(reason.into())
+  }
+
+  /// Failed to generate the mipmaps of a texture.
+  pub fn cannot_generate_mipmaps(reason: impl Into<String>) -> Self {
+    TextureError::CannotGenerateMipmaps(reason.into())
+  }
 }
 
 impl fmt::Display for TextureError {
@@ -544,6 +677,10 @@ impl fmt::Display for TextureError {
       TextureError::CannotUploadTexels(ref e) => {
         write!(f, "cannot upload texels to texture: {}", e)
       }
+
+      TextureError::CannotCopyTexels(ref e) => write!(f, "cannot copy texels: {}", e),
+
+      TextureError::CannotGenerateMipmaps(ref e) => write!(f, "cannot generate mipmaps: {}", e),
     }
   }
 }
@@ -578,6 +715,15 @@ where
   /// `sampler` is a [`Sampler`] object that will be used when sampling the texture from inside a
   /// shader, for instance.
   ///
+  /// # Errors
+  ///
+  /// Compressed pixel formats — see [`CompressedPixel`] — are only supported with [`Dim2`],
+  /// [`Cubemap`] and [`Dim2Array`] textures; [`TextureError::UnsupportedPixelFormat`] is returned
+  /// otherwise, or if the backend doesn’t support their compression.
+  ///
+  /// [`TextureError::TextureStorageCreationFailed`] is returned if the base level of `sampler` is
+  /// above its max level or the last level of the texture.
+  ///
   /// # Notes
   ///
   /// Feel free to have a look at the documentation of [`GraphicsContext::new_texture`] for a
@@ -591,6 +737,24 @@ where
   where
     C: GraphicsContext<Backend = B>,
   {
+    let pf = P::pixel_format();
+
+    if pf.format.compression().is_some()
+      && !matches!(D::dim(), Dim::Dim2 | Dim::Cubemap | Dim::Dim2Array)
+    {
+      return Err(TextureError::unsupported_pixel_format(pf));
+    }
+
+    let max_level = sampler
+      .max_level
+      .map_or(mipmaps, |level| level.min(mipmaps));
+    if sampler.base_level > max_level {
+      return Err(TextureError::texture_storage_creation_failed(format!(
+        "base level {} is above max level {}",
+        sampler.base_level, max_level
+      )));
+    }
+
     unsafe {
       ctx
         .backend()
@@ -677,10 +841,457 @@ where
   }
 
   /// Get a copy of all the pixels from the texture.
+  ///
+  /// Texels of compressed pixel formats cannot be retrieved.
   pub fn get_raw_texels(&self) -> Result<Vec<P::RawEncoding>, TextureError>
   where
     P::RawEncoding: Copy + Default,
   {
+    reject_compressed_readback::<P>()?;
+
     unsafe { B::get_raw_texels(&self.repr, self.size) }
   }
+
+  /// Upload pixels to a region of the mipmap `level` of the texture described by the rectangle
+  /// made with `size` and `offset`.
+  ///
+  /// `level` `0` is the base level. The other levels are left untouched, so that hand-made or
+  /// pre-filtered mipmaps can be uploaded level after level. For cubemaps, the region only covers
+  /// the face it is offset to.
+  ///
+  /// # Errors
+  ///
+  /// [`TextureError::CannotUploadTexels`] is returned if `level` doesn’t exist or if the region
+  /// doesn’t fit in the level.
+  pub fn upload_level_part(
+    &mut self,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::Encoding],
+  ) -> Result<(), TextureError> {
+    self
+      .check_level_region(level, offset, size)
+      .map_err(TextureError::cannot_upload_texels)?;
+
+    unsafe { B::upload_level_part(&mut self.repr, level, offset, size, texels) }
+  }
+
+  /// Upload pixels to the whole mipmap `level` of the texture.
+  ///
+  /// For cubemaps, `texels` holds the texels of the six faces, face after face, in the order of
+  /// [`CubeFace`]; [`TextureError::NotEnoughPixels`] is returned if they cannot be split evenly
+  /// into faces.
+  ///
+  /// See [`Texture::upload_level_part`] for further details.
+  pub fn upload_level(&mut self, level: usize, texels: &[P::Encoding]) -> Result<(), TextureError> {
+    self.upload_whole_level(level, texels, |texture, offset, size, texels| {
+      texture.upload_level_part(level, offset, size, texels)
+    })
+  }
+
+  /// Upload raw data to a region of the mipmap `level` of the texture described by the rectangle
+  /// made with `size` and `offset`.
+  ///
+  /// See [`Texture::upload_level_part`] for further details.
+  pub fn upload_level_part_raw(
+    &mut self,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    self
+      .check_level_region(level, offset, size)
+      .map_err(TextureError::cannot_upload_texels)?;
+
+    unsafe { B::upload_level_part_raw(&mut self.repr, level, offset, size, texels) }
+  }
+
+  /// Upload raw data to the whole mipmap `level` of the texture.
+  ///
+  /// See [`Texture::upload_level`] for further details.
+  pub fn upload_level_raw(
+    &mut self,
+    level: usize,
+    texels: &[P::RawEncoding],
+  ) -> Result<(), TextureError> {
+    self.upload_whole_level(level, texels, |texture, offset, size, texels| {
+      texture.upload_level_part_raw(level, offset, size, texels)
+    })
+  }
+
+  /// Get a copy of the pixels of a region of the mipmap `level` of the texture described by the
+  /// rectangle made with `size` and `offset`.
+  ///
+  /// `level` `0` is the base level. For cubemaps, the region only covers the face it is offset to.
+  /// Texels of compressed pixel formats cannot be retrieved.
+  ///
+  /// # Errors
+  ///
+  /// [`TextureError::CannotRetrieveTexels`] is returned if `level` doesn’t exist or if the region
+  /// doesn’t fit in the level.
+  pub fn get_level_part_raw_texels(
+    &self,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    reject_compressed_readback::<P>()?;
+
+    self
+      .check_level_region(level, offset, size)
+      .map_err(TextureError::cannot_retrieve_texels)?;
+
+    unsafe { B::get_level_part_raw_texels(&self.repr, level, offset, size) }
+  }
+
+  /// Get a copy of all the pixels of the mipmap `level` of the texture.
+  ///
+  /// For cubemaps, the texels of the six faces are returned face after face, in the order of
+  /// [`CubeFace`].
+  ///
+  /// See [`Texture::get_level_part_raw_texels`] for further details.
+  pub fn get_level_raw_texels(&self, level: usize) -> Result<Vec<P::RawEncoding>, TextureError>
+  where
+    P::RawEncoding: Copy + Default,
+  {
+    let [w, h, d] = mipmap_extent::<D>(self.size, level);
+
+    match D::dim() {
+      Dim::Cubemap => {
+        let size = D::new_size(w, h, 1);
+        let mut texels = Vec::new();
+
+        for face in 0..6 {
+          texels.extend(self.get_level_part_raw_texels(level, D::new_offset(0, 0, face), size)?);
+        }
+
+        Ok(texels)
+      }
+
+      _ => self.get_level_part_raw_texels(level, D::ZERO_OFFSET, D::new_size(w, h, d)),
+    }
+  }
+
+  /// Copy the texels of a region of `src` to a region of the texture.
+  ///
+  /// The region of size `size` at `src_offset` in `src` is copied to `dst_offset` in the texture.
+  /// Only the base levels are copied; the mipmaps of the texture are regenerated if `gen_mipmaps`
+  /// is [`GenMipmaps::Yes`]. For cubemaps, regions only cover the face they are offset to.
+  ///
+  /// # Errors
+  ///
+  /// [`TextureError::CannotCopyTexels`] is returned if a region doesn’t fit in its texture.
+  pub fn copy_from(
+    &mut self,
+    gen_mipmaps: GenMipmaps,
+    src: &Self,
+    src_offset: D::Offset,
+    dst_offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), TextureError> {
+    if !region_fits::<D>(src_offset, size, mipmap_extent::<D>(src.size, 0)) {
+      return Err(TextureError::cannot_copy_texels(
+        "source region is out of bounds",
+      ));
+    }
+
+    if !region_fits::<D>(dst_offset, size, mipmap_extent::<D>(self.size, 0)) {
+      return Err(TextureError::cannot_copy_texels(
+        "destination region is out of bounds",
+      ));
+    }
+
+    unsafe {
+      B::copy_from(
+        &mut self.repr,
+        gen_mipmaps,
+        &src.repr,
+        src_offset,
+        dst_offset,
+        size,
+      )
+    }
+  }
+
+  /// Regenerate the mipmaps of the texture.
+  ///
+  /// Mipmaps are generated out of the base level of the [`Sampler`] of the texture, up to its max
+  /// level. That is typically used after having rendered to the texture via a [`Framebuffer`].
+  ///
+  /// # Errors
+  ///
+  /// [`TextureError::CannotGenerateMipmaps`] is returned for compressed pixel formats.
+  ///
+  /// [`Framebuffer`]: crate::framebuffer::Framebuffer
+  pub fn generate_mipmaps(&mut self) -> Result<(), TextureError> {
+    if P::pixel_format().format.compression().is_some() {
+      return Err(TextureError::cannot_generate_mipmaps(
+        "mipmaps of compressed pixel formats cannot be generated",
+      ));
+    }
+
+    unsafe { B::generate_mipmaps(&mut self.repr) }
+  }
+
+  /// Check that the mipmap `level` exists and that the region of size `size` at `offset` fits in
+  /// it, returning the reason why otherwise.
+  fn check_level_region(
+    &self,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+  ) -> Result<(), String> {
+    if level >= self.mipmaps() {
+      return Err(format!("mipmap level {} doesn’t exist", level));
+    }
+
+    if !region_fits::<D>(offset, size, mipmap_extent::<D>(self.size, level)) {
+      return Err("region is out of bounds".to_owned());
+    }
+
+    Ok(())
+  }
+
+  /// Upload `texels` to the whole mipmap `level` with `upload_part`, face after face for cubemaps.
+  fn upload_whole_level<T>(
+    &mut self,
+    level: usize,
+    texels: &[T],
+    mut upload_part: impl FnMut(&mut Self, D::Offset, D::Size, &[T]) -> Result<(), TextureError>,
+  ) -> Result<(), TextureError> {
+    let [w, h, d] = mipmap_extent::<D>(self.size, level);
+
+    match D::dim() {
+      Dim::Cubemap => {
+        let size = D::new_size(w, h, 1);
+
+        let face_len = texels.len() / 6;
+
+        // every face must get the same amount of texels
+        if face_len * 6 != texels.len() {
+          return Err(TextureError::not_enough_pixels(
+            (face_len + 1) * 6 * mem::size_of::<T>(),
+            mem::size_of_val(texels),
+          ));
+        }
+
+        for face in 0..6 {
+          let face_texels = &texels[face * face_len..(face + 1) * face_len];
+          upload_part(self, D::new_offset(0, 0, face as u32), size, face_texels)?;
+        }
+
+        Ok(())
+      }
+
+      _ => upload_part(self, D::ZERO_OFFSET, D::new_size(w, h, d), texels),
+    }
+  }
+}
+
+/// Width, height and depth of the mipmap `level` of a texture of size `size`.
+///
+/// Only the depth of [`Dim3`] textures is halved along levels; layers and cubemap faces are kept.
+fn mipmap_extent<D>(size: D::Size, level: usize) -> [u32; 3]
+where
+  D: Dimensionable,
+{
+  let halve = |x: u32| x.checked_shr(level as u32).unwrap_or(0).max(1);
+  let [w, h, d] = [D::width(size), D::height(size), D::depth(size)];
+
+  // the height of 1D array textures is their number of layers
+  match D::dim() {
+    Dim::Dim1 | Dim::Dim1Array => [halve(w), h, d],
+    Dim::Dim3 => [halve(w), halve(h), halve(d)],
+    _ => [halve(w), halve(h), d],
+  }
+}
+
+/// Reject reading back texels of compressed pixel formats.
+fn reject_compressed_readback<P>() -> Result<(), TextureError>
+where
+  P: Pixel,
+{
+  if P::pixel_format().format.compression().is_some() {
+    Err(TextureError::cannot_retrieve_texels(
+      "texels of compressed pixel formats cannot be retrieved",
+    ))
+  } else {
+    Ok(())
+  }
+}
+
+/// Whether the region of size `size` at `offset` fits in a level of extent `[w, h, depth]` — see
+/// [`mipmap_extent`].
+///
+/// Cubemap regions only cover the face they are offset to.
+fn region_fits<D>(offset: D::Offset, size: D::Size, [w, h, depth]: [u32; 3]) -> bool
+where
+  D: Dimensionable,
+{
+  let fits_x = span_fits(D::x_offset(offset), D::width(size), w);
+  let fits_y = || span_fits(D::y_offset(offset), D::height(size), h);
+
+  match D::dim() {
+    Dim::Dim1 => fits_x,
+    Dim::Dim2 | Dim::Dim1Array => fits_x && fits_y(),
+    Dim::Cubemap => fits_x && fits_y() && D::z_offset(offset) < depth,
+    Dim::Dim3 | Dim::Dim2Array => {
+      fits_x && fits_y() && span_fits(D::z_offset(offset), D::depth(size), depth)
+    }
+  }
+}
+
+/// Whether `len` texels starting at `offset` fit in `extent` texels.
+///
+/// Offsets large enough to overflow never fit.
+fn span_fits(offset: u32, len: u32, extent: u32) -> bool {
+  matches!(offset.checked_add(len), Some(end) if end <= extent)
+}
+
+impl<B, D, P> Texture<B, D, P>
+where
+  B: ?Sized + TextureCompressedBackend<D, P>,
+  D: Dimensionable,
+  P: CompressedPixel,
+{
+  /// Upload pre-compressed blocks to a region of the mipmap `level` of the texture described by
+  /// the rectangle made with `size` and `offset`.
+  ///
+  /// `level` `0` is the base level. `blocks` holds the compressed blocks of the region, row by row,
+  /// then layer by layer for [`Dim2Array`] textures. For cubemaps, the region only covers the face
+  /// it is offset to.
+  ///
+  /// # Errors
+  ///
+  /// [`TextureError::CannotUploadTexels`] is returned if `level` doesn’t exist, if the region
+  /// doesn’t fit in the level or if it is not aligned on blocks — its offset must be a multiple of
+  /// the block size, and so must its size, unless it reaches the edge of the level.
+  /// [`TextureError::NotEnoughPixels`] is returned if `blocks` doesn’t hold exactly the blocks of
+  /// the region.
+  pub fn upload_compressed_part(
+    &mut self,
+    level: usize,
+    offset: D::Offset,
+    size: D::Size,
+    blocks: &[u8],
+  ) -> Result<(), TextureError> {
+    let compression = match P::pixel_format().format.compression() {
+      Some(compression) => compression,
+      None => return Err(TextureError::unsupported_pixel_format(P::pixel_format())),
+    };
+
+    if level >= self.mipmaps() {
+      return Err(TextureError::cannot_upload_texels(format!(
+        "mipmap level {} doesn’t exist",
+        level
+      )));
+    }
+
+    let level_size = mipmap_extent::<D>(self.size, level);
+
+    let [x, y] = [D::x_offset(offset), D::y_offset(offset)];
+    let [w, h] = [D::width(size), D::height(size)];
+    let fits = span_fits(x, w, level_size[0])
+      && span_fits(y, h, level_size[1])
+      && match D::dim() {
+        Dim::Cubemap => D::z_offset(offset) < 6,
+        Dim::Dim2Array => span_fits(D::z_offset(offset), D::depth(size), D::depth(self.size)),
+        _ => true,
+      };
+
+    if !fits {
+      return Err(TextureError::cannot_upload_texels(
+        "region is out of bounds",
+      ));
+    }
+
+    let [bw, bh] = compression.block_size();
+    let aligned = x % bw == 0
+      && y % bh == 0
+      && (w % bw == 0 || x + w == level_size[0])
+      && (h % bh == 0 || y + h == level_size[1]);
+
+    if !aligned {
+      return Err(TextureError::cannot_upload_texels(
+        "region is not aligned on blocks",
+      ));
+    }
+
+    let layers = match D::dim() {
+      Dim::Dim2Array => D::depth(size) as usize,
+      _ => 1,
+    };
+    let expected_bytes = compression
+      .image_bytes(w, h)
+      .and_then(|len| len.checked_mul(layers))
+      .ok_or_else(|| TextureError::cannot_upload_texels("region is too large"))?;
+
+    if blocks.len() != expected_bytes {
+      return Err(TextureError::not_enough_pixels(
+        expected_bytes,
+        blocks.len(),
+      ));
+    }
+
+    unsafe { B::upload_compressed_part(&mut self.repr, level, offset, size, blocks) }
+  }
+
+  /// Upload pre-compressed blocks to the whole mipmap `level` of the texture.
+  ///
+  /// For cubemaps, `blocks` holds the blocks of the six faces, face after face, in the order of
+  /// [`CubeFace`].
+  ///
+  /// See [`Texture::upload_compressed_part`] for further details.
+  pub fn upload_compressed(&mut self, level: usize, blocks: &[u8]) -> Result<(), TextureError> {
+    let compression = match P::pixel_format().format.compression() {
+      Some(compression) => compression,
+      None => return Err(TextureError::unsupported_pixel_format(P::pixel_format())),
+    };
+
+    if level >= self.mipmaps() {
+      return Err(TextureError::cannot_upload_texels(format!(
+        "mipmap level {} doesn’t exist",
+        level
+      )));
+    }
+
+    let [w, h, depth] = mipmap_extent::<D>(self.size, level);
+    let expected_bytes = compression
+      .image_bytes(w, h)
+      .and_then(|len| len.checked_mul(depth as usize))
+      .ok_or_else(|| TextureError::cannot_upload_texels("level is too large"))?;
+
+    if blocks.len() != expected_bytes {
+      return Err(TextureError::not_enough_pixels(
+        expected_bytes,
+        blocks.len(),
+      ));
+    }
+
+    unsafe { B::upload_compressed(&mut self.repr, level, self.size, blocks) }
+  }
+}
+
+impl<B, D, P> Texture<B, D, P>
+where
+  B: ?Sized + TextureReadbackBackend<D, P>,
+  D: Dimensionable,
+  P: Pixel,
+  P::RawEncoding: Copy,
+{
+  /// Start reading back all the pixels from the texture.
+  ///
+  /// The returned [`Readback`] gets the pixels once the GPU is done with all the commands issued so
+  /// far — like the ones rendering to the texture — without blocking. The pixels are laid out as
+  /// with [`Texture::get_raw_texels`].
+  pub fn get_raw_texels_async(&self) -> Result<Readback<B, P::RawEncoding>, TextureError> {
+    reject_compressed_readback::<P>()?;
+
+    unsafe { B::get_raw_texels_async(&self.repr, self.size).map(Readback::from_repr) }
+  }
 }